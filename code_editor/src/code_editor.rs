@@ -1,12 +1,20 @@
 use {
     crate::{
-        decoration::Decoration,
+        completion::{Completion, CompletionPopup},
+        decoration::{Decoration, Severity},
+        diff::LineChangeKind,
+        find_bar::{FindBar, FindBarAction},
+        prompt_bar::{PromptBar, PromptBarAction},
+        keymap::{Command, KeyResult, Keymap, KeymapState, Mode},
         layout::{BlockElement, WrappedElement},
         selection::Affinity,
+        minimap::Minimap,
         session::Session,
         settings::Settings,
         str::StrExt,
+        structure::{enclosing_headers, FoldKind},
         text::Position,
+        tooltip::Tooltip,
         token::TokenKind,
         text::Length,
         Line,
@@ -15,12 +23,17 @@ use {
     },
     makepad_widgets::*,
     std::fmt::Write,
-    std::{mem, slice::Iter},
+    std::{mem, rc::Rc, slice::Iter},
 };
 
 live_design!{
     import makepad_draw::shader::std::*;
     import makepad_widgets::theme_desktop_dark::*;
+    import crate::completion::CompletionPopup;
+    import crate::find_bar::FindBar;
+    import crate::prompt_bar::PromptBar;
+    import crate::minimap::Minimap;
+    import crate::tooltip::Tooltip;
     
     TokenColors = {{TokenColors}} {
         unknown: #C0C0C0,
@@ -37,6 +50,20 @@ live_design!{
         typename: #56C9B1;
         whitespace: #6E6E6E,
         delimiter_highlight: #f,
+        inlay: #7A7A7A,
+    }
+    
+    LineChangeColors = {{LineChangeColors}} {
+        added: #4A8C3C,
+        modified: #3C7AB4,
+        deleted: #B44040,
+    }
+    
+    SeverityColors = {{SeverityColors}} {
+        error: #f00,
+        warning: #D7BA3D,
+        information: #3C9BDC,
+        hint: #8,
     }
     
     DrawIndentGuide = {{DrawIndentGuide}} {
@@ -49,13 +76,32 @@ live_design!{
         }
     }
     
+    DrawFoldMarker = {{DrawFoldMarker}} {
+        fn pixel(self) -> vec4 {
+            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+            let size = self.rect_size.x * 0.35;
+            let c = self.rect_size * 0.5;
+            if self.folded > 0.5 {
+                sdf.move_to(c.x - 0.5 * size, c.y - size);
+                sdf.line_to(c.x + 0.5 * size, c.y);
+                sdf.line_to(c.x - 0.5 * size, c.y + size);
+            } else {
+                sdf.move_to(c.x - size, c.y - 0.5 * size);
+                sdf.line_to(c.x + size, c.y - 0.5 * size);
+                sdf.line_to(c.x, c.y + 0.5 * size);
+            }
+            sdf.close_path();
+            return sdf.fill(self.color);
+        }
+    }
+    
     DrawDecoration = {{DrawDecoration}} {
         fn pixel(self) -> vec4 {
             let transformed_pos = vec2(self.pos.x, self.pos.y + 0.03 * sin(self.pos.x * self.rect_size.x));
             let cx = Sdf2d::viewport(transformed_pos * self.rect_size);
             cx.move_to(0.0, self.rect_size.y - 1.0);
             cx.line_to(self.rect_size.x, self.rect_size.y - 1.0);
-            return cx.stroke(vec4(1.0, 0.0, 0.0, 1.0), 0.8);
+            return cx.stroke(self.color, 0.8);
         }
     }
     
@@ -115,6 +161,11 @@ live_design!{
         width: Fill,
         height: Fill,
         margin: 0,
+        prompt_bar: <PromptBar> {}
+        find_bar: <FindBar> {}
+        minimap: <Minimap> {}
+        completion_popup: <CompletionPopup> {}
+        tooltip: <Tooltip> {}
         scroll_bars: <ScrollBars> {}
         draw_bg: {
            // draw_depth: 0.0,
@@ -142,9 +193,26 @@ live_design!{
            // draw_depth: 1.0,
             color: #5,
         }
+        draw_fold_marker: {
+            color: #8,
+        }
+        draw_line_change: {}
         draw_decoration: {
           //  draw_depth: 2.0,
         }
+        draw_search_match: {
+            color: #ffcc0040
+        }
+        draw_peer_selection: {}
+        draw_peer_cursor: {}
+        draw_sticky_bg: {
+            draw_depth: 2.0,
+            color: #2f
+        }
+        draw_sticky_text: {
+            draw_depth: 2.1,
+            text_style: <THEME_FONT_CODE> {}
+        }
         draw_selection: {
            // draw_depth: 3.0,
         }
@@ -219,6 +287,45 @@ pub struct CodeEditor {
     #[walk]
     walk: Walk,
     #[live]
+    prompt_bar: PromptBar,
+    #[rust]
+    prompt_bar_open: bool,
+    #[live]
+    find_bar: FindBar,
+    #[rust]
+    find_bar_open: bool,
+    #[rust]
+    keymap: Rc<Keymap>,
+    #[rust]
+    keymap_state: KeymapState,
+    // set when the keymap handled a key, whose text input should then be ignored
+    #[rust]
+    swallow_text_input: bool,
+    #[live]
+    minimap: Minimap,
+    #[live(true)]
+    show_minimap: bool,
+    // pins the lines of the items that enclose the top of the viewport
+    #[live(true)]
+    sticky_scroll: bool,
+    #[live(3usize)]
+    max_sticky_line_count: usize,
+    #[rust]
+    sticky_lines: Vec<usize>,
+    #[rust]
+    sticky_rect: Rect,
+    #[live]
+    completion_popup: CompletionPopup,
+    #[live]
+    tooltip: Tooltip,
+    // how long the mouse has to rest on a position before its hover information is requested
+    #[live(0.5)]
+    hover_delay: f64,
+    #[rust]
+    hover_timer: Timer,
+    #[rust]
+    hover_position: Option<Position>,
+    #[live]
     scroll_bars: ScrollBars,
     #[rust]
     draw_state: DrawStateWrap<Walk>,
@@ -234,8 +341,29 @@ pub struct CodeEditor {
     #[live]
     draw_indent_guide: DrawIndentGuide,
     #[live]
+    draw_fold_marker: DrawFoldMarker,
+    #[live]
+    line_change_colors: LineChangeColors,
+    #[live]
+    severity_colors: SeverityColors,
+    #[live]
+    draw_line_change: DrawColor,
+    // tints the lines that changed across the viewport as well, as the sides of a diff view do
+    #[live]
+    highlight_line_changes: bool,
+    #[live]
     draw_decoration: DrawDecoration,
     #[live]
+    draw_search_match: DrawColor,
+    #[live]
+    draw_peer_selection: DrawColor,
+    #[live]
+    draw_peer_cursor: DrawColor,
+    #[live]
+    draw_sticky_bg: DrawColor,
+    #[live]
+    draw_sticky_text: DrawText,
+    #[live]
     draw_selection: DrawSelection,
     #[live]
     draw_cursor: DrawColor,
@@ -302,6 +430,11 @@ impl KeepCursorInView {
 
 impl LiveHook for CodeEditor {
     fn before_live_design(cx: &mut Cx) {
+        crate::completion::live_design(cx);
+        crate::find_bar::live_design(cx);
+        crate::prompt_bar::live_design(cx);
+        crate::minimap::live_design(cx);
+        crate::tooltip::live_design(cx);
         register_widget!(cx, CodeEditor)
     }
 }
@@ -337,6 +470,12 @@ pub struct CodeEditorRef(WidgetRef);
 
 impl CodeEditor {
     pub fn draw(&mut self, cx: &mut Cx2d, session: &mut Session) {
+        let walk = self.draw_state.get().unwrap();
+        self.draw_walk(cx, session, walk);
+    }
+    
+    /// Draws the editor with a walk of its own, for widgets that lay out several editors.
+    pub fn draw_walk(&mut self, cx: &mut Cx2d, session: &mut Session, walk: Walk) {
         // This needs to be called first to ensure the session is up to date.
         session.handle_changes();
 
@@ -428,11 +567,18 @@ impl CodeEditor {
             }
         }
         
-        let walk = self.draw_state.get().unwrap();
-        self.scroll_bars.begin(cx, walk, Layout::default());
+        cx.begin_turtle(walk, Layout::flow_down());
+        if self.prompt_bar_open {
+            self.prompt_bar.draw(cx);
+        }
+        if self.find_bar_open {
+            self.find_bar.draw(cx);
+        }
+        self.scroll_bars.begin(cx, Walk::fill(), Layout::default());
         
         let turtle_rect = cx.turtle().rect();
-        let gutter_width = (session .document() .as_text() .as_lines() .len() .to_string() .column_count() + 3) as f64
+        // the line numbers are at least four columns wide, and are followed by the fold markers
+        let gutter_width = (session .document() .as_text() .as_lines() .len() .to_string() .column_count() .max(4) + 4) as f64
             * self.cell_size.x;
         self.gutter_rect = Rect {
             pos: turtle_rect.pos,
@@ -458,6 +604,9 @@ impl CodeEditor {
         self.gutter_rect.size -= pad_left_top;
         self.viewport_rect.pos += pad_left_top;
         self.viewport_rect.size -= pad_left_top;
+        if self.show_minimap {
+            self.viewport_rect.size.x -= self.minimap.width();
+        }
         
         session.set_wrap_column(if self.word_wrap {Some(
             (self.viewport_rect.size.x / self.cell_size.x) as usize,
@@ -475,10 +624,19 @@ impl CodeEditor {
         self.draw_bg.draw_abs(cx, cx.turtle().unscrolled_rect());
         
         self.draw_gutter(cx, session);
+        self.draw_line_change_layer(cx, session);
+        self.draw_search_match_layer(cx, session);
+        self.draw_peer_selection_layer(cx, session);
         self.draw_selection_layer(cx, session);
         self.draw_text_layer(cx, session);
         self.draw_indent_guide_layer(cx, session);
         self.draw_decoration_layer(cx, session);
+        self.draw_sticky_header(cx, session);
+        if self.show_minimap {
+            self.draw_minimap(cx, session);
+        }
+        self.draw_completion_popup(cx, session);
+        self.draw_tooltip(cx, session);
         
         // Get the last added selection.
         // Get the normalized cursor position. To go from normalized to screen position, multiply by
@@ -490,6 +648,7 @@ impl CodeEditor {
         );
         
         self.scroll_bars.end(cx);
+        cx.end_turtle();
         if session.update_folds() {
             self.scroll_bars.area().redraw(cx);
         }
@@ -502,12 +661,270 @@ impl CodeEditor {
         cx.set_key_focus(self.scroll_bars.area());
     }
     
+    pub fn has_key_focus(&self, cx: &Cx) -> bool {
+        cx.has_key_focus(self.scroll_bars.area())
+    }
+    
+    /// The line at the top of the viewport, and how far into it the viewport is scrolled, as a
+    /// fraction of its height.
+    pub fn top_line(&self, session: &Session) -> (usize, f64) {
+        if self.cell_size.y == 0.0 {
+            // not drawn yet
+            return (0, 0.0);
+        }
+        let y = self.scroll_bars.get_scroll_pos().y / self.cell_size.y;
+        let layout = session.layout();
+        let line_index = layout.find_first_line_ending_after_y(y);
+        let line = layout.line(line_index);
+        let offset = if line.height() > 0.0 {
+            ((y - line.y()) / line.height()).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (line_index, offset)
+    }
+    
+    /// Scrolls the given line to the top of the viewport, see `top_line`.
+    pub fn set_top_line(&mut self, cx: &mut Cx, session: &Session, line_index: usize, offset: f64) {
+        let layout = session.layout();
+        let line = layout.line(line_index.min(layout.as_text().as_lines().len() - 1));
+        let y = (line.y() + offset * line.height()) * self.cell_size.y;
+        let scroll_pos = self.scroll_bars.get_scroll_pos();
+        self.scroll_bars.set_scroll_pos(cx, dvec2(scroll_pos.x, y));
+    }
+    
     pub fn set_cursor_and_scroll(&mut self, cx:&mut Cx, pos:Position, _lenght:Length, session: &mut Session){
         session.set_selection(pos, Affinity::Before, 1);
         self.keep_cursor_in_view = KeepCursorInView::JumpToPosition;
         self.redraw(cx);
     }
     
+    pub fn keymap(&self) -> &Rc<Keymap> {
+        &self.keymap
+    }
+    
+    /// Replaces the keymap, which can be shared between editors, and switches to its initial mode.
+    pub fn set_keymap(&mut self, keymap: Rc<Keymap>) {
+        self.keymap_state = KeymapState::new(keymap.initial_mode());
+        self.keymap = keymap;
+    }
+    
+    pub fn mode(&self) -> Mode {
+        self.keymap_state.mode()
+    }
+    
+    /// Runs a command, returns whether it changed the text.
+    pub fn execute_command(&mut self, cx: &mut Cx, session: &mut Session, command: Command, extend_selection: bool) -> bool {
+        let reset_anchor = !extend_selection;
+        let page_row_count = (self.viewport_rect.size.y / self.cell_size.y).max(1.0) as usize;
+        match command {
+            Command::MoveLeft => session.move_left(reset_anchor),
+            Command::MoveRight => session.move_right(reset_anchor),
+            Command::MoveUp => session.move_up(reset_anchor),
+            Command::MoveDown => session.move_down(reset_anchor),
+            Command::MoveWordLeft => session.move_word_left(reset_anchor),
+            Command::MoveWordRight => session.move_word_right(reset_anchor),
+            Command::MoveToStartOfLine => session.move_to_start_of_line(reset_anchor),
+            Command::MoveToEndOfLine => session.move_to_end_of_line(reset_anchor),
+            Command::MovePageUp => session.move_page_up(reset_anchor, page_row_count),
+            Command::MovePageDown => session.move_page_down(reset_anchor, page_row_count),
+            Command::MoveToStartOfText => session.move_to_start_of_text(reset_anchor),
+            Command::MoveToEndOfText => session.move_to_end_of_text(reset_anchor),
+            Command::SelectWord => session.select_word(),
+            Command::SelectLine => session.select_line(),
+            Command::MoveLinesUp => {
+                session.move_lines_up();
+                return true;
+            }
+            Command::MoveLinesDown => {
+                session.move_lines_down();
+                return true;
+            }
+            Command::DuplicateLines => {
+                session.duplicate_lines();
+                return true;
+            }
+            Command::Enter => {
+                session.enter();
+                return true;
+            }
+            Command::Indent => {
+                session.indent();
+                return true;
+            }
+            Command::Outdent => {
+                session.outdent();
+                return true;
+            }
+            Command::Delete => {
+                session.delete();
+                return true;
+            }
+            Command::Backspace => {
+                session.backspace();
+                return true;
+            }
+            Command::DeleteLine => {
+                session.select_line();
+                session.delete();
+                return true;
+            }
+            Command::DeleteToEndOfLine => {
+                session.move_to_end_of_line(false);
+                session.delete();
+                return true;
+            }
+            Command::Undo => if session.undo() {
+                cx.redraw_all();
+                return true;
+            }
+            Command::Redo => return session.redo(),
+            Command::Find => self.open_find_bar(cx, session),
+            Command::FindNext | Command::FindPrevious | Command::AddNextMatch | Command::AddPreviousMatch => {
+                let add_selection = matches!(command, Command::AddNextMatch | Command::AddPreviousMatch);
+                if if matches!(command, Command::FindNext | Command::AddNextMatch) {
+                    session.find_next(add_selection)
+                } else {
+                    session.find_previous(add_selection)
+                } {
+                    self.keep_cursor_in_view = KeepCursorInView::JumpToPosition;
+                }
+            }
+            Command::IncreaseFontSize => self.increase_font_size(),
+            Command::DecreaseFontSize => self.decrease_font_size(),
+            Command::ResetFontSize => self.reset_font_size(),
+            Command::ToggleWordWrap => self.word_wrap = !self.word_wrap,
+            Command::ToggleFold => session.toggle_fold(cursor_position(session).line_index),
+            Command::FoldAll => session.fold_all(None),
+            Command::UnfoldAll => session.unfold_all(),
+            Command::FoldComments => session.fold_all(Some(FoldKind::Comment)),
+            Command::FoldDesignBlocks => session.fold_all(Some(FoldKind::Design)),
+            // these are answered by the host, see `CodeEditorAction`
+            Command::TriggerCompletion | Command::GoToDefinition => {}
+            Command::EnterNormalMode => self.keymap_state.set_mode(Mode::Normal),
+            Command::EnterInsertMode => self.keymap_state.set_mode(Mode::Insert),
+            Command::AppendAfterCursor => {
+                session.move_right(true);
+                self.keymap_state.set_mode(Mode::Insert);
+            }
+            Command::AppendToEndOfLine => {
+                session.move_to_end_of_line(true);
+                self.keymap_state.set_mode(Mode::Insert);
+            }
+            Command::InsertLineBelow => {
+                session.move_to_end_of_line(true);
+                session.enter();
+                self.keymap_state.set_mode(Mode::Insert);
+                return true;
+            }
+        }
+        false
+    }
+    
+    /// Shows the find bar, searching for the selected text if it is on a single line.
+    pub fn open_find_bar(&mut self, cx: &mut Cx, session: &mut Session) {
+        let selections = session.selections();
+        let text = match *selections {
+            [selection] if !selection.is_empty() && selection.line_range().len() == 1 => {
+                Some(session.copy())
+            }
+            _ => None,
+        };
+        drop(selections);
+        self.find_bar_open = true;
+        self.find_bar.open(cx, session, text);
+        self.redraw(cx);
+    }
+    
+    pub fn close_find_bar(&mut self, cx: &mut Cx, session: &mut Session) {
+        self.find_bar_open = false;
+        session.clear_search();
+        self.set_key_focus(cx);
+        self.redraw(cx);
+    }
+    
+    /// Asks a question about the document above the text, which is answered with a
+    /// `PromptAnswered` action.
+    pub fn show_prompt(&mut self, cx: &mut Cx, message: &str, accept_text: &str, decline_text: &str) {
+        self.prompt_bar_open = true;
+        self.prompt_bar.open(cx, message, accept_text, decline_text);
+        self.redraw(cx);
+    }
+    
+    pub fn close_prompt(&mut self, cx: &mut Cx) {
+        self.prompt_bar_open = false;
+        self.redraw(cx);
+    }
+    
+    /// Shows completions for the word before the cursor, narrowed down to the ones that start
+    /// with what has been typed of it.
+    pub fn show_completions(&mut self, cx: &mut Cx, session: &Session, completions: Vec<Completion>) {
+        let cursor = cursor_position(session);
+        let text = session.document().as_text();
+        let line = &text.as_lines()[cursor.line_index];
+        let byte_index = line[..cursor.byte_index]
+            .char_indices()
+            .rev()
+            .take_while(| &(_, char) | is_word_char(char))
+            .last()
+            .map_or(cursor.byte_index, | (byte_index, _) | byte_index);
+        drop(text);
+        let start = Position {line_index: cursor.line_index, byte_index};
+        self.completion_popup.open(start, completions);
+        self.update_completion_popup(session);
+        self.redraw(cx);
+    }
+    
+    /// Shows the hover information for a position that was asked for with
+    /// `CodeEditorAction::RequestHover`, unless the mouse has moved on since.
+    pub fn show_tooltip(&mut self, cx: &mut Cx, position: Position, text: &str) {
+        if self.hover_position == Some(position) {
+            self.tooltip.show(position, text);
+            self.redraw(cx);
+        }
+    }
+    
+    pub fn hide_tooltip(&mut self, cx: &mut Cx) {
+        if self.tooltip.position().is_some() {
+            self.tooltip.hide();
+            self.redraw(cx);
+        }
+    }
+    
+    // closes the completion popup once the cursor leaves the word it is for
+    fn update_completion_popup(&mut self, session: &Session) {
+        if !self.completion_popup.is_open() {
+            return;
+        }
+        let start = self.completion_popup.start();
+        let cursor = cursor_position(session);
+        let text = session.document().as_text();
+        let line = &text.as_lines()[cursor.line_index];
+        let is_open = cursor.line_index == start.line_index
+            && start.byte_index <= cursor.byte_index
+            && line.is_char_boundary(start.byte_index)
+            && line[start.byte_index..cursor.byte_index].chars().all(is_word_char)
+            && self.completion_popup.filter(&line[start.byte_index..cursor.byte_index]);
+        if !is_open {
+            self.completion_popup.close();
+        }
+    }
+    
+    /// Replaces the word before the cursor with the selected completion, returns whether there
+    /// was one.
+    fn accept_completion(&mut self, cx: &mut Cx, session: &mut Session) -> bool {
+        let Some(completion) = self.completion_popup.selected().cloned() else {
+            return false;
+        };
+        let cursor = cursor_position(session);
+        session.set_selection(self.completion_popup.start(), Affinity::Before, 1);
+        session.move_to(cursor, Affinity::After);
+        session.insert(completion.insert_text.into());
+        self.completion_popup.close();
+        self.redraw(cx);
+        true
+    }
+    
     pub fn reset_font_size(&mut self) {
         self.draw_gutter.text_style.font_size = 9.0;
         self.draw_text.text_style.font_size = 9.0;
@@ -567,6 +984,29 @@ impl CodeEditor {
        
         session.handle_changes();
         
+        if self.prompt_bar_open {
+            for action in self.prompt_bar.handle_event(cx, event) {
+                self.close_prompt(cx);
+                dispatch_action(cx, CodeEditorAction::PromptAnswered(action == PromptBarAction::Accept));
+            }
+        }
+        
+        if self.find_bar_open {
+            for action in self.find_bar.handle_event(cx, event, session) {
+                match action {
+                    FindBarAction::SelectionChanged => {
+                        self.keep_cursor_in_view = KeepCursorInView::JumpToPosition;
+                        self.redraw(cx);
+                    }
+                    FindBarAction::TextDidChange => {
+                        self.redraw(cx);
+                        dispatch_action(cx, CodeEditorAction::TextDidChange);
+                    }
+                    FindBarAction::Close => self.close_find_bar(cx, session),
+                }
+            }
+        }
+        
         self.scroll_bars.handle_event_with(cx, event, &mut | cx, _ | {
             cx.redraw_all();
         });
@@ -579,6 +1019,11 @@ impl CodeEditor {
             }
             self.blink_timer = cx.start_timeout(self.blink_speed)
         }
+        if self.hover_timer.is_event(event).is_some() {
+            if let Some(position) = self.hover_position {
+                dispatch_action(cx, CodeEditorAction::RequestHover(position));
+            }
+        }
         let mut keyboard_moved_cursor = false;
         match event.hits(cx, self.scroll_bars.area()) {
             Hit::KeyFocusLost(_) => {
@@ -588,16 +1033,63 @@ impl CodeEditor {
                 self.animator_play(cx, id!(focus.on));
             }
             Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Escape,
-                is_repeat: false,
+                key_code,
+                modifiers,
                 ..
-            }) => {
-                session.fold();
-                if !self.keep_cursor_in_view.is_locked() {
-                    self.keep_cursor_in_view = KeepCursorInView::LockStart;
+            }) if self.completion_popup.is_open() && modifiers == KeyModifiers::default() && matches!(
+                key_code,
+                KeyCode::ArrowUp | KeyCode::ArrowDown | KeyCode::ReturnKey | KeyCode::Tab | KeyCode::Escape
+            ) => {
+                match key_code {
+                    KeyCode::ArrowUp => self.completion_popup.select_previous(),
+                    KeyCode::ArrowDown => self.completion_popup.select_next(),
+                    KeyCode::ReturnKey | KeyCode::Tab => if self.accept_completion(cx, session) {
+                        keyboard_moved_cursor = true;
+                        dispatch_action(cx, CodeEditorAction::TextDidChange);
+                    }
+                    _ => self.completion_popup.close(),
                 }
+                self.swallow_text_input = true;
                 self.redraw(cx);
             }
+            Hit::KeyDown(KeyEvent {
+                key_code,
+                modifiers,
+                is_repeat,
+                ..
+            }) => {
+                self.hide_tooltip(cx);
+                let result = self.keymap.handle_key(&mut self.keymap_state, key_code, modifiers);
+                self.swallow_text_input = result != KeyResult::Unbound;
+                match result {
+                    KeyResult::Command(Command::TriggerCompletion, _) => {
+                        dispatch_action(cx, CodeEditorAction::RequestCompletion(cursor_position(session)));
+                    }
+                    KeyResult::Command(Command::GoToDefinition, _) => {
+                        dispatch_action(cx, CodeEditorAction::GoToDefinition(cursor_position(session)));
+                    }
+                    KeyResult::Command(command, extend_selection) => {
+                        if self.execute_command(cx, session, command, extend_selection) {
+                            dispatch_action(cx, CodeEditorAction::TextDidChange);
+                        }
+                        // these keep the cursor in view themselves, or leave it where it is
+                        keyboard_moved_cursor = !matches!(
+                            command,
+                            Command::Find | Command::FindNext | Command::FindPrevious | Command::AddNextMatch | Command::AddPreviousMatch
+                                | Command::IncreaseFontSize | Command::DecreaseFontSize | Command::ResetFontSize | Command::ToggleWordWrap
+                        );
+                        self.redraw(cx);
+                    }
+                    KeyResult::Unbound if key_code == KeyCode::Escape && !is_repeat => {
+                        session.fold();
+                        if !self.keep_cursor_in_view.is_locked() {
+                            self.keep_cursor_in_view = KeepCursorInView::LockStart;
+                        }
+                        self.redraw(cx);
+                    }
+                    _ => {}
+                }
+            }
             Hit::KeyUp(KeyEvent {
                 key_code: KeyCode::Escape,
                 ..
@@ -608,135 +1100,17 @@ impl CodeEditor {
                 }
                 self.redraw(cx);
             }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Minus,
-                modifiers: KeyModifiers {control, logo, ..},
-                ..
-            }) => {
-                if control || logo {
-                    self.decrease_font_size();
-                    self.redraw(cx);
-                }
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Key0,
-                modifiers: KeyModifiers {control, logo, ..},
-                ..
-            }) => {
-                if control || logo {
-                    self.reset_font_size();
-                    self.redraw(cx);
-                }
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Equals,
-                modifiers: KeyModifiers {control, logo, ..},
-                ..
-            }) => {
-                if control || logo {
-                    self.increase_font_size();
-                    self.redraw(cx);
-                }
-            }
-             Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::KeyW,
-                modifiers: KeyModifiers {control, logo, ..},
-                ..
-            }) => {
-                if control || logo {
-                    self.word_wrap = !self.word_wrap;
+            Hit::TextInput(TextInputEvent {ref input, was_paste, ..}) if input.len() > 0 => {
+                if !mem::take(&mut self.swallow_text_input) && (was_paste || self.keymap_state.accepts_text_input()) {
+                    session.insert(input.into());
                     self.redraw(cx);
+                    keyboard_moved_cursor = true;
+                    dispatch_action(cx, CodeEditorAction::TextDidChange);
+                    if input == "." && !was_paste {
+                        dispatch_action(cx, CodeEditorAction::RequestCompletion(cursor_position(session)));
+                    }
                 }
             }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowLeft,
-                modifiers: KeyModifiers {shift, ..},
-                ..
-            }) => {
-                session.move_left(!shift);
-                keyboard_moved_cursor = true;
-                self.redraw(cx);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowRight,
-                modifiers: KeyModifiers {shift, ..},
-                ..
-            }) => {
-                session.move_right(!shift);
-                keyboard_moved_cursor = true;
-                self.redraw(cx);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowUp,
-                modifiers: KeyModifiers {shift, ..},
-                ..
-            }) => {
-                session.move_up(!shift);
-                keyboard_moved_cursor = true;
-                self.redraw(cx);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::ArrowDown,
-                modifiers: KeyModifiers {shift, ..},
-                ..
-            }) => {
-                session.move_down(!shift);
-                keyboard_moved_cursor = true;
-                self.redraw(cx);
-            }
-            Hit::TextInput(TextInputEvent {ref input, ..}) if input.len() > 0 => {
-                session.insert(input.into());
-                self.redraw(cx);
-                keyboard_moved_cursor = true;
-                dispatch_action(cx, CodeEditorAction::TextDidChange);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::ReturnKey,
-                ..
-            }) => {
-                session.enter();
-                self.redraw(cx);
-                keyboard_moved_cursor = true;
-                dispatch_action(cx, CodeEditorAction::TextDidChange);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Tab,
-                modifiers: KeyModifiers {shift: false, ..},
-                ..
-            }) => {
-                session.indent();
-                self.redraw(cx);
-                keyboard_moved_cursor = true;
-                dispatch_action(cx, CodeEditorAction::TextDidChange);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Tab,
-                modifiers: KeyModifiers {shift: true, ..},
-                ..
-            }) => {
-                session.outdent();
-                self.redraw(cx);
-                keyboard_moved_cursor = true;
-                dispatch_action(cx, CodeEditorAction::TextDidChange);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Delete,
-                ..
-            }) => {
-                session.delete();
-                self.redraw(cx);
-                keyboard_moved_cursor = true;
-                dispatch_action(cx, CodeEditorAction::TextDidChange);
-            }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::Backspace,
-                ..
-            }) => {
-                session.backspace();
-                self.redraw(cx);
-                keyboard_moved_cursor = true;
-                dispatch_action(cx, CodeEditorAction::TextDidChange);
-            }
             Hit::TextCopy(ce) => {
                 *ce.response.borrow_mut() = Some(session.copy());
                 keyboard_moved_cursor = true;
@@ -747,38 +1121,28 @@ impl CodeEditor {
                 keyboard_moved_cursor = true;
                 self.redraw(cx);
             }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::KeyZ,
-                modifiers:
-                KeyModifiers {
-                    logo: true,
-                    shift: false,
-                    ..
-                },
-                ..
-            }) => {
-                if session.undo() {
-                    cx.redraw_all();
+            Hit::FingerDown(FingerDownEvent {abs, ..}) if self.completion_popup.contains(abs) => {
+                if self.completion_popup.select_at(abs) && self.accept_completion(cx, session) {
                     dispatch_action(cx, CodeEditorAction::TextDidChange);
-                    keyboard_moved_cursor = true;
                 }
             }
-            Hit::KeyDown(KeyEvent {
-                key_code: KeyCode::KeyZ,
-                modifiers:
-                KeyModifiers {
-                    logo: true,
-                    shift: true,
-                    ..
-                },
-                ..
-            }) => {
-                if session.redo() {
+            Hit::FingerDown(FingerDownEvent {abs, ..}) if self.show_minimap && self.minimap.contains(abs) => {
+                let line_index = self.minimap.handle_finger_down(abs);
+                self.scroll_to_line(cx, session, line_index);
+            }
+            Hit::FingerDown(FingerDownEvent {abs, ..}) if self.sticky_rect.contains(abs) => {
+                let row_index = ((abs.y - self.sticky_rect.pos.y) / self.cell_size.y) as usize;
+                if let Some(&line_index) = self.sticky_lines.get(row_index) {
+                    session.set_selection(Position {line_index, byte_index: 0}, Affinity::Before, 1);
+                    self.keep_cursor_in_view = KeepCursorInView::JumpToPosition;
                     self.redraw(cx);
-                    dispatch_action(cx, CodeEditorAction::TextDidChange);
-                    keyboard_moved_cursor = true;
                 }
             }
+            Hit::FingerDown(FingerDownEvent {abs, ..}) if self.pick_fold_marker(session, abs).is_some() => {
+                let line_index = self.pick_fold_marker(session, abs).unwrap();
+                session.toggle_fold(line_index);
+                self.redraw(cx);
+            }
             Hit::FingerDown(FingerDownEvent {
                 abs,
                 modifiers: KeyModifiers {alt, ..},
@@ -786,6 +1150,8 @@ impl CodeEditor {
                 ..
             }) => {
                 cx.set_key_focus(self.scroll_bars.area());
+                self.completion_popup.close();
+                self.hide_tooltip(cx);
                 if let Some((cursor, affinity)) = self.pick(session, abs) {
                     if alt {
                         session.add_selection(cursor, affinity, tap_count);
@@ -798,11 +1164,40 @@ impl CodeEditor {
                 self.keep_cursor_in_view = KeepCursorInView::Always(abs, cx.new_next_frame());
             }
             Hit::FingerUp(_) => {
+                self.minimap.handle_finger_up();
                 self.reset_cursor_blinker(cx);
                 self.keep_cursor_in_view = KeepCursorInView::Off;
             }
-            Hit::FingerHoverIn(_) | Hit::FingerHoverOver(_) => {
-                cx.set_cursor(MouseCursor::Text);
+            Hit::FingerHoverIn(FingerHoverEvent {abs, ..}) | Hit::FingerHoverOver(FingerHoverEvent {abs, ..}) => {
+                if self.show_minimap && self.minimap.contains(abs)
+                    || self.sticky_rect.contains(abs)
+                    || self.pick_fold_marker(session, abs).is_some()
+                    || self.completion_popup.contains(abs)
+                    || self.tooltip.contains(abs)
+                {
+                    cx.set_cursor(MouseCursor::Default);
+                } else {
+                    cx.set_cursor(MouseCursor::Text);
+                    let position = self.pick(session, abs).map( | (position, _) | position);
+                    if position != self.hover_position {
+                        self.hover_position = position;
+                        self.hide_tooltip(cx);
+                        cx.stop_timer(self.hover_timer);
+                        if position.is_some() {
+                            self.hover_timer = cx.start_timeout(self.hover_delay);
+                        }
+                    }
+                }
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hover_position = None;
+                cx.stop_timer(self.hover_timer);
+                self.hide_tooltip(cx);
+            }
+            Hit::FingerMove(FingerMoveEvent {abs, ..}) if self.minimap.is_dragging() => {
+                if let Some(line_index) = self.minimap.handle_finger_move(abs) {
+                    self.scroll_to_line(cx, session, line_index);
+                }
             }
             Hit::FingerMove(FingerMoveEvent {abs, ..}) => {
                 self.reset_cursor_blinker(cx);
@@ -818,6 +1213,7 @@ impl CodeEditor {
             }
             _ => {}
         }
+        self.update_completion_popup(session);
         if keyboard_moved_cursor{
             self.keep_cursor_in_view = KeepCursorInView::Once;
             self.reset_cursor_blinker(cx);
@@ -843,6 +1239,10 @@ impl CodeEditor {
             .block_elements(self.line_start, self.line_end)
         {
             match element {
+                BlockElement::Line {line, ..} if line.scale() == 0.0 => {
+                    // hidden in a fold
+                    line_index += 1;
+                }
                 BlockElement::Line {line, ..} => {
                     self.draw_gutter.font_scale = line.scale();
                     buf.clear();
@@ -857,6 +1257,17 @@ impl CodeEditor {
                             + dvec2((1.0-line.scale()) * -self.cell_size.x + self.gutter_rect.size.x - line.scale() * self.gutter_rect.size.x,0.0),
                             &buf
                     );
+                    if session.fold_range_at(line_index).is_some() {
+                        let x = self.fold_marker_x();
+                        self.draw_fold_marker.folded = if session.is_folded(line_index) {1.0} else {0.0};
+                        self.draw_fold_marker.draw_abs(
+                            cx,
+                            Rect {
+                                pos: dvec2(x, origin_y * self.cell_size.y + self.gutter_rect.pos.y),
+                                size: dvec2(self.cell_size.x, line.scale() * self.cell_size.y),
+                            },
+                        );
+                    }
                     line_index += 1;
                     origin_y += line.height();
                 }
@@ -867,6 +1278,57 @@ impl CodeEditor {
         }
     }
     
+    fn draw_line_change_layer(&mut self, cx: &mut Cx2d, session: &Session) {
+        let document = session.document();
+        let line_changes = document.line_changes();
+        let layout = session.layout();
+        let line_count = layout.as_text().as_lines().len();
+        // the lane is in the padding to the left of the line numbers
+        let x = self.gutter_rect.pos.x - 7.0;
+        let highlight_width = self.viewport_rect.size.x.max(layout.width() * self.cell_size.x);
+        for change in line_changes.iter() {
+            let range = &change.new_line_range;
+            if range.end < self.line_start || range.start > self.line_end {
+                continue;
+            }
+            let color = self.line_change_colors.color(change.kind());
+            if range.is_empty() {
+                // deleted lines are marked at the boundary between the lines that surrounded them
+                let y = if range.start < line_count {
+                    layout.line(range.start).y()
+                } else {
+                    layout.height()
+                };
+                self.draw_line_change.color = color;
+                self.draw_line_change.draw_abs(cx, Rect {
+                    pos: dvec2(x, y * self.cell_size.y + self.gutter_rect.pos.y - 2.0),
+                    size: dvec2(6.0, 4.0),
+                });
+                continue;
+            }
+            for line_index in range.start.max(self.line_start)..range.end.min(self.line_end) {
+                let line = layout.line(line_index);
+                if line.scale() == 0.0 {
+                    continue;
+                }
+                let y = line.y() * self.cell_size.y + self.gutter_rect.pos.y;
+                let height = line.height() * self.cell_size.y;
+                if self.highlight_line_changes {
+                    self.draw_line_change.color = vec4(color.x, color.y, color.z, 0.15);
+                    self.draw_line_change.draw_abs(cx, Rect {
+                        pos: dvec2(self.viewport_rect.pos.x, y),
+                        size: dvec2(highlight_width, height),
+                    });
+                }
+                self.draw_line_change.color = color;
+                self.draw_line_change.draw_abs(cx, Rect {
+                    pos: dvec2(x, y),
+                    size: dvec2(3.0, height),
+                });
+            }
+        }
+    }
+    
     fn draw_text_layer(&mut self, cx: &mut Cx2d, session: &Session) {
         let highlighted_delimiter_positions = session.highlighted_delimiter_positions();
         let mut line_index = self.line_start;
@@ -876,6 +1338,9 @@ impl CodeEditor {
             .block_elements(self.line_start, self.line_end)
         {
             match element {
+                BlockElement::Line {line, ..} if line.scale() == 0.0 => {
+                    line_index += 1;
+                }
                 BlockElement::Line {line, ..} => {
                     self.draw_text.font_scale = line.scale();
                     let mut token_iter = line.tokens().iter().copied();
@@ -913,23 +1378,7 @@ impl CodeEditor {
                                     };
                                     let (text_0, text_1) = text.split_at(token.len);
                                     text = text_1;
-                                    self.draw_text.color = match token.kind {
-                                        TokenKind::Unknown => self.token_colors.unknown,
-                                        TokenKind::BranchKeyword => {
-                                            self.token_colors.branch_keyword
-                                        }
-                                        TokenKind::Comment => self.token_colors.comment,
-                                        TokenKind::Constant => self.token_colors.constant,
-                                        TokenKind::Delimiter => self.token_colors.delimiter,
-                                        TokenKind::Identifier => self.token_colors.identifier,
-                                        TokenKind::LoopKeyword => self.token_colors.loop_keyword,
-                                        TokenKind::Number => self.token_colors.number,
-                                        TokenKind::OtherKeyword => self.token_colors.other_keyword,
-                                        TokenKind::Punctuator => self.token_colors.punctuator,
-                                        TokenKind::String => self.token_colors.string,
-                                        TokenKind::Typename => self.token_colors.typename,
-                                        TokenKind::Whitespace => self.token_colors.whitespace,
-                                    };
+                                    self.draw_text.color = self.token_colors.color(token.kind);
                                     self.draw_text.outline = 0.0;
                                     if let TokenKind::Delimiter = token.kind {
                                         if highlighted_delimiter_positions.contains(&Position {
@@ -960,6 +1409,8 @@ impl CodeEditor {
                             } => {
                                 let (x, y) =
                                 line.grid_to_normalized_position(row_index, column_index);
+                                self.draw_text.color = self.token_colors.inlay;
+                                self.draw_text.outline = 0.0;
                                 self.draw_text.draw_abs(
                                     cx,
                                     DVec2 {x, y: origin_y + y} *self.cell_size
@@ -1026,9 +1477,177 @@ impl CodeEditor {
         }
     }
     
+    fn draw_sticky_header(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
+        self.sticky_lines.clear();
+        self.sticky_rect = Rect::default();
+        if !self.sticky_scroll {
+            return;
+        }
+        let layout = session.layout();
+        let first_visible_line_index = self.first_visible_line_index(session);
+        let lines = layout.as_text().as_lines();
+        if first_visible_line_index >= lines.len() {
+            return;
+        }
+        let mut sticky_lines = enclosing_headers(lines, first_visible_line_index);
+        sticky_lines.truncate(self.max_sticky_line_count);
+        if sticky_lines.is_empty() {
+            return;
+        }
+        let mut width = self.unscrolled_rect.size.x;
+        if self.show_minimap {
+            width -= self.minimap.width();
+        }
+        self.sticky_rect = Rect {
+            pos: self.unscrolled_rect.pos,
+            size: dvec2(width, sticky_lines.len() as f64 * self.cell_size.y),
+        };
+        self.draw_sticky_bg.draw_abs(cx, self.sticky_rect);
+        self.draw_sticky_text.text_style.font_size = self.draw_text.text_style.font_size;
+        let mut buf = String::new();
+        for (row_index, &line_index) in sticky_lines.iter().enumerate() {
+            let y = self.sticky_rect.pos.y + row_index as f64 * self.cell_size.y;
+            buf.clear();
+            let _ = write!(buf, "{: >4}", line_index + 1);
+            self.draw_sticky_text.color = self.draw_gutter.color;
+            self.draw_sticky_text.draw_abs(cx, dvec2(self.gutter_rect.pos.x, y), &buf);
+            let line = layout.line(line_index);
+            let text = line.text();
+            let mut byte_index = 0;
+            let mut column_index = 0;
+            for token in line.tokens() {
+                let end_byte_index = (byte_index + token.len).min(text.len());
+                let token_text = &text[byte_index..end_byte_index];
+                if token.kind != TokenKind::Whitespace {
+                    self.draw_sticky_text.color = self.token_colors.color(token.kind);
+                    self.draw_sticky_text.draw_abs(
+                        cx,
+                        dvec2(self.viewport_rect.pos.x + column_index as f64 * self.cell_size.x, y),
+                        token_text,
+                    );
+                }
+                byte_index = end_byte_index;
+                column_index += token_text.column_count();
+            }
+        }
+        self.sticky_lines = sticky_lines;
+    }
+    
+    fn draw_minimap(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
+        let first_visible_line_index = self.first_visible_line_index(session);
+        let rect = Rect {
+            pos: dvec2(
+                self.unscrolled_rect.pos.x + self.unscrolled_rect.size.x - self.minimap.width(),
+                self.unscrolled_rect.pos.y,
+            ),
+            size: dvec2(self.minimap.width(), self.unscrolled_rect.size.y),
+        };
+        self.minimap.draw(
+            cx,
+            session,
+            rect,
+            first_visible_line_index,
+            self.line_end.saturating_sub(first_visible_line_index),
+            &self.token_colors,
+            &self.severity_colors,
+        );
+    }
+    
+    fn draw_completion_popup(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
+        if self.completion_popup.is_open() {
+            let anchor = self.position_to_abs(session, self.completion_popup.start());
+            let font_size = self.draw_text.text_style.font_size;
+            self.completion_popup.draw(cx, anchor, self.cell_size, font_size, self.unscrolled_rect);
+        }
+    }
+    
+    fn draw_tooltip(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
+        if let Some(position) = self.tooltip.position() {
+            let anchor = self.position_to_abs(session, position);
+            let font_size = self.draw_text.text_style.font_size;
+            self.tooltip.draw(cx, anchor, self.cell_size, font_size, self.unscrolled_rect);
+        }
+    }
+    
+    fn position_to_abs(&self, session: &Session, position: Position) -> DVec2 {
+        let (x, y) = session.layout().logical_to_normalized_position(position, Affinity::Before);
+        dvec2(x, y) * self.cell_size + self.viewport_rect.pos
+    }
+    
+    // the left edge of the column of the gutter that the fold markers are drawn in
+    fn fold_marker_x(&self) -> f64 {
+        self.gutter_rect.pos.x + self.gutter_rect.size.x - 1.75 * self.cell_size.x
+    }
+    
+    // the line of the fold marker at the given position, if there is one
+    fn pick_fold_marker(&self, session: &Session, position: DVec2) -> Option<usize> {
+        let x = self.fold_marker_x();
+        if !(x..x + self.cell_size.x).contains(&position.x) || !self.gutter_rect.contains(position) {
+            return None;
+        }
+        let line_index = session
+            .layout()
+            .find_first_line_ending_after_y((position.y - self.viewport_rect.pos.y) / self.cell_size.y);
+        session.fold_range_at(line_index).map(|_| line_index)
+    }
+    
+    fn first_visible_line_index(&self, session: &Session) -> usize {
+        session
+            .layout()
+            .find_first_line_ending_after_y(self.scroll_bars.get_scroll_pos().y / self.cell_size.y)
+    }
+    
+    fn scroll_to_line(&mut self, cx: &mut Cx, session: &Session, line_index: usize) {
+        let y = session.layout().line(line_index).y() * self.cell_size.y;
+        let x = self.scroll_bars.get_scroll_pos().x;
+        self.scroll_bars.set_scroll_pos(cx, dvec2(x, y));
+        self.redraw(cx);
+    }
+    
     fn draw_decoration_layer(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
-        let mut active_decoration = None;
         let decorations = session.document().decorations();
+        self.draw_decorations(cx, session, &decorations, DecorationStyle::Diagnostic);
+    }
+    
+    fn draw_search_match_layer(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
+        let search_matches = session.search_matches();
+        self.draw_decorations(cx, session, &search_matches, DecorationStyle::SearchMatch);
+    }
+    
+    fn draw_peer_selection_layer(&mut self, cx: &mut Cx2d<'_>, session: &Session) {
+        let peer_selections = session.document().peer_selections();
+        // the selections of different peers can overlap, but decorations can't
+        let mut decorations: Vec<Decoration> = Vec::new();
+        for selection in peer_selections.iter() {
+            if selection.anchor != selection.cursor && decorations.last().map_or(true, | decoration | {
+                decoration.end() <= selection.start()
+            }) {
+                decorations.push(Decoration::new(selection.peer_id, selection.start(), selection.end()));
+            }
+        }
+        self.draw_decorations(cx, session, &decorations, DecorationStyle::PeerSelection);
+        for selection in peer_selections.iter() {
+            let line_index = selection.cursor.line_index;
+            if line_index < self.line_start || line_index >= self.line_end {
+                continue;
+            }
+            let pos = self.position_to_abs(session, selection.cursor);
+            self.draw_peer_cursor.color = peer_color(selection.peer_id, 0xFF);
+            self.draw_peer_cursor.draw_abs(cx, Rect {
+                pos,
+                size: dvec2(2.0, self.cell_size.y),
+            });
+        }
+    }
+    
+    fn draw_decorations(
+        &mut self,
+        cx: &mut Cx2d<'_>,
+        session: &Session,
+        decorations: &[Decoration],
+        style: DecorationStyle,
+    ) {
+        let mut active_decoration = None;
         let mut decorations = decorations.iter();
         while decorations.as_slice().first().map_or(false, | decoration | {
             decoration.end().line_index < self.line_start
@@ -1047,6 +1666,7 @@ impl CodeEditor {
             code_editor: self,
             active_decoration,
             decorations,
+            style,
         }
         .draw_decoration_layer(cx, session)
     }
@@ -1217,15 +1837,32 @@ impl CodeEditor {
     }
 }
 
+fn cursor_position(session: &Session) -> Position {
+    session.selections()[session.last_added_selection_index().unwrap()].cursor.position
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum CodeEditorAction {
     TextDidChange,
+    /// The mouse rested on a position, whose hover information can be shown with `show_tooltip`.
+    RequestHover(Position),
+    /// Completions for the word before the cursor were asked for, which can be shown with
+    /// `show_completions`.
+    RequestCompletion(Position),
+    GoToDefinition(Position),
+    /// The question that was asked with `show_prompt` was answered, with yes if `true`.
+    PromptAnswered(bool),
 }
 
 struct DrawDecorationLayer<'a> {
     code_editor: &'a mut CodeEditor,
     active_decoration: Option<ActiveDecoration>,
     decorations: Iter<'a, Decoration>,
+    style: DecorationStyle,
 }
 
 impl<'a> DrawDecorationLayer<'a> {
@@ -1386,25 +2023,49 @@ impl<'a> DrawDecorationLayer<'a> {
         row_index: usize,
         column_index: usize,
     ) {
-        let start_x = mem::take(&mut self.active_decoration.as_mut().unwrap().start_x);
+        let active_decoration = self.active_decoration.as_mut().unwrap();
+        let start_x = mem::take(&mut active_decoration.start_x);
+        let id = active_decoration.decoration.id;
+        let severity = active_decoration.decoration.severity;
         let (x, y) = line.grid_to_normalized_position(row_index, column_index);
-        self.code_editor.draw_decoration.draw_abs(
-            cx,
-            Rect {
-                pos: DVec2 {
-                    x: start_x,
-                    y: origin_y + y,
-                } *self.code_editor.cell_size
-                    + self.code_editor.viewport_rect.pos,
-                size: DVec2 {
-                    x: x - start_x,
-                    y: line.scale(),
-                } *self.code_editor.cell_size,
-            },
-        );
+        let rect = Rect {
+            pos: DVec2 {
+                x: start_x,
+                y: origin_y + y,
+            } *self.code_editor.cell_size
+                + self.code_editor.viewport_rect.pos,
+            size: DVec2 {
+                x: x - start_x,
+                y: line.scale(),
+            } *self.code_editor.cell_size,
+        };
+        match self.style {
+            DecorationStyle::Diagnostic => {
+                self.code_editor.draw_decoration.color = self.code_editor.severity_colors.color(severity);
+                self.code_editor.draw_decoration.draw_abs(cx, rect);
+            }
+            DecorationStyle::SearchMatch => self.code_editor.draw_search_match.draw_abs(cx, rect),
+            DecorationStyle::PeerSelection => {
+                self.code_editor.draw_peer_selection.color = peer_color(id, 0x40);
+                self.code_editor.draw_peer_selection.draw_abs(cx, rect);
+            }
+        }
     }
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum DecorationStyle {
+    Diagnostic,
+    SearchMatch,
+    PeerSelection,
+}
+
+// a color per peer, so that they can be told apart
+fn peer_color(peer_id: usize, alpha: u32) -> Vec4 {
+    const COLORS: [u32; 6] = [0x4EC9B0, 0xD7BA7D, 0xC586C0, 0x569CD6, 0xCE9178, 0xB5CEA8];
+    Vec4::from_u32(COLORS[peer_id % COLORS.len()] << 8 | alpha)
+}
+
 struct ActiveDecoration {
     decoration: Decoration,
     start_x: f64,
@@ -1642,7 +2303,7 @@ struct ActiveSelection {
 }
 
 #[derive(Live, LiveHook)]
-struct TokenColors {
+pub struct TokenColors {
     #[live]
     unknown: Vec4,
     #[live]
@@ -1671,6 +2332,71 @@ struct TokenColors {
     typename: Vec4,
     #[live]
     whitespace: Vec4,
+    #[live]
+    inlay: Vec4,
+}
+
+impl TokenColors {
+    pub fn color(&self, kind: TokenKind) -> Vec4 {
+        match kind {
+            TokenKind::Unknown => self.unknown,
+            TokenKind::BranchKeyword => self.branch_keyword,
+            TokenKind::Comment => self.comment,
+            TokenKind::Constant => self.constant,
+            TokenKind::Delimiter => self.delimiter,
+            TokenKind::Identifier => self.identifier,
+            TokenKind::LoopKeyword => self.loop_keyword,
+            TokenKind::Number => self.number,
+            TokenKind::OtherKeyword => self.other_keyword,
+            TokenKind::Punctuator => self.punctuator,
+            TokenKind::String => self.string,
+            TokenKind::Typename => self.typename,
+            TokenKind::Whitespace => self.whitespace,
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct LineChangeColors {
+    #[live]
+    added: Vec4,
+    #[live]
+    modified: Vec4,
+    #[live]
+    deleted: Vec4,
+}
+
+impl LineChangeColors {
+    pub fn color(&self, kind: LineChangeKind) -> Vec4 {
+        match kind {
+            LineChangeKind::Added => self.added,
+            LineChangeKind::Modified => self.modified,
+            LineChangeKind::Deleted => self.deleted,
+        }
+    }
+}
+
+#[derive(Live, LiveHook)]
+pub struct SeverityColors {
+    #[live]
+    error: Vec4,
+    #[live]
+    warning: Vec4,
+    #[live]
+    information: Vec4,
+    #[live]
+    hint: Vec4,
+}
+
+impl SeverityColors {
+    pub fn color(&self, severity: Severity) -> Vec4 {
+        match severity {
+            Severity::Error => self.error,
+            Severity::Warning => self.warning,
+            Severity::Information => self.information,
+            Severity::Hint => self.hint,
+        }
+    }
 }
 
 #[derive(Live, LiveHook)]
@@ -1683,9 +2409,23 @@ pub struct DrawIndentGuide {
 }
 
 #[derive(Live, LiveHook)]
+#[repr(C)]
+pub struct DrawFoldMarker {
+    #[deref]
+    draw_super: DrawQuad,
+    #[live]
+    color: Vec4,
+    #[live]
+    folded: f32,
+}
+
+#[derive(Live, LiveHook)]
+#[repr(C)]
 struct DrawDecoration {
     #[deref]
     draw_super: DrawQuad,
+    #[live]
+    color: Vec4,
 }
 
 #[derive(Live, LiveHook)]
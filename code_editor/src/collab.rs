@@ -0,0 +1,328 @@
+//! Operational transformation for editing a text with several peers at once.
+//!
+//! The edits of all peers are put in a single order by a relay, which keeps a `SharedText`. Each
+//! peer sends its changes along with the revision of the shared text that they were made to, and
+//! waits for the relay to confirm them before it sends any more, so that it only ever has one set
+//! of changes in flight. The relay transforms the changes against the ones that were ordered
+//! after that revision, and passes them on to all peers. A peer keeps track of this with a
+//! `SyncState`, which transforms the changes it receives against the local ones that the relay
+//! has not ordered yet.
+
+use crate::text::{Change, Drift, Edit, Length, Position, Text};
+
+/// Transforms two lists of changes that were made to the same text, so that `b` can be applied
+/// after `a` and `a` after `b`, with the same result. The first list that is returned is `a`
+/// transformed to apply after `b`, and the second one `b` transformed to apply after `a`.
+///
+/// `a` is ordered before `b`, so where both insert text at the same position, the text of `a` ends
+/// up first. Text that is inserted inside a range that is deleted by the other list is kept.
+pub fn transform(a: &[Change], b: &[Change]) -> (Vec<Change>, Vec<Change>) {
+    if a.is_empty() || b.is_empty() {
+        return (a.to_vec(), b.to_vec());
+    }
+    if a.len() > 1 {
+        let (a_first, b) = transform(&a[..1], b);
+        let (a_rest, b) = transform(&a[1..], &b);
+        return ([a_first, a_rest].concat(), b);
+    }
+    if b.len() > 1 {
+        let (a, b_first) = transform(a, &b[..1]);
+        let (a, b_rest) = transform(&a, &b[1..]);
+        return (a, [b_first, b_rest].concat());
+    }
+    transform_change(&a[0], &b[0])
+}
+
+/// Moves `position` along with the text around it as `changes` are applied. `drift` says on which
+/// side of text that is inserted at the position it ends up, as in `Position::apply_edit`.
+pub fn transform_position(position: Position, changes: &[Change], drift: Drift) -> Position {
+    changes
+        .iter()
+        .fold(position, |position, change| move_position(position, change, drift))
+}
+
+/// The copy of a text that a relay keeps, along with the changes that it put in order. The
+/// revision of the text is the number of changes.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SharedText {
+    text: Text,
+    // the revision that the oldest changes in the history were made to
+    base_revision: usize,
+    history: Vec<Vec<Change>>,
+}
+
+impl SharedText {
+    pub fn new(text: Text) -> Self {
+        Self {
+            text,
+            base_revision: 0,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn as_text(&self) -> &Text {
+        &self.text
+    }
+
+    pub fn revision(&self) -> usize {
+        self.base_revision + self.history.len()
+    }
+
+    /// Forgets the changes that were made before `revision`, so that changes and positions in
+    /// older revisions can't be transformed anymore. The relay calls this with the oldest revision
+    /// that a peer may still send changes in.
+    pub fn trim_history(&mut self, revision: usize) {
+        let count = revision.saturating_sub(self.base_revision).min(self.history.len());
+        self.history.drain(..count);
+        self.base_revision += count;
+    }
+
+    /// Applies changes that a peer made to `revision` of the text, and returns them as they apply
+    /// to the latest one. Returns `None` without changing anything if there is no such revision or
+    /// the changes don't fit the text, since they come from elsewhere.
+    pub fn apply_changes(&mut self, revision: usize, mut changes: Vec<Change>) -> Option<Vec<Change>> {
+        for history_changes in self.history_since(revision)? {
+            changes = transform(history_changes, &changes).1;
+        }
+        let mut text = self.text.clone();
+        for change in &changes {
+            if !fits(&text, change) {
+                return None;
+            }
+            text.apply_change(change.clone());
+        }
+        self.text = text;
+        self.history.push(changes.clone());
+        Some(changes)
+    }
+
+    /// Moves a position in `revision` of the text to where it is in the latest one, or returns
+    /// `None` if there is no such revision or the position is not in the text.
+    pub fn transform_position(&self, revision: usize, position: Position) -> Option<Position> {
+        let mut position = position;
+        for changes in self.history_since(revision)? {
+            position = transform_position(position, changes, Drift::Before);
+        }
+        if is_in_text(&self.text, position) {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    fn history_since(&self, revision: usize) -> Option<&[Vec<Change>]> {
+        self.history.get(revision.checked_sub(self.base_revision)?..)
+    }
+}
+
+/// What a peer knows about the shared text: the revision that its copy is based on, and the local
+/// changes that the relay has not ordered yet.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncState {
+    revision: usize,
+    // the changes that were sent to the relay, and not confirmed yet
+    sent_changes: Option<Vec<Change>>,
+    // the changes that were made since then
+    pending_changes: Vec<Change>,
+}
+
+impl SyncState {
+    pub fn new(revision: usize) -> Self {
+        Self {
+            revision,
+            ..Self::default()
+        }
+    }
+
+    pub fn revision(&self) -> usize {
+        self.revision
+    }
+
+    /// Returns whether all local changes have been ordered by the relay, so that positions in the
+    /// local text are positions in the latest revision.
+    pub fn is_synced(&self) -> bool {
+        self.sent_changes.is_none()
+    }
+
+    /// Records changes that were made locally. Returns them along with the revision they were
+    /// made to if they should be sent to the relay now, which is when there are no others in
+    /// flight.
+    pub fn apply_local_changes(&mut self, changes: Vec<Change>) -> Option<(usize, Vec<Change>)> {
+        if changes.is_empty() {
+            return None;
+        }
+        if self.sent_changes.is_some() {
+            self.pending_changes.extend(changes);
+            return None;
+        }
+        self.sent_changes = Some(changes.clone());
+        Some((self.revision, changes))
+    }
+
+    /// Records that the relay ordered the changes that were sent to it, and returns the changes
+    /// that were made in the meantime if there are any, which should be sent next.
+    pub fn confirm_changes(&mut self) -> Option<(usize, Vec<Change>)> {
+        self.sent_changes = None;
+        self.revision += 1;
+        let changes = std::mem::take(&mut self.pending_changes);
+        self.apply_local_changes(changes)
+    }
+
+    /// Transforms the changes of another peer, as ordered by the relay, so that they can be
+    /// applied to the local text.
+    pub fn apply_remote_changes(&mut self, changes: Vec<Change>) -> Vec<Change> {
+        self.revision += 1;
+        let Some(sent_changes) = &self.sent_changes else {
+            return changes;
+        };
+        let (changes, sent_changes) = transform(&changes, sent_changes);
+        let (changes, pending_changes) = transform(&changes, &self.pending_changes);
+        self.sent_changes = Some(sent_changes);
+        self.pending_changes = pending_changes;
+        changes
+    }
+
+    /// Moves a position in the latest revision to where it is in the local text.
+    pub fn transform_remote_position(&self, position: Position) -> Position {
+        let position = match &self.sent_changes {
+            Some(sent_changes) => transform_position(position, sent_changes, Drift::Before),
+            None => position,
+        };
+        transform_position(position, &self.pending_changes, Drift::Before)
+    }
+}
+
+/// A selection of another peer, which is shown in the local text.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PeerSelection {
+    pub peer_id: usize,
+    pub anchor: Position,
+    pub cursor: Position,
+}
+
+impl PeerSelection {
+    pub fn start(self) -> Position {
+        self.anchor.min(self.cursor)
+    }
+
+    pub fn end(self) -> Position {
+        self.anchor.max(self.cursor)
+    }
+
+    pub fn apply_edit(self, edit: &Edit) -> Self {
+        Self {
+            anchor: self.anchor.apply_edit(edit),
+            cursor: self.cursor.apply_edit(edit),
+            ..self
+        }
+    }
+}
+
+fn transform_change(a: &Change, b: &Change) -> (Vec<Change>, Vec<Change>) {
+    match (a, b) {
+        (Change::Insert(position_a, text_a), Change::Insert(position_b, text_b)) => {
+            if position_a <= position_b {
+                (
+                    vec![a.clone()],
+                    vec![Change::Insert(move_position(*position_b, a, Drift::Before), text_b.clone())],
+                )
+            } else {
+                (
+                    vec![Change::Insert(move_position(*position_a, b, Drift::Before), text_a.clone())],
+                    vec![b.clone()],
+                )
+            }
+        }
+        (Change::Insert(position, text), Change::Delete(start, length)) => {
+            let (insert, delete) = transform_insert_delete(*position, text, *start, *length);
+            (vec![insert], delete)
+        }
+        (Change::Delete(start, length), Change::Insert(position, text)) => {
+            let (insert, delete) = transform_insert_delete(*position, text, *start, *length);
+            (delete, vec![insert])
+        }
+        (Change::Delete(..), Change::Delete(..)) => (
+            transform_delete_delete(a, b).into_iter().collect(),
+            transform_delete_delete(b, a).into_iter().collect(),
+        ),
+    }
+}
+
+// text that is inserted inside the range of a delete is kept, by splitting the delete in two
+fn transform_insert_delete(
+    position: Position,
+    text: &Text,
+    start: Position,
+    length: Length,
+) -> (Change, Vec<Change>) {
+    let end = start + length;
+    if position <= start {
+        let insert = Change::Insert(position, text.clone());
+        let start = move_position(start, &insert, Drift::Before);
+        (insert, vec![Change::Delete(start, length)])
+    } else if position >= end {
+        let delete = Change::Delete(start, length);
+        let position = move_position(position, &delete, Drift::Before);
+        (Change::Insert(position, text.clone()), vec![delete])
+    } else {
+        (
+            Change::Insert(start, text.clone()),
+            // the second half is deleted first, so that the first one stays where it is
+            vec![
+                Change::Delete(position + text.length(), end - position),
+                Change::Delete(start, position - start),
+            ],
+        )
+    }
+}
+
+// the part of `a` that is left after `b` is applied
+fn transform_delete_delete(a: &Change, b: &Change) -> Option<Change> {
+    let Change::Delete(start, length) = *a else {
+        unreachable!()
+    };
+    let new_start = move_position(start, b, Drift::Before);
+    let new_end = move_position(start + length, b, Drift::Before);
+    if new_start == new_end {
+        None
+    } else {
+        Some(Change::Delete(new_start, new_end - new_start))
+    }
+}
+
+fn move_position(position: Position, change: &Change, drift: Drift) -> Position {
+    match *change {
+        Change::Insert(point, ref text) => {
+            if position < point || position == point && drift == Drift::After {
+                position
+            } else {
+                point + text.length() + (position - point)
+            }
+        }
+        Change::Delete(start, length) => {
+            let end = start + length;
+            if position < start {
+                position
+            } else {
+                start + (position - end.min(position))
+            }
+        }
+    }
+}
+
+fn fits(text: &Text, change: &Change) -> bool {
+    match change {
+        Change::Insert(position, inserted_text) => {
+            is_in_text(text, *position) && !inserted_text.as_lines().is_empty()
+        }
+        Change::Delete(start, length) => {
+            is_in_text(text, *start) && is_in_text(text, *start + *length)
+        }
+    }
+}
+
+fn is_in_text(text: &Text, position: Position) -> bool {
+    text.as_lines()
+        .get(position.line_index)
+        .map_or(false, |line| line.is_char_boundary(position.byte_index))
+}
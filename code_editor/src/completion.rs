@@ -0,0 +1,219 @@
+use {
+    crate::{str::StrExt, text::Position},
+    makepad_widgets::*,
+};
+
+live_design!{
+    import makepad_widgets::theme_desktop_dark::*;
+
+    CompletionPopup = {{CompletionPopup}} {
+        min_column_count: 20.0,
+        draw_bg: {
+            draw_depth: 3.0,
+            color: #25
+        }
+        draw_selection: {
+            draw_depth: 3.1,
+            color: #094771
+        }
+        draw_label: {
+            draw_depth: 3.2,
+            text_style: <THEME_FONT_CODE> {},
+            color: #D4D4D4
+        }
+        draw_detail: {
+            draw_depth: 3.2,
+            text_style: <THEME_FONT_CODE> {},
+            color: #8
+        }
+    }
+}
+
+/// A suggestion for the word before the cursor.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Completion {
+    pub label: String,
+    pub detail: Option<String>,
+    /// The text that replaces the word when the completion is accepted.
+    pub insert_text: String,
+}
+
+/// A list of completions below the word that they are for, which narrows down to the ones that
+/// start with what has been typed of the word so far.
+#[derive(Live, LiveHook)]
+pub struct CompletionPopup {
+    #[live]
+    min_column_count: f64,
+    #[live(10usize)]
+    max_visible_item_count: usize,
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    draw_selection: DrawColor,
+    #[live]
+    draw_label: DrawText,
+    #[live]
+    draw_detail: DrawText,
+    #[rust]
+    is_open: bool,
+    // where the word that is completed starts
+    #[rust]
+    start: Position,
+    #[rust]
+    completions: Vec<Completion>,
+    // the indices of the completions that match the word
+    #[rust]
+    matches: Vec<usize>,
+    // an index into `matches`
+    #[rust]
+    selected_index: usize,
+    #[rust]
+    first_visible_index: usize,
+    #[rust]
+    rect: Rect,
+    #[rust]
+    row_height: f64,
+}
+
+impl CompletionPopup {
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    pub fn contains(&self, abs: DVec2) -> bool {
+        self.is_open && self.rect.contains(abs)
+    }
+
+    pub fn open(&mut self, start: Position, completions: Vec<Completion>) {
+        self.is_open = true;
+        self.start = start;
+        self.matches = (0..completions.len()).collect();
+        self.completions = completions;
+        self.selected_index = 0;
+        self.first_visible_index = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.is_open = false;
+        self.completions.clear();
+        self.matches.clear();
+    }
+
+    /// Keeps the completions that start with `prefix`, ignoring case, and returns whether there
+    /// are any.
+    pub fn filter(&mut self, prefix: &str) -> bool {
+        let prefix = prefix.to_lowercase();
+        let selected = self.matches.get(self.selected_index).copied();
+        self.matches = self
+            .completions
+            .iter()
+            .enumerate()
+            .filter(|(_, completion)| completion.label.to_lowercase().starts_with(&prefix))
+            .map(|(index, _)| index)
+            .collect();
+        self.selected_index = selected
+            .and_then(|selected| self.matches.iter().position(|&index| index == selected))
+            .unwrap_or(0);
+        self.scroll_to_selection();
+        !self.matches.is_empty()
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.matches.len();
+            self.scroll_to_selection();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected_index = (self.selected_index + self.matches.len() - 1) % self.matches.len();
+            self.scroll_to_selection();
+        }
+    }
+
+    pub fn selected(&self) -> Option<&Completion> {
+        self.matches
+            .get(self.selected_index)
+            .map(|&index| &self.completions[index])
+    }
+
+    /// Selects the completion under `abs`, and returns whether there is one.
+    pub fn select_at(&mut self, abs: DVec2) -> bool {
+        if !self.contains(abs) || self.row_height <= 0.0 {
+            return false;
+        }
+        let index = self.first_visible_index + ((abs.y - self.rect.pos.y) / self.row_height) as usize;
+        if index < self.matches.len() {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Draws the popup below the cell at `anchor`, or above it if there is no room below.
+    pub fn draw(&mut self, cx: &mut Cx2d, anchor: DVec2, cell_size: DVec2, font_size: f64, bounds: Rect) {
+        if !self.is_open || self.matches.is_empty() {
+            return;
+        }
+        let end = (self.first_visible_index + self.max_visible_item_count).min(self.matches.len());
+        let visible_matches = &self.matches[self.first_visible_index..end];
+        let column_count = visible_matches
+            .iter()
+            .map(|&index| {
+                let completion = &self.completions[index];
+                completion.label.column_count()
+                    + completion.detail.as_ref().map_or(0, |detail| detail.column_count() + 2)
+            })
+            .max()
+            .unwrap_or(0) as f64;
+        let size = dvec2(
+            (column_count.max(self.min_column_count) + 2.0) * cell_size.x,
+            visible_matches.len() as f64 * cell_size.y,
+        );
+        let mut pos = dvec2(anchor.x - cell_size.x, anchor.y + cell_size.y);
+        if pos.y + size.y > bounds.pos.y + bounds.size.y {
+            pos.y = anchor.y - size.y;
+        }
+        pos.x = pos.x.min(bounds.pos.x + bounds.size.x - size.x).max(bounds.pos.x);
+        self.rect = Rect {pos, size};
+        self.row_height = cell_size.y;
+        self.draw_bg.draw_abs(cx, self.rect);
+        self.draw_label.text_style.font_size = font_size;
+        self.draw_detail.text_style.font_size = font_size;
+        for (row_index, &index) in visible_matches.iter().enumerate() {
+            let y = pos.y + row_index as f64 * cell_size.y;
+            if self.first_visible_index + row_index == self.selected_index {
+                self.draw_selection.draw_abs(
+                    cx,
+                    Rect {
+                        pos: dvec2(pos.x, y),
+                        size: dvec2(size.x, cell_size.y),
+                    },
+                );
+            }
+            let completion = &self.completions[index];
+            self.draw_label.draw_abs(cx, dvec2(pos.x + cell_size.x, y), &completion.label);
+            if let Some(detail) = &completion.detail {
+                let x = pos.x + size.x - (detail.column_count() + 1) as f64 * cell_size.x;
+                self.draw_detail.draw_abs(cx, dvec2(x, y), detail);
+            }
+        }
+    }
+
+    fn scroll_to_selection(&mut self) {
+        if self.selected_index < self.first_visible_index {
+            self.first_visible_index = self.selected_index;
+        } else if self.selected_index >= self.first_visible_index + self.max_visible_item_count {
+            self.first_visible_index = self.selected_index + 1 - self.max_visible_item_count;
+        }
+        self.first_visible_index = self
+            .first_visible_index
+            .min(self.matches.len().saturating_sub(self.max_visible_item_count));
+    }
+}
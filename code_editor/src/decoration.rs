@@ -3,9 +3,20 @@ use {
     std::{ops::Deref, slice::Iter},
 };
 
+/// How serious the problem marked by a decoration is, the editor draws each in a color of its own.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Decoration {
     pub id: usize,
+    pub severity: Severity,
     start: Position,
     end: Position,
 }
@@ -15,17 +26,23 @@ impl Decoration {
         if start > end{
             return Self {
                 id,
+                severity: Severity::default(),
                 start:end,
                 end:start,
             }
         }
         Self {
             id,
+            severity: Severity::default(),
             start,
             end,
         }
     }
 
+    pub fn with_severity(self, severity: Severity) -> Self {
+        Self { severity, ..self }
+    }
+
     pub fn is_empty(self) -> bool {
         self.start == self.end
     }
@@ -90,6 +107,10 @@ impl DecorationSet {
         self.decorations.clear();
     }
 
+    pub fn clear_with_id(&mut self, id: usize) {
+        self.decorations.retain(|decoration| decoration.id != id);
+    }
+
     pub fn apply_edit(&mut self, edit: &Edit) {
         for decoration in &mut self.decorations {
             *decoration = decoration.apply_edit(edit);
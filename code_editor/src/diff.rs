@@ -0,0 +1,174 @@
+use std::ops::Range;
+
+/// How a range of lines differs from the text it is compared with.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum LineChangeKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// A range of lines of the old text that was replaced by a range of lines of the new one. The old
+/// range is empty for lines that were added, and the new range for lines that were deleted.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct LineChange {
+    pub old_line_range: Range<usize>,
+    pub new_line_range: Range<usize>,
+}
+
+impl LineChange {
+    pub fn kind(&self) -> LineChangeKind {
+        if self.old_line_range.is_empty() {
+            LineChangeKind::Added
+        } else if self.new_line_range.is_empty() {
+            LineChangeKind::Deleted
+        } else {
+            LineChangeKind::Modified
+        }
+    }
+}
+
+// past this many edits, the lines that are left are compared as a whole, to bound the time and
+// memory that very different texts take
+const MAX_EDIT_COUNT: usize = 1000;
+
+/// Compares two texts line by line, and returns the changes that turn the old one into the new
+/// one, ordered by line.
+///
+/// The changes are a shortest edit script found with Myers' algorithm, after the lines that both
+/// texts start and end with are skipped.
+pub fn diff_lines(old_lines: &[String], new_lines: &[String]) -> Vec<LineChange> {
+    let prefix_len = old_lines
+        .iter()
+        .zip(new_lines)
+        .take_while(|(old_line, new_line)| old_line == new_line)
+        .count();
+    let suffix_len = old_lines[prefix_len..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix_len..].iter().rev())
+        .take_while(|(old_line, new_line)| old_line == new_line)
+        .count();
+    let old_lines = &old_lines[prefix_len..old_lines.len() - suffix_len];
+    let new_lines = &new_lines[prefix_len..new_lines.len() - suffix_len];
+    let mut changes = Vec::new();
+    let mut old_line_index = 0;
+    let mut new_line_index = 0;
+    let mut push_change = |old_line_range: Range<usize>, new_line_range: Range<usize>| {
+        if !old_line_range.is_empty() || !new_line_range.is_empty() {
+            changes.push(LineChange {
+                old_line_range: prefix_len + old_line_range.start..prefix_len + old_line_range.end,
+                new_line_range: prefix_len + new_line_range.start..prefix_len + new_line_range.end,
+            });
+        }
+    };
+    for (old_match_index, new_match_index) in matching_lines(old_lines, new_lines).unwrap_or_default() {
+        push_change(old_line_index..old_match_index, new_line_index..new_match_index);
+        old_line_index = old_match_index + 1;
+        new_line_index = new_match_index + 1;
+    }
+    push_change(old_line_index..old_lines.len(), new_line_index..new_lines.len());
+    changes
+}
+
+/// Returns the line of the old text that a line of the new text was compared with. That is `Ok`
+/// with the same line if it is unchanged, and `Err` with the first line of the change it is in
+/// otherwise.
+pub fn map_new_line_index(changes: &[LineChange], new_line_index: usize) -> Result<usize, usize> {
+    map_line_index(
+        changes.iter().map(|change| (&change.new_line_range, &change.old_line_range)),
+        new_line_index,
+    )
+}
+
+/// Returns the line of the new text that a line of the old text was compared with, see
+/// `map_new_line_index`.
+pub fn map_old_line_index(changes: &[LineChange], old_line_index: usize) -> Result<usize, usize> {
+    map_line_index(
+        changes.iter().map(|change| (&change.old_line_range, &change.new_line_range)),
+        old_line_index,
+    )
+}
+
+fn map_line_index<'a>(
+    ranges: impl Iterator<Item = (&'a Range<usize>, &'a Range<usize>)>,
+    line_index: usize,
+) -> Result<usize, usize> {
+    // the number of lines that the changes so far added to the other text
+    let mut line_count_delta = 0;
+    for (range, other_range) in ranges {
+        if line_index < range.start {
+            break;
+        }
+        if line_index < range.end {
+            return Err(other_range.start);
+        }
+        line_count_delta = other_range.end as isize - range.end as isize;
+    }
+    Ok((line_index as isize + line_count_delta) as usize)
+}
+
+// the pairs of lines that are the same in both texts, in order, or `None` if there are too many
+// edits between them
+fn matching_lines(old_lines: &[String], new_lines: &[String]) -> Option<Vec<(usize, usize)>> {
+    let old_len = old_lines.len() as isize;
+    let new_len = new_lines.len() as isize;
+    let max_edit_count = (old_lines.len() + new_lines.len()).min(MAX_EDIT_COUNT) as isize;
+    // the furthest old line index that each diagonal `k` reaches, offset by the edit count
+    let offset = max_edit_count + 1;
+    let mut furthest = vec![0; 2 * offset as usize + 1];
+    let mut trace = Vec::new();
+    for edit_count in 0..=max_edit_count {
+        // the diagonals of the previous edit count, which the path is traced back through
+        trace.push(furthest[(offset - edit_count) as usize..=(offset + edit_count) as usize].to_vec());
+        for k in (-edit_count..=edit_count).step_by(2) {
+            let mut old_index = if k == -edit_count
+                || k != edit_count && furthest[(offset + k - 1) as usize] < furthest[(offset + k + 1) as usize]
+            {
+                furthest[(offset + k + 1) as usize]
+            } else {
+                furthest[(offset + k - 1) as usize] + 1
+            };
+            let mut new_index = old_index - k;
+            while old_index < old_len
+                && new_index < new_len
+                && old_lines[old_index as usize] == new_lines[new_index as usize]
+            {
+                old_index += 1;
+                new_index += 1;
+            }
+            furthest[(offset + k) as usize] = old_index;
+            if old_index >= old_len && new_index >= new_len {
+                return Some(trace_matching_lines(&trace, old_len, new_len));
+            }
+        }
+    }
+    None
+}
+
+fn trace_matching_lines(trace: &[Vec<isize>], old_len: isize, new_len: isize) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+    let mut old_index = old_len;
+    let mut new_index = new_len;
+    for (edit_count, furthest) in trace.iter().enumerate().rev() {
+        let edit_count = edit_count as isize;
+        let furthest = |k: isize| furthest[(k + edit_count) as usize];
+        let k = old_index - new_index;
+        let prev_k = if k == -edit_count || k != edit_count && furthest(k - 1) < furthest(k + 1) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let prev_old_index = if edit_count == 0 { 0 } else { furthest(prev_k) };
+        let prev_new_index = prev_old_index - prev_k;
+        while old_index > prev_old_index.max(0) && new_index > prev_new_index.max(0) {
+            old_index -= 1;
+            new_index -= 1;
+            matches.push((old_index as usize, new_index as usize));
+        }
+        old_index = prev_old_index;
+        new_index = prev_new_index;
+    }
+    matches.reverse();
+    matches
+}
@@ -0,0 +1,160 @@
+use {
+    crate::{
+        code_editor::{CodeEditor, CodeEditorAction},
+        diff::{self, LineChange},
+        session::Session,
+    },
+    makepad_widgets::*,
+};
+
+live_design!{
+    import makepad_widgets::theme_desktop_dark::*;
+    import crate::code_editor::CodeEditor;
+
+    DiffView = {{DiffView}} {
+        width: Fill,
+        height: Fill,
+        flow: Right,
+        spacing: 2.0,
+        // the old text is compared with the new one, so its changes are the other way around
+        old_editor: <CodeEditor> {
+            show_minimap: false,
+            highlight_line_changes: true,
+            line_change_colors: {
+                added: #B44040,
+                deleted: #4A8C3C,
+            }
+        }
+        new_editor: <CodeEditor> {
+            show_minimap: false,
+            highlight_line_changes: true,
+        }
+    }
+}
+
+/// Shows two versions of a text side by side, each in a `CodeEditor` of its own, and keeps the
+/// lines that correspond to each other at the same height as either side is scrolled.
+///
+/// The new document should have the old text as its diff base, and the old document the new text,
+/// so that both sides mark the lines that changed.
+#[derive(Live)]
+pub struct DiffView {
+    #[walk]
+    walk: Walk,
+    #[layout]
+    layout: Layout,
+    #[live]
+    old_editor: CodeEditor,
+    #[live]
+    new_editor: CodeEditor,
+    #[rust]
+    draw_state: DrawStateWrap<Walk>,
+    // the top lines of the old and the new editor as they were last synchronized
+    #[rust]
+    synced_top_lines: Option<((usize, f64), (usize, f64))>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DiffViewAction {
+    /// The new text was edited.
+    TextDidChange,
+}
+
+impl LiveHook for DiffView {
+    fn before_live_design(cx: &mut Cx) {
+        register_widget!(cx, DiffView)
+    }
+}
+
+impl Widget for DiffView {
+    fn redraw(&mut self, cx: &mut Cx) {
+        self.old_editor.redraw(cx);
+        self.new_editor.redraw(cx);
+    }
+
+    fn handle_widget_event_with(
+        &mut self,
+        _cx: &mut Cx,
+        _event: &Event,
+        _dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem),
+    ) {
+    }
+
+    fn walk(&mut self, _cx: &mut Cx) -> Walk {
+        self.walk
+    }
+
+    fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
+        if self.draw_state.begin(cx, walk) {
+            return WidgetDraw::hook_above();
+        }
+        self.draw_state.end();
+        WidgetDraw::done()
+    }
+}
+
+#[derive(Clone, PartialEq, WidgetRef)]
+pub struct DiffViewRef(WidgetRef);
+
+impl DiffView {
+    pub fn draw(&mut self, cx: &mut Cx2d, old_session: &mut Session, new_session: &mut Session) {
+        let walk = self.draw_state.get().unwrap();
+        cx.begin_turtle(walk, self.layout);
+        let width = (cx.turtle().rect().size.x - self.layout.spacing) / 2.0;
+        self.old_editor.draw_walk(cx, old_session, Walk::size(Size::Fixed(width), Size::Fill));
+        self.new_editor.draw_walk(cx, new_session, Walk::size(Size::Fill, Size::Fill));
+        cx.end_turtle();
+    }
+
+    pub fn handle_event(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        old_session: &mut Session,
+        new_session: &mut Session,
+    ) -> Vec<DiffViewAction> {
+        let mut actions = Vec::new();
+        self.old_editor.handle_event(cx, event, old_session);
+        for action in self.new_editor.handle_event(cx, event, new_session) {
+            if let CodeEditorAction::TextDidChange = action {
+                actions.push(DiffViewAction::TextDidChange);
+            }
+        }
+        self.sync_scroll(cx, old_session, new_session);
+        actions
+    }
+
+    fn sync_scroll(&mut self, cx: &mut Cx, old_session: &Session, new_session: &Session) {
+        let old_top_line = self.old_editor.top_line(old_session);
+        let new_top_line = self.new_editor.top_line(new_session);
+        if let Some((synced_old_top_line, synced_new_top_line)) = self.synced_top_lines {
+            let document = new_session.document();
+            let line_changes = document.line_changes();
+            if old_top_line != synced_old_top_line {
+                let (line_index, offset) = map_top_line(&line_changes, old_top_line, diff::map_old_line_index);
+                self.new_editor.set_top_line(cx, new_session, line_index, offset);
+            } else if new_top_line != synced_new_top_line {
+                let (line_index, offset) = map_top_line(&line_changes, new_top_line, diff::map_new_line_index);
+                self.old_editor.set_top_line(cx, old_session, line_index, offset);
+            }
+        }
+        // the editors can't always scroll as far as they were asked to, so the top lines are read
+        // back rather than assumed
+        self.synced_top_lines = Some((
+            self.old_editor.top_line(old_session),
+            self.new_editor.top_line(new_session),
+        ));
+    }
+}
+
+// lines that changed are lined up with the start of the lines they changed into
+fn map_top_line(
+    line_changes: &[LineChange],
+    (line_index, offset): (usize, f64),
+    map_line_index: fn(&[LineChange], usize) -> Result<usize, usize>,
+) -> (usize, f64) {
+    match map_line_index(line_changes, line_index) {
+        Ok(line_index) => (line_index, offset),
+        Err(line_index) => (line_index, 0.0),
+    }
+}
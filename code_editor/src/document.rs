@@ -1,22 +1,26 @@
 use {
     crate::{
         char::CharExt,
+        collab::PeerSelection,
         decoration::{Decoration, DecorationSet},
-        history::{EditKind, History},
+        diff::{self, LineChange},
+        history::{EditKind, History, HistoryStacks},
         inlays::{BlockInlay, InlineInlay},
         iter::IteratorExt,
+        language::{Language, Rust},
         selection::SelectionSet,
         session::SessionId,
         settings::Settings,
         str::StrExt,
+        structure::{self, FoldRange},
         text::{Change, Drift, Edit, Length, Position, Text},
         token::{Token, TokenKind},
         tokenizer::Tokenizer,
     },
     std::{
-        cell::{Ref, RefCell},
+        cell::{Cell, Ref, RefCell},
         cmp::Ordering,
-        collections::HashMap,
+        collections::{BTreeSet, HashMap},
         iter,
         ops::Range,
         rc::Rc,
@@ -29,6 +33,21 @@ pub struct Document(Rc<DocumentInner>);
 
 impl Document {
     pub fn new(text: Text, decorations:DecorationSet) -> Self {
+        Self::with_language(text, decorations, Rust)
+    }
+
+    pub fn with_language<L: Language + 'static>(text: Text, decorations: DecorationSet, language: L) -> Self {
+        let line_count = text.as_lines().len();
+        Self::with_tokenizer(text, decorations, Tokenizer::new(language, line_count))
+    }
+
+    /// Picks the language from the extension of a file name, see `Tokenizer::for_extension`.
+    pub fn for_extension(text: Text, decorations: DecorationSet, extension: &str) -> Self {
+        let line_count = text.as_lines().len();
+        Self::with_tokenizer(text, decorations, Tokenizer::for_extension(extension, line_count))
+    }
+
+    fn with_tokenizer(text: Text, decorations: DecorationSet, tokenizer: Tokenizer) -> Self {
         let line_count = text.as_lines().len();
         let tokens: Vec<_> = (0..line_count)
             .map(|line| tokenize(&text.as_lines()[line]).collect::<Vec<_>>())
@@ -41,9 +60,16 @@ impl Document {
                 inline_inlays: (0..line_count).map(|_| Vec::new()).collect(),
                 block_inlays: Vec::new(),
             }),
-            tokenizer: RefCell::new(Tokenizer::new(line_count)),
+            tokenizer: RefCell::new(tokenizer),
             decorations: RefCell::new(decorations),
+            peer_selections: RefCell::new(Vec::new()),
+            fold_ranges: RefCell::new(None),
+            folded_lines: RefCell::new(BTreeSet::new()),
+            fold_revision: Cell::new(0),
+            diff_base: RefCell::new(None),
+            line_changes: RefCell::new(None),
             edit_senders: RefCell::new(HashMap::new()),
+            edit_listeners: RefCell::new(Vec::new()),
         }));
         inner.update_indent_state();
         inner.0.tokenizer.borrow_mut().update(
@@ -65,6 +91,132 @@ impl Document {
         Ref::map(self.0.decorations.borrow(), |decorations| decorations.as_decorations())
     }
 
+    pub fn peer_selections(&self) -> Ref<'_, [PeerSelection]> {
+        Ref::map(self.0.peer_selections.borrow(), |peer_selections| peer_selections.as_slice())
+    }
+
+    /// Replaces the selections of the peer with the given id.
+    pub fn set_peer_selections(&mut self, peer_id: usize, selections: impl IntoIterator<Item = (Position, Position)>) {
+        let mut peer_selections = self.0.peer_selections.borrow_mut();
+        peer_selections.retain(|selection| selection.peer_id != peer_id);
+        peer_selections.extend(selections.into_iter().map(|(anchor, cursor)| PeerSelection {
+            peer_id,
+            anchor,
+            cursor,
+        }));
+        peer_selections.sort_by_key(|selection| selection.start());
+    }
+
+    pub fn clear_peer_selections(&mut self) {
+        self.0.peer_selections.borrow_mut().clear();
+    }
+
+    /// The ranges of lines that can be folded, see `structure::fold_ranges`.
+    pub fn fold_ranges(&self) -> Ref<'_, [FoldRange]> {
+        if self.0.fold_ranges.borrow().is_none() {
+            let fold_ranges = structure::fold_ranges(self.as_text().as_lines(), &self.layout().tokens);
+            *self.0.fold_ranges.borrow_mut() = Some(fold_ranges);
+        }
+        Ref::map(self.0.fold_ranges.borrow(), |fold_ranges| {
+            fold_ranges.as_deref().unwrap()
+        })
+    }
+
+    /// The lines that start the fold ranges that are folded. They are kept with the document, so
+    /// that every session of it shows the same folds.
+    pub fn folded_lines(&self) -> Ref<'_, BTreeSet<usize>> {
+        self.0.folded_lines.borrow()
+    }
+
+    /// A number that changes whenever a range is folded or unfolded, so that sessions know when to
+    /// update the lines they hide.
+    pub fn fold_revision(&self) -> usize {
+        self.0.fold_revision.get()
+    }
+
+    /// Folds or unfolds the range that starts at the given line, if there is one.
+    pub fn set_folded(&mut self, line_index: usize, is_folded: bool) {
+        let mut folded_lines = self.0.folded_lines.borrow_mut();
+        let is_changed = if is_folded {
+            self.fold_ranges()
+                .binary_search_by_key(&line_index, |range| range.start_line_index)
+                .is_ok()
+                && folded_lines.insert(line_index)
+        } else {
+            folded_lines.remove(&line_index)
+        };
+        if is_changed {
+            self.0.fold_revision.set(self.0.fold_revision.get() + 1);
+        }
+    }
+
+    pub fn diff_base(&self) -> Ref<'_, Option<Text>> {
+        self.0.diff_base.borrow()
+    }
+
+    /// Sets the text that the document is compared with, such as the version of its file in version
+    /// control, or clears it.
+    pub fn set_diff_base(&mut self, diff_base: Option<Text>) {
+        *self.0.diff_base.borrow_mut() = diff_base;
+        *self.0.line_changes.borrow_mut() = None;
+    }
+
+    /// The ranges of lines that differ from the diff base, see `diff::diff_lines`. There are none
+    /// without a diff base.
+    pub fn line_changes(&self) -> Ref<'_, [LineChange]> {
+        if self.0.line_changes.borrow().is_none() {
+            let line_changes = match &*self.diff_base() {
+                Some(diff_base) => diff::diff_lines(diff_base.as_lines(), self.as_text().as_lines()),
+                None => Vec::new(),
+            };
+            *self.0.line_changes.borrow_mut() = Some(line_changes);
+        }
+        Ref::map(self.0.line_changes.borrow(), |line_changes| {
+            line_changes.as_deref().unwrap()
+        })
+    }
+
+    /// Replaces the inlays of a line, which are given with the byte they are shown before.
+    pub fn set_inline_inlays(&mut self, line_index: usize, inlays: Vec<(usize, InlineInlay)>) {
+        self.0.layout.borrow_mut().inline_inlays[line_index] = inlays;
+        self.notify_line_changed(line_index);
+    }
+
+    /// Removes the inlays of every line.
+    pub fn clear_inline_inlays(&mut self) {
+        let line_indices: Vec<_> = self
+            .layout()
+            .inline_inlays
+            .iter()
+            .enumerate()
+            .filter(|(_, inlays)| !inlays.is_empty())
+            .map(|(line_index, _)| line_index)
+            .collect();
+        for line_index in line_indices {
+            self.0.layout.borrow_mut().inline_inlays[line_index].clear();
+            self.notify_line_changed(line_index);
+        }
+    }
+
+    fn notify_line_changed(&self, line_index: usize) {
+        // sessions lay out the lines that are edited again, so an empty insert tells them that a
+        // line changed without changing its text. Edit listeners only care about the text, so they
+        // aren't told.
+        let edit = Edit {
+            change: Change::Insert(
+                Position {
+                    line_index,
+                    byte_index: 0,
+                },
+                Text::new(),
+            ),
+            drift: Drift::Before,
+        };
+        for edit_sender in self.0.edit_senders.borrow().values() {
+            edit_sender.send((None, vec![edit.clone()])).unwrap();
+        }
+    }
+
     pub fn edit_selections(
         &self,
         session_id: SessionId,
@@ -119,7 +271,7 @@ impl Document {
         }
         drop(history);
         self.autoindent(&line_ranges, settings.tab_column_count, &mut edits);
-        self.update_after_edit(session_id, None, &edits);
+        self.update_after_edit(Some(session_id), None, &edits);
     }
 
     pub fn edit_linewise(
@@ -155,7 +307,79 @@ impl Document {
             }
         }
         drop(history);
-        self.update_after_edit(origin_id, None, &edits);
+        self.update_after_edit(Some(origin_id), None, &edits);
+    }
+
+    /// Replaces each range with its text in a single undo group. The ranges have to be sorted
+    /// and must not overlap.
+    pub fn replace(
+        &self,
+        session_id: SessionId,
+        selections: &SelectionSet,
+        replacements: Vec<(Position, Length, Text)>,
+    ) {
+        if replacements.is_empty() {
+            return;
+        }
+        let mut history = self.0.history.borrow_mut();
+        history.push_or_extend_group(session_id, EditKind::Other, selections);
+        let mut edits = Vec::new();
+        // replacing from back to front keeps the positions of the remaining ranges valid
+        for (start, length, text) in replacements.into_iter().rev() {
+            if length != Length::zero() {
+                edits.push(Edit {
+                    change: Change::Delete(start, length),
+                    drift: Drift::Before,
+                });
+            }
+            if !text.is_empty() {
+                edits.push(Edit {
+                    change: Change::Insert(start, text),
+                    drift: Drift::Before,
+                });
+            }
+        }
+        for edit in &edits {
+            history.apply_edit(edit.clone());
+        }
+        drop(history);
+        self.update_after_edit(Some(session_id), None, &edits);
+    }
+
+    /// Applies changes that were made elsewhere, such as by another peer. They can't be undone,
+    /// and move the selections of all sessions as edits from another session would.
+    pub fn apply_remote_changes(&mut self, changes: Vec<Change>) {
+        let edits: Vec<_> = changes
+            .into_iter()
+            .map(|change| Edit {
+                change,
+                drift: Drift::Before,
+            })
+            .collect();
+        let mut history = self.0.history.borrow_mut();
+        for edit in &edits {
+            history.apply_remote_edit(edit.clone());
+        }
+        drop(history);
+        self.update_after_edit(None, None, &edits);
+    }
+
+    pub fn history_stacks(&self) -> HistoryStacks {
+        self.0.history.borrow().stacks()
+    }
+
+    /// Replaces the text and the undo history, such as with ones that were saved in an earlier
+    /// session. A different text replaces the current one as a whole, as if it was changed
+    /// elsewhere.
+    pub fn restore_history(&mut self, text: Text, stacks: HistoryStacks) {
+        let current_text = self.as_text().clone();
+        if current_text != text {
+            self.apply_remote_changes(vec![
+                Change::Delete(Position::zero(), current_text.length()),
+                Change::Insert(Position::zero(), text),
+            ]);
+        }
+        self.0.history.borrow_mut().set_stacks(stacks);
     }
 
     pub fn add_decoration(&mut self, decoration: Decoration) {
@@ -166,6 +390,10 @@ impl Document {
         self.0.decorations.borrow_mut().clear()
     }
 
+    pub fn clear_decorations_with_id(&mut self, id: usize) {
+        self.0.decorations.borrow_mut().clear_with_id(id)
+    }
+
     pub fn add_session(
         &mut self,
         session_id: SessionId,
@@ -181,6 +409,13 @@ impl Document {
         self.0.edit_senders.borrow_mut().remove(&session_id);
     }
 
+    /// Sends the edits that are applied to the text from now on to `edit_sender`, in the order
+    /// they are applied, so that a copy of the text elsewhere can be kept in sync. The listener is
+    /// dropped when its receiver is.
+    pub fn add_edit_listener(&mut self, edit_sender: Sender<Vec<Edit>>) {
+        self.0.edit_listeners.borrow_mut().push(edit_sender);
+    }
+
     fn autoindent(
         &self,
         line_ranges: &[Range<usize>],
@@ -307,7 +542,7 @@ impl Document {
         let mut changes = Vec::new();
         let selections = self.0.history.borrow_mut().undo(selections, &mut changes);
         if let Some(selections) = selections {
-            self.update_after_edit(origin_id, Some(selections), &changes);
+            self.update_after_edit(Some(origin_id), Some(selections), &changes);
             true
         } else {
             false
@@ -318,7 +553,7 @@ impl Document {
         let mut changes = Vec::new();
         let selections = self.0.history.borrow_mut().redo(selections, &mut changes);
         if let Some(selections) = selections {
-            self.update_after_edit(origin_id, Some(selections), &changes);
+            self.update_after_edit(Some(origin_id), Some(selections), &changes);
             true
         } else {
             false
@@ -327,7 +562,7 @@ impl Document {
 
     fn update_after_edit(
         &self,
-        origin_id: SessionId,
+        origin_id: Option<SessionId>,
         selections: Option<SelectionSet>,
         edits: &[Edit],
     ) {
@@ -356,6 +591,7 @@ impl Document {
         }
         drop(layout);
         for edit in edits {
+            self.apply_change_to_folded_lines(&edit.change);
             self.apply_change_to_tokens(&edit.change);
             self.apply_change_to_inline_inlays(&edit.change, edit.drift);
             self.0.tokenizer.borrow_mut().apply_change(&edit.change);
@@ -365,13 +601,33 @@ impl Document {
             self.0.history.borrow().as_text(),
             &mut self.0.layout.borrow_mut().tokens,
         );
+        *self.0.fold_ranges.borrow_mut() = None;
+        *self.0.line_changes.borrow_mut() = None;
+        if !self.0.folded_lines.borrow().is_empty() {
+            // a fold whose range was edited away is dropped
+            let fold_ranges = self.fold_ranges();
+            self.0.folded_lines.borrow_mut().retain(|&line_index| {
+                fold_ranges
+                    .binary_search_by_key(&line_index, |range| range.start_line_index)
+                    .is_ok()
+            });
+        }
         let mut decorations = self.0.decorations.borrow_mut();
         for edit in edits {
             decorations.apply_edit(edit);
         }
         drop(decorations);
+        for selection in self.0.peer_selections.borrow_mut().iter_mut() {
+            for edit in edits {
+                *selection = selection.apply_edit(edit);
+            }
+        }
+        self.0
+            .edit_listeners
+            .borrow_mut()
+            .retain(|edit_sender| edit_sender.send(edits.to_vec()).is_ok());
         for (&session_id, edit_sender) in &*self.0.edit_senders.borrow() {
-            if session_id == origin_id {
+            if Some(session_id) == origin_id {
                 edit_sender
                     .send((selections.clone(), edits.to_vec()))
                     .unwrap();
@@ -393,6 +649,52 @@ impl Document {
         }
     }
 
+    fn apply_change_to_folded_lines(&self, change: &Change) {
+        let mut folded_lines = self.0.folded_lines.borrow_mut();
+        if folded_lines.is_empty() {
+            return;
+        }
+        // a folded line moves with lines that are inserted or deleted in front of it as a whole
+        *folded_lines = match *change {
+            Change::Insert(position, ref text) => {
+                let line_count = text.length().line_count;
+                folded_lines
+                    .iter()
+                    .map(|&line_index| {
+                        if line_index > position.line_index
+                            || line_index == position.line_index && position.byte_index == 0
+                        {
+                            line_index + line_count
+                        } else {
+                            line_index
+                        }
+                    })
+                    .collect()
+            }
+            Change::Delete(start, length) => {
+                let end_line_index = start.line_index + length.line_count;
+                let deletes_whole_lines =
+                    length.line_count > 0 && start.byte_index == 0 && length.byte_count == 0;
+                folded_lines
+                    .iter()
+                    .filter_map(|&line_index| {
+                        if line_index > end_line_index {
+                            Some(line_index - length.line_count)
+                        } else if line_index == end_line_index && deletes_whole_lines {
+                            Some(start.line_index)
+                        } else if line_index < start.line_index
+                            || line_index == start.line_index && !deletes_whole_lines
+                        {
+                            Some(line_index)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect()
+            }
+        };
+    }
+
     fn apply_change_to_tokens(&self, change: &Change) {
         let mut layout = self.0.layout.borrow_mut();
         let tokens = &mut layout.tokens;
@@ -668,7 +970,16 @@ struct DocumentInner {
     layout: RefCell<DocumentLayout>,
     tokenizer: RefCell<Tokenizer>,
     decorations: RefCell<DecorationSet>,
+    peer_selections: RefCell<Vec<PeerSelection>>,
+    // computed from the tokens when they are first asked for after an edit
+    fold_ranges: RefCell<Option<Vec<FoldRange>>>,
+    folded_lines: RefCell<BTreeSet<usize>>,
+    fold_revision: Cell<usize>,
+    diff_base: RefCell<Option<Text>>,
+    // computed from the diff base when they are first asked for after an edit
+    line_changes: RefCell<Option<Vec<LineChange>>>,
     edit_senders: RefCell<HashMap<SessionId, Sender<(Option<SelectionSet>, Vec<Edit>)>>>,
+    edit_listeners: RefCell<Vec<Sender<Vec<Edit>>>>,
 }
 
 fn tokenize(text: &str) -> impl Iterator<Item = Token> + '_ {
@@ -0,0 +1,243 @@
+use {
+    crate::{search::SearchQuery, session::Session},
+    makepad_widgets::*,
+};
+
+live_design!{
+    import makepad_draw::shader::std::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    FindBar = {{FindBar}} {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 5.0,
+        padding: {left: 10.0, top: 4.0, right: 10.0, bottom: 4.0},
+        align: {y: 0.5},
+        draw_bg: {
+            color: #33
+        }
+        query_input: <TextInput> {
+            width: 200,
+            height: Fit,
+            empty_message: "Find"
+        }
+        case_sensitive: <CheckBox> {text: "Aa"}
+        whole_word: <CheckBox> {text: "Word"}
+        regex: <CheckBox> {text: ".*"}
+        previous_button: <Button> {text: "<"}
+        next_button: <Button> {text: ">"}
+        replacement_input: <TextInput> {
+            width: 200,
+            height: Fit,
+            empty_message: "Replace"
+        }
+        replace_button: <Button> {text: "Replace"}
+        replace_all_button: <Button> {text: "All"}
+        status_walk: {width: Fit, height: Fit, margin: {left: 5.0}}
+        draw_status: {
+            text_style: <THEME_FONT_LABEL> {},
+            color: #a
+        }
+    }
+}
+
+/// The find and replace bar that the `CodeEditor` shows above the text.
+#[derive(Live, LiveHook)]
+pub struct FindBar {
+    #[walk]
+    walk: Walk,
+    #[layout]
+    layout: Layout,
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    query_input: TextInput,
+    #[live]
+    case_sensitive: CheckBox,
+    #[live]
+    whole_word: CheckBox,
+    #[live]
+    regex: CheckBox,
+    #[live]
+    previous_button: Button,
+    #[live]
+    next_button: Button,
+    #[live]
+    replacement_input: TextInput,
+    #[live]
+    replace_button: Button,
+    #[live]
+    replace_all_button: Button,
+    #[live]
+    status_walk: Walk,
+    #[live]
+    draw_status: DrawText,
+    #[rust]
+    query: SearchQuery,
+    #[rust]
+    status: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FindBarAction {
+    /// The selections moved to a match.
+    SelectionChanged,
+    TextDidChange,
+    Close,
+}
+
+impl FindBar {
+    /// Starts a search for `text`, or for the text of the last search if it is `None`, and gives
+    /// the query input key focus.
+    pub fn open(&mut self, cx: &mut Cx, session: &mut Session, text: Option<String>) {
+        if let Some(text) = text {
+            self.query_input.text = text;
+        }
+        self.query_input.select_all();
+        self.query_input.set_key_focus(cx);
+        self.update_search(session);
+        self.draw_bg.redraw(cx);
+    }
+
+    pub fn draw(&mut self, cx: &mut Cx2d) {
+        self.draw_bg.begin(cx, self.walk, self.layout);
+        let walk = self.query_input.walk(cx);
+        self.query_input.draw_walk(cx, walk);
+        for check_box in [&mut self.case_sensitive, &mut self.whole_word, &mut self.regex] {
+            let walk = check_box.walk(cx);
+            check_box.draw_walk(cx, walk);
+        }
+        for button in [&mut self.previous_button, &mut self.next_button] {
+            let walk = button.walk(cx);
+            button.draw_walk(cx, walk);
+        }
+        let walk = self.replacement_input.walk(cx);
+        self.replacement_input.draw_walk(cx, walk);
+        for button in [&mut self.replace_button, &mut self.replace_all_button] {
+            let walk = button.walk(cx);
+            button.draw_walk(cx, walk);
+        }
+        self.draw_status.draw_walk(cx, self.status_walk, Align::default(), &self.status);
+        self.draw_bg.end(cx);
+    }
+
+    pub fn handle_event(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        session: &mut Session,
+    ) -> Vec<FindBarAction> {
+        let mut a = Vec::new();
+        self.handle_event_with(cx, event, session, &mut | _, v | a.push(v));
+        a
+    }
+
+    pub fn handle_event_with(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        session: &mut Session,
+        dispatch_action: &mut dyn FnMut(&mut Cx, FindBarAction),
+    ) {
+        let mut query_actions = Vec::new();
+        self.query_input.handle_event_with(cx, event, &mut | _, action | query_actions.push(action));
+        // losing key focus also sends a return, which shouldn't move to the next match
+        let lost_focus = query_actions.contains(&TextInputAction::KeyFocusLost);
+        for action in query_actions {
+            match action {
+                TextInputAction::Change(_) => {
+                    self.update_search(session);
+                    if session.find_next(false) {
+                        dispatch_action(cx, FindBarAction::SelectionChanged);
+                    }
+                }
+                TextInputAction::Return(_) if !lost_focus => {
+                    if session.find_next(false) {
+                        dispatch_action(cx, FindBarAction::SelectionChanged);
+                    }
+                }
+                TextInputAction::Escape => dispatch_action(cx, FindBarAction::Close),
+                _ => {}
+            }
+        }
+
+        let mut options_changed = false;
+        for (check_box, option) in [
+            (&mut self.case_sensitive, &mut self.query.case_sensitive),
+            (&mut self.whole_word, &mut self.query.whole_word),
+            (&mut self.regex, &mut self.query.regex),
+        ] {
+            check_box.handle_event_with(cx, event, &mut | _, action | {
+                if let CheckBoxAction::Change(value) = action {
+                    *option = value;
+                    options_changed = true;
+                }
+            });
+        }
+        if options_changed {
+            self.update_search(session);
+        }
+
+        let mut previous = false;
+        let mut next = false;
+        self.previous_button.handle_event_with(cx, event, &mut | _, action | {
+            previous |= matches!(action, ButtonAction::Clicked)
+        });
+        self.next_button.handle_event_with(cx, event, &mut | _, action | {
+            next |= matches!(action, ButtonAction::Clicked)
+        });
+        if previous && session.find_previous(false) || next && session.find_next(false) {
+            dispatch_action(cx, FindBarAction::SelectionChanged);
+        }
+
+        let mut replace = false;
+        let mut replace_all = false;
+        let mut replacement_actions = Vec::new();
+        self.replacement_input.handle_event_with(cx, event, &mut | _, action | replacement_actions.push(action));
+        let lost_focus = replacement_actions.contains(&TextInputAction::KeyFocusLost);
+        for action in replacement_actions {
+            match action {
+                TextInputAction::Return(_) if !lost_focus => replace = true,
+                TextInputAction::Escape => dispatch_action(cx, FindBarAction::Close),
+                _ => {}
+            }
+        }
+        self.replace_button.handle_event_with(cx, event, &mut | _, action | {
+            replace |= matches!(action, ButtonAction::Clicked)
+        });
+        self.replace_all_button.handle_event_with(cx, event, &mut | _, action | {
+            replace_all |= matches!(action, ButtonAction::Clicked)
+        });
+        if replace {
+            if session.replace(&self.replacement_input.text) {
+                dispatch_action(cx, FindBarAction::TextDidChange);
+            }
+            dispatch_action(cx, FindBarAction::SelectionChanged);
+            self.update_status(session);
+            self.draw_bg.redraw(cx);
+        }
+        if replace_all && session.replace_all(&self.replacement_input.text) > 0 {
+            dispatch_action(cx, FindBarAction::TextDidChange);
+            self.update_status(session);
+            self.draw_bg.redraw(cx);
+        }
+    }
+
+    fn update_search(&mut self, session: &mut Session) {
+        self.query.text = self.query_input.text.clone();
+        match session.set_search(self.query.clone()) {
+            Ok(_) => self.update_status(session),
+            Err(error) => self.status = error.to_string(),
+        }
+    }
+
+    fn update_status(&mut self, session: &Session) {
+        self.status = match session.search_matches().len() {
+            _ if self.query.text.is_empty() => String::new(),
+            0 => "No results".to_string(),
+            1 => "1 match".to_string(),
+            count => format!("{} matches", count),
+        };
+    }
+}
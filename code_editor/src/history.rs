@@ -1,7 +1,11 @@
-use crate::{
-    selection::SelectionSet,
-    session::SessionId,
-    text::{Edit, Text},
+use {
+    crate::{
+        collab,
+        selection::SelectionSet,
+        session::SessionId,
+        text::{Change, Drift, Edit, Text},
+    },
+    makepad_widgets::makepad_micro_serde::{DeBin, DeBinErr, SerBin},
 };
 
 #[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
@@ -51,6 +55,15 @@ impl History {
         self.redo_stack.clear();
     }
 
+    /// Applies an edit that was made elsewhere, such as by another peer. It can't be undone, but
+    /// the edits on the undo and redo stacks are transformed so that they still apply after it.
+    pub fn apply_remote_edit(&mut self, edit: Edit) {
+        self.undo_stack.apply_remote_change(&edit.change);
+        self.redo_stack.apply_remote_change(&edit.change);
+        self.text.apply_change(edit.change);
+        self.current_desc = None;
+    }
+
     pub fn undo(
         &mut self,
         selections: &SelectionSet,
@@ -89,6 +102,23 @@ impl History {
         }
     }
 
+    /// Returns the undo and redo stacks, which can be saved so that the edits on them can still be
+    /// undone in a later session.
+    pub fn stacks(&self) -> HistoryStacks {
+        HistoryStacks {
+            undo_stack: self.undo_stack.clone(),
+            redo_stack: self.redo_stack.clone(),
+        }
+    }
+
+    /// Replaces the undo and redo stacks with ones that were taken from a history with the same
+    /// text.
+    pub fn set_stacks(&mut self, stacks: HistoryStacks) {
+        self.undo_stack = stacks.undo_stack;
+        self.redo_stack = stacks.redo_stack;
+        self.current_desc = None;
+    }
+
     pub fn into_text(self) -> Text {
         self.text
     }
@@ -103,6 +133,19 @@ impl From<Text> for History {
     }
 }
 
+/// The undo and redo stacks of a `History`, apart from its text.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, SerBin, DeBin)]
+pub struct HistoryStacks {
+    undo_stack: Stack,
+    redo_stack: Stack,
+}
+
+impl HistoryStacks {
+    pub fn is_empty(&self) -> bool {
+        self.undo_stack.groups.is_empty() && self.redo_stack.groups.is_empty()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum EditKind {
     Insert,
@@ -134,7 +177,7 @@ impl GroupDesc {
     }
 }
 
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, SerBin, DeBin)]
 struct Stack {
     groups: Vec<Group>,
     edits: Vec<Edit>,
@@ -166,9 +209,38 @@ impl Stack {
         self.groups.clear();
         self.edits.clear();
     }
+
+    fn apply_remote_change(&mut self, change: &Change) {
+        // the groups are transformed from the top down, since that is the order in which their
+        // edits apply, with the change moving down along with them
+        let mut changes = vec![change.clone()];
+        let mut groups = Vec::new();
+        while let Some(mut group) = self.groups.pop() {
+            let mut edits = Vec::new();
+            for edit in self.edits.drain(group.edit_start..).rev() {
+                let (new_changes, edit_changes) = collab::transform(&changes, &[edit.change]);
+                changes = new_changes;
+                edits.extend(edit_changes.into_iter().map(|change| Edit {
+                    change,
+                    drift: edit.drift,
+                }));
+            }
+            for change in &changes {
+                group.selections.apply_edit(&Edit {
+                    change: change.clone(),
+                    drift: Drift::Before,
+                });
+            }
+            groups.push((group.selections, edits));
+        }
+        for (selections, edits) in groups.into_iter().rev() {
+            self.push_group(selections);
+            self.edits.extend(edits.into_iter().rev());
+        }
+    }
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SerBin, DeBin)]
 struct Group {
     selections: SelectionSet,
     edit_start: usize,
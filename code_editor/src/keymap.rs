@@ -0,0 +1,791 @@
+use {
+    makepad_toml_parser::{parse_toml, Toml},
+    makepad_widgets::{KeyCode, KeyModifiers},
+    std::{collections::HashMap, error, fmt, mem},
+};
+
+/// An editor operation that can be bound to a key.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Command {
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    MoveWordLeft,
+    MoveWordRight,
+    MoveToStartOfLine,
+    MoveToEndOfLine,
+    MovePageUp,
+    MovePageDown,
+    MoveToStartOfText,
+    MoveToEndOfText,
+    SelectWord,
+    SelectLine,
+    MoveLinesUp,
+    MoveLinesDown,
+    DuplicateLines,
+    Enter,
+    Indent,
+    Outdent,
+    Delete,
+    Backspace,
+    DeleteLine,
+    DeleteToEndOfLine,
+    Undo,
+    Redo,
+    Find,
+    FindNext,
+    FindPrevious,
+    AddNextMatch,
+    AddPreviousMatch,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+    ToggleWordWrap,
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
+    FoldComments,
+    FoldDesignBlocks,
+    TriggerCompletion,
+    GoToDefinition,
+    EnterNormalMode,
+    EnterInsertMode,
+    AppendAfterCursor,
+    AppendToEndOfLine,
+    InsertLineBelow,
+}
+
+const COMMAND_NAMES: &[(Command, &str)] = &[
+    (Command::MoveLeft, "move_left"),
+    (Command::MoveRight, "move_right"),
+    (Command::MoveUp, "move_up"),
+    (Command::MoveDown, "move_down"),
+    (Command::MoveWordLeft, "move_word_left"),
+    (Command::MoveWordRight, "move_word_right"),
+    (Command::MoveToStartOfLine, "move_to_start_of_line"),
+    (Command::MoveToEndOfLine, "move_to_end_of_line"),
+    (Command::MovePageUp, "move_page_up"),
+    (Command::MovePageDown, "move_page_down"),
+    (Command::MoveToStartOfText, "move_to_start_of_text"),
+    (Command::MoveToEndOfText, "move_to_end_of_text"),
+    (Command::SelectWord, "select_word"),
+    (Command::SelectLine, "select_line"),
+    (Command::MoveLinesUp, "move_lines_up"),
+    (Command::MoveLinesDown, "move_lines_down"),
+    (Command::DuplicateLines, "duplicate_lines"),
+    (Command::Enter, "enter"),
+    (Command::Indent, "indent"),
+    (Command::Outdent, "outdent"),
+    (Command::Delete, "delete"),
+    (Command::Backspace, "backspace"),
+    (Command::DeleteLine, "delete_line"),
+    (Command::DeleteToEndOfLine, "delete_to_end_of_line"),
+    (Command::Undo, "undo"),
+    (Command::Redo, "redo"),
+    (Command::Find, "find"),
+    (Command::FindNext, "find_next"),
+    (Command::FindPrevious, "find_previous"),
+    (Command::AddNextMatch, "add_next_match"),
+    (Command::AddPreviousMatch, "add_previous_match"),
+    (Command::IncreaseFontSize, "increase_font_size"),
+    (Command::DecreaseFontSize, "decrease_font_size"),
+    (Command::ResetFontSize, "reset_font_size"),
+    (Command::ToggleWordWrap, "toggle_word_wrap"),
+    (Command::ToggleFold, "toggle_fold"),
+    (Command::FoldAll, "fold_all"),
+    (Command::UnfoldAll, "unfold_all"),
+    (Command::FoldComments, "fold_comments"),
+    (Command::FoldDesignBlocks, "fold_design_blocks"),
+    (Command::TriggerCompletion, "trigger_completion"),
+    (Command::GoToDefinition, "go_to_definition"),
+    (Command::EnterNormalMode, "enter_normal_mode"),
+    (Command::EnterInsertMode, "enter_insert_mode"),
+    (Command::AppendAfterCursor, "append_after_cursor"),
+    (Command::AppendToEndOfLine, "append_to_end_of_line"),
+    (Command::InsertLineBelow, "insert_line_below"),
+];
+
+impl Command {
+    /// The name of the command in keymap files, such as `move_word_left`.
+    pub fn name(self) -> &'static str {
+        COMMAND_NAMES
+            .iter()
+            .find(|&&(command, _)| command == self)
+            .map(|&(_, name)| name)
+            .unwrap()
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        COMMAND_NAMES
+            .iter()
+            .find(|&&(_, other_name)| other_name == name)
+            .map(|&(command, _)| command)
+    }
+
+    /// Whether the command moves the cursors, in which case holding shift extends the selections.
+    pub fn is_movement(self) -> bool {
+        matches!(
+            self,
+            Self::MoveLeft
+                | Self::MoveRight
+                | Self::MoveUp
+                | Self::MoveDown
+                | Self::MoveWordLeft
+                | Self::MoveWordRight
+                | Self::MoveToStartOfLine
+                | Self::MoveToEndOfLine
+                | Self::MovePageUp
+                | Self::MovePageDown
+                | Self::MoveToStartOfText
+                | Self::MoveToEndOfText
+        )
+    }
+}
+
+/// The mode of the editor. Keys are bound per mode, and text is only typed in insert mode.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Mode {
+    #[default]
+    Insert,
+    Normal,
+}
+
+/// The operating system that the default bindings are chosen for.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Os {
+    MacOs,
+    Windows,
+    Linux,
+}
+
+impl Os {
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else if cfg!(target_os = "windows") {
+            Self::Windows
+        } else {
+            Self::Linux
+        }
+    }
+
+    /// The modifier of most shortcuts, which is command on macOS and control elsewhere. Keymap
+    /// files refer to it as `mod`.
+    pub fn primary_modifier(self) -> KeyModifiers {
+        match self {
+            Self::MacOs => KeyModifiers {
+                logo: true,
+                ..KeyModifiers::default()
+            },
+            Self::Windows | Self::Linux => KeyModifiers {
+                control: true,
+                ..KeyModifiers::default()
+            },
+        }
+    }
+}
+
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::KeyA, "a"),
+    (KeyCode::KeyB, "b"),
+    (KeyCode::KeyC, "c"),
+    (KeyCode::KeyD, "d"),
+    (KeyCode::KeyE, "e"),
+    (KeyCode::KeyF, "f"),
+    (KeyCode::KeyG, "g"),
+    (KeyCode::KeyH, "h"),
+    (KeyCode::KeyI, "i"),
+    (KeyCode::KeyJ, "j"),
+    (KeyCode::KeyK, "k"),
+    (KeyCode::KeyL, "l"),
+    (KeyCode::KeyM, "m"),
+    (KeyCode::KeyN, "n"),
+    (KeyCode::KeyO, "o"),
+    (KeyCode::KeyP, "p"),
+    (KeyCode::KeyQ, "q"),
+    (KeyCode::KeyR, "r"),
+    (KeyCode::KeyS, "s"),
+    (KeyCode::KeyT, "t"),
+    (KeyCode::KeyU, "u"),
+    (KeyCode::KeyV, "v"),
+    (KeyCode::KeyW, "w"),
+    (KeyCode::KeyX, "x"),
+    (KeyCode::KeyY, "y"),
+    (KeyCode::KeyZ, "z"),
+    (KeyCode::Key0, "0"),
+    (KeyCode::Key1, "1"),
+    (KeyCode::Key2, "2"),
+    (KeyCode::Key3, "3"),
+    (KeyCode::Key4, "4"),
+    (KeyCode::Key5, "5"),
+    (KeyCode::Key6, "6"),
+    (KeyCode::Key7, "7"),
+    (KeyCode::Key8, "8"),
+    (KeyCode::Key9, "9"),
+    (KeyCode::Escape, "escape"),
+    (KeyCode::Backtick, "backtick"),
+    (KeyCode::Minus, "minus"),
+    (KeyCode::Equals, "equals"),
+    (KeyCode::Backspace, "backspace"),
+    (KeyCode::Tab, "tab"),
+    (KeyCode::LBracket, "lbracket"),
+    (KeyCode::RBracket, "rbracket"),
+    (KeyCode::ReturnKey, "enter"),
+    (KeyCode::Semicolon, "semicolon"),
+    (KeyCode::Quote, "quote"),
+    (KeyCode::Backslash, "backslash"),
+    (KeyCode::Comma, "comma"),
+    (KeyCode::Period, "period"),
+    (KeyCode::Slash, "slash"),
+    (KeyCode::Space, "space"),
+    (KeyCode::F1, "f1"),
+    (KeyCode::F2, "f2"),
+    (KeyCode::F3, "f3"),
+    (KeyCode::F4, "f4"),
+    (KeyCode::F5, "f5"),
+    (KeyCode::F6, "f6"),
+    (KeyCode::F7, "f7"),
+    (KeyCode::F8, "f8"),
+    (KeyCode::F9, "f9"),
+    (KeyCode::F10, "f10"),
+    (KeyCode::F11, "f11"),
+    (KeyCode::F12, "f12"),
+    (KeyCode::Insert, "insert"),
+    (KeyCode::Delete, "delete"),
+    (KeyCode::Home, "home"),
+    (KeyCode::End, "end"),
+    (KeyCode::PageUp, "pageup"),
+    (KeyCode::PageDown, "pagedown"),
+    (KeyCode::ArrowUp, "up"),
+    (KeyCode::ArrowDown, "down"),
+    (KeyCode::ArrowLeft, "left"),
+    (KeyCode::ArrowRight, "right"),
+    // aliases, which come after the names that keys are displayed with
+    (KeyCode::Escape, "esc"),
+    (KeyCode::ReturnKey, "return"),
+    (KeyCode::Backtick, "`"),
+    (KeyCode::Minus, "-"),
+    (KeyCode::Equals, "="),
+    (KeyCode::LBracket, "["),
+    (KeyCode::RBracket, "]"),
+    (KeyCode::Semicolon, ";"),
+    (KeyCode::Quote, "'"),
+    (KeyCode::Backslash, "\\"),
+    (KeyCode::Comma, ","),
+    (KeyCode::Period, "."),
+    (KeyCode::Slash, "/"),
+];
+
+/// A key together with the modifiers that are held while pressing it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct KeyStroke {
+    pub key_code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyStroke {
+    pub fn new(key_code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self {
+            key_code,
+            modifiers,
+        }
+    }
+
+    /// Parses a stroke such as `ctrl+shift+k`, where `mod` stands for the primary modifier of
+    /// `os`.
+    pub fn parse(string: &str, os: Os) -> Result<Self, KeymapError> {
+        let invalid = || KeymapError::InvalidKeys(string.to_string());
+        let mut parts: Vec<&str> = string.split('+').collect();
+        let key_name = parts.pop().filter(|name| !name.is_empty()).ok_or_else(invalid)?;
+        let mut modifiers = KeyModifiers::default();
+        for part in parts {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.control = true,
+                "shift" => modifiers.shift = true,
+                "alt" | "option" => modifiers.alt = true,
+                "cmd" | "logo" | "super" => modifiers.logo = true,
+                "mod" => {
+                    let primary = os.primary_modifier();
+                    modifiers.control |= primary.control;
+                    modifiers.logo |= primary.logo;
+                }
+                _ => return Err(invalid()),
+            }
+        }
+        let key_name = key_name.to_ascii_lowercase();
+        let key_code = KEY_NAMES
+            .iter()
+            .find(|&&(_, name)| name == key_name)
+            .map(|&(key_code, _)| key_code)
+            .ok_or_else(invalid)?;
+        Ok(Self::new(key_code, modifiers))
+    }
+
+    /// Parses a chord of strokes that are separated by spaces, such as `ctrl+x u`.
+    pub fn parse_sequence(string: &str, os: Os) -> Result<Vec<Self>, KeymapError> {
+        let keys = string
+            .split_whitespace()
+            .map(|stroke| Self::parse(stroke, os))
+            .collect::<Result<Vec<_>, _>>()?;
+        if keys.is_empty() {
+            return Err(KeymapError::InvalidKeys(string.to_string()));
+        }
+        Ok(keys)
+    }
+}
+
+impl fmt::Display for KeyStroke {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (held, name) in [
+            (self.modifiers.control, "ctrl"),
+            (self.modifiers.alt, "alt"),
+            (self.modifiers.shift, "shift"),
+            (self.modifiers.logo, "cmd"),
+        ] {
+            if held {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match KEY_NAMES.iter().find(|&&(key_code, _)| key_code == self.key_code) {
+            Some((_, name)) => write!(f, "{}", name),
+            None => write!(f, "{:?}", self.key_code),
+        }
+    }
+}
+
+/// A set of bindings to start a keymap from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Preset {
+    Default,
+    /// Starts in normal mode, with escape switching back to it from insert mode.
+    Vim,
+    Emacs,
+}
+
+impl Preset {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::Default),
+            "vim" => Some(Self::Vim),
+            "emacs" => Some(Self::Emacs),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Binding {
+    mode: Mode,
+    keys: Vec<KeyStroke>,
+    command: Command,
+}
+
+/// What a key press did.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum KeyResult {
+    /// The key completed a binding. The flag is whether the command should extend the selections.
+    Command(Command, bool),
+    /// The key started or continued a chord.
+    Pending,
+    /// The key didn't continue the pending chord, which was dropped.
+    Cancelled,
+    Unbound,
+}
+
+/// The mode and the pending chord of an editor that uses a `Keymap`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeymapState {
+    mode: Mode,
+    pending: Vec<KeyStroke>,
+}
+
+impl KeymapState {
+    pub fn new(mode: Mode) -> Self {
+        Self {
+            mode,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.pending.clear();
+    }
+
+    /// The strokes of the chord that has been started so far.
+    pub fn pending(&self) -> &[KeyStroke] {
+        &self.pending
+    }
+
+    pub fn accepts_text_input(&self) -> bool {
+        self.mode == Mode::Insert
+    }
+}
+
+/// Maps keys and chords to the commands of the `CodeEditor`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+    initial_mode: Mode,
+}
+
+impl Keymap {
+    /// A keymap without any bindings.
+    pub fn new() -> Self {
+        Self {
+            bindings: Vec::new(),
+            initial_mode: Mode::Insert,
+        }
+    }
+
+    pub fn preset(preset: Preset, os: Os) -> Self {
+        let mut keymap = Self::new();
+        keymap.bind_all(Mode::Insert, os, DEFAULT_BINDINGS);
+        keymap.bind_all(
+            Mode::Insert,
+            os,
+            match os {
+                Os::MacOs => MAC_OS_BINDINGS,
+                Os::Windows => WINDOWS_BINDINGS,
+                Os::Linux => LINUX_BINDINGS,
+            },
+        );
+        match preset {
+            Preset::Default => {}
+            Preset::Vim => {
+                keymap.bind_all(Mode::Insert, os, &[("escape", Command::EnterNormalMode)]);
+                keymap.bind_all(Mode::Normal, os, VIM_NORMAL_BINDINGS);
+                keymap.initial_mode = Mode::Normal;
+            }
+            Preset::Emacs => keymap.bind_all(Mode::Insert, os, EMACS_BINDINGS),
+        }
+        keymap
+    }
+
+    /// Parses a keymap file. The optional top-level `preset` names the preset to start from, and
+    /// the `keys` and `normal` tables map the chords of insert and normal mode to command names,
+    /// or to `none` to remove a binding of the preset. Other tables are ignored, so that hosts can
+    /// keep their own bindings in the same file, and read them with `parse_table`.
+    ///
+    /// ```toml
+    /// preset = "vim"
+    ///
+    /// [keys]
+    /// "ctrl+k ctrl+u" = "move_lines_up"
+    /// "mod+d" = "none"
+    /// ```
+    pub fn from_toml(string: &str, os: Os) -> Result<Self, KeymapError> {
+        let toml = parse(string)?;
+        let preset = match toml.get("preset") {
+            Some(value) => {
+                let name = string_value("preset", value)?;
+                Preset::from_name(name).ok_or_else(|| KeymapError::UnknownPreset(name.to_string()))?
+            }
+            None => Preset::Default,
+        };
+        let mut keymap = Self::preset(preset, os);
+        for (mode, table) in [(Mode::Insert, "keys"), (Mode::Normal, "normal")] {
+            for (keys, name) in table_bindings(&toml, table, os)? {
+                match name.as_str() {
+                    "none" => keymap.unbind(mode, &keys),
+                    name => {
+                        let command = Command::from_name(name)
+                            .ok_or_else(|| KeymapError::UnknownCommand(name.to_string()))?;
+                        keymap.bind(mode, &keys, command);
+                    }
+                }
+            }
+        }
+        Ok(keymap)
+    }
+
+    /// The mode that editors start in.
+    pub fn initial_mode(&self) -> Mode {
+        self.initial_mode
+    }
+
+    pub fn set_initial_mode(&mut self, mode: Mode) {
+        self.initial_mode = mode;
+    }
+
+    /// Binds a chord to a command, replacing the previous binding of the chord.
+    pub fn bind(&mut self, mode: Mode, keys: &[KeyStroke], command: Command) {
+        self.unbind(mode, keys);
+        self.bindings.push(Binding {
+            mode,
+            keys: keys.to_vec(),
+            command,
+        });
+    }
+
+    pub fn unbind(&mut self, mode: Mode, keys: &[KeyStroke]) {
+        self.bindings
+            .retain(|binding| binding.mode != mode || binding.keys != keys);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Mode, &[KeyStroke], Command)> + '_ {
+        self.bindings
+            .iter()
+            .map(|binding| (binding.mode, binding.keys.as_slice(), binding.command))
+    }
+
+    /// The command that a chord is bound to.
+    pub fn command(&self, mode: Mode, keys: &[KeyStroke]) -> Option<Command> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.mode == mode && binding.keys == keys)
+            .map(|binding| binding.command)
+    }
+
+    /// Feeds a key press to the pending chord of `state`. A chord that is bound fires as soon as it
+    /// is complete, even if it also starts a longer chord. A chord that is bound without shift
+    /// fires when shift is held as well, in which case movements extend the selections.
+    pub fn handle_key(
+        &self,
+        state: &mut KeymapState,
+        key_code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> KeyResult {
+        if matches!(
+            key_code,
+            KeyCode::Control | KeyCode::Shift | KeyCode::Alt | KeyCode::Logo
+        ) {
+            // pressing the modifiers of the next stroke doesn't break a chord
+            return if state.pending.is_empty() {
+                KeyResult::Unbound
+            } else {
+                KeyResult::Pending
+            };
+        }
+        let was_pending = !state.pending.is_empty();
+        let mut keys = mem::take(&mut state.pending);
+        keys.push(KeyStroke::new(key_code, modifiers));
+        if let Some(command) = self.command(state.mode, &keys) {
+            return KeyResult::Command(command, false);
+        }
+        if self.bindings.iter().any(|binding| {
+            binding.mode == state.mode
+                && binding.keys.len() > keys.len()
+                && binding.keys.starts_with(&keys)
+        }) {
+            state.pending = keys;
+            return KeyResult::Pending;
+        }
+        if modifiers.shift {
+            keys.last_mut().unwrap().modifiers.shift = false;
+            if let Some(command) = self.command(state.mode, &keys) {
+                return KeyResult::Command(command, command.is_movement());
+            }
+        }
+        if was_pending {
+            KeyResult::Cancelled
+        } else {
+            KeyResult::Unbound
+        }
+    }
+
+    fn bind_all(&mut self, mode: Mode, os: Os, bindings: &[(&str, Command)]) {
+        for &(keys, command) in bindings {
+            let keys = KeyStroke::parse_sequence(keys, os).unwrap();
+            self.bind(mode, &keys, command);
+        }
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self::preset(Preset::Default, Os::current())
+    }
+}
+
+/// Parses the chords of a table in a keymap file, together with the names they are bound to.
+pub fn parse_table(
+    string: &str,
+    table: &str,
+    os: Os,
+) -> Result<Vec<(Vec<KeyStroke>, String)>, KeymapError> {
+    table_bindings(&parse(string)?, table, os)
+}
+
+fn parse(string: &str) -> Result<HashMap<String, Toml>, KeymapError> {
+    parse_toml(string).map_err(|error| KeymapError::Toml {
+        message: error.msg,
+        offset: error.span.start,
+    })
+}
+
+fn table_bindings(
+    toml: &HashMap<String, Toml>,
+    table: &str,
+    os: Os,
+) -> Result<Vec<(Vec<KeyStroke>, String)>, KeymapError> {
+    let mut bindings = Vec::new();
+    for (key, value) in toml {
+        if let Some(keys) = key
+            .strip_prefix(table)
+            .and_then(|keys| keys.strip_prefix('.'))
+        {
+            bindings.push((
+                KeyStroke::parse_sequence(keys, os)?,
+                string_value(key, value)?.to_string(),
+            ));
+        }
+    }
+    Ok(bindings)
+}
+
+fn string_value<'a>(key: &str, value: &'a Toml) -> Result<&'a str, KeymapError> {
+    match value {
+        Toml::Str(string, _) => Ok(string),
+        _ => Err(KeymapError::ExpectedString(key.to_string())),
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum KeymapError {
+    Toml { message: String, offset: usize },
+    InvalidKeys(String),
+    UnknownCommand(String),
+    UnknownPreset(String),
+    /// The value of the key isn't a string.
+    ExpectedString(String),
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Toml { message, offset } => write!(f, "{} at {}", message, offset),
+            Self::InvalidKeys(keys) => write!(f, "invalid keys `{}`", keys),
+            Self::UnknownCommand(name) => write!(f, "unknown command `{}`", name),
+            Self::UnknownPreset(name) => write!(f, "unknown preset `{}`", name),
+            Self::ExpectedString(key) => write!(f, "expected a string for `{}`", key),
+        }
+    }
+}
+
+impl error::Error for KeymapError {}
+
+const DEFAULT_BINDINGS: &[(&str, Command)] = &[
+    ("left", Command::MoveLeft),
+    ("right", Command::MoveRight),
+    ("up", Command::MoveUp),
+    ("down", Command::MoveDown),
+    ("home", Command::MoveToStartOfLine),
+    ("end", Command::MoveToEndOfLine),
+    ("pageup", Command::MovePageUp),
+    ("pagedown", Command::MovePageDown),
+    ("mod+d", Command::SelectWord),
+    ("mod+l", Command::SelectLine),
+    ("alt+up", Command::MoveLinesUp),
+    ("alt+down", Command::MoveLinesDown),
+    ("mod+shift+d", Command::DuplicateLines),
+    ("enter", Command::Enter),
+    ("tab", Command::Indent),
+    ("shift+tab", Command::Outdent),
+    ("delete", Command::Delete),
+    ("backspace", Command::Backspace),
+    ("mod+z", Command::Undo),
+    ("mod+shift+z", Command::Redo),
+    ("mod+f", Command::Find),
+    ("mod+g", Command::FindNext),
+    ("mod+shift+g", Command::FindPrevious),
+    ("mod+alt+g", Command::AddNextMatch),
+    ("mod+alt+shift+g", Command::AddPreviousMatch),
+    ("mod+equals", Command::IncreaseFontSize),
+    ("mod+minus", Command::DecreaseFontSize),
+    ("mod+0", Command::ResetFontSize),
+    ("mod+w", Command::ToggleWordWrap),
+    ("mod+shift+lbracket", Command::ToggleFold),
+    ("mod+alt+lbracket", Command::FoldAll),
+    ("mod+alt+rbracket", Command::UnfoldAll),
+    ("mod+alt+slash", Command::FoldComments),
+    ("ctrl+space", Command::TriggerCompletion),
+    ("f12", Command::GoToDefinition),
+];
+
+const MAC_OS_BINDINGS: &[(&str, Command)] = &[
+    ("alt+left", Command::MoveWordLeft),
+    ("alt+right", Command::MoveWordRight),
+    ("cmd+left", Command::MoveToStartOfLine),
+    ("cmd+right", Command::MoveToEndOfLine),
+    ("cmd+up", Command::MoveToStartOfText),
+    ("cmd+down", Command::MoveToEndOfText),
+];
+
+const LINUX_BINDINGS: &[(&str, Command)] = &[
+    ("ctrl+left", Command::MoveWordLeft),
+    ("ctrl+right", Command::MoveWordRight),
+    ("ctrl+home", Command::MoveToStartOfText),
+    ("ctrl+end", Command::MoveToEndOfText),
+];
+
+const WINDOWS_BINDINGS: &[(&str, Command)] = &[
+    ("ctrl+left", Command::MoveWordLeft),
+    ("ctrl+right", Command::MoveWordRight),
+    ("ctrl+home", Command::MoveToStartOfText),
+    ("ctrl+end", Command::MoveToEndOfText),
+    ("ctrl+y", Command::Redo),
+];
+
+const VIM_NORMAL_BINDINGS: &[(&str, Command)] = &[
+    ("h", Command::MoveLeft),
+    ("l", Command::MoveRight),
+    ("k", Command::MoveUp),
+    ("j", Command::MoveDown),
+    ("left", Command::MoveLeft),
+    ("right", Command::MoveRight),
+    ("up", Command::MoveUp),
+    ("down", Command::MoveDown),
+    ("b", Command::MoveWordLeft),
+    ("w", Command::MoveWordRight),
+    ("0", Command::MoveToStartOfLine),
+    ("shift+4", Command::MoveToEndOfLine),
+    ("ctrl+b", Command::MovePageUp),
+    ("ctrl+f", Command::MovePageDown),
+    ("g g", Command::MoveToStartOfText),
+    ("shift+g", Command::MoveToEndOfText),
+    ("x", Command::Delete),
+    ("d d", Command::DeleteLine),
+    ("shift+d", Command::DeleteToEndOfLine),
+    ("i", Command::EnterInsertMode),
+    ("a", Command::AppendAfterCursor),
+    ("shift+a", Command::AppendToEndOfLine),
+    ("o", Command::InsertLineBelow),
+    ("u", Command::Undo),
+    ("ctrl+r", Command::Redo),
+    ("slash", Command::Find),
+    ("n", Command::FindNext),
+    ("shift+n", Command::FindPrevious),
+    ("g d", Command::GoToDefinition),
+    ("z a", Command::ToggleFold),
+    ("z shift+m", Command::FoldAll),
+    ("z shift+r", Command::UnfoldAll),
+];
+
+const EMACS_BINDINGS: &[(&str, Command)] = &[
+    ("ctrl+b", Command::MoveLeft),
+    ("ctrl+f", Command::MoveRight),
+    ("ctrl+p", Command::MoveUp),
+    ("ctrl+n", Command::MoveDown),
+    ("alt+b", Command::MoveWordLeft),
+    ("alt+f", Command::MoveWordRight),
+    ("ctrl+a", Command::MoveToStartOfLine),
+    ("ctrl+e", Command::MoveToEndOfLine),
+    ("alt+v", Command::MovePageUp),
+    ("ctrl+v", Command::MovePageDown),
+    ("alt+shift+comma", Command::MoveToStartOfText),
+    ("alt+shift+period", Command::MoveToEndOfText),
+    ("ctrl+d", Command::Delete),
+    ("ctrl+k", Command::DeleteToEndOfLine),
+    ("ctrl+slash", Command::Undo),
+    ("ctrl+x u", Command::Undo),
+    ("ctrl+s", Command::Find),
+    ("ctrl+r", Command::FindPrevious),
+    ("alt+period", Command::GoToDefinition),
+    ("alt+slash", Command::TriggerCompletion),
+];
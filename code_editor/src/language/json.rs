@@ -0,0 +1,119 @@
+use crate::{
+    language::Language,
+    token::TokenKind,
+    tokenizer::{CharExt, Cursor},
+};
+
+/// JSON, with the `//` and `/* */` comments that many configuration files allow.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Json;
+
+impl Language for Json {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::BlockCommentTail => block_comment_tail(cursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    BlockCommentTail,
+}
+
+fn initial(cursor: &mut Cursor) -> (State, TokenKind) {
+    match (cursor.peek(0), cursor.peek(1)) {
+        ('/', '/') => {
+            while cursor.skip_if(|char| char != '\0') {}
+            (State::Initial, TokenKind::Comment)
+        }
+        ('/', '*') => {
+            cursor.skip(2);
+            block_comment_tail(cursor)
+        }
+        ('"', _) => string_or_key(cursor),
+        ('{', _) | ('}', _) | ('[', _) | (']', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        (':', _) | (',', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        ('-', char) | (char, _) if char.is_ascii_digit() => number(cursor),
+        (char, _) if char.is_identifier_start() => {
+            let start = cursor.index();
+            while cursor.skip_if(|char| char.is_identifier_continue()) {}
+            (
+                State::Initial,
+                match cursor.slice_from(start) {
+                    "true" | "false" | "null" => TokenKind::OtherKeyword,
+                    _ => TokenKind::Unknown,
+                },
+            )
+        }
+        (char, _) if char.is_whitespace() => {
+            while cursor.skip_if(|char| char.is_whitespace()) {}
+            (State::Initial, TokenKind::Whitespace)
+        }
+        _ => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Unknown)
+        }
+    }
+}
+
+// a string that is followed by a colon is the key of an object member
+fn string_or_key(cursor: &mut Cursor) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '"');
+    cursor.skip(1);
+    loop {
+        match cursor.peek(0) {
+            '"' => {
+                cursor.skip(1);
+                break;
+            }
+            '\0' => return (State::Initial, TokenKind::String),
+            '\\' => cursor.skip(2),
+            _ => cursor.skip(1),
+        }
+    }
+    if cursor.rest().trim_start().starts_with(':') {
+        (State::Initial, TokenKind::Identifier)
+    } else {
+        (State::Initial, TokenKind::String)
+    }
+}
+
+fn number(cursor: &mut Cursor) -> (State, TokenKind) {
+    cursor.skip_if(|char| char == '-');
+    if !cursor.skip_digits(10) {
+        return (State::Initial, TokenKind::Unknown);
+    }
+    if cursor.peek(0) == '.' && cursor.peek(1).is_ascii_digit() {
+        cursor.skip(1);
+        cursor.skip_digits(10);
+    }
+    if (cursor.peek(0) == 'e' || cursor.peek(0) == 'E') && !cursor.skip_exponent() {
+        return (State::Initial, TokenKind::Unknown);
+    }
+    (State::Initial, TokenKind::Number)
+}
+
+fn block_comment_tail(cursor: &mut Cursor) -> (State, TokenKind) {
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('*', '/') => {
+                cursor.skip(2);
+                break (State::Initial, TokenKind::Comment);
+            }
+            ('\0', _) => break (State::BlockCommentTail, TokenKind::Comment),
+            _ => cursor.skip(1),
+        }
+    }
+}
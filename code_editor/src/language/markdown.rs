@@ -0,0 +1,236 @@
+use crate::{language::Language, token::TokenKind, tokenizer::Cursor};
+
+/// Markdown, with headings as typenames, emphasis as constants and code as strings.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Markdown;
+
+impl Language for Markdown {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::FencedCode { fence, count } => fenced_code(cursor, fence, count),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    /// Inside a code block opened by `count` backticks or tildes.
+    FencedCode { fence: char, count: usize },
+}
+
+fn initial(cursor: &mut Cursor) -> (State, TokenKind) {
+    if cursor.before().chars().all(|char| char.is_whitespace()) && cursor.before().len() < 4 {
+        if let Some(result) = line_start(cursor) {
+            return result;
+        }
+    }
+    match (cursor.peek(0), cursor.peek(1)) {
+        ('`', _) => code_span(cursor),
+        ('*', _) | ('_', _) => emphasis(cursor),
+        ('!', '[') | ('\\', _) => {
+            cursor.skip(if cursor.peek(0) == '\\' { 2 } else { 1 });
+            (State::Initial, TokenKind::Punctuator)
+        }
+        ('[', _) => link_text(cursor),
+        ('(', _) if cursor.before().ends_with(']') => link_destination(cursor),
+        ('<', char) if char.is_ascii_alphabetic() || char == '/' || char == '!' => tag(cursor),
+        (char, _) if char.is_whitespace() => {
+            while cursor.skip_if(|char| char.is_whitespace()) {}
+            (State::Initial, TokenKind::Whitespace)
+        }
+        _ => {
+            cursor.skip(1);
+            while cursor.skip_if(|char| {
+                char != '\0' && !char.is_whitespace() && !matches!(char, '`' | '*' | '[' | '\\')
+            }) {}
+            (State::Initial, TokenKind::Identifier)
+        }
+    }
+}
+
+// block level syntax, which is only recognized at the start of a line
+fn line_start(cursor: &mut Cursor) -> Option<(State, TokenKind)> {
+    let rest = cursor.rest();
+    let first = rest.chars().next()?;
+    let run = rest.chars().take_while(|&char| char == first).count();
+    let after_run = &rest[run * first.len_utf8()..];
+    match first {
+        '`' | '~' if run >= 3 => {
+            // the info string of a fence can't contain backticks
+            if first == '`' && after_run.contains('`') {
+                return None;
+            }
+            cursor.skip(run);
+            Some((State::FencedCode { fence: first, count: run }, TokenKind::Punctuator))
+        }
+        '#' if run <= 6 && (after_run.is_empty() || after_run.starts_with(' ')) => {
+            skip_line(cursor);
+            Some((State::Initial, TokenKind::Typename))
+        }
+        '>' => {
+            skip_line(cursor);
+            Some((State::Initial, TokenKind::Comment))
+        }
+        '-' | '*' | '_' if is_thematic_break(rest, first) => {
+            skip_line(cursor);
+            Some((State::Initial, TokenKind::Punctuator))
+        }
+        '-' | '*' | '+' if after_run.starts_with(' ') && run == 1 => {
+            cursor.skip(1);
+            Some((State::Initial, TokenKind::Punctuator))
+        }
+        char if char.is_ascii_digit() => {
+            let digits = rest.chars().take_while(|char| char.is_ascii_digit()).count();
+            let after_digits = &rest[digits..];
+            if digits <= 9 && (after_digits.starts_with(". ") || after_digits.starts_with(") ")) {
+                cursor.skip(digits + 1);
+                Some((State::Initial, TokenKind::Punctuator))
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn is_thematic_break(line: &str, char: char) -> bool {
+    line.chars().filter(|&other| other == char).count() >= 3
+        && line.chars().all(|other| other == char || other.is_whitespace())
+}
+
+fn fenced_code(cursor: &mut Cursor, fence: char, count: usize) -> (State, TokenKind) {
+    let line = cursor.rest().trim_start();
+    let is_closing = cursor.before().chars().all(|char| char.is_whitespace())
+        && line.chars().take_while(|&char| char == fence).count() >= count
+        && line.trim_start_matches(fence).trim().is_empty();
+    skip_line(cursor);
+    if is_closing {
+        (State::Initial, TokenKind::Punctuator)
+    } else {
+        (State::FencedCode { fence, count }, TokenKind::String)
+    }
+}
+
+// a run of backticks up to the next run of the same length
+fn code_span(cursor: &mut Cursor) -> (State, TokenKind) {
+    let rest = cursor.rest();
+    let count = rest.chars().take_while(|&char| char == '`').count();
+    let fence = &rest[..count];
+    let mut search = &rest[count..];
+    let mut offset = count;
+    while let Some(index) = search.find(fence) {
+        let run = search[index..].chars().take_while(|&char| char == '`').count();
+        if run == count {
+            cursor.skip(rest[..offset + index + count].chars().count());
+            return (State::Initial, TokenKind::String);
+        }
+        offset += index + run;
+        search = &search[index + run..];
+    }
+    cursor.skip(count);
+    (State::Initial, TokenKind::Punctuator)
+}
+
+// emphasis is a run of asterisks or underscores up to the next run of the same length
+fn emphasis(cursor: &mut Cursor) -> (State, TokenKind) {
+    let rest = cursor.rest();
+    let delimiter = cursor.peek(0);
+    let count = rest.chars().take_while(|&char| char == delimiter).count();
+    let opens = rest[count..]
+        .chars()
+        .next()
+        .map_or(false, |char| !char.is_whitespace())
+        && (delimiter == '*'
+            || cursor
+                .before()
+                .chars()
+                .last()
+                .map_or(true, |char| !char.is_alphanumeric()));
+    if opens {
+        let fence = &rest[..count];
+        let mut offset = count;
+        while let Some(index) = rest[offset..].find(fence) {
+            let start = offset + index;
+            let run = rest[start..]
+                .chars()
+                .take_while(|&char| char == delimiter)
+                .count();
+            let closes = run == count
+                && !rest[..start].ends_with(char::is_whitespace)
+                && (delimiter == '*'
+                    || rest[start + run..]
+                        .chars()
+                        .next()
+                        .map_or(true, |char| !char.is_alphanumeric()));
+            if closes {
+                cursor.skip(rest[..start + count].chars().count());
+                return (State::Initial, TokenKind::Constant);
+            }
+            offset = start + run;
+        }
+    }
+    if delimiter == '_' && !opens {
+        // an underscore inside a word
+        cursor.skip(count);
+        return (State::Initial, TokenKind::Identifier);
+    }
+    cursor.skip(count);
+    (State::Initial, TokenKind::Punctuator)
+}
+
+fn link_text(cursor: &mut Cursor) -> (State, TokenKind) {
+    match cursor.rest().find(']') {
+        Some(index) if cursor.rest()[index + 1..].starts_with('(') => {
+            cursor.skip(cursor.rest()[..index + 1].chars().count());
+            (State::Initial, TokenKind::Identifier)
+        }
+        _ => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+    }
+}
+
+fn link_destination(cursor: &mut Cursor) -> (State, TokenKind) {
+    match cursor.rest().find(')') {
+        Some(index) => {
+            cursor.skip(cursor.rest()[..index + 1].chars().count());
+            (State::Initial, TokenKind::String)
+        }
+        None => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+    }
+}
+
+// html tags and autolinks
+fn tag(cursor: &mut Cursor) -> (State, TokenKind) {
+    match cursor.rest().find('>') {
+        Some(index) => {
+            let tag = &cursor.rest()[..index + 1];
+            cursor.skip(tag.chars().count());
+            (
+                State::Initial,
+                if tag.contains("://") || tag.contains('@') {
+                    TokenKind::String
+                } else {
+                    TokenKind::Typename
+                },
+            )
+        }
+        None => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+    }
+}
+
+fn skip_line(cursor: &mut Cursor) {
+    while cursor.skip_if(|char| char != '\0') {}
+}
@@ -0,0 +1,47 @@
+pub mod json;
+pub mod markdown;
+pub mod rust;
+pub mod shader_dsl;
+pub mod toml;
+
+pub use self::{
+    json::Json, markdown::Markdown, rust::Rust, shader_dsl::ShaderDsl, toml::Toml,
+};
+
+use {
+    crate::{token::TokenKind, tokenizer::Cursor},
+    std::fmt,
+};
+
+/// The syntax of a language, as a state machine that is run over every line of a document.
+pub trait Language: fmt::Debug {
+    /// Carried from the end of one line to the start of the next, for tokens such as block
+    /// comments and multiline strings. A line is only tokenized again when its start state changes.
+    type State: Copy + fmt::Debug + Default + Eq;
+
+    /// Skips over the next token of a line that has at least one character left, and returns
+    /// its kind together with the state after it.
+    fn next_token(&self, state: Self::State, cursor: &mut Cursor) -> (Self::State, TokenKind);
+}
+
+/// Splits lines into whitespace and everything in between.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct PlainText;
+
+impl Language for PlainText {
+    type State = ();
+
+    fn next_token(&self, _state: (), cursor: &mut Cursor) -> ((), TokenKind) {
+        let is_whitespace = cursor.peek(0).is_whitespace();
+        cursor.skip(1);
+        while cursor.skip_if(|char| char != '\0' && char.is_whitespace() == is_whitespace) {}
+        (
+            (),
+            if is_whitespace {
+                TokenKind::Whitespace
+            } else {
+                TokenKind::Unknown
+            },
+        )
+    }
+}
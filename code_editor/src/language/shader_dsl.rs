@@ -0,0 +1,151 @@
+use crate::{
+    language::Language,
+    token::TokenKind,
+    tokenizer::{CharExt, Cursor},
+};
+
+/// The live design language, including the shader functions written in it.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct ShaderDsl;
+
+impl Language for ShaderDsl {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::BlockCommentTail => block_comment_tail(cursor),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    BlockCommentTail,
+}
+
+fn initial(cursor: &mut Cursor) -> (State, TokenKind) {
+    match (cursor.peek(0), cursor.peek(1)) {
+        ('/', '/') => {
+            while cursor.skip_if(|char| char != '\0') {}
+            (State::Initial, TokenKind::Comment)
+        }
+        ('/', '*') => {
+            cursor.skip(2);
+            block_comment_tail(cursor)
+        }
+        ('{', '{') | ('}', '}') => {
+            cursor.skip(2);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        ('(', _) | (')', _) | ('[', _) | (']', _) | ('{', _) | ('}', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        ('"', _) => string(cursor),
+        // colors, as in #f80 or #x2f2f2f
+        ('#', char) if char.is_ascii_hexdigit() || char == 'x' => {
+            cursor.skip(1);
+            while cursor.skip_if(|char| char.is_ascii_alphanumeric()) {}
+            (State::Initial, TokenKind::Number)
+        }
+        ('-', '>') | ('=', '=') | ('!', '=') | ('<', '=') | ('>', '=') | ('&', '&') | ('|', '|')
+        | ('+', '=') | ('-', '=') | ('*', '=') | ('/', '=') | ('.', '.') | (':', ':') => {
+            cursor.skip(2);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        ('.', char) if char.is_ascii_digit() => number(cursor),
+        (char, _) if char.is_ascii_digit() => number(cursor),
+        (char, _) if char.is_identifier_start() => identifier_or_keyword(cursor),
+        (char, _) if char.is_whitespace() => {
+            while cursor.skip_if(|char| char.is_whitespace()) {}
+            (State::Initial, TokenKind::Whitespace)
+        }
+        ('!', _) | ('#', _) | ('$', _) | ('%', _) | ('&', _) | ('*', _) | ('+', _) | (',', _)
+        | ('-', _) | ('.', _) | ('/', _) | (':', _) | (';', _) | ('<', _) | ('=', _)
+        | ('>', _) | ('?', _) | ('@', _) | ('^', _) | ('|', _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        _ => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Unknown)
+        }
+    }
+}
+
+fn identifier_or_keyword(cursor: &mut Cursor) -> (State, TokenKind) {
+    let start = cursor.index();
+    cursor.skip(1);
+    while cursor.skip_if(|char| char.is_identifier_continue()) {}
+    let string = cursor.slice_from(start);
+    (
+        State::Initial,
+        match string {
+            "if" | "else" | "match" | "return" => TokenKind::BranchKeyword,
+            "for" | "while" | "loop" | "break" | "continue" => TokenKind::LoopKeyword,
+            "fn" | "let" | "mut" | "self" | "true" | "false" | "in" | "const" | "import"
+            | "use" | "pub" | "struct" | "instance" | "uniform" | "varying" | "texture"
+            | "geometry" => TokenKind::OtherKeyword,
+            "bool" | "int" | "float" | "vec2" | "vec3" | "vec4" | "ivec2" | "ivec3" | "ivec4"
+            | "bvec2" | "bvec3" | "bvec4" | "mat2" | "mat3" | "mat4" | "texture2d" => {
+                TokenKind::Typename
+            }
+            _ => {
+                let mut chars = string.chars();
+                if chars.next().unwrap().is_uppercase() {
+                    match chars.next() {
+                        Some(char) if char.is_uppercase() => TokenKind::Constant,
+                        _ => TokenKind::Typename,
+                    }
+                } else {
+                    TokenKind::Identifier
+                }
+            }
+        },
+    )
+}
+
+fn number(cursor: &mut Cursor) -> (State, TokenKind) {
+    cursor.skip_digits(10);
+    if cursor.peek(0) == '.' && cursor.peek(1) != '.' {
+        cursor.skip(1);
+        cursor.skip_digits(10);
+    }
+    if (cursor.peek(0) == 'e' || cursor.peek(0) == 'E') && !cursor.skip_exponent() {
+        return (State::Initial, TokenKind::Unknown);
+    }
+    (State::Initial, TokenKind::Number)
+}
+
+fn string(cursor: &mut Cursor) -> (State, TokenKind) {
+    debug_assert!(cursor.peek(0) == '"');
+    cursor.skip(1);
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('"', _) => {
+                cursor.skip(1);
+                break;
+            }
+            ('\0', _) => break,
+            ('\\', '"') | ('\\', '\\') => cursor.skip(2),
+            _ => cursor.skip(1),
+        }
+    }
+    (State::Initial, TokenKind::String)
+}
+
+fn block_comment_tail(cursor: &mut Cursor) -> (State, TokenKind) {
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            ('*', '/') => {
+                cursor.skip(2);
+                break (State::Initial, TokenKind::Comment);
+            }
+            ('\0', _) => break (State::BlockCommentTail, TokenKind::Comment),
+            _ => cursor.skip(1),
+        }
+    }
+}
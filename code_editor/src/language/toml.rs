@@ -0,0 +1,130 @@
+use crate::{
+    language::Language,
+    token::TokenKind,
+    tokenizer::{CharExt, Cursor},
+};
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Toml;
+
+impl Language for Toml {
+    type State = State;
+
+    fn next_token(&self, state: State, cursor: &mut Cursor) -> (State, TokenKind) {
+        match state {
+            State::Initial => initial(cursor),
+            State::MultilineStringTail { literal } => multiline_string_tail(cursor, literal),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum State {
+    #[default]
+    Initial,
+    MultilineStringTail { literal: bool },
+}
+
+fn initial(cursor: &mut Cursor) -> (State, TokenKind) {
+    match (cursor.peek(0), cursor.peek(1), cursor.peek(2)) {
+        ('"', '"', '"') => {
+            cursor.skip(3);
+            multiline_string_tail(cursor, false)
+        }
+        ('\'', '\'', '\'') => {
+            cursor.skip(3);
+            multiline_string_tail(cursor, true)
+        }
+        ('"', _, _) => string(cursor, '"'),
+        ('\'', _, _) => string(cursor, '\''),
+        ('#', _, _) => {
+            while cursor.skip_if(|char| char != '\0') {}
+            (State::Initial, TokenKind::Comment)
+        }
+        ('[', _, _) | (']', _, _) | ('{', _, _) | ('}', _, _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Delimiter)
+        }
+        ('=', _, _) | ('.', _, _) | (',', _, _) => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Punctuator)
+        }
+        ('+', char, _) | ('-', char, _) if char.is_ascii_digit() => number(cursor),
+        (char, _, _) if char.is_ascii_digit() => number(cursor),
+        (char, _, _) if is_bare_key_char(char) => bare_key_or_keyword(cursor),
+        (char, _, _) if char.is_whitespace() => {
+            while cursor.skip_if(|char| char.is_whitespace()) {}
+            (State::Initial, TokenKind::Whitespace)
+        }
+        _ => {
+            cursor.skip(1);
+            (State::Initial, TokenKind::Unknown)
+        }
+    }
+}
+
+// integers, floats, dates and times
+fn number(cursor: &mut Cursor) -> (State, TokenKind) {
+    cursor.skip(1);
+    while cursor.skip_if(|char| {
+        char.is_ascii_alphanumeric() || matches!(char, '_' | '.' | ':' | '+' | '-')
+    }) {}
+    (State::Initial, TokenKind::Number)
+}
+
+fn bare_key_or_keyword(cursor: &mut Cursor) -> (State, TokenKind) {
+    let start = cursor.index();
+    let before = cursor.before().trim_start();
+    while cursor.skip_if(is_bare_key_char) {}
+    let string = cursor.slice_from(start);
+    let rest = cursor.rest().trim_start();
+    let kind = if before.starts_with('[') && !before.contains('=') {
+        TokenKind::Typename
+    } else if rest.starts_with('=') || rest.starts_with('.') {
+        TokenKind::Identifier
+    } else {
+        match string {
+            "true" | "false" => TokenKind::OtherKeyword,
+            "inf" | "nan" => TokenKind::Number,
+            _ => TokenKind::Unknown,
+        }
+    };
+    (State::Initial, kind)
+}
+
+fn string(cursor: &mut Cursor, quote: char) -> (State, TokenKind) {
+    cursor.skip(1);
+    loop {
+        match (cursor.peek(0), cursor.peek(1)) {
+            (char, _) if char == quote => {
+                cursor.skip(1);
+                break;
+            }
+            ('\0', _) => break,
+            ('\\', _) if quote == '"' => cursor.skip(2),
+            _ => cursor.skip(1),
+        }
+    }
+    (State::Initial, TokenKind::String)
+}
+
+fn multiline_string_tail(cursor: &mut Cursor, literal: bool) -> (State, TokenKind) {
+    let quote = if literal { '\'' } else { '"' };
+    loop {
+        match (cursor.peek(0), cursor.peek(1), cursor.peek(2)) {
+            (char_0, char_1, char_2) if char_0 == quote && char_1 == quote && char_2 == quote => {
+                cursor.skip(3);
+                // quotes right before the closing ones belong to the string
+                while cursor.skip_if(|char| char == quote) {}
+                break (State::Initial, TokenKind::String);
+            }
+            ('\0', _, _) => break (State::MultilineStringTail { literal }, TokenKind::String),
+            ('\\', _, _) if !literal => cursor.skip(2),
+            _ => cursor.skip(1),
+        }
+    }
+}
+
+fn is_bare_key_char(char: char) -> bool {
+    char.is_identifier_continue() || char == '-'
+}
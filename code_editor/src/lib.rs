@@ -3,27 +3,41 @@ use makepad_widgets::*;
 
 pub mod char;
 pub mod code_editor;
+pub mod collab;
+pub mod completion;
 pub mod decoration;
+pub mod diff;
+pub mod diff_view;
 pub mod document;
+pub mod find_bar;
 pub mod history;
 pub mod inlays;
 pub mod iter;
+pub mod keymap;
+pub mod language;
 pub mod layout;
+pub mod minimap;
+pub mod prompt_bar;
+pub mod regex;
+pub mod search;
 pub mod selection;
 pub mod session;
 pub mod settings;
 pub mod str;
+pub mod structure;
 pub mod text;
 pub mod token;
 pub mod tokenizer;
+pub mod tooltip;
 pub mod widgets;
 pub mod wrap;
 
 pub use self::{
-    code_editor::CodeEditor, document::Document, history::History, layout::Line,
-    selection::Selection, session::Session, settings::Settings, token::Token, tokenizer::Tokenizer,
+    code_editor::CodeEditor, diff_view::DiffView, document::Document, history::History, language::Language,
+    layout::Line, selection::Selection, session::Session, settings::Settings, token::Token, tokenizer::Tokenizer,
 };
 
 pub fn live_design(cx: &mut Cx) {
     crate::code_editor::live_design(cx);
+    crate::diff_view::live_design(cx);
 }
@@ -0,0 +1,248 @@
+use {
+    crate::{
+        code_editor::{SeverityColors, TokenColors},
+        session::Session,
+        str::StrExt,
+        token::TokenKind,
+    },
+    makepad_widgets::*,
+    std::cmp::Reverse,
+};
+
+live_design!{
+    Minimap = {{Minimap}} {
+        width: 100.0,
+        line_height: 3.0,
+        token_height: 2.0,
+        column_width: 1.0,
+        marker_width: 4.0,
+        marker_height: 2.0,
+        draw_bg: {
+            draw_depth: 2.0,
+            color: #25
+        }
+        draw_token: {
+            draw_depth: 2.1,
+        }
+        draw_viewport: {
+            draw_depth: 2.2,
+            color: #ffffff14
+        }
+        draw_marker: {
+            draw_depth: 2.3,
+        }
+    }
+}
+
+/// A scaled down view of the whole text along the right edge of the `CodeEditor`, drawn as one
+/// quad per run of tokens, with the visible part of the text marked by a draggable indicator and
+/// the decorations of the document marked along its edge.
+#[derive(Live, LiveHook)]
+pub struct Minimap {
+    #[live]
+    width: f64,
+    #[live]
+    line_height: f64,
+    #[live]
+    token_height: f64,
+    #[live]
+    column_width: f64,
+    #[live]
+    marker_width: f64,
+    #[live]
+    marker_height: f64,
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    draw_token: DrawColor,
+    #[live]
+    draw_viewport: DrawColor,
+    #[live]
+    draw_marker: DrawColor,
+    #[rust]
+    rect: Rect,
+    // how far the lines are scrolled up when they don't fit
+    #[rust]
+    offset: f64,
+    #[rust]
+    viewport_rect: Rect,
+    #[rust]
+    line_count: usize,
+    #[rust]
+    visible_line_count: usize,
+    // where the indicator was grabbed, relative to its top
+    #[rust]
+    drag_offset: Option<f64>,
+}
+
+impl Minimap {
+    pub fn width(&self) -> f64 {
+        self.width
+    }
+
+    pub fn contains(&self, abs: DVec2) -> bool {
+        self.rect.contains(abs)
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.drag_offset.is_some()
+    }
+
+    /// Draws the minimap into `rect`, with the indicator over the `visible_line_count` lines that
+    /// start at `first_visible_line_index`.
+    pub fn draw(
+        &mut self,
+        cx: &mut Cx2d,
+        session: &Session,
+        rect: Rect,
+        first_visible_line_index: usize,
+        visible_line_count: usize,
+        token_colors: &TokenColors,
+        severity_colors: &SeverityColors,
+    ) {
+        let layout = session.layout();
+        self.rect = rect;
+        self.line_count = layout.as_text().as_lines().len();
+        self.visible_line_count = visible_line_count.min(self.line_count);
+        self.offset = if self.fits() {
+            0.0
+        } else {
+            let scrolled_fraction = first_visible_line_index as f64
+                / (self.line_count - self.visible_line_count) as f64;
+            scrolled_fraction.min(1.0)
+                * (self.line_count as f64 * self.line_height - rect.size.y)
+        };
+        self.draw_bg.draw_abs(cx, rect);
+
+        let first_line_index = (self.offset / self.line_height) as usize;
+        let last_line_index = (first_line_index + (rect.size.y / self.line_height) as usize + 1)
+            .min(self.line_count);
+        for line_index in first_line_index..last_line_index {
+            let line = layout.line(line_index);
+            let text = line.text();
+            let y = rect.pos.y + line_index as f64 * self.line_height - self.offset;
+            let mut byte_index = 0;
+            let mut column_index = 0;
+            // the kind and start column of the run that is being extended
+            let mut run: Option<(TokenKind, usize)> = None;
+            for token in line.tokens() {
+                let end_byte_index = (byte_index + token.len).min(text.len());
+                let column_count = text[byte_index..end_byte_index].column_count();
+                match run {
+                    Some((kind, _)) if kind == token.kind => {}
+                    _ => {
+                        if let Some((kind, start_column_index)) = run.take() {
+                            self.draw_run(cx, token_colors, kind, y, start_column_index, column_index);
+                        }
+                        if token.kind != TokenKind::Whitespace {
+                            run = Some((token.kind, column_index));
+                        }
+                    }
+                }
+                byte_index = end_byte_index;
+                column_index += column_count;
+            }
+            if let Some((kind, start_column_index)) = run {
+                self.draw_run(cx, token_colors, kind, y, start_column_index, column_index);
+            }
+        }
+
+        self.viewport_rect = Rect {
+            pos: dvec2(
+                rect.pos.x,
+                rect.pos.y + first_visible_line_index as f64 * self.line_height - self.offset,
+            ),
+            size: dvec2(rect.size.x, self.visible_line_count as f64 * self.line_height),
+        };
+        self.draw_viewport.draw_abs(cx, self.viewport_rect);
+
+        // the most severe markers are drawn last, over the others on their line
+        let mut decorations = session.document().decorations().to_vec();
+        decorations.sort_by_key(|decoration| Reverse(decoration.severity));
+        for decoration in decorations {
+            let line_index = decoration.start().line_index;
+            // markers are laid out like the lines if they fit, and spread over the height otherwise
+            let y = if self.fits() {
+                line_index as f64 * self.line_height
+            } else {
+                line_index as f64 / self.line_count as f64 * rect.size.y
+            };
+            self.draw_marker.color = severity_colors.color(decoration.severity);
+            self.draw_marker.draw_abs(
+                cx,
+                Rect {
+                    pos: dvec2(rect.pos.x + rect.size.x - self.marker_width, rect.pos.y + y),
+                    size: dvec2(self.marker_width, self.marker_height),
+                },
+            );
+        }
+    }
+
+    /// Starts dragging the indicator, and returns the line to scroll to. Pressing next to the
+    /// indicator centers it on the pressed line.
+    pub fn handle_finger_down(&mut self, abs: DVec2) -> usize {
+        let drag_offset = if self.viewport_rect.contains(abs) {
+            abs.y - self.viewport_rect.pos.y
+        } else {
+            self.viewport_rect.size.y / 2.0
+        };
+        self.drag_offset = Some(drag_offset);
+        self.line_index_at(abs.y - drag_offset)
+    }
+
+    /// Returns the line to scroll to while the indicator is dragged.
+    pub fn handle_finger_move(&mut self, abs: DVec2) -> Option<usize> {
+        self.drag_offset
+            .map(|drag_offset| self.line_index_at(abs.y - drag_offset))
+    }
+
+    pub fn handle_finger_up(&mut self) {
+        self.drag_offset = None;
+    }
+
+    fn fits(&self) -> bool {
+        self.line_count as f64 * self.line_height <= self.rect.size.y
+            || self.line_count <= self.visible_line_count
+    }
+
+    // the first visible line for which the top of the indicator is at `y`
+    fn line_index_at(&self, y: f64) -> usize {
+        let y = y - self.rect.pos.y;
+        let line_index = if self.fits() {
+            y / self.line_height
+        } else {
+            let track_height = self.rect.size.y - self.visible_line_count as f64 * self.line_height;
+            if track_height <= 0.0 {
+                0.0
+            } else {
+                y / track_height * (self.line_count - self.visible_line_count) as f64
+            }
+        };
+        (line_index.max(0.0) as usize).min(self.line_count.saturating_sub(1))
+    }
+
+    fn draw_run(
+        &mut self,
+        cx: &mut Cx2d,
+        token_colors: &TokenColors,
+        kind: TokenKind,
+        y: f64,
+        start_column_index: usize,
+        end_column_index: usize,
+    ) {
+        let start_x = self.rect.pos.x + start_column_index as f64 * self.column_width;
+        let end_x = (self.rect.pos.x + end_column_index as f64 * self.column_width)
+            .min(self.rect.pos.x + self.rect.size.x);
+        if end_x <= start_x {
+            return;
+        }
+        self.draw_token.color = token_colors.color(kind);
+        self.draw_token.draw_abs(
+            cx,
+            Rect {
+                pos: dvec2(start_x, y),
+                size: dvec2(end_x - start_x, self.token_height),
+            },
+        );
+    }
+}
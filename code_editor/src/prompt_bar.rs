@@ -0,0 +1,96 @@
+use makepad_widgets::*;
+
+live_design!{
+    import makepad_draw::shader::std::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    PromptBar = {{PromptBar}} {
+        width: Fill,
+        height: Fit,
+        flow: Right,
+        spacing: 5.0,
+        padding: {left: 10.0, top: 4.0, right: 10.0, bottom: 4.0},
+        align: {y: 0.5},
+        draw_bg: {
+            color: #3a3220
+        }
+        message_walk: {width: Fill, height: Fit}
+        draw_message: {
+            text_style: <THEME_FONT_LABEL> {},
+            color: #d
+        }
+        accept_button: <Button> {text: "Yes"}
+        decline_button: <Button> {text: "No"}
+    }
+}
+
+/// A bar that the `CodeEditor` shows above the text to ask a question about the document, such as
+/// whether to restore edits that were never saved.
+#[derive(Live, LiveHook)]
+pub struct PromptBar {
+    #[walk]
+    walk: Walk,
+    #[layout]
+    layout: Layout,
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    message_walk: Walk,
+    #[live]
+    draw_message: DrawText,
+    #[live]
+    accept_button: Button,
+    #[live]
+    decline_button: Button,
+    #[rust]
+    message: String,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PromptBarAction {
+    Accept,
+    Decline,
+}
+
+impl PromptBar {
+    pub fn open(&mut self, cx: &mut Cx, message: &str, accept_text: &str, decline_text: &str) {
+        self.message = message.to_string();
+        self.accept_button.set_text(accept_text);
+        self.decline_button.set_text(decline_text);
+        self.draw_bg.redraw(cx);
+    }
+
+    pub fn draw(&mut self, cx: &mut Cx2d) {
+        self.draw_bg.begin(cx, self.walk, self.layout);
+        self.draw_message.draw_walk(cx, self.message_walk, Align::default(), &self.message);
+        for button in [&mut self.accept_button, &mut self.decline_button] {
+            let walk = button.walk(cx);
+            button.draw_walk(cx, walk);
+        }
+        self.draw_bg.end(cx);
+    }
+
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event) -> Vec<PromptBarAction> {
+        let mut a = Vec::new();
+        self.handle_event_with(cx, event, &mut | _, v | a.push(v));
+        a
+    }
+
+    pub fn handle_event_with(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        dispatch_action: &mut dyn FnMut(&mut Cx, PromptBarAction),
+    ) {
+        self.accept_button.handle_event_with(cx, event, &mut | cx, action | {
+            if let ButtonAction::Clicked = action {
+                dispatch_action(cx, PromptBarAction::Accept);
+            }
+        });
+        self.decline_button.handle_event_with(cx, event, &mut | cx, action | {
+            if let ButtonAction::Clicked = action {
+                dispatch_action(cx, PromptBarAction::Decline);
+            }
+        });
+    }
+}
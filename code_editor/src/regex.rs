@@ -0,0 +1,740 @@
+use std::{fmt, ops::Range};
+
+/// A backtracking regular expression that matches within a single line.
+///
+/// Supports literals, `.`, classes such as `[a-z_]` and `[^0-9]`, the escapes `\d`, `\w`, `\s`
+/// and their negations, the anchors `^`, `$`, `\b` and `\B`, capturing and non-capturing `(?:)`
+/// groups, alternation and greedy or lazy quantifiers `*`, `+`, `?` and `{n,m}`.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    node: Node,
+    program: Program,
+    group_count: usize,
+    case_sensitive: bool,
+}
+
+impl Regex {
+    pub fn new(pattern: &str, case_sensitive: bool) -> Result<Self, RegexError> {
+        let mut parser = Parser {
+            chars: pattern.chars().collect(),
+            index: 0,
+            group_count: 0,
+        };
+        let node = parser.parse_alternation()?;
+        if parser.index < parser.chars.len() {
+            return Err(parser.error("unmatched `)`"));
+        }
+        Ok(Self::from_node(node, parser.group_count, case_sensitive))
+    }
+
+    fn from_node(node: Node, group_count: usize, case_sensitive: bool) -> Self {
+        Self {
+            program: Program::compile(&node, group_count),
+            node,
+            group_count,
+            case_sensitive,
+        }
+    }
+
+    /// Matches `text` literally.
+    pub fn literal(text: &str, case_sensitive: bool) -> Self {
+        Self::from_node(Node::Concat(text.chars().map(Node::Char).collect()), 0, case_sensitive)
+    }
+
+    /// Only matches that start and end at a word boundary.
+    pub fn whole_word(self) -> Self {
+        let node = Node::Concat(vec![
+            Node::WordBoundary(true),
+            Node::Group(Box::new(self.node), None),
+            Node::WordBoundary(true),
+        ]);
+        Self::from_node(node, self.group_count, self.case_sensitive)
+    }
+
+    /// The number of capturing groups, not counting the whole match.
+    pub fn group_count(&self) -> usize {
+        self.group_count
+    }
+
+    /// Finds the first match that starts at or after byte `start` of `line`.
+    pub fn captures_at(&self, line: &str, start: usize) -> Option<Captures> {
+        let chars: Vec<char> = line.chars().collect();
+        let mut byte_indices: Vec<usize> = line.char_indices().map(|(index, _)| index).collect();
+        byte_indices.push(line.len());
+        let mut matcher = Matcher {
+            program: &self.program,
+            chars: &chars,
+            case_sensitive: self.case_sensitive,
+            steps: 0,
+            stack: Vec::new(),
+        };
+        let first = byte_indices.iter().position(|&index| index >= start)?;
+        let mut slots = vec![None; self.program.slot_count];
+        for char_start in first..=chars.len() {
+            slots.iter_mut().for_each(|slot| *slot = None);
+            if let Some(end) = matcher.run(char_start, &mut slots) {
+                slots[0] = Some(char_start);
+                slots[1] = Some(end);
+                return Some(Captures {
+                    groups: (0..=self.group_count)
+                        .map(|group| match (slots[2 * group], slots[2 * group + 1]) {
+                            (Some(start), Some(end)) if start <= end => {
+                                Some(byte_indices[start]..byte_indices[end])
+                            }
+                            _ => None,
+                        })
+                        .collect(),
+                });
+            }
+            if matcher.steps > MAX_STEPS {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// The byte ranges of all non-empty, non-overlapping matches in `line`.
+    pub fn find_all(&self, line: &str) -> Vec<Range<usize>> {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        while let Some(captures) = self.captures_at(line, start) {
+            let range = captures.range();
+            if range.is_empty() {
+                match line[range.start..].chars().next() {
+                    Some(char) => start = range.start + char.len_utf8(),
+                    None => break,
+                }
+            } else {
+                start = range.end;
+                ranges.push(range);
+            }
+        }
+        ranges
+    }
+}
+
+/// The byte ranges of a match and its groups, group 0 is the whole match.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Captures {
+    groups: Vec<Option<Range<usize>>>,
+}
+
+impl Captures {
+    pub fn range(&self) -> Range<usize> {
+        self.groups[0].clone().unwrap()
+    }
+
+    /// The range of a group, or `None` if the group did not take part in the match.
+    pub fn get(&self, index: usize) -> Option<Range<usize>> {
+        self.groups.get(index).cloned().flatten()
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct RegexError {
+    /// The index of the char in the pattern where the error was found.
+    pub index: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.index)
+    }
+}
+
+// bounds the time spent on patterns that backtrack exponentially
+const MAX_STEPS: usize = 1_000_000;
+
+#[derive(Clone, Debug)]
+enum Node {
+    Empty,
+    Char(char),
+    Any,
+    Class(Class),
+    Start,
+    End,
+    WordBoundary(bool),
+    Group(Box<Node>, Option<usize>),
+    Concat(Vec<Node>),
+    Alternate(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+}
+
+impl Node {
+    fn is_single_char(&self) -> bool {
+        matches!(self, Node::Char(_) | Node::Any | Node::Class(_))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+impl ClassItem {
+    fn matches(self, char: char) -> bool {
+        match self {
+            ClassItem::Range(start, end) => start <= char && char <= end,
+            ClassItem::Digit(expected) => char.is_ascii_digit() == expected,
+            ClassItem::Word(expected) => is_word_char(char) == expected,
+            ClassItem::Space(expected) => char.is_whitespace() == expected,
+        }
+    }
+}
+
+impl Class {
+    fn matches(&self, char: char, case_sensitive: bool) -> bool {
+        let matches = |char: char| self.items.iter().any(|item| item.matches(char));
+        let is_match = matches(char)
+            || (!case_sensitive
+                && (char.to_lowercase().any(matches) || char.to_uppercase().any(matches)));
+        is_match != self.negated
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize,
+    group_count: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn error(&self, message: &'static str) -> RegexError {
+        RegexError {
+            index: self.index,
+            message,
+        }
+    }
+
+    fn parse_alternation(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = vec![self.parse_concat()?];
+        while self.peek() == Some('|') {
+            self.index += 1;
+            nodes.push(self.parse_concat()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().unwrap()
+        } else {
+            Node::Alternate(nodes)
+        })
+    }
+
+    fn parse_concat(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = Vec::new();
+        while let Some(char) = self.peek() {
+            if char == '|' || char == ')' {
+                break;
+            }
+            nodes.push(self.parse_repeat()?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn parse_repeat(&mut self) -> Result<Node, RegexError> {
+        if let Some('*' | '+' | '?') = self.peek() {
+            return Err(self.error("nothing to repeat"));
+        }
+        let mut node = self.parse_atom()?;
+        loop {
+            let start = self.index;
+            let (min, max) = match self.peek() {
+                Some('*') => {
+                    self.index += 1;
+                    (0, None)
+                }
+                Some('+') => {
+                    self.index += 1;
+                    (1, None)
+                }
+                Some('?') => {
+                    self.index += 1;
+                    (0, Some(1))
+                }
+                Some('{') => match self.parse_counted()? {
+                    Some(counts) => counts,
+                    None => break,
+                },
+                _ => break,
+            };
+            if matches!(node, Node::Start | Node::End | Node::WordBoundary(_)) {
+                self.index = start;
+                return Err(self.error("nothing to repeat"));
+            }
+            let greedy = if self.peek() == Some('?') {
+                self.index += 1;
+                false
+            } else {
+                true
+            };
+            node = Node::Repeat {
+                node: Box::new(node),
+                min,
+                max,
+                greedy,
+            };
+        }
+        Ok(node)
+    }
+
+    // `{n}`, `{n,}` or `{n,m}`, a `{` that doesn't start one of those is a literal
+    fn parse_counted(&mut self) -> Result<Option<(usize, Option<usize>)>, RegexError> {
+        let start = self.index;
+        self.index += 1;
+        let min = self.parse_number();
+        let max = if self.peek() == Some(',') {
+            self.index += 1;
+            self.parse_number()
+        } else {
+            min.or(Some(0))
+        };
+        if min.is_none() || self.peek() != Some('}') {
+            self.index = start;
+            return Ok(None);
+        }
+        self.index += 1;
+        let min = min.unwrap();
+        if max.map_or(false, |max| max < min) {
+            self.index = start;
+            return Err(self.error("invalid repetition count"));
+        }
+        Ok(Some((min, max)))
+    }
+
+    fn parse_number(&mut self) -> Option<usize> {
+        let start = self.index;
+        while self.peek().map_or(false, |char| char.is_ascii_digit()) {
+            self.index += 1;
+        }
+        self.chars[start..self.index]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()
+    }
+
+    fn parse_atom(&mut self) -> Result<Node, RegexError> {
+        let char = self.peek().unwrap();
+        self.index += 1;
+        Ok(match char {
+            '(' => {
+                let index = if self.chars[self.index..].starts_with(&['?', ':']) {
+                    self.index += 2;
+                    None
+                } else {
+                    self.group_count += 1;
+                    Some(self.group_count)
+                };
+                let node = self.parse_alternation()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                self.index += 1;
+                Node::Group(Box::new(node), index)
+            }
+            '[' => self.parse_class()?,
+            '.' => Node::Any,
+            '^' => Node::Start,
+            '$' => Node::End,
+            '\\' => match self.parse_escape()? {
+                Escape::Char(char) => Node::Char(char),
+                Escape::Class(item) => Node::Class(Class {
+                    negated: false,
+                    items: vec![item],
+                }),
+                Escape::WordBoundary(expected) => Node::WordBoundary(expected),
+            },
+            char => Node::Char(char),
+        })
+    }
+
+    fn parse_escape(&mut self) -> Result<Escape, RegexError> {
+        let char = match self.peek() {
+            Some(char) => char,
+            None => return Err(self.error("trailing backslash")),
+        };
+        self.index += 1;
+        Ok(match char {
+            'd' => Escape::Class(ClassItem::Digit(true)),
+            'D' => Escape::Class(ClassItem::Digit(false)),
+            'w' => Escape::Class(ClassItem::Word(true)),
+            'W' => Escape::Class(ClassItem::Word(false)),
+            's' => Escape::Class(ClassItem::Space(true)),
+            'S' => Escape::Class(ClassItem::Space(false)),
+            'b' => Escape::WordBoundary(true),
+            'B' => Escape::WordBoundary(false),
+            'n' => Escape::Char('\n'),
+            'r' => Escape::Char('\r'),
+            't' => Escape::Char('\t'),
+            char if char.is_ascii_alphanumeric() => {
+                self.index -= 1;
+                return Err(self.error("unsupported escape"));
+            }
+            char => Escape::Char(char),
+        })
+    }
+
+    fn parse_class(&mut self) -> Result<Node, RegexError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.index += 1;
+        }
+        let mut items = Vec::new();
+        let mut first = true;
+        loop {
+            let char = match self.peek() {
+                Some(char) => char,
+                None => return Err(self.error("unclosed character class")),
+            };
+            self.index += 1;
+            if char == ']' && !first {
+                break;
+            }
+            first = false;
+            let start = if char == '\\' {
+                match self.parse_escape()? {
+                    Escape::Char(char) => char,
+                    Escape::Class(item) => {
+                        items.push(item);
+                        continue;
+                    }
+                    Escape::WordBoundary(_) => {
+                        self.index -= 1;
+                        return Err(self.error("word boundary in character class"));
+                    }
+                }
+            } else {
+                char
+            };
+            if self.peek() == Some('-') && self.chars.get(self.index + 1).map_or(false, |&char| char != ']') {
+                self.index += 1;
+                let end = match self.peek().unwrap() {
+                    '\\' => {
+                        self.index += 1;
+                        match self.parse_escape()? {
+                            Escape::Char(char) => char,
+                            _ => return Err(self.error("invalid range in character class")),
+                        }
+                    }
+                    char => {
+                        self.index += 1;
+                        char
+                    }
+                };
+                if end < start {
+                    return Err(self.error("invalid range in character class"));
+                }
+                items.push(ClassItem::Range(start, end));
+            } else {
+                items.push(ClassItem::Range(start, start));
+            }
+        }
+        Ok(Node::Class(Class { negated, items }))
+    }
+}
+
+enum Escape {
+    Char(char),
+    Class(ClassItem),
+    WordBoundary(bool),
+}
+
+// the instructions that a pattern is compiled to, which are run with a stack of their own rather
+// than by recursing, so that long lines can't overflow the stack
+#[derive(Clone, Debug)]
+enum Inst {
+    Char(Node),
+    // repeats of a single char don't need to backtrack into the repeated node, so they are matched
+    // in one go
+    RepeatChar {
+        node: Node,
+        min: usize,
+        max: Option<usize>,
+        greedy: bool,
+    },
+    Start,
+    End,
+    WordBoundary(bool),
+    Save(usize),
+    // continues at the first instruction, and backtracks to the second
+    Split(usize, usize),
+    Jump(usize),
+    // fails if nothing was matched since the slot was saved, as a repetition that matches nothing
+    // would repeat forever
+    CheckProgress(usize),
+    Match,
+}
+
+#[derive(Clone, Debug)]
+struct Program {
+    insts: Vec<Inst>,
+    // the start and end of every group, followed by the positions where repetitions started
+    slot_count: usize,
+}
+
+impl Program {
+    fn compile(node: &Node, group_count: usize) -> Self {
+        let mut program = Self {
+            insts: Vec::new(),
+            slot_count: 2 * (group_count + 1),
+        };
+        program.compile_node(node);
+        program.insts.push(Inst::Match);
+        program
+    }
+
+    fn compile_node(&mut self, node: &Node) {
+        match node {
+            Node::Empty => {}
+            Node::Char(_) | Node::Any | Node::Class(_) => self.insts.push(Inst::Char(node.clone())),
+            Node::Start => self.insts.push(Inst::Start),
+            Node::End => self.insts.push(Inst::End),
+            Node::WordBoundary(expected) => self.insts.push(Inst::WordBoundary(*expected)),
+            Node::Group(node, None) => self.compile_node(node),
+            Node::Group(node, Some(group)) => {
+                self.insts.push(Inst::Save(2 * group));
+                self.compile_node(node);
+                self.insts.push(Inst::Save(2 * group + 1));
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile_node(node);
+                }
+            }
+            Node::Alternate(nodes) => {
+                let mut jumps = Vec::new();
+                let (last, nodes) = nodes.split_last().unwrap();
+                for node in nodes {
+                    let split = self.insts.len();
+                    self.insts.push(Inst::Split(split + 1, 0));
+                    self.compile_node(node);
+                    jumps.push(self.insts.len());
+                    self.insts.push(Inst::Jump(0));
+                    self.insts[split] = Inst::Split(split + 1, self.insts.len());
+                }
+                self.compile_node(last);
+                let end = self.insts.len();
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jump(end);
+                }
+            }
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } if node.is_single_char() => self.insts.push(Inst::RepeatChar {
+                node: (**node).clone(),
+                min: *min,
+                max: *max,
+                greedy: *greedy,
+            }),
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => {
+                for _ in 0..*min {
+                    self.compile_node(node);
+                }
+                let split = |body, exit| {
+                    if *greedy {
+                        Inst::Split(body, exit)
+                    } else {
+                        Inst::Split(exit, body)
+                    }
+                };
+                match max {
+                    Some(max) => {
+                        let mut splits = Vec::new();
+                        for _ in *min..*max {
+                            splits.push(self.insts.len());
+                            self.insts.push(Inst::Jump(0));
+                            self.compile_node(node);
+                        }
+                        let exit = self.insts.len();
+                        for index in splits {
+                            self.insts[index] = split(index + 1, exit);
+                        }
+                    }
+                    None => {
+                        let slot = self.slot_count;
+                        self.slot_count += 1;
+                        let start = self.insts.len();
+                        self.insts.push(Inst::Jump(0));
+                        self.insts.push(Inst::Save(slot));
+                        self.compile_node(node);
+                        self.insts.push(Inst::CheckProgress(slot));
+                        self.insts.push(Inst::Jump(start));
+                        self.insts[start] = split(start + 1, self.insts.len());
+                    }
+                }
+            }
+        }
+    }
+}
+
+// what to undo or try next when the instructions that are running fail
+enum Backtrack {
+    Resume { pc: usize, index: usize },
+    RestoreSlot { slot: usize, value: Option<usize> },
+    // the next count to try for the repetition of a single char at `pc`
+    RepeatChar { pc: usize, start: usize, count: usize },
+}
+
+struct Matcher<'a> {
+    program: &'a Program,
+    chars: &'a [char],
+    case_sensitive: bool,
+    steps: usize,
+    stack: Vec<Backtrack>,
+}
+
+impl<'a> Matcher<'a> {
+    // returns the end of the first way the program matches at `index`, with the slots it saved
+    fn run(&mut self, mut index: usize, slots: &mut [Option<usize>]) -> Option<usize> {
+        self.stack.clear();
+        let mut pc = 0;
+        loop {
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                return None;
+            }
+            let is_match = match &self.program.insts[pc] {
+                Inst::Char(node) => {
+                    let is_match = self.match_char(node, index);
+                    index += 1;
+                    is_match
+                }
+                Inst::RepeatChar {
+                    node,
+                    min,
+                    max,
+                    greedy,
+                } => {
+                    let limit = if *greedy { *max } else { Some(*min) };
+                    let mut count = 0;
+                    while limit.map_or(true, |limit| count < limit) && self.match_char(node, index + count) {
+                        count += 1;
+                    }
+                    if count < *min {
+                        false
+                    } else {
+                        if *greedy && count > *min {
+                            self.stack.push(Backtrack::RepeatChar { pc, start: index, count: count - 1 });
+                        } else if !*greedy {
+                            self.stack.push(Backtrack::RepeatChar { pc, start: index, count: count + 1 });
+                        }
+                        index += count;
+                        true
+                    }
+                }
+                Inst::Start => index == 0,
+                Inst::End => index == self.chars.len(),
+                Inst::WordBoundary(expected) => {
+                    let before = index > 0 && is_word_char(self.chars[index - 1]);
+                    let after = index < self.chars.len() && is_word_char(self.chars[index]);
+                    (before != after) == *expected
+                }
+                Inst::Save(slot) => {
+                    self.stack.push(Backtrack::RestoreSlot {
+                        slot: *slot,
+                        value: slots[*slot],
+                    });
+                    slots[*slot] = Some(index);
+                    true
+                }
+                Inst::Split(next_pc, other_pc) => {
+                    self.stack.push(Backtrack::Resume { pc: *other_pc, index });
+                    pc = *next_pc;
+                    continue;
+                }
+                Inst::Jump(next_pc) => {
+                    pc = *next_pc;
+                    continue;
+                }
+                Inst::CheckProgress(slot) => slots[*slot] != Some(index),
+                Inst::Match => return Some(index),
+            };
+            if is_match {
+                pc += 1;
+                continue;
+            }
+            (pc, index) = self.backtrack(slots)?;
+        }
+    }
+
+    // undoes the instructions that ran since the last choice that is left, and returns where to
+    // continue with it
+    fn backtrack(&mut self, slots: &mut [Option<usize>]) -> Option<(usize, usize)> {
+        loop {
+            match self.stack.pop()? {
+                Backtrack::Resume { pc, index } => return Some((pc, index)),
+                Backtrack::RestoreSlot { slot, value } => slots[slot] = value,
+                Backtrack::RepeatChar { pc, start, count } => {
+                    let Inst::RepeatChar { node, min, max, greedy } = &self.program.insts[pc] else {
+                        unreachable!()
+                    };
+                    if *greedy {
+                        if count > *min {
+                            self.stack.push(Backtrack::RepeatChar { pc, start, count: count - 1 });
+                        }
+                        return Some((pc + 1, start + count));
+                    }
+                    // a lazy repetition takes one more char each time
+                    if max.map_or(true, |max| count <= max) && self.match_char(node, start + count - 1) {
+                        self.stack.push(Backtrack::RepeatChar { pc, start, count: count + 1 });
+                        return Some((pc + 1, start + count));
+                    }
+                }
+            }
+        }
+    }
+
+    fn match_char(&self, node: &Node, index: usize) -> bool {
+        let char = match self.chars.get(index) {
+            Some(&char) => char,
+            None => return false,
+        };
+        match node {
+            Node::Char(expected) => {
+                char == *expected
+                    || (!self.case_sensitive && char.to_lowercase().eq(expected.to_lowercase()))
+            }
+            Node::Any => true,
+            Node::Class(class) => class.matches(char, self.case_sensitive),
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn is_word_char(char: char) -> bool {
+    char.is_alphanumeric() || char == '_'
+}
@@ -0,0 +1,87 @@
+use {
+    crate::{
+        regex::{Captures, Regex, RegexError},
+        text::{Position, Text},
+    },
+    std::ops::Range,
+};
+
+/// What the find bar searches for.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    /// Whether `text` is a regular expression rather than a literal string.
+    pub regex: bool,
+}
+
+impl SearchQuery {
+    pub fn compile(&self) -> Result<Regex, RegexError> {
+        let regex = if self.regex {
+            Regex::new(&self.text, self.case_sensitive)?
+        } else {
+            Regex::literal(&self.text, self.case_sensitive)
+        };
+        Ok(if self.whole_word {
+            regex.whole_word()
+        } else {
+            regex
+        })
+    }
+
+    /// The text that replaces a match. In regex mode, `$0` to `$9` and `${n}` refer to groups of
+    /// the match and `$$` is a dollar sign.
+    pub fn expand_replacement(&self, replacement: &str, line: &str, captures: &Captures) -> String {
+        if !self.regex {
+            return replacement.to_string();
+        }
+        let mut string = String::new();
+        let mut rest = replacement;
+        while let Some(index) = rest.find('$') {
+            string.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+            let (group, len) = if rest.starts_with('$') {
+                string.push('$');
+                rest = &rest[1..];
+                continue;
+            } else if let Some(digit) = rest.chars().next().and_then(|char| char.to_digit(10)) {
+                (Some(digit as usize), 1)
+            } else if let Some(end) = rest.strip_prefix('{').and_then(|rest| rest.find('}')) {
+                (rest[1..end + 1].parse().ok(), end + 2)
+            } else {
+                (None, 0)
+            };
+            match group {
+                Some(group) => {
+                    if let Some(range) = captures.get(group) {
+                        string.push_str(&line[range]);
+                    }
+                    rest = &rest[len..];
+                }
+                None => string.push('$'),
+            }
+        }
+        string.push_str(rest);
+        string
+    }
+}
+
+/// The ranges of all matches of `regex` in `text`, in order.
+pub fn find_all(regex: &Regex, text: &Text) -> Vec<Range<Position>> {
+    let mut ranges = Vec::new();
+    for (line_index, line) in text.as_lines().iter().enumerate() {
+        for range in regex.find_all(line) {
+            ranges.push(
+                Position {
+                    line_index,
+                    byte_index: range.start,
+                }..Position {
+                    line_index,
+                    byte_index: range.end,
+                },
+            );
+        }
+    }
+    ranges
+}
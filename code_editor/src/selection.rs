@@ -4,10 +4,11 @@ use {
         str::StrExt,
         text::{Edit, Length, Position},
     },
+    makepad_widgets::makepad_micro_serde::{DeBin, DeBinErr, SerBin},
     std::{ops, ops::Deref, slice::Iter},
 };
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Hash, Eq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Hash, Eq, SerBin, DeBin)]
 pub struct Selection {
     pub cursor: Cursor,
     pub anchor: Position,
@@ -116,7 +117,7 @@ impl From<Cursor> for Selection {
     }
 }
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SerBin, DeBin)]
 pub struct SelectionSet {
     selections: Vec<Selection>,
 }
@@ -243,7 +244,7 @@ impl<'a> IntoIterator for &'a SelectionSet {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, SerBin, DeBin)]
 pub struct Cursor {
     pub position: Position,
     pub affinity: Affinity,
@@ -320,6 +321,102 @@ impl Cursor {
         self
     }
 
+    pub fn move_to_prev_word(self, lines: &[String]) -> Self {
+        if self.is_at_start_of_line() {
+            return self.move_left(lines);
+        }
+        let line = &lines[self.position.line_index][..self.position.byte_index];
+        let mut chars = line.char_indices().rev().skip_while(|(_, char)| char.is_whitespace());
+        let byte_index = match chars.next() {
+            Some((index, char)) => {
+                let class = CharClass::of(char);
+                chars
+                    .take_while(|&(_, char)| CharClass::of(char) == class)
+                    .last()
+                    .map_or(index, |(index, _)| index)
+            }
+            None => 0,
+        };
+        Self {
+            position: Position {
+                line_index: self.position.line_index,
+                byte_index,
+            },
+            affinity: Affinity::After,
+            preferred_column_index: None,
+        }
+    }
+
+    pub fn move_to_next_word(self, lines: &[String]) -> Self {
+        if self.is_at_end_of_line(lines) {
+            return self.move_right(lines);
+        }
+        let line = &lines[self.position.line_index];
+        let mut chars = line[self.position.byte_index..]
+            .char_indices()
+            .map(|(index, char)| (self.position.byte_index + index, char))
+            .skip_while(|(_, char)| char.is_whitespace())
+            .peekable();
+        let byte_index = match chars.peek() {
+            Some(&(_, char)) => {
+                let class = CharClass::of(char);
+                chars
+                    .find(|&(_, char)| CharClass::of(char) != class)
+                    .map_or(line.len(), |(index, _)| index)
+            }
+            None => line.len(),
+        };
+        Self {
+            position: Position {
+                line_index: self.position.line_index,
+                byte_index,
+            },
+            affinity: Affinity::Before,
+            preferred_column_index: None,
+        }
+    }
+
+    /// Moves to the end of the indentation, or to the start of the line if the cursor is already
+    /// there.
+    pub fn move_to_start_of_line(self, lines: &[String]) -> Self {
+        let line = &lines[self.position.line_index];
+        let indent_len = line.indent().unwrap_or(line).len();
+        Self {
+            position: Position {
+                line_index: self.position.line_index,
+                byte_index: if self.position.byte_index == indent_len {
+                    0
+                } else {
+                    indent_len
+                },
+            },
+            affinity: Affinity::Before,
+            preferred_column_index: None,
+        }
+    }
+
+    pub fn move_to_end_of_line(self, lines: &[String]) -> Self {
+        Self {
+            position: Position {
+                line_index: self.position.line_index,
+                byte_index: lines[self.position.line_index].len(),
+            },
+            affinity: Affinity::After,
+            preferred_column_index: None,
+        }
+    }
+
+    pub fn move_to_start_of_text(self) -> Self {
+        Self::from(Position::zero())
+    }
+
+    pub fn move_to_end_of_text(self, lines: &[String]) -> Self {
+        Self::from(Position {
+            line_index: lines.len() - 1,
+            byte_index: lines[lines.len() - 1].len(),
+        })
+    }
+
     pub fn move_to_prev_grapheme(self, lines: &[String]) -> Self {
         Self {
             position: Position {
@@ -457,6 +554,26 @@ impl Cursor {
     }
 }
 
+/// Word movement stops where the class of the chars changes.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum CharClass {
+    Whitespace,
+    Word,
+    Punctuation,
+}
+
+impl CharClass {
+    pub fn of(char: char) -> Self {
+        if char.is_whitespace() {
+            Self::Whitespace
+        } else if char.is_alphanumeric() || char == '_' {
+            Self::Word
+        } else {
+            Self::Punctuation
+        }
+    }
+}
+
 impl From<Position> for Cursor {
     fn from(position: Position) -> Self {
         Self {
@@ -467,7 +584,7 @@ impl From<Position> for Cursor {
     }
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, SerBin, DeBin)]
 pub enum Affinity {
     Before,
     After,
@@ -1,11 +1,16 @@
 use {
     crate::{
         char::CharExt,
+        decoration::{Decoration, DecorationSet},
         document::Document,
         history::EditKind,
         layout::{BlockElement, Layout, WrappedElement},
-        selection::{Affinity, Cursor, SelectionSet},
+        regex::{Regex, RegexError},
+        search::{self, SearchQuery},
+        iter::IteratorExt,
+        selection::{Affinity, CharClass, Cursor, SelectionSet},
         str::StrExt,
+        structure::{FoldKind, FoldRange},
         text::{Change, Drift, Edit, Length, Position, Text},
         wrap,
         wrap::WrapData,
@@ -16,6 +21,7 @@ use {
         collections::HashSet,
         fmt::Write,
         iter, mem,
+        ops::Range,
         rc::Rc,
         sync::{atomic, atomic::AtomicUsize, mpsc, mpsc::Receiver},
     },
@@ -32,6 +38,10 @@ pub struct Session {
     folding_lines: HashSet<usize>,
     folded_lines: HashSet<usize>,
     unfolding_lines: HashSet<usize>,
+    // the fold revision of the document that `hidden_line_ranges` were last updated for
+    fold_revision: usize,
+    hidden_line_ranges: Vec<Range<usize>>,
+    search: RefCell<Option<SearchState>>,
     edit_receiver: Receiver<(Option<SelectionSet>, Vec<Edit>)>,
 }
 
@@ -62,13 +72,16 @@ impl Session {
             folding_lines: HashSet::new(),
             folded_lines: HashSet::new(),
             unfolding_lines: HashSet::new(),
-
+            fold_revision: 0,
+            hidden_line_ranges: Vec::new(),
+            search: RefCell::new(None),
             edit_receiver,
         };
         for line in 0..line_count {
             session.update_wrap_data(line);
         }
         session.update_y();
+        session.update_hidden_lines();
         session.document.add_session(session.id, edit_sender);
         session
     }
@@ -128,6 +141,10 @@ impl Session {
     pub fn fold(&mut self) {
         let line_count = self.document().as_text().as_lines().len();
         for line_index in 0..line_count {
+            // the lines of folded ranges are hidden already
+            if self.layout.borrow().scale[line_index] == 0.0 {
+                continue;
+            }
             let layout = self.layout();
             let line = layout.line(line_index);
             let indent_level = line.indent_column_count() / self.settings.tab_column_count;
@@ -182,6 +199,70 @@ impl Session {
         true
     }
 
+    /// The fold range that starts at the given line, if there is one.
+    pub fn fold_range_at(&self, line_index: usize) -> Option<FoldRange> {
+        let fold_ranges = self.document.fold_ranges();
+        fold_ranges
+            .binary_search_by_key(&line_index, |range| range.start_line_index)
+            .ok()
+            .map(|index| fold_ranges[index])
+    }
+
+    pub fn is_folded(&self, line_index: usize) -> bool {
+        self.document.folded_lines().contains(&line_index)
+    }
+
+    /// Whether the line is hidden in a folded range.
+    pub fn is_line_hidden(&self, line_index: usize) -> bool {
+        self.hidden_line_ranges
+            .iter()
+            .any(|range| range.contains(&line_index))
+    }
+
+    /// Folds or unfolds the range that starts at the given line. On a line that doesn't start a
+    /// range, the innermost range that the line is in is folded instead.
+    pub fn toggle_fold(&mut self, line_index: usize) {
+        if self.fold_range_at(line_index).is_some() {
+            let is_folded = self.is_folded(line_index);
+            self.document.set_folded(line_index, !is_folded);
+        } else {
+            let range = self
+                .document
+                .fold_ranges()
+                .iter()
+                .filter(|range| range.hidden_line_range().contains(&line_index))
+                .last()
+                .copied();
+            if let Some(range) = range {
+                self.document.set_folded(range.start_line_index, true);
+            }
+        }
+        self.update_hidden_lines();
+    }
+
+    /// Folds all ranges of the given kind, or of any kind if it is `None`.
+    pub fn fold_all(&mut self, kind: Option<FoldKind>) {
+        let start_line_indices: Vec<_> = self
+            .document
+            .fold_ranges()
+            .iter()
+            .filter(|range| kind.map_or(true, |kind| range.kind == kind))
+            .map(|range| range.start_line_index)
+            .collect();
+        for line_index in start_line_indices {
+            self.document.set_folded(line_index, true);
+        }
+        self.update_hidden_lines();
+    }
+
+    pub fn unfold_all(&mut self) {
+        let folded_lines: Vec<_> = self.document.folded_lines().iter().copied().collect();
+        for line_index in folded_lines {
+            self.document.set_folded(line_index, false);
+        }
+        self.update_hidden_lines();
+    }
+
     pub fn set_selection(&mut self, position: Position, affinity: Affinity, tap_count: u32) {
         let mut selection= Selection::from(Cursor {
             position,
@@ -236,6 +317,38 @@ impl Session {
         self.document.force_new_group();
     }
 
+    /// Replaces the selections, such as with ones that were saved in an earlier session. Selections
+    /// that don't fit the text are left out, and if none of them fit, nothing changes.
+    pub fn set_selections(&mut self, selections: &[Selection]) {
+        let text = self.document.as_text();
+        let fits = |position: Position| {
+            text.as_lines()
+                .get(position.line_index)
+                .map_or(false, |line| line.is_char_boundary(position.byte_index))
+        };
+        let mut selections = selections
+            .iter()
+            .copied()
+            .filter(|selection| fits(selection.anchor) && fits(selection.cursor.position));
+        let Some(first_selection) = selections.next() else {
+            return;
+        };
+        let mut selection_set = SelectionSet::new();
+        selection_set.set_selection(first_selection);
+        let mut last_added_selection_index = 0;
+        for selection in selections {
+            last_added_selection_index = selection_set.add_selection(selection);
+        }
+        drop(text);
+        let mut selection_state = self.selection_state.borrow_mut();
+        selection_state.selections = selection_set;
+        selection_state.last_added_selection_index = Some(last_added_selection_index);
+        selection_state.injected_char_stack.clear();
+        drop(selection_state);
+        self.update_highlighted_delimiter_positions();
+        self.document.force_new_group();
+    }
+
     pub fn move_to(&mut self, position: Position, affinity: Affinity) {
         let mut selection_state = self.selection_state.borrow_mut();
         let last_added_selection_index = selection_state.last_added_selection_index.unwrap();
@@ -280,6 +393,209 @@ impl Session {
         });
     }
 
+    pub fn move_word_left(&mut self, reset_anchor: bool) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| cursor.move_to_prev_word(layout.as_text().as_lines()))
+        });
+    }
+
+    pub fn move_word_right(&mut self, reset_anchor: bool) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| cursor.move_to_next_word(layout.as_text().as_lines()))
+        });
+    }
+
+    /// Moves to the end of the indentation, or to the start of the line if the cursor is already
+    /// there.
+    pub fn move_to_start_of_line(&mut self, reset_anchor: bool) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| cursor.move_to_start_of_line(layout.as_text().as_lines()))
+        });
+    }
+
+    pub fn move_to_end_of_line(&mut self, reset_anchor: bool) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| cursor.move_to_end_of_line(layout.as_text().as_lines()))
+        });
+    }
+
+    pub fn move_page_up(&mut self, reset_anchor: bool, row_count: usize) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| {
+                (0..row_count).fold(cursor, |cursor, _| cursor.move_up(layout))
+            })
+        });
+    }
+
+    pub fn move_page_down(&mut self, reset_anchor: bool, row_count: usize) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| {
+                (0..row_count).fold(cursor, |cursor, _| cursor.move_down(layout))
+            })
+        });
+    }
+
+    pub fn move_to_start_of_text(&mut self, reset_anchor: bool) {
+        self.modify_selections(reset_anchor, |selection, _| {
+            selection.update_cursor(|cursor| cursor.move_to_start_of_text())
+        });
+    }
+
+    pub fn move_to_end_of_text(&mut self, reset_anchor: bool) {
+        self.modify_selections(reset_anchor, |selection, layout| {
+            selection.update_cursor(|cursor| cursor.move_to_end_of_text(layout.as_text().as_lines()))
+        });
+    }
+
+    /// Expands each selection to the words at its ends. An empty selection selects the word it
+    /// touches.
+    pub fn select_word(&mut self) {
+        self.modify_selections(false, |selection, layout| {
+            let lines = layout.as_text().as_lines();
+            let (start, end) = (selection.start(), selection.end());
+            let start_line = &lines[start.line_index];
+            let end_line = &lines[end.line_index];
+            let start_class = if selection.is_empty() {
+                let before = start_line[..start.byte_index].chars().next_back().map(CharClass::of);
+                let after = start_line[start.byte_index..].chars().next().map(CharClass::of);
+                match (before, after) {
+                    (_, Some(CharClass::Word)) | (Some(CharClass::Word), _) => CharClass::Word,
+                    (_, Some(class)) | (Some(class), None) => class,
+                    (None, None) => return selection,
+                }
+            } else {
+                start_line[start.byte_index..].chars().next().map_or(CharClass::Whitespace, CharClass::of)
+            };
+            let end_class = if selection.is_empty() {
+                start_class
+            } else {
+                end_line[..end.byte_index].chars().next_back().map_or(CharClass::Whitespace, CharClass::of)
+            };
+            let start_byte_index = start_line[..start.byte_index]
+                .char_indices()
+                .rev()
+                .take_while(|&(_, char)| CharClass::of(char) == start_class)
+                .last()
+                .map_or(start.byte_index, |(index, _)| index);
+            let end_byte_index = end_line[end.byte_index..]
+                .char_indices()
+                .find(|&(_, char)| CharClass::of(char) != end_class)
+                .map_or(end_line.len(), |(index, _)| end.byte_index + index);
+            Selection {
+                anchor: Position {
+                    line_index: start.line_index,
+                    byte_index: start_byte_index,
+                },
+                cursor: Cursor::from(Position {
+                    line_index: end.line_index,
+                    byte_index: end_byte_index,
+                }),
+            }
+        });
+    }
+
+    /// Expands each selection to whole lines, or by one more line if it already covers whole
+    /// lines.
+    pub fn select_line(&mut self) {
+        self.modify_selections(false, |selection, layout| {
+            let lines = layout.as_text().as_lines();
+            let end_line_index = selection.end().line_index + 1;
+            Selection {
+                anchor: Position {
+                    line_index: selection.start().line_index,
+                    byte_index: 0,
+                },
+                cursor: Cursor::from(if end_line_index < lines.len() {
+                    Position {
+                        line_index: end_line_index,
+                        byte_index: 0,
+                    }
+                } else {
+                    Position {
+                        line_index: lines.len() - 1,
+                        byte_index: lines[lines.len() - 1].len(),
+                    }
+                }),
+            }
+        });
+    }
+
+    /// Swaps the lines of each selection with the line above them.
+    pub fn move_lines_up(&mut self) {
+        let line_ranges = self.selected_line_ranges();
+        if line_ranges.first().map_or(true, |line_range| line_range.start == 0) {
+            return;
+        }
+        let text = self.document.as_text();
+        let lines = text.as_lines();
+        let replacements = line_ranges
+            .iter()
+            .map(|line_range| {
+                let mut moved_lines: Vec<&str> = lines[line_range.clone()].iter().map(String::as_str).collect();
+                moved_lines.push(&lines[line_range.start - 1]);
+                replace_lines(lines, line_range.start - 1..line_range.end, &moved_lines)
+            })
+            .collect();
+        drop(text);
+        self.replace_lines(replacements, |line_index| line_index - 1);
+    }
+
+    /// Swaps the lines of each selection with the line below them.
+    pub fn move_lines_down(&mut self) {
+        let line_ranges = self.selected_line_ranges();
+        let text = self.document.as_text();
+        let lines = text.as_lines();
+        if line_ranges.last().map_or(true, |line_range| line_range.end == lines.len()) {
+            return;
+        }
+        let replacements = line_ranges
+            .iter()
+            .map(|line_range| {
+                let mut moved_lines = vec![lines[line_range.end].as_str()];
+                moved_lines.extend(lines[line_range.clone()].iter().map(String::as_str));
+                replace_lines(lines, line_range.start..line_range.end + 1, &moved_lines)
+            })
+            .collect();
+        drop(text);
+        self.replace_lines(replacements, |line_index| line_index + 1);
+    }
+
+    /// Inserts a copy of the lines of each selection below them, and moves the selections to the
+    /// copies.
+    pub fn duplicate_lines(&mut self) {
+        let line_ranges = self.selected_line_ranges();
+        let text = self.document.as_text();
+        let lines = text.as_lines();
+        let replacements = line_ranges
+            .iter()
+            .map(|line_range| {
+                let last_line_index = line_range.end - 1;
+                let mut copy = String::new();
+                for line in &lines[line_range.clone()] {
+                    copy.push('\n');
+                    copy.push_str(line);
+                }
+                (
+                    Position {
+                        line_index: last_line_index,
+                        byte_index: lines[last_line_index].len(),
+                    },
+                    Length::zero(),
+                    Text::from(copy),
+                )
+            })
+            .collect();
+        drop(text);
+        self.replace_lines(replacements, |line_index| {
+            line_index
+                + line_ranges
+                    .iter()
+                    .take_while(|line_range| line_range.start <= line_index)
+                    .map(|line_range| line_range.len())
+                    .sum::<usize>()
+        });
+    }
+
     pub fn insert(&mut self, text: Text) {
         let mut edit_kind = EditKind::Insert;
         let mut inject_char = None;
@@ -727,6 +1043,138 @@ impl Session {
         string
     }
 
+    pub fn search_query(&self) -> Option<SearchQuery> {
+        self.search.borrow().as_ref().map(|search| search.query.clone())
+    }
+
+    /// The matches of the current search, which are kept up to date as the text changes.
+    pub fn search_matches(&self) -> Ref<'_, [Decoration]> {
+        Ref::map(self.search.borrow(), |search| match search {
+            Some(search) => search.matches.as_decorations(),
+            None => &[],
+        })
+    }
+
+    /// Starts searching for `query` and returns the number of matches.
+    pub fn set_search(&mut self, query: SearchQuery) -> Result<usize, RegexError> {
+        if query.text.is_empty() {
+            self.clear_search();
+            return Ok(0);
+        }
+        let regex = query.compile()?;
+        *self.search.borrow_mut() = Some(SearchState {
+            query,
+            regex,
+            matches: DecorationSet::new(),
+        });
+        self.update_search_matches();
+        Ok(self.search_matches().len())
+    }
+
+    pub fn clear_search(&mut self) {
+        *self.search.borrow_mut() = None;
+    }
+
+    /// Selects the first match that starts at or after the last added selection, skipping the
+    /// match that is selected, and wraps around at the end of the text. If `add_selection` is
+    /// true, the match is added to the selections instead.
+    pub fn find_next(&mut self, add_selection: bool) -> bool {
+        let selection = self.last_added_selection();
+        let matches = self.search_matches();
+        let next = matches
+            .iter()
+            .find(|decoration| {
+                decoration.start() > selection.start()
+                    || decoration.start() == selection.start() && decoration.end() != selection.end()
+            })
+            .or(matches.first())
+            .copied();
+        drop(matches);
+        match next {
+            Some(next) => {
+                self.select_match(next, add_selection);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Selects the last match that starts before the last added selection, wrapping around at the
+    /// start of the text. If `add_selection` is true, the match is added to the selections instead.
+    pub fn find_previous(&mut self, add_selection: bool) -> bool {
+        let position = self.last_added_selection().start();
+        let matches = self.search_matches();
+        let prev = matches
+            .iter()
+            .rev()
+            .find(|decoration| decoration.start() < position)
+            .or(matches.last())
+            .copied();
+        drop(matches);
+        match prev {
+            Some(prev) => {
+                self.select_match(prev, add_selection);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn select_all_matches(&mut self) -> bool {
+        let matches: Vec<_> = self.search_matches().to_vec();
+        for (index, &decoration) in matches.iter().enumerate() {
+            self.select_match(decoration, index > 0);
+        }
+        !matches.is_empty()
+    }
+
+    /// Replaces the match under the last added selection, if any, and selects the next match.
+    /// Returns whether a match was replaced.
+    pub fn replace(&mut self, replacement: &str) -> bool {
+        let selection = self.last_added_selection();
+        let current = self
+            .search_matches()
+            .iter()
+            .find(|decoration| {
+                decoration.start() == selection.start() && decoration.end() == selection.end()
+            })
+            .copied();
+        if let Some(current) = current {
+            let text = self.replacement_text(current, replacement);
+            self.document.replace(
+                self.id,
+                &self.selection_state.borrow().selections,
+                vec![(current.start(), current.length(), text)],
+            );
+            self.handle_changes();
+        }
+        self.find_next(false);
+        current.is_some()
+    }
+
+    /// Replaces all matches in a single undo group and returns how many were replaced.
+    pub fn replace_all(&mut self, replacement: &str) -> usize {
+        let replacements: Vec<_> = self
+            .search_matches()
+            .iter()
+            .map(|&decoration| {
+                (
+                    decoration.start(),
+                    decoration.length(),
+                    self.replacement_text(decoration, replacement),
+                )
+            })
+            .collect();
+        let count = replacements.len();
+        self.document.replace(
+            self.id,
+            &self.selection_state.borrow().selections,
+            replacements,
+        );
+        self.handle_changes();
+        count
+    }
+
     pub fn undo(&mut self) -> bool {
         self.selection_state.borrow_mut().injected_char_stack.clear();
         self.document
@@ -740,8 +1188,14 @@ impl Session {
     }
 
     pub fn handle_changes(&mut self) {
+        let mut is_edited = false;
         while let Ok((selections, edits)) = self.edit_receiver.try_recv() {
             self.update_after_edit(selections, &edits);
+            is_edited = true;
+        }
+        // the folds of the document are shared with its other sessions, which can change them
+        if is_edited || self.fold_revision != self.document.fold_revision() {
+            self.update_hidden_lines();
         }
     }
 
@@ -755,12 +1209,24 @@ impl Session {
             document_layout: self.document.layout(),
             session_layout: self.layout.borrow(),
         };
+        let lines = layout.as_text().as_lines();
         let mut selection_state = self.selection_state.borrow_mut();
         let last_added_selection_index = selection_state.last_added_selection_index;
         selection_state.last_added_selection_index = selection_state
             .selections
             .update_all_selections(last_added_selection_index, |selection| {
+                let prev_position = selection.cursor.position;
                 let mut selection = f(selection, &layout);
+                let position = selection.cursor.position;
+                if let Some(range) = self
+                    .hidden_line_ranges
+                    .iter()
+                    .find(|range| range.contains(&position.line_index))
+                {
+                    selection = selection.update_cursor(|cursor| {
+                        skip_hidden_lines(cursor, range, lines, position > prev_position)
+                    });
+                }
                 if reset_anchor {
                     selection = selection.reset_anchor();
                 }
@@ -773,6 +1239,112 @@ impl Session {
         self.document.force_new_group();
     }
 
+    // the lines touched by the selections, with adjacent ranges merged
+    fn selected_line_ranges(&self) -> Vec<Range<usize>> {
+        self.selection_state
+            .borrow()
+            .selections
+            .iter()
+            .map(|selection| selection.line_range())
+            .merge(|line_range_0, line_range_1| {
+                if line_range_0.end >= line_range_1.start {
+                    Ok(line_range_0.start..line_range_1.end)
+                } else {
+                    Err((line_range_0, line_range_1))
+                }
+            })
+            .collect()
+    }
+
+    // replaces whole lines and moves the selections to the line that `f` maps their line to
+    fn replace_lines(
+        &mut self,
+        replacements: Vec<(Position, Length, Text)>,
+        f: impl Fn(usize) -> usize,
+    ) {
+        let selections = self.selection_state.borrow().selections.clone();
+        self.document.replace(self.id, &selections, replacements);
+        self.handle_changes();
+        let mut selection_state = self.selection_state.borrow_mut();
+        selection_state.selections = selections;
+        let last_added_selection_index = selection_state.last_added_selection_index;
+        selection_state.last_added_selection_index = selection_state
+            .selections
+            .update_all_selections(last_added_selection_index, |selection| Selection {
+                anchor: Position {
+                    line_index: f(selection.anchor.line_index),
+                    ..selection.anchor
+                },
+                cursor: Cursor {
+                    position: Position {
+                        line_index: f(selection.cursor.position.line_index),
+                        ..selection.cursor.position
+                    },
+                    ..selection.cursor
+                },
+            });
+        selection_state.injected_char_stack.clear();
+        drop(selection_state);
+        self.update_highlighted_delimiter_positions();
+    }
+
+    fn last_added_selection(&self) -> Selection {
+        let selection_state = self.selection_state.borrow();
+        let index = selection_state
+            .last_added_selection_index
+            .unwrap_or(selection_state.selections.len() - 1);
+        selection_state.selections[index]
+    }
+
+    fn select_match(&mut self, decoration: Decoration, add_selection: bool) {
+        let selection = Selection {
+            cursor: Cursor {
+                position: decoration.end(),
+                affinity: Affinity::Before,
+                preferred_column_index: None,
+            },
+            anchor: decoration.start(),
+        };
+        let mut selection_state = self.selection_state.borrow_mut();
+        if add_selection {
+            selection_state.last_added_selection_index =
+                Some(selection_state.selections.add_selection(selection));
+        } else {
+            selection_state.selections.set_selection(selection);
+            selection_state.last_added_selection_index = Some(0);
+        }
+        selection_state.injected_char_stack.clear();
+        drop(selection_state);
+        self.update_highlighted_delimiter_positions();
+        self.document.force_new_group();
+    }
+
+    fn replacement_text(&self, decoration: Decoration, replacement: &str) -> Text {
+        let search = self.search.borrow();
+        let search = search.as_ref().unwrap();
+        let text = self.document.as_text();
+        let line = &text.as_lines()[decoration.start().line_index];
+        match search.regex.captures_at(line, decoration.start().byte_index) {
+            Some(captures) => search
+                .query
+                .expand_replacement(replacement, line, &captures)
+                .into(),
+            None => replacement.into(),
+        }
+    }
+
+    fn update_search_matches(&self) {
+        let mut search = self.search.borrow_mut();
+        if let Some(search) = &mut *search {
+            search.matches.clear();
+            for range in search::find_all(&search.regex, &self.document.as_text()) {
+                search
+                    .matches
+                    .add_decoration(Decoration::new(0, range.start, range.end));
+            }
+        }
+    }
+
     fn update_after_edit(&self, selections: Option<SelectionSet>, edits: &[Edit]) {
         for edit in edits {
             match edit.change {
@@ -843,6 +1415,7 @@ impl Session {
         }
         drop(selection_state);
         self.update_highlighted_delimiter_positions();
+        self.update_search_matches();
     }
 
     fn update_y(&self) {
@@ -913,6 +1486,73 @@ impl Session {
         self.update_column_count(line);
     }
 
+    // hides the lines of the folded ranges of the document, shows the lines of ranges that were
+    // unfolded, and moves the cursors that were in newly hidden lines to the line of their fold
+    fn update_hidden_lines(&mut self) {
+        self.fold_revision = self.document.fold_revision();
+        let fold_ranges = self.document.fold_ranges();
+        let mut hidden_line_ranges: Vec<Range<usize>> = Vec::new();
+        for &line_index in self.document.folded_lines().iter() {
+            let Ok(index) = fold_ranges.binary_search_by_key(&line_index, |range| range.start_line_index) else {
+                continue;
+            };
+            let range = fold_ranges[index].hidden_line_range();
+            match hidden_line_ranges.last_mut() {
+                // a fold inside another one, or one that overlaps it such as a region
+                Some(last_range) if range.start <= last_range.end => {
+                    last_range.end = last_range.end.max(range.end);
+                }
+                _ => hidden_line_ranges.push(range),
+            }
+        }
+        drop(fold_ranges);
+        if hidden_line_ranges.is_empty() && self.hidden_line_ranges.is_empty() {
+            return;
+        }
+        let mut layout = self.layout.borrow_mut();
+        let mut first_changed_line_index = None;
+        let mut ranges = hidden_line_ranges.iter().peekable();
+        for line_index in 0..layout.scale.len() {
+            while ranges.next_if(|range| range.end <= line_index).is_some() {}
+            let is_hidden = ranges.peek().map_or(false, |range| range.contains(&line_index));
+            let scale = &mut layout.scale[line_index];
+            if is_hidden == (*scale == 0.0) {
+                continue;
+            }
+            *scale = if is_hidden { 0.0 } else { 1.0 };
+            if is_hidden {
+                self.folding_lines.remove(&line_index);
+                self.folded_lines.remove(&line_index);
+                self.unfolding_lines.remove(&line_index);
+            }
+            first_changed_line_index.get_or_insert(line_index);
+        }
+        if let Some(line_index) = first_changed_line_index {
+            layout.y.truncate(line_index + 1);
+        }
+        drop(layout);
+        self.update_y();
+        let text = self.document.as_text();
+        let lines = text.as_lines();
+        let mut selection_state = self.selection_state.borrow_mut();
+        let last_added_selection_index = selection_state.last_added_selection_index;
+        selection_state.last_added_selection_index = selection_state
+            .selections
+            .update_all_selections(last_added_selection_index, |selection| {
+                match hidden_line_ranges
+                    .iter()
+                    .find(|range| range.contains(&selection.cursor.position.line_index))
+                {
+                    Some(range) => selection.update_cursor(|cursor| skip_hidden_lines(cursor, range, lines, false)),
+                    None => selection,
+                }
+            });
+        drop(selection_state);
+        drop(text);
+        self.hidden_line_ranges = hidden_line_ranges;
+        self.update_highlighted_delimiter_positions();
+    }
+
     fn update_highlighted_delimiter_positions(&self) {
         let mut selection_state = self.selection_state.borrow_mut();
         let mut highlighted_delimiter_positions =
@@ -962,6 +1602,31 @@ struct SelectionState {
     highlighted_delimiter_positions: HashSet<Position>,
 }
 
+#[derive(Debug)]
+struct SearchState {
+    query: SearchQuery,
+    regex: Regex,
+    matches: DecorationSet,
+}
+
+// a replacement of the lines in `line_range` by `new_lines`, which leaves the newline after the
+// last line alone
+fn replace_lines(
+    lines: &[String],
+    line_range: Range<usize>,
+    new_lines: &[&str],
+) -> (Position, Length, Text) {
+    let start = Position {
+        line_index: line_range.start,
+        byte_index: 0,
+    };
+    let end = Position {
+        line_index: line_range.end - 1,
+        byte_index: lines[line_range.end - 1].len(),
+    };
+    (start, end - start, Text::from(new_lines.join("\n")))
+}
+
 pub fn reindent(string: &str, f: impl FnOnce(usize) -> usize) -> (usize, usize, String) {
     let indentation = string.indent().unwrap_or("");
     let indentation_column_count = indentation.column_count();
@@ -975,6 +1640,28 @@ pub fn reindent(string: &str, f: impl FnOnce(usize) -> usize) -> (usize, usize,
     )
 }
 
+// moves a cursor out of the hidden lines of a fold, past them if it was moving down, or to the
+// end of the line of the fold otherwise
+fn skip_hidden_lines(cursor: Cursor, hidden_line_range: &Range<usize>, lines: &[String], is_moving_down: bool) -> Cursor {
+    let position = if is_moving_down && hidden_line_range.end < lines.len() {
+        Position {
+            line_index: hidden_line_range.end,
+            byte_index: 0,
+        }
+    } else {
+        let line_index = hidden_line_range.start - 1;
+        Position {
+            line_index,
+            byte_index: lines[line_index].len(),
+        }
+    };
+    Cursor {
+        position,
+        affinity: Affinity::Before,
+        ..cursor
+    }
+}
+
 fn new_indentation(column_count: usize) -> String {
     iter::repeat(' ').take(column_count).collect()
 }
@@ -0,0 +1,215 @@
+use {
+    crate::{
+        str::StrExt,
+        token::{Token, TokenKind},
+    },
+    std::ops::Range,
+};
+
+/// Returns the indices of the lines that start the items enclosing the line at `line_index`,
+/// outermost first, such as the `impl` and `fn` lines above a line in the body of a method.
+///
+/// The items are found from the indentation: each of them is indented less than all the lines
+/// between it and `line_index`. A blank line belongs to the next line that isn't blank.
+pub fn enclosing_headers(lines: &[String], line_index: usize) -> Vec<usize> {
+    let Some(indent) = lines[line_index..].iter().find_map(|line| line.indent()) else {
+        return Vec::new();
+    };
+    let mut column_count = indent.column_count();
+    let mut headers = Vec::new();
+    for index in (0..line_index).rev() {
+        if column_count == 0 {
+            break;
+        }
+        let Some(indent) = lines[index].indent() else {
+            continue;
+        };
+        let indent_column_count = indent.column_count();
+        if indent_column_count < column_count {
+            column_count = indent_column_count;
+            if is_header(&lines[index]) {
+                headers.push(index);
+            }
+        }
+    }
+    headers.reverse();
+    headers
+}
+
+/// Whether a line starts a `fn`, `impl`, `mod` or `trait` item, possibly after qualifiers such as
+/// `pub(crate)` or `async`.
+pub fn is_header(line: &str) -> bool {
+    let mut string = line.trim_start();
+    loop {
+        let (word, rest) = string.split_at(
+            string
+                .find(|char: char| !(char.is_alphanumeric() || char == '_'))
+                .unwrap_or(string.len()),
+        );
+        let mut rest = rest.trim_start();
+        match word {
+            "fn" | "impl" | "mod" | "trait" => return true,
+            "pub" if rest.starts_with('(') => match rest.find(')') {
+                Some(index) => rest = &rest[index + 1..],
+                None => return false,
+            },
+            "extern" if rest.starts_with('"') => match rest[1..].find('"') {
+                Some(index) => rest = &rest[index + 2..],
+                None => return false,
+            },
+            "pub" | "async" | "const" | "default" | "extern" | "unsafe" => {}
+            _ => return false,
+        }
+        string = rest.trim_start();
+    }
+}
+
+/// What a `FoldRange` was found from.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FoldKind {
+    /// A pair of brackets that open and close on different lines.
+    Block,
+    /// The brackets of a `live_design!` block.
+    Design,
+    /// Consecutive lines that only hold comments.
+    Comment,
+    /// The lines between a `// region` and an `// endregion` comment.
+    Region,
+}
+
+/// A range of lines that can be folded away behind the line it starts at.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct FoldRange {
+    pub kind: FoldKind,
+    pub start_line_index: usize,
+    /// The first line after the folded lines, which stays visible so that the closing bracket of a
+    /// block or the end of a region can still be seen.
+    pub end_line_index: usize,
+}
+
+impl FoldRange {
+    /// The lines that are hidden while the range is folded.
+    pub fn hidden_line_range(self) -> Range<usize> {
+        self.start_line_index + 1..self.end_line_index
+    }
+}
+
+/// Returns the ranges of lines that can be folded, ordered by the line they start at. Of the
+/// ranges that start at the same line, only the longest is kept.
+///
+/// Blocks are found from the delimiter tokens of each line, so brackets in strings and comments
+/// are skipped. Regions are started and ended by comments such as `// region: Name` and
+/// `// endregion`, or `# region` in languages with `#` comments, and can be nested.
+pub fn fold_ranges(lines: &[String], tokens: &[Vec<Token>]) -> Vec<FoldRange> {
+    let mut ranges = Vec::new();
+    let mut open_delimiters = Vec::new();
+    let mut region_starts = Vec::new();
+    let mut comment_start = None;
+    for (line_index, (line, tokens)) in lines.iter().zip(tokens).enumerate() {
+        let is_comment = is_comment_line(tokens);
+        let region_marker = if is_comment {
+            region_marker(line)
+        } else {
+            None
+        };
+        if !is_comment || region_marker.is_some() {
+            if let Some(start_line_index) = comment_start.take() {
+                push_comment_range(&mut ranges, start_line_index, line_index);
+            }
+        }
+        match region_marker {
+            Some(RegionMarker::Start) => region_starts.push(line_index),
+            Some(RegionMarker::End) => {
+                if let Some(start_line_index) = region_starts.pop() {
+                    ranges.push(FoldRange {
+                        kind: FoldKind::Region,
+                        start_line_index,
+                        end_line_index: line_index,
+                    });
+                }
+            }
+            None if is_comment => {
+                comment_start.get_or_insert(line_index);
+            }
+            None => {}
+        }
+        let mut byte_index = 0;
+        for token in tokens {
+            if token.kind == TokenKind::Delimiter {
+                for char in line[byte_index..byte_index + token.len].chars() {
+                    match char {
+                        '(' | '[' | '{' => open_delimiters.push((char, line_index)),
+                        ')' | ']' | '}' => {
+                            // a stray closing delimiter is skipped, rather than closing a block
+                            // that it doesn't match
+                            if open_delimiters.last().map_or(false, |&(open, _)| matches_delimiter(open, char)) {
+                                let (_, start_line_index) = open_delimiters.pop().unwrap();
+                                if line_index > start_line_index + 1 {
+                                    ranges.push(FoldRange {
+                                        kind: if lines[start_line_index].contains("live_design!") {
+                                            FoldKind::Design
+                                        } else {
+                                            FoldKind::Block
+                                        },
+                                        start_line_index,
+                                        end_line_index: line_index,
+                                    });
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            byte_index += token.len;
+        }
+    }
+    if let Some(start_line_index) = comment_start {
+        push_comment_range(&mut ranges, start_line_index, lines.len());
+    }
+    ranges.sort_by_key(|range| (range.start_line_index, usize::MAX - range.end_line_index));
+    ranges.dedup_by_key(|range| range.start_line_index);
+    ranges
+}
+
+fn push_comment_range(ranges: &mut Vec<FoldRange>, start_line_index: usize, end_line_index: usize) {
+    if end_line_index > start_line_index + 1 {
+        ranges.push(FoldRange {
+            kind: FoldKind::Comment,
+            start_line_index,
+            end_line_index,
+        });
+    }
+}
+
+fn is_comment_line(tokens: &[Token]) -> bool {
+    tokens.iter().any(|token| token.kind == TokenKind::Comment)
+        && tokens
+            .iter()
+            .all(|token| matches!(token.kind, TokenKind::Comment | TokenKind::Whitespace))
+}
+
+fn matches_delimiter(open: char, close: char) -> bool {
+    matches!((open, close), ('(', ')') | ('[', ']') | ('{', '}'))
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RegionMarker {
+    Start,
+    End,
+}
+
+fn region_marker(line: &str) -> Option<RegionMarker> {
+    let string = line.trim_start_matches(|char: char| char.is_whitespace() || "/*#-!".contains(char));
+    let (marker, rest) = if let Some(rest) = string.strip_prefix("endregion") {
+        (RegionMarker::End, rest)
+    } else if let Some(rest) = string.strip_prefix("region") {
+        (RegionMarker::Start, rest)
+    } else {
+        return None;
+    };
+    if rest.starts_with(|char: char| char.is_alphanumeric() || char == '_') {
+        return None;
+    }
+    Some(marker)
+}
@@ -0,0 +1,111 @@
+use {
+    crate::{str::StrExt, text::Position},
+    makepad_widgets::*,
+};
+
+live_design!{
+    import makepad_widgets::theme_desktop_dark::*;
+
+    Tooltip = {{Tooltip}} {
+        padding: 6.0,
+        draw_bg: {
+            draw_depth: 3.0,
+            color: #30
+        }
+        draw_text: {
+            draw_depth: 3.1,
+            text_style: <THEME_FONT_CODE> {},
+            color: #D4D4D4
+        }
+    }
+}
+
+/// A box of text next to a position in the `CodeEditor`, such as the hover information that a
+/// language server has for the word under the mouse. Code fences are left out of the text, and
+/// lines are wrapped at `max_column_count`.
+#[derive(Live, LiveHook)]
+pub struct Tooltip {
+    #[live]
+    padding: f64,
+    #[live(80usize)]
+    max_column_count: usize,
+    #[live(20usize)]
+    max_line_count: usize,
+    #[live]
+    draw_bg: DrawColor,
+    #[live]
+    draw_text: DrawText,
+    #[rust]
+    position: Option<Position>,
+    #[rust]
+    lines: Vec<String>,
+    #[rust]
+    rect: Rect,
+}
+
+impl Tooltip {
+    pub fn position(&self) -> Option<Position> {
+        self.position
+    }
+
+    pub fn contains(&self, abs: DVec2) -> bool {
+        self.position.is_some() && self.rect.contains(abs)
+    }
+
+    pub fn show(&mut self, position: Position, text: &str) {
+        self.lines.clear();
+        for line in text.lines().filter(|line| !line.trim_start().starts_with("```")) {
+            let mut chars = line.trim_end().chars().peekable();
+            loop {
+                let mut row = String::new();
+                while row.column_count() < self.max_column_count {
+                    match chars.next() {
+                        Some(char) => row.push(char),
+                        None => break,
+                    }
+                }
+                self.lines.push(row);
+                if chars.peek().is_none() {
+                    break;
+                }
+            }
+        }
+        while self.lines.last().map_or(false, |line| line.is_empty()) {
+            self.lines.pop();
+        }
+        if self.lines.len() > self.max_line_count {
+            self.lines.truncate(self.max_line_count);
+            self.lines.push("…".to_string());
+        }
+        self.position = if self.lines.is_empty() { None } else { Some(position) };
+    }
+
+    pub fn hide(&mut self) {
+        self.position = None;
+    }
+
+    /// Draws the tooltip above the cell at `anchor`, or below it if there is no room above,
+    /// keeping it inside `bounds`.
+    pub fn draw(&mut self, cx: &mut Cx2d, anchor: DVec2, cell_size: DVec2, font_size: f64, bounds: Rect) {
+        if self.position.is_none() {
+            return;
+        }
+        let column_count = self.lines.iter().map(|line| line.column_count()).max().unwrap_or(0);
+        let size = dvec2(
+            column_count as f64 * cell_size.x,
+            self.lines.len() as f64 * cell_size.y,
+        ) + dvec2(2.0, 2.0) * self.padding;
+        let mut pos = dvec2(anchor.x, anchor.y - size.y);
+        if pos.y < bounds.pos.y {
+            pos.y = anchor.y + cell_size.y;
+        }
+        pos.x = pos.x.min(bounds.pos.x + bounds.size.x - size.x).max(bounds.pos.x);
+        self.rect = Rect {pos, size};
+        self.draw_bg.draw_abs(cx, self.rect);
+        self.draw_text.text_style.font_size = font_size;
+        for (index, line) in self.lines.iter().enumerate() {
+            let pos = pos + dvec2(self.padding, self.padding + index as f64 * cell_size.y);
+            self.draw_text.draw_abs(cx, pos, line);
+        }
+    }
+}
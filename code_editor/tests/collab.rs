@@ -0,0 +1,246 @@
+mod common;
+
+use {
+    common::position,
+    makepad_code_editor::{
+        collab::{transform, transform_position, SharedText, SyncState},
+        text::{Change, Drift, Length, Position, Text},
+    },
+    std::collections::VecDeque,
+};
+
+fn length(line_count: usize, byte_count: usize) -> Length {
+    Length {
+        line_count,
+        byte_count,
+    }
+}
+
+fn apply(text: &str, changes: &[Change]) -> String {
+    let mut text = Text::from(text);
+    for change in changes {
+        text.apply_change(change.clone());
+    }
+    text.to_string()
+}
+
+// checks that both orders give the same text, and returns it
+fn converge(text: &str, a: &[Change], b: &[Change]) -> String {
+    let (a_after_b, b_after_a) = transform(a, b);
+    let a_then_b = apply(&apply(text, a), &b_after_a);
+    let b_then_a = apply(&apply(text, b), &a_after_b);
+    assert_eq!(a_then_b, b_then_a);
+    a_then_b
+}
+
+#[test]
+fn transforms_inserts() {
+    let a = [Change::Insert(position(0, 1), Text::from("x"))];
+    let b = [Change::Insert(position(0, 1), Text::from("y"))];
+    // the inserts of the first list go first
+    assert_eq!(converge("ab", &a, &b), "axyb");
+    assert_eq!(converge("ab", &b, &a), "ayxb");
+
+    let a = [Change::Insert(position(0, 0), Text::from("x\ny"))];
+    let b = [Change::Insert(position(0, 2), Text::from("z"))];
+    assert_eq!(converge("ab", &a, &b), "x\nyabz");
+}
+
+#[test]
+fn keeps_inserts_inside_deletes() {
+    let a = [Change::Insert(position(0, 2), Text::from("x"))];
+    let b = [Change::Delete(position(0, 1), length(0, 3))];
+    assert_eq!(converge("abcde", &a, &b), "axe");
+    assert_eq!(converge("abcde", &b, &a), "axe");
+
+    let a = [Change::Insert(position(1, 1), Text::from("x\ny"))];
+    let b = [Change::Delete(position(0, 1), length(2, 1))];
+    assert_eq!(converge("ab\ncd\nef", &a, &b), "ax\nyf");
+}
+
+#[test]
+fn transforms_overlapping_deletes() {
+    let a = [Change::Delete(position(0, 1), length(0, 3))];
+    let b = [Change::Delete(position(0, 2), length(0, 3))];
+    assert_eq!(converge("abcdefg", &a, &b), "afg");
+
+    let a = [Change::Delete(position(0, 1), length(2, 0))];
+    let b = [Change::Delete(position(1, 0), length(0, 2))];
+    assert_eq!(converge("ab\ncd\nef", &a, &b), "aef");
+
+    let a = [Change::Delete(position(0, 0), length(0, 2))];
+    assert_eq!(transform(&a, &a), (vec![], vec![]));
+}
+
+#[test]
+fn transforms_lists_of_changes() {
+    let a = [
+        Change::Insert(position(0, 0), Text::from("x")),
+        Change::Delete(position(0, 2), length(0, 2)),
+    ];
+    let b = [
+        Change::Delete(position(0, 0), length(0, 1)),
+        Change::Insert(position(0, 3), Text::from("y\nz")),
+    ];
+    assert_eq!(converge("abcde", &a, &b), "xdy\nze");
+}
+
+#[test]
+fn transforms_positions() {
+    let changes = [Change::Insert(position(0, 1), Text::from("x\ny"))];
+    assert_eq!(transform_position(position(0, 1), &changes, Drift::Before), position(1, 1));
+    assert_eq!(transform_position(position(0, 1), &changes, Drift::After), position(0, 1));
+    assert_eq!(transform_position(position(0, 2), &changes, Drift::After), position(1, 2));
+
+    let changes = [Change::Delete(position(0, 1), length(1, 1))];
+    assert_eq!(transform_position(position(1, 0), &changes, Drift::Before), position(0, 1));
+    assert_eq!(transform_position(position(1, 3), &changes, Drift::Before), position(0, 3));
+}
+
+#[test]
+fn rejects_changes_that_dont_fit() {
+    let mut shared_text = SharedText::new(Text::from("ab"));
+    assert_eq!(shared_text.apply_changes(1, vec![]), None);
+    assert_eq!(
+        shared_text.apply_changes(0, vec![Change::Delete(position(0, 1), length(0, 5))]),
+        None
+    );
+    assert_eq!(shared_text.revision(), 0);
+
+    let insert = vec![Change::Insert(position(0, 0), Text::from("x"))];
+    assert_eq!(shared_text.apply_changes(0, insert.clone()), Some(insert));
+    // a change that was made to the first revision is moved past the insert
+    assert_eq!(
+        shared_text.apply_changes(0, vec![Change::Delete(position(0, 1), length(0, 1))]),
+        Some(vec![Change::Delete(position(0, 2), length(0, 1))])
+    );
+    assert_eq!(shared_text.as_text().to_string(), "xa");
+    assert_eq!(shared_text.transform_position(0, position(0, 2)), Some(position(0, 2)));
+    assert_eq!(shared_text.transform_position(2, position(0, 3)), None);
+}
+
+#[test]
+fn trims_history() {
+    let mut shared_text = SharedText::new(Text::from("ab"));
+    for _ in 0..3 {
+        let insert = vec![Change::Insert(position(0, 0), Text::from("x"))];
+        shared_text.apply_changes(shared_text.revision(), insert);
+    }
+    shared_text.trim_history(2);
+    assert_eq!(shared_text.revision(), 3);
+    // the changes before revision 2 are gone
+    assert_eq!(shared_text.transform_position(1, position(0, 0)), None);
+    assert_eq!(shared_text.transform_position(2, position(0, 0)), Some(position(0, 1)));
+    assert_eq!(shared_text.apply_changes(1, vec![]), None);
+    assert_eq!(
+        shared_text.apply_changes(2, vec![Change::Delete(position(0, 2), length(0, 1))]),
+        Some(vec![Change::Delete(position(0, 3), length(0, 1))])
+    );
+    assert_eq!(shared_text.as_text().to_string(), "xxxb");
+    // trimming past the latest revision keeps nothing, and doesn't change the revision
+    shared_text.trim_history(10);
+    assert_eq!(shared_text.revision(), 4);
+    assert_eq!(shared_text.transform_position(4, position(0, 4)), Some(position(0, 4)));
+}
+
+// a small generator, so that failures can be reproduced
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, bound: usize) -> usize {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        ((self.0 >> 33) as usize) % bound
+    }
+
+    fn position(&mut self, text: &Text) -> Position {
+        let line_index = self.next(text.as_lines().len());
+        let byte_index = self.next(text.as_lines()[line_index].len() + 1);
+        position(line_index, byte_index)
+    }
+
+    fn change(&mut self, text: &Text) -> Change {
+        let start = self.position(text);
+        if self.next(2) == 0 {
+            let string: String = (0..1 + self.next(3)).map(|_| ['a', 'b', '\n'][self.next(3)]).collect();
+            Change::Insert(start, Text::from(string))
+        } else {
+            let end = self.position(text).max(start);
+            Change::Delete(start, end - start)
+        }
+    }
+}
+
+struct Peer {
+    text: Text,
+    sync_state: SyncState,
+    // the changes that are on their way to the relay, along with their revision
+    outbox: VecDeque<(usize, Vec<Change>)>,
+    // the changes that are on their way from the relay, along with the id of their author
+    inbox: VecDeque<(usize, Vec<Change>)>,
+}
+
+#[test]
+fn converges_with_many_peers() {
+    for seed in 0..200 {
+        let mut rng = Rng(seed);
+        let text = Text::from("abc\ndef\nghi");
+        let mut shared_text = SharedText::new(text.clone());
+        let mut peers: Vec<_> = (0..3)
+            .map(|_| Peer {
+                text: text.clone(),
+                sync_state: SyncState::new(0),
+                outbox: VecDeque::new(),
+                inbox: VecDeque::new(),
+            })
+            .collect();
+        for step in 0.. {
+            let is_done = step >= 100;
+            let peer_id = rng.next(peers.len());
+            match rng.next(3) {
+                0 if !is_done => {
+                    let peer = &mut peers[peer_id];
+                    let changes: Vec<_> = (0..1 + rng.next(2))
+                        .map(|_| {
+                            let change = rng.change(&peer.text);
+                            peer.text.apply_change(change.clone());
+                            change
+                        })
+                        .collect();
+                    if let Some(message) = peer.sync_state.apply_local_changes(changes) {
+                        peer.outbox.push_back(message);
+                    }
+                }
+                1 => {
+                    if let Some((revision, changes)) = peers[peer_id].outbox.pop_front() {
+                        let changes = shared_text.apply_changes(revision, changes).unwrap();
+                        for peer in &mut peers {
+                            peer.inbox.push_back((peer_id, changes.clone()));
+                        }
+                    }
+                }
+                _ => {
+                    let peer = &mut peers[peer_id];
+                    if let Some((author_id, changes)) = peer.inbox.pop_front() {
+                        if author_id == peer_id {
+                            if let Some(message) = peer.sync_state.confirm_changes() {
+                                peer.outbox.push_back(message);
+                            }
+                        } else {
+                            for change in peer.sync_state.apply_remote_changes(changes) {
+                                peer.text.apply_change(change);
+                            }
+                        }
+                    }
+                }
+            }
+            if is_done && peers.iter().all(|peer| peer.outbox.is_empty() && peer.inbox.is_empty()) {
+                break;
+            }
+        }
+        for peer in &peers {
+            assert!(peer.sync_state.is_synced());
+            assert_eq!(peer.sync_state.revision(), shared_text.revision());
+            assert_eq!(peer.text, *shared_text.as_text(), "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+// fixtures shared by the test files, each of them only uses some
+#![allow(dead_code)]
+
+use makepad_code_editor::{decoration::DecorationSet, text::Position, Document, Session};
+
+pub fn document(text: &str) -> Document {
+    Document::new(text.into(), DecorationSet::new())
+}
+
+pub fn session(text: &str) -> Session {
+    Session::new(document(text))
+}
+
+pub fn position(line_index: usize, byte_index: usize) -> Position {
+    Position {
+        line_index,
+        byte_index,
+    }
+}
+
+pub fn text(session: &Session) -> String {
+    session.document().as_text().to_string()
+}
+
+pub fn cursor(session: &Session) -> Position {
+    session.selections()[0].cursor.position
+}
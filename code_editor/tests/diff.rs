@@ -0,0 +1,67 @@
+use makepad_code_editor::{
+    decoration::DecorationSet,
+    diff::{self, LineChange, LineChangeKind},
+    Document,
+};
+
+fn lines(text: &str) -> Vec<String> {
+    text.split('\n').map(|line| line.to_string()).collect()
+}
+
+fn line_change(old_line_range: std::ops::Range<usize>, new_line_range: std::ops::Range<usize>) -> LineChange {
+    LineChange {
+        old_line_range,
+        new_line_range,
+    }
+}
+
+#[test]
+fn finds_added_modified_and_deleted_lines() {
+    let old = lines("a\nb\nc\nd\ne\nf");
+    let new = lines("a\nx\nc\nd\ny\nz\nf");
+    let changes = diff::diff_lines(&old, &new);
+    assert_eq!(changes, vec![line_change(1..2, 1..2), line_change(4..5, 4..6)]);
+    assert!(changes.iter().all(|change| change.kind() == LineChangeKind::Modified));
+
+    let changes = diff::diff_lines(&lines("a\nb\nc"), &lines("a\nc\nd"));
+    assert_eq!(changes, vec![line_change(1..2, 1..1), line_change(3..3, 2..3)]);
+    assert_eq!(changes[0].kind(), LineChangeKind::Deleted);
+    assert_eq!(changes[1].kind(), LineChangeKind::Added);
+
+    assert_eq!(diff::diff_lines(&old, &old), vec![]);
+    assert_eq!(diff::diff_lines(&lines("a"), &lines("b\na\nb")), vec![line_change(0..0, 0..1), line_change(1..1, 2..3)]);
+}
+
+#[test]
+fn finds_a_shortest_edit_script() {
+    // the classic example from Myers' paper, which takes five edits
+    let old = lines("a\nb\nc\na\nb\nb\na");
+    let new = lines("c\nb\na\nb\na\nc");
+    let changes = diff::diff_lines(&old, &new);
+    let edit_count: usize = changes
+        .iter()
+        .map(|change| change.old_line_range.len() + change.new_line_range.len())
+        .sum();
+    assert_eq!(edit_count, 5);
+}
+
+#[test]
+fn maps_lines_between_texts() {
+    let changes = diff::diff_lines(&lines("a\nb\nc\nd"), &lines("a\nx\ny\nc\nd\ne"));
+    assert_eq!(diff::map_new_line_index(&changes, 0), Ok(0));
+    assert_eq!(diff::map_new_line_index(&changes, 2), Err(1));
+    assert_eq!(diff::map_new_line_index(&changes, 3), Ok(2));
+    assert_eq!(diff::map_new_line_index(&changes, 5), Err(4));
+    assert_eq!(diff::map_old_line_index(&changes, 1), Err(1));
+    assert_eq!(diff::map_old_line_index(&changes, 3), Ok(4));
+}
+
+#[test]
+fn compares_a_document_with_its_diff_base() {
+    let mut document = Document::new("a\nb".into(), DecorationSet::new());
+    assert!(document.line_changes().is_empty());
+    document.set_diff_base(Some("a\nc\nb".into()));
+    assert_eq!(document.line_changes().to_vec(), vec![line_change(1..2, 1..1)]);
+    document.set_diff_base(None);
+    assert!(document.line_changes().is_empty());
+}
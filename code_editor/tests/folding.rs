@@ -0,0 +1,120 @@
+mod common;
+
+use {
+    common::{cursor, document, position},
+    makepad_code_editor::{
+        selection::Affinity,
+        structure::{FoldKind, FoldRange},
+        Session,
+    },
+};
+
+fn fold_range(kind: FoldKind, start_line_index: usize, end_line_index: usize) -> FoldRange {
+    FoldRange {
+        kind,
+        start_line_index,
+        end_line_index,
+    }
+}
+
+fn fold_ranges(text: &str) -> Vec<FoldRange> {
+    document(text).fold_ranges().to_vec()
+}
+
+#[test]
+fn finds_blocks_from_delimiters() {
+    let text = "fn f() {\n\
+                \x20   let s = \"{\";\n\
+                \x20   g(\n\
+                \x20       1,\n\
+                \x20   );\n\
+                \x20   if x { y }\n\
+                }";
+    assert_eq!(
+        fold_ranges(text),
+        vec![fold_range(FoldKind::Block, 0, 6), fold_range(FoldKind::Block, 2, 4)]
+    );
+    // only the longest of the ranges that start at the same line is kept, and a block that closes
+    // on the next line has nothing to fold
+    assert_eq!(
+        fold_ranges("f({\n1\n}, [\n2\n])"),
+        vec![fold_range(FoldKind::Block, 0, 4), fold_range(FoldKind::Block, 2, 4)]
+    );
+    assert_eq!(fold_ranges("if x {\n}"), vec![]);
+}
+
+#[test]
+fn finds_design_blocks_comments_and_regions() {
+    let text = "// one\n\
+                // two\n\
+                live_design! {\n\
+                \x20   A = {}\n\
+                }\n\
+                // region: Tests\n\
+                /* a\n\
+                \x20  b */\n\
+                // region\n\
+                x\n\
+                // endregion\n\
+                // endregion\n\
+                // regional";
+    assert_eq!(
+        fold_ranges(text),
+        vec![
+            fold_range(FoldKind::Comment, 0, 2),
+            fold_range(FoldKind::Design, 2, 4),
+            fold_range(FoldKind::Region, 5, 11),
+            fold_range(FoldKind::Comment, 6, 8),
+            fold_range(FoldKind::Region, 8, 10),
+        ]
+    );
+}
+
+#[test]
+fn hides_folded_lines() {
+    let mut session = Session::new(document("fn f() {\n    1\n    2\n}\nx"));
+    session.toggle_fold(0);
+    assert!(session.is_folded(0));
+    assert!(session.is_line_hidden(1) && session.is_line_hidden(2));
+    assert!(!session.is_line_hidden(3));
+    assert_eq!(session.layout().line(3).y(), session.layout().line(1).y());
+
+    // the cursor moves past the folded lines
+    session.set_selection(position(0, 2), Affinity::Before, 1);
+    session.move_down(true);
+    assert_eq!(cursor(&session), position(3, 0));
+    session.move_up(true);
+    assert_eq!(cursor(&session), position(0, 8));
+
+    // folding from inside a range folds the innermost one, and moves the cursor out of it
+    session.unfold_all();
+    session.set_selection(position(2, 1), Affinity::Before, 1);
+    session.toggle_fold(2);
+    assert!(session.is_folded(0));
+    assert_eq!(cursor(&session), position(0, 8));
+}
+
+#[test]
+fn keeps_folds_with_the_document() {
+    let mut session = Session::new(document("a\nfn f() {\n    1\n    2\n}"));
+    let mut other_session = Session::new(session.document().clone());
+    session.fold_all(Some(FoldKind::Block));
+    other_session.handle_changes();
+    assert!(other_session.is_line_hidden(2));
+
+    // the fold moves with the lines that are inserted above it
+    session.set_selection(position(0, 1), Affinity::Before, 1);
+    session.enter();
+    session.handle_changes();
+    other_session.handle_changes();
+    assert!(session.is_folded(2) && !session.is_folded(1));
+    assert!(other_session.is_line_hidden(3) && other_session.is_line_hidden(4));
+
+    // and is dropped when its range is deleted
+    session.set_selection(position(2, 0), Affinity::Before, 1);
+    session.select_line();
+    session.delete();
+    session.handle_changes();
+    assert!(session.document().folded_lines().is_empty());
+    assert!(!session.is_line_hidden(3));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use {
+    common::{position, session, text},
+    makepad_code_editor::{
+        history::HistoryStacks,
+        makepad_widgets::makepad_micro_serde::{DeBin, SerBin},
+        selection::Affinity,
+        Selection,
+    },
+};
+
+#[test]
+fn restores_history_in_another_session() {
+    let mut session = session("fn main() {\n}");
+    session.set_selection(position(0, 11), Affinity::Before, 1);
+    session.enter();
+    session.handle_changes();
+    let entered_text = text(&session);
+    session.insert("x".into());
+    session.handle_changes();
+    let inserted_text = text(&session);
+    session.undo();
+    session.handle_changes();
+    let stacks = session.document().history_stacks();
+    let selections: Vec<Selection> = session.selections().to_vec();
+    let stacks = HistoryStacks::deserialize_bin(&stacks.serialize_bin()).unwrap();
+    let selections = Vec::<Selection>::deserialize_bin(&selections.serialize_bin()).unwrap();
+
+    // the history was taken after the text was saved, so it is restored to the same text
+    let mut restored_session = self::session(&text(&session));
+    let mut document = restored_session.document().clone();
+    document.restore_history(session.document().as_text().clone(), stacks);
+    restored_session.set_selections(&selections);
+    assert_eq!(restored_session.selections()[0], session.selections()[0]);
+
+    assert!(restored_session.redo());
+    assert_eq!(text(&restored_session), inserted_text);
+    assert!(restored_session.undo());
+    assert_eq!(text(&restored_session), entered_text);
+    assert!(restored_session.undo());
+    assert_eq!(text(&restored_session), "fn main() {\n}");
+    assert!(!restored_session.undo());
+}
+
+#[test]
+fn restores_unsaved_text() {
+    let mut session = session("a\nb");
+    session.set_selection(position(1, 1), Affinity::Before, 1);
+    session.insert("c".into());
+    session.handle_changes();
+    let stacks = session.document().history_stacks();
+    let selections = session.selections().to_vec();
+
+    // the text on disk is the one from before the edit
+    let mut restored_session = self::session("a\nb");
+    let mut document = restored_session.document().clone();
+    document.restore_history(session.document().as_text().clone(), stacks);
+    restored_session.handle_changes();
+    restored_session.set_selections(&selections);
+    assert_eq!(text(&restored_session), "a\nbc");
+    assert_eq!(restored_session.selections()[0].cursor.position, position(1, 2));
+
+    assert!(restored_session.undo());
+    assert_eq!(text(&restored_session), "a\nb");
+}
+
+#[test]
+fn drops_selections_that_dont_fit() {
+    let mut session = session("ab\ncd");
+    let selection = |line_index, byte_index| Selection::from(makepad_code_editor::selection::Cursor {
+        position: position(line_index, byte_index),
+        affinity: Affinity::Before,
+        preferred_column_index: None,
+    });
+    session.set_selections(&[selection(0, 1), selection(1, 5), selection(4, 0)]);
+    assert_eq!(session.selections().len(), 1);
+    assert_eq!(session.selections()[0].cursor.position, position(0, 1));
+
+    session.set_selections(&[selection(7, 0)]);
+    assert_eq!(session.selections()[0].cursor.position, position(0, 1));
+}
@@ -0,0 +1,202 @@
+use makepad_code_editor::{
+    keymap::{parse_table, Command, KeyResult, KeyStroke, Keymap, KeymapError, KeymapState, Mode, Os, Preset},
+    makepad_widgets::{KeyCode, KeyModifiers},
+};
+
+fn stroke(string: &str) -> KeyStroke {
+    KeyStroke::parse(string, Os::Linux).unwrap()
+}
+
+fn press(keymap: &Keymap, state: &mut KeymapState, string: &str) -> KeyResult {
+    let stroke = stroke(string);
+    keymap.handle_key(state, stroke.key_code, stroke.modifiers)
+}
+
+#[test]
+fn parses_strokes() {
+    let ctrl_shift_k = KeyStroke::parse("Ctrl+Shift+K", Os::Linux).unwrap();
+    assert_eq!(ctrl_shift_k.key_code, KeyCode::KeyK);
+    assert!(ctrl_shift_k.modifiers.control && ctrl_shift_k.modifiers.shift);
+    assert_eq!(ctrl_shift_k.to_string(), "ctrl+shift+k");
+    assert_eq!(KeyStroke::parse("alt+.", Os::Linux).unwrap().to_string(), "alt+period");
+
+    // `mod` is command on macOS and control elsewhere
+    assert!(KeyStroke::parse("mod+d", Os::MacOs).unwrap().modifiers.logo);
+    assert!(KeyStroke::parse("mod+d", Os::Windows).unwrap().modifiers.control);
+
+    assert_eq!(
+        KeyStroke::parse_sequence("ctrl+x  u", Os::Linux).unwrap(),
+        vec![stroke("ctrl+x"), stroke("u")]
+    );
+    for string in ["", "ctrl+", "hyper+a", "ctrl+nothing"] {
+        assert_eq!(
+            KeyStroke::parse_sequence(string, Os::Linux),
+            Err(KeymapError::InvalidKeys(string.to_string())),
+        );
+    }
+}
+
+#[test]
+fn default_bindings_depend_on_the_os() {
+    let command = |os, string| {
+        Keymap::preset(Preset::Default, os)
+            .command(Mode::Insert, &[KeyStroke::parse(string, os).unwrap()])
+    };
+    assert_eq!(command(Os::MacOs, "cmd+left"), Some(Command::MoveToStartOfLine));
+    assert_eq!(command(Os::MacOs, "alt+left"), Some(Command::MoveWordLeft));
+    assert_eq!(command(Os::Linux, "ctrl+left"), Some(Command::MoveWordLeft));
+    assert_eq!(command(Os::MacOs, "cmd+z"), Some(Command::Undo));
+    assert_eq!(command(Os::Linux, "ctrl+z"), Some(Command::Undo));
+    assert_eq!(command(Os::Windows, "ctrl+y"), Some(Command::Redo));
+    assert_eq!(command(Os::Linux, "ctrl+y"), None);
+}
+
+#[test]
+fn extends_movements_with_shift() {
+    let mut keymap = Keymap::preset(Preset::Default, Os::Linux);
+    let mut state = KeymapState::default();
+    assert_eq!(
+        press(&keymap, &mut state, "home"),
+        KeyResult::Command(Command::MoveToStartOfLine, false)
+    );
+    assert_eq!(
+        press(&keymap, &mut state, "shift+home"),
+        KeyResult::Command(Command::MoveToStartOfLine, true)
+    );
+    // other commands fire with shift, but don't extend anything
+    assert_eq!(
+        press(&keymap, &mut state, "shift+enter"),
+        KeyResult::Command(Command::Enter, false)
+    );
+    // a binding with shift wins over the one without
+    assert_eq!(
+        press(&keymap, &mut state, "ctrl+shift+d"),
+        KeyResult::Command(Command::DuplicateLines, false)
+    );
+
+    keymap.bind(Mode::Insert, &[stroke("home")], Command::MoveToStartOfText);
+    assert_eq!(
+        press(&keymap, &mut state, "shift+home"),
+        KeyResult::Command(Command::MoveToStartOfText, true)
+    );
+    keymap.unbind(Mode::Insert, &[stroke("home")]);
+    assert_eq!(press(&keymap, &mut state, "home"), KeyResult::Unbound);
+}
+
+#[test]
+fn chords() {
+    let keymap = Keymap::preset(Preset::Emacs, Os::Linux);
+    let mut state = KeymapState::default();
+    assert_eq!(press(&keymap, &mut state, "ctrl+x"), KeyResult::Pending);
+    assert_eq!(state.pending(), &[stroke("ctrl+x")]);
+    // pressing a modifier on its own keeps the chord going
+    assert_eq!(
+        keymap.handle_key(&mut state, KeyCode::Shift, KeyModifiers::default()),
+        KeyResult::Pending
+    );
+    assert_eq!(
+        press(&keymap, &mut state, "u"),
+        KeyResult::Command(Command::Undo, false)
+    );
+    assert!(state.pending().is_empty());
+
+    assert_eq!(press(&keymap, &mut state, "ctrl+x"), KeyResult::Pending);
+    assert_eq!(press(&keymap, &mut state, "a"), KeyResult::Cancelled);
+    assert!(state.pending().is_empty());
+    assert_eq!(press(&keymap, &mut state, "a"), KeyResult::Unbound);
+}
+
+#[test]
+fn vim_preset_switches_modes() {
+    let keymap = Keymap::preset(Preset::Vim, Os::Linux);
+    let mut state = KeymapState::new(keymap.initial_mode());
+    assert_eq!(state.mode(), Mode::Normal);
+    assert!(!state.accepts_text_input());
+    assert_eq!(
+        press(&keymap, &mut state, "j"),
+        KeyResult::Command(Command::MoveDown, false)
+    );
+    assert_eq!(press(&keymap, &mut state, "g"), KeyResult::Pending);
+    assert_eq!(
+        press(&keymap, &mut state, "g"),
+        KeyResult::Command(Command::MoveToStartOfText, false)
+    );
+    assert_eq!(
+        press(&keymap, &mut state, "shift+4"),
+        KeyResult::Command(Command::MoveToEndOfLine, false)
+    );
+    assert_eq!(
+        press(&keymap, &mut state, "i"),
+        KeyResult::Command(Command::EnterInsertMode, false)
+    );
+
+    state.set_mode(Mode::Insert);
+    assert!(state.accepts_text_input());
+    assert_eq!(press(&keymap, &mut state, "j"), KeyResult::Unbound);
+    assert_eq!(
+        press(&keymap, &mut state, "escape"),
+        KeyResult::Command(Command::EnterNormalMode, false)
+    );
+}
+
+#[test]
+fn loads_overrides_from_toml() {
+    let keymap = Keymap::from_toml(
+        r#"
+        preset = "vim"
+
+        [keys]
+        "ctrl+k ctrl+u" = "move_lines_up"
+        "mod+d" = "none"
+
+        [normal]
+        "shift+j" = "move_lines_down"
+
+        [studio]
+        "mod+backtick" = "recompile"
+        "#,
+        Os::Linux,
+    )
+    .unwrap();
+    assert_eq!(keymap.initial_mode(), Mode::Normal);
+    assert_eq!(
+        keymap.command(Mode::Insert, &[stroke("ctrl+k"), stroke("ctrl+u")]),
+        Some(Command::MoveLinesUp)
+    );
+    assert_eq!(keymap.command(Mode::Insert, &[stroke("ctrl+d")]), None);
+    assert_eq!(
+        keymap.command(Mode::Normal, &[stroke("shift+j")]),
+        Some(Command::MoveLinesDown)
+    );
+    assert_eq!(
+        keymap.command(Mode::Normal, &[stroke("d"), stroke("d")]),
+        Some(Command::DeleteLine)
+    );
+    assert_eq!(
+        parse_table("[studio]\n\"mod+backtick\" = \"recompile\"", "studio", Os::Linux),
+        Ok(vec![(vec![stroke("ctrl+backtick")], "recompile".to_string())])
+    );
+
+    let error = |toml| Keymap::from_toml(toml, Os::Linux).unwrap_err();
+    assert_eq!(
+        error("[keys]\n\"ctrl+k\" = \"fly\""),
+        KeymapError::UnknownCommand("fly".to_string())
+    );
+    assert_eq!(
+        error("preset = \"nano\""),
+        KeymapError::UnknownPreset("nano".to_string())
+    );
+    assert_eq!(
+        error("[keys]\n\"ctrl+k\" = 1"),
+        KeymapError::ExpectedString("keys.ctrl+k".to_string())
+    );
+    assert!(matches!(error("[keys"), KeymapError::Toml { .. }));
+}
+
+#[test]
+fn command_names_round_trip() {
+    for command in [Command::MoveWordLeft, Command::DeleteToEndOfLine, Command::EnterNormalMode] {
+        assert_eq!(Command::from_name(command.name()), Some(command));
+    }
+    assert_eq!(Command::from_name("MoveLeft"), None);
+}
@@ -0,0 +1,237 @@
+use makepad_code_editor::{
+    language::{Json, Markdown, Rust, ShaderDsl, Toml},
+    text::{Change, Length, Position, Text},
+    token::TokenKind,
+    Language, Tokenizer,
+};
+
+fn tokenize_with(tokenizer: &mut Tokenizer, text: &Text) -> Vec<Vec<(TokenKind, String)>> {
+    let mut tokens = vec![Vec::new(); text.as_lines().len()];
+    tokenizer.update(text, &mut tokens);
+    text.as_lines()
+        .iter()
+        .zip(tokens)
+        .map(|(line, tokens)| {
+            let mut byte = 0;
+            tokens
+                .into_iter()
+                .map(|token| {
+                    let string = line[byte..byte + token.len].to_string();
+                    byte += token.len;
+                    (token.kind, string)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn tokenize<L: Language + 'static>(language: L, text: &str) -> Vec<Vec<(TokenKind, String)>> {
+    let text = Text::from(text);
+    let mut tokenizer = Tokenizer::new(language, text.as_lines().len());
+    tokenize_with(&mut tokenizer, &text)
+}
+
+// the non whitespace tokens of all lines
+fn kinds<L: Language + 'static>(language: L, text: &str) -> Vec<(TokenKind, String)> {
+    tokenize(language, text)
+        .into_iter()
+        .flatten()
+        .filter(|(kind, _)| *kind != TokenKind::Whitespace)
+        .collect()
+}
+
+fn token(kind: TokenKind, string: &str) -> (TokenKind, String) {
+    (kind, string.to_string())
+}
+
+#[test]
+fn rust() {
+    assert_eq!(
+        kinds(Rust, "fn main() /* a\nb */ \"c"),
+        vec![
+            token(TokenKind::OtherKeyword, "fn"),
+            token(TokenKind::Identifier, "main"),
+            token(TokenKind::Delimiter, "("),
+            token(TokenKind::Delimiter, ")"),
+            token(TokenKind::Comment, "/* a"),
+            token(TokenKind::Comment, "b */"),
+            token(TokenKind::String, "\"c"),
+        ]
+    );
+}
+
+#[test]
+fn toml() {
+    assert_eq!(
+        kinds(
+            Toml,
+            "[package.metadata] # table\nname = \"a\"\nflags = [true, 1_000, 1979-05-27T07:32:00Z]\ndesc = \"\"\"\nline\"\"\"",
+        ),
+        vec![
+            token(TokenKind::Delimiter, "["),
+            token(TokenKind::Typename, "package"),
+            token(TokenKind::Punctuator, "."),
+            token(TokenKind::Typename, "metadata"),
+            token(TokenKind::Delimiter, "]"),
+            token(TokenKind::Comment, "# table"),
+            token(TokenKind::Identifier, "name"),
+            token(TokenKind::Punctuator, "="),
+            token(TokenKind::String, "\"a\""),
+            token(TokenKind::Identifier, "flags"),
+            token(TokenKind::Punctuator, "="),
+            token(TokenKind::Delimiter, "["),
+            token(TokenKind::OtherKeyword, "true"),
+            token(TokenKind::Punctuator, ","),
+            token(TokenKind::Number, "1_000"),
+            token(TokenKind::Punctuator, ","),
+            token(TokenKind::Number, "1979-05-27T07:32:00Z"),
+            token(TokenKind::Delimiter, "]"),
+            token(TokenKind::Identifier, "desc"),
+            token(TokenKind::Punctuator, "="),
+            token(TokenKind::String, "\"\"\""),
+            token(TokenKind::String, "line\"\"\""),
+        ]
+    );
+}
+
+#[test]
+fn json() {
+    assert_eq!(
+        kinds(Json, "{\"a\": [-1.5e3, null], // c\n\"b\" : \"x\\\"y\"}"),
+        vec![
+            token(TokenKind::Delimiter, "{"),
+            token(TokenKind::Identifier, "\"a\""),
+            token(TokenKind::Punctuator, ":"),
+            token(TokenKind::Delimiter, "["),
+            token(TokenKind::Number, "-1.5e3"),
+            token(TokenKind::Punctuator, ","),
+            token(TokenKind::OtherKeyword, "null"),
+            token(TokenKind::Delimiter, "]"),
+            token(TokenKind::Punctuator, ","),
+            token(TokenKind::Comment, "// c"),
+            token(TokenKind::Identifier, "\"b\""),
+            token(TokenKind::Punctuator, ":"),
+            token(TokenKind::String, "\"x\\\"y\""),
+            token(TokenKind::Delimiter, "}"),
+        ]
+    );
+}
+
+#[test]
+fn markdown() {
+    assert_eq!(
+        kinds(
+            Markdown,
+            "# Title\n- a **b** `c` [d](e) snake_case\n```rust\nlet x = *y;\n```\n> quote",
+        ),
+        vec![
+            token(TokenKind::Typename, "# Title"),
+            token(TokenKind::Punctuator, "-"),
+            token(TokenKind::Identifier, "a"),
+            token(TokenKind::Constant, "**b**"),
+            token(TokenKind::String, "`c`"),
+            token(TokenKind::Identifier, "[d]"),
+            token(TokenKind::String, "(e)"),
+            token(TokenKind::Identifier, "snake_case"),
+            token(TokenKind::Punctuator, "```"),
+            token(TokenKind::String, "rust"),
+            token(TokenKind::String, "let x = *y;"),
+            token(TokenKind::Punctuator, "```"),
+            token(TokenKind::Comment, "> quote"),
+        ]
+    );
+}
+
+#[test]
+fn shader_dsl() {
+    assert_eq!(
+        kinds(
+            ShaderDsl,
+            "DrawQuad = {{DrawQuad}} {\n    color: #f80\n    fn pixel(self) -> vec4 { return mix(self.color, #0, 0.5); }",
+        ),
+        vec![
+            token(TokenKind::Typename, "DrawQuad"),
+            token(TokenKind::Punctuator, "="),
+            token(TokenKind::Delimiter, "{{"),
+            token(TokenKind::Typename, "DrawQuad"),
+            token(TokenKind::Delimiter, "}}"),
+            token(TokenKind::Delimiter, "{"),
+            token(TokenKind::Identifier, "color"),
+            token(TokenKind::Punctuator, ":"),
+            token(TokenKind::Number, "#f80"),
+            token(TokenKind::OtherKeyword, "fn"),
+            token(TokenKind::Identifier, "pixel"),
+            token(TokenKind::Delimiter, "("),
+            token(TokenKind::OtherKeyword, "self"),
+            token(TokenKind::Delimiter, ")"),
+            token(TokenKind::Punctuator, "->"),
+            token(TokenKind::Typename, "vec4"),
+            token(TokenKind::Delimiter, "{"),
+            token(TokenKind::BranchKeyword, "return"),
+            token(TokenKind::Identifier, "mix"),
+            token(TokenKind::Delimiter, "("),
+            token(TokenKind::OtherKeyword, "self"),
+            token(TokenKind::Punctuator, "."),
+            token(TokenKind::Identifier, "color"),
+            token(TokenKind::Punctuator, ","),
+            token(TokenKind::Number, "#0"),
+            token(TokenKind::Punctuator, ","),
+            token(TokenKind::Number, "0.5"),
+            token(TokenKind::Delimiter, ")"),
+            token(TokenKind::Punctuator, ";"),
+            token(TokenKind::Delimiter, "}"),
+        ]
+    );
+}
+
+#[test]
+fn tokens_cover_every_line() {
+    let text = "a = '''x\n\u{e9}\u{e9} ``` _ ** [ ( < \\\n{\"\n#";
+    let lines: Vec<&str> = text.split('\n').collect();
+    for tokens in [
+        tokenize(Rust, text),
+        tokenize(Toml, text),
+        tokenize(Json, text),
+        tokenize(Markdown, text),
+        tokenize(ShaderDsl, text),
+    ] {
+        for (line, tokens) in lines.iter().zip(tokens) {
+            assert_eq!(tokens.into_iter().map(|(_, string)| string).collect::<String>(), *line);
+        }
+    }
+}
+
+#[test]
+fn retokenizes_lines_after_a_state_change() {
+    let mut text = Text::from("a = 1\nb = 2\nc = 3");
+    let mut tokenizer = Tokenizer::for_extension("toml", 3);
+    assert_eq!(tokenize_with(&mut tokenizer, &text)[2][0], token(TokenKind::Identifier, "c"));
+
+    // opening a multiline string turns the lines after it into string contents
+    let change = Change::Insert(
+        Position {
+            line_index: 0,
+            byte_index: 4,
+        },
+        Text::from("'''"),
+    );
+    tokenizer.apply_change(&change);
+    text.apply_change(change);
+    let tokens = tokenize_with(&mut tokenizer, &text);
+    assert_eq!(tokens[1], vec![token(TokenKind::String, "b = 2")]);
+    assert_eq!(tokens[2], vec![token(TokenKind::String, "c = 3")]);
+
+    let change = Change::Delete(
+        Position {
+            line_index: 0,
+            byte_index: 4,
+        },
+        Length {
+            line_count: 0,
+            byte_count: 3,
+        },
+    );
+    tokenizer.apply_change(&change);
+    text.apply_change(change);
+    assert_eq!(tokenize_with(&mut tokenizer, &text)[2][0], token(TokenKind::Identifier, "c"));
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use {
+    common::{cursor, position, session, text},
+    makepad_code_editor::selection::Affinity,
+};
+
+#[test]
+fn moves_by_words() {
+    let mut session = session("let foo_bar = (a+1);\nx");
+    let mut stops = Vec::new();
+    for _ in 0..8 {
+        session.move_word_right(true);
+        stops.push(cursor(&session).byte_index);
+    }
+    assert_eq!(stops, vec![3, 11, 13, 15, 16, 17, 18, 20]);
+    session.move_word_right(true);
+    assert_eq!(cursor(&session), position(1, 0));
+
+    session.move_word_left(true);
+    assert_eq!(cursor(&session), position(0, 20));
+    session.move_word_left(true);
+    assert_eq!(cursor(&session), position(0, 18));
+    session.move_to_end_of_line(true);
+    session.move_word_left(false);
+    session.move_word_left(false);
+    assert_eq!(session.copy(), "1);");
+}
+
+#[test]
+fn home_toggles_between_indentation_and_line_start() {
+    let mut session = session("    foo\n   \n");
+    session.set_selection(position(0, 6), Affinity::Before, 1);
+    session.move_to_start_of_line(true);
+    assert_eq!(cursor(&session), position(0, 4));
+    session.move_to_start_of_line(true);
+    assert_eq!(cursor(&session), position(0, 0));
+    session.move_to_start_of_line(true);
+    assert_eq!(cursor(&session), position(0, 4));
+    session.move_to_end_of_line(false);
+    assert_eq!(session.copy(), "foo");
+
+    // a line of only whitespace has its end as indentation
+    session.set_selection(position(1, 1), Affinity::Before, 1);
+    session.move_to_start_of_line(true);
+    assert_eq!(cursor(&session), position(1, 3));
+}
+
+#[test]
+fn moves_by_pages_and_to_the_ends_of_the_text() {
+    let mut session = session("a\nbb\nc\ndddd\ne");
+    session.set_selection(position(0, 1), Affinity::Before, 1);
+    session.move_page_down(true, 3);
+    assert_eq!(cursor(&session), position(3, 1));
+    session.move_page_down(true, 3);
+    assert_eq!(cursor(&session), position(4, 1));
+    session.move_page_up(false, 2);
+    assert_eq!(cursor(&session), position(2, 1));
+    assert_eq!(session.copy(), "\ndddd\ne");
+
+    session.move_to_start_of_text(true);
+    assert_eq!(cursor(&session), position(0, 0));
+    session.move_to_end_of_text(false);
+    assert_eq!(session.copy(), text(&session));
+}
+
+#[test]
+fn selects_words_and_lines() {
+    let mut session = session("foo.bar_baz(1)\nnext\nlast");
+    session.set_selection(position(0, 6), Affinity::Before, 1);
+    session.select_word();
+    assert_eq!(session.copy(), "bar_baz");
+    session.set_selection(position(0, 3), Affinity::Before, 1);
+    session.select_word();
+    assert_eq!(session.copy(), "foo");
+
+    session.select_line();
+    assert_eq!(session.copy(), "foo.bar_baz(1)\n");
+    session.select_line();
+    assert_eq!(session.copy(), "foo.bar_baz(1)\nnext\n");
+    session.select_line();
+    session.select_line();
+    assert_eq!(session.copy(), text(&session));
+}
+
+#[test]
+fn moves_lines_up_and_down() {
+    let mut session = session("a\nb\nc\nd");
+    session.set_selection(position(1, 1), Affinity::Before, 1);
+    session.move_down(false);
+    session.move_lines_up();
+    assert_eq!(text(&session), "b\nc\na\nd");
+    assert_eq!(session.copy(), "\nc");
+
+    session.move_lines_up();
+    assert_eq!(text(&session), "b\nc\na\nd");
+
+    session.move_to_end_of_text(true);
+    session.move_lines_up();
+    assert_eq!(text(&session), "b\nc\nd\na");
+    session.move_lines_down();
+    assert_eq!(text(&session), "b\nc\na\nd");
+    assert_eq!(cursor(&session), position(3, 1));
+
+    // moving lines is a single undo group
+    assert!(session.undo());
+    session.handle_changes();
+    assert_eq!(text(&session), "b\nc\nd\na");
+    assert_eq!(cursor(&session), position(2, 1));
+}
+
+#[test]
+fn duplicates_lines() {
+    let mut session = session("a\nb\nc");
+    session.set_selection(position(0, 1), Affinity::Before, 1);
+    session.add_selection(position(2, 0), Affinity::Before, 1);
+    session.duplicate_lines();
+    assert_eq!(text(&session), "a\na\nb\nc\nc");
+    let selections = session.selections();
+    assert_eq!(selections[0].cursor.position, position(1, 1));
+    assert_eq!(selections[1].cursor.position, position(4, 0));
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use {
+    common::{session, text},
+    makepad_code_editor::{
+        regex::Regex,
+        search::SearchQuery,
+        text::{Position, Text},
+    },
+};
+
+fn find_all(pattern: &str, line: &str) -> Vec<String> {
+    let regex = Regex::new(pattern, true).unwrap();
+    regex
+        .find_all(line)
+        .into_iter()
+        .map(|range| line[range].to_string())
+        .collect()
+}
+
+fn query(text: &str) -> SearchQuery {
+    SearchQuery {
+        text: text.to_string(),
+        case_sensitive: true,
+        ..SearchQuery::default()
+    }
+}
+
+#[test]
+fn regex_syntax() {
+    assert_eq!(find_all("a.c", "abc a-c ac"), vec!["abc", "a-c"]);
+    assert_eq!(find_all("[a-c]+", "abcdcba"), vec!["abc", "cba"]);
+    assert_eq!(find_all("[^a-c ]+", "abxyc z"), vec!["xy", "z"]);
+    assert_eq!(find_all(r"\d+(\.\d+)?", "1 2.5 x.3"), vec!["1", "2.5", "3"]);
+    assert_eq!(find_all(r"\bfoo\b", "foo food _foo foo"), vec!["foo", "foo"]);
+    assert_eq!(find_all("^a|b$", "aab ab"), vec!["a", "b"]);
+    assert_eq!(find_all("(?:ab){2,3}", "ab abab abababab"), vec!["abab", "ababab"]);
+    assert_eq!(find_all("<.+?>", "<a><b>"), vec!["<a>", "<b>"]);
+    assert_eq!(find_all("<.+>", "<a><b>"), vec!["<a><b>"]);
+    assert_eq!(find_all("(a|ab)(c|bcd)", "abcd"), vec!["abcd"]);
+    assert_eq!(find_all("x*", "axxb"), vec!["xx"]);
+    assert_eq!(find_all("(a*)*b", "aaab"), vec!["aaab"]);
+    assert_eq!(find_all("é+", "aéé"), vec!["éé"]);
+}
+
+#[test]
+fn regex_long_lines() {
+    // repetitions of groups used to recurse once per repetition, which overflowed the stack
+    let line = "x".repeat(100_000);
+    assert_eq!(Regex::new("(x|y)*", true).unwrap().find_all(&line), vec![0..line.len()]);
+    assert_eq!(Regex::new("(?:x(y)?)+?$", true).unwrap().find_all(&line), vec![0..line.len()]);
+    let line = format!("{{{}}}", "\"a\":1,".repeat(10_000));
+    assert_eq!(find_all(r#"(?:"\w+":\d+,)+"#, &line).len(), 1);
+}
+
+#[test]
+fn regex_errors() {
+    for pattern in ["(a", "a)", "*a", "[a-", r"\q", "a{3,1}", "\\"] {
+        assert!(Regex::new(pattern, true).is_err(), "{}", pattern);
+    }
+    assert_eq!(Regex::new("(a", true).unwrap_err().index, 2);
+    // a brace that doesn't start a count is a literal
+    assert_eq!(find_all("a{x}", "a{x}"), vec!["a{x}"]);
+}
+
+#[test]
+fn captures() {
+    let regex = Regex::new(r"(\w+)=(\d+)?", true).unwrap();
+    let captures = regex.captures_at("a=1 b=", 3).unwrap();
+    assert_eq!(captures.range(), 4..6);
+    assert_eq!(captures.get(1), Some(4..5));
+    assert_eq!(captures.get(2), None);
+}
+
+#[test]
+fn query_options() {
+    let matches = |query: SearchQuery| {
+        query
+            .compile()
+            .unwrap()
+            .find_all("Foo foo.bar foobar")
+            .len()
+    };
+    assert_eq!(matches(query("foo")), 2);
+    assert_eq!(
+        matches(SearchQuery {
+            case_sensitive: false,
+            ..query("foo")
+        }),
+        3
+    );
+    assert_eq!(
+        matches(SearchQuery {
+            whole_word: true,
+            ..query("foo")
+        }),
+        1
+    );
+    // without the regex flag, the dot is literal
+    assert_eq!(matches(query("o.b")), 1);
+    assert_eq!(
+        matches(SearchQuery {
+            regex: true,
+            ..query("o.b")
+        }),
+        2
+    );
+}
+
+#[test]
+fn expands_replacements() {
+    let query = SearchQuery {
+        regex: true,
+        ..query(r"(\w+)=(\w+)")
+    };
+    let line = "key=value";
+    let captures = query.compile().unwrap().captures_at(line, 0).unwrap();
+    assert_eq!(
+        query.expand_replacement("$2=$1 ${1}s $$1 $x", line, &captures),
+        "value=key keys $1 $x"
+    );
+}
+
+#[test]
+fn finds_next_and_previous_with_wrap_around() {
+    let mut session = session("ab\nb ab\nb");
+    assert_eq!(session.set_search(query("b")), Ok(4));
+    assert!(session.find_next(false));
+    let selection = session.selections()[0];
+    assert_eq!(selection.start(), Position { line_index: 0, byte_index: 1 });
+    assert_eq!(selection.end(), Position { line_index: 0, byte_index: 2 });
+
+    assert!(session.find_previous(false));
+    assert_eq!(session.selections()[0].start(), Position { line_index: 2, byte_index: 0 });
+
+    // adding keeps the previous matches selected
+    assert!(session.find_next(true));
+    assert!(session.find_next(true));
+    assert_eq!(session.selections().len(), 3);
+
+    assert!(session.select_all_matches());
+    assert_eq!(session.selections().len(), 4);
+    assert_eq!(session.copy(), "bbbb");
+
+    assert_eq!(session.set_search(query("z")), Ok(0));
+    assert!(!session.find_next(false));
+}
+
+#[test]
+fn matches_follow_edits() {
+    let mut session = session("x = 1");
+    session.set_search(query("1")).unwrap();
+    session.find_previous(false);
+    session.insert(Text::from("11"));
+    session.handle_changes();
+    assert_eq!(text(&session), "x = 11");
+    let matches = session.search_matches();
+    assert_eq!(matches.len(), 2);
+    assert_eq!(matches[1].end(), Position { line_index: 0, byte_index: 6 });
+}
+
+#[test]
+fn replaces_the_selected_match() {
+    let mut session = session("a a a");
+    session.set_search(query("a")).unwrap();
+    // nothing is replaced until a match is selected
+    assert!(!session.replace("b"));
+    assert_eq!(text(&session), "a a a");
+    assert!(session.replace("b"));
+    assert_eq!(text(&session), "b a a");
+    assert_eq!(session.selections()[0].start(), Position { line_index: 0, byte_index: 2 });
+    assert_eq!(session.search_matches().len(), 2);
+}
+
+#[test]
+fn replace_all_is_a_single_undo_group() {
+    let mut session = session("fn f(a: A) {\n    a + a\n}");
+    session
+        .set_search(SearchQuery {
+            whole_word: true,
+            regex: true,
+            ..query("[a-z]+")
+        })
+        .unwrap();
+    assert_eq!(session.replace_all("x_$0"), 5);
+    assert_eq!(text(&session), "x_fn x_f(x_a: A) {\n    x_a + x_a\n}");
+    assert!(session.search_matches().is_empty());
+
+    assert!(session.undo());
+    session.handle_changes();
+    assert_eq!(text(&session), "fn f(a: A) {\n    a + a\n}");
+    assert!(session.redo());
+    session.handle_changes();
+    assert_eq!(text(&session), "x_fn x_f(x_a: A) {\n    x_a + x_a\n}");
+}
@@ -0,0 +1,50 @@
+use makepad_code_editor::structure::{enclosing_headers, is_header};
+
+fn lines(text: &str) -> Vec<String> {
+    text.lines().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn recognizes_headers() {
+    for line in [
+        "fn main() {",
+        "    pub(crate) async fn run(&self) {",
+        "impl<T> Foo for Bar<T> {",
+        "pub mod tests {",
+        "unsafe trait Send {}",
+        "pub const unsafe extern \"C\" fn f() {",
+    ] {
+        assert!(is_header(line), "{}", line);
+    }
+    for line in ["", "}", "let fn_name = 1;", "pub struct Foo {", "const X: usize = 1;", "implementation"] {
+        assert!(!is_header(line), "{}", line);
+    }
+}
+
+#[test]
+fn finds_enclosing_headers_from_indentation() {
+    let lines = lines(
+        "mod a {\n\
+         \x20   struct S;\n\
+         \x20   impl S {\n\
+         \x20       fn f() {\n\
+         \x20           1\n\
+         \x20       }\n\
+         \n\
+         \x20       fn g() {\n\
+         \x20           if x {\n\
+         \x20               2\n\
+         \x20           }\n\
+         \x20       }\n\
+         \x20   }\n\
+         }",
+    );
+    assert_eq!(enclosing_headers(&lines, 4), vec![0, 2, 3]);
+    // blocks that aren't items are skipped
+    assert_eq!(enclosing_headers(&lines, 9), vec![0, 2, 7]);
+    // a blank line belongs to the next line
+    assert_eq!(enclosing_headers(&lines, 6), vec![0, 2]);
+    assert_eq!(enclosing_headers(&lines, 7), vec![0, 2]);
+    assert_eq!(enclosing_headers(&lines, 0), Vec::<usize>::new());
+    assert_eq!(enclosing_headers(&lines, 13), Vec::<usize>::new());
+}
@@ -0,0 +1,164 @@
+use crate::makepad_platform::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CameraMode {
+    /// Dragging rotates around the target, scrolling zooms towards it.
+    Orbit,
+    /// Dragging looks around, scrolling and WASD/QE move the camera.
+    Fly,
+}
+
+/// A camera for 3d views that turns finger and key input into the view of a pass.
+/// Angles are in degrees, a yaw and pitch of 0 looks down the negative z axis.
+#[derive(Clone, Debug)]
+pub struct Camera3d {
+    pub mode: CameraMode,
+    /// The point orbited around, in orbit mode.
+    pub target: Vec3,
+    /// The distance to the target, in orbit mode.
+    pub distance: f32,
+    /// The position of the camera, in fly mode.
+    pub position: Vec3,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov_y: f32,
+    pub near: f32,
+    pub far: f32,
+    /// Degrees per pixel dragged.
+    pub rotate_speed: f32,
+    /// Units per pixel scrolled, or per key press in fly mode.
+    pub move_speed: f32,
+    last_abs: Option<DVec2>,
+}
+
+impl Default for Camera3d {
+    fn default() -> Self {
+        Self {
+            mode: CameraMode::Orbit,
+            target: Vec3::default(),
+            distance: 5.0,
+            position: vec3(0.0, 0.0, 5.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            fov_y: 45.0,
+            near: 0.1,
+            far: 1000.0,
+            rotate_speed: 0.3,
+            move_speed: 0.01,
+            last_abs: None,
+        }
+    }
+}
+
+impl Camera3d {
+    pub fn orbit(target: Vec3, distance: f32) -> Self {
+        Self {target, distance, ..Self::default()}
+    }
+
+    pub fn fly(position: Vec3) -> Self {
+        Self {mode: CameraMode::Fly, position, ..Self::default()}
+    }
+
+    /// The direction the camera looks in.
+    pub fn forward(&self) -> Vec3 {
+        let (yaw, pitch) = (self.yaw.to_radians(), self.pitch.to_radians());
+        vec3(-yaw.sin() * pitch.cos(), pitch.sin(), -yaw.cos() * pitch.cos())
+    }
+
+    pub fn right(&self) -> Vec3 {
+        let yaw = self.yaw.to_radians();
+        vec3(yaw.cos(), 0.0, -yaw.sin())
+    }
+
+    /// The position of the camera in either mode.
+    pub fn eye(&self) -> Vec3 {
+        match self.mode {
+            CameraMode::Orbit => self.target - self.forward() * self.distance,
+            CameraMode::Fly => self.position,
+        }
+    }
+
+    /// The world to view matrix, with the camera looking down its negative z axis.
+    pub fn view_matrix(&self) -> Mat4 {
+        let eye = self.eye();
+        let f = self.forward();
+        let r = self.right();
+        let u = Vec3::cross(r, f);
+        Mat4 {v: [
+            r.x, u.x, -f.x, 0.0,
+            r.y, u.y, -f.y, 0.0,
+            r.z, u.z, -f.z, 0.0,
+            -r.dot(eye), -u.dot(eye), f.dot(eye), 1.0
+        ]}
+    }
+
+    pub fn projection_matrix(&self, aspect: f32) -> Mat4 {
+        Mat4::perspective(self.fov_y, aspect, self.near, self.far)
+    }
+
+    /// The matrix mode to set on the pass the scene is drawn in, the pass supplies the aspect ratio.
+    pub fn matrix_mode(&self) -> PassMatrixMode {
+        PassMatrixMode::Projection {
+            fov_y: self.fov_y,
+            near: self.near,
+            far: self.far,
+            cam: self.view_matrix()
+        }
+    }
+
+    /// Moves the camera with the input on `area`, returns true when the view changed.
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, area: Area) -> bool {
+        match event.hits(cx, area) {
+            Hit::FingerDown(fe) => {
+                cx.set_key_focus(area);
+                self.last_abs = Some(fe.abs);
+                false
+            }
+            Hit::FingerMove(fe) => {
+                let last_abs = self.last_abs.unwrap_or(fe.abs);
+                self.last_abs = Some(fe.abs);
+                let delta = fe.abs - last_abs;
+                self.rotate(delta.x as f32 * self.rotate_speed, -delta.y as f32 * self.rotate_speed);
+                delta != DVec2::default()
+            }
+            Hit::FingerUp(_) => {
+                self.last_abs = None;
+                false
+            }
+            Hit::FingerScroll(fs) => {
+                self.dolly(-fs.scroll.y as f32 * self.move_speed);
+                fs.scroll.y != 0.0
+            }
+            Hit::KeyDown(ke) if self.mode == CameraMode::Fly => {
+                let step = self.move_speed * 20.0;
+                let (forward, right) = (self.forward(), self.right());
+                let movement = match ke.key_code {
+                    KeyCode::KeyW => forward * step,
+                    KeyCode::KeyS => forward * -step,
+                    KeyCode::KeyD => right * step,
+                    KeyCode::KeyA => right * -step,
+                    KeyCode::KeyE => vec3(0.0, step, 0.0),
+                    KeyCode::KeyQ => vec3(0.0, -step, 0.0),
+                    _ => return false
+                };
+                self.position += movement;
+                true
+            }
+            _ => false
+        }
+    }
+
+    /// Turns the camera to the right and up, in orbit mode it moves around the target.
+    pub fn rotate(&mut self, yaw: f32, pitch: f32) {
+        self.yaw = (self.yaw - yaw) % 360.0;
+        self.pitch = (self.pitch + pitch).clamp(-89.0, 89.0);
+    }
+
+    /// Moves towards the target in orbit mode, or forward in fly mode.
+    pub fn dolly(&mut self, amount: f32) {
+        match self.mode {
+            CameraMode::Orbit => self.distance = (self.distance - amount).max(self.near * 2.0),
+            CameraMode::Fly => self.position += self.forward() * amount,
+        }
+    }
+}
@@ -0,0 +1,1072 @@
+//! Color glyphs, as used by emoji fonts. Layered glyphs from the COLR table (the version 0
+//! layer records and the version 1 paint graph) are painted with the first CPAL palette,
+//! bitmap glyphs from the CBDT and sbix tables are decoded from their PNG data. Both end up
+//! as a premultiplied RGBA image rendered on the cpu, which the font atlas copies into its
+//! color page.
+//!
+//! Variable fonts are painted at their default instance, the variation deltas of the
+//! `PaintVar*` formats are ignored.
+
+use {
+    crate::makepad_platform::*,
+    rustybuzz::ttf_parser::{self, Face, GlyphId, RasterImageFormat, Tag},
+    makepad_zune_png::{
+        PngDecoder,
+        makepad_zune_core::{options::DecoderOptions, result::DecodingResult},
+    },
+};
+
+// paint graphs referencing themselves stop here
+const MAX_PAINT_DEPTH: usize = 64;
+
+// color glyphs larger than this are not drawn
+const MAX_IMAGE_SIZE: usize = 1024;
+
+// palette index of the text color. The image in the atlas is shared by every color the
+// text is drawn in, so these layers are painted white.
+const FOREGROUND_INDEX: u16 = 0xffff;
+const FOREGROUND: Vec4 = Vec4 {x: 1.0, y: 1.0, z: 1.0, w: 1.0};
+
+pub struct ColorGlyphImage {
+    pub width: usize,
+    pub height: usize,
+    // premultiplied 0xAARRGGBB, top row first
+    pub pixels: Vec<u32>,
+    // bottom left corner relative to the glyph origin in pixels, y up
+    pub left: f64,
+    pub bottom: f64,
+}
+
+/// Whether the face has any table with color glyphs
+pub fn has_color_tables(face: &Face) -> bool {
+    [b"COLR", b"CBDT", b"sbix"].iter().any( | tag | face.raw_face().table(Tag::from_bytes(tag)).is_some())
+}
+
+/// Renders a color glyph at `ppem` pixels per em, `None` for glyphs that aren't colored
+pub fn render_color_glyph(face: &Face, glyph_id: u16, ppem: f64) -> Option<ColorGlyphImage> {
+    let raw_face = face.raw_face();
+    if let Some(colr) = raw_face.table(Tag::from_bytes(b"COLR")).and_then(Colr::parse) {
+        let cpal = raw_face.table(Tag::from_bytes(b"CPAL")).and_then(Cpal::parse);
+        if let Some(image) = render_colr_glyph(&colr, cpal.as_ref(), face, glyph_id, ppem) {
+            return Some(image)
+        }
+    }
+    render_bitmap_glyph(face, glyph_id, ppem)
+}
+
+/// Paints a glyph of a COLR table, with its layers outlined by the glyphs of `face`
+pub fn render_colr_glyph(colr: &Colr, cpal: Option<&Cpal>, face: &Face, glyph_id: u16, ppem: f64) -> Option<ColorGlyphImage> {
+    let bounds = if let Some(clip_box) = colr.clip_box(glyph_id) {
+        clip_box
+    }
+    else {
+        let mut bounds_painter = BoundsPainter {
+            face,
+            transforms: vec![Affine::identity()],
+            bounds: None
+        };
+        if !colr.paint(glyph_id, cpal, &mut bounds_painter) {
+            return None
+        }
+        bounds_painter.bounds?
+    };
+    let scale = ppem / face.units_per_em() as f64;
+    let left = (bounds.0 * scale).floor();
+    let bottom = (bounds.1 * scale).floor();
+    let right = (bounds.2 * scale).ceil();
+    let top = (bounds.3 * scale).ceil();
+    let width = (right - left) as usize;
+    let height = (top - bottom) as usize;
+    if width == 0 || height == 0 || width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        return None
+    }
+    let mut painter = RasterPainter {
+        face,
+        width,
+        height,
+        transforms: vec![Affine {xx: scale, yx: 0.0, xy: 0.0, yy: -scale, dx: -left, dy: top}],
+        clips: Vec::new(),
+        layers: vec![vec![[0.0; 4]; width * height]],
+    };
+    if !colr.paint(glyph_id, cpal, &mut painter) {
+        return None
+    }
+    let layer = painter.layers.swap_remove(0);
+    Some(ColorGlyphImage {
+        width,
+        height,
+        pixels: layer.iter().map( | pixel | pack_pixel(*pixel)).collect(),
+        left,
+        bottom,
+    })
+}
+
+/// Decodes the PNG bitmap of the strike closest to `ppem` and scales it to `ppem`
+pub fn render_bitmap_glyph(face: &Face, glyph_id: u16, ppem: f64) -> Option<ColorGlyphImage> {
+    let raster = face.glyph_raster_image(GlyphId(glyph_id), ppem.round().max(1.0).min(u16::MAX as f64) as u16)?;
+    if raster.format != RasterImageFormat::PNG || raster.pixels_per_em == 0 {
+        return None
+    }
+    let (src_width, src_height, src) = decode_png(raster.data)?;
+    let scale = ppem / raster.pixels_per_em as f64;
+    let width = ((src_width as f64 * scale).round() as usize).max(1);
+    let height = ((src_height as f64 * scale).round() as usize).max(1);
+    if width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+        return None
+    }
+    Some(ColorGlyphImage {
+        width,
+        height,
+        pixels: resample(&src, src_width, src_height, width, height),
+        left: raster.x as f64 * scale,
+        bottom: raster.y as f64 * scale,
+    })
+}
+
+// premultiplied pixels of a PNG image
+fn decode_png(data: &[u8]) -> Option<(usize, usize, Vec<[f32; 4]>)> {
+    let mut decoder = PngDecoder::new_with_options(data, DecoderOptions::default().png_set_add_alpha_channel(true));
+    let values: Vec<f32> = match decoder.decode().ok()? {
+        DecodingResult::U8(data) => data.iter().map( | v | *v as f32 / 255.0).collect(),
+        DecodingResult::U16(data) => data.iter().map( | v | *v as f32 / 65535.0).collect(),
+        _ => return None
+    };
+    let (width, height) = decoder.get_dimensions()?;
+    let pixel_count = width * height;
+    if pixel_count == 0 {
+        return None
+    }
+    let pixels = match values.len() / pixel_count {
+        // gray with alpha
+        2 => values.chunks_exact(2).map( | v | [v[0] * v[1], v[0] * v[1], v[0] * v[1], v[1]]).collect(),
+        4 => values.chunks_exact(4).map( | v | [v[0] * v[3], v[1] * v[3], v[2] * v[3], v[3]]).collect(),
+        _ => return None
+    };
+    Some((width, height, pixels))
+}
+
+// averages the source pixels under each target pixel, for scaling both up and down
+fn resample(src: &[[f32; 4]], src_width: usize, src_height: usize, width: usize, height: usize) -> Vec<u32> {
+    let sx = src_width as f64 / width as f64;
+    let sy = src_height as f64 / height as f64;
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        let y0 = y as f64 * sy;
+        let y1 = y0 + sy;
+        for x in 0..width {
+            let x0 = x as f64 * sx;
+            let x1 = x0 + sx;
+            let mut sum = [0.0f32; 4];
+            let mut total = 0.0;
+            for yi in y0.floor() as usize..(y1.ceil() as usize).min(src_height) {
+                let wy = y1.min(yi as f64 + 1.0) - y0.max(yi as f64);
+                for xi in x0.floor() as usize..(x1.ceil() as usize).min(src_width) {
+                    let w = (wy * (x1.min(xi as f64 + 1.0) - x0.max(xi as f64))) as f32;
+                    let pixel = src[yi * src_width + xi];
+                    for c in 0..4 {
+                        sum[c] += pixel[c] * w;
+                    }
+                    total += w;
+                }
+            }
+            if total > 0.0 {
+                for c in &mut sum {
+                    *c /= total;
+                }
+            }
+            pixels.push(pack_pixel(sum));
+        }
+    }
+    pixels
+}
+
+fn pack_pixel(pixel: [f32; 4]) -> u32 {
+    let channel = | v: f32 | (v.max(0.0).min(1.0) * 255.0).round() as u32;
+    channel(pixel[3]) << 24 | channel(pixel[0]) << 16 | channel(pixel[1]) << 8 | channel(pixel[2])
+}
+
+// big endian reads that fail instead of panicking on truncated tables
+#[derive(Clone, Copy)]
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn u8(&self, at: usize) -> Option<u8> {
+        self.0.get(at).cloned()
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let b = self.0.get(at..at + 2)?;
+        Some(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn i16(&self, at: usize) -> Option<f64> {
+        Some(self.u16(at)? as i16 as f64)
+    }
+
+    fn u24(&self, at: usize) -> Option<usize> {
+        let b = self.0.get(at..at + 3)?;
+        Some((b[0] as usize) << 16 | (b[1] as usize) << 8 | b[2] as usize)
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let b = self.0.get(at..at + 4)?;
+        Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f2dot14(&self, at: usize) -> Option<f64> {
+        Some(self.u16(at)? as i16 as f64 / 16384.0)
+    }
+
+    fn fixed(&self, at: usize) -> Option<f64> {
+        Some(self.u32(at)? as i32 as f64 / 65536.0)
+    }
+}
+
+/// The colors of the first palette of a CPAL table, straight alpha
+pub struct Cpal {
+    pub colors: Vec<Vec4>,
+}
+
+impl Cpal {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let r = Reader(data);
+        let num_entries = r.u16(2)? as usize;
+        if r.u16(4)? == 0 {
+            return None
+        }
+        let records = r.u32(8)? as usize;
+        let first = r.u16(12)? as usize;
+        let mut colors = Vec::with_capacity(num_entries);
+        for i in 0..num_entries {
+            // stored as blue, green, red, alpha
+            let at = records + (first + i) * 4;
+            colors.push(vec4(
+                r.u8(at + 2)? as f32 / 255.0,
+                r.u8(at + 1)? as f32 / 255.0,
+                r.u8(at)? as f32 / 255.0,
+                r.u8(at + 3)? as f32 / 255.0,
+            ));
+        }
+        Some(Self {colors})
+    }
+}
+
+/// 2D affine transform, maps (x, y) to (xx * x + xy * y + dx, yx * x + yy * y + dy)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub xx: f64,
+    pub yx: f64,
+    pub xy: f64,
+    pub yy: f64,
+    pub dx: f64,
+    pub dy: f64,
+}
+
+impl Affine {
+    pub fn identity() -> Self {
+        Self::translate(0.0, 0.0)
+    }
+
+    pub fn translate(dx: f64, dy: f64) -> Self {
+        Self {xx: 1.0, yx: 0.0, xy: 0.0, yy: 1.0, dx, dy}
+    }
+
+    pub fn scale(sx: f64, sy: f64) -> Self {
+        Self {xx: sx, yx: 0.0, xy: 0.0, yy: sy, dx: 0.0, dy: 0.0}
+    }
+
+    /// Counter clockwise rotation, in radians
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {xx: cos, yx: sin, xy: -sin, yy: cos, dx: 0.0, dy: 0.0}
+    }
+
+    /// Skew angles in radians, counter clockwise
+    pub fn skew(x_angle: f64, y_angle: f64) -> Self {
+        Self {xx: 1.0, yx: y_angle.tan(), xy: -x_angle.tan(), yy: 1.0, dx: 0.0, dy: 0.0}
+    }
+
+    /// The transform applying `inner` first and then `self`
+    pub fn then(&self, inner: &Affine) -> Affine {
+        Affine {
+            xx: self.xx * inner.xx + self.xy * inner.yx,
+            yx: self.yx * inner.xx + self.yy * inner.yx,
+            xy: self.xx * inner.xy + self.xy * inner.yy,
+            yy: self.yx * inner.xy + self.yy * inner.yy,
+            dx: self.xx * inner.dx + self.xy * inner.dy + self.dx,
+            dy: self.yx * inner.dx + self.yy * inner.dy + self.dy,
+        }
+    }
+
+    // the transform with (cx, cy) as its origin
+    fn around(&self, cx: f64, cy: f64) -> Affine {
+        Affine::translate(cx, cy).then(self).then(&Affine::translate(-cx, -cy))
+    }
+
+    pub fn inverse(&self) -> Option<Affine> {
+        let det = self.xx * self.yy - self.xy * self.yx;
+        if det.abs() < 1e-12 {
+            return None
+        }
+        let xx = self.yy / det;
+        let yx = -self.yx / det;
+        let xy = -self.xy / det;
+        let yy = self.xx / det;
+        Some(Affine {
+            xx,
+            yx,
+            xy,
+            yy,
+            dx: -(xx * self.dx + xy * self.dy),
+            dy: -(yx * self.dx + yy * self.dy),
+        })
+    }
+
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (self.xx * x + self.xy * y + self.dx, self.yx * x + self.yy * y + self.dy)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Extend {
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorLine {
+    pub extend: Extend,
+    // offsets and straight alpha colors, sorted by offset
+    pub stops: Vec<(f64, Vec4)>,
+}
+
+impl ColorLine {
+    /// The premultiplied color at `t` along the line
+    pub fn color_at(&self, t: f64) -> [f32; 4] {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first.0, last.0),
+            _ => return [0.0; 4]
+        };
+        let t = if last - first > 1e-9 {
+            let u = (t - first) / (last - first);
+            let u = match self.extend {
+                Extend::Pad => u.max(0.0).min(1.0),
+                Extend::Repeat => u - u.floor(),
+                Extend::Reflect => {
+                    let u = u.rem_euclid(2.0);
+                    if u > 1.0 {2.0 - u} else {u}
+                }
+            };
+            first + u * (last - first)
+        }
+        else {
+            t
+        };
+        let i = self.stops.partition_point( | stop | stop.0 <= t);
+        let premultiply = | c: Vec4 | [c.x * c.w, c.y * c.w, c.z * c.w, c.w];
+        if i == 0 {
+            return premultiply(self.stops[0].1)
+        }
+        if i == self.stops.len() {
+            return premultiply(self.stops[i - 1].1)
+        }
+        let (t0, c0) = self.stops[i - 1];
+        let (t1, c1) = self.stops[i];
+        let f = ((t - t0) / (t1 - t0)) as f32;
+        let (c0, c1) = (premultiply(c0), premultiply(c1));
+        [0, 1, 2, 3].map( | c | c0[c] + (c1[c] - c0[c]) * f)
+    }
+}
+
+/// What a region is filled with. Gradient coordinates are in the space of the transform
+/// current at the fill.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Brush {
+    Solid(Vec4),
+    LinearGradient {p0: DVec2, p1: DVec2, p2: DVec2, color_line: ColorLine},
+    RadialGradient {c0: DVec2, r0: f64, c1: DVec2, r1: f64, color_line: ColorLine},
+    // angles in radians, counter clockwise
+    SweepGradient {center: DVec2, start_angle: f64, end_angle: f64, color_line: ColorLine},
+}
+
+impl Brush {
+    // premultiplied color at a point, `None` where a radial gradient isn't defined
+    fn color_at(&self, p: DVec2) -> Option<[f32; 4]> {
+        match self {
+            Brush::Solid(c) => Some([c.x * c.w, c.y * c.w, c.z * c.w, c.w]),
+            Brush::LinearGradient {p0, p1, p2, color_line} => {
+                // the gradient runs from p0 along p0->p1 projected onto the normal of p0->p2
+                let n = *p2 - *p0;
+                let normal = dvec2(n.y, -n.x);
+                let d = *p1 - *p0;
+                let normal_len = dot(normal, normal);
+                let dir = if normal_len > 1e-9 {normal * (dot(d, normal) / normal_len)} else {d};
+                let len = dot(dir, dir);
+                if len < 1e-9 {
+                    return Some(color_line.color_at(0.0))
+                }
+                Some(color_line.color_at(dot(p - *p0, dir) / len))
+            }
+            Brush::RadialGradient {c0, r0, c1, r1, color_line} => {
+                // largest t where p lies on the circle interpolated between both circles
+                let cd = *c1 - *c0;
+                let pd = p - *c0;
+                let dr = r1 - r0;
+                let a = dot(cd, cd) - dr * dr;
+                let b = dot(pd, cd) + r0 * dr;
+                let c = dot(pd, pd) - r0 * r0;
+                let t = if a.abs() < 1e-9 {
+                    if b.abs() < 1e-9 {
+                        return None
+                    }
+                    c / (2.0 * b)
+                }
+                else {
+                    let disc = b * b - a * c;
+                    if disc < 0.0 {
+                        return None
+                    }
+                    let t0 = (b + disc.sqrt()) / a;
+                    let t1 = (b - disc.sqrt()) / a;
+                    let (hi, lo) = if t0 > t1 {(t0, t1)} else {(t1, t0)};
+                    if r0 + hi * dr >= 0.0 {hi} else if r0 + lo * dr >= 0.0 {lo} else {return None}
+                };
+                if r0 + t * dr < 0.0 {
+                    return None
+                }
+                Some(color_line.color_at(t))
+            }
+            Brush::SweepGradient {center, start_angle, end_angle, color_line} => {
+                let d = p - *center;
+                let angle = d.y.atan2(d.x).rem_euclid(std::f64::consts::TAU);
+                if (end_angle - start_angle).abs() < 1e-9 {
+                    return Some(color_line.color_at(if angle < *start_angle {0.0} else {1.0}))
+                }
+                Some(color_line.color_at((angle - start_angle) / (end_angle - start_angle)))
+            }
+        }
+    }
+}
+
+fn dot(a: DVec2, b: DVec2) -> f64 {
+    a.x * b.x + a.y * b.y
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CompositeMode {
+    Clear,
+    Src,
+    Dest,
+    SrcOver,
+    DestOver,
+    SrcIn,
+    DestIn,
+    SrcOut,
+    DestOut,
+    SrcAtop,
+    DestAtop,
+    Xor,
+    Plus,
+    Screen,
+    Multiply,
+}
+
+impl CompositeMode {
+    // the blend modes other than screen and multiply are drawn over
+    fn from_u8(mode: u8) -> Self {
+        match mode {
+            0 => Self::Clear,
+            1 => Self::Src,
+            2 => Self::Dest,
+            4 => Self::DestOver,
+            5 => Self::SrcIn,
+            6 => Self::DestIn,
+            7 => Self::SrcOut,
+            8 => Self::DestOut,
+            9 => Self::SrcAtop,
+            10 => Self::DestAtop,
+            11 => Self::Xor,
+            12 => Self::Plus,
+            13 => Self::Screen,
+            23 => Self::Multiply,
+            _ => Self::SrcOver,
+        }
+    }
+
+    fn composite(&self, src: [f32; 4], dst: [f32; 4]) -> [f32; 4] {
+        let (sa, da) = (src[3], dst[3]);
+        let (fa, fb) = match self {
+            Self::Clear => (0.0, 0.0),
+            Self::Src => (1.0, 0.0),
+            Self::Dest => (0.0, 1.0),
+            Self::SrcOver => (1.0, 1.0 - sa),
+            Self::DestOver => (1.0 - da, 1.0),
+            Self::SrcIn => (da, 0.0),
+            Self::DestIn => (0.0, sa),
+            Self::SrcOut => (1.0 - da, 0.0),
+            Self::DestOut => (0.0, 1.0 - sa),
+            Self::SrcAtop => (da, 1.0 - sa),
+            Self::DestAtop => (1.0 - da, sa),
+            Self::Xor => (1.0 - da, 1.0 - sa),
+            Self::Plus => return [0, 1, 2, 3].map( | c | (src[c] + dst[c]).min(1.0)),
+            Self::Screen => return [0, 1, 2, 3].map( | c | src[c] + dst[c] - src[c] * dst[c]),
+            Self::Multiply => return [0, 1, 2, 3].map( | c | {
+                src[c] * (1.0 - da) + dst[c] * (1.0 - sa) + src[c] * dst[c]
+            }),
+        };
+        [0, 1, 2, 3].map( | c | src[c] * fa + dst[c] * fb)
+    }
+}
+
+/// Receives the drawing operations of a color glyph. Fills cover the intersection of the
+/// pushed glyph clips, and draw into the top layer.
+pub trait ColrPainter {
+    fn push_transform(&mut self, transform: Affine);
+    fn pop_transform(&mut self);
+    fn push_clip_glyph(&mut self, glyph_id: u16);
+    fn pop_clip(&mut self);
+    fn fill(&mut self, brush: &Brush);
+    fn push_layer(&mut self);
+    // composites the top layer onto the one below it
+    fn pop_layer(&mut self, mode: CompositeMode);
+}
+
+/// A parsed COLR table, version 0 or 1
+pub struct Colr<'a> {
+    r: Reader<'a>,
+    base_glyphs: usize,
+    num_base_glyphs: usize,
+    layers: usize,
+    num_layers: usize,
+    // offsets of the version 1 lists, 0 when absent
+    base_glyph_list: usize,
+    layer_list: usize,
+    clip_list: usize,
+}
+
+impl<'a> Colr<'a> {
+    pub fn parse(data: &'a [u8]) -> Option<Self> {
+        let r = Reader(data);
+        let version = r.u16(0)?;
+        let mut colr = Colr {
+            r,
+            num_base_glyphs: r.u16(2)? as usize,
+            base_glyphs: r.u32(4)? as usize,
+            layers: r.u32(8)? as usize,
+            num_layers: r.u16(12)? as usize,
+            base_glyph_list: 0,
+            layer_list: 0,
+            clip_list: 0,
+        };
+        if version >= 1 {
+            colr.base_glyph_list = r.u32(14)? as usize;
+            colr.layer_list = r.u32(18)? as usize;
+            colr.clip_list = r.u32(22)? as usize;
+        }
+        Some(colr)
+    }
+
+    pub fn contains(&self, glyph_id: u16) -> bool {
+        self.base_paint(glyph_id).is_some() || self.base_layers(glyph_id).is_some()
+    }
+
+    /// The clip box of a version 1 glyph in font units, as (x_min, y_min, x_max, y_max)
+    pub fn clip_box(&self, glyph_id: u16) -> Option<(f64, f64, f64, f64)> {
+        if self.clip_list == 0 {
+            return None
+        }
+        let r = self.r;
+        let num_clips = r.u32(self.clip_list + 1)? as usize;
+        for i in 0..num_clips {
+            let at = self.clip_list + 5 + i * 7;
+            if (r.u16(at)? ..= r.u16(at + 2)?).contains(&glyph_id) {
+                let clip_box = self.clip_list + r.u24(at + 4)?;
+                return Some((r.i16(clip_box + 1)?, r.i16(clip_box + 3)?, r.i16(clip_box + 5)?, r.i16(clip_box + 7)?))
+            }
+        }
+        None
+    }
+
+    /// Paints a glyph, false when the table has no such color glyph or it is malformed
+    pub fn paint(&self, glyph_id: u16, cpal: Option<&Cpal>, painter: &mut dyn ColrPainter) -> bool {
+        if let Some(paint) = self.base_paint(glyph_id) {
+            return self.paint_at(paint, cpal, painter, 0).is_some()
+        }
+        if let Some((first, count)) = self.base_layers(glyph_id) {
+            for i in first..first + count {
+                let at = self.layers + i * 4;
+                let (layer_glyph, index) = if let (Some(glyph), Some(index)) = (self.r.u16(at), self.r.u16(at + 2)) {
+                    (glyph, index)
+                }
+                else {
+                    return false
+                };
+                painter.push_clip_glyph(layer_glyph);
+                painter.fill(&Brush::Solid(self.color(cpal, index, 1.0)));
+                painter.pop_clip();
+            }
+            return true
+        }
+        false
+    }
+
+    // binary search over records sorted by their leading glyph id
+    fn find_record(&self, start: usize, count: usize, size: usize, glyph_id: u16) -> Option<usize> {
+        let (mut lo, mut hi) = (0, count);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let at = start + mid * size;
+            let id = self.r.u16(at)?;
+            if id == glyph_id {
+                return Some(at)
+            }
+            if id < glyph_id {
+                lo = mid + 1;
+            }
+            else {
+                hi = mid;
+            }
+        }
+        None
+    }
+
+    fn base_paint(&self, glyph_id: u16) -> Option<usize> {
+        if self.base_glyph_list == 0 {
+            return None
+        }
+        let count = self.r.u32(self.base_glyph_list)? as usize;
+        let at = self.find_record(self.base_glyph_list + 4, count, 6, glyph_id)?;
+        Some(self.base_glyph_list + self.r.u32(at + 2)? as usize)
+    }
+
+    fn base_layers(&self, glyph_id: u16) -> Option<(usize, usize)> {
+        if self.base_glyphs == 0 {
+            return None
+        }
+        let at = self.find_record(self.base_glyphs, self.num_base_glyphs, 6, glyph_id)?;
+        let first = self.r.u16(at + 2)? as usize;
+        let count = self.r.u16(at + 4)? as usize;
+        if first + count > self.num_layers {
+            return None
+        }
+        Some((first, count))
+    }
+
+    fn color(&self, cpal: Option<&Cpal>, index: u16, alpha: f64) -> Vec4 {
+        let color = if index == FOREGROUND_INDEX {
+            FOREGROUND
+        }
+        else {
+            cpal.and_then( | cpal | cpal.colors.get(index as usize).cloned()).unwrap_or(FOREGROUND)
+        };
+        vec4(color.x, color.y, color.z, color.w * alpha as f32)
+    }
+
+    fn color_line(&self, at: usize, var: bool, cpal: Option<&Cpal>) -> Option<ColorLine> {
+        let r = self.r;
+        let extend = match r.u8(at)? {
+            1 => Extend::Repeat,
+            2 => Extend::Reflect,
+            _ => Extend::Pad
+        };
+        let num_stops = r.u16(at + 1)? as usize;
+        let size = if var {10} else {6};
+        let mut stops = Vec::with_capacity(num_stops);
+        for i in 0..num_stops {
+            let stop = at + 3 + i * size;
+            stops.push((r.f2dot14(stop)?, self.color(cpal, r.u16(stop + 2)?, r.f2dot14(stop + 4)?)));
+        }
+        stops.sort_by( | a, b | a.0.total_cmp(&b.0));
+        Some(ColorLine {extend, stops})
+    }
+
+    fn paint_transformed(&self, child: usize, transform: Affine, cpal: Option<&Cpal>, painter: &mut dyn ColrPainter, depth: usize) -> Option<()> {
+        painter.push_transform(transform);
+        let result = self.paint_at(child, cpal, painter, depth + 1);
+        painter.pop_transform();
+        result
+    }
+
+    fn paint_at(&self, at: usize, cpal: Option<&Cpal>, painter: &mut dyn ColrPainter, depth: usize) -> Option<()> {
+        if depth > MAX_PAINT_DEPTH {
+            return None
+        }
+        let r = self.r;
+        let format = r.u8(at)?;
+        // formats come in pairs, the odd one being the variable version of the even one
+        // before it, with the same fields followed by variation indices
+        let var = format < 32 && format % 2 == 1;
+        match format {
+            1 => { // PaintColrLayers
+                let num_layers = r.u8(at + 1)? as usize;
+                let first = r.u32(at + 2)? as usize;
+                for i in first..first + num_layers {
+                    let layer = self.layer_list + r.u32(self.layer_list + 4 + i * 4)? as usize;
+                    self.paint_at(layer, cpal, painter, depth + 1)?;
+                }
+            }
+            2 | 3 => { // PaintSolid
+                painter.fill(&Brush::Solid(self.color(cpal, r.u16(at + 1)?, r.f2dot14(at + 3)?)));
+            }
+            4 | 5 => { // PaintLinearGradient
+                let color_line = self.color_line(at + r.u24(at + 1)?, var, cpal)?;
+                painter.fill(&Brush::LinearGradient {
+                    p0: dvec2(r.i16(at + 4)?, r.i16(at + 6)?),
+                    p1: dvec2(r.i16(at + 8)?, r.i16(at + 10)?),
+                    p2: dvec2(r.i16(at + 12)?, r.i16(at + 14)?),
+                    color_line
+                });
+            }
+            6 | 7 => { // PaintRadialGradient
+                let color_line = self.color_line(at + r.u24(at + 1)?, var, cpal)?;
+                painter.fill(&Brush::RadialGradient {
+                    c0: dvec2(r.i16(at + 4)?, r.i16(at + 6)?),
+                    r0: r.u16(at + 8)? as f64,
+                    c1: dvec2(r.i16(at + 10)?, r.i16(at + 12)?),
+                    r1: r.u16(at + 14)? as f64,
+                    color_line
+                });
+            }
+            8 | 9 => { // PaintSweepGradient, angles in half turns
+                let color_line = self.color_line(at + r.u24(at + 1)?, var, cpal)?;
+                painter.fill(&Brush::SweepGradient {
+                    center: dvec2(r.i16(at + 4)?, r.i16(at + 6)?),
+                    start_angle: r.f2dot14(at + 8)? * std::f64::consts::PI,
+                    end_angle: r.f2dot14(at + 10)? * std::f64::consts::PI,
+                    color_line
+                });
+            }
+            10 => { // PaintGlyph
+                let child = at + r.u24(at + 1)?;
+                painter.push_clip_glyph(r.u16(at + 4)?);
+                let result = self.paint_at(child, cpal, painter, depth + 1);
+                painter.pop_clip();
+                result?;
+            }
+            11 => { // PaintColrGlyph
+                let paint = self.base_paint(r.u16(at + 1)?)?;
+                self.paint_at(paint, cpal, painter, depth + 1)?;
+            }
+            12..=31 => {
+                let child = at + r.u24(at + 1)?;
+                let transform = match format {
+                    12 | 13 => {
+                        let t = at + r.u24(at + 4)?;
+                        Affine {
+                            xx: r.fixed(t)?,
+                            yx: r.fixed(t + 4)?,
+                            xy: r.fixed(t + 8)?,
+                            yy: r.fixed(t + 12)?,
+                            dx: r.fixed(t + 16)?,
+                            dy: r.fixed(t + 20)?,
+                        }
+                    }
+                    14 | 15 => Affine::translate(r.i16(at + 4)?, r.i16(at + 6)?),
+                    16 | 17 => Affine::scale(r.f2dot14(at + 4)?, r.f2dot14(at + 6)?),
+                    18 | 19 => Affine::scale(r.f2dot14(at + 4)?, r.f2dot14(at + 6)?)
+                        .around(r.i16(at + 8)?, r.i16(at + 10)?),
+                    20 | 21 => Affine::scale(r.f2dot14(at + 4)?, r.f2dot14(at + 4)?),
+                    22 | 23 => Affine::scale(r.f2dot14(at + 4)?, r.f2dot14(at + 4)?)
+                        .around(r.i16(at + 6)?, r.i16(at + 8)?),
+                    24 | 25 => Affine::rotate(r.f2dot14(at + 4)? * std::f64::consts::PI),
+                    26 | 27 => Affine::rotate(r.f2dot14(at + 4)? * std::f64::consts::PI)
+                        .around(r.i16(at + 6)?, r.i16(at + 8)?),
+                    28 | 29 => Affine::skew(
+                        r.f2dot14(at + 4)? * std::f64::consts::PI,
+                        r.f2dot14(at + 6)? * std::f64::consts::PI
+                    ),
+                    _ => Affine::skew(
+                        r.f2dot14(at + 4)? * std::f64::consts::PI,
+                        r.f2dot14(at + 6)? * std::f64::consts::PI
+                    ).around(r.i16(at + 8)?, r.i16(at + 10)?),
+                };
+                self.paint_transformed(child, transform, cpal, painter, depth)?;
+            }
+            32 => { // PaintComposite
+                let source = at + r.u24(at + 1)?;
+                let mode = CompositeMode::from_u8(r.u8(at + 4)?);
+                let backdrop = at + r.u24(at + 5)?;
+                painter.push_layer();
+                let backdrop_result = self.paint_at(backdrop, cpal, painter, depth + 1);
+                painter.push_layer();
+                let source_result = self.paint_at(source, cpal, painter, depth + 1);
+                painter.pop_layer(mode);
+                painter.pop_layer(CompositeMode::SrcOver);
+                backdrop_result?;
+                source_result?;
+            }
+            _ => return None
+        }
+        Some(())
+    }
+}
+
+// collects the bounds of the glyph outlines a color glyph is clipped to, in font units
+struct BoundsPainter<'a> {
+    face: &'a Face<'a>,
+    transforms: Vec<Affine>,
+    bounds: Option<(f64, f64, f64, f64)>,
+}
+
+impl<'a> ColrPainter for BoundsPainter<'a> {
+    fn push_transform(&mut self, transform: Affine) {
+        let top = self.transforms.last().unwrap().then(&transform);
+        self.transforms.push(top);
+    }
+
+    fn pop_transform(&mut self) {
+        self.transforms.pop();
+    }
+
+    fn push_clip_glyph(&mut self, glyph_id: u16) {
+        let rect = if let Some(rect) = self.face.glyph_bounding_box(GlyphId(glyph_id)) {rect} else {return};
+        let transform = self.transforms.last().unwrap();
+        for (x, y) in [(rect.x_min, rect.y_min), (rect.x_max, rect.y_min), (rect.x_min, rect.y_max), (rect.x_max, rect.y_max)] {
+            let (x, y) = transform.apply(x as f64, y as f64);
+            self.bounds = Some(match self.bounds {
+                Some(b) => (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y)),
+                None => (x, y, x, y)
+            });
+        }
+    }
+
+    fn pop_clip(&mut self) {}
+    fn fill(&mut self, _brush: &Brush) {}
+    fn push_layer(&mut self) {}
+    fn pop_layer(&mut self, _mode: CompositeMode) {}
+}
+
+// paints into premultiplied pixel layers, glyph clips are coverage masks
+struct RasterPainter<'a> {
+    face: &'a Face<'a>,
+    width: usize,
+    height: usize,
+    transforms: Vec<Affine>,
+    clips: Vec<Vec<f32 >>,
+    layers: Vec<Vec<[f32; 4] >>,
+}
+
+impl<'a> ColrPainter for RasterPainter<'a> {
+    fn push_transform(&mut self, transform: Affine) {
+        let top = self.transforms.last().unwrap().then(&transform);
+        self.transforms.push(top);
+    }
+
+    fn pop_transform(&mut self) {
+        self.transforms.pop();
+    }
+
+    fn push_clip_glyph(&mut self, glyph_id: u16) {
+        let mut coverage = Coverage::new(self.width, self.height, *self.transforms.last().unwrap());
+        self.face.outline_glyph(GlyphId(glyph_id), &mut coverage);
+        let mut mask = coverage.into_mask();
+        if let Some(clip) = self.clips.last() {
+            for (m, c) in mask.iter_mut().zip(clip) {
+                *m *= c;
+            }
+        }
+        self.clips.push(mask);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn fill(&mut self, brush: &Brush) {
+        let inverse = if let Some(inverse) = self.transforms.last().unwrap().inverse() {inverse} else {return};
+        let clip = self.clips.last();
+        let layer = self.layers.last_mut().unwrap();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let i = y * self.width + x;
+                let m = clip.map_or(1.0, | clip | clip[i]);
+                if m <= 0.0 {
+                    continue;
+                }
+                let (px, py) = inverse.apply(x as f64 + 0.5, y as f64 + 0.5);
+                if let Some(color) = brush.color_at(dvec2(px, py)) {
+                    let src = color.map( | c | c * m);
+                    layer[i] = CompositeMode::SrcOver.composite(src, layer[i]);
+                }
+            }
+        }
+    }
+
+    fn push_layer(&mut self) {
+        self.layers.push(vec![[0.0; 4]; self.width * self.height]);
+    }
+
+    fn pop_layer(&mut self, mode: CompositeMode) {
+        if self.layers.len() < 2 {
+            return
+        }
+        let src = self.layers.pop().unwrap();
+        let dst = self.layers.last_mut().unwrap();
+        for (d, s) in dst.iter_mut().zip(src) {
+            *d = mode.composite(s, *d);
+        }
+    }
+}
+
+// Coverage of an outline by signed area accumulation: each edge adds the area it covers
+// to the cells it crosses, and a running sum along a row gives the winding coverage. Rows
+// have two extra cells for the edges at and beyond the right border.
+struct Coverage {
+    width: usize,
+    height: usize,
+    acc: Vec<f64>,
+    transform: Affine,
+    start: (f64, f64),
+    last: (f64, f64),
+}
+
+impl Coverage {
+    fn new(width: usize, height: usize, transform: Affine) -> Self {
+        Self {
+            width,
+            height,
+            acc: vec![0.0; (width + 2) * height],
+            transform,
+            start: (0.0, 0.0),
+            last: (0.0, 0.0),
+        }
+    }
+
+    fn into_mask(mut self) -> Vec<f32> {
+        self.close_contour();
+        let stride = self.width + 2;
+        let mut mask = Vec::with_capacity(self.width * self.height);
+        for y in 0..self.height {
+            let mut sum = 0.0;
+            for x in 0..self.width {
+                sum += self.acc[y * stride + x];
+                mask.push(sum.abs().min(1.0) as f32);
+            }
+        }
+        mask
+    }
+
+    fn close_contour(&mut self) {
+        if self.last != self.start {
+            self.line(self.last, self.start);
+        }
+        self.last = self.start;
+    }
+
+    fn line(&mut self, p0: (f64, f64), p1: (f64, f64)) {
+        if p0.1 == p1.1 {
+            return
+        }
+        let (dir, p0, p1) = if p0.1 < p1.1 {(1.0, p0, p1)} else {(-1.0, p1, p0)};
+        let y_start = p0.1.max(0.0);
+        let y_end = p1.1.min(self.height as f64);
+        if y_start >= y_end {
+            return
+        }
+        let stride = self.width + 2;
+        let max_x = self.width as f64;
+        let dxdy = (p1.0 - p0.0) / (p1.1 - p0.1);
+        let mut x = p0.0 + (y_start - p0.1) * dxdy;
+        for y in y_start.floor() as usize..y_end.ceil() as usize {
+            let row = y * stride;
+            let dy = ((y + 1) as f64).min(y_end) - (y as f64).max(y_start);
+            let x_next = x + dxdy * dy;
+            let d = dy * dir;
+            let (x0, x1) = if x < x_next {(x, x_next)} else {(x_next, x)};
+            let (x0, x1) = (x0.max(0.0).min(max_x), x1.max(0.0).min(max_x));
+            let x0_floor = x0.floor();
+            let x0i = x0_floor as usize;
+            let x1_ceil = x1.ceil();
+            let x1i = x1_ceil as usize;
+            if x1i <= x0i + 1 {
+                let xmf = 0.5 * (x0 + x1) - x0_floor;
+                self.acc[row + x0i] += d - d * xmf;
+                self.acc[row + x0i + 1] += d * xmf;
+            }
+            else {
+                let s = 1.0 / (x1 - x0);
+                let x0f = x0 - x0_floor;
+                let a0 = 0.5 * s * (1.0 - x0f) * (1.0 - x0f);
+                let x1f = x1 - x1_ceil + 1.0;
+                let am = 0.5 * s * x1f * x1f;
+                self.acc[row + x0i] += d * a0;
+                if x1i == x0i + 2 {
+                    self.acc[row + x0i + 1] += d * (1.0 - a0 - am);
+                }
+                else {
+                    let a1 = s * (1.5 - x0f);
+                    self.acc[row + x0i + 1] += d * (a1 - a0);
+                    for xi in x0i + 2..x1i - 1 {
+                        self.acc[row + xi] += d * s;
+                    }
+                    let a2 = a1 + (x1i - x0i - 3) as f64 * s;
+                    self.acc[row + x1i - 1] += d * (1.0 - a2 - am);
+                }
+                self.acc[row + x1i] += d * am;
+            }
+            x = x_next;
+        }
+    }
+
+    // curves are flattened into lines of about two pixels
+    fn curve(&mut self, points: &[(f64, f64)]) {
+        let p0 = self.last;
+        let mut len = 0.0;
+        let mut prev = p0;
+        for p in points {
+            len += ((p.0 - prev.0).powi(2) + (p.1 - prev.1).powi(2)).sqrt();
+            prev = *p;
+        }
+        let steps = (len / 2.0).ceil().max(1.0).min(64.0) as usize;
+        for step in 1..=steps {
+            let t = step as f64 / steps as f64;
+            let u = 1.0 - t;
+            let p = match points {
+                [c, p] => (
+                    u * u * p0.0 + 2.0 * u * t * c.0 + t * t * p.0,
+                    u * u * p0.1 + 2.0 * u * t * c.1 + t * t * p.1
+                ),
+                [c1, c2, p] => (
+                    u * u * u * p0.0 + 3.0 * u * u * t * c1.0 + 3.0 * u * t * t * c2.0 + t * t * t * p.0,
+                    u * u * u * p0.1 + 3.0 * u * u * t * c1.1 + 3.0 * u * t * t * c2.1 + t * t * t * p.1
+                ),
+                _ => return
+            };
+            self.line(self.last, p);
+            self.last = p;
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for Coverage {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close_contour();
+        let p = self.transform.apply(x as f64, y as f64);
+        self.start = p;
+        self.last = p;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.transform.apply(x as f64, y as f64);
+        self.line(self.last, p);
+        self.last = p;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let c = self.transform.apply(x1 as f64, y1 as f64);
+        let p = self.transform.apply(x as f64, y as f64);
+        self.curve(&[c, p]);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let c1 = self.transform.apply(x1 as f64, y1 as f64);
+        let c2 = self.transform.apply(x2 as f64, y2 as f64);
+        let p = self.transform.apply(x as f64, y as f64);
+        self.curve(&[c1, c2, p]);
+    }
+
+    fn close(&mut self) {
+        self.close_contour();
+    }
+}
@@ -169,7 +169,7 @@ impl DrawList2d {
             /*
             let w = Size::Fixed(cx.cx.draw_lists[self.draw_list.id()].rect.size.x);
             let h = Size::Fixed(cx.cx.draw_lists[self.draw_list.id()].rect.size.y);
-            let walk = Walk {abs_pos: None, width: w, height: h, margin: walk.margin};
+            let walk = Walk {abs_pos: None, width: w, height: h, ..walk};
             //let pos = cx.peek_walk_pos(walk);
             //if pos == cx.cx.draw_lists[self.draw_list.id()].rect.pos {
              cx.walk_turtle(walk);*/
@@ -8,6 +8,7 @@ pub use {
         io::prelude::*,
         fs::File,
         collections::HashMap,
+        ops::Range,
     },
     crate::{
         makepad_platform::*,
@@ -21,6 +22,8 @@ pub use {
         makepad_vector::geometry::{AffineTransformation, Transform, Vector},
         makepad_vector::internal_iter::ExtendFromInternalIterator,
         makepad_vector::path::PathIterator,
+        system_fonts::SystemFonts,
+        color_glyph::{self, ColorGlyphImage},
     },
     rustybuzz::{Direction, GlyphInfo, UnicodeBuffer},
 };
@@ -30,7 +33,9 @@ pub struct CxFontsAtlas {
     pub path_to_font_id: HashMap<String, usize>,
     pub texture_id: TextureId,
     pub clear_buffer: bool,
-    pub alloc: CxFontsAtlasAlloc
+    pub alloc: CxFontsAtlasAlloc,
+    pub color: CxColorAtlas,
+    pub system_fonts: SystemFonts,
 }
 
 #[derive(Default)]
@@ -43,13 +48,27 @@ pub struct CxFontsAtlasAlloc {
     pub todo: Vec<CxFontsAtlasTodo>,
 }
 
+/// The RGBA page of the atlas for color glyphs. Unlike the outline glyphs these are
+/// rendered on the cpu, each image is queued in `uploads` and copied into its rect of the
+/// texture when the atlas is drawn. Once the page is full it is emptied, and the glyphs
+/// are added again as they are drawn.
+pub struct CxColorAtlas {
+    pub texture_id: TextureId,
+    pub alloc: CxFontsAtlasAlloc,
+    // premultiplied 0xAARRGGBB rows of each image, top row first
+    pub uploads: Vec<(TextureRect, Vec<u32>)>,
+    // whether the texture has its size and an image to update
+    pub texture_ready: bool,
+}
+
 impl CxFontsAtlas {
-    pub fn new(texture_id: TextureId) -> Self {
+    pub fn new(texture_id: TextureId, color_texture_id: TextureId) -> Self {
         Self {
             fonts: Vec::new(),
             path_to_font_id: HashMap::new(),
             texture_id,
             clear_buffer: false,
+            system_fonts: SystemFonts::default(),
             alloc: CxFontsAtlasAlloc {
                 full: false,
                 texture_size: DVec2 {x: 4096.0, y: 4096.0},
@@ -57,10 +76,76 @@ impl CxFontsAtlas {
                 ypos: 0.0,
                 hmax: 0.0,
                 todo: Vec::new(),
+            },
+            color: CxColorAtlas {
+                texture_id: color_texture_id,
+                alloc: CxFontsAtlasAlloc {
+                    texture_size: DVec2 {x: 1024.0, y: 1024.0},
+                    ..Default::default()
+                },
+                uploads: Vec::new(),
+                texture_ready: false,
             }
         }
     }
 }
+
+impl CxColorAtlas {
+    /// The color glyph for a glyph id of the page's size, rendered and added to the atlas
+    /// the first time. `None` for glyphs that aren't colored, which is cached as well.
+    pub fn get_glyph(&mut self, face: &rustybuzz::Face, atlas_page: &mut CxFontAtlasPage, glyph_id: usize) -> Option<CxColorGlyph> {
+        if let Some(glyph) = atlas_page.color_glyphs.get(&glyph_id) {
+            return *glyph
+        }
+        let ppem = atlas_page.font_size * 96.0 / 72.0 * atlas_page.dpi_factor;
+        let Some(image) = color_glyph::render_color_glyph(face, glyph_id as u16, ppem) else {
+            atlas_page.color_glyphs.insert(glyph_id, None);
+            return None
+        };
+        // a glyph that didn't fit is rendered again once the page is emptied
+        let glyph = self.add_image(&image)?;
+        atlas_page.color_glyphs.insert(glyph_id, Some(glyph));
+        Some(glyph)
+    }
+    
+    /// Queues the image for upload in a free rect of the page, `None` once the page is full.
+    pub fn add_image(&mut self, image: &ColorGlyphImage) -> Option<CxColorGlyph> {
+        let size = self.alloc.texture_size;
+        let tc = self.alloc.alloc_atlas_glyph(image.width as f64, image.height as f64);
+        if self.alloc.full {
+            return None
+        }
+        let x = (tc.t1.x as f64 * size.x).round() as usize;
+        let y = (tc.t1.y as f64 * size.y).round() as usize;
+        // images as wide as the page are cut off at its edge
+        let width = image.width.min(size.x as usize - x);
+        let height = image.height.min(size.y as usize - y);
+        let pixels = if width == image.width {
+            image.pixels[..width * height].to_vec()
+        }
+        else {
+            image.pixels.chunks(image.width).take(height).flat_map( | row | &row[..width]).cloned().collect()
+        };
+        self.uploads.push((TextureRect {x, y, width, height}, pixels));
+        // images are stored top row first, and drawn from their bottom left corner
+        Some(CxColorGlyph {
+            t1: vec2(tc.t1.x, tc.t2.y),
+            t2: vec2(tc.t2.x, tc.t1.y),
+            left: image.left,
+            bottom: image.bottom,
+            width: image.width as f64,
+            height: image.height as f64,
+        })
+    }
+    
+    fn reset(&mut self) {
+        self.alloc.xpos = 0.;
+        self.alloc.ypos = 0.;
+        self.alloc.hmax = 0.;
+        self.alloc.full = false;
+        self.uploads.clear();
+    }
+}
 impl CxFontsAtlasAlloc {
     pub fn alloc_atlas_glyph(&mut self, w: f64, h: f64) -> CxFontAtlasGlyph {
         if w + self.xpos >= self.texture_size.x {
@@ -95,6 +180,20 @@ pub struct Font {
     #[live] pub path: LiveDependency
 }
 
+/// The fonts tried in order for each character of a text, built from a `TextStyle`.
+/// The first font is the primary one, its metrics are used for the line layout.
+#[derive(Clone, Debug, Default)]
+pub struct FontChain {
+    pub font_ids: Vec<usize>,
+    pub system_fallback: bool,
+}
+
+impl FontChain {
+    pub fn primary(&self) -> Option<usize> {
+        self.font_ids.first().cloned()
+    }
+}
+
 #[derive(Clone)]
 pub struct CxFontsAtlasRc(pub Rc<RefCell<CxFontsAtlas >>);
 
@@ -136,6 +235,94 @@ impl CxFontsAtlas {
         font_id
     }
     
+    fn has_glyph(&self, font_id: usize, c: char) -> bool {
+        if let Some(Some(cxfont)) = self.fonts.get(font_id) {
+            cxfont.has_glyph(c)
+        }
+        else {
+            false
+        }
+    }
+    
+    /// Loads the installed font covering the character, if the system has one
+    pub fn get_system_font_for_char(&mut self, c: char) -> Option<usize> {
+        loop {
+            let face_index = self.system_fonts.find_face(c)?;
+            let face = self.system_fonts.face(face_index);
+            if let Some(font_id) = face.font_id {
+                return Some(font_id)
+            }
+            let path = face.path.clone();
+            let index = face.index;
+            let cxfont = std::fs::read(&path).ok().and_then( | data | {
+                CxFont::load_from_collection_bytes(Rc::new(data), index).ok()
+            });
+            if let Some(cxfont) = cxfont {
+                let font_id = self.fonts.len();
+                self.fonts.push(Some(cxfont));
+                self.path_to_font_id.insert(format!("{}#{}", path.display(), index), font_id);
+                self.system_fonts.face(face_index).font_id = Some(font_id);
+                return Some(font_id)
+            }
+            error!("Error loading system font {}", path.display());
+            self.system_fonts.forget_face(face_index);
+        }
+    }
+    
+    /// The font a character is drawn with: the first font of the chain that has a glyph
+    /// for it, then a system font if the chain allows it. Characters no font covers use
+    /// the primary font, which draws its missing glyph.
+    pub fn font_for_char(&mut self, chain: &FontChain, c: char) -> Option<usize> {
+        if let Some(font_id) = chain.font_ids.iter().cloned().find( | font_id | self.has_glyph(*font_id, c)) {
+            return Some(font_id)
+        }
+        if chain.system_fallback && !c.is_control() {
+            if let Some(font_id) = self.get_system_font_for_char(c) {
+                return Some(font_id)
+            }
+        }
+        chain.font_ids.iter().cloned().find( | font_id | matches!(self.fonts.get(*font_id), Some(Some(_))))
+    }
+    
+    /// Splits a text into byte ranges that are each shaped with a single font, appended to
+    /// `runs` in logical order. Whitespace and joiners stay in the run before them when its
+    /// font has them, so a phrase in a fallback script is shaped as a whole.
+    pub fn font_runs(&mut self, chain: &FontChain, text: &str, runs: &mut Vec<(usize, Range<usize>)>) {
+        let mut current: Option<(usize, Range<usize>)> = None;
+        for (i, c) in text.char_indices() {
+            let end = i + c.len_utf8();
+            if let Some((font_id, range)) = &mut current {
+                if (c.is_whitespace() || is_joiner(c) || c.is_control()) && self.has_glyph(*font_id, c) {
+                    range.end = end;
+                    continue;
+                }
+            }
+            let font_id = if let Some(font_id) = self.font_for_char(chain, c) {font_id} else {return};
+            match &mut current {
+                Some((current_id, range)) if *current_id == font_id => range.end = end,
+                _ => {
+                    if let Some(run) = current.take() {
+                        runs.push(run);
+                    }
+                    current = Some((font_id, i..end));
+                }
+            }
+        }
+        if let Some(run) = current {
+            runs.push(run);
+        }
+    }
+    
+    /// The advance of a character in ems of the font it is drawn with
+    pub fn char_advance(&mut self, chain: &FontChain, c: char) -> Option<f64> {
+        let font_id = self.font_for_char(chain, c)?;
+        let cxfont = self.fonts[font_id].as_mut().unwrap();
+        let glyph_id = cxfont.owned_font_face.with_ref( | face | face.glyph_index(c))?.0 as usize;
+        let units_per_em = cxfont.ttf_font.units_per_em;
+        let glyph = cxfont.get_glyph_by_id(glyph_id).ok()?;
+        Some(glyph.horizontal_metrics.advance_width / units_per_em)
+    }
+    
     pub fn reset_fonts_atlas(&mut self) {
         for cxfont in &mut self.fonts {
             if let Some(cxfont) = cxfont {
@@ -146,6 +333,17 @@ impl CxFontsAtlas {
         self.alloc.ypos = 0.;
         self.alloc.hmax = 0.;
         self.clear_buffer = true;
+        self.color.reset();
+    }
+    
+    /// Empties the color page, the color glyphs are added again as they are drawn
+    pub fn reset_color_atlas(&mut self) {
+        for cxfont in self.fonts.iter_mut().flatten() {
+            for atlas_page in &mut cxfont.atlas_pages {
+                atlas_page.color_glyphs.clear();
+            }
+        }
+        self.color.reset();
     }
     
     pub fn get_internal_font_atlas_texture_id(&self) -> TextureId {
@@ -153,6 +351,11 @@ impl CxFontsAtlas {
     }
 }
 
+// zero width joiners and variation selectors belong with the character before them
+fn is_joiner(c: char) -> bool {
+    matches!(c, '\u{200C}' | '\u{200D}' | '\u{FE00}'..='\u{FE0F}' | '\u{E0100}'..='\u{E01EF}')
+}
+
 impl DrawTrapezoidVector {
     
     // atlas drawing function used by CxAfterDraw
@@ -229,6 +432,7 @@ pub struct CxDrawFontsAtlas {
     pub atlas_pass: Pass,
     pub atlas_draw_list: DrawList2d,
     pub atlas_texture: Texture,
+    pub color_texture: Texture,
     pub counter: usize
 }
 
@@ -246,7 +450,8 @@ impl CxDrawFontsAtlas {
             draw_trapezoid,
             atlas_pass: Pass::new(cx),
             atlas_draw_list: DrawList2d::new(cx),
-            atlas_texture: atlas_texture
+            atlas_texture: atlas_texture,
+            color_texture: Texture::new(cx),
         }
     }
 }
@@ -258,13 +463,26 @@ impl<'a> Cx2d<'a> {
             
             let draw_fonts_atlas = CxDrawFontsAtlas::new(cx);
             let texture_id = draw_fonts_atlas.atlas_texture.texture_id();
+            let color_texture_id = draw_fonts_atlas.color_texture.texture_id();
             cx.set_global(CxDrawFontsAtlasRc(Rc::new(RefCell::new(draw_fonts_atlas))));
             
-            let fonts_atlas = CxFontsAtlas::new(texture_id);
+            let fonts_atlas = CxFontsAtlas::new(texture_id, color_texture_id);
             cx.set_global(CxFontsAtlasRc(Rc::new(RefCell::new(fonts_atlas))));
         }
     }
     
+    /// Redraws everything once the system fonts are scanned, so the characters that were
+    /// waiting for a fallback font get one. Call on `Event::Signal`, it only does work
+    /// when the signal came from the scan.
+    pub fn handle_system_fonts_scan(cx: &mut Cx) {
+        if cx.has_global::<CxFontsAtlasRc>() {
+            let done = cx.get_global::<CxFontsAtlasRc>().0.borrow_mut().system_fonts.handle_scan_done();
+            if done {
+                cx.redraw_all();
+            }
+        }
+    }
+    
     pub fn reset_fonts_atlas(cx:&mut Cx){
         if cx.has_global::<CxFontsAtlasRc>() {
             let mut fonts_atlas = cx.get_global::<CxFontsAtlasRc>().0.borrow_mut();
@@ -279,6 +497,27 @@ impl<'a> Cx2d<'a> {
         let mut fonts_atlas = fonts_atlas_rc.0.borrow_mut();
         let fonts_atlas = &mut*fonts_atlas;
         //let start = Cx::profile_time_ns();
+        // glyphs that didn't fit were left out of this frame, they are drawn in the next one
+        if fonts_atlas.color.alloc.full {
+            fonts_atlas.reset_color_atlas();
+            self.cx.redraw_all();
+        }
+        if !fonts_atlas.color.uploads.is_empty() {
+            if !fonts_atlas.color.texture_ready {
+                fonts_atlas.color.texture_ready = true;
+                let size = fonts_atlas.color.alloc.texture_size;
+                let (width, height) = (size.x as usize, size.y as usize);
+                draw_fonts_atlas.color_texture.set_desc(self.cx, TextureDesc {
+                    format: TextureFormat::ImageBGRA,
+                    width: Some(width),
+                    height: Some(height),
+                });
+                draw_fonts_atlas.color_texture.swap_image_u32(self.cx, &mut vec![0; width * height]);
+            }
+            for (rect, pixels) in fonts_atlas.color.uploads.drain(..) {
+                draw_fonts_atlas.color_texture.update_image_rect(self.cx, rect, &pixels);
+            }
+        }
         // we need to start a pass that just uses the texture
         if fonts_atlas.alloc.todo.len()>0 {
             self.begin_pass(&draw_fonts_atlas.atlas_pass, None);
@@ -323,6 +562,8 @@ pub struct CxFont {
     pub owned_font_face: crate::owned_font_face::OwnedFace,
     pub atlas_pages: Vec<CxFontAtlasPage>,
     pub shape_cache: ShapeCache,
+    // the face has COLR, CBDT or sbix glyphs, looked up before drawing the outline
+    pub has_color_glyphs: bool,
 }
 
 pub struct ShapeCache {
@@ -456,7 +697,8 @@ pub const ATLAS_SUBPIXEL_SLOTS: usize = 64;
 pub struct CxFontAtlasPage {
     pub dpi_factor: f64,
     pub font_size: f64,
-    pub atlas_glyphs: HashMap<usize,[Option<CxFontAtlasGlyph>; ATLAS_SUBPIXEL_SLOTS]>
+    pub atlas_glyphs: HashMap<usize,[Option<CxFontAtlasGlyph>; ATLAS_SUBPIXEL_SLOTS]>,
+    pub color_glyphs: HashMap<usize, Option<CxColorGlyph>>,
 }
 
 #[derive(Clone, Copy)]
@@ -465,6 +707,18 @@ pub struct CxFontAtlasGlyph {
     pub t2: Vec2,
 }
 
+/// A glyph in the color atlas, `t1` is the texture coordinate of its bottom left corner.
+/// The placement is in pixels at the page's dpi factor, y up from the baseline.
+#[derive(Clone, Copy)]
+pub struct CxColorGlyph {
+    pub t1: Vec2,
+    pub t2: Vec2,
+    pub left: f64,
+    pub bottom: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
 #[derive(Default, Debug)]
 pub struct CxFontsAtlasTodo {
     pub subpixel_x_fract: f64,
@@ -477,13 +731,20 @@ pub struct CxFontsAtlasTodo {
 
 impl CxFont {
     pub fn load_from_ttf_bytes(bytes: Rc<Vec<u8>>) -> Result<Self, crate::owned_font_face::FaceParsingError> {
-        let owned_font_face = crate::owned_font_face::OwnedFace::parse(bytes, 0)?;
+        Self::load_from_collection_bytes(bytes, 0)
+    }
+    
+    /// Loads one face of a font collection (.ttc), or of a plain font file with index 0
+    pub fn load_from_collection_bytes(bytes: Rc<Vec<u8>>, index: u32) -> Result<Self, crate::owned_font_face::FaceParsingError> {
+        let owned_font_face = crate::owned_font_face::OwnedFace::parse(bytes, index)?;
         let ttf_font = owned_font_face.with_ref(|face| makepad_vector::ttf_parser::from_ttf_parser_face(face));
+        let has_color_glyphs = owned_font_face.with_ref(|face| color_glyph::has_color_tables(face));
         Ok(Self {
             ttf_font,
             owned_font_face,
             atlas_pages: Vec::new(),
             shape_cache: ShapeCache::new(),
+            has_color_glyphs,
         })
     }
     
@@ -497,7 +758,8 @@ impl CxFont {
         self.atlas_pages.push(CxFontAtlasPage {
             dpi_factor: dpi_factor,
             font_size: font_size,
-            atlas_glyphs:HashMap::new(),/* {
+            atlas_glyphs:HashMap::new(),
+            color_glyphs: HashMap::new(),/* {
                 let mut v = Vec::new();
                 v.resize(self.owned_font_face.with_ref(|face| face.number_of_glyphs() as usize), [None; ATLAS_SUBPIXEL_SLOTS]);
                 v
@@ -506,6 +768,10 @@ impl CxFont {
         self.atlas_pages.len() - 1
     }
 
+    pub fn has_glyph(&self, c: char) -> bool {
+        self.owned_font_face.with_ref( | face | face.glyph_index(c).is_some())
+    }
+
     pub fn get_glyph(&mut self, c:char)->Option<&Glyph>{
         if c < '\u{10000}' {
             Some(self.get_glyph_by_id(self.owned_font_face.with_ref(|face| face.glyph_index(c))?.0 as usize).unwrap())
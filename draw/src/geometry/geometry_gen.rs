@@ -12,6 +12,12 @@ live_design!{
         x2: 1.0;
         y2: 1.0;
     }
+    
+    GeometryCube3D = {{GeometryCube3D}} {
+        x_size: 1.0;
+        y_size: 1.0;
+        z_size: 1.0;
+    }
 }
 
 impl LiveHook for GeometryQuad2D {
@@ -61,6 +67,56 @@ pub struct GeometryQuad2D {
     #[live(1.0)] pub y2: f32,
 }
 
+impl LiveHook for GeometryCube3D {
+    fn after_apply(&mut self, cx: &mut Cx, _apply_from:ApplyFrom, _index:usize, _nodes:&[LiveNode]) {
+        let mut fp = GeometryFingerprint::new(LiveType::of::<Self>());
+        fp.push(self.x_size);
+        fp.push(self.y_size);
+        fp.push(self.z_size);
+        self.geometry_ref = Some(cx.get_geometry_ref(fp));
+        GeometryGen::from_cube_3d(
+            self.x_size,
+            self.y_size,
+            self.z_size,
+            1,
+            1,
+            1
+        ).to_geometry(cx, &self.geometry_ref.as_ref().unwrap().0);
+    }
+}
+
+impl GeometryFields for GeometryCube3D {
+    fn geometry_fields(&self, fields: &mut Vec<GeometryField>) {
+        fields.push(GeometryField {id: live_id!(geom_pos), ty: ShaderTy::Vec3});
+        // shaders rarely mention geom_id, so its name has to be registered for the generated code
+        fields.push(GeometryField {id: LiveId::from_str_with_lut("geom_id").unwrap(), ty: ShaderTy::Float});
+        fields.push(GeometryField {id: live_id!(geom_normal), ty: ShaderTy::Vec3});
+        fields.push(GeometryField {id: live_id!(geom_uv), ty: ShaderTy::Vec2});
+    }
+    
+    fn get_geometry_id(&self) -> Option<GeometryId> {
+        if let Some(gr) = &self.geometry_ref{
+            Some(gr.0.geometry_id())
+        }
+        else{
+            None
+        }
+    }
+    
+    fn live_type_check(&self) -> LiveType {
+        LiveType::of::<Self>()
+    }
+}
+
+/// A centered box with the 3d vertex layout of `GeometryGen`, meshes with the same layout can take its place.
+#[derive(Live)]
+pub struct GeometryCube3D {
+    #[rust] pub geometry_ref: Option<GeometryRef>,
+    #[live(1.0)] pub x_size: f32,
+    #[live(1.0)] pub y_size: f32,
+    #[live(1.0)] pub z_size: f32,
+}
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct GeometryGen {
     pub vertices: Vec<f32>, // vec4 pos, vec3 normal, vec2 uv
@@ -0,0 +1,310 @@
+use crate::{
+    makepad_platform::*,
+    makepad_platform::makepad_micro_serde::JsonValue,
+    geometry::GeometryGen,
+};
+
+/// The static meshes of a binary glTF 2.0 (.glb) file with their base colors.
+/// Vertices are laid out like the 3d shapes of `GeometryGen`: position, id, normal and uv.
+#[derive(Clone, Debug, Default)]
+pub struct GltfScene {
+    pub meshes: Vec<GltfMesh>,
+    pub materials: Vec<GltfMaterial>,
+    pub images: Vec<GltfImage>,
+    /// Every mesh placed by a node of the default scene, with its world transform.
+    pub instances: Vec<GltfInstance>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GltfMesh {
+    pub primitives: Vec<GltfPrimitive>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfPrimitive {
+    pub geometry: GeometryGen,
+    pub material: Option<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfMaterial {
+    pub base_color: Vec4,
+    /// Index into the images of the scene.
+    pub base_color_image: Option<usize>,
+}
+
+/// An image embedded in the binary chunk, still encoded.
+#[derive(Clone, Debug)]
+pub struct GltfImage {
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GltfInstance {
+    pub mesh: usize,
+    pub transform: Mat4,
+}
+
+impl GltfScene {
+    pub fn from_glb(data: &[u8]) -> Result<GltfScene, String> {
+        let read_u32 = | offset: usize | -> Result<u32, String> {
+            if let Some(bytes) = data.get(offset..offset + 4) {
+                Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            }
+            else {
+                Err("Unexpected end of glb file".to_string())
+            }
+        };
+        if data.get(0..4) != Some(b"glTF") {
+            return Err("Not a glb file".to_string())
+        }
+        if read_u32(4)? != 2 {
+            return Err(format!("Unsupported glTF version {}", read_u32(4)?))
+        }
+        let mut json = None;
+        let mut bin: &[u8] = &[];
+        let mut offset = 12;
+        while offset + 8 <= data.len() {
+            let len = read_u32(offset)? as usize;
+            let kind = read_u32(offset + 4)?;
+            let chunk = data.get(offset + 8..offset + 8 + len).ok_or("glb chunk out of bounds")?;
+            match kind {
+                0x4E4F534A => json = Some(std::str::from_utf8(chunk).map_err( | _ | "glb json chunk is not utf8")?),
+                0x004E4942 => bin = chunk,
+                _ => ()
+            }
+            offset += 8 + len;
+        }
+        let json = JsonValue::parse(json.ok_or("glb file has no json chunk")?).map_err( | err | format!("glTF json: {}", err))?;
+        let reader = GltfReader {json: &json, bin};
+
+        let mut scene = GltfScene::default();
+        for image in json["images"].items() {
+            scene.images.push(GltfImage {
+                mime_type: image["mimeType"].as_str().unwrap_or("").to_string(),
+                // images with an uri are external files and left empty
+                data: if let Some(view) = image["bufferView"].as_usize() {
+                    reader.buffer_view(view)?.0.to_vec()
+                } else {Vec::new()}
+            });
+        }
+        for material in json["materials"].items() {
+            let pbr = &material["pbrMetallicRoughness"];
+            let factor: Vec<f32> = pbr["baseColorFactor"].items().iter().filter_map( | v | v.as_f64()).map( | v | v as f32).collect();
+            scene.materials.push(GltfMaterial {
+                base_color: if factor.len() == 4 {vec4(factor[0], factor[1], factor[2], factor[3])} else {vec4(1.0, 1.0, 1.0, 1.0)},
+                base_color_image: pbr["baseColorTexture"]["index"].as_usize()
+                    .and_then( | texture | json["textures"][texture]["source"].as_usize())
+                    .filter( | image | scene.images.get(*image).map_or(false, | image | !image.data.is_empty()))
+            });
+        }
+        for mesh in json["meshes"].items() {
+            let mut primitives = Vec::new();
+            for primitive in mesh["primitives"].items() {
+                // only triangle lists
+                if primitive["mode"].as_usize().unwrap_or(4) != 4 {
+                    continue;
+                }
+                primitives.push(GltfPrimitive {
+                    geometry: reader.primitive_geometry(primitive)?,
+                    material: primitive["material"].as_usize(),
+                });
+            }
+            scene.meshes.push(GltfMesh {primitives});
+        }
+
+        let nodes = json["nodes"].items();
+        let scenes = &json["scenes"];
+        let roots: Vec<usize> = if !scenes.items().is_empty() {
+            scenes[json["scene"].as_usize().unwrap_or(0)]["nodes"].items().iter().filter_map( | v | v.as_usize()).collect()
+        }
+        else {
+            // without scenes every node that isn't a child is a root
+            (0..nodes.len()).filter( | node | !nodes.iter().any( | parent | parent["children"].items().iter().any( | child | child.as_usize() == Some(*node)))).collect()
+        };
+        let mut stack: Vec<(usize, Mat4, usize)> = roots.into_iter().map( | node | (node, Mat4::identity(), 0)).collect();
+        while let Some((node, parent, depth)) = stack.pop() {
+            let node_json = if let Some(node_json) = nodes.get(node) {node_json} else {
+                return Err(format!("Node {} does not exist", node))
+            };
+            if depth > 64 {
+                return Err("glTF node hierarchy is too deep".to_string())
+            }
+            // Mat4::mul(a, b) applies a first, so this is parent * local
+            let transform = Mat4::mul(&node_transform(node_json), &parent);
+            if let Some(mesh) = node_json["mesh"].as_usize() {
+                if mesh < scene.meshes.len() {
+                    scene.instances.push(GltfInstance {mesh, transform});
+                }
+            }
+            for child in node_json["children"].items() {
+                if let Some(child) = child.as_usize() {
+                    stack.push((child, transform, depth + 1));
+                }
+            }
+        }
+        Ok(scene)
+    }
+}
+
+fn node_transform(node: &JsonValue) -> Mat4 {
+    let floats = | key: &str | -> Vec<f32> {node[key].items().iter().filter_map( | v | v.as_f64()).map( | v | v as f32).collect()};
+    let matrix = floats("matrix");
+    if matrix.len() == 16 {
+        let mut v = [0.0; 16];
+        v.copy_from_slice(&matrix);
+        return Mat4 {v}
+    }
+    let t = Some(floats("translation")).filter( | t | t.len() == 3).unwrap_or(vec![0.0, 0.0, 0.0]);
+    let r = Some(floats("rotation")).filter( | r | r.len() == 4).unwrap_or(vec![0.0, 0.0, 0.0, 1.0]);
+    let s = Some(floats("scale")).filter( | s | s.len() == 3).unwrap_or(vec![1.0, 1.0, 1.0]);
+    let (x, y, z, w) = (r[0], r[1], r[2], r[3]);
+    // translation * rotation * scale, column major
+    Mat4 {v: [
+        (1.0 - 2.0 * (y * y + z * z)) * s[0], 2.0 * (x * y + z * w) * s[0], 2.0 * (x * z - y * w) * s[0], 0.0,
+        2.0 * (x * y - z * w) * s[1], (1.0 - 2.0 * (x * x + z * z)) * s[1], 2.0 * (y * z + x * w) * s[1], 0.0,
+        2.0 * (x * z + y * w) * s[2], 2.0 * (y * z - x * w) * s[2], (1.0 - 2.0 * (x * x + y * y)) * s[2], 0.0,
+        t[0], t[1], t[2], 1.0
+    ]}
+}
+
+struct GltfReader<'a> {
+    json: &'a JsonValue,
+    bin: &'a [u8],
+}
+
+impl<'a> GltfReader<'a> {
+    fn buffer_view(&self, index: usize) -> Result<(&'a [u8], Option<usize>), String> {
+        let view = &self.json["bufferViews"][index];
+        if view["buffer"].as_usize() != Some(0) || self.json["buffers"][0]["uri"].as_str().is_some() {
+            return Err("Only the binary chunk of a glb file is supported as buffer".to_string())
+        }
+        let offset = view["byteOffset"].as_usize().unwrap_or(0);
+        let len = view["byteLength"].as_usize().ok_or("bufferView without byteLength")?;
+        let data = offset.checked_add(len).and_then( | end | self.bin.get(offset..end)).ok_or("bufferView out of bounds")?;
+        Ok((data, view["byteStride"].as_usize()))
+    }
+
+    // reads every component of an accessor as float, with the number of components per element
+    fn accessor(&self, index: usize) -> Result<(Vec<f32>, usize), String> {
+        let accessor = &self.json["accessors"][index];
+        let count = accessor["count"].as_usize().ok_or("accessor without count")?;
+        let components = match accessor["type"].as_str() {
+            Some("SCALAR") => 1,
+            Some("VEC2") => 2,
+            Some("VEC3") => 3,
+            Some("VEC4") => 4,
+            ty => return Err(format!("Unsupported accessor type {:?}", ty))
+        };
+        let component_type = accessor["componentType"].as_usize().unwrap_or(0);
+        let (size, max) = match component_type {
+            5120 => (1, 127.0),
+            5121 => (1, 255.0),
+            5122 => (2, 32767.0),
+            5123 => (2, 65535.0),
+            5125 => (4, 1.0),
+            5126 => (4, 1.0),
+            _ => return Err(format!("Unsupported accessor component type {}", component_type))
+        };
+        let normalized = accessor["normalized"].as_bool().unwrap_or(false);
+        // the count comes from the document, so it is checked against the data before allocating
+        let value_count = count.checked_mul(components).ok_or("accessor out of bounds")?;
+        let view = if let Some(view) = accessor["bufferView"].as_usize() {view} else {
+            // an accessor without a buffer view is all zeros, which take no more room than
+            // the attributes read from the binary chunk that they stand in for
+            if value_count.checked_mul(size).map_or(true, | len | len > self.bin.len()) {
+                return Err("accessor out of bounds".to_string())
+            }
+            return Ok((vec![0.0; value_count], components))
+        };
+        let (data, stride) = self.buffer_view(view)?;
+        let offset = accessor["byteOffset"].as_usize().unwrap_or(0);
+        let stride = stride.unwrap_or(size * components);
+        let end = match count.checked_sub(1) {
+            Some(last) => last.checked_mul(stride)
+                .and_then( | at | at.checked_add(offset))
+                .and_then( | at | at.checked_add(size * components)),
+            None => Some(offset)
+        };
+        if end.map_or(true, | end | end > data.len()) {
+            return Err("accessor out of bounds".to_string())
+        }
+        let mut values = Vec::with_capacity(value_count);
+        for element in 0..count {
+            for component in 0..components {
+                let at = offset + element * stride + component * size;
+                let bytes = data.get(at..at + size).ok_or("accessor out of bounds")?;
+                let value = match component_type {
+                    5120 => bytes[0] as i8 as f32,
+                    5121 => bytes[0] as f32,
+                    5122 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+                    5123 => u16::from_le_bytes([bytes[0], bytes[1]]) as f32,
+                    5125 => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32,
+                    _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                };
+                values.push(if normalized {(value / max).max(-1.0)} else {value});
+            }
+        }
+        Ok((values, components))
+    }
+
+    fn primitive_geometry(&self, primitive: &JsonValue) -> Result<GeometryGen, String> {
+        let attributes = &primitive["attributes"];
+        let (positions, components) = self.accessor(attributes["POSITION"].as_usize().ok_or("primitive without POSITION")?)?;
+        if components != 3 {
+            return Err("POSITION has to be a VEC3".to_string())
+        }
+        let count = positions.len() / 3;
+        let indices: Vec<u32> = if let Some(indices) = primitive["indices"].as_usize() {
+            self.accessor(indices)?.0.into_iter().map( | i | i as u32).collect()
+        } else {
+            (0..count as u32).collect()
+        };
+        if indices.len() % 3 != 0 || indices.iter().any( | i | *i as usize >= count) {
+            return Err("Invalid primitive indices".to_string())
+        }
+        let position = | i: usize | vec3(positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+
+        let normals = match attributes["NORMAL"].as_usize() {
+            Some(normals) => self.accessor(normals)?.0,
+            None => {
+                // smooth normals from the area weighted faces
+                let mut normals = vec![0.0; count * 3];
+                for triangle in indices.chunks(3) {
+                    let [a, b, c] = [triangle[0] as usize, triangle[1] as usize, triangle[2] as usize];
+                    let normal = Vec3::cross(position(b) - position(a), position(c) - position(a));
+                    for i in [a, b, c] {
+                        normals[i * 3] += normal.x;
+                        normals[i * 3 + 1] += normal.y;
+                        normals[i * 3 + 2] += normal.z;
+                    }
+                }
+                for normal in normals.chunks_mut(3) {
+                    let n = vec3(normal[0], normal[1], normal[2]).normalize();
+                    normal.copy_from_slice(&[n.x, n.y, n.z]);
+                }
+                normals
+            }
+        };
+        let uvs = match attributes["TEXCOORD_0"].as_usize() {
+            Some(uvs) => self.accessor(uvs)?.0,
+            None => vec![0.0; count * 2]
+        };
+        if normals.len() != count * 3 || uvs.len() != count * 2 {
+            return Err("Primitive attributes differ in length".to_string())
+        }
+
+        let mut geometry = GeometryGen::default();
+        for i in 0..count {
+            geometry.vertices.extend_from_slice(&[
+                positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2],
+                0.0,
+                normals[i * 3], normals[i * 3 + 1], normals[i * 3 + 2],
+                uvs[i * 2], uvs[i * 2 + 1],
+            ]);
+        }
+        geometry.indices = indices;
+        Ok(geometry)
+    }
+}
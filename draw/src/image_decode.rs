@@ -0,0 +1,49 @@
+use {
+    crate::makepad_platform::*,
+    makepad_zune_jpeg::JpegDecoder,
+    makepad_zune_png::PngDecoder,
+};
+
+/// Decoding of image files into the `ImageBuffer` of the platform.
+pub trait ImageBufferDecode: Sized {
+    fn from_png(data: &[u8]) -> Result<Self, String>;
+    fn from_jpg(data: &[u8]) -> Result<Self, String>;
+}
+
+impl ImageBufferDecode for ImageBuffer {
+    fn from_png(
+        data: &[u8]
+    ) -> Result<Self, String> {
+        let mut decoder = PngDecoder::new(data);
+        match decoder.decode() {
+            Ok(image) => {
+                if let Some(data) = image.u8(){
+                    let (width,height) = decoder.get_dimensions().unwrap();
+                    ImageBuffer::new(&data, width as usize, height as usize)
+                }
+                else{
+                    Err("Error decoding PNG: image data empty".to_string())
+                }
+            }
+            Err(err) => {
+                Err(format!("Error decoding PNG: {:?}", err))
+            }
+        }
+    }
+
+    fn from_jpg(
+        data: &[u8]
+    ) -> Result<Self, String> {
+        let mut decoder = JpegDecoder::new(&*data);
+        // decode the file
+        match decoder.decode() {
+            Ok(data) => {
+                let info = decoder.info().unwrap();
+                ImageBuffer::new(&data, info.width as usize, info.height as usize)
+            },
+            Err(err) => {
+                Err(format!("Error decoding JPG: {:?}", err))
+            }
+        }
+    }
+}
@@ -9,6 +9,14 @@ pub mod draw_list_2d;
 pub mod shader;
 pub mod turtle;
 pub mod font_atlas;
+pub mod system_fonts;
+pub mod color_glyph;
+pub mod path_tessellator;
+pub mod svg;
+pub mod gltf;
+pub mod camera_3d;
+pub mod image_decode;
+pub mod rich_text;
 pub mod geometry;
 pub mod nav;
 pub mod icon_atlas;
@@ -24,6 +32,10 @@ pub use crate::{
         Padding,
         Flow,
         Size,
+        GridLayout,
+        GridTrack,
+        GridTracks,
+        GridSpan,
         TurtleAlignRange,
         DeferWalk
     },
@@ -52,10 +64,17 @@ pub use crate::{
         draw_quad::DrawQuad,
         draw_text::DrawText,
         draw_color::DrawColor,
+        draw_path::DrawPath,
+        draw_svg::DrawSvg,
+        draw_mesh::{DrawMesh, Mesh, GltfModel},
     },
+    gltf::GltfScene,
+    camera_3d::{Camera3d, CameraMode},
+    image_decode::ImageBufferDecode,
     geometry::{
         GeometryGen,
         GeometryQuad2D,
+        GeometryCube3D,
     },
 };
 
@@ -64,7 +83,11 @@ pub fn live_design(cx: &mut Cx) {
     crate::shader::draw_color::live_design(cx);
     crate::shader::draw_icon::live_design(cx);
     crate::shader::draw_text::live_design(cx);
+    crate::rich_text::live_design(cx);
     crate::geometry::geometry_gen::live_design(cx);
     crate::shader::std::live_design(cx);
     crate::shader::draw_trapezoid::live_design(cx);
+    crate::shader::draw_path::live_design(cx);
+    crate::shader::draw_svg::live_design(cx);
+    crate::shader::draw_mesh::live_design(cx);
 }
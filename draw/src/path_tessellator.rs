@@ -0,0 +1,231 @@
+//! Turns `makepad_vector` paths into triangles for `DrawPath` and `DrawSvg`. Curves are
+//! flattened for the size they are drawn at, fills and strokes are tessellated by `bender`.
+//!
+//! Every triangle knows which of its edges are on the outline of the shape, the shaders
+//! antialias those and cut the inner edges hard, so neighbouring triangles leave no seams.
+
+use {
+    std::collections::HashMap,
+    crate::{
+        makepad_platform::*,
+        makepad_vector::path::{LinePathCommand, Path, PathIterator},
+    },
+    bender_filler::Filler,
+    bender_geometry::{Mesh, Polygon, Polyline},
+    bender_stroker::{CapKind, JoinKind, Stroker},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillRule {
+    NonZero,
+    EvenOdd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineJoin {
+    Miter,
+    Round,
+    Bevel,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineCap {
+    Butt,
+    Round,
+    Square,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f64,
+    pub line_join: LineJoin,
+    pub line_cap: LineCap,
+    /// The longest a miter join gets, in stroke widths, before it is beveled.
+    pub miter_limit: f64,
+}
+
+impl Default for StrokeStyle {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            line_join: LineJoin::Miter,
+            line_cap: LineCap::Butt,
+            miter_limit: 4.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PathTriangle {
+    /// Counter clockwise in a y down space.
+    pub points: [DVec2; 3],
+    /// Whether the edges from point 0 to 1, 1 to 2 and 2 to 0 are on the outline of the
+    /// shape. Inner edges are shared with another triangle.
+    pub outline: [bool; 3],
+}
+
+impl PathTriangle {
+    /// The bounds with a pixel of room for antialiasing.
+    pub fn bounds(&self) -> Rect {
+        let [a, b, c] = self.points;
+        let min = dvec2(a.x.min(b.x).min(c.x), a.y.min(b.y).min(c.y)) - 1.0;
+        let max = dvec2(a.x.max(b.x).max(c.x), a.y.max(b.y).max(c.y)) + 1.0;
+        Rect {pos: min, size: max - min}
+    }
+}
+
+/// A flattened subpath.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<DVec2>,
+    pub closed: bool,
+}
+
+/// Flattens a path to within `tolerance` after mapping its points through `transform`.
+pub fn flatten_path(path: &Path, tolerance: f64, transform: impl Fn(DVec2) -> DVec2) -> Vec<Contour> {
+    // scoped, its `map` and `filter` clash with the ones of `Iterator`
+    use crate::makepad_vector::internal_iter::InternalIterator;
+    let mut path = path.clone();
+    for point in path.points_mut() {
+        let p = transform(dvec2(point.x, point.y));
+        point.x = p.x;
+        point.y = p.y;
+    }
+    let mut contours: Vec<Contour> = Vec::new();
+    path.commands().linearize(tolerance).for_each(&mut | command | {
+        match command {
+            LinePathCommand::MoveTo(p) => contours.push(Contour {points: vec![dvec2(p.x, p.y)], closed: false}),
+            LinePathCommand::LineTo(p) => if let Some(contour) = contours.last_mut() {
+                let p = dvec2(p.x, p.y);
+                if (*contour.points.last().unwrap() - p).length() > 1e-4 {
+                    contour.points.push(p);
+                }
+            }
+            LinePathCommand::Close => if let Some(contour) = contours.last_mut() {
+                contour.closed = true;
+            }
+        }
+        true
+    });
+    for contour in &mut contours {
+        if contour.closed && contour.points.len() > 1 && (contour.points[0] - *contour.points.last().unwrap()).length() <= 1e-4 {
+            contour.points.pop();
+        }
+    }
+    contours
+}
+
+pub struct PathTessellator {
+    filler: Filler,
+    stroker: Stroker,
+    mesh: Mesh,
+}
+
+impl Default for PathTessellator {
+    fn default() -> Self {
+        Self {
+            filler: Filler::new(),
+            stroker: Stroker::new(),
+            mesh: Mesh::new(),
+        }
+    }
+}
+
+impl PathTessellator {
+    /// Fills the contours, open ones are closed implicitly.
+    pub fn fill(&mut self, contours: &[Contour], fill_rule: FillRule, triangles: &mut Vec<PathTriangle>) {
+        let polygons: Vec<Polygon> = contours.iter().filter( | c | c.points.len() >= 3).map( | c | {
+            Polygon {vertices: c.points.iter().map( | p | mesh_point(*p)).collect()}
+        }).collect();
+        if polygons.is_empty() {
+            return
+        }
+        self.mesh.vertices.clear();
+        self.mesh.indices.clear();
+        self.filler.fill(&polygons, match fill_rule {
+            FillRule::NonZero => bender_filler::FillRule::NonZero,
+            FillRule::EvenOdd => bender_filler::FillRule::EvenOdd,
+        }, &mut self.mesh);
+        push_mesh_triangles(&self.mesh, triangles);
+    }
+
+    /// Strokes the contours, round joins and caps are flattened to within `tolerance`.
+    pub fn stroke(&mut self, contours: &[Contour], style: &StrokeStyle, tolerance: f64, triangles: &mut Vec<PathTriangle>) {
+        if style.width <= 0.0 {
+            return
+        }
+        // closed contours wrap around to get a join at their start, open ones get caps
+        for closed in [true, false] {
+            let polylines: Vec<Polyline> = contours.iter().filter( | c | c.closed == closed && c.points.len() >= 2).map( | c | {
+                let mut vertices: Vec<_> = c.points.iter().map( | p | mesh_point(*p)).collect();
+                if closed {
+                    vertices.extend_from_slice(&vertices[0..2].to_vec());
+                }
+                Polyline {vertices}
+            }).collect();
+            if polylines.is_empty() {
+                continue;
+            }
+            self.mesh.vertices.clear();
+            self.mesh.indices.clear();
+            self.stroker.stroke(&polylines, bender_stroker::Options {
+                stroke_width: style.width as f32,
+                join_kind: match style.line_join {
+                    LineJoin::Miter => JoinKind::Miter,
+                    LineJoin::Round => JoinKind::Round,
+                    LineJoin::Bevel => JoinKind::Bevel,
+                },
+                cap_kind: match (closed, style.line_cap) {
+                    (true, _) | (_, LineCap::Butt) => CapKind::Butt,
+                    (_, LineCap::Round) => CapKind::Round,
+                    (_, LineCap::Square) => CapKind::Square,
+                },
+                miter_limit: style.miter_limit as f32,
+                arc_tolerance: tolerance as f32,
+                fill_rule: bender_filler::FillRule::NonZero,
+            }, &mut self.mesh);
+            push_mesh_triangles(&self.mesh, triangles);
+        }
+    }
+}
+
+fn mesh_point(p: DVec2) -> bender_geometry::Point {
+    bender_geometry::Point::new(p.x as f32, p.y as f32)
+}
+
+fn push_mesh_triangles(mesh: &Mesh, triangles: &mut Vec<PathTriangle>) {
+    let point = | index: u16 | {
+        let p = mesh.vertices[index as usize].position;
+        dvec2(p[0] as f64, p[1] as f64)
+    };
+    // vertices are not shared between all triangles, so edges are matched by position
+    let key = | index: u16 | {
+        let p = mesh.vertices[index as usize].position;
+        (p[0].to_bits(), p[1].to_bits())
+    };
+    let edge_key = | a: u16, b: u16 | {
+        let (a, b) = (key(a), key(b));
+        if a < b {(a, b)} else {(b, a)}
+    };
+    let mut edge_count = HashMap::new();
+    for tri in mesh.indices.chunks_exact(3) {
+        for i in 0..3 {
+            *edge_count.entry(edge_key(tri[i], tri[(i + 1) % 3])).or_insert(0) += 1;
+        }
+    }
+    for tri in mesh.indices.chunks_exact(3) {
+        let mut points = [point(tri[0]), point(tri[1]), point(tri[2])];
+        let mut outline = [0, 1, 2].map( | i | edge_count[&edge_key(tri[i], tri[(i + 1) % 3])] == 1);
+        let d1 = points[1] - points[0];
+        let d2 = points[2] - points[0];
+        let area = d1.x * d2.y - d1.y * d2.x;
+        if area == 0.0 {
+            continue;
+        }
+        if area < 0.0 {
+            points.swap(1, 2);
+            outline = [outline[2], outline[1], outline[0]];
+        }
+        triangles.push(PathTriangle {points, outline});
+    }
+}
@@ -0,0 +1,580 @@
+use {
+    crate::{
+        makepad_platform::*,
+        turtle::{Walk, Size, Align},
+        font_atlas::{CxFontsAtlas, Font, FontChain},
+        shader::{
+            draw_text::{DrawText, TextStyle},
+            draw_color::DrawColor,
+        },
+        cx_2d::Cx2d
+    },
+};
+
+live_design!{
+    DrawRichTextGlyph = {{DrawRichTextGlyph}} {
+        fn vertex(self) -> vec4 {
+            let min_pos = vec2(self.rect_pos.x, self.rect_pos.y)
+            let max_pos = vec2(self.rect_pos.x + self.rect_size.x, self.rect_pos.y - self.rect_size.y)
+
+            self.clipped = clamp(
+                mix(min_pos, max_pos, self.geom_pos),
+                self.draw_clip.xy,
+                self.draw_clip.zw
+            )
+
+            let normalized: vec2 = (self.clipped - min_pos) / vec2(self.rect_size.x, -self.rect_size.y)
+
+            self.tex_coord1 = mix(
+                self.font_t1.xy,
+                self.font_t2.xy,
+                normalized.xy
+            )
+            self.pos = normalized;
+
+            // slant around the baseline, for italic spans without an italic font
+            let slanted = self.clipped.x + (self.baseline - self.clipped.y) * self.italic * 0.2;
+
+            return self.camera_projection * (self.camera_view * (self.view_transform * vec4(
+                slanted,
+                self.clipped.y,
+                self.char_depth + self.draw_zbias,
+                1.
+            )))
+        }
+    }
+
+    RichText = {{RichText}} {}
+}
+
+#[derive(Live, LiveHook)]
+#[repr(C)]
+pub struct DrawRichTextGlyph {
+    #[deref] pub draw_super: DrawText,
+    #[live] pub italic: f32,
+    #[live] pub baseline: f32,
+}
+
+/// The styling of one span of a rich text. Unset values come from the text style of the
+/// `RichText` drawing it.
+#[derive(Clone, Default)]
+pub struct SpanStyle {
+    // replaces the font of the text style, its fallback fonts stay
+    pub font: Option<Font>,
+    pub font_size: Option<f64>,
+    pub color: Option<Vec4>,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikethrough: bool,
+    pub background: Option<Vec4>,
+    // raises the span above the baseline, negative values lower it
+    pub baseline_shift: f64,
+}
+
+impl SpanStyle {
+    pub fn bold(self) -> Self {Self {bold: true, ..self}}
+    pub fn italic(self) -> Self {Self {italic: true, ..self}}
+    pub fn underline(self) -> Self {Self {underline: true, ..self}}
+    pub fn strikethrough(self) -> Self {Self {strikethrough: true, ..self}}
+    pub fn with_font(self, font: Font) -> Self {Self {font: Some(font), ..self}}
+    pub fn with_font_size(self, font_size: f64) -> Self {Self {font_size: Some(font_size), ..self}}
+    pub fn with_color(self, color: Vec4) -> Self {Self {color: Some(color), ..self}}
+    pub fn with_background(self, color: Vec4) -> Self {Self {background: Some(color), ..self}}
+    pub fn with_baseline_shift(self, baseline_shift: f64) -> Self {Self {baseline_shift, ..self}}
+}
+
+#[derive(Clone)]
+pub struct TextSpan<'a> {
+    pub text: &'a str,
+    pub style: SpanStyle,
+}
+
+impl<'a> TextSpan<'a> {
+    pub fn new(text: &'a str, style: SpanStyle) -> Self {
+        Self {text, style}
+    }
+
+    pub fn plain(text: &'a str) -> Self {
+        Self {text, style: SpanStyle::default()}
+    }
+}
+
+/// A position in a rich text, as a byte index into the text of a span
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RichTextPos {
+    pub span: usize,
+    pub index: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RichTextLine {
+    // x of the line start after alignment
+    pub left: f64,
+    pub top: f64,
+    pub height: f64,
+    // distance from the top of the line to its baseline
+    pub baseline: f64,
+    // width without trailing whitespace
+    pub width: f64,
+    // range into the characters of the layout
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct RichTextChar {
+    pub pos: RichTextPos,
+    pub line: usize,
+    // the advance of the character over the full height of its line
+    pub rect: Rect,
+}
+
+/// The wrapped lines and character boxes of a rich text, in logical order. After
+/// `RichText::draw_walk` the rects are in absolute coordinates.
+#[derive(Clone, Debug, Default)]
+pub struct RichTextLayout {
+    pub size: DVec2,
+    pub lines: Vec<RichTextLine>,
+    pub chars: Vec<RichTextChar>,
+    // the position after the last character
+    pub end: RichTextPos,
+}
+
+impl RichTextLayout {
+    fn translate(&mut self, offset: DVec2) {
+        for line in &mut self.lines {
+            line.left += offset.x;
+            line.top += offset.y;
+        }
+        for c in &mut self.chars {
+            c.rect.pos += offset;
+        }
+    }
+
+    // index of the first character at or after pos
+    fn char_index(&self, pos: RichTextPos) -> usize {
+        self.chars.partition_point( | c | c.pos < pos)
+    }
+
+    fn pos_at(&self, index: usize) -> RichTextPos {
+        self.chars.get(index).map_or(self.end, | c | c.pos)
+    }
+
+    /// The position a click at `pos` puts the cursor at
+    pub fn closest_offset(&self, pos: DVec2) -> Option<RichTextPos> {
+        let line_index = self.lines.iter().position( | line | pos.y < line.top + line.height)
+            .unwrap_or(self.lines.len().checked_sub(1)?);
+        let line = &self.lines[line_index];
+        for c in &self.chars[line.start..line.end] {
+            if pos.x < c.rect.pos.x + c.rect.size.x * 0.5 {
+                return Some(c.pos)
+            }
+        }
+        // past the end of a line that continues below, the cursor goes before the
+        // newline or the whitespace the line wrapped at
+        if let Some(last) = self.chars[line.start..line.end].last() {
+            let trailing_space = line.left + line.width < last.rect.pos.x + last.rect.size.x;
+            if line_index + 1 < self.lines.len() && (last.rect.size.x == 0.0 || trailing_space) {
+                return Some(last.pos)
+            }
+        }
+        Some(self.pos_at(line.end))
+    }
+
+    /// The top left of a cursor placed before the character at `pos`
+    pub fn cursor_pos(&self, pos: RichTextPos) -> Option<DVec2> {
+        let index = self.char_index(pos);
+        if let Some(c) = self.chars.get(index) {
+            return Some(c.rect.pos)
+        }
+        let line = self.lines.last()?;
+        if let Some(c) = self.chars[line.start..line.end].last() {
+            return Some(dvec2(c.rect.pos.x + c.rect.size.x, line.top))
+        }
+        Some(dvec2(line.left, line.top))
+    }
+
+    /// One rect per line covering the characters from start up to end
+    pub fn get_selection_rects(&self, start: RichTextPos, end: RichTextPos) -> Vec<Rect> {
+        let start = self.char_index(start);
+        let end = self.char_index(end);
+        self.line_rects(start..end)
+    }
+
+    /// The rects covered by a span, one per line it is on, for hit testing
+    pub fn span_rects(&self, span: usize) -> Vec<Rect> {
+        let start = self.char_index(RichTextPos {span, index: 0});
+        let end = self.char_index(RichTextPos {span: span + 1, index: 0});
+        self.line_rects(start..end)
+    }
+
+    fn line_rects(&self, range: std::ops::Range<usize>) -> Vec<Rect> {
+        let mut out: Vec<(usize, Rect)> = Vec::new();
+        for c in &self.chars[range] {
+            match out.last_mut() {
+                Some((line, rect)) if *line == c.line => {
+                    rect.size.x = c.rect.pos.x + c.rect.size.x - rect.pos.x;
+                }
+                _ => out.push((c.line, c.rect))
+            }
+        }
+        out.into_iter().map( | (_, rect) | rect).collect()
+    }
+}
+
+/// Fonts for bold and italic spans, replacing the font of the text style
+#[derive(Clone, Live, LiveHook)]
+#[live_ignore]
+pub struct RichTextFonts {
+    #[live] pub bold: Font,
+    #[live] pub italic: Font,
+    #[live] pub bold_italic: Font,
+}
+
+// a span with its text style resolved against the rich text
+struct ResolvedSpan {
+    text_style: TextStyle,
+    chain: FontChain,
+    color: Vec4,
+    // font size in logical pixels and pixels per em
+    size: f64,
+    em: f64,
+    ascent: f64,
+    descent: f64,
+    line_spacing: f64,
+    shift: f64,
+    fake_bold: bool,
+    fake_italic: bool,
+}
+
+/// Lays out styled spans as one paragraph, wrapping words across span boundaries. Bold and
+/// italic spans use the fonts in `fonts` when they are set, and are emboldened or slanted
+/// otherwise. The layout of the last draw is kept for hit testing.
+#[derive(Live, LiveHook)]
+pub struct RichText {
+    #[live] pub draw_glyph: DrawRichTextGlyph,
+    #[live] pub draw_highlight: DrawColor,
+    #[live] pub draw_decoration: DrawColor,
+    #[live] pub fonts: RichTextFonts,
+    #[live] pub align: Align,
+    #[rust] pub layout: RichTextLayout,
+}
+
+impl RichText {
+    fn resolve_span(&self, style: &SpanStyle) -> ResolvedSpan {
+        let base = &self.draw_glyph.text_style;
+        let mut text_style = base.clone();
+        let mut fake_bold = style.bold;
+        let mut fake_italic = style.italic;
+        if let Some(font) = &style.font {
+            text_style.font = font.clone();
+        }
+        else {
+            let styled = [
+                (true, true, &self.fonts.bold_italic),
+                (true, false, &self.fonts.bold),
+                (false, true, &self.fonts.italic)
+            ];
+            for (bold, italic, font) in styled {
+                if font.font_id.is_some() && (!bold || style.bold) && (!italic || style.italic) {
+                    text_style.font = font.clone();
+                    fake_bold = style.bold && !bold;
+                    fake_italic = style.italic && !italic;
+                    break;
+                }
+            }
+        }
+        if let Some(font_size) = style.font_size {
+            text_style.font_size = font_size;
+        }
+        let size = text_style.font_size * self.draw_glyph.font_scale;
+        ResolvedSpan {
+            chain: text_style.font_chain(),
+            color: style.color.unwrap_or(self.draw_glyph.color),
+            size,
+            em: size * 96.0 / 72.0,
+            ascent: size * text_style.top_drop + style.baseline_shift,
+            descent: size * (text_style.height_factor - text_style.top_drop) - style.baseline_shift,
+            line_spacing: text_style.line_spacing,
+            shift: style.baseline_shift,
+            fake_bold,
+            fake_italic,
+            text_style,
+        }
+    }
+
+    /// Lays out the spans at the origin, wrapping at `max_width` unless it is NaN
+    pub fn compute_layout(&self, fonts_atlas: &mut CxFontsAtlas, spans: &[TextSpan], max_width: f64) -> RichTextLayout {
+        let resolved: Vec<_> = spans.iter().map( | span | self.resolve_span(&span.style)).collect();
+        self.compute_layout_inner(fonts_atlas, spans, &resolved, max_width)
+    }
+
+    fn compute_layout_inner(&self, fonts_atlas: &mut CxFontsAtlas, spans: &[TextSpan], resolved: &[ResolvedSpan], max_width: f64) -> RichTextLayout {
+        let mut layout = RichTextLayout::default();
+        let mut text = Vec::new();
+        for (span_index, span) in spans.iter().enumerate() {
+            let r = &resolved[span_index];
+            for (index, c) in span.text.char_indices() {
+                let advance = if c == '\n' || c == '\r' {0.0} else {
+                    fonts_atlas.char_advance(&r.chain, c).unwrap_or(0.0) * r.em
+                };
+                layout.chars.push(RichTextChar {
+                    pos: RichTextPos {span: span_index, index},
+                    line: 0,
+                    rect: Rect {pos: DVec2::default(), size: dvec2(advance, 0.0)}
+                });
+                text.push(c);
+            }
+        }
+        layout.end = spans.len().checked_sub(1).map_or(RichTextPos::default(), | span | {
+            RichTextPos {span, index: spans[span].text.len()}
+        });
+
+        // break into lines at whitespace, or between characters when a word doesn't fit
+        let wrap = !max_width.is_nan();
+        let mut ranges = Vec::new();
+        let mut line_start = 0;
+        let mut break_at = None;
+        let mut x = 0.0;
+        let mut i = 0;
+        while i < text.len() {
+            let c = text[i];
+            let advance = layout.chars[i].rect.size.x;
+            if c == '\n' {
+                ranges.push(line_start..i + 1);
+                line_start = i + 1;
+                break_at = None;
+                x = 0.0;
+                i += 1;
+                continue;
+            }
+            if wrap && !c.is_whitespace() && i > line_start && x + advance > max_width {
+                let start = match break_at {
+                    Some(b) if b > line_start => b,
+                    _ => i
+                };
+                ranges.push(line_start..start);
+                line_start = start;
+                break_at = None;
+                x = layout.chars[start..i].iter().map( | c | c.rect.size.x).sum();
+                continue;
+            }
+            x += advance;
+            if c.is_whitespace() {
+                break_at = Some(i + 1);
+            }
+            i += 1;
+        }
+        ranges.push(line_start..text.len());
+
+        let base = self.resolve_span(&SpanStyle::default());
+        let mut top = 0.0;
+        let mut width: f64 = 0.0;
+        for (line_index, range) in ranges.into_iter().enumerate() {
+            // an empty line takes its height from the span before it
+            let metrics_range = if range.is_empty() {range.start.saturating_sub(1)..range.start} else {range.clone()};
+            let mut ascent: f64 = 0.0;
+            let mut descent: f64 = 0.0;
+            let mut line_spacing: f64 = 0.0;
+            for c in &layout.chars[metrics_range] {
+                let r = &resolved[c.pos.span];
+                ascent = ascent.max(r.ascent);
+                descent = descent.max(r.descent);
+                line_spacing = line_spacing.max(r.line_spacing);
+            }
+            if line_spacing == 0.0 {
+                ascent = base.ascent;
+                descent = base.descent;
+                line_spacing = base.line_spacing;
+            }
+            let height = ascent + descent;
+            let mut x = 0.0;
+            let mut line_width = 0.0;
+            for i in range.clone() {
+                let c = &mut layout.chars[i];
+                c.line = line_index;
+                c.rect.pos = dvec2(x, top);
+                c.rect.size.y = height;
+                x += c.rect.size.x;
+                if !text[i].is_whitespace() {
+                    line_width = x;
+                }
+            }
+            width = width.max(line_width);
+            layout.lines.push(RichTextLine {
+                left: 0.0,
+                top,
+                height,
+                baseline: ascent,
+                width: line_width,
+                start: range.start,
+                end: range.end
+            });
+            layout.size.y = top + height;
+            top += height * line_spacing;
+        }
+        layout.size.x = width;
+
+        let align_width = if wrap {max_width} else {width};
+        for line in &mut layout.lines {
+            let offset = (align_width - line.width) * self.align.x;
+            if offset > 0.0 {
+                line.left = offset;
+                for c in &mut layout.chars[line.start..line.end] {
+                    c.rect.pos.x += offset;
+                }
+            }
+        }
+        layout
+    }
+
+    pub fn draw_walk(&mut self, cx: &mut Cx2d, walk: Walk, spans: &[TextSpan]) {
+        if self.draw_glyph.text_style.font.font_id.is_none() {
+            return
+        }
+        let fonts_atlas_rc = cx.fonts_atlas_rc.clone();
+        let mut fonts_atlas = fonts_atlas_rc.0.borrow_mut();
+        let fonts_atlas = &mut*fonts_atlas;
+
+        let resolved: Vec<_> = spans.iter().map( | span | self.resolve_span(&span.style)).collect();
+        let eval_size = cx.turtle().eval_walk_size(&walk);
+        let fit_width = walk.width.is_fit() || eval_size.x.is_nan();
+        let mut layout = self.compute_layout_inner(fonts_atlas, spans, &resolved, if fit_width {f64::NAN} else {eval_size.x});
+
+        let width = if fit_width {layout.size.x} else {eval_size.x};
+        let height = if walk.height.is_fit() || eval_size.y.is_nan() {layout.size.y} else {eval_size.y};
+        let rect = cx.walk_turtle(Walk {
+            width: Size::Fixed(width),
+            height: Size::Fixed(height),
+            ..walk
+        });
+        let y_align = (height - layout.size.y) * self.align.y;
+        layout.translate(rect.pos + dvec2(0.0, y_align));
+
+        // consecutive characters on a line from the same span are drawn together
+        let mut runs = Vec::new();
+        let mut start = 0;
+        for i in 1..=layout.chars.len() {
+            if i == layout.chars.len()
+                || layout.chars[i].line != layout.chars[start].line
+                || layout.chars[i].pos.span != layout.chars[start].pos.span {
+                runs.push(start..i);
+                start = i;
+            }
+        }
+        let run_rect = | layout: &RichTextLayout, run: &std::ops::Range<usize> | {
+            let first = layout.chars[run.start].rect;
+            let last = layout.chars[run.end - 1].rect;
+            Rect {pos: first.pos, size: dvec2(last.pos.x + last.size.x - first.pos.x, first.size.y)}
+        };
+
+        for run in &runs {
+            if let Some(background) = spans[layout.chars[run.start].pos.span].style.background {
+                self.draw_highlight.color = background;
+                self.draw_highlight.draw_abs(cx, run_rect(&layout, run));
+            }
+        }
+
+        let base_style = self.draw_glyph.text_style.clone();
+        let base_color = self.draw_glyph.color;
+        let mut current_span = None;
+        for run in &runs {
+            let first = layout.chars[run.start];
+            let span = first.pos.span;
+            let r = &resolved[span];
+            let end = layout.chars.get(run.end).filter( | c | c.pos.span == span).map_or(spans[span].text.len(), | c | c.pos.index);
+            let text = spans[span].text[first.pos.index..end].trim_end_matches(['\n', '\r']);
+            if text.is_empty() {
+                continue;
+            }
+            if current_span != Some(span) {
+                self.draw_glyph.text_style = r.text_style.clone();
+                current_span = Some(span);
+            }
+            let line = &layout.lines[first.line];
+            let baseline = line.top + line.baseline - r.shift;
+            self.draw_glyph.color = r.color;
+            self.draw_glyph.italic = if r.fake_italic {1.0} else {0.0};
+            self.draw_glyph.baseline = baseline as f32;
+            let pos = dvec2(first.rect.pos.x, baseline - r.size * r.text_style.top_drop);
+            self.draw_glyph.draw_inner(cx, pos, text, fonts_atlas);
+            if r.fake_bold {
+                let offset = (r.size * 0.04).max(0.5);
+                self.draw_glyph.draw_inner(cx, pos + dvec2(offset, 0.0), text, fonts_atlas);
+            }
+        }
+        if self.draw_glyph.many_instances.is_some() {
+            self.draw_glyph.end_many_instances(cx);
+        }
+        self.draw_glyph.text_style = base_style;
+        self.draw_glyph.color = base_color;
+
+        let mut new_draw_call = true;
+        for run in &runs {
+            let first = layout.chars[run.start];
+            let style = &spans[first.pos.span].style;
+            if !style.underline && !style.strikethrough {
+                continue;
+            }
+            if new_draw_call {
+                // keeps the lines on top of the glyphs
+                self.draw_decoration.new_draw_call(cx);
+                new_draw_call = false;
+            }
+            let r = &resolved[first.pos.span];
+            let line = &layout.lines[first.line];
+            let baseline = line.top + line.baseline - r.shift;
+            let thickness = (r.size * 0.07).max(1.0);
+            let rect = run_rect(&layout, run);
+            self.draw_decoration.color = r.color;
+            if style.underline {
+                self.draw_decoration.draw_abs(cx, Rect {
+                    pos: dvec2(rect.pos.x, baseline + r.size * 0.12),
+                    size: dvec2(rect.size.x, thickness)
+                });
+            }
+            if style.strikethrough {
+                self.draw_decoration.draw_abs(cx, Rect {
+                    pos: dvec2(rect.pos.x, baseline - r.size * 0.35 - thickness * 0.5),
+                    size: dvec2(rect.size.x, thickness)
+                });
+            }
+        }
+        self.layout = layout;
+    }
+
+    pub fn area(&self) -> Area {
+        self.draw_glyph.area()
+    }
+
+    pub fn redraw(&self, cx: &mut Cx) {
+        self.draw_glyph.redraw(cx)
+    }
+
+    /// The layout of the last draw
+    pub fn layout(&self) -> &RichTextLayout {
+        &self.layout
+    }
+
+    pub fn closest_offset(&self, pos: DVec2) -> Option<RichTextPos> {
+        self.layout.closest_offset(pos)
+    }
+
+    pub fn get_selection_rects(&self, start: RichTextPos, end: RichTextPos) -> Vec<Rect> {
+        self.layout.get_selection_rects(start, end)
+    }
+
+    pub fn get_cursor_pos(&self, pos: RichTextPos) -> Option<DVec2> {
+        self.layout.cursor_pos(pos)
+    }
+
+    /// The span under a point, for links and tooltips
+    pub fn span_at(&self, pos: DVec2) -> Option<usize> {
+        self.layout.chars.iter().find( | c | c.rect.contains(pos)).map( | c | c.pos.span)
+    }
+
+    pub fn span_rects(&self, span: usize) -> Vec<Rect> {
+        self.layout.span_rects(span)
+    }
+}
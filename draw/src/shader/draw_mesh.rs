@@ -0,0 +1,216 @@
+use {
+    crate::{
+        makepad_platform::*,
+        draw_list_2d::ManyInstances,
+        geometry::{GeometryCube3D, GeometryGen},
+        gltf::{GltfScene, GltfInstance},
+        image_decode::ImageBufferDecode,
+        cx_2d::Cx2d,
+    },
+};
+
+live_design!{
+    DrawMesh = {{DrawMesh}} {
+        texture base_color_texture: texture2d
+
+        varying world_normal: vec3
+        varying uv: vec2
+
+        light_dir: vec3(0.3, 0.8, 0.6)
+
+        fn transform_point(self, p: vec3) -> vec4 {
+            return self.transform_x * p.x + self.transform_y * p.y + self.transform_z * p.z + self.transform_w;
+        }
+
+        fn vertex(self) -> vec4 {
+            self.world_normal = (self.transform_x.xyz * self.geom_normal.x + self.transform_y.xyz * self.geom_normal.y + self.transform_z.xyz * self.geom_normal.z);
+            self.uv = self.geom_uv;
+            return self.camera_projection * (self.camera_view * (self.view_transform * self.transform_point(self.geom_pos)));
+        }
+
+        fn get_color(self) -> vec4 {
+            if self.has_texture > 0.5 {
+                return self.color * sample2d(self.base_color_texture, self.uv);
+            }
+            return self.color;
+        }
+
+        fn pixel(self) -> vec4 {
+            let color = self.get_color();
+            let light = max(dot(normalize(self.world_normal), normalize(self.light_dir)), 0.0);
+            let shade = self.ambient + (1.0 - self.ambient) * light;
+            return vec4(color.rgb * shade * color.a, color.a);
+        }
+    }
+}
+
+/// A mesh on the gpu with its base color, drawn with `DrawMesh`.
+pub struct Mesh {
+    pub geometry: Geometry,
+    pub color: Vec4,
+    pub texture: Option<Texture>,
+}
+
+impl Mesh {
+    /// `geometry_gen` needs the 3d vertex layout: position, id, normal and uv.
+    pub fn new(cx: &mut Cx, geometry_gen: GeometryGen) -> Self {
+        let geometry = Geometry::new(cx);
+        geometry_gen.to_geometry(cx, &geometry);
+        Self {
+            geometry,
+            color: vec4(1.0, 1.0, 1.0, 1.0),
+            texture: None
+        }
+    }
+}
+
+/// The meshes of a glTF scene uploaded to the gpu, with the instances that place them.
+pub struct GltfModel {
+    /// The primitives of every mesh of the scene.
+    pub meshes: Vec<Vec<Mesh>>,
+    pub instances: Vec<GltfInstance>,
+}
+
+impl GltfModel {
+    pub fn new(cx: &mut Cx, scene: &GltfScene) -> Self {
+        let textures: Vec<Option<Texture>> = scene.images.iter().map( | image | {
+            if image.data.is_empty() {
+                return None
+            }
+            let decoded = match image.mime_type.as_str() {
+                "image/jpeg" => ImageBuffer::from_jpg(&image.data),
+                _ => ImageBuffer::from_png(&image.data),
+            };
+            match decoded {
+                Ok(buffer) => Some(buffer.into_new_texture(cx)),
+                Err(err) => {
+                    error!("Cannot decode glTF image: {}", err);
+                    None
+                }
+            }
+        }).collect();
+        let meshes = scene.meshes.iter().map( | mesh | {
+            mesh.primitives.iter().map( | primitive | {
+                let mut mesh = Mesh::new(cx, primitive.geometry.clone());
+                if let Some(material) = primitive.material.and_then( | material | scene.materials.get(material)) {
+                    mesh.color = material.base_color;
+                    mesh.texture = material.base_color_image.and_then( | image | textures.get(image).cloned().flatten());
+                }
+                mesh
+            }).collect()
+        }).collect();
+        Self {
+            meshes,
+            instances: scene.instances.clone()
+        }
+    }
+}
+
+/// Draws indexed triangle meshes with a transform per instance, lit by a single directional light.
+/// The transform is in the space of the pass camera, for a 3d view draw it in a pass with a
+/// projection matrix mode and a depth texture.
+#[derive(Live)]
+#[repr(C)]
+pub struct DrawMesh {
+    #[rust] pub many_instances: Option<ManyInstances>,
+    #[live] pub geometry: GeometryCube3D,
+    #[deref] pub draw_vars: DrawVars,
+    #[calc] pub transform_x: Vec4,
+    #[calc] pub transform_y: Vec4,
+    #[calc] pub transform_z: Vec4,
+    #[calc] pub transform_w: Vec4,
+    #[live] pub color: Vec4,
+    #[live] pub light_dir: Vec3,
+    #[live(0.3)] pub ambient: f32,
+    #[calc] pub has_texture: f32,
+}
+
+impl LiveHook for DrawMesh {
+    fn before_apply(&mut self, cx: &mut Cx, apply_from: ApplyFrom, index: usize, nodes: &[LiveNode]) {
+        self.draw_vars.before_apply_init_shader(cx, apply_from, index, nodes, &self.geometry);
+    }
+    fn after_apply(&mut self, cx: &mut Cx, apply_from: ApplyFrom, index: usize, nodes: &[LiveNode]) {
+        self.draw_vars.after_apply_update_self(cx, apply_from, index, nodes, &self.geometry);
+    }
+}
+
+impl DrawMesh {
+    pub fn set_transform(&mut self, transform: &Mat4) {
+        let v = &transform.v;
+        self.transform_x = vec4(v[0], v[1], v[2], v[3]);
+        self.transform_y = vec4(v[4], v[5], v[6], v[7]);
+        self.transform_z = vec4(v[8], v[9], v[10], v[11]);
+        self.transform_w = vec4(v[12], v[13], v[14], v[15]);
+    }
+
+    /// Draws the geometry of the shader, a cube unless it is configured otherwise.
+    pub fn draw(&mut self, cx: &mut Cx2d, transform: &Mat4) {
+        self.set_transform(transform);
+        if let Some(mi) = &mut self.many_instances {
+            mi.instances.extend_from_slice(self.draw_vars.as_slice());
+        }
+        else if self.draw_vars.can_instance() {
+            let new_area = cx.add_instance(&self.draw_vars);
+            self.draw_vars.area = cx.update_area_refs(self.draw_vars.area, new_area);
+        }
+    }
+
+    pub fn draw_mesh(&mut self, cx: &mut Cx2d, mesh: &Mesh, transform: &Mat4) {
+        self.draw_mesh_instances(cx, mesh, std::slice::from_ref(transform));
+    }
+
+    /// Draws `mesh` once for every transform, in a single draw call.
+    pub fn draw_mesh_instances(&mut self, cx: &mut Cx2d, mesh: &Mesh, transforms: &[Mat4]) {
+        self.draw_vars.set_geometry(&mesh.geometry);
+        if let Some(texture) = &mesh.texture {
+            self.draw_vars.set_texture(0, texture);
+            self.has_texture = 1.0;
+        }
+        else {
+            self.draw_vars.empty_texture(0);
+            self.has_texture = 0.0;
+        }
+        let color = self.color;
+        self.color = mesh.color * color;
+        self.begin_many_instances(cx);
+        for transform in transforms {
+            self.draw(cx, transform);
+        }
+        self.end_many_instances(cx);
+        self.color = color;
+        self.has_texture = 0.0;
+        self.draw_vars.empty_texture(0);
+        if let Some(geometry_ref) = &self.geometry.geometry_ref {
+            self.draw_vars.set_geometry(&geometry_ref.0);
+        }
+    }
+
+    /// Draws every instance of the model, placed by `transform`.
+    pub fn draw_model(&mut self, cx: &mut Cx2d, model: &GltfModel, transform: &Mat4) {
+        for (index, meshes) in model.meshes.iter().enumerate() {
+            // Mat4::mul(a, b) applies a first
+            let transforms: Vec<Mat4> = model.instances.iter()
+                .filter( | instance | instance.mesh == index)
+                .map( | instance | Mat4::mul(&instance.transform, transform))
+                .collect();
+            if transforms.is_empty() {
+                continue;
+            }
+            for mesh in meshes {
+                self.draw_mesh_instances(cx, mesh, &transforms);
+            }
+        }
+    }
+
+    pub fn begin_many_instances(&mut self, cx: &mut Cx2d) {
+        let mi = cx.begin_many_instances(&self.draw_vars);
+        self.many_instances = mi;
+    }
+
+    pub fn end_many_instances(&mut self, cx: &mut Cx2d) {
+        if let Some(mi) = self.many_instances.take() {
+            let new_area = cx.end_many_instances(mi);
+            self.draw_vars.area = cx.update_area_refs(self.draw_vars.area, new_area);
+        }
+    }
+}
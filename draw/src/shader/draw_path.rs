@@ -0,0 +1,89 @@
+use {
+    crate::{
+        makepad_platform::*,
+        makepad_vector::path::Path,
+        shader::draw_quad::DrawQuad,
+        path_tessellator::{PathTessellator, PathTriangle, FillRule, StrokeStyle, flatten_path},
+        cx_2d::Cx2d,
+    },
+};
+
+live_design!{
+    DrawPath = {{DrawPath}} {
+        // signed distance to the edge a-b, positive on the inside of a counter clockwise triangle
+        fn edge_coverage(self, a: vec2, b: vec2, p: vec2, outline: float) -> float {
+            let e = b - a;
+            let d = (e.x * (p.y - a.y) - e.y * (p.x - a.x)) / max(length(e), 0.0001);
+            if outline > 0.5 {
+                return clamp(d * self.dpi_factor + 0.5, 0.0, 1.0);
+            }
+            return step(0.0, d);
+        }
+
+        fn coverage(self) -> float {
+            let p = self.pos * self.rect_size;
+            return self.edge_coverage(self.p0, self.p1, p, self.outline.x)
+                * self.edge_coverage(self.p1, self.p2, p, self.outline.y)
+                * self.edge_coverage(self.p2, self.p0, p, self.outline.z);
+        }
+
+        fn pixel(self) -> vec4 {
+            let alpha = self.color.w * self.coverage();
+            return vec4(self.color.xyz * alpha, alpha);
+        }
+    }
+}
+
+/// Fills and strokes vector paths as antialiased triangles, each call is a single instanced draw.
+/// Paths are in absolute coordinates and flattened for the current dpi factor.
+#[derive(Live, LiveHook)]
+#[repr(C)]
+pub struct DrawPath {
+    #[rust] tessellator: PathTessellator,
+    #[rust] triangles: Vec<PathTriangle>,
+    #[deref] pub draw_super: DrawQuad,
+    #[calc] pub p0: Vec2,
+    #[calc] pub p1: Vec2,
+    #[calc] pub p2: Vec2,
+    #[calc] pub outline: Vec3,
+    #[live] pub color: Vec4,
+}
+
+impl DrawPath {
+    pub fn fill(&mut self, cx: &mut Cx2d, path: &Path, fill_rule: FillRule) {
+        let contours = flatten_path(path, 0.25 / cx.current_dpi_factor(), | p | p);
+        self.triangles.clear();
+        self.tessellator.fill(&contours, fill_rule, &mut self.triangles);
+        self.draw_triangles(cx);
+    }
+
+    pub fn stroke(&mut self, cx: &mut Cx2d, path: &Path, style: &StrokeStyle) {
+        let tolerance = 0.25 / cx.current_dpi_factor();
+        let contours = flatten_path(path, tolerance, | p | p);
+        self.triangles.clear();
+        self.tessellator.stroke(&contours, style, tolerance, &mut self.triangles);
+        self.draw_triangles(cx);
+    }
+
+    fn draw_triangles(&mut self, cx: &mut Cx2d) {
+        if self.triangles.is_empty() {
+            return
+        }
+        self.draw_super.begin_many_instances(cx);
+        for i in 0..self.triangles.len() {
+            let triangle = self.triangles[i];
+            let rect = triangle.bounds();
+            let [a, b, c] = triangle.points;
+            self.p0 = (a - rect.pos).into();
+            self.p1 = (b - rect.pos).into();
+            self.p2 = (c - rect.pos).into();
+            self.outline = vec3(
+                triangle.outline[0] as u8 as f32,
+                triangle.outline[1] as u8 as f32,
+                triangle.outline[2] as u8 as f32
+            );
+            self.draw_super.draw_abs(cx, rect);
+        }
+        self.draw_super.end_many_instances(cx);
+    }
+}
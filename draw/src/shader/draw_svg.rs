@@ -0,0 +1,221 @@
+use {
+    std::rc::Rc,
+    crate::{
+        makepad_platform::*,
+        shader::draw_quad::DrawQuad,
+        svg::{SvgDocument, SvgMesh, SvgPaint, SvgGradientKind, SvgSpread},
+        turtle::{Walk, Size},
+        cx_2d::Cx2d,
+    },
+};
+
+live_design!{
+    DrawSvg = {{DrawSvg}} {
+        // signed distance to the edge a-b, positive on the inside of a counter clockwise triangle
+        fn edge_coverage(self, a: vec2, b: vec2, p: vec2, outline: float) -> float {
+            let e = b - a;
+            let d = (e.x * (p.y - a.y) - e.y * (p.x - a.x)) / max(length(e), 0.0001);
+            if outline > 0.5 {
+                return clamp(d * self.dpi_factor + 0.5, 0.0, 1.0);
+            }
+            return step(0.0, d);
+        }
+
+        fn gradient_color(self, t: float) -> vec4 {
+            if self.spread > 1.5 {
+                t = fract(t);
+            }
+            else if self.spread > 0.5 {
+                t = 1.0 - abs(mod(t, 2.0) - 1.0);
+            }
+            let o = self.stop_offsets;
+            if t <= o.y {
+                return mix(self.stop0, self.stop1, clamp((t - o.x) / max(o.y - o.x, 0.0001), 0.0, 1.0));
+            }
+            if t <= o.z {
+                return mix(self.stop1, self.stop2, clamp((t - o.y) / max(o.z - o.y, 0.0001), 0.0, 1.0));
+            }
+            return mix(self.stop2, self.stop3, clamp((t - o.z) / max(o.w - o.z, 0.0001), 0.0, 1.0));
+        }
+
+        fn pixel(self) -> vec4 {
+            let p = self.pos * self.rect_size;
+            let coverage = self.edge_coverage(self.p0, self.p1, p, self.outline.x)
+                * self.edge_coverage(self.p1, self.p2, p, self.outline.y)
+                * self.edge_coverage(self.p2, self.p0, p, self.outline.z);
+            let color = self.stop0;
+            if self.paint_kind > 0.5 {
+                let u = vec2(dot(self.grad_x, vec3(p, 1.0)), dot(self.grad_y, vec3(p, 1.0)));
+                let t = u.x;
+                if self.paint_kind > 1.5 {
+                    t = length(u);
+                }
+                color = self.gradient_color(t);
+            }
+            let alpha = color.w * coverage;
+            return vec4(color.xyz * alpha, alpha);
+        }
+    }
+}
+
+/// Draws SVG documents as antialiased triangles, retessellating only when the size
+/// they are drawn at changes. `currentColor` in the document draws in `color`.
+#[derive(Live)]
+#[repr(C)]
+pub struct DrawSvg {
+    #[live] pub svg_file: LiveDependency,
+    #[live] pub color: Vec4,
+    #[rust] pub document: Option<Rc<SvgDocument >>,
+    #[rust] loaded_dep: String,
+    #[rust] mesh: Option<SvgMesh>,
+    #[deref] pub draw_super: DrawQuad,
+    #[calc] pub p0: Vec2,
+    #[calc] pub p1: Vec2,
+    #[calc] pub p2: Vec2,
+    #[calc] pub outline: Vec3,
+    // 0 for a solid color, 1 for a linear and 2 for a radial gradient
+    #[calc] pub paint_kind: f32,
+    #[calc] pub grad_x: Vec3,
+    #[calc] pub grad_y: Vec3,
+    // 0 to pad, 1 to reflect and 2 to repeat
+    #[calc] pub spread: f32,
+    // a solid color is the first stop
+    #[calc] pub stop_offsets: Vec4,
+    #[calc] pub stop0: Vec4,
+    #[calc] pub stop1: Vec4,
+    #[calc] pub stop2: Vec4,
+    #[calc] pub stop3: Vec4,
+}
+
+impl LiveHook for DrawSvg {
+    fn after_apply(&mut self, cx: &mut Cx, _from: ApplyFrom, _index: usize, _nodes: &[LiveNode]) {
+        let dep = self.svg_file.as_str().to_string();
+        if dep.is_empty() || dep == self.loaded_dep {
+            return
+        }
+        self.loaded_dep = dep.clone();
+        match cx.get_dependency(&dep) {
+            Ok(data) => if let Err(e) = self.load_svg_from_data(&data) {
+                error!("Error in SVG {}: {}", dep, e);
+            }
+            Err(e) => error!("Error loading SVG {}: {}", dep, e)
+        }
+    }
+}
+
+impl DrawSvg {
+    pub fn load_svg_from_data(&mut self, data: &[u8]) -> Result<(), String> {
+        let svg = std::str::from_utf8(data).map_err( | e | e.to_string())?;
+        self.set_document(Some(Rc::new(SvgDocument::parse(svg)?)));
+        Ok(())
+    }
+
+    pub fn set_document(&mut self, document: Option<Rc<SvgDocument>>) {
+        self.document = document;
+        self.mesh = None;
+    }
+
+    /// Walks the turtle, a `Fit` width or height follows the aspect ratio of the document.
+    pub fn draw_walk(&mut self, cx: &mut Cx2d, mut walk: Walk) -> Rect {
+        let size = self.document.as_ref().map_or(dvec2(0.0, 0.0), | doc | doc.size);
+        let width_is_fit = walk.width.is_fit();
+        let height_is_fit = walk.height.is_fit();
+        if size.x > 0.0 && size.y > 0.0 && width_is_fit != height_is_fit {
+            let peek = cx.peek_walk_turtle(walk);
+            if width_is_fit {
+                walk.width = Size::Fixed(peek.size.y * size.x / size.y);
+            }
+            else {
+                walk.height = Size::Fixed(peek.size.x * size.y / size.x);
+            }
+        }
+        else {
+            if width_is_fit {
+                walk.width = Size::Fixed(size.x);
+            }
+            if height_is_fit {
+                walk.height = Size::Fixed(size.y);
+            }
+        }
+        let rect = cx.walk_turtle(walk);
+        self.draw_svg_abs(cx, rect);
+        rect
+    }
+
+    pub fn draw_svg_abs(&mut self, cx: &mut Cx2d, rect: Rect) {
+        if rect.is_nan() || rect.size.x <= 0.0 || rect.size.y <= 0.0 {
+            return
+        }
+        let document = if let Some(document) = &self.document {document.clone()} else {return};
+        let tolerance = 0.25 / cx.current_dpi_factor();
+        let mesh = match self.mesh.take() {
+            Some(mesh) if mesh.size == rect.size && mesh.tolerance == tolerance => mesh,
+            _ => document.tessellate(rect.size, tolerance)
+        };
+        if !mesh.triangles.is_empty() {
+            self.draw_super.begin_many_instances(cx);
+            for triangle in &mesh.triangles {
+                let bounds = triangle.triangle.bounds();
+                let [a, b, c] = triangle.triangle.points;
+                self.p0 = (a - bounds.pos).into();
+                self.p1 = (b - bounds.pos).into();
+                self.p2 = (c - bounds.pos).into();
+                self.outline = vec3(
+                    triangle.triangle.outline[0] as u8 as f32,
+                    triangle.triangle.outline[1] as u8 as f32,
+                    triangle.triangle.outline[2] as u8 as f32
+                );
+                self.set_paint(&mesh.paints[triangle.paint], bounds.pos);
+                self.draw_super.draw_abs(cx, Rect {pos: rect.pos + bounds.pos, size: bounds.size});
+            }
+            self.draw_super.end_many_instances(cx);
+        }
+        self.mesh = Some(mesh);
+    }
+
+    // gradients are mapped from mesh space, the shader works relative to the triangle rect at `offset`
+    fn set_paint(&mut self, paint: &SvgPaint, offset: DVec2) {
+        match paint {
+            SvgPaint::Color(color) => {
+                self.paint_kind = 0.0;
+                self.stop0 = *color;
+            }
+            SvgPaint::CurrentColor(opacity) => {
+                self.paint_kind = 0.0;
+                self.stop0 = vec4(self.color.x, self.color.y, self.color.z, self.color.w * opacity);
+            }
+            SvgPaint::Gradient(gradient) => {
+                self.paint_kind = match gradient.kind {
+                    SvgGradientKind::Linear => 1.0,
+                    SvgGradientKind::Radial => 2.0,
+                };
+                self.spread = match gradient.spread {
+                    SvgSpread::Pad => 0.0,
+                    SvgSpread::Reflect => 1.0,
+                    SvgSpread::Repeat => 2.0,
+                };
+                let t = &gradient.transform;
+                self.grad_x = vec3(t.a as f32, t.c as f32, (t.e + t.a * offset.x + t.c * offset.y) as f32);
+                self.grad_y = vec3(t.b as f32, t.d as f32, (t.f + t.b * offset.x + t.d * offset.y) as f32);
+                // the shader takes four stops, longer gradients are resampled evenly
+                let stops = &gradient.stops;
+                let stops: Vec<(f64, Vec4)> = if stops.len() <= 4 {
+                    stops.clone()
+                }
+                else {
+                    let (first, last) = (stops[0].0, stops[stops.len() - 1].0);
+                    (0..4).map( | i | {
+                        let offset = first + (last - first) * i as f64 / 3.0;
+                        (offset, gradient.color_at(offset))
+                    }).collect()
+                };
+                let stop = | i: usize | stops[i.min(stops.len() - 1)];
+                self.stop_offsets = vec4(stop(0).0 as f32, stop(1).0 as f32, stop(2).0 as f32, stop(3).0 as f32);
+                self.stop0 = stop(0).1;
+                self.stop1 = stop(1).1;
+                self.stop2 = stop(2).1;
+                self.stop3 = stop(3).1;
+            }
+        }
+    }
+}
@@ -2,7 +2,7 @@ use {
     crate::{
         makepad_platform::*,
         turtle::{Walk, Size, Align},
-        font_atlas::{CxFontsAtlasTodo, CxFont, CxFontsAtlas, Font},
+        font_atlas::{CxFontsAtlasTodo, CxFontsAtlas, Font, FontChain},
         draw_list_2d::ManyInstances,
         geometry::GeometryQuad2D,
         cx_2d::Cx2d
@@ -20,6 +20,7 @@ live_design!{
         uniform curve: float
         
         texture tex: texture2d
+        texture color_tex: texture2d
         
         varying tex_coord1: vec2
         varying tex_coord2: vec2
@@ -74,6 +75,10 @@ live_design!{
             return incol
         }
         fn pixel(self) -> vec4 {
+            // color glyphs are premultiplied and keep their colors, only the opacity applies
+            if self.color_glyph > 0.5 {
+                return sample2d(self.color_tex, self.tex_coord1.xy) * self.get_color().w;
+            }
             
             //let dx = dFdx(vec2(self.tex_coord1.x * 2048.0, 0.)).x;
             //let dp = 1.0 / 2048.0;
@@ -114,6 +119,10 @@ live_design!{
 #[live_ignore]
 pub struct TextStyle {
     #[live()] pub font: Font,
+    // tried in order for characters the font has no glyph for
+    #[live] pub fallback_fonts: Vec<Font>,
+    // after the fallback fonts, look for an installed font covering the character (Linux only)
+    #[live(false)] pub system_fallback: bool,
     #[live(9.0)] pub font_size: f64,
     #[live(1.0)] pub brightness: f32,
     #[live(0.6)] pub curve: f32,
@@ -122,6 +131,21 @@ pub struct TextStyle {
     #[live(1.3)] pub height_factor: f64,
 }
 
+impl TextStyle {
+    /// The font followed by the fallback fonts that loaded, empty without a font
+    pub fn font_chain(&self) -> FontChain {
+        let mut font_ids = Vec::new();
+        if let Some(font_id) = self.font.font_id {
+            font_ids.push(font_id);
+            font_ids.extend(self.fallback_fonts.iter().filter_map( | font | font.font_id));
+        }
+        FontChain {
+            font_ids,
+            system_fallback: self.system_fallback
+        }
+    }
+}
+
 #[derive(Clone, Live, LiveHook)]
 #[live_ignore]
 pub enum TextWrap {
@@ -161,7 +185,7 @@ impl<'a> WordIterator<'a> {
             font_size_total
         }
     }
-    fn next_word(&mut self, font: &mut CxFont) -> Option<WordIteratorItem> {
+    fn next_word(&mut self, fonts_atlas: &mut CxFontsAtlas, chain: &FontChain) -> Option<WordIteratorItem> {
         if let Some(char_iter) = &mut self.char_iter {
             while let Some((i, c)) = char_iter.next() {
                 self.last_index = i;
@@ -173,8 +197,8 @@ impl<'a> WordIterator<'a> {
                     with_newline: false
                 };
                 
-                let adv = if let Some(glyph) = font.get_glyph(c) {
-                    glyph.horizontal_metrics.advance_width * self.font_size_total
+                let adv = if let Some(advance) = fonts_atlas.char_advance(chain, c) {
+                    advance * self.font_size_total
                 }else {0.0};
                 
                 if c == '\r' {
@@ -233,6 +257,7 @@ pub struct TextGeom {
     pub eval_height: f64,
     pub measured_width: f64,
     pub measured_height: f64,
+    // byte offset of the cut, its x position and the number of dots
     pub ellip_pt: Option<(usize, f64, usize)>
 }
 
@@ -259,6 +284,7 @@ pub struct DrawText {
     #[calc] pub delta: Vec2,
     #[calc] pub font_size: f32,
     #[calc] pub advance: f32,
+    #[calc] pub color_glyph: f32,
 }
 
 impl LiveHook for DrawText {
@@ -318,11 +344,12 @@ impl DrawText {
     
     pub fn update_draw_call_vars(&mut self, font_atlas: &CxFontsAtlas) {
         self.draw_vars.texture_slots[0] = Some(font_atlas.texture_id);
+        self.draw_vars.texture_slots[1] = Some(font_atlas.color.texture_id);
         self.draw_vars.user_uniforms[0] = self.text_style.brightness;
         self.draw_vars.user_uniforms[1] = self.text_style.curve;
     }
     
-    fn draw_inner(&mut self, cx: &mut Cx2d, pos: DVec2, chunk: &str, fonts_atlas: &mut CxFontsAtlas) {
+    pub(crate) fn draw_inner(&mut self, cx: &mut Cx2d, pos: DVec2, chunk: &str, fonts_atlas: &mut CxFontsAtlas) {
         if !self.draw_vars.can_instance()
             || pos.x.is_nan()
             || pos.y.is_nan()
@@ -331,7 +358,8 @@ impl DrawText {
         }
         //self.draw_clip = cx.turtle().draw_clip().into();
         //let in_many = self.many_instances.is_some();
-        let font_id = self.text_style.font.font_id.unwrap();
+        let chain = self.text_style.font_chain();
+        let font_id = chain.primary().unwrap();
         
         if fonts_atlas.fonts[font_id].is_none() {
             return
@@ -348,24 +376,13 @@ impl DrawText {
             self.begin_many_instances_internal(cx, fonts_atlas);
         }
         
-        let cxfont = fonts_atlas.fonts[font_id].as_mut().unwrap();
         let dpi_factor = cx.current_dpi_factor();
         
-        let atlas_page_id = cxfont.get_atlas_page_id(dpi_factor, self.text_style.font_size);
-        
-        let font = &mut cxfont.ttf_font;
-        let owned_font_face = &cxfont.owned_font_face;
-        
-        let font_size_logical = self.text_style.font_size * 96.0 / (72.0 * font.units_per_em);
-        let font_size_pixels = font_size_logical * dpi_factor;
-        
-        let atlas_page = &mut cxfont.atlas_pages[atlas_page_id];
-        
-        let mi = if let Some(mi) = &mut self.many_instances {mi} else {return};
         let zbias_step = 0.00001;
         let mut char_depth = self.draw_depth;
         
         let mut rustybuzz_buffer = rustybuzz::UnicodeBuffer::new();
+        let mut font_runs = Vec::new();
         
         // This relies on the UBA ("Unicode Bidirectional Algorithm")
         // (see http://www.unicode.org/reports/tr9/#Basic_Display_Algorithm),
@@ -386,93 +403,144 @@ impl DrawText {
             for (run_level, run_range) in runs_with_level_and_range {
                 // FIXME(eddyb) UBA/`unicode_bidi` only offers a LTR/RTL distinction,
                 // even if `rustybuzz` has vertical `Direction`s as well.
-                let (glyph_ids, new_rustybuzz_buffer) = cxfont
-                    .shape_cache
-                    .get_or_compute_glyph_ids(
-                    (
-                            if run_level.is_rtl() {
-                                rustybuzz::Direction::RightToLeft
-                            } else {
-                                rustybuzz::Direction::LeftToRight
-                            },
-                            &bidi_info.text[run_range]
-                        ),
-                        rustybuzz_buffer,
-                        owned_font_face
-                    );
-                rustybuzz_buffer = new_rustybuzz_buffer;
-                for &glyph_id in glyph_ids {
-                    let glyph = owned_font_face.with_ref(|face| font.get_glyph_by_id(face, glyph_id).unwrap());
-                    
-                    let advance = glyph.horizontal_metrics.advance_width * font_size_logical * self.font_scale;
+                let direction = if run_level.is_rtl() {
+                    rustybuzz::Direction::RightToLeft
+                } else {
+                    rustybuzz::Direction::LeftToRight
+                };
+                let run = &bidi_info.text[run_range];
+                
+                // each run is split further into the parts drawn by one font of the chain,
+                // which are laid out right to left within a right to left run
+                font_runs.clear();
+                fonts_atlas.font_runs(&chain, run, &mut font_runs);
+                if run_level.is_rtl() {
+                    font_runs.reverse();
+                }
+                
+                for (font_id, font_range) in font_runs.drain(..) {
+                    let cxfont = fonts_atlas.fonts[font_id].as_mut().unwrap();
+                    let atlas_page_id = cxfont.get_atlas_page_id(dpi_factor, self.text_style.font_size);
+                    let has_color_glyphs = cxfont.has_color_glyphs;
                     
-                    // snap width/height to pixel granularity
-                    let w = ((glyph.bounds.p_max.x - glyph.bounds.p_min.x) * font_size_pixels).ceil() + 1.0;
-                    let h = ((glyph.bounds.p_max.y - glyph.bounds.p_min.y) * font_size_pixels).ceil() + 1.0;
+                    let font = &mut cxfont.ttf_font;
+                    let owned_font_face = &cxfont.owned_font_face;
                     
-                    // this one needs pixel snapping
-                    let min_pos_x = walk_x + font_size_logical * glyph.bounds.p_min.x;
-                    let min_pos_y = pos.y - font_size_logical * glyph.bounds.p_min.y + self.text_style.font_size * self.text_style.top_drop;
+                    let font_size_logical = self.text_style.font_size * 96.0 / (72.0 * font.units_per_em);
+                    let font_size_pixels = font_size_logical * dpi_factor;
                     
-                    // compute subpixel shift
-                    let subpixel_x_fract = min_pos_x - (min_pos_x * dpi_factor).floor() / dpi_factor;
-                    let subpixel_y_fract = min_pos_y - (min_pos_y * dpi_factor).floor() / dpi_factor;
-                    // scale and snap it
-                    // only use a subpixel id for small fonts
-                    let subpixel_id = if self.text_style.font_size>32.0 {
-                        0
-                    }
-                    else { // subtle 64 index subpixel id
-                        ((subpixel_y_fract * dpi_factor * 7.0) as usize) << 3 |
-                        (subpixel_x_fract * dpi_factor * 7.0) as usize
-                    };
+                    let atlas_page = &mut cxfont.atlas_pages[atlas_page_id];
                     
-                    let subpixel_map = if let Some(tc) = atlas_page.atlas_glyphs.get_mut(&glyph_id){
-                        tc
-                    }
-                    else{
-                        atlas_page.atlas_glyphs.insert(glyph_id, [None; crate::font_atlas::ATLAS_SUBPIXEL_SLOTS]);
-                        atlas_page.atlas_glyphs.get_mut(&glyph_id).unwrap()
-                    };
+                    let mi = if let Some(mi) = &mut self.many_instances {mi} else {return};
                     
-                    let tc = if let Some(tc) = &subpixel_map[subpixel_id]{
-                        tc
-                    }
-                    else {
-                        // see if we can fit it
-                        // allocate slot
-                        fonts_atlas.alloc.todo.push(CxFontsAtlasTodo {
-                            subpixel_x_fract,
-                            subpixel_y_fract,
-                            font_id,
-                            atlas_page_id,
-                            glyph_id,
-                            subpixel_id
-                        });
+                    let (glyph_ids, new_rustybuzz_buffer) = cxfont
+                        .shape_cache
+                        .get_or_compute_glyph_ids(
+                        (direction, &run[font_range]),
+                        rustybuzz_buffer,
+                        owned_font_face
+                    );
+                    rustybuzz_buffer = new_rustybuzz_buffer;
+                    for &glyph_id in glyph_ids {
+                        let glyph = owned_font_face.with_ref(|face| font.get_glyph_by_id(face, glyph_id).unwrap());
                         
-                        subpixel_map[subpixel_id] = Some(
-                            fonts_atlas.alloc.alloc_atlas_glyph(w, h)
-                        );
-                        subpixel_map[subpixel_id].as_ref().unwrap()
-                    };
-                    
-                    let delta_x = font_size_logical * self.font_scale * glyph.bounds.p_min.x - subpixel_x_fract;
-                    let delta_y = -font_size_logical * self.font_scale * glyph.bounds.p_min.y + self.text_style.font_size * self.font_scale * self.text_style.top_drop - subpixel_y_fract;
-                    // give the callback a chance to do things
-                    //et scaled_min_pos_x = walk_x + delta_x;
-                    //let scaled_min_pos_y = pos.y - delta_y;
-                    self.font_t1 = tc.t1;
-                    self.font_t2 = tc.t2;
-                    self.rect_pos = dvec2(walk_x + delta_x, pos.y + delta_y).into();
-                    self.rect_size = dvec2(w * self.font_scale / dpi_factor, h * self.font_scale / dpi_factor).into();
-                    self.char_depth = char_depth;
-                    self.delta.x = delta_x as f32;
-                    self.delta.y = delta_y as f32;
-                    self.font_size = self.text_style.font_size as f32;
-                    self.advance = advance as f32; //char_offset as f32;
-                    char_depth += zbias_step;
-                    mi.instances.extend_from_slice(self.draw_vars.as_slice());
-                    walk_x += advance;
+                        let advance = glyph.horizontal_metrics.advance_width * font_size_logical * self.font_scale;
+                        
+                        let color_glyph = if has_color_glyphs {
+                            owned_font_face.with_ref( | face | fonts_atlas.color.get_glyph(face, atlas_page, glyph_id))
+                        }
+                        else {
+                            None
+                        };
+                        if let Some(color_glyph) = color_glyph {
+                            let scale = self.font_scale / dpi_factor;
+                            let delta_x = color_glyph.left * scale;
+                            let delta_y = -color_glyph.bottom * scale + self.text_style.font_size * self.font_scale * self.text_style.top_drop;
+                            self.font_t1 = color_glyph.t1;
+                            self.font_t2 = color_glyph.t2;
+                            self.rect_pos = dvec2(walk_x + delta_x, pos.y + delta_y).into();
+                            self.rect_size = dvec2(color_glyph.width * scale, color_glyph.height * scale).into();
+                            self.char_depth = char_depth;
+                            self.delta.x = delta_x as f32;
+                            self.delta.y = delta_y as f32;
+                            self.font_size = self.text_style.font_size as f32;
+                            self.advance = advance as f32;
+                            self.color_glyph = 1.0;
+                            char_depth += zbias_step;
+                            mi.instances.extend_from_slice(self.draw_vars.as_slice());
+                            self.color_glyph = 0.0;
+                            walk_x += advance;
+                            continue;
+                        }
+                        
+                        // snap width/height to pixel granularity
+                        let w = ((glyph.bounds.p_max.x - glyph.bounds.p_min.x) * font_size_pixels).ceil() + 1.0;
+                        let h = ((glyph.bounds.p_max.y - glyph.bounds.p_min.y) * font_size_pixels).ceil() + 1.0;
+                        
+                        // this one needs pixel snapping
+                        let min_pos_x = walk_x + font_size_logical * glyph.bounds.p_min.x;
+                        let min_pos_y = pos.y - font_size_logical * glyph.bounds.p_min.y + self.text_style.font_size * self.text_style.top_drop;
+                        
+                        // compute subpixel shift
+                        let subpixel_x_fract = min_pos_x - (min_pos_x * dpi_factor).floor() / dpi_factor;
+                        let subpixel_y_fract = min_pos_y - (min_pos_y * dpi_factor).floor() / dpi_factor;
+                        // scale and snap it
+                        // only use a subpixel id for small fonts
+                        let subpixel_id = if self.text_style.font_size>32.0 {
+                            0
+                        }
+                        else { // subtle 64 index subpixel id
+                            ((subpixel_y_fract * dpi_factor * 7.0) as usize) << 3 |
+                            (subpixel_x_fract * dpi_factor * 7.0) as usize
+                        };
+                        
+                        let subpixel_map = if let Some(tc) = atlas_page.atlas_glyphs.get_mut(&glyph_id){
+                            tc
+                        }
+                        else{
+                            atlas_page.atlas_glyphs.insert(glyph_id, [None; crate::font_atlas::ATLAS_SUBPIXEL_SLOTS]);
+                            atlas_page.atlas_glyphs.get_mut(&glyph_id).unwrap()
+                        };
+                        
+                        let tc = if let Some(tc) = &subpixel_map[subpixel_id]{
+                            tc
+                        }
+                        else {
+                            // see if we can fit it
+                            // allocate slot
+                            fonts_atlas.alloc.todo.push(CxFontsAtlasTodo {
+                                subpixel_x_fract,
+                                subpixel_y_fract,
+                                font_id,
+                                atlas_page_id,
+                                glyph_id,
+                                subpixel_id
+                            });
+                            
+                            subpixel_map[subpixel_id] = Some(
+                                fonts_atlas.alloc.alloc_atlas_glyph(w, h)
+                            );
+                            subpixel_map[subpixel_id].as_ref().unwrap()
+                        };
+                        
+                        let delta_x = font_size_logical * self.font_scale * glyph.bounds.p_min.x - subpixel_x_fract;
+                        let delta_y = -font_size_logical * self.font_scale * glyph.bounds.p_min.y + self.text_style.font_size * self.font_scale * self.text_style.top_drop - subpixel_y_fract;
+                        // give the callback a chance to do things
+                        //et scaled_min_pos_x = walk_x + delta_x;
+                        //let scaled_min_pos_y = pos.y - delta_y;
+                        self.font_t1 = tc.t1;
+                        self.font_t2 = tc.t2;
+                        self.rect_pos = dvec2(walk_x + delta_x, pos.y + delta_y).into();
+                        self.rect_size = dvec2(w * self.font_scale / dpi_factor, h * self.font_scale / dpi_factor).into();
+                        self.char_depth = char_depth;
+                        self.delta.x = delta_x as f32;
+                        self.delta.y = delta_y as f32;
+                        self.font_size = self.text_style.font_size as f32;
+                        self.advance = advance as f32; //char_offset as f32;
+                        char_depth += zbias_step;
+                        mi.instances.extend_from_slice(self.draw_vars.as_slice());
+                        walk_x += advance;
+                    }
                 }
             }
         }
@@ -484,21 +552,23 @@ impl DrawText {
     
     fn compute_geom_inner(&self, cx: &Cx2d, walk: Walk, text: &str, fonts_atlas: &mut CxFontsAtlas) -> Option<TextGeom> {
         // we include the align factor and the width/height
-        let font_id = self.text_style.font.font_id.unwrap();
+        let chain = self.text_style.font_chain();
+        let font_id = chain.primary()?;
         
         if fonts_atlas.fonts[font_id].is_none() {
             return None
         }
         
-        let font_size_logical = self.text_style.font_size * 96.0 / (72.0 * fonts_atlas.fonts[font_id].as_ref().unwrap().ttf_font.units_per_em);
+        // advances are in ems of the font drawing the character, fallback fonts can differ in units per em
+        let font_size_em = self.text_style.font_size * 96.0 / 72.0 * self.font_scale;
         let line_height = self.text_style.font_size * self.text_style.height_factor * self.font_scale;
-        let eval_width = cx.turtle().eval_width(walk.width, walk.margin, cx.turtle().layout().flow);
-        let eval_height = cx.turtle().eval_height(walk.height, walk.margin, cx.turtle().layout().flow);
+        let eval_size = cx.turtle().eval_walk_size(&walk);
+        let (eval_width, eval_height) = (eval_size.x, eval_size.y);
         
         match if walk.width.is_fit() {&TextWrap::Line}else {&self.wrap} {
             TextWrap::Ellipsis => {
-                let ellip_width = if let Some(glyph) = fonts_atlas.fonts[font_id].as_mut().unwrap().get_glyph('.') {
-                    glyph.horizontal_metrics.advance_width * font_size_logical * self.font_scale
+                let ellip_width = if let Some(advance) = fonts_atlas.char_advance(&chain, '.') {
+                    advance * font_size_em
                 }
                 else {
                     0.0
@@ -506,13 +576,13 @@ impl DrawText {
                 
                 let mut measured_width = 0.0;
                 let mut ellip_pt = None;
-                for (i, c) in text.chars().enumerate() {
+                for (i, c) in text.char_indices() {
                     
                     if measured_width + ellip_width * 3.0 < eval_width {
                         ellip_pt = Some((i, measured_width, 3));
                     }
-                    if let Some(glyph) = fonts_atlas.fonts[font_id].as_mut().unwrap().get_glyph(c) {
-                        let adv = glyph.horizontal_metrics.advance_width * font_size_logical * self.font_scale;
+                    if let Some(advance) = fonts_atlas.char_advance(&chain, c) {
+                        let adv = advance * font_size_em;
                         // ok so now what.
                         if measured_width + adv >= eval_width { // we have to drop back to ellip_pt
                             // if we don't have an ellip_pt, set it to 0
@@ -548,8 +618,8 @@ impl DrawText {
                 let mut measured_width = 0.0;
                 let mut measured_height = line_height;
                 
-                let mut iter = WordIterator::new(text.char_indices(), eval_width, font_size_logical * self.font_scale);
-                while let Some(word) = iter.next_word(fonts_atlas.fonts[font_id].as_mut().unwrap()) {
+                let mut iter = WordIterator::new(text.char_indices(), eval_width, font_size_em);
+                while let Some(word) = iter.next_word(fonts_atlas, &chain) {
                     if measured_width + word.width >= eval_width {
                         measured_height += line_height * self.text_style.line_spacing;
                         measured_width = word.width;
@@ -581,9 +651,8 @@ impl DrawText {
                     if c == '\n' {
                         measured_height += line_height * self.text_style.line_spacing;
                     }
-                    if let Some(glyph) = fonts_atlas.fonts[font_id].as_mut().unwrap().get_glyph(c) {
-                        let adv = glyph.horizontal_metrics.advance_width * font_size_logical * self.font_scale;
-                        measured_width += adv;
+                    if let Some(advance) = fonts_atlas.char_advance(&chain, c) {
+                        measured_width += advance * font_size_em;
                     }
                     if measured_width > max_width {
                         max_width = measured_width;
@@ -602,10 +671,10 @@ impl DrawText {
     
     
     pub fn draw_walk(&mut self, cx: &mut Cx2d, walk: Walk, align: Align, text: &str) {
-        let font_id = if let Some(font_id) = self.text_style.font.font_id{font_id}else{
+        if self.text_style.font.font_id.is_none() {
             //log!("Draw text without font");
             return
-        };
+        }
         let fonts_atlas_rc = cx.fonts_atlas_rc.clone();
         let mut fonts_atlas = fonts_atlas_rc.0.borrow_mut();
         let fonts_atlas = &mut*fonts_atlas;
@@ -635,7 +704,8 @@ impl DrawText {
                             abs_pos: walk.abs_pos,
                             margin: walk.margin,
                             width: Size::Fixed(geom.eval_width),
-                            height: Size::Fixed(height)
+                            height: Size::Fixed(height),
+                            ..walk
                         });
                         
                         self.draw_inner(cx, rect.pos + dvec2(0.0, y_align), &text[0..ellip], fonts_atlas);
@@ -652,26 +722,29 @@ impl DrawText {
                                 } else {
                                     geom.eval_height
                                 }
-                            )
+                            ),
+                            ..walk
                         });
                         let x_align = (geom.eval_width - geom.measured_width) * align.x;
                         self.draw_inner(cx, rect.pos + dvec2(x_align, y_align), text, fonts_atlas);
                     }
                 }
                 TextWrap::Word => {
-                    let font_size_logical = self.text_style.font_size * 96.0 / (72.0 * fonts_atlas.fonts[font_id].as_ref().unwrap().ttf_font.units_per_em);
+                    let chain = self.text_style.font_chain();
+                    let font_size_em = self.text_style.font_size * 96.0 / 72.0 * self.font_scale;
                     let line_height = self.text_style.font_size * self.text_style.height_factor * self.font_scale;
                     
                     let rect = cx.walk_turtle(Walk {
                         abs_pos: walk.abs_pos,
                         margin: walk.margin,
                         width: Size::Fixed(geom.eval_width),
-                        height: Size::Fixed(geom.measured_height)
+                        height: Size::Fixed(geom.measured_height),
+                        ..walk
                     });
                     let mut pos = dvec2(0.0, 0.0);
                     
-                    let mut iter = WordIterator::new(text.char_indices(), geom.eval_width, font_size_logical * self.font_scale);
-                    while let Some(word) = iter.next_word(fonts_atlas.fonts[font_id].as_mut().unwrap()) {
+                    let mut iter = WordIterator::new(text.char_indices(), geom.eval_width, font_size_em);
+                    while let Some(word) = iter.next_word(fonts_atlas, &chain) {
                         if pos.x + word.width >= geom.eval_width {
                             pos.y += line_height * self.text_style.line_spacing;
                             pos.x = 0.0;
@@ -692,7 +765,8 @@ impl DrawText {
                         abs_pos: walk.abs_pos,
                         margin: walk.margin,
                         width: Size::Fixed(geom.measured_width),
-                        height: Size::Fixed(height)
+                        height: Size::Fixed(height),
+                        ..walk
                     });
                     // lets do our y alignment
                     let mut ypos = 0.0;
@@ -5,3 +5,6 @@ pub mod draw_icon;
 pub mod draw_text;
 pub mod std;
 pub mod draw_trapezoid;
+pub mod draw_path;
+pub mod draw_svg;
+pub mod draw_mesh;
@@ -0,0 +1,1323 @@
+//! SVG documents, parsed into filled and stroked paths and tessellated into triangles for
+//! `DrawSvg`. Covers the static subset icons and illustrations use: paths and the basic
+//! shapes, groups and `use`, transforms, fill and stroke, linear and radial gradients and
+//! the viewBox. Text, filters, masks, clip paths and stylesheets are ignored.
+//!
+//! Paths are kept in viewBox space, tessellation flattens them at a given output size so
+//! curves stay smooth at any scale, and then fills and strokes them with `bender`.
+
+use {
+    std::collections::HashMap,
+    crate::{
+        makepad_platform::*,
+        makepad_vector::{
+            geometry::Point,
+            path::{LinePathCommand, Path, PathIterator},
+        },
+        path_tessellator::{PathTessellator, PathTriangle, FillRule, LineJoin, LineCap, StrokeStyle, flatten_path},
+    },
+};
+
+/// An affine transform in the order of SVG's `matrix(a b c d e f)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SvgTransform {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Default for SvgTransform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl SvgTransform {
+    pub const IDENTITY: Self = Self {a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0};
+
+    pub fn translate(x: f64, y: f64) -> Self {
+        Self {e: x, f: y, ..Self::IDENTITY}
+    }
+
+    pub fn scale(x: f64, y: f64) -> Self {
+        Self {a: x, d: y, ..Self::IDENTITY}
+    }
+
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self {a: cos, b: sin, c: -sin, d: cos, e: 0.0, f: 0.0}
+    }
+
+    pub fn skew_x(degrees: f64) -> Self {
+        Self {c: degrees.to_radians().tan(), ..Self::IDENTITY}
+    }
+
+    pub fn skew_y(degrees: f64) -> Self {
+        Self {b: degrees.to_radians().tan(), ..Self::IDENTITY}
+    }
+
+    /// The transform that applies `self` first and `next` after it.
+    pub fn then(&self, next: &SvgTransform) -> SvgTransform {
+        SvgTransform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    pub fn inverse(&self) -> Option<SvgTransform> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < 1e-12 {
+            return None
+        }
+        let (a, b, c, d) = (self.d / det, -self.b / det, -self.c / det, self.a / det);
+        Some(SvgTransform {a, b, c, d, e: -(a * self.e + c * self.f), f: -(b * self.e + d * self.f)})
+    }
+
+    pub fn apply(&self, p: DVec2) -> DVec2 {
+        dvec2(self.a * p.x + self.c * p.y + self.e, self.b * p.x + self.d * p.y + self.f)
+    }
+
+    /// How much the transform scales lengths on average, used for stroke widths.
+    pub fn scale_factor(&self) -> f64 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgGradientKind {
+    Linear,
+    Radial,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgSpread {
+    Pad,
+    Reflect,
+    Repeat,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgGradient {
+    pub kind: SvgGradientKind,
+    /// Maps a point to gradient space. A linear gradient runs along x from 0 to 1, a radial
+    /// gradient runs from its center outwards and reaches 1 at its radius.
+    pub transform: SvgTransform,
+    /// Offsets in increasing order with non premultiplied colors.
+    pub stops: Vec<(f64, Vec4)>,
+    pub spread: SvgSpread,
+}
+
+impl SvgGradient {
+    pub fn color_at(&self, t: f64) -> Vec4 {
+        let t = match self.spread {
+            SvgSpread::Pad => t,
+            SvgSpread::Repeat => t - t.floor(),
+            SvgSpread::Reflect => 1.0 - ((t.rem_euclid(2.0)) - 1.0).abs(),
+        };
+        let first = self.stops[0];
+        if t <= first.0 {
+            return first.1
+        }
+        for pair in self.stops.windows(2) {
+            let ((o0, c0), (o1, c1)) = (pair[0], pair[1]);
+            if t <= o1 {
+                let f = if o1 > o0 {((t - o0) / (o1 - o0)) as f32} else {1.0};
+                return c0 + (c1 - c0) * f
+            }
+        }
+        self.stops[self.stops.len() - 1].1
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SvgPaint {
+    Color(Vec4),
+    /// `currentColor`, filled in by whoever draws the document, with an opacity.
+    CurrentColor(f32),
+    Gradient(SvgGradient),
+}
+
+impl SvgPaint {
+    /// The same paint for points that are mapped through `transform` before they reach the
+    /// space the paint was defined in.
+    pub fn mapped(&self, transform: &SvgTransform) -> SvgPaint {
+        match self {
+            SvgPaint::Gradient(gradient) => SvgPaint::Gradient(SvgGradient {
+                transform: transform.then(&gradient.transform),
+                ..gradient.clone()
+            }),
+            paint => paint.clone()
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgStroke {
+    pub paint: SvgPaint,
+    /// With the width in viewBox units.
+    pub style: StrokeStyle,
+}
+
+/// A path with its paints, in viewBox space.
+#[derive(Clone, Debug)]
+pub struct SvgShape {
+    pub path: Path,
+    pub fill: Option<SvgPaint>,
+    pub fill_rule: FillRule,
+    pub stroke: Option<SvgStroke>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SvgDocument {
+    /// The intrinsic size from the `width` and `height` attributes.
+    pub size: DVec2,
+    pub view_box: Rect,
+    /// False for `preserveAspectRatio="none"`, which stretches the viewBox.
+    pub preserve_aspect: bool,
+    /// In painting order.
+    pub shapes: Vec<SvgShape>,
+}
+
+/// A triangle of a tessellated document, in pixels from the top left of the output rect.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SvgTriangle {
+    pub triangle: PathTriangle,
+    pub paint: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct SvgMesh {
+    pub size: DVec2,
+    pub tolerance: f64,
+    /// Paints of the triangles, mapped to the pixel space of the mesh.
+    pub paints: Vec<SvgPaint>,
+    /// In painting order.
+    pub triangles: Vec<SvgTriangle>,
+}
+
+impl SvgDocument {
+    pub fn parse(svg: &str) -> Result<SvgDocument, String> {
+        let root = XmlParser {data: svg.as_bytes(), pos: 0}.parse_document()?;
+        if root.name != "svg" {
+            return Err(format!("root element is <{}> instead of <svg>", root.name))
+        }
+        let mut ids = HashMap::new();
+        collect_ids(&root, &mut ids);
+
+        let view_box = root.attr("viewBox").and_then( | v | {
+            let v = parse_number_list(v);
+            if v.len() == 4 && v[2] > 0.0 && v[3] > 0.0 {
+                Some(Rect {pos: dvec2(v[0], v[1]), size: dvec2(v[2], v[3])})
+            }
+            else {
+                None
+            }
+        });
+        let width = root.attr("width").and_then(parse_length);
+        let height = root.attr("height").and_then(parse_length);
+        let size = match (width, height, view_box) {
+            (Some(w), Some(h), _) => dvec2(w, h),
+            (Some(w), None, Some(vb)) => dvec2(w, w * vb.size.y / vb.size.x),
+            (None, Some(h), Some(vb)) => dvec2(h * vb.size.x / vb.size.y, h),
+            (None, None, Some(vb)) => vb.size,
+            _ => dvec2(width.unwrap_or(100.0), height.unwrap_or(100.0))
+        };
+        let view_box = view_box.unwrap_or(Rect {pos: dvec2(0.0, 0.0), size});
+        let preserve_aspect = root.attr("preserveAspectRatio").map_or(true, | v | v.trim() != "none");
+
+        let mut parser = SvgParser {
+            ids,
+            view_box,
+            shapes: Vec::new(),
+        };
+        parser.children(&root, &SvgStyle::default(), &SvgTransform::IDENTITY, 0);
+        Ok(SvgDocument {
+            size,
+            view_box,
+            preserve_aspect,
+            shapes: parser.shapes,
+        })
+    }
+
+    /// Maps the viewBox onto a rect of the given size, centered and scaled uniformly
+    /// unless the aspect ratio isn't preserved.
+    pub fn view_transform(&self, size: DVec2) -> SvgTransform {
+        let mut scale = size / self.view_box.size;
+        let mut offset = dvec2(0.0, 0.0);
+        if self.preserve_aspect {
+            let s = scale.x.min(scale.y);
+            scale = dvec2(s, s);
+            offset = (size - self.view_box.size * s) * 0.5;
+        }
+        SvgTransform::translate(-self.view_box.pos.x, -self.view_box.pos.y)
+            .then(&SvgTransform::scale(scale.x, scale.y))
+            .then(&SvgTransform::translate(offset.x, offset.y))
+    }
+
+    /// Tessellates the document for a rect of the given size, flattening curves to within
+    /// `tolerance` pixels.
+    pub fn tessellate(&self, size: DVec2, tolerance: f64) -> SvgMesh {
+        let view = self.view_transform(size);
+        let pixel_to_view = view.inverse().unwrap_or_default();
+        let mut mesh = SvgMesh {
+            size,
+            tolerance,
+            paints: Vec::new(),
+            triangles: Vec::new(),
+        };
+        let mut tessellator = PathTessellator::default();
+        let mut triangles = Vec::new();
+        for shape in &self.shapes {
+            let contours = flatten_path(&shape.path, tolerance, | p | view.apply(p));
+            if let Some(fill) = &shape.fill {
+                tessellator.fill(&contours, shape.fill_rule, &mut triangles);
+                mesh.push_triangles(fill.mapped(&pixel_to_view), &mut triangles);
+            }
+            if let Some(stroke) = &shape.stroke {
+                let style = StrokeStyle {width: stroke.style.width * view.scale_factor(), ..stroke.style};
+                tessellator.stroke(&contours, &style, tolerance, &mut triangles);
+                mesh.push_triangles(stroke.paint.mapped(&pixel_to_view), &mut triangles);
+            }
+        }
+        mesh
+    }
+}
+
+impl SvgMesh {
+    fn push_triangles(&mut self, paint: SvgPaint, triangles: &mut Vec<PathTriangle>) {
+        if triangles.is_empty() {
+            return
+        }
+        let paint_index = self.paints.len();
+        self.paints.push(paint);
+        self.triangles.extend(triangles.drain(..).map( | triangle | SvgTriangle {triangle, paint: paint_index}));
+    }
+}
+
+// Parsing
+
+#[derive(Clone, Debug)]
+enum PaintRef {
+    None,
+    Color(Vec4),
+    CurrentColor,
+    Url(String, Option<Vec4>),
+}
+
+#[derive(Clone, Debug)]
+struct SvgStyle {
+    fill: PaintRef,
+    fill_opacity: f64,
+    fill_rule: FillRule,
+    stroke: PaintRef,
+    stroke_opacity: f64,
+    stroke_width: f64,
+    line_join: LineJoin,
+    line_cap: LineCap,
+    miter_limit: f64,
+    // not inherited, the product of the opacities of the element and its ancestors
+    opacity: f64,
+    visible: bool,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            fill: PaintRef::Color(vec4(0.0, 0.0, 0.0, 1.0)),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: PaintRef::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            line_join: LineJoin::Miter,
+            line_cap: LineCap::Butt,
+            miter_limit: 4.0,
+            opacity: 1.0,
+            visible: true,
+        }
+    }
+}
+
+impl SvgStyle {
+    fn apply(&mut self, name: &str, value: &str) {
+        let value = value.trim();
+        if value == "inherit" {
+            return
+        }
+        match name {
+            "fill" => if let Some(paint) = parse_paint(value) {self.fill = paint},
+            "stroke" => if let Some(paint) = parse_paint(value) {self.stroke = paint},
+            "fill-opacity" => if let Some(v) = parse_opacity(value) {self.fill_opacity = v},
+            "stroke-opacity" => if let Some(v) = parse_opacity(value) {self.stroke_opacity = v},
+            "opacity" => if let Some(v) = parse_opacity(value) {self.opacity *= v},
+            "fill-rule" => self.fill_rule = if value == "evenodd" {FillRule::EvenOdd} else {FillRule::NonZero},
+            "stroke-width" => if let Some(v) = parse_length(value) {self.stroke_width = v},
+            "stroke-miterlimit" => if let Ok(v) = value.parse() {self.miter_limit = v},
+            "stroke-linejoin" => self.line_join = match value {
+                "round" => LineJoin::Round,
+                "bevel" => LineJoin::Bevel,
+                _ => LineJoin::Miter
+            },
+            "stroke-linecap" => self.line_cap = match value {
+                "round" => LineCap::Round,
+                "square" => LineCap::Square,
+                _ => LineCap::Butt
+            },
+            "display" => if value == "none" {self.visible = false},
+            "visibility" => self.visible = value == "visible",
+            _ => ()
+        }
+    }
+}
+
+struct SvgParser<'a> {
+    ids: HashMap<&'a str, &'a XmlElement<'a >>,
+    view_box: Rect,
+    shapes: Vec<SvgShape>,
+}
+
+impl<'a> SvgParser<'a> {
+    fn children(&mut self, element: &'a XmlElement<'a>, style: &SvgStyle, transform: &SvgTransform, depth: usize) {
+        for child in &element.children {
+            self.element(child, style, transform, depth);
+        }
+    }
+
+    fn element(&mut self, element: &'a XmlElement<'a>, parent_style: &SvgStyle, parent_transform: &SvgTransform, depth: usize) {
+        if depth > 32 {
+            return
+        }
+        let mut style = parent_style.clone();
+        style.visible = true;
+        for (name, value) in &element.attrs {
+            style.apply(name, value);
+        }
+        if let Some(css) = element.attr("style") {
+            for decl in css.split(';') {
+                if let Some((name, value)) = decl.split_once(':') {
+                    style.apply(name.trim(), value);
+                }
+            }
+        }
+        if !style.visible {
+            return
+        }
+        let mut transform = element.attr("transform").map_or(SvgTransform::IDENTITY, parse_transform).then(parent_transform);
+
+        let path = match element.name {
+            "g" | "svg" | "a" | "switch" => {
+                if element.name == "svg" {
+                    let x = element.attr("x").and_then(parse_length).unwrap_or(0.0);
+                    let y = element.attr("y").and_then(parse_length).unwrap_or(0.0);
+                    transform = SvgTransform::translate(x, y).then(&transform);
+                }
+                self.children(element, &style, &transform, depth + 1);
+                return
+            }
+            "use" => {
+                let href = element.attr("href").or_else( || element.attr("xlink:href"));
+                if let Some(target) = href.and_then( | href | href.strip_prefix('#')).and_then( | id | self.ids.get(id)) {
+                    let x = element.attr("x").and_then(parse_length).unwrap_or(0.0);
+                    let y = element.attr("y").and_then(parse_length).unwrap_or(0.0);
+                    let transform = SvgTransform::translate(x, y).then(&transform);
+                    if target.name == "symbol" {
+                        self.children(target, &style, &transform, depth + 1);
+                    }
+                    else {
+                        self.element(target, &style, &transform, depth + 1);
+                    }
+                }
+                return
+            }
+            "path" => element.attr("d").map(parse_path_data).unwrap_or_default(),
+            "rect" => {
+                let x = self.length(element, "x", 0);
+                let y = self.length(element, "y", 1);
+                let w = self.length(element, "width", 0);
+                let h = self.length(element, "height", 1);
+                let rx = element.attr("rx").and_then(parse_length);
+                let ry = element.attr("ry").and_then(parse_length);
+                let (rx, ry) = match (rx, ry) {
+                    (Some(rx), Some(ry)) => (rx, ry),
+                    (Some(r), None) | (None, Some(r)) => (r, r),
+                    (None, None) => (0.0, 0.0)
+                };
+                if w <= 0.0 || h <= 0.0 {
+                    return
+                }
+                rect_path(x, y, w, h, rx.min(w * 0.5), ry.min(h * 0.5))
+            }
+            "circle" => {
+                let r = self.length(element, "r", 2);
+                ellipse_path(self.length(element, "cx", 0), self.length(element, "cy", 1), r, r)
+            }
+            "ellipse" => ellipse_path(
+                self.length(element, "cx", 0),
+                self.length(element, "cy", 1),
+                self.length(element, "rx", 0),
+                self.length(element, "ry", 1)
+            ),
+            "line" => {
+                let mut path = Path::new();
+                path.move_to(Point::new(self.length(element, "x1", 0), self.length(element, "y1", 1)));
+                path.line_to(Point::new(self.length(element, "x2", 0), self.length(element, "y2", 1)));
+                path
+            }
+            "polyline" | "polygon" => {
+                let points = parse_number_list(element.attr("points").unwrap_or(""));
+                let mut path = Path::new();
+                for (i, p) in points.chunks_exact(2).enumerate() {
+                    if i == 0 {
+                        path.move_to(Point::new(p[0], p[1]));
+                    }
+                    else {
+                        path.line_to(Point::new(p[0], p[1]));
+                    }
+                }
+                if element.name == "polygon" && points.len() >= 2 {
+                    path.close();
+                }
+                path
+            }
+            _ => return
+        };
+        if path.points().is_empty() {
+            return
+        }
+
+        // gradients in bounding box units need the bounds before the path is transformed
+        let bounds = path_bounds(&path);
+        let fill = self.paint(&style.fill, style.fill_opacity * style.opacity, bounds, &transform);
+        let stroke = self.paint(&style.stroke, style.stroke_opacity * style.opacity, bounds, &transform).map( | paint | SvgStroke {
+            paint,
+            style: StrokeStyle {
+                width: style.stroke_width * transform.scale_factor(),
+                line_join: style.line_join,
+                line_cap: style.line_cap,
+                miter_limit: style.miter_limit,
+            },
+        });
+        if fill.is_none() && stroke.is_none() {
+            return
+        }
+        let mut path = path;
+        for point in path.points_mut() {
+            let p = transform.apply(dvec2(point.x, point.y));
+            *point = Point::new(p.x, p.y);
+        }
+        self.shapes.push(SvgShape {
+            path,
+            fill,
+            fill_rule: style.fill_rule,
+            stroke,
+        });
+    }
+
+    // axis 0 and 1 resolve percentages against the viewBox width and height, 2 against its diagonal
+    fn length(&self, element: &XmlElement, name: &str, axis: usize) -> f64 {
+        element.attr(name).and_then( | v | parse_length_or_percent(v, self.view_box.size, axis)).unwrap_or(0.0)
+    }
+
+    fn paint(&self, paint: &PaintRef, opacity: f64, bounds: Rect, transform: &SvgTransform) -> Option<SvgPaint> {
+        let with_opacity = | color: Vec4 | vec4(color.x, color.y, color.z, color.w * opacity as f32);
+        match paint {
+            PaintRef::None => None,
+            PaintRef::Color(color) => Some(SvgPaint::Color(with_opacity(*color))),
+            PaintRef::CurrentColor => Some(SvgPaint::CurrentColor(opacity as f32)),
+            PaintRef::Url(id, fallback) => {
+                if let Some(gradient) = self.gradient(id, bounds, transform) {
+                    let stops: Vec<(f64, Vec4)> = gradient.stops.iter().map( | (o, c) | (*o, with_opacity(*c))).collect();
+                    if stops.len() == 1 {
+                        return Some(SvgPaint::Color(stops[0].1))
+                    }
+                    return Some(SvgPaint::Gradient(SvgGradient {stops, ..gradient}))
+                }
+                fallback.map( | color | SvgPaint::Color(with_opacity(color)))
+            }
+        }
+    }
+
+    fn gradient(&self, id: &str, bounds: Rect, transform: &SvgTransform) -> Option<SvgGradient> {
+        let element = *self.ids.get(id)?;
+        let kind = match element.name {
+            "linearGradient" => SvgGradientKind::Linear,
+            "radialGradient" => SvgGradientKind::Radial,
+            _ => return None
+        };
+        // attributes and stops are inherited through href
+        let mut chain = vec![element];
+        while chain.len() < 8 {
+            let last = chain[chain.len() - 1];
+            let href = last.attr("href").or_else( || last.attr("xlink:href"));
+            if let Some(next) = href.and_then( | href | href.strip_prefix('#')).and_then( | id | self.ids.get(id)) {
+                chain.push(next);
+            }
+            else {
+                break
+            }
+        }
+        let attr = | name: &str | chain.iter().find_map( | e | e.attr(name));
+        let stops_element = chain.iter().find( | e | e.children.iter().any( | c | c.name == "stop"))?;
+        let mut stops: Vec<(f64, Vec4)> = Vec::new();
+        for stop in stops_element.children.iter().filter( | c | c.name == "stop") {
+            let mut color = vec4(0.0, 0.0, 0.0, 1.0);
+            let mut opacity = 1.0;
+            let mut apply = | name: &str, value: &str | match name {
+                "stop-color" => if let Some(PaintRef::Color(c)) = parse_paint(value.trim()) {color = c},
+                "stop-opacity" => if let Some(v) = parse_opacity(value.trim()) {opacity = v},
+                _ => ()
+            };
+            for (name, value) in &stop.attrs {
+                apply(name, value);
+            }
+            if let Some(css) = stop.attr("style") {
+                for decl in css.split(';') {
+                    if let Some((name, value)) = decl.split_once(':') {
+                        apply(name.trim(), value);
+                    }
+                }
+            }
+            let offset = stop.attr("offset").and_then(parse_offset).unwrap_or(0.0).max(stops.last().map_or(0.0, | s | s.0));
+            stops.push((offset, vec4(color.x, color.y, color.z, color.w * opacity as f32)));
+        }
+        if stops.is_empty() {
+            return None
+        }
+
+        let bounding_box = attr("gradientUnits").map_or(true, | v | v != "userSpaceOnUse");
+        // percentages are of the bounding box, or of the viewBox in user space units
+        let reference = if bounding_box {dvec2(1.0, 1.0)} else {self.view_box.size};
+        let coord = | name: &str, axis: usize, default: f64 | attr(name).and_then( | v | {
+            if bounding_box && !v.trim().ends_with('%') {
+                v.trim().parse().ok()
+            }
+            else {
+                parse_length_or_percent(v, reference, axis)
+            }
+        }).unwrap_or(default * if axis == 0 {reference.x} else if axis == 1 {reference.y} else {diagonal(reference)});
+
+        let mut to_user = attr("gradientTransform").map_or(SvgTransform::IDENTITY, parse_transform);
+        if bounding_box {
+            if bounds.size.x <= 0.0 || bounds.size.y <= 0.0 {
+                return None
+            }
+            to_user = to_user.then(&SvgTransform::scale(bounds.size.x, bounds.size.y)).then(&SvgTransform::translate(bounds.pos.x, bounds.pos.y));
+        }
+        let to_view = to_user.then(transform).inverse()?;
+        let to_gradient = match kind {
+            SvgGradientKind::Linear => {
+                let (x1, y1) = (coord("x1", 0, 0.0), coord("y1", 1, 0.0));
+                let (x2, y2) = (coord("x2", 0, 1.0), coord("y2", 1, 0.0));
+                let (dx, dy) = (x2 - x1, y2 - y1);
+                let len_sq = dx * dx + dy * dy;
+                if len_sq == 0.0 {
+                    return Some(SvgGradient {kind, transform: to_view, stops: vec![stops[stops.len() - 1]], spread: SvgSpread::Pad})
+                }
+                SvgTransform {a: dx / len_sq, b: 0.0, c: dy / len_sq, d: 0.0, e: -(x1 * dx + y1 * dy) / len_sq, f: 0.0}
+            }
+            SvgGradientKind::Radial => {
+                let (cx, cy, r) = (coord("cx", 0, 0.5), coord("cy", 1, 0.5), coord("r", 2, 0.5));
+                if r <= 0.0 {
+                    return Some(SvgGradient {kind, transform: to_view, stops: vec![stops[stops.len() - 1]], spread: SvgSpread::Pad})
+                }
+                SvgTransform {a: 1.0 / r, b: 0.0, c: 0.0, d: 1.0 / r, e: -cx / r, f: -cy / r}
+            }
+        };
+        let spread = match attr("spreadMethod") {
+            Some("reflect") => SvgSpread::Reflect,
+            Some("repeat") => SvgSpread::Repeat,
+            _ => SvgSpread::Pad
+        };
+        Some(SvgGradient {kind, transform: to_view.then(&to_gradient), stops, spread})
+    }
+}
+
+fn diagonal(size: DVec2) -> f64 {
+    (size.x * size.x + size.y * size.y).sqrt() / 2f64.sqrt()
+}
+
+fn path_bounds(path: &Path) -> Rect {
+    use crate::makepad_vector::internal_iter::InternalIterator;
+    let mut min = dvec2(f64::INFINITY, f64::INFINITY);
+    let mut max = dvec2(f64::NEG_INFINITY, f64::NEG_INFINITY);
+    path.commands().linearize(0.01).for_each(&mut | command | {
+        if let LinePathCommand::MoveTo(p) | LinePathCommand::LineTo(p) = command {
+            min = dvec2(min.x.min(p.x), min.y.min(p.y));
+            max = dvec2(max.x.max(p.x), max.y.max(p.y));
+        }
+        true
+    });
+    Rect {pos: min, size: max - min}
+}
+
+// the four cubic quarter circles of an ellipse approximate it to within 0.03%
+const ARC_K: f64 = 0.5522847498;
+
+fn ellipse_path(cx: f64, cy: f64, rx: f64, ry: f64) -> Path {
+    let mut path = Path::new();
+    if rx <= 0.0 || ry <= 0.0 {
+        return path
+    }
+    let (kx, ky) = (rx * ARC_K, ry * ARC_K);
+    path.move_to(Point::new(cx + rx, cy));
+    path.cubic_to(Point::new(cx + rx, cy + ky), Point::new(cx + kx, cy + ry), Point::new(cx, cy + ry));
+    path.cubic_to(Point::new(cx - kx, cy + ry), Point::new(cx - rx, cy + ky), Point::new(cx - rx, cy));
+    path.cubic_to(Point::new(cx - rx, cy - ky), Point::new(cx - kx, cy - ry), Point::new(cx, cy - ry));
+    path.cubic_to(Point::new(cx + kx, cy - ry), Point::new(cx + rx, cy - ky), Point::new(cx + rx, cy));
+    path.close();
+    path
+}
+
+fn rect_path(x: f64, y: f64, w: f64, h: f64, rx: f64, ry: f64) -> Path {
+    let mut path = Path::new();
+    if rx <= 0.0 || ry <= 0.0 {
+        path.move_to(Point::new(x, y));
+        path.line_to(Point::new(x + w, y));
+        path.line_to(Point::new(x + w, y + h));
+        path.line_to(Point::new(x, y + h));
+        path.close();
+        return path
+    }
+    let (kx, ky) = (rx * (1.0 - ARC_K), ry * (1.0 - ARC_K));
+    let (r, b) = (x + w, y + h);
+    path.move_to(Point::new(x + rx, y));
+    path.line_to(Point::new(r - rx, y));
+    path.cubic_to(Point::new(r - kx, y), Point::new(r, y + ky), Point::new(r, y + ry));
+    path.line_to(Point::new(r, b - ry));
+    path.cubic_to(Point::new(r, b - ky), Point::new(r - kx, b), Point::new(r - rx, b));
+    path.line_to(Point::new(x + rx, b));
+    path.cubic_to(Point::new(x + kx, b), Point::new(x, b - ky), Point::new(x, b - ry));
+    path.line_to(Point::new(x, y + ry));
+    path.cubic_to(Point::new(x, y + ky), Point::new(x + kx, y), Point::new(x + rx, y));
+    path.close();
+    path
+}
+
+/// Parses SVG path data into a path, stopping at the first error like browsers do.
+pub fn parse_path_data(data: &str) -> Path {
+    let mut s = NumberScanner {data: data.as_bytes(), pos: 0};
+    let mut path = Path::new();
+    let mut start = dvec2(0.0, 0.0);
+    let mut current = dvec2(0.0, 0.0);
+    // the control point to reflect for S and T
+    let mut last_control: Option<(u8, DVec2)> = None;
+    let mut command = 0u8;
+    let mut open = false;
+    let point = | p: DVec2 | Point::new(p.x, p.y);
+    loop {
+        s.skip_separators();
+        let c = if let Some(c) = s.peek() {c} else {break};
+        if c.is_ascii_alphabetic() {
+            command = c;
+            s.pos += 1;
+        }
+        else if command == 0 {
+            break
+        }
+        let relative = command.is_ascii_lowercase();
+        let base = if relative {current} else {dvec2(0.0, 0.0)};
+        let upper = command.to_ascii_uppercase();
+        if upper != b'M' && upper != b'Z' && !open {
+            path.move_to(point(current));
+            start = current;
+            open = true;
+        }
+        let mut control = None;
+        match upper {
+            b'M' => {
+                let p = if let Some(p) = s.point() {p + base} else {break};
+                path.move_to(point(p));
+                start = p;
+                current = p;
+                open = true;
+                // further coordinate pairs are implicit line tos
+                command = if relative {b'l'} else {b'L'};
+            }
+            b'L' => {
+                let p = if let Some(p) = s.point() {p + base} else {break};
+                path.line_to(point(p));
+                current = p;
+            }
+            b'H' => {
+                let x = if let Some(x) = s.number() {x} else {break};
+                current.x = if relative {current.x + x} else {x};
+                path.line_to(point(current));
+            }
+            b'V' => {
+                let y = if let Some(y) = s.number() {y} else {break};
+                current.y = if relative {current.y + y} else {y};
+                path.line_to(point(current));
+            }
+            b'C' | b'S' => {
+                let p1 = if upper == b'S' {
+                    match last_control {
+                        Some((b'C', c)) => current * 2.0 - c,
+                        _ => current
+                    }
+                }
+                else if let Some(p) = s.point() {p + base} else {break};
+                let (p2, p) = if let (Some(p2), Some(p)) = (s.point(), s.point()) {(p2 + base, p + base)} else {break};
+                path.cubic_to(point(p1), point(p2), point(p));
+                control = Some((b'C', p2));
+                current = p;
+            }
+            b'Q' | b'T' => {
+                let p1 = if upper == b'T' {
+                    match last_control {
+                        Some((b'Q', c)) => current * 2.0 - c,
+                        _ => current
+                    }
+                }
+                else if let Some(p) = s.point() {p + base} else {break};
+                let p = if let Some(p) = s.point() {p + base} else {break};
+                path.quadratic_to(point(p1), point(p));
+                control = Some((b'Q', p1));
+                current = p;
+            }
+            b'A' => {
+                let (rx, ry, angle) = if let (Some(rx), Some(ry), Some(a)) = (s.number(), s.number(), s.number()) {(rx, ry, a)} else {break};
+                let (large_arc, sweep) = if let (Some(l), Some(s)) = (s.flag(), s.flag()) {(l, s)} else {break};
+                let p = if let Some(p) = s.point() {p + base} else {break};
+                arc_to(&mut path, current, rx, ry, angle, large_arc, sweep, p);
+                current = p;
+            }
+            b'Z' => {
+                path.close();
+                current = start;
+                open = false;
+            }
+            _ => break
+        }
+        last_control = control;
+    }
+    path
+}
+
+// endpoint to center conversion from the SVG implementation notes, then cubics of at most a quarter turn
+fn arc_to(path: &mut Path, p0: DVec2, rx: f64, ry: f64, angle: f64, large_arc: bool, sweep: bool, p1: DVec2) {
+    let (mut rx, mut ry) = (rx.abs(), ry.abs());
+    if rx == 0.0 || ry == 0.0 || p0 == p1 {
+        if p0 != p1 {
+            path.line_to(Point::new(p1.x, p1.y));
+        }
+        return
+    }
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let half = (p0 - p1) * 0.5;
+    let x1 = cos * half.x + sin * half.y;
+    let y1 = -sin * half.x + cos * half.y;
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let num = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let den = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep {
+        coef = -coef;
+    }
+    let cx1 = coef * rx * y1 / ry;
+    let cy1 = -coef * ry * x1 / rx;
+    let mid = (p0 + p1) * 0.5;
+    let center = dvec2(cos * cx1 - sin * cy1 + mid.x, sin * cx1 + cos * cy1 + mid.y);
+    let vector_angle = | ux: f64, uy: f64 | uy.atan2(ux);
+    let theta1 = vector_angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut delta = vector_angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - theta1;
+    if sweep && delta < 0.0 {
+        delta += std::f64::consts::TAU;
+    }
+    else if !sweep && delta > 0.0 {
+        delta -= std::f64::consts::TAU;
+    }
+    let segments = (delta.abs() / std::f64::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = delta / segments as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let at = | t: f64 | {
+        let (s, c) = t.sin_cos();
+        (
+            dvec2(cos * rx * c - sin * ry * s, sin * rx * c + cos * ry * s) + center,
+            dvec2(-cos * rx * s - sin * ry * c, -sin * rx * s + cos * ry * c)
+        )
+    };
+    let mut t = theta1;
+    for i in 0..segments {
+        let (a, da) = at(t);
+        let (b, db) = at(t + step);
+        let b = if i + 1 == segments {p1} else {b};
+        let c1 = a + da * k;
+        let c2 = b - db * k;
+        path.cubic_to(Point::new(c1.x, c1.y), Point::new(c2.x, c2.y), Point::new(b.x, b.y));
+        t += step;
+    }
+}
+
+struct NumberScanner<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NumberScanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).cloned()
+    }
+
+    fn skip_separators(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_ascii_whitespace() || c == b',' {
+                self.pos += 1;
+            }
+            else {
+                break
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<f64> {
+        self.skip_separators();
+        let start = self.pos;
+        if let Some(b'+' | b'-') = self.peek() {
+            self.pos += 1;
+        }
+        let mut digits = 0;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+            digits += 1;
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            while let Some(b'0'..=b'9') = self.peek() {
+                self.pos += 1;
+                digits += 1;
+            }
+        }
+        if digits == 0 {
+            self.pos = start;
+            return None
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            let mark = self.pos;
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            let exp_start = self.pos;
+            while let Some(b'0'..=b'9') = self.peek() {
+                self.pos += 1;
+            }
+            if self.pos == exp_start {
+                self.pos = mark;
+            }
+        }
+        std::str::from_utf8(&self.data[start..self.pos]).ok()?.parse().ok()
+    }
+
+    fn point(&mut self) -> Option<DVec2> {
+        Some(dvec2(self.number()?, self.number()?))
+    }
+
+    // arc flags can be written without separators, as in `a1 1 0 00 1 1`
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None
+        };
+        self.pos += 1;
+        Some(flag)
+    }
+}
+
+fn parse_number_list(value: &str) -> Vec<f64> {
+    let mut s = NumberScanner {data: value.as_bytes(), pos: 0};
+    let mut numbers = Vec::new();
+    while let Some(n) = s.number() {
+        numbers.push(n);
+    }
+    numbers
+}
+
+/// Parses a length in user units, converting absolute units at 96 dpi.
+fn parse_length(value: &str) -> Option<f64> {
+    let mut s = NumberScanner {data: value.trim().as_bytes(), pos: 0};
+    let number = s.number()?;
+    let unit = std::str::from_utf8(&s.data[s.pos..]).ok()?;
+    let scale = match unit.trim() {
+        "" | "px" => 1.0,
+        "pt" => 96.0 / 72.0,
+        "pc" => 16.0,
+        "in" => 96.0,
+        "cm" => 96.0 / 2.54,
+        "mm" => 96.0 / 25.4,
+        "em" => 16.0,
+        _ => return None
+    };
+    Some(number * scale)
+}
+
+fn parse_length_or_percent(value: &str, reference: DVec2, axis: usize) -> Option<f64> {
+    if let Some(percent) = value.trim().strip_suffix('%') {
+        let reference = match axis {
+            0 => reference.x,
+            1 => reference.y,
+            _ => diagonal(reference)
+        };
+        return percent.trim().parse::<f64>().ok().map( | p | p / 100.0 * reference)
+    }
+    parse_length(value)
+}
+
+fn parse_offset(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let offset = if let Some(percent) = value.strip_suffix('%') {
+        percent.trim().parse::<f64>().ok()? / 100.0
+    }
+    else {
+        value.parse::<f64>().ok()?
+    };
+    Some(offset.clamp(0.0, 1.0))
+}
+
+fn parse_opacity(value: &str) -> Option<f64> {
+    parse_offset(value)
+}
+
+fn parse_transform(value: &str) -> SvgTransform {
+    let mut transform = SvgTransform::IDENTITY;
+    let mut rest = value;
+    while let Some(open) = rest.find('(') {
+        let close = if let Some(close) = rest[open..].find(')') {open + close} else {break};
+        let name = rest[..open].trim_matches( | c: char | c.is_whitespace() || c == ',');
+        let args = parse_number_list(&rest[open + 1..close]);
+        let arg = | i: usize, default: f64 | args.get(i).cloned().unwrap_or(default);
+        let next = match name {
+            "matrix" if args.len() == 6 => SvgTransform {a: args[0], b: args[1], c: args[2], d: args[3], e: args[4], f: args[5]},
+            "translate" => SvgTransform::translate(arg(0, 0.0), arg(1, 0.0)),
+            "scale" => SvgTransform::scale(arg(0, 1.0), arg(1, arg(0, 1.0))),
+            "rotate" => {
+                let (cx, cy) = (arg(1, 0.0), arg(2, 0.0));
+                SvgTransform::translate(-cx, -cy).then(&SvgTransform::rotate(arg(0, 0.0))).then(&SvgTransform::translate(cx, cy))
+            }
+            "skewX" => SvgTransform::skew_x(arg(0, 0.0)),
+            "skewY" => SvgTransform::skew_y(arg(0, 0.0)),
+            _ => SvgTransform::IDENTITY
+        };
+        // a list applies right to left, the last transform is closest to the content
+        transform = next.then(&transform);
+        rest = &rest[close + 1..];
+    }
+    transform
+}
+
+fn parse_paint(value: &str) -> Option<PaintRef> {
+    match value {
+        "none" => return Some(PaintRef::None),
+        "currentColor" => return Some(PaintRef::CurrentColor),
+        _ => ()
+    }
+    if let Some(url) = value.strip_prefix("url(") {
+        let close = url.find(')')?;
+        let id = url[..close].trim().trim_matches( | c | c == '\'' || c == '"').strip_prefix('#')?.to_string();
+        let fallback = match parse_paint(url[close + 1..].trim()) {
+            Some(PaintRef::Color(color)) => Some(color),
+            _ => None
+        };
+        return Some(PaintRef::Url(id, fallback))
+    }
+    parse_color(value).map(PaintRef::Color)
+}
+
+fn parse_color(value: &str) -> Option<Vec4> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<f32> = hex.chars().map( | c | c.to_digit(16).map( | d | d as f32)).collect::<Option<_ >>()?;
+        return match digits.len() {
+            3 | 4 => {
+                let c = | i: usize | digits.get(i).map_or(1.0, | d | d * 17.0 / 255.0);
+                Some(vec4(c(0), c(1), c(2), c(3)))
+            }
+            6 | 8 => {
+                let c = | i: usize | digits.get(i * 2).map_or(1.0, | d | (d * 16.0 + digits[i * 2 + 1]) / 255.0);
+                Some(vec4(c(0), c(1), c(2), c(3)))
+            }
+            _ => None
+        }
+    }
+    if let Some(args) = value.strip_prefix("rgba(").or_else( || value.strip_prefix("rgb(")) {
+        let args = args.strip_suffix(')')?;
+        let parts: Vec<&str> = args.split( | c | c == ',' || c == '/').map( | p | p.trim()).collect();
+        if parts.len() < 3 {
+            return None
+        }
+        let channel = | v: &str | -> Option<f32> {
+            if let Some(p) = v.strip_suffix('%') {
+                Some(p.trim().parse::<f32>().ok()? / 100.0)
+            }
+            else {
+                Some(v.parse::<f32>().ok()? / 255.0)
+            }
+        };
+        let alpha = parts.get(3).map_or(Some(1.0), | a | parse_offset(a).map( | a | a as f32))?;
+        return Some(vec4(channel(parts[0])?.clamp(0.0, 1.0), channel(parts[1])?.clamp(0.0, 1.0), channel(parts[2])?.clamp(0.0, 1.0), alpha))
+    }
+    let rgb = match value.to_ascii_lowercase().as_str() {
+        "transparent" => return Some(vec4(0.0, 0.0, 0.0, 0.0)),
+        "black" => 0x000000,
+        "white" => 0xffffff,
+        "red" => 0xff0000,
+        "lime" => 0x00ff00,
+        "green" => 0x008000,
+        "blue" => 0x0000ff,
+        "yellow" => 0xffff00,
+        "cyan" | "aqua" => 0x00ffff,
+        "magenta" | "fuchsia" => 0xff00ff,
+        "gray" | "grey" => 0x808080,
+        "silver" => 0xc0c0c0,
+        "maroon" => 0x800000,
+        "olive" => 0x808000,
+        "purple" => 0x800080,
+        "teal" => 0x008080,
+        "navy" => 0x000080,
+        "orange" => 0xffa500,
+        "pink" => 0xffc0cb,
+        "brown" => 0xa52a2a,
+        "gold" => 0xffd700,
+        "darkgray" | "darkgrey" => 0xa9a9a9,
+        "lightgray" | "lightgrey" => 0xd3d3d3,
+        _ => return None
+    };
+    let c = | shift: u32 | ((rgb >> shift) & 0xff) as f32 / 255.0;
+    Some(vec4(c(16), c(8), c(0), 1.0))
+}
+
+// XML
+
+struct XmlElement<'a> {
+    name: &'a str,
+    attrs: Vec<(&'a str, String)>,
+    children: Vec<XmlElement<'a >>,
+}
+
+impl<'a> XmlElement<'a> {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs.iter().find( | (n, _) | *n == name).map( | (_, v) | v.as_str())
+    }
+}
+
+fn collect_ids<'a>(element: &'a XmlElement<'a>, ids: &mut HashMap<&'a str, &'a XmlElement<'a >>) {
+    if let Some((_, id)) = element.attrs.iter().find( | (n, _) | *n == "id") {
+        ids.entry(id.as_str()).or_insert(element);
+    }
+    for child in &element.children {
+        collect_ids(child, ids);
+    }
+}
+
+/// Just enough XML for SVG files: elements and attributes, skipping text, comments,
+/// processing instructions, the doctype and CDATA sections.
+struct XmlParser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    fn rest(&self) -> &'a [u8] {
+        &self.data[self.pos.min(self.data.len())..]
+    }
+
+    fn error(&self, message: &str) -> String {
+        format!("{} at byte {}", message, self.pos)
+    }
+
+    fn skip_until(&mut self, end: &str) -> Result<(), String> {
+        let end = end.as_bytes();
+        while self.pos < self.data.len() {
+            if self.rest().starts_with(end) {
+                self.pos += end.len();
+                return Ok(())
+            }
+            self.pos += 1;
+        }
+        Err(self.error("unterminated markup"))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.data.len() && self.data[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    // skips text and non element markup up to the next tag
+    fn skip_misc(&mut self) -> Result<(), String> {
+        loop {
+            while self.pos < self.data.len() && self.data[self.pos] != b'<' {
+                self.pos += 1;
+            }
+            let rest = self.rest();
+            if rest.starts_with(b"<!--") {
+                self.skip_until("-->")?;
+            }
+            else if rest.starts_with(b"<![CDATA[") {
+                self.skip_until("]]>")?;
+            }
+            else if rest.starts_with(b"<?") {
+                self.skip_until("?>")?;
+            }
+            else if rest.starts_with(b"<!") {
+                // a doctype can contain an internal subset in brackets
+                let mut depth = 0;
+                while self.pos < self.data.len() {
+                    match self.data[self.pos] {
+                        b'[' => depth += 1,
+                        b']' => depth -= 1,
+                        b'>' if depth <= 0 => break,
+                        _ => ()
+                    }
+                    self.pos += 1;
+                }
+                self.pos += 1;
+            }
+            else {
+                return Ok(())
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let start = self.pos;
+        while self.pos < self.data.len() {
+            let c = self.data[self.pos];
+            if c.is_ascii_whitespace() || c == b'>' || c == b'/' || c == b'=' {
+                break
+            }
+            self.pos += 1;
+        }
+        if start == self.pos {
+            return Err(self.error("expected a name"))
+        }
+        std::str::from_utf8(&self.data[start..self.pos]).map_err( | _ | self.error("invalid utf8 in name"))
+    }
+
+    fn parse_document(mut self) -> Result<XmlElement<'a>, String> {
+        self.skip_misc()?;
+        if self.pos >= self.data.len() {
+            return Err(self.error("no root element"))
+        }
+        self.element(0)
+    }
+
+    fn element(&mut self, depth: usize) -> Result<XmlElement<'a>, String> {
+        if depth > 256 {
+            return Err(self.error("elements nested too deeply"))
+        }
+        self.pos += 1;
+        let name = self.name()?;
+        let mut element = XmlElement {name, attrs: Vec::new(), children: Vec::new()};
+        loop {
+            self.skip_whitespace();
+            match self.data.get(self.pos) {
+                Some(b'/') => {
+                    self.pos += 1;
+                    if self.data.get(self.pos) != Some(&b'>') {
+                        return Err(self.error("expected >"))
+                    }
+                    self.pos += 1;
+                    return Ok(element)
+                }
+                Some(b'>') => {
+                    self.pos += 1;
+                    break
+                }
+                Some(_) => {
+                    let attr = self.name()?;
+                    self.skip_whitespace();
+                    if self.data.get(self.pos) != Some(&b'=') {
+                        return Err(self.error("expected ="))
+                    }
+                    self.pos += 1;
+                    self.skip_whitespace();
+                    let quote = match self.data.get(self.pos) {
+                        Some(q @ (b'"' | b'\'')) => *q,
+                        _ => return Err(self.error("expected a quoted attribute value"))
+                    };
+                    self.pos += 1;
+                    let start = self.pos;
+                    while self.pos < self.data.len() && self.data[self.pos] != quote {
+                        self.pos += 1;
+                    }
+                    if self.pos >= self.data.len() {
+                        return Err(self.error("unterminated attribute value"))
+                    }
+                    let value = std::str::from_utf8(&self.data[start..self.pos]).map_err( | _ | self.error("invalid utf8 in attribute"))?;
+                    self.pos += 1;
+                    element.attrs.push((attr, decode_entities(value)));
+                }
+                None => return Err(self.error("unterminated tag"))
+            }
+        }
+        loop {
+            self.skip_misc()?;
+            if self.pos >= self.data.len() {
+                return Err(self.error(&format!("unclosed <{}>", name)))
+            }
+            if self.rest().starts_with(b"</") {
+                self.pos += 2;
+                let close = self.name()?;
+                if close != name {
+                    return Err(self.error(&format!("</{}> closes <{}>", close, name)))
+                }
+                self.skip_whitespace();
+                self.pos += 1;
+                return Ok(element)
+            }
+            element.children.push(self.element(depth + 1)?);
+        }
+    }
+}
+
+fn decode_entities(value: &str) -> String {
+    if !value.contains('&') {
+        return value.to_string()
+    }
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let semi = if let Some(semi) = rest.find(';') {semi} else {break};
+        let entity = &rest[1..semi];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => if let Some(hex) = entity.strip_prefix("#x") {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            }
+            else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            }
+            else {
+                None
+            }
+        };
+        if let Some(c) = c {
+            out.push(c);
+            rest = &rest[semi + 1..];
+        }
+        else {
+            out.push('&');
+            rest = &rest[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
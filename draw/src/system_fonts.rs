@@ -0,0 +1,187 @@
+//! Last resort font fallback: looks through the fonts installed on the system for one that
+//! covers a character, in the directories fontconfig searches by default. Only Linux has
+//! directories to search, elsewhere nothing is ever found.
+//!
+//! The first lookup starts a thread that reads every font file once to record which codepoints
+//! it covers, nothing is found until it is done. After that lookups are a binary search per face
+//! and their results are cached per character.
+
+use {
+    std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::mpsc::TryRecvError,
+    },
+    crate::makepad_platform::thread::{ToUIReceiver, Signal},
+    rustybuzz::ttf_parser,
+};
+
+pub struct SystemFontFace {
+    pub path: PathBuf,
+    pub index: u32,
+    // sorted, non overlapping inclusive codepoint ranges
+    ranges: Vec<(u32, u32)>,
+    // set once the face is loaded into the font atlas
+    pub font_id: Option<usize>,
+}
+
+impl SystemFontFace {
+    pub fn covers(&self, c: char) -> bool {
+        let c = c as u32;
+        let i = self.ranges.partition_point( | r | r.1 < c);
+        i < self.ranges.len() && self.ranges[i].0 <= c
+    }
+
+    // stops the face from being found again, used when it fails to load
+    pub fn disable(&mut self) {
+        self.ranges.clear();
+    }
+}
+
+#[derive(Default)]
+pub struct SystemFonts {
+    faces: Option<Vec<SystemFontFace >>,
+    scan: Option<ToUIReceiver<Vec<SystemFontFace >>>,
+    // set by the scan thread once it is done, other threads signal the UI as well
+    scan_done: Signal,
+    found: HashMap<char, Option<usize >>,
+}
+
+impl SystemFonts {
+    pub fn font_dirs() -> Vec<PathBuf> {
+        #[cfg(target_os = "linux")] {
+            let mut dirs = vec![
+                PathBuf::from("/usr/share/fonts"),
+                PathBuf::from("/usr/local/share/fonts"),
+            ];
+            if let Ok(data_home) = std::env::var("XDG_DATA_HOME") {
+                dirs.push(Path::new(&data_home).join("fonts"));
+            }
+            if let Ok(home) = std::env::var("HOME") {
+                dirs.push(Path::new(&home).join(".local/share/fonts"));
+                dirs.push(Path::new(&home).join(".fonts"));
+            }
+            dirs
+        }
+        #[cfg(not(target_os = "linux"))] {
+            Vec::new()
+        }
+    }
+
+    pub fn face(&mut self, face_index: usize) -> &mut SystemFontFace {
+        &mut self.faces.as_mut().unwrap()[face_index]
+    }
+
+    /// Finds a face covering the character. Faces already loaded into the atlas are
+    /// preferred, so a script keeps using the same font for all of its characters.
+    pub fn find_face(&mut self, c: char) -> Option<usize> {
+        if let Some(found) = self.found.get(&c) {
+            return *found
+        }
+        self.poll_scan();
+        let Some(faces) = &self.faces else {
+            self.start_scan();
+            return None
+        };
+        let found = faces.iter().position( | face | face.font_id.is_some() && face.covers(c))
+            .or_else( || faces.iter().position( | face | face.covers(c)));
+        self.found.insert(c, found);
+        found
+    }
+
+    fn start_scan(&mut self) {
+        if self.scan.is_some() {
+            return
+        }
+        let scan = ToUIReceiver::default();
+        let sender = scan.sender();
+        let scan_done = self.scan_done.clone();
+        std::thread::spawn(move || {
+            let mut faces = Vec::new();
+            for dir in Self::font_dirs() {
+                scan_dir(&dir, &mut faces);
+            }
+            let _ = sender.send(faces);
+            scan_done.set();
+        });
+        self.scan = Some(scan);
+    }
+
+    /// Takes the faces of a finished scan, returns true if the scan finished since the
+    /// last call. The faces may have been taken by `find_face` already.
+    pub fn handle_scan_done(&mut self) -> bool {
+        if !self.scan_done.check_and_clear() {
+            return false
+        }
+        self.poll_scan();
+        true
+    }
+
+    /// Takes the faces of the scan once it is done, returns true if that happened now.
+    pub fn poll_scan(&mut self) -> bool {
+        let Some(scan) = &self.scan else {return false};
+        let faces = match scan.try_recv() {
+            Ok(faces) => faces,
+            Err(TryRecvError::Empty) => return false,
+            Err(TryRecvError::Disconnected) => Vec::new()
+        };
+        self.faces = Some(faces);
+        self.scan = None;
+        true
+    }
+
+    // a face failed to load, the characters it was found for need another lookup
+    pub fn forget_face(&mut self, face_index: usize) {
+        self.face(face_index).disable();
+        self.found.retain( | _, found | *found != Some(face_index));
+    }
+}
+
+fn scan_dir(dir: &Path, faces: &mut Vec<SystemFontFace>) {
+    let entries = if let Ok(entries) = std::fs::read_dir(dir) {entries} else {return};
+    let mut paths: Vec<PathBuf> = entries.filter_map( | entry | entry.ok().map( | entry | entry.path())).collect();
+    paths.sort();
+    for path in paths {
+        if path.is_dir() {
+            scan_dir(&path, faces);
+            continue;
+        }
+        let is_font = path.extension().and_then( | ext | ext.to_str()).map_or(false, | ext | {
+            matches!(ext.to_ascii_lowercase().as_str(), "ttf" | "otf" | "ttc" | "otc")
+        });
+        if !is_font {
+            continue;
+        }
+        let data = if let Ok(data) = std::fs::read(&path) {data} else {continue};
+        let count = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+        for index in 0..count {
+            if let Ok(face) = ttf_parser::Face::parse(&data, index) {
+                let ranges = face_ranges(&face);
+                if !ranges.is_empty() {
+                    faces.push(SystemFontFace {path: path.clone(), index, ranges, font_id: None});
+                }
+            }
+        }
+    }
+}
+
+fn face_ranges(face: &ttf_parser::Face) -> Vec<(u32, u32)> {
+    let mut codepoints = Vec::new();
+    if let Some(cmap) = face.tables().cmap {
+        for subtable in cmap.subtables {
+            if subtable.is_unicode() {
+                subtable.codepoints( | c | codepoints.push(c));
+            }
+        }
+    }
+    codepoints.sort_unstable();
+    codepoints.dedup();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for c in codepoints {
+        match ranges.last_mut() {
+            Some(last) if last.1 + 1 == c => last.1 = c,
+            _ => ranges.push((c, c))
+        }
+    }
+    ranges
+}
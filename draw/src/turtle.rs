@@ -14,7 +14,9 @@ pub struct Layout {
     #[live] pub padding: Padding,
     #[live] pub align: Align,
     #[live] pub flow: Flow,
-    #[live] pub spacing: f64
+    #[live] pub spacing: f64,
+    #[live] pub row_spacing: f64,
+    #[live] pub grid: GridLayout,
 }
 
 impl Default for Layout{
@@ -26,7 +28,9 @@ impl Default for Layout{
             padding: Padding::default(),
             align: Align{x:0.0,y:0.0},
             flow: Flow::Right,
-            spacing: 0.0
+            spacing: 0.0,
+            row_spacing: 0.0,
+            grid: GridLayout::default(),
         }
     }
 }
@@ -38,6 +42,13 @@ pub struct Walk {
     #[live] pub margin: Margin,
     #[live] pub width: Size,
     #[live] pub height: Size,
+    #[live] pub span: GridSpan,
+    #[live] pub min_width: Option<f64>,
+    #[live] pub max_width: Option<f64>,
+    #[live] pub min_height: Option<f64>,
+    #[live] pub max_height: Option<f64>,
+    // width divided by height, sizes the axis that isn't Fixed from the other one
+    #[live] pub aspect_ratio: Option<f64>,
 }
 
 #[derive(Clone, Copy, Default, Debug, Live, LiveHook)]
@@ -74,7 +85,42 @@ impl Default for Axis {
 pub enum Flow {
     #[pick] Right,
     Down,
-    Overlay
+    Overlay,
+    RightWrap,
+    Grid
+}
+
+/// Column and row templates for `Flow::Grid`. Without columns the grid has a single
+/// `Fr(1.0)` column, rows past the template are `Fit`
+#[derive(Copy, Clone, Default, Debug, Live, LiveHook)]
+#[live_ignore]
+pub struct GridLayout {
+    #[live] pub columns: GridTracks,
+    #[live] pub rows: GridTracks,
+}
+
+#[derive(Copy, Clone, Debug, Live)]
+#[live_ignore]
+pub enum GridTrack {
+    #[pick] Fit,
+    #[live(100.0)] Fixed(f64),
+    #[live(1.0)] Fr(f64),
+}
+
+pub const GRID_MAX_TRACKS: usize = 16;
+
+#[derive(Copy, Clone)]
+pub struct GridTracks {
+    len: usize,
+    tracks: [GridTrack; GRID_MAX_TRACKS]
+}
+
+/// The number of grid columns and rows a child covers
+#[derive(Copy, Clone, Debug, Live)]
+#[live_ignore]
+pub struct GridSpan {
+    #[live(1usize)] pub columns: usize,
+    #[live(1usize)] pub rows: usize,
 }
 
 #[derive(Copy, Clone, Debug, Live)]
@@ -90,8 +136,7 @@ pub enum Size {
 pub enum DeferWalk{
     Unresolved{
         defer_index: usize,
-        margin: Margin,
-        other_axis: Size,
+        walk: Walk,
         pos: DVec2
     },
     Resolved(Walk)
@@ -111,9 +156,32 @@ pub enum AlignEntry{
 pub struct TurtleWalk {
     align_start: usize,
     defer_index: usize,
+    // the row of a RightWrap flow the walk was placed in
+    row: usize,
     rect: Rect,
 }
 
+#[derive(Clone, Copy, Debug)]
+struct GridCell {
+    walk_index: usize,
+    column: usize,
+    row: usize,
+    span: GridSpan,
+}
+
+/// Placement state of a `Flow::Grid` turtle. Children are auto placed row by row
+/// into the first free cells their span fits in
+#[derive(Clone, Default, Debug)]
+struct TurtleGrid {
+    // row major, one entry per column
+    occupied: Vec<bool>,
+    cursor: (usize, usize),
+    // measured sizes of the children that span a single track
+    column_fit: Vec<f64>,
+    row_fit: Vec<f64>,
+    cells: Vec<GridCell>,
+}
+
 #[derive(Clone, Default, Debug)]
 pub struct Turtle {
     walk: Walk,
@@ -121,6 +189,9 @@ pub struct Turtle {
     align_start: usize,
     turtle_walks_start: usize,
     defer_count: usize,
+    // min and max size along the flow of the deferred Fill walks
+    defer_limits: Vec<(f64, f64)>,
+    wrap_row: usize,
     shift: DVec2,
     pos: DVec2,
     origin: DVec2,
@@ -128,6 +199,7 @@ pub struct Turtle {
     height: f64,
     width_used: f64,
     height_used: f64,
+    grid: TurtleGrid,
     guard_area: Area
 }
 
@@ -151,10 +223,7 @@ impl<'a> Cx2d<'a> {
         let turtle = self.turtles.last_mut().unwrap();
         let defer_index = turtle.defer_count;
         let pos = turtle.pos;
-        let size = dvec2(
-            turtle.eval_width(walk.width, walk.margin, turtle.layout.flow),
-            turtle.eval_height(walk.height, walk.margin, turtle.layout.flow)
-        );
+        let size = turtle.eval_walk_size(&walk);
         let margin_size = walk.margin.size();
         match turtle.layout.flow {
             Flow::Right if walk.width.is_fill() => {
@@ -163,10 +232,10 @@ impl<'a> Cx2d<'a> {
                 turtle.update_width_max(turtle.pos.x, 0.0);
                 turtle.update_height_max(turtle.pos.y, size.y + margin_size.y);
                 turtle.defer_count += 1;
+                turtle.defer_limits.push((walk.min_width.unwrap_or(0.0), walk.max_width.unwrap_or(f64::INFINITY)));
                 Some(DeferWalk::Unresolved{
                     defer_index,
-                    margin: walk.margin,
-                    other_axis: walk.height,
+                    walk,
                     pos: pos + spacing
                 })
             },
@@ -176,10 +245,10 @@ impl<'a> Cx2d<'a> {
                 turtle.update_width_max(turtle.pos.x, size.x + margin_size.x);
                 turtle.update_height_max(turtle.pos.y, 0.0);
                 turtle.defer_count += 1;
+                turtle.defer_limits.push((walk.min_height.unwrap_or(0.0), walk.max_height.unwrap_or(f64::INFINITY)));
                 Some(DeferWalk::Unresolved {
                     defer_index,
-                    margin: walk.margin,
-                    other_axis: walk.width,
+                    walk,
                     pos: pos + spacing
                 })
             },
@@ -198,6 +267,8 @@ impl<'a> Cx2d<'a> {
             align_start: self.align_list.len() - 1,
             turtle_walks_start: self.turtle_walks.len(),
             defer_count: 0,
+            defer_limits: Vec::new(),
+            wrap_row: 0,
             pos: DVec2 {
                 x: layout.padding.left,
                 y: layout.padding.top
@@ -208,6 +279,7 @@ impl<'a> Cx2d<'a> {
             shift: dvec2(0.0,0.0),
             width_used: layout.padding.left,
             height_used: layout.padding.top,
+            grid: TurtleGrid::default(),
             guard_area: Area::Empty,
         };
         self.turtles.push(turtle);
@@ -241,11 +313,11 @@ impl<'a> Cx2d<'a> {
         let (origin, width, height, draw_clip) = if let Some(parent) = self.turtles.last() {
             
             let o = walk.margin.left_top() + if let Some(pos) = walk.abs_pos {pos} else {
-                parent.pos + parent.child_spacing(self.turtle_walks.len()) 
+                parent.next_walk_pos(&walk, self.turtle_walks.len())
             };
             
-            let w = parent.eval_width(walk.width, walk.margin, parent.layout.flow);
-            let h = parent.eval_height(walk.height, walk.margin, parent.layout.flow);
+            let size = parent.eval_walk_size(&walk);
+            let (w, h) = (size.x, size.y);
             
             // figure out new clipping rect
             let (x0, x1) = if layout.clip_x {
@@ -271,8 +343,8 @@ impl<'a> Cx2d<'a> {
         }
         else {
             let o = DVec2 {x: walk.margin.left, y: walk.margin.top};
-            let w = walk.width.fixed_or_nan();
-            let h = walk.height.fixed_or_nan();
+            let size = walk.constrain(dvec2(walk.width.fixed_or_nan(), walk.height.fixed_or_nan()));
+            let (w, h) = (size.x, size.y);
             
             (o, w, h, (dvec2(o.x, o.y), dvec2(o.x + w, o.y + h)))
         };
@@ -283,6 +355,8 @@ impl<'a> Cx2d<'a> {
             align_start: self.align_list.len()-1,
             turtle_walks_start: self.turtle_walks.len(),
             defer_count: 0,
+            defer_limits: Vec::new(),
+            wrap_row: 0,
             pos: DVec2 {
                 x: origin.x + layout.padding.left,
                 y: origin.y + layout.padding.top
@@ -293,6 +367,7 @@ impl<'a> Cx2d<'a> {
             shift: dvec2(0.0,0.0),
             width_used: layout.padding.left,
             height_used: layout.padding.top,
+            grid: TurtleGrid::default(),
             guard_area,
         };
         
@@ -313,34 +388,52 @@ impl<'a> Cx2d<'a> {
     }
     
     pub fn end_turtle_with_guard(&mut self, guard_area: Area) -> Rect {
-        let turtle = self.turtles.pop().unwrap();
+        let mut turtle = self.turtles.pop().unwrap();
         if guard_area != turtle.guard_area {
             panic!("End turtle guard area misaligned!, begin/end pair not matched begin {:?} end {:?}", turtle.guard_area, guard_area)
         }
         
-        // computed height
-        let w = if turtle.width.is_nan() {
-            Size::Fixed(turtle.width_used + turtle.layout.padding.right - turtle.layout.scroll.x)
+        // the final grid tracks, which also decide the used size of a Fit grid
+        let grid_tracks = if let Flow::Grid = turtle.layout.flow {
+            let (columns, rows) = turtle.grid_tracks(0);
+            let width = columns.iter().sum::<f64>() + turtle.layout.spacing * columns.len().saturating_sub(1) as f64;
+            let height = rows.iter().sum::<f64>() + turtle.layout.row_spacing * rows.len().saturating_sub(1) as f64;
+            turtle.width_used = turtle.width_used.max(turtle.layout.padding.left + width);
+            turtle.height_used = turtle.height_used.max(turtle.layout.padding.top + height);
+            Some((columns, rows))
         }
         else {
-            Size::Fixed(turtle.width)
+            None
         };
         
-        let h = if turtle.height.is_nan() {
-            Size::Fixed(turtle.height_used + turtle.layout.padding.bottom - turtle.layout.scroll.y)
+        // computed height
+        let mut size = dvec2(
+            if turtle.width.is_nan() {
+                turtle.width_used + turtle.layout.padding.right - turtle.layout.scroll.x
+            }
+            else {
+                turtle.width
+            },
+            if turtle.height.is_nan() {
+                turtle.height_used + turtle.layout.padding.bottom - turtle.layout.scroll.y
+            }
+            else {
+                turtle.height
+            }
+        );
+        if turtle.width.is_nan() || turtle.height.is_nan() {
+            size = turtle.walk.constrain_measured(size, turtle.width.is_nan() && turtle.height.is_nan());
         }
-        else {
-            Size::Fixed(turtle.height)
-        };
+        let w = Size::Fixed(size.x);
+        let h = Size::Fixed(size.y);
         
         match turtle.layout.flow {
             Flow::Right => {
                 if turtle.defer_count > 0 {
-                    let left = turtle.width_left();
-                    let part = left / turtle.defer_count as f64;
+                    let parts = turtle.defer_parts();
                     for i in turtle.turtle_walks_start..self.turtle_walks.len() {
                         let walk = &self.turtle_walks[i];
-                        let shift_x = walk.defer_index as f64 * part;
+                        let shift_x = parts[..walk.defer_index].iter().sum::<f64>();
                         let shift_y = turtle.layout.align.y * (turtle.padded_height_or_used() - walk.rect.size.y);
                         let align_start = walk.align_start;
                         let align_end = self.get_turtle_walk_align_end(i);
@@ -360,12 +453,11 @@ impl<'a> Cx2d<'a> {
             },
             Flow::Down => {
                 if turtle.defer_count > 0 {
-                    let left = turtle.height_left();
-                    let part = left / turtle.defer_count as f64;
+                    let parts = turtle.defer_parts();
                     for i in turtle.turtle_walks_start..self.turtle_walks.len() {
                         let walk = &self.turtle_walks[i];
                         let shift_x = turtle.layout.align.x * (turtle.padded_width_or_used() - walk.rect.size.x);
-                        let shift_y = walk.defer_index as f64 * part;
+                        let shift_y = parts[..walk.defer_index].iter().sum::<f64>();
                         let align_start = walk.align_start;
                         let align_end = self.get_turtle_walk_align_end(i);
                         self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle.shift);
@@ -392,6 +484,58 @@ impl<'a> Cx2d<'a> {
                     self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle.shift);
                 }
             }
+            Flow::RightWrap => {
+                // align.x applies per row and align.y within the height of the row
+                let row_start_x = turtle.origin.x + turtle.layout.padding.left;
+                let width = if turtle.width.is_nan() {
+                    turtle.width_used - turtle.layout.padding.left
+                }
+                else {
+                    turtle.width - turtle.layout.padding.width()
+                };
+                let mut row_start = turtle.turtle_walks_start;
+                while row_start < self.turtle_walks.len() {
+                    let row = self.turtle_walks[row_start].row;
+                    let mut row_end = row_start;
+                    let mut row_right = row_start_x;
+                    let mut row_height = 0.0;
+                    while row_end < self.turtle_walks.len() && self.turtle_walks[row_end].row == row {
+                        let rect = self.turtle_walks[row_end].rect;
+                        row_right = f64::max(row_right, rect.pos.x + rect.size.x);
+                        row_height = f64::max(row_height, rect.size.y);
+                        row_end += 1;
+                    }
+                    let shift_x = turtle.layout.align.x * (width - (row_right - row_start_x));
+                    for i in row_start..row_end {
+                        let walk = &self.turtle_walks[i];
+                        let shift_y = turtle.layout.align.y * (row_height - walk.rect.size.y);
+                        let align_start = walk.align_start;
+                        let align_end = self.get_turtle_walk_align_end(i);
+                        self.move_align_list(shift_x, shift_y, align_start, align_end, false, turtle.shift);
+                    }
+                    row_start = row_end;
+                }
+            }
+            Flow::Grid => {
+                // move every child from where it was drawn to its final cell, aligned within it
+                let (columns, rows) = grid_tracks.unwrap();
+                let mut cells = turtle.grid.cells.iter().peekable();
+                for i in turtle.turtle_walks_start..self.turtle_walks.len() {
+                    let walk = &self.turtle_walks[i];
+                    let mut shift = dvec2(0.0, 0.0);
+                    if let Some(cell) = cells.next_if( | cell | cell.walk_index == i) {
+                        let rect = turtle.grid_cell_rect(cell, &columns, &rows);
+                        let free = rect.size - walk.rect.size;
+                        shift = rect.pos - walk.rect.pos + dvec2(
+                            if free.x.is_nan() {0.0} else {turtle.layout.align.x * free.x},
+                            if free.y.is_nan() {0.0} else {turtle.layout.align.y * free.y}
+                        );
+                    }
+                    let align_start = walk.align_start;
+                    let align_end = self.get_turtle_walk_align_end(i);
+                    self.move_align_list(shift.x, shift.y, align_start, align_end, false, turtle.shift);
+                }
+            }
         }
 
         self.turtle_walks.truncate(turtle.turtle_walks_start);
@@ -442,15 +586,13 @@ impl<'a> Cx2d<'a> {
      fn walk_turtle_move(&mut self, walk: Walk, align_start: usize) -> Rect {
         
         let turtle = self.turtles.last_mut().unwrap();
-        let size = dvec2(
-            turtle.eval_width(walk.width, walk.margin, turtle.layout.flow),
-            turtle.eval_height(walk.height, walk.margin, turtle.layout.flow)
-        );
+        let size = turtle.eval_walk_size(&walk);
         
         if let Some(pos) = walk.abs_pos {
             self.turtle_walks.push(TurtleWalk {
                 align_start,
                 defer_index: 0,
+                row: turtle.wrap_row,
                 rect: Rect {pos, size: size + walk.margin.size()}
             });
             
@@ -461,9 +603,28 @@ impl<'a> Cx2d<'a> {
             }
             Rect {pos: pos + walk.margin.left_top(), size}
         }
+        else if let Flow::Grid = turtle.layout.flow {
+            // children are placed at their cell with the track sizes known so far,
+            // end_turtle moves them once all tracks are measured
+            let (mut cell, rect) = turtle.grid_place(walk.span);
+            let margin_size = walk.margin.size();
+            cell.walk_index = self.turtle_walks.len();
+            turtle.grid.occupy(turtle.grid_columns(), cell, size + margin_size);
+            turtle.update_width_max(rect.pos.x, size.x + margin_size.x);
+            turtle.update_height_max(rect.pos.y, size.y + margin_size.y);
+            
+            self.turtle_walks.push(TurtleWalk {
+                align_start,
+                defer_index: 0,
+                row: turtle.wrap_row,
+                rect: Rect {pos: rect.pos, size: size + margin_size}
+            });
+            Rect {pos: rect.pos + walk.margin.left_top(), size}
+        }
         else {
-            let spacing = turtle.child_spacing(self.turtle_walks.len());
-            let pos = turtle.pos;
+            let mut spacing = turtle.child_spacing(self.turtle_walks.len());
+            let mut pos = turtle.pos;
+            let mut wrap_shift = None;
         
             let margin_size = walk.margin.size();
             match turtle.layout.flow {
@@ -493,13 +654,36 @@ impl<'a> Cx2d<'a> {
                     turtle.update_width_max(turtle.pos.x, size.x);
                     turtle.update_height_max(turtle.pos.y,size.y);
                 }
+                Flow::RightWrap => {
+                    // a child that doesn't fit the rest of the row starts a new one. Its size is
+                    // only known now, so whatever it already drew is moved along
+                    let row_start = turtle.origin.x + turtle.layout.padding.left;
+                    let row_end = turtle.origin.x + turtle.width - turtle.layout.padding.right;
+                    if pos.x > row_start && pos.x + spacing.x + size.x + margin_size.x > row_end {
+                        let row_pos = dvec2(row_start, turtle.origin.y + turtle.height_used + turtle.layout.row_spacing);
+                        wrap_shift = Some(row_pos - (pos + spacing));
+                        turtle.wrap_row += 1;
+                        pos = row_pos;
+                        spacing = dvec2(0.0, 0.0);
+                    }
+                    pos += spacing;
+                    spacing = dvec2(0.0, 0.0);
+                    turtle.pos = dvec2(pos.x + size.x + margin_size.x, pos.y);
+                    turtle.update_width_max(turtle.pos.x, 0.0);
+                    turtle.update_height_max(pos.y, size.y + margin_size.y);
+                }
+                Flow::Grid => unreachable!()
             };
             
             self.turtle_walks.push(TurtleWalk {
                 align_start,
                 defer_index: turtle.defer_count,
+                row: turtle.wrap_row,
                 rect: Rect {pos, size: size + margin_size}
             });
+            if let Some(shift) = wrap_shift {
+                self.move_align_list(shift.x, shift.y, align_start, self.align_list.len(), false, dvec2(0.0, 0.0));
+            }
             Rect {pos: pos + walk.margin.left_top() + spacing, size}
         }
     }
@@ -509,18 +693,14 @@ impl<'a> Cx2d<'a> {
             return Rect::default()
         }
         let turtle = self.turtles.last().unwrap();
-        let size = dvec2(
-            turtle.eval_width(walk.width, walk.margin, turtle.layout.flow),
-            turtle.eval_height(walk.height, walk.margin, turtle.layout.flow)
-        );
+        let size = turtle.eval_walk_size(&walk);
         
         if let Some(pos) = walk.abs_pos {
             Rect {pos: pos + walk.margin.left_top(), size}
         }
         else {
-            let spacing = turtle.child_spacing(self.turtle_walks.len());
-            let pos = turtle.pos;
-            Rect {pos: pos + walk.margin.left_top() + spacing, size}
+            let pos = turtle.next_walk_pos(&walk, self.turtle_walks.len());
+            Rect {pos: pos + walk.margin.left_top(), size}
         }
     }
     
@@ -727,9 +907,12 @@ impl Turtle {
                 Flow::Down => {
                     dvec2(0.0, self.layout.spacing)
                 }
-                Flow::Overlay => {
+                Flow::Overlay | Flow::Grid => {
                     dvec2(0.0, 0.0)
                 }
+                Flow::RightWrap => {
+                    dvec2(self.wrap_spacing(), 0.0)
+                }
             }
         }
         else {
@@ -737,6 +920,177 @@ impl Turtle {
         }
     }
     
+    // splits what is left along the flow over the deferred Fill walks. Walks that
+    // hit their min or max size take that, the others share the rest again
+    fn defer_parts(&self) -> Vec<f64> {
+        let mut space = match self.layout.flow {
+            Flow::Down => self.height_left(),
+            _ => self.width_left()
+        };
+        let mut parts = vec![f64::NAN; self.defer_count];
+        let mut open = self.defer_count;
+        while open > 0 {
+            let share = space / open as f64;
+            let mut clamped = false;
+            for (part, (min, max)) in parts.iter_mut().zip(self.defer_limits.iter()) {
+                if part.is_nan() && (share < *min || share > *max) {
+                    *part = share.max(*min).min(*max);
+                    space -= *part;
+                    open -= 1;
+                    clamped = true;
+                }
+            }
+            if !clamped {
+                for part in parts.iter_mut().filter( | part | part.is_nan()) {
+                    *part = share;
+                }
+                break;
+            }
+        }
+        parts
+    }
+    
+    // no spacing in front of the first child of a row
+    fn wrap_spacing(&self) -> f64 {
+        if self.pos.x > self.origin.x + self.layout.padding.left {
+            self.layout.spacing
+        }
+        else {
+            0.0
+        }
+    }
+    
+    fn next_walk_pos(&self, walk: &Walk, walks_len: usize) -> DVec2 {
+        if let Flow::Grid = self.layout.flow {
+            self.grid_place(walk.span).1.pos
+        }
+        else {
+            self.pos + self.child_spacing(walks_len)
+        }
+    }
+    
+    /// Evaluates the size of a walk in this turtle, with its min/max sizes and aspect ratio
+    /// applied. Fit axes are NaN
+    pub fn eval_walk_size(&self, walk: &Walk) -> DVec2 {
+        let mut size = dvec2(
+            self.eval_width(walk.width, walk.margin, self.layout.flow),
+            self.eval_height(walk.height, walk.margin, self.layout.flow)
+        );
+        // eval_width/eval_height assume a single cell
+        if let Flow::Grid = self.layout.flow {
+            if walk.abs_pos.is_none() && (walk.span.columns > 1 || walk.span.rows > 1) {
+                let cell = self.grid_fill_size(walk.span);
+                if walk.width.is_fill() {
+                    size.x = max_zero_keep_nan(cell.x - walk.margin.width());
+                }
+                if walk.height.is_fill() {
+                    size.y = max_zero_keep_nan(cell.y - walk.margin.height());
+                }
+            }
+        }
+        walk.constrain(size)
+    }
+    
+    fn grid_columns(&self) -> usize {
+        self.layout.grid.columns.len().max(1)
+    }
+    
+    fn grid_column_track(&self, column: usize) -> GridTrack {
+        if self.layout.grid.columns.len() == 0 {
+            GridTrack::Fr(1.0)
+        }
+        else {
+            self.layout.grid.columns.get(column).unwrap_or(GridTrack::Fit)
+        }
+    }
+    
+    fn grid_row_track(&self, row: usize) -> GridTrack {
+        self.layout.grid.rows.get(row).unwrap_or(GridTrack::Fit)
+    }
+    
+    // the first free cell from the cursor on that fits the span
+    fn grid_next_cell(&self, span: GridSpan) -> GridCell {
+        let columns = self.grid_columns();
+        let span = GridSpan {
+            columns: span.columns.clamp(1, columns),
+            rows: span.rows.max(1)
+        };
+        let (mut column, mut row) = self.grid.cursor;
+        loop {
+            if column + span.columns > columns {
+                column = 0;
+                row += 1;
+                continue;
+            }
+            let free = (row..row + span.rows).all( | r | {
+                (column..column + span.columns).all( | c | !self.grid.is_occupied(columns, c, r))
+            });
+            if free {
+                return GridCell {walk_index: 0, column, row, span}
+            }
+            column += 1;
+        }
+    }
+    
+    fn grid_tracks(&self, min_rows: usize) -> (Vec<f64>, Vec<f64>) {
+        let columns = self.grid_columns();
+        let rows = (self.grid.occupied.len() / columns).max(self.layout.grid.rows.len()).max(min_rows);
+        (
+            grid_track_sizes(
+                columns,
+                | i | self.grid_column_track(i),
+                &self.grid.column_fit,
+                self.width - self.layout.padding.width(),
+                self.layout.spacing
+            ),
+            grid_track_sizes(
+                rows,
+                | i | self.grid_row_track(i),
+                &self.grid.row_fit,
+                self.height - self.layout.padding.height(),
+                self.layout.row_spacing
+            )
+        )
+    }
+    
+    fn grid_cell_rect(&self, cell: &GridCell, columns: &[f64], rows: &[f64]) -> Rect {
+        let x = columns[..cell.column].iter().sum::<f64>() + self.layout.spacing * cell.column as f64;
+        let y = rows[..cell.row].iter().sum::<f64>() + self.layout.row_spacing * cell.row as f64;
+        let width = columns[cell.column..cell.column + cell.span.columns].iter().sum::<f64>()
+            + self.layout.spacing * (cell.span.columns - 1) as f64;
+        let height = rows[cell.row..cell.row + cell.span.rows].iter().sum::<f64>()
+            + self.layout.row_spacing * (cell.span.rows - 1) as f64;
+        Rect {
+            pos: self.origin + self.layout.padding.left_top() + dvec2(x, y),
+            size: dvec2(width, height)
+        }
+    }
+    
+    // the cell the next child with this span goes in, with the track sizes known so far
+    fn grid_place(&self, span: GridSpan) -> (GridCell, Rect) {
+        let cell = self.grid_next_cell(span);
+        let (columns, rows) = self.grid_tracks(cell.row + cell.span.rows);
+        let rect = self.grid_cell_rect(&cell, &columns, &rows);
+        (cell, rect)
+    }
+    
+    // the size a Fill child gets in the next cell. Tracks sized by their content
+    // aren't known yet, so on those axes Fill behaves as Fit
+    fn grid_fill_size(&self, span: GridSpan) -> DVec2 {
+        let (cell, rect) = self.grid_place(span);
+        let sized = | track, space: f64 | match track {
+            GridTrack::Fixed(_) => true,
+            GridTrack::Fr(_) => !space.is_nan(),
+            GridTrack::Fit => false
+        };
+        let columns_sized = (cell.column..cell.column + cell.span.columns).all( | i | sized(self.grid_column_track(i), self.width));
+        let rows_sized = (cell.row..cell.row + cell.span.rows).all( | i | sized(self.grid_row_track(i), self.height));
+        dvec2(
+            if columns_sized {rect.size.x} else {f64::NAN},
+            if rows_sized {rect.size.y} else {f64::NAN}
+        )
+    }
+    
     pub fn rect_is_visible(&self, geom: Rect) -> bool {
         let view = Rect {pos: self.origin + self.layout.scroll, size: dvec2(self.width, self.height)};
         return view.intersects(geom)
@@ -777,6 +1131,14 @@ impl Turtle {
                         }
                         return r
                     }
+                    Flow::RightWrap => {
+                        // the rest of the current row
+                        let row_used = self.pos.x - self.origin.x + self.wrap_spacing();
+                        max_zero_keep_nan(self.width - row_used - self.layout.padding.right - margin.width())
+                    }
+                    Flow::Grid => {
+                        max_zero_keep_nan(self.grid_fill_size(GridSpan::default()).x - margin.width())
+                    }
                 }
             },
             Size::All=>self.width
@@ -789,7 +1151,7 @@ impl Turtle {
             Size::Fixed(v) => max_zero_keep_nan(v),
             Size::Fill => {
                 match flow {
-                    Flow::Right | Flow::Overlay => {
+                    Flow::Right | Flow::Overlay | Flow::RightWrap => {
                         let r = max_zero_keep_nan(self.height - self.layout.padding.height() - margin.height());
                         if r.is_nan() {
                             return self.height_used - margin.height() - self.layout.padding.bottom
@@ -799,6 +1161,9 @@ impl Turtle {
                     Flow::Down => {
                         max_zero_keep_nan(self.height_left() - margin.height())
                     }
+                    Flow::Grid => {
+                        max_zero_keep_nan(self.grid_fill_size(GridSpan::default()).y - margin.height())
+                    }
                 }
             }
             Size::All=>self.height
@@ -872,35 +1237,96 @@ impl Turtle {
     }
 }
 
+impl TurtleGrid {
+    fn is_occupied(&self, columns: usize, column: usize, row: usize) -> bool {
+        self.occupied.get(row * columns + column).copied().unwrap_or(false)
+    }
+    
+    fn occupy(&mut self, columns: usize, cell: GridCell, size: DVec2) {
+        let rows = cell.row + cell.span.rows;
+        if self.occupied.len() < rows * columns {
+            self.occupied.resize(rows * columns, false);
+        }
+        for row in cell.row..rows {
+            for column in cell.column..cell.column + cell.span.columns {
+                self.occupied[row * columns + column] = true;
+            }
+        }
+        self.cursor = (cell.column + cell.span.columns, cell.row);
+        if cell.span.columns == 1 {
+            if self.column_fit.len() <= cell.column {
+                self.column_fit.resize(cell.column + 1, 0.0);
+            }
+            self.column_fit[cell.column] = f64::max(self.column_fit[cell.column], size.x);
+        }
+        if cell.span.rows == 1 {
+            if self.row_fit.len() <= cell.row {
+                self.row_fit.resize(cell.row + 1, 0.0);
+            }
+            self.row_fit[cell.row] = f64::max(self.row_fit[cell.row], size.y);
+        }
+        self.cells.push(cell);
+    }
+}
+
+/// Fixed and Fit tracks take their size, Fr tracks share what is left of the space.
+/// Without space (a Fit turtle) Fr tracks size to their content like Fit tracks.
+/// Children spanning several tracks don't grow them
+fn grid_track_sizes(count: usize, track: impl Fn(usize) -> GridTrack, fit: &[f64], space: f64, spacing: f64) -> Vec<f64> {
+    let mut sizes = Vec::with_capacity(count);
+    let mut used = spacing * count.saturating_sub(1) as f64;
+    let mut fr_total = 0.0;
+    for i in 0..count {
+        let measured = fit.get(i).copied().unwrap_or(0.0);
+        let size = match track(i) {
+            GridTrack::Fixed(v) => v,
+            GridTrack::Fit => measured,
+            GridTrack::Fr(fr) if !space.is_nan() => {
+                fr_total += fr;
+                0.0
+            }
+            GridTrack::Fr(_) => measured
+        };
+        used += size;
+        sizes.push(size);
+    }
+    if fr_total > 0.0 {
+        let unit = max_zero_keep_nan(space - used) / fr_total;
+        for (i, size) in sizes.iter_mut().enumerate() {
+            if let GridTrack::Fr(fr) = track(i) {
+                *size = fr * unit;
+            }
+        }
+    }
+    sizes
+}
+
 impl DeferWalk {
     
     pub fn resolve(&mut self, cx: &Cx2d) -> Walk {
         match self{
             Self::Resolved(walk)=>{*walk},
-            Self::Unresolved{pos, defer_index, margin, other_axis}=>{
+            Self::Unresolved{pos, defer_index, walk}=>{
                 let turtle = cx.turtles.last().unwrap();
+                let parts = turtle.defer_parts();
+                let offset = parts[..*defer_index].iter().sum::<f64>();
+                let part = parts[*defer_index];
                 let walk = match turtle.layout.flow {
                     Flow::Right => {
-                        let left = turtle.width_left();
-                        let part = left / turtle.defer_count as f64;
                         Walk {
-                            abs_pos: Some(*pos + dvec2(part * *defer_index as f64, 0.)),
-                            margin: *margin,
+                            abs_pos: Some(*pos + dvec2(offset, 0.)),
                             width: Size::Fixed(part),
-                            height: *other_axis
+                            ..*walk
                         }
                     },
                     Flow::Down => {
-                        let left = turtle.height_left();
-                        let part = left / turtle.defer_count as f64;
                         Walk {
-                            abs_pos: Some(*pos + dvec2(0., part * *defer_index as f64)),
-                            margin: *margin,
+                            abs_pos: Some(*pos + dvec2(0., offset)),
                             height: Size::Fixed(part),
-                            width: *other_axis
+                            ..*walk
                         }
                     }
-                    Flow::Overlay => panic!()
+                    Flow::Overlay | Flow::RightWrap | Flow::Grid => panic!()
                 };
                 *self = DeferWalk::Resolved(walk);
                 walk
@@ -925,6 +1351,39 @@ impl Layout {
         }
     }
     
+    pub fn flow_right_wrap() -> Self {
+        Self {
+            flow: Flow::RightWrap,
+            ..Self::default()
+        }
+    }
+    
+    pub fn flow_grid(columns: &[GridTrack]) -> Self {
+        Self {
+            flow: Flow::Grid,
+            grid: GridLayout {
+                columns: GridTracks::from_slice(columns),
+                rows: GridTracks::default()
+            },
+            ..Self::default()
+        }
+    }
+    
+    pub fn with_grid_rows(mut self, rows: &[GridTrack]) -> Self {
+        self.grid.rows = GridTracks::from_slice(rows);
+        self
+    }
+    
+    pub fn with_spacing(mut self, v: f64) -> Self {
+        self.spacing = v;
+        self
+    }
+    
+    pub fn with_row_spacing(mut self, v: f64) -> Self {
+        self.row_spacing = v;
+        self
+    }
+    
     pub fn with_scroll(mut self, v: DVec2) -> Self {
         self.scroll = v;
         self
@@ -984,6 +1443,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(0.0),
             height: Size::Fixed(0.0),
+            ..Self::default()
         }
     }
     
@@ -993,6 +1453,7 @@ impl Walk {
             margin: Margin::default(),
             width: w,
             height: h,
+            ..Self::default()
         }
     }
 
@@ -1003,6 +1464,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(w),
             height: Size::Fixed(h),
+            ..Self::default()
         }
     }
         
@@ -1012,6 +1474,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fixed(size.x),
             height: Size::Fixed(size.y),
+            ..Self::default()
         }
     }
     
@@ -1021,6 +1484,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fit,
             height: Size::Fit,
+            ..Self::default()
         }
     }
     
@@ -1030,6 +1494,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fill,
             height: Size::Fill,
+            ..Self::default()
         }
     }
     
@@ -1039,6 +1504,7 @@ impl Walk {
             margin: Margin::default(),
             width: Size::Fill,
             height: Size::Fit,
+            ..Self::default()
         }
     }
     
@@ -1076,6 +1542,85 @@ impl Walk {
         self
     }
     
+    pub fn with_span(mut self, columns: usize, rows: usize) -> Self {
+        self.span = GridSpan {columns, rows};
+        self
+    }
+    
+    pub fn with_min_width(mut self, v: f64) -> Self {
+        self.min_width = Some(v);
+        self
+    }
+    
+    pub fn with_max_width(mut self, v: f64) -> Self {
+        self.max_width = Some(v);
+        self
+    }
+    
+    pub fn with_min_height(mut self, v: f64) -> Self {
+        self.min_height = Some(v);
+        self
+    }
+    
+    pub fn with_max_height(mut self, v: f64) -> Self {
+        self.max_height = Some(v);
+        self
+    }
+    
+    pub fn with_aspect_ratio(mut self, v: f64) -> Self {
+        self.aspect_ratio = Some(v);
+        self
+    }
+    
+    fn clamp_size(&self, size: DVec2) -> DVec2 {
+        dvec2(
+            clamp_keep_nan(size.x, self.min_width, self.max_width),
+            clamp_keep_nan(size.y, self.min_height, self.max_height)
+        )
+    }
+    
+    /// Applies the min/max sizes and the aspect ratio to an evaluated size. The aspect
+    /// ratio sizes the axis that isn't known or isn't Fixed from the other one, when
+    /// neither is Fixed the largest size with that ratio inside the given size is used.
+    /// Fit axes are NaN here and are constrained once measured
+    pub fn constrain(&self, size: DVec2) -> DVec2 {
+        let mut size = self.clamp_size(size);
+        if let Some(ratio) = self.aspect_ratio.filter( | ratio | *ratio > 0.0) {
+            let from_width = match (size.x.is_nan(), size.y.is_nan()) {
+                (false, true) => Some(true),
+                (true, false) => Some(false),
+                (false, false) => match (self.width.is_fixed(), self.height.is_fixed()) {
+                    (true, true) => None,
+                    (true, false) => Some(true),
+                    (false, true) => Some(false),
+                    (false, false) => Some(size.x / ratio <= size.y)
+                },
+                (true, true) => None
+            };
+            match from_width {
+                Some(true) => size.y = clamp_keep_nan(size.x / ratio, self.min_height, self.max_height),
+                Some(false) => size.x = clamp_keep_nan(size.y * ratio, self.min_width, self.max_width),
+                None => ()
+            }
+        }
+        size
+    }
+    
+    // constrains the size a Fit turtle measured, when both axes were measured the
+    // aspect ratio grows the one that falls short
+    fn constrain_measured(&self, size: DVec2, both_measured: bool) -> DVec2 {
+        let mut size = self.clamp_size(size);
+        if let (true, Some(ratio)) = (both_measured, self.aspect_ratio.filter( | ratio | *ratio > 0.0)) {
+            if size.x / ratio < size.y {
+                size.x = clamp_keep_nan(size.y * ratio, self.min_width, self.max_width);
+            }
+            else {
+                size.y = clamp_keep_nan(size.x / ratio, self.min_height, self.max_height);
+            }
+        }
+        size
+    }
+    
     pub fn with_add_padding(mut self, v: Padding) -> Self {
         self.margin.top += v.top;
         self.margin.left += v.left;
@@ -1119,6 +1664,118 @@ impl Default for Flow {
     fn default() -> Self {Self::Down}
 }
 
+impl Default for GridTrack {
+    fn default() -> Self {Self::Fit}
+}
+
+impl LiveHook for GridTrack {
+    fn skip_apply(&mut self, _cx: &mut Cx, _apply_from: ApplyFrom, index: usize, nodes: &[LiveNode]) -> Option<usize> {
+        if let Some(v) = nodes[index].value.as_float() {
+            *self = Self::Fixed(v);
+            Some(index + 1)
+        }
+        else {
+            None
+        }
+    }
+}
+
+impl GridTracks {
+    pub fn from_slice(tracks: &[GridTrack]) -> Self {
+        let mut ret = Self::default();
+        for track in tracks.iter().take(GRID_MAX_TRACKS) {
+            ret.tracks[ret.len] = *track;
+            ret.len += 1;
+        }
+        ret
+    }
+    
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    pub fn get(&self, index: usize) -> Option<GridTrack> {
+        self.tracks[..self.len].get(index).copied()
+    }
+    
+    pub fn as_slice(&self) -> &[GridTrack] {
+        &self.tracks[..self.len]
+    }
+}
+
+impl std::fmt::Debug for GridTracks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl Default for GridTracks {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            tracks: [GridTrack::Fit; GRID_MAX_TRACKS]
+        }
+    }
+}
+
+impl LiveHook for GridTracks {}
+impl LiveNew for GridTracks {
+    fn new(_cx: &mut Cx) -> Self {
+        Self::default()
+    }
+    
+    fn live_type_info(_cx: &mut Cx) -> LiveTypeInfo {
+        LiveTypeInfo {
+            module_id: LiveModuleId::from_str(&module_path!()).unwrap(),
+            live_type: LiveType::of::<Self>(),
+            live_ignore: true,
+            fields: Vec::new(),
+            type_name: id_lut!(GridTracks)
+        }
+    }
+}
+
+impl LiveApply for GridTracks {
+    fn apply(&mut self, cx: &mut Cx, from: ApplyFrom, index: usize, nodes: &[LiveNode]) -> usize {
+        if !nodes[index].is_array() {
+            cx.apply_error_expected_array(live_error_origin!(), index, nodes);
+            return nodes.skip_node(index)
+        }
+        *self = Self::default();
+        let mut index = index + 1;
+        while !nodes[index].is_close() {
+            if self.len < GRID_MAX_TRACKS {
+                self.tracks[self.len] = GridTrack::new_apply_mut_index(cx, from, &mut index, nodes);
+                self.len += 1;
+            }
+            else {
+                cx.apply_error(live_error_origin!(), index, nodes, format!("grid has more than {} tracks", GRID_MAX_TRACKS));
+                index = nodes.skip_node(index);
+            }
+        }
+        index + 1
+    }
+}
+
+impl Default for GridSpan {
+    fn default() -> Self {
+        Self {columns: 1, rows: 1}
+    }
+}
+
+impl LiveHook for GridSpan {
+    // `span: 2` spans two columns
+    fn skip_apply(&mut self, _cx: &mut Cx, _apply_from: ApplyFrom, index: usize, nodes: &[LiveNode]) -> Option<usize> {
+        if let Some(v) = nodes[index].value.as_int() {
+            *self = Self {columns: v.max(1) as usize, rows: 1};
+            Some(index + 1)
+        }
+        else {
+            None
+        }
+    }
+}
+
 
 impl LiveHook for Size {
     fn skip_apply(&mut self, cx: &mut Cx, _apply_from: ApplyFrom, index: usize, nodes: &[LiveNode]) -> Option<usize> {
@@ -1200,6 +1857,14 @@ impl Size {
     }
 }
 
+fn clamp_keep_nan(v: f64, min: Option<f64>, max: Option<f64>) -> f64 {
+    if v.is_nan() {
+        return v
+    }
+    let v = if let Some(max) = max {v.min(max)} else {v};
+    if let Some(min) = min {v.max(min)} else {v}
+}
+
 fn max_zero_keep_nan(v: f64) -> f64 {
     if v.is_nan() {
         v
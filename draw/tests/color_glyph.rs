@@ -0,0 +1,363 @@
+use {
+    std::rc::Rc,
+    makepad_draw::{
+        *,
+        color_glyph::*,
+        font_atlas::{CxFont, CxFontsAtlas},
+    },
+};
+
+// builds big endian tables, with slots for offsets that are known once their target is written
+#[derive(Default)]
+struct Table(Vec<u8>);
+
+impl Table {
+    fn pos(&self) -> usize {
+        self.0.len()
+    }
+
+    fn u8(&mut self, v: u8) {
+        self.0.push(v);
+    }
+
+    fn u16(&mut self, v: u16) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u32(&mut self, v: u32) {
+        self.0.extend_from_slice(&v.to_be_bytes());
+    }
+
+    fn u24_slot(&mut self) -> usize {
+        self.0.extend_from_slice(&[0; 3]);
+        self.pos() - 3
+    }
+
+    fn u32_slot(&mut self) -> usize {
+        self.u32(0);
+        self.pos() - 4
+    }
+
+    fn set_u24(&mut self, slot: usize, v: usize) {
+        self.0[slot..slot + 3].copy_from_slice(&(v as u32).to_be_bytes()[1..]);
+    }
+
+    fn set_u32(&mut self, slot: usize, v: usize) {
+        self.0[slot..slot + 4].copy_from_slice(&(v as u32).to_be_bytes());
+    }
+}
+
+// one palette of red, green and blue
+fn cpal() -> Vec<u8> {
+    let mut t = Table::default();
+    t.u16(0);
+    t.u16(3);
+    t.u16(1);
+    t.u16(3);
+    t.u32(14);
+    t.u16(0);
+    for bgra in [[0, 0, 255, 255], [0, 255, 0, 255], [255, 0, 0, 128]] {
+        t.0.extend_from_slice(&bgra);
+    }
+    t.0
+}
+
+// version 0 glyph 10 of two layers, glyph 3 in red and glyph 4 in the text color
+fn colr_v0(glyph_a: u16, glyph_b: u16) -> Vec<u8> {
+    let mut t = Table::default();
+    t.u16(0);
+    t.u16(1);
+    t.u32(14);
+    t.u32(20);
+    t.u16(2);
+    t.u16(10);
+    t.u16(0);
+    t.u16(2);
+    t.u16(glyph_a);
+    t.u16(0);
+    t.u16(glyph_b);
+    t.u16(0xffff);
+    t.0
+}
+
+// version 1 glyph 20, a graph of a layer list with a translated composite and a gradient
+fn colr_v1(glyph_a: u16, glyph_b: u16) -> Vec<u8> {
+    let mut t = Table::default();
+    t.u16(1);
+    t.u16(0);
+    t.u32(0);
+    t.u32(0);
+    t.u16(0);
+    let base_list_slot = t.u32_slot();
+    let layer_list_slot = t.u32_slot();
+    let clip_list_slot = t.u32_slot();
+    t.u32(0);
+    t.u32(0);
+
+    let base_list = t.pos();
+    t.set_u32(base_list_slot, base_list);
+    t.u32(1);
+    t.u16(20);
+    let root_slot = t.u32_slot();
+
+    let layer_list = t.pos();
+    t.set_u32(layer_list_slot, layer_list);
+    t.u32(2);
+    let layer0_slot = t.u32_slot();
+    let layer1_slot = t.u32_slot();
+
+    // PaintColrLayers of both layers
+    t.set_u32(root_slot, t.pos() - base_list);
+    t.u8(1);
+    t.u8(2);
+    t.u32(0);
+
+    // PaintGlyph of glyph a, filled with half transparent green
+    let glyph = t.pos();
+    t.set_u32(layer0_slot, glyph - layer_list);
+    t.u8(10);
+    let solid_slot = t.u24_slot();
+    t.u16(glyph_a);
+    t.set_u24(solid_slot, t.pos() - glyph);
+    t.u8(2);
+    t.u16(1);
+    t.u16(0x2000);
+
+    // PaintTranslate by (10, 20) of a composite
+    let translate = t.pos();
+    t.set_u32(layer1_slot, translate - layer_list);
+    t.u8(14);
+    let composite_slot = t.u24_slot();
+    t.u16(10);
+    t.u16(20);
+
+    // PaintComposite of a gradient glyph in the text color
+    let composite = t.pos();
+    t.set_u24(composite_slot, composite - translate);
+    t.u8(32);
+    let source_slot = t.u24_slot();
+    t.u8(5);
+    let backdrop_slot = t.u24_slot();
+
+    let glyph = t.pos();
+    t.set_u24(source_slot, glyph - composite);
+    t.u8(10);
+    let gradient_slot = t.u24_slot();
+    t.u16(glyph_b);
+
+    // PaintLinearGradient from red at x 0 to green at x 500
+    let gradient = t.pos();
+    t.set_u24(gradient_slot, gradient - glyph);
+    t.u8(4);
+    let color_line_slot = t.u24_slot();
+    for v in [0, 0, 500, 0, 0, 1000] {
+        t.u16(v);
+    }
+    t.set_u24(color_line_slot, t.pos() - gradient);
+    t.u8(0);
+    t.u16(2);
+    for (offset, index) in [(0x4000, 1), (0, 0)] {
+        t.u16(offset);
+        t.u16(index);
+        t.u16(0x4000);
+    }
+
+    t.set_u24(backdrop_slot, t.pos() - composite);
+    t.u8(2);
+    t.u16(0xffff);
+    t.u16(0x4000);
+
+    // clip box of glyph 20
+    let clip_list = t.pos();
+    t.set_u32(clip_list_slot, clip_list);
+    t.u8(1);
+    t.u32(1);
+    t.u16(20);
+    t.u16(20);
+    let clip_box_slot = t.u24_slot();
+    t.set_u24(clip_box_slot, t.pos() - clip_list);
+    t.u8(1);
+    for v in [-10i16, -20, 500, 600] {
+        t.u16(v as u16);
+    }
+    t.0
+}
+
+#[derive(Debug, PartialEq)]
+enum Op {
+    Transform(Affine),
+    PopTransform,
+    Clip(u16),
+    PopClip,
+    Fill(Brush),
+    Layer,
+    PopLayer(CompositeMode),
+}
+
+#[derive(Default)]
+struct RecordingPainter(Vec<Op>);
+
+impl ColrPainter for RecordingPainter {
+    fn push_transform(&mut self, transform: Affine) {
+        self.0.push(Op::Transform(transform));
+    }
+    fn pop_transform(&mut self) {
+        self.0.push(Op::PopTransform);
+    }
+    fn push_clip_glyph(&mut self, glyph_id: u16) {
+        self.0.push(Op::Clip(glyph_id));
+    }
+    fn pop_clip(&mut self) {
+        self.0.push(Op::PopClip);
+    }
+    fn fill(&mut self, brush: &Brush) {
+        self.0.push(Op::Fill(brush.clone()));
+    }
+    fn push_layer(&mut self) {
+        self.0.push(Op::Layer);
+    }
+    fn pop_layer(&mut self, mode: CompositeMode) {
+        self.0.push(Op::PopLayer(mode));
+    }
+}
+
+const RED: Vec4 = Vec4 {x: 1.0, y: 0.0, z: 0.0, w: 1.0};
+const WHITE: Vec4 = Vec4 {x: 1.0, y: 1.0, z: 1.0, w: 1.0};
+
+#[test]
+fn parses_v0_layers() {
+    let cpal_data = cpal();
+    let cpal = Cpal::parse(&cpal_data).unwrap();
+    assert_eq!(cpal.colors[0], RED);
+    assert_eq!(cpal.colors[2], vec4(0.0, 0.0, 1.0, 128.0 / 255.0));
+
+    let colr_data = colr_v0(3, 4);
+    let colr = Colr::parse(&colr_data).unwrap();
+    assert!(colr.contains(10));
+    assert!(!colr.contains(3));
+    let mut painter = RecordingPainter::default();
+    assert!(colr.paint(10, Some(&cpal), &mut painter));
+    assert_eq!(painter.0, vec![
+        Op::Clip(3),
+        Op::Fill(Brush::Solid(RED)),
+        Op::PopClip,
+        Op::Clip(4),
+        Op::Fill(Brush::Solid(WHITE)),
+        Op::PopClip,
+    ]);
+    assert!(!colr.paint(3, Some(&cpal), &mut RecordingPainter::default()));
+}
+
+#[test]
+fn parses_v1_paint_graph() {
+    let cpal_data = cpal();
+    let cpal = Cpal::parse(&cpal_data).unwrap();
+    let colr_data = colr_v1(5, 6);
+    let colr = Colr::parse(&colr_data).unwrap();
+    assert_eq!(colr.clip_box(20), Some((-10.0, -20.0, 500.0, 600.0)));
+    assert_eq!(colr.clip_box(21), None);
+
+    let mut painter = RecordingPainter::default();
+    assert!(colr.paint(20, Some(&cpal), &mut painter));
+    let gradient = Brush::LinearGradient {
+        p0: dvec2(0.0, 0.0),
+        p1: dvec2(500.0, 0.0),
+        p2: dvec2(0.0, 1000.0),
+        color_line: ColorLine {
+            extend: Extend::Pad,
+            stops: vec![(0.0, RED), (1.0, cpal.colors[1])],
+        }
+    };
+    assert_eq!(painter.0, vec![
+        Op::Clip(5),
+        Op::Fill(Brush::Solid(vec4(0.0, 1.0, 0.0, 0.5))),
+        Op::PopClip,
+        Op::Transform(Affine::translate(10.0, 20.0)),
+        Op::Layer,
+        Op::Fill(Brush::Solid(WHITE)),
+        Op::Layer,
+        Op::Clip(6),
+        Op::Fill(gradient),
+        Op::PopClip,
+        Op::PopLayer(CompositeMode::SrcIn),
+        Op::PopLayer(CompositeMode::SrcOver),
+        Op::PopTransform,
+    ]);
+}
+
+#[test]
+fn renders_layers_with_outlines() {
+    let data = std::fs::read(format!("{}/../widgets/resources/LiberationMono-Regular.ttf", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let font = CxFont::load_from_ttf_bytes(Rc::new(data)).unwrap();
+    assert!(!font.has_color_glyphs);
+    font.owned_font_face.with_ref( | face | {
+        let glyph_l = face.glyph_index('l').unwrap().0;
+        let glyph_o = face.glyph_index('O').unwrap().0;
+        let cpal_data = cpal();
+        let cpal = Cpal::parse(&cpal_data).unwrap();
+
+        // a red 'l' inside a white 'O'
+        let colr_data = colr_v0(glyph_o, glyph_l);
+        let colr = Colr::parse(&colr_data).unwrap();
+        let image = render_colr_glyph(&colr, Some(&cpal), face, 10, 64.0).unwrap();
+        assert!(image.width > 20 && image.height > 30);
+        assert!(image.left >= 0.0 && image.bottom >= -1.0);
+        assert_eq!(image.pixels.len(), image.width * image.height);
+        let count = | color: u32 | image.pixels.iter().filter( | p | **p == color).count();
+        assert!(count(0xffff0000) > 20);
+        assert!(count(0xffffffff) > 20);
+        assert!(count(0) > 20);
+        // the middle of the 'O' is where the 'l' is drawn over it
+        let center = image.pixels[(image.height / 2) * image.width + image.width / 2];
+        assert_eq!(center, 0xffffffff);
+
+        // the 'O' is cut off by the clip box, its gradient runs from red to green
+        let colr_data = colr_v1(glyph_l, glyph_o);
+        let colr = Colr::parse(&colr_data).unwrap();
+        let image = render_colr_glyph(&colr, Some(&cpal), face, 20, 64.0).unwrap();
+        let ppem_scale = 64.0 / 2048.0;
+        assert_eq!(image.left, (-10.0f64 * ppem_scale).floor());
+        assert_eq!(image.width as f64, (500.0f64 * ppem_scale).ceil() - image.left);
+        let row = &image.pixels[(image.height / 2) * image.width..(image.height / 2 + 1) * image.width];
+        let opaque: Vec<u32> = row.iter().cloned().filter( | p | *p >> 24 == 0xff).collect();
+        let (first, last) = (opaque[0], opaque[opaque.len() - 1]);
+        assert!((first >> 16) & 0xff > (first >> 8) & 0xff);
+        assert!((last >> 8) & 0xff > (last >> 16) & 0xff);
+    });
+}
+
+#[test]
+fn color_atlas_is_emptied_once_full() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    let mut atlas = CxFontsAtlas::new(Texture::new(&mut cx).texture_id(), Texture::new(&mut cx).texture_id());
+    atlas.color.alloc.texture_size = dvec2(64.0, 64.0);
+    let image = ColorGlyphImage {
+        width: 20,
+        height: 20,
+        pixels: (0..400).collect(),
+        left: 0.0,
+        bottom: 0.0,
+    };
+    // three rows of three images, with a pixel between them
+    let mut added = 0;
+    while atlas.color.add_image(&image).is_some() {
+        added += 1;
+    }
+    assert_eq!(added, 9);
+    assert!(atlas.color.alloc.full);
+    // each image is uploaded on its own
+    let rects: Vec<_> = atlas.color.uploads.iter().map( | (rect, _) | (rect.x, rect.y, rect.width, rect.height)).collect();
+    assert_eq!(&rects[..4], &[(0, 0, 20, 20), (21, 0, 20, 20), (42, 0, 20, 20), (0, 21, 20, 20)]);
+    assert!(atlas.color.uploads.iter().all( | (_, pixels) | *pixels == image.pixels));
+
+    let data = std::fs::read(format!("{}/../widgets/resources/LiberationMono-Regular.ttf", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    let mut font = CxFont::load_from_ttf_bytes(Rc::new(data)).unwrap();
+    let page = font.get_atlas_page_id(1.0, 12.0);
+    font.atlas_pages[page].color_glyphs.insert(10, None);
+    atlas.fonts.push(Some(font));
+    atlas.reset_color_atlas();
+    assert!(!atlas.color.alloc.full);
+    assert!(atlas.color.uploads.is_empty());
+    assert!(atlas.fonts[0].as_ref().unwrap().atlas_pages[page].color_glyphs.is_empty());
+    assert!(atlas.color.add_image(&image).is_some());
+}
@@ -0,0 +1,65 @@
+use {
+    std::rc::Rc,
+    makepad_draw::{
+        *,
+        font_atlas::{CxFont, CxFontsAtlas, FontChain},
+        system_fonts::SystemFonts,
+    },
+};
+
+// an atlas holding a monospace font without check marks, followed by a font that has them
+fn atlas() -> (CxFontsAtlas, FontChain) {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    let mut atlas = CxFontsAtlas::new(Texture::new(&mut cx).texture_id(), Texture::new(&mut cx).texture_id());
+    for file in ["LiberationMono-Regular.ttf", "IBMPlexSans-Text.ttf"] {
+        let data = std::fs::read(format!("{}/../widgets/resources/{}", env!("CARGO_MANIFEST_DIR"), file)).unwrap();
+        atlas.fonts.push(Some(CxFont::load_from_ttf_bytes(Rc::new(data)).unwrap()));
+    }
+    (atlas, FontChain {font_ids: vec![0, 1], system_fallback: false})
+}
+
+#[test]
+fn font_for_char() {
+    let (mut atlas, chain) = atlas();
+    assert_eq!(atlas.font_for_char(&chain, 'a'), Some(0));
+    assert_eq!(atlas.font_for_char(&chain, '✓'), Some(1));
+    // nothing covers it, the primary font draws its missing glyph
+    assert_eq!(atlas.font_for_char(&chain, 'א'), Some(0));
+    assert_eq!(atlas.font_for_char(&FontChain::default(), 'a'), None);
+}
+
+#[test]
+fn font_runs() {
+    let (mut atlas, chain) = atlas();
+    let text = "ab ✓✓ cd";
+    let mut runs = Vec::new();
+    atlas.font_runs(&chain, text, &mut runs);
+    let runs: Vec<_> = runs.into_iter().map( | (font_id, range) | (font_id, &text[range])).collect();
+    assert_eq!(runs, vec![(0, "ab "), (1, "✓✓ "), (0, "cd")]);
+}
+
+#[test]
+fn char_advance_in_ems() {
+    let (mut atlas, chain) = atlas();
+    // the fonts have different units per em, advances are relative to the em of each
+    assert_eq!(atlas.char_advance(&chain, 'a'), Some(1229.0 / 2048.0));
+    let check = atlas.char_advance(&chain, '✓').unwrap();
+    assert!(check > 0.2 && check < 1.5);
+}
+
+#[test]
+fn system_fonts_are_scanned_on_a_thread() {
+    let mut fonts = SystemFonts::default();
+    // the first lookup only starts the scan, and doesn't remember that nothing was found
+    assert_eq!(fonts.find_face('a'), None);
+    let start = std::time::Instant::now();
+    while !fonts.handle_scan_done() {
+        assert!(start.elapsed().as_secs() < 60);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    assert!(!fonts.handle_scan_done());
+    assert!(!fonts.poll_scan());
+    if let Some(face_index) = fonts.find_face('a') {
+        assert!(fonts.face(face_index).covers('a'));
+    }
+}
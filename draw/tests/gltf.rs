@@ -0,0 +1,119 @@
+use makepad_draw::*;
+
+// one triangle and a u16 index buffer, placed by a translated parent and a scaled child
+const TRIANGLE_JSON: &str = r#"{
+        "asset": {"version": "2.0"},
+        "scene": 0,
+        "scenes": [{"nodes": [0]}],
+        "nodes": [
+            {"translation": [1, 2, 3], "children": [1]},
+            {"scale": [2, 2, 2], "mesh": 0}
+        ],
+        "meshes": [{"primitives": [{"attributes": {"POSITION": 0}, "indices": 1, "material": 0}]}],
+        "materials": [{"pbrMetallicRoughness": {"baseColorFactor": [1, 0.5, 0.25, 1]}}],
+        "accessors": [
+            {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"},
+            {"bufferView": 1, "componentType": 5123, "count": 3, "type": "SCALAR"}
+        ],
+        "bufferViews": [
+            {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+            {"buffer": 0, "byteOffset": 36, "byteLength": 6}
+        ],
+        "buffers": [{"byteLength": 44}]
+    }"#;
+
+fn triangle_glb() -> Vec<u8> {
+    glb(TRIANGLE_JSON)
+}
+
+// the binary chunk of the triangle, with the given json chunk
+fn glb(json: &str) -> Vec<u8> {
+    let mut bin = Vec::new();
+    for v in [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0] {
+        bin.extend_from_slice(&v.to_le_bytes());
+    }
+    for i in [0u16, 1, 2, 0] {
+        bin.extend_from_slice(&i.to_le_bytes());
+    }
+    let mut json = json.as_bytes().to_vec();
+    while json.len() % 4 != 0 {
+        json.push(b' ');
+    }
+    let mut glb = Vec::new();
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&((12 + 8 + json.len() + 8 + bin.len()) as u32).to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(&0x4E4F534Au32.to_le_bytes());
+    glb.extend_from_slice(&json);
+    glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    glb.extend_from_slice(&0x004E4942u32.to_le_bytes());
+    glb.extend_from_slice(&bin);
+    glb
+}
+
+fn assert_close(a: Vec4, b: Vec4) {
+    let d = a - b;
+    assert!(d.x.abs() + d.y.abs() + d.z.abs() + d.w.abs() < 1e-4, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn loads_triangle_with_node_transforms() {
+    let scene = GltfScene::from_glb(&triangle_glb()).unwrap();
+    assert_eq!(scene.meshes.len(), 1);
+    let geometry = &scene.meshes[0].primitives[0].geometry;
+    assert_eq!(geometry.indices, vec![0, 1, 2]);
+    // position, id, normal and uv per vertex
+    assert_eq!(geometry.vertices.len(), 3 * 9);
+    assert_eq!(&geometry.vertices[9..12], &[1.0, 0.0, 0.0]);
+    // the normal is computed from the winding
+    assert_eq!(&geometry.vertices[13..16], &[0.0, 0.0, 1.0]);
+    assert_eq!(scene.materials[0].base_color, vec4(1.0, 0.5, 0.25, 1.0));
+
+    assert_eq!(scene.instances.len(), 1);
+    let transform = scene.instances[0].transform;
+    // scaled by the child first, then translated by the parent
+    assert_close(transform.transform_vec4(vec4(1.0, 0.0, 0.0, 1.0)), vec4(3.0, 2.0, 3.0, 1.0));
+}
+
+#[test]
+fn rejects_invalid_glb() {
+    assert!(GltfScene::from_glb(b"not a glb").is_err());
+    let mut glb = triangle_glb();
+    glb.truncate(glb.len() - 10);
+    assert!(GltfScene::from_glb(&glb).is_err());
+}
+
+#[test]
+fn rejects_sizes_beyond_the_buffer() {
+    let positions = r#"{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3"}"#;
+    for (from, to) in [
+        // counts that overflow, or that the buffer view is too short for
+        (positions, r#"{"bufferView": 0, "componentType": 5126, "count": 4611686018427387904, "type": "VEC3"}"#),
+        (positions, r#"{"bufferView": 0, "componentType": 5126, "count": 1000000000, "type": "VEC3"}"#),
+        (positions, r#"{"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "byteOffset": 18446744073709551615}"#),
+        // zeros without a buffer view are limited by the size of the binary chunk
+        (positions, r#"{"componentType": 5126, "count": 1000000000, "type": "VEC3"}"#),
+        (r#""byteOffset": 36, "byteLength": 6"#, r#""byteOffset": 18446744073709551615, "byteLength": 6"#),
+    ] {
+        let json = TRIANGLE_JSON.replace(from, to);
+        assert_ne!(json, TRIANGLE_JSON);
+        assert!(GltfScene::from_glb(&glb(&json)).is_err(), "{}", to);
+    }
+}
+
+#[test]
+fn camera_views_target_from_eye() {
+    let mut camera = Camera3d::orbit(vec3(1.0, 0.0, 0.0), 4.0);
+    camera.rotate(90.0, 0.0);
+    let view = camera.view_matrix();
+    let eye = camera.eye();
+    assert_close(view.transform_vec4(vec4(eye.x, eye.y, eye.z, 1.0)), vec4(0.0, 0.0, 0.0, 1.0));
+    // the target lies straight ahead, down the negative z axis
+    assert_close(view.transform_vec4(vec4(1.0, 0.0, 0.0, 1.0)), vec4(0.0, 0.0, -4.0, 1.0));
+
+    camera.mode = CameraMode::Fly;
+    camera.position = vec3(0.0, 0.0, 0.0);
+    camera.dolly(2.0);
+    assert_close(vec4(camera.position.x, camera.position.y, camera.position.z, 1.0), vec4(2.0, 0.0, 0.0, 1.0));
+}
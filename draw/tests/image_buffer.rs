@@ -0,0 +1,34 @@
+use {
+    makepad_draw::*,
+    makepad_zune_png::PngDecoder,
+};
+
+#[test]
+fn unpremultiplies_and_flips_readback() {
+    // two rows from the bottom up, an opaque red and a half transparent white pixel on top
+    let rgba = [255, 0, 0, 255, 0, 0, 0, 0, 128, 128, 128, 128, 0, 64, 0, 64];
+    let image = ImageBuffer::from_premultiplied_rgba_bottom_up(&rgba, 2, 2);
+    assert_eq!(image.data, vec![0x80ffffff, 0x4000ff00, 0xffff0000, 0]);
+}
+
+#[test]
+fn encodes_png() {
+    let (width, height) = (67, 41);
+    let mut image = ImageBuffer {width, height, data: Vec::new()};
+    for y in 0..height {
+        for x in 0..width {
+            let noise = ((x * 7919 + y * 104729) % 251) as u32;
+            image.data.push(if x < 30 {0xff204080} else {(noise << 24) | ((x as u32 * 3) << 16) | ((y as u32 * 5) << 8) | noise});
+        }
+    }
+    let png = image.to_png();
+    assert_eq!(&png[1..4], b"PNG");
+    // the flat left half compresses
+    assert!(png.len() < width * height * 4 * 3 / 4);
+
+    let mut decoder = PngDecoder::new(&png);
+    let pixels = decoder.decode().unwrap().u8().unwrap();
+    assert_eq!(decoder.get_dimensions(), Some((width, height)));
+    let decoded = ImageBuffer::new(&pixels, width, height).unwrap();
+    assert_eq!(decoded.data, image.data);
+}
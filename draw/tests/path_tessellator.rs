@@ -0,0 +1,73 @@
+use makepad_draw::{
+    makepad_vector::{geometry::Point, path::Path},
+    path_tessellator::*,
+};
+
+fn area(triangles: &[PathTriangle]) -> f64 {
+    triangles.iter().map( | t | {
+        let [a, b, c] = t.points;
+        ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)) * 0.5
+    }).sum()
+}
+
+fn square(path: &mut Path, min: f64, max: f64) {
+    path.move_to(Point::new(min, min));
+    path.line_to(Point::new(max, min));
+    path.line_to(Point::new(max, max));
+    path.line_to(Point::new(min, max));
+    path.close();
+}
+
+#[test]
+fn fills_with_both_rules() {
+    // two squares wound the same way, the inner one is a hole only for even-odd
+    let mut path = Path::new();
+    square(&mut path, 0.0, 10.0);
+    square(&mut path, 2.0, 8.0);
+    let contours = flatten_path(&path, 0.1, | p | p * 2.0);
+    assert_eq!(contours.len(), 2);
+    assert!(contours.iter().all( | c | c.closed && c.points.len() == 4));
+
+    let mut tessellator = PathTessellator::default();
+    let mut triangles = Vec::new();
+    tessellator.fill(&contours, FillRule::NonZero, &mut triangles);
+    assert!((area(&triangles) - 400.0).abs() < 1e-3);
+    assert!(triangles.iter().all( | t | area(&[*t]) > 0.0));
+
+    triangles.clear();
+    tessellator.fill(&contours, FillRule::EvenOdd, &mut triangles);
+    assert!((area(&triangles) - (400.0 - 144.0)).abs() < 1e-3);
+    let outline: f64 = triangles.iter().map( | t | {
+        (0..3).filter( | i | t.outline[*i]).map( | i | (t.points[(i + 1) % 3] - t.points[i]).length()).sum::<f64>()
+    }).sum();
+    assert!((outline - (80.0 + 48.0)).abs() < 1e-3);
+}
+
+#[test]
+fn strokes_curves_with_caps() {
+    let mut path = Path::new();
+    path.move_to(Point::new(0.0, 0.0));
+    path.line_to(Point::new(10.0, 0.0));
+    let contours = flatten_path(&path, 0.1, | p | p);
+    let mut tessellator = PathTessellator::default();
+    let mut triangles = Vec::new();
+    let mut style = StrokeStyle {width: 2.0, ..StrokeStyle::default()};
+    tessellator.stroke(&contours, &style, 0.1, &mut triangles);
+    assert!((area(&triangles) - 20.0).abs() < 1e-3);
+
+    triangles.clear();
+    style.line_cap = LineCap::Square;
+    tessellator.stroke(&contours, &style, 0.1, &mut triangles);
+    assert!((area(&triangles) - 24.0).abs() < 1e-3);
+
+    // a quarter of a circle of radius 10 from a cubic
+    let mut path = Path::new();
+    path.move_to(Point::new(10.0, 0.0));
+    path.cubic_to(Point::new(10.0, 5.5228), Point::new(5.5228, 10.0), Point::new(0.0, 10.0));
+    let contours = flatten_path(&path, 0.01, | p | p);
+    assert!(contours[0].points.len() > 4);
+    triangles.clear();
+    tessellator.stroke(&contours, &StrokeStyle {width: 2.0, ..StrokeStyle::default()}, 0.01, &mut triangles);
+    let expected = std::f64::consts::FRAC_PI_4 * (11.0 * 11.0 - 9.0 * 9.0);
+    assert!((area(&triangles) - expected).abs() < expected * 0.01);
+}
@@ -0,0 +1,98 @@
+use {
+    std::rc::Rc,
+    makepad_draw::{
+        *,
+        font_atlas::{CxFont, CxFontsAtlas},
+        rich_text::*,
+    },
+};
+
+// a rich text using the monospace font, 9pt so every character advances 7.2 pixels
+fn rich_text(f: impl FnOnce(&mut RichText, &mut CxFontsAtlas)) {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    let mut atlas = CxFontsAtlas::new(Texture::new(&mut cx).texture_id(), Texture::new(&mut cx).texture_id());
+    let data = std::fs::read(format!("{}/../widgets/resources/LiberationMono-Regular.ttf", env!("CARGO_MANIFEST_DIR"))).unwrap();
+    atlas.fonts.push(Some(CxFont::load_from_ttf_bytes(Rc::new(data)).unwrap()));
+    let mut rich_text = RichText::new(&mut cx);
+    rich_text.draw_glyph.text_style.font.font_id = Some(0);
+    rich_text.draw_glyph.text_style.font_size = 9.0;
+    f(&mut rich_text, &mut atlas)
+}
+
+const ADVANCE: f64 = 9.0 * 96.0 / 72.0 * 1229.0 / 2048.0;
+
+fn line_text(layout: &RichTextLayout, spans: &[TextSpan], line: usize) -> String {
+    let line = &layout.lines[line];
+    layout.chars[line.start..line.end].iter().map( | c | {
+        spans[c.pos.span].text[c.pos.index..].chars().next().unwrap()
+    }).collect()
+}
+
+#[test]
+fn wraps_words_across_spans() {
+    rich_text( | rich_text, atlas | {
+        let spans = [
+            TextSpan::plain("hello "),
+            TextSpan::new("wor", SpanStyle::default().bold()),
+            TextSpan::plain("ld again"),
+        ];
+        let layout = rich_text.compute_layout(atlas, &spans, 70.0);
+        let lines: Vec<_> = (0..layout.lines.len()).map( | i | line_text(&layout, &spans, i)).collect();
+        assert_eq!(lines, vec!["hello ", "world ", "again"]);
+        assert_eq!(layout.lines[1].width, 5.0 * ADVANCE);
+        assert_eq!(layout.chars[6].pos, RichTextPos {span: 1, index: 0});
+        assert_eq!(layout.chars[6].rect.pos, dvec2(0.0, layout.lines[1].top));
+
+        // without a width nothing wraps, and newlines still break
+        let spans = [TextSpan::plain("ab\ncd")];
+        let layout = rich_text.compute_layout(atlas, &spans, f64::NAN);
+        assert_eq!(layout.lines.len(), 2);
+        assert_eq!(layout.size.x, 2.0 * ADVANCE);
+    });
+}
+
+#[test]
+fn mixed_sizes_share_a_baseline() {
+    rich_text( | rich_text, atlas | {
+        let spans = [
+            TextSpan::plain("a"),
+            TextSpan::new("B", SpanStyle::default().with_font_size(18.0)),
+            TextSpan::new("2", SpanStyle::default().with_baseline_shift(4.0)),
+        ];
+        let layout = rich_text.compute_layout(atlas, &spans, f64::NAN);
+        let line = layout.lines[0];
+        // top_drop 1.1 and height_factor 1.3 of the largest size
+        assert!((line.baseline - 18.0 * 1.1).abs() < 1e-9);
+        assert!((line.height - 18.0 * 1.3).abs() < 1e-9);
+        assert_eq!(layout.chars[1].rect.size.x, 2.0 * ADVANCE);
+        assert_eq!(layout.size.y, line.height);
+    });
+}
+
+#[test]
+fn hit_testing() {
+    rich_text( | rich_text, atlas | {
+        let spans = [
+            TextSpan::plain("one two "),
+            TextSpan::new("three", SpanStyle::default().underline()),
+        ];
+        let layout = rich_text.compute_layout(atlas, &spans, 10.0 * ADVANCE);
+        assert_eq!(layout.lines.len(), 2);
+        let line2 = layout.lines[1];
+
+        assert_eq!(layout.closest_offset(dvec2(ADVANCE * 1.4, 1.0)), Some(RichTextPos {span: 0, index: 1}));
+        // past the end of the first line the cursor stays before the wrapping space
+        assert_eq!(layout.closest_offset(dvec2(9.5 * ADVANCE, 1.0)), Some(RichTextPos {span: 0, index: 7}));
+        assert_eq!(layout.closest_offset(dvec2(100.0 * ADVANCE, line2.top + 1.0)), Some(layout.end));
+        assert_eq!(layout.cursor_pos(layout.end), Some(dvec2(5.0 * ADVANCE, line2.top)));
+
+        let rects = layout.get_selection_rects(RichTextPos {span: 0, index: 4}, RichTextPos {span: 1, index: 2});
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0].pos.x, 4.0 * ADVANCE);
+        assert_eq!(rects[0].size.x, 4.0 * ADVANCE);
+        assert_eq!(rects[1].size.x, 2.0 * ADVANCE);
+
+        let rects = layout.span_rects(1);
+        assert_eq!(rects, vec![Rect {pos: dvec2(0.0, line2.top), size: dvec2(5.0 * ADVANCE, line2.height)}]);
+    });
+}
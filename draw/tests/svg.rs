@@ -0,0 +1,124 @@
+use makepad_draw::{
+    *,
+    svg::*,
+};
+
+fn triangle_area(t: &SvgTriangle) -> f64 {
+    let [a, b, c] = t.triangle.points;
+    ((b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)) * 0.5
+}
+
+fn outline_length(mesh: &SvgMesh, paint: usize) -> f64 {
+    mesh.triangles.iter().filter( | t | t.paint == paint).map( | t | {
+        (0..3).filter( | i | t.triangle.outline[*i]).map( | i | (t.triangle.points[(i + 1) % 3] - t.triangle.points[i]).length()).sum::<f64>()
+    }).sum()
+}
+
+#[test]
+fn parses_shapes_and_transforms() {
+    let doc = SvgDocument::parse(r##"<?xml version="1.0"?>
+        <!-- an icon -->
+        <svg xmlns="http://www.w3.org/2000/svg" width="48" viewBox="0 0 24 12">
+            <g transform="translate(2 1) scale(2)" fill="red" opacity="0.5">
+                <rect width="3" height="2"/>
+                <circle cx="5" cy="1" r="1" style="fill:none;stroke:#00f;stroke-width:.5"/>
+            </g>
+            <path d="M0 0h4v4H0z m6 0l2 2-2 2Z" fill="currentColor" fill-opacity="50%"/>
+            <polyline points="0,0 1,1" fill="none"/>
+        </svg>
+    "##).unwrap();
+    assert_eq!(doc.size, dvec2(48.0, 24.0));
+    assert_eq!(doc.view_box, Rect {pos: dvec2(0.0, 0.0), size: dvec2(24.0, 12.0)});
+    assert_eq!(doc.shapes.len(), 3);
+
+    let rect = &doc.shapes[0];
+    let points: Vec<DVec2> = rect.path.points().iter().map( | p | dvec2(p.x, p.y)).collect();
+    assert_eq!(points, vec![dvec2(2.0, 1.0), dvec2(8.0, 1.0), dvec2(8.0, 5.0), dvec2(2.0, 5.0)]);
+    assert_eq!(rect.fill, Some(SvgPaint::Color(vec4(1.0, 0.0, 0.0, 0.5))));
+    assert!(rect.stroke.is_none());
+
+    let circle = &doc.shapes[1];
+    assert!(circle.fill.is_none());
+    let stroke = circle.stroke.as_ref().unwrap();
+    assert_eq!(stroke.paint, SvgPaint::Color(vec4(0.0, 0.0, 1.0, 0.5)));
+    assert_eq!(stroke.style.width, 1.0);
+
+    let path = &doc.shapes[2];
+    assert_eq!(path.fill, Some(SvgPaint::CurrentColor(0.5)));
+    assert_eq!(path.path.points().len(), 7);
+    assert_eq!((path.path.points()[5].x, path.path.points()[5].y), (8.0, 2.0));
+}
+
+#[test]
+fn parses_arcs_and_smooth_curves() {
+    let path = parse_path_data("M10 0 A10 10 0 0 1 0 10 a10,10 0 01-10-10 S-5-10 0-10t10 0");
+    let points = path.points();
+    let last = points[points.len() - 1];
+    assert_eq!((last.x, last.y), (10.0, -10.0));
+    // the arcs are split into quarter turns that stay on the circle
+    let end = points[3];
+    assert!((end.x - 0.0).abs() < 1e-9 && (end.y - 10.0).abs() < 1e-9);
+    let mid = points[4];
+    assert!((mid.x - -5.5228).abs() < 1e-3 && (mid.y - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn resolves_gradients() {
+    let doc = SvgDocument::parse(r##"<svg viewBox="0 0 100 100">
+        <defs>
+            <linearGradient id="base"><stop offset="0" stop-color="#000"/><stop offset="1" style="stop-color:white;stop-opacity:0.5"/></linearGradient>
+            <linearGradient id="diag" href="#base" x2="0" y2="1"/>
+            <radialGradient id="dot" gradientUnits="userSpaceOnUse" cx="50" cy="50" r="25" spreadMethod="reflect" xlink:href="#base"/>
+        </defs>
+        <rect x="10" y="20" width="40" height="60" fill="url(#diag)"/>
+        <circle cx="50" cy="50" r="50" fill="url(#dot) red"/>
+        <rect width="10" height="10" fill="url(#missing) blue"/>
+    </svg>"##).unwrap();
+    let gradient = match &doc.shapes[0].fill {
+        Some(SvgPaint::Gradient(gradient)) => gradient.clone(),
+        paint => panic!("{:?}", paint)
+    };
+    assert_eq!(gradient.kind, SvgGradientKind::Linear);
+    assert_eq!(gradient.stops, vec![(0.0, vec4(0.0, 0.0, 0.0, 1.0)), (1.0, vec4(1.0, 1.0, 1.0, 0.5))]);
+    // vertical across the bounding box
+    assert!((gradient.transform.apply(dvec2(30.0, 20.0)).x - 0.0).abs() < 1e-9);
+    assert!((gradient.transform.apply(dvec2(0.0, 50.0)).x - 0.5).abs() < 1e-9);
+    assert!((gradient.transform.apply(dvec2(99.0, 80.0)).x - 1.0).abs() < 1e-9);
+
+    let gradient = match &doc.shapes[1].fill {
+        Some(SvgPaint::Gradient(gradient)) => gradient.clone(),
+        paint => panic!("{:?}", paint)
+    };
+    assert_eq!(gradient.kind, SvgGradientKind::Radial);
+    assert_eq!(gradient.spread, SvgSpread::Reflect);
+    let u = gradient.transform.apply(dvec2(50.0, 75.0));
+    assert!((u.length() - 1.0).abs() < 1e-9);
+    assert_eq!(gradient.color_at(1.5), vec4(0.5, 0.5, 0.5, 0.75));
+
+    assert_eq!(doc.shapes[2].fill, Some(SvgPaint::Color(vec4(0.0, 0.0, 1.0, 1.0))));
+}
+
+#[test]
+fn tessellates_fills_and_strokes() {
+    let doc = SvgDocument::parse(r##"<svg width="10" height="10">
+        <path d="M1 1H9V9H1Z M3 3V7H7V3Z" fill-rule="evenodd"/>
+        <circle cx="5" cy="5" r="4" fill="none" stroke="blue" stroke-width="1"/>
+    </svg>"##).unwrap();
+    // drawn at twice the size, centered in the wider rect
+    let mesh = doc.tessellate(dvec2(40.0, 20.0), 0.1);
+    assert_eq!(mesh.paints.len(), 2);
+    let area = | paint: usize | mesh.triangles.iter().filter( | t | t.paint == paint).map(triangle_area).sum::<f64>();
+    assert!(mesh.triangles.iter().all( | t | triangle_area(t) > 0.0));
+    // a 16 square with an 8 square hole
+    assert!((area(0) - (256.0 - 64.0)).abs() < 1e-3);
+    // only the outer and inner squares are outlines, the seams between triangles are not
+    assert!((outline_length(&mesh, 0) - (64.0 + 32.0)).abs() < 1e-3);
+    let min_x = mesh.triangles.iter().filter( | t | t.paint == 0).flat_map( | t | t.triangle.points).map( | p | p.x).fold(f64::INFINITY, f64::min);
+    assert!((min_x - 12.0).abs() < 1e-6);
+
+    // a ring 2 pixels wide around a circle of radius 8
+    let expected = std::f64::consts::PI * (9.0 * 9.0 - 7.0 * 7.0);
+    assert!((area(1) - expected).abs() < expected * 0.02);
+    let expected = std::f64::consts::TAU * (9.0 + 7.0);
+    assert!((outline_length(&mesh, 1) - expected).abs() < expected * 0.02);
+}
@@ -0,0 +1,161 @@
+use makepad_draw::*;
+
+// runs the layout code on a Cx2d without a window, rects are read back from rect areas
+fn layout(f: impl FnOnce(&mut Cx2d)) {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_draw::live_design(&mut cx);
+    cx.live_expand();
+    let draw_event = DrawEvent::default();
+    let cx = &mut Cx2d::new(&mut cx, &draw_event);
+    let draw_list = DrawList2d::new(cx);
+    let redraw_id = cx.redraw_id;
+    cx.draw_lists[draw_list.draw_list_id()].redraw_id = redraw_id;
+    cx.draw_list_stack.push(draw_list.draw_list_id());
+    f(cx);
+}
+
+fn walk_rect(cx: &mut Cx2d, walk: Walk) -> Area {
+    let mut area = Area::Empty;
+    cx.walk_turtle_with_area(&mut area, walk);
+    area
+}
+
+fn rect(x: f64, y: f64, w: f64, h: f64) -> Rect {
+    Rect {pos: dvec2(x, y), size: dvec2(w, h)}
+}
+
+#[test]
+fn fill_with_max_width() {
+    layout( | cx | {
+        cx.begin_turtle(Walk::fixed(1000.0, 100.0), Layout::flow_down());
+        let a = walk_rect(cx, Walk::fill().with_max_width(600.0));
+        let b = walk_rect(cx, Walk::fixed(20.0, 10.0).with_min_width(50.0).with_max_height(5.0));
+        cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(0.0, 0.0, 600.0, 100.0));
+        assert_eq!(b.get_rect(cx), rect(0.0, 100.0, 50.0, 5.0));
+    });
+}
+
+#[test]
+fn aspect_ratio() {
+    layout( | cx | {
+        cx.begin_turtle(Walk::fixed(320.0, 1000.0), Layout::flow_down());
+        // the height follows the width
+        let a = walk_rect(cx, Walk::size(Size::Fill, Size::Fit).with_aspect_ratio(16.0 / 9.0));
+        // the width follows the fixed height
+        let b = walk_rect(cx, Walk::size(Size::Fill, Size::Fixed(90.0)).with_aspect_ratio(16.0 / 9.0));
+        // both fixed, the ratio is ignored
+        let c = walk_rect(cx, Walk::fixed(10.0, 10.0).with_aspect_ratio(2.0));
+        cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(0.0, 0.0, 320.0, 180.0));
+        assert_eq!(b.get_rect(cx), rect(0.0, 180.0, 160.0, 90.0));
+        assert_eq!(c.get_rect(cx), rect(0.0, 270.0, 10.0, 10.0));
+
+        // fill on both axes keeps the largest size with the ratio that fits
+        cx.begin_turtle(Walk::fixed(400.0, 100.0), Layout::flow_down());
+        let d = walk_rect(cx, Walk::fill().with_aspect_ratio(2.0));
+        cx.end_turtle();
+        assert_eq!(d.get_rect(cx), rect(0.0, 0.0, 200.0, 100.0));
+    });
+}
+
+#[test]
+fn deferred_fill_limits() {
+    layout( | cx | {
+        cx.begin_turtle(Walk::fixed(300.0, 100.0), Layout::flow_right());
+        let mut first = cx.defer_walk(Walk::fill().with_max_width(50.0)).unwrap();
+        let mut second = cx.defer_walk(Walk::fill()).unwrap();
+        let fixed = walk_rect(cx, Walk::fixed(100.0, 10.0));
+        let walk = first.resolve(cx);
+        let a = walk_rect(cx, walk);
+        let walk = second.resolve(cx);
+        let b = walk_rect(cx, walk);
+        cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(0.0, 0.0, 50.0, 100.0));
+        assert_eq!(b.get_rect(cx), rect(50.0, 0.0, 150.0, 100.0));
+        assert_eq!(fixed.get_rect(cx), rect(200.0, 0.0, 100.0, 10.0));
+
+        cx.begin_turtle(Walk::fixed(100.0, 300.0), Layout::flow_down());
+        let mut first = cx.defer_walk(Walk::fill().with_min_height(200.0)).unwrap();
+        let mut second = cx.defer_walk(Walk::fill()).unwrap();
+        let walk = first.resolve(cx);
+        let a = walk_rect(cx, walk);
+        let walk = second.resolve(cx);
+        let b = walk_rect(cx, walk);
+        cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(0.0, 0.0, 100.0, 200.0));
+        assert_eq!(b.get_rect(cx), rect(0.0, 200.0, 100.0, 100.0));
+    });
+}
+
+#[test]
+fn fit_measurement() {
+    layout( | cx | {
+        cx.begin_turtle(Walk::fixed(1000.0, 1000.0), Layout::flow_down());
+
+        cx.begin_turtle(Walk::fit().with_min_width(40.0).with_max_height(5.0), Layout::default());
+        walk_rect(cx, Walk::fixed(10.0, 10.0));
+        let a = cx.end_turtle();
+
+        cx.begin_turtle(Walk::fit().with_aspect_ratio(1.0), Layout::default());
+        walk_rect(cx, Walk::fixed(40.0, 10.0));
+        let b = cx.end_turtle();
+
+        cx.begin_turtle(Walk::fit().with_max_width(30.0), Layout::default());
+        walk_rect(cx, Walk::fixed(40.0, 10.0));
+        let c = cx.end_turtle();
+
+        cx.end_turtle();
+        assert_eq!(a, rect(0.0, 0.0, 40.0, 5.0));
+        assert_eq!(b, rect(0.0, 5.0, 40.0, 40.0));
+        assert_eq!(c, rect(0.0, 45.0, 30.0, 10.0));
+    });
+}
+
+#[test]
+fn wrap_rows() {
+    layout( | cx | {
+        let layout = Layout::flow_right_wrap().with_spacing(10.0).with_row_spacing(5.0).with_align_x(1.0).with_align_y(0.5);
+        cx.begin_turtle(Walk::fixed(100.0, 200.0), layout);
+        let a = walk_rect(cx, Walk::fixed(40.0, 10.0));
+        let b = walk_rect(cx, Walk::fixed(40.0, 20.0));
+        let c = walk_rect(cx, Walk::fixed(40.0, 10.0));
+        let d = walk_rect(cx, Walk::fixed(30.0, 10.0));
+        cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(10.0, 5.0, 40.0, 10.0));
+        assert_eq!(b.get_rect(cx), rect(60.0, 0.0, 40.0, 20.0));
+        assert_eq!(c.get_rect(cx), rect(20.0, 25.0, 40.0, 10.0));
+        assert_eq!(d.get_rect(cx), rect(70.0, 25.0, 30.0, 10.0));
+
+        // rows without height start at the same y and are still aligned on their own
+        cx.begin_turtle(Walk::fixed(100.0, 200.0), Layout::flow_right_wrap().with_align_x(1.0));
+        let a = walk_rect(cx, Walk::fixed(60.0, 0.0));
+        let b = walk_rect(cx, Walk::fixed(80.0, 0.0));
+        cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(40.0, 0.0, 60.0, 0.0));
+        assert_eq!(b.get_rect(cx), rect(20.0, 0.0, 80.0, 0.0));
+    });
+}
+
+#[test]
+fn grid_spans() {
+    layout( | cx | {
+        let layout = Layout::flow_grid(&[GridTrack::Fixed(50.0), GridTrack::Fr(1.0)])
+            .with_grid_rows(&[GridTrack::Fit, GridTrack::Fit, GridTrack::Fixed(20.0), GridTrack::Fixed(20.0)])
+            .with_spacing(10.0)
+            .with_row_spacing(5.0);
+        cx.begin_turtle(Walk::size(Size::Fixed(200.0), Size::Fit), layout);
+        let a = walk_rect(cx, Walk::size(Size::Fill, Size::Fixed(20.0)).with_span(2, 1));
+        let b = walk_rect(cx, Walk::fixed(30.0, 10.0));
+        let c = walk_rect(cx, Walk::fixed(20.0, 30.0));
+        let d = walk_rect(cx, Walk::size(Size::Fixed(10.0), Size::Fill).with_span(1, 2));
+        let e = walk_rect(cx, Walk::fixed(10.0, 10.0));
+        let grid = cx.end_turtle();
+        assert_eq!(a.get_rect(cx), rect(0.0, 0.0, 200.0, 20.0));
+        assert_eq!(b.get_rect(cx), rect(0.0, 25.0, 30.0, 10.0));
+        assert_eq!(c.get_rect(cx), rect(60.0, 25.0, 20.0, 30.0));
+        assert_eq!(d.get_rect(cx), rect(0.0, 60.0, 10.0, 45.0));
+        assert_eq!(e.get_rect(cx), rect(60.0, 60.0, 10.0, 10.0));
+        assert_eq!(grid, rect(0.0, 0.0, 200.0, 105.0));
+    });
+}
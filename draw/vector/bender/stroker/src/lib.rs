@@ -31,6 +31,7 @@ impl Stroker {
         }: Options,
         output_mesh: &mut Mesh,
     ) {
+        self.offset_polygons.clear();
         for polyline in polylines {
             self.offset_polygons.push(bender_offsetter::offset_polyline(
                 polyline,
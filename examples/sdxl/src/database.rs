@@ -1,7 +1,7 @@
 use crate::{makepad_live_id::*};
 use makepad_micro_serde::*;
 use makepad_widgets::*;
-use makepad_widgets::image_cache::{ImageBuffer};
+use makepad_widgets::image_cache::ImageBufferDecode;
 use std::fs;
 use std::time::Instant;
 use std::time::SystemTime;
@@ -95,6 +95,16 @@ impl BinaryMessageHeader{
     }
 }
 
+// clients have to mask the messages they send to a server
+pub fn mask_binary_message(data:&[u8], mask:[u8;4])->Vec<u8>{
+    let header = BinaryMessageHeader::from_len(data.len());
+    let mut message = header.as_slice().to_vec();
+    message[1] |= 128;
+    message.extend_from_slice(&mask);
+    message.extend(data.iter().enumerate().map(|(i, byte)| byte ^ mask[i & 3]));
+    message
+}
+
 impl WebSocket {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,231 @@
+use std::fmt;
+use std::ops::Index;
+use std::str::Chars;
+use crate::serde_json::*;
+
+// documents nested deeper than this are rejected rather than overflowing the stack
+const MAX_DEPTH: usize = 128;
+
+/// A JSON value of any shape, for documents with many optional and unknown fields that are
+/// picked apart rather than deserialized into fixed structs. Missing fields and elements
+/// index as `Null`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+static NULL: JsonValue = JsonValue::Null;
+
+impl JsonValue {
+    /// Parses a whole document, unlike `deserialize_json` anything after the value is an error
+    pub fn parse(input: &str) -> Result<Self, DeJsonErr> {
+        let mut state = DeJsonState::default();
+        let mut chars = input.chars();
+        state.next(&mut chars);
+        state.next_tok(&mut chars) ?;
+        let value = Self::de_json_depth(&mut state, &mut chars, 0) ?;
+        if state.tok != DeJsonTok::Eof {
+            return Err(state.err_token("end of input"))
+        }
+        Ok(value)
+    }
+
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, JsonValue)>) -> Self {
+        Self::Object(fields.into_iter().map( | (key, value) | (key.to_string(), value)).collect())
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+
+    /// The field with the given key, if this is an object that has it
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            Self::Object(fields) => fields.iter().find( | (other, _) | other == key).map( | (_, value) | value),
+            _ => None
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        if let Self::Bool(value) = self {Some(*value)} else {None}
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        if let Self::Number(value) = self {Some(*value)} else {None}
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_f64().filter( | value | value.fract() == 0.0).map( | value | value as i64)
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_f64().filter( | value | value.fract() == 0.0 && *value >= 0.0).map( | value | value as u64)
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_u64().map( | value | value as usize)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        if let Self::String(value) = self {Some(value)} else {None}
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        if let Self::Array(values) = self {Some(values)} else {None}
+    }
+
+    /// The elements of an array, none for anything else
+    pub fn items(&self) -> &[JsonValue] {
+        self.as_array().unwrap_or(&[])
+    }
+
+    fn de_json_depth(s: &mut DeJsonState, i: &mut Chars, depth: usize) -> Result<Self, DeJsonErr> {
+        if depth > MAX_DEPTH {
+            return Err(s.err_parse("json nested too deep"))
+        }
+        let value = match s.tok {
+            DeJsonTok::Null => JsonValue::Null,
+            DeJsonTok::Bool(value) => JsonValue::Bool(value),
+            DeJsonTok::U64(_) | DeJsonTok::I64(_) | DeJsonTok::F64(_) => JsonValue::Number(s.as_f64() ?),
+            DeJsonTok::Str => JsonValue::String(s.as_string() ?),
+            DeJsonTok::BlockOpen => {
+                s.block_open(i) ?;
+                let mut values = Vec::new();
+                while s.tok != DeJsonTok::BlockClose {
+                    values.push(Self::de_json_depth(s, i, depth + 1) ?);
+                    s.eat_comma_block(i) ?;
+                }
+                s.block_close(i) ?;
+                return Ok(JsonValue::Array(values))
+            }
+            DeJsonTok::CurlyOpen => {
+                s.curly_open(i) ?;
+                let mut fields = Vec::new();
+                while s.tok != DeJsonTok::CurlyClose {
+                    let key = s.as_string() ?;
+                    s.next_colon(i) ?;
+                    fields.push((key, Self::de_json_depth(s, i, depth + 1) ?));
+                    s.eat_comma_curly(i) ?;
+                }
+                s.curly_close(i) ?;
+                return Ok(JsonValue::Object(fields))
+            }
+            _ => return Err(s.err_token("json value"))
+        };
+        s.next_tok(i) ?;
+        Ok(value)
+    }
+}
+
+impl DeJson for JsonValue {
+    fn de_json(s: &mut DeJsonState, i: &mut Chars) -> Result<Self, DeJsonErr> {
+        Self::de_json_depth(s, i, 0)
+    }
+}
+
+impl SerJson for JsonValue {
+    fn ser_json(&self, d: usize, s: &mut SerJsonState) {
+        match self {
+            Self::Null => s.out.push_str("null"),
+            Self::Bool(value) => value.ser_json(d, s),
+            // json has no infinities or nans
+            Self::Number(value) if value.is_finite() => s.out.push_str(&value.to_string()),
+            Self::Number(_) => s.out.push_str("null"),
+            Self::String(value) => value.ser_json(d, s),
+            Self::Array(values) => values.ser_json(d, s),
+            Self::Object(fields) => {
+                s.st_pre();
+                for (index, (key, value)) in fields.iter().enumerate() {
+                    if index > 0 {
+                        s.conl();
+                    }
+                    key.ser_json(d + 1, s);
+                    s.out.push(':');
+                    value.ser_json(d + 1, s);
+                }
+                s.st_post(d);
+            }
+        }
+    }
+}
+
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.serialize_json())
+    }
+}
+
+impl Index<&str> for JsonValue {
+    type Output = JsonValue;
+
+    fn index(&self, key: &str) -> &Self::Output {
+        self.get(key).unwrap_or(&NULL)
+    }
+}
+
+impl Index<usize> for JsonValue {
+    type Output = JsonValue;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        self.items().get(index).unwrap_or(&NULL)
+    }
+}
+
+impl From<bool> for JsonValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<f64> for JsonValue {
+    fn from(value: f64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl From<u32> for JsonValue {
+    fn from(value: u32) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<u64> for JsonValue {
+    fn from(value: u64) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<i64> for JsonValue {
+    fn from(value: i64) -> Self {
+        Self::Number(value as f64)
+    }
+}
+
+impl From<&str> for JsonValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for JsonValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<Vec<JsonValue>> for JsonValue {
+    fn from(values: Vec<JsonValue>) -> Self {
+        Self::Array(values)
+    }
+}
+
+impl<T: Into<JsonValue>> From<Option<T>> for JsonValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
@@ -117,6 +117,14 @@ impl std::fmt::Debug for DeJsonErr {
     }
 }
 
+impl std::fmt::Display for DeJsonErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+impl std::error::Error for DeJsonErr {}
+
 impl DeJsonState {
     pub fn next(&mut self, i: &mut Chars) {
         if let Some(c) = i.next() {
@@ -323,6 +331,18 @@ impl DeJsonState {
         Err(self.err_token("string"))
     }
     
+    // the four hex digits after a \u, with the u as the current character
+    fn hex_escape(&mut self, i: &mut Chars) -> Result<u32, DeJsonErr> {
+        let mut code = 0u32;
+        for _ in 0..4 {
+            self.next(i);
+            let digit = self.cur.to_digit(16).ok_or_else( || self.err_parse("string escape"))?;
+            code = code * 16 + digit;
+        }
+        self.next(i);
+        Ok(code)
+    }
+    
     pub fn next_tok(&mut self, i: &mut Chars) -> Result<(), DeJsonErr> {
         while self.cur == '\n' || self.cur == '\r' || self.cur == '\t' || self.cur == ' ' {
             self.next(i);
@@ -376,13 +396,30 @@ impl DeJsonState {
                     self.numbuf.push(self.cur);
                     self.next(i);
                 }
+                let mut is_float = false;
                 if self.cur == '.' {
+                    is_float = true;
+                    self.numbuf.push(self.cur);
+                    self.next(i);
+                    while self.cur >= '0' && self.cur <= '9' {
+                        self.numbuf.push(self.cur);
+                        self.next(i);
+                    }
+                }
+                if self.cur == 'e' || self.cur == 'E' {
+                    is_float = true;
                     self.numbuf.push(self.cur);
                     self.next(i);
+                    if self.cur == '-' || self.cur == '+' {
+                        self.numbuf.push(self.cur);
+                        self.next(i);
+                    }
                     while self.cur >= '0' && self.cur <= '9' {
                         self.numbuf.push(self.cur);
                         self.next(i);
                     }
+                }
+                if is_float {
                     if let Ok(num) = self.numbuf.parse() {
                         self.tok = DeJsonTok::F64(num);
                         Ok(())
@@ -436,13 +473,37 @@ impl DeJsonState {
             '"' => {
                 self.strbuf.clear();
                 self.next(i);
+                // characters outside the basic plane are escaped as a pair of surrogates
+                let mut high_surrogate: Option<u32> = None;
                 while self.cur != '"' {
                     if self.cur == '\\' {
                         self.next(i);
+                        if self.cur == 'u' {
+                            let code = self.hex_escape(i)?;
+                            if let Some(high) = high_surrogate.take() {
+                                if (0xDC00..0xE000).contains(&code) {
+                                    self.strbuf.push(char::from_u32(0x10000 + ((high - 0xD800) << 10) + (code - 0xDC00)).unwrap());
+                                    continue;
+                                }
+                                self.strbuf.push(char::REPLACEMENT_CHARACTER);
+                            }
+                            if (0xD800..0xDC00).contains(&code) {
+                                high_surrogate = Some(code);
+                            }
+                            else {
+                                self.strbuf.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                            }
+                            continue;
+                        }
+                        if high_surrogate.take().is_some() {
+                            self.strbuf.push(char::REPLACEMENT_CHARACTER);
+                        }
                         match self.cur{
                             'n'=>self.strbuf.push('\n'),
                             'r'=>self.strbuf.push('\r'),
                             't'=>self.strbuf.push('\t'),
+                            'b'=>self.strbuf.push('\u{8}'),
+                            'f'=>self.strbuf.push('\u{c}'),
                             '0'=>self.strbuf.push('\0'),
                             '\0'=>{
                                 return Err(self.err_parse("string"));
@@ -455,10 +516,16 @@ impl DeJsonState {
                         if self.cur == '\0' {
                             return Err(self.err_parse("string"));
                         }
+                        if high_surrogate.take().is_some() {
+                            self.strbuf.push(char::REPLACEMENT_CHARACTER);
+                        }
                         self.strbuf.push(self.cur);
                         self.next(i);
                     }
                 }
+                if high_surrogate.is_some() {
+                    self.strbuf.push(char::REPLACEMENT_CHARACTER);
+                }
                 self.next(i);
                 self.tok = DeJsonTok::Str;
                 Ok(())
@@ -590,9 +657,9 @@ impl SerJson for String {
                 '\n'=>{s.out.push('\\');s.out.push('n');},
                 '\r'=>{s.out.push('\\');s.out.push('r');},
                 '\t'=>{s.out.push('\\');s.out.push('t');},
-                '\0'=>{s.out.push('\\');s.out.push('0');},
                 '\\'=>{s.out.push('\\');s.out.push('\\');},
                 '"'=>{s.out.push('\\');s.out.push('"');},
+                c if (c as u32) < 0x20 => s.out.push_str(&format!("\\u{:04x}", c as u32)),
                 _=>s.out.push(c)
             }
         }
@@ -1,7 +1,7 @@
 use std::{
     path::{Path, PathBuf},
     fs::File,
-    io::{Write,Read},
+    io::Write,
     fs,
     io::prelude::*,
     io::BufReader,
@@ -53,17 +53,15 @@ pub fn shell_env_cap(env: &[(&str, &str)], cwd: &Path, cmd: &str, args: &[&str])
     for (key, value) in env {
         cmd_build.env(key, value);
     }
-    let mut child = cmd_build.spawn().map_err( | e | format!("Error starting {} in dir {:?} - {:?}", cmd, cwd, e)) ?;
+    let child = cmd_build.spawn().map_err( | e | format!("Error starting {} in dir {:?} - {:?}", cmd, cwd, e)) ?;
     
-    let r = child.wait().map_err( | e | format!("Process {} in dir {:?} returned error {:?} ", cmd, cwd, e)) ?;
-    if !r.success() {
-        let mut out = String::new();
-        let _ = child.stderr.unwrap().read_to_string(&mut out);
+    // the output is read while the process runs, as it blocks once a pipe is full
+    let r = child.wait_with_output().map_err( | e | format!("Process {} in dir {:?} returned error {:?} ", cmd, cwd, e)) ?;
+    if !r.status.success() {
+        let out = String::from_utf8_lossy(&r.stderr);
         return Err(format!("Process {} in dir {:?} returned error exit code {} ", cmd, cwd, out));
     }
-    let mut out = String::new();
-    let _ = child.stdout.unwrap().read_to_string(&mut out);
-    Ok(out)
+    Ok(String::from_utf8_lossy(&r.stdout).into_owned())
 }
 
 pub fn shell_env_filter(start:&str, minus:Vec<String>, env: &[(&str, &str)], cwd: &Path, cmd: &str,  args: &[&str]) -> Result<(), String> {
@@ -169,7 +169,13 @@ fn derive_live_impl_inner(parser: &mut TokenParser, tb: &mut TokenBuilder) -> Re
                     if field.name == "abs_pos" ||
                       field.name == "margin" ||
                       field.name == "width" ||
-                      field.name == "height" {
+                      field.name == "height" ||
+                      field.name == "span" ||
+                      field.name == "min_width" ||
+                      field.name == "max_width" ||
+                      field.name == "min_height" ||
+                      field.name == "max_height" ||
+                      field.name == "aspect_ratio" {
                           return error_result(&format!("Name collision between walk splat and {}", field.name));
                       }
                 }
@@ -177,6 +183,12 @@ fn derive_live_impl_inner(parser: &mut TokenParser, tb: &mut TokenBuilder) -> Re
                 tb.add("        live_id!(margin)=>self.").ident(&field.name).add(".margin.apply(cx, apply_from, index, nodes),");
                 tb.add("        live_id!(width)=>self.").ident(&field.name).add(".width.apply(cx, apply_from, index, nodes),");
                 tb.add("        live_id!(height)=>self.").ident(&field.name).add(".height.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(span)=>self.").ident(&field.name).add(".span.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(min_width)=>self.").ident(&field.name).add(".min_width.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(max_width)=>self.").ident(&field.name).add(".max_width.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(min_height)=>self.").ident(&field.name).add(".min_height.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(max_height)=>self.").ident(&field.name).add(".max_height.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(aspect_ratio)=>self.").ident(&field.name).add(".aspect_ratio.apply(cx, apply_from, index, nodes),");
             }
             else if field.attrs[0].name == "layout" {
                 for field in &fields {
@@ -186,7 +198,9 @@ fn derive_live_impl_inner(parser: &mut TokenParser, tb: &mut TokenBuilder) -> Re
                       field.name == "padding" ||
                       field.name == "align" ||
                       field.name == "flow" ||
-                      field.name == "spacing"{
+                      field.name == "spacing" ||
+                      field.name == "row_spacing" ||
+                      field.name == "grid"{
                           return error_result(&format!("Name collision between layout splat and {}", field.name));
                       }
                 }
@@ -197,6 +211,8 @@ fn derive_live_impl_inner(parser: &mut TokenParser, tb: &mut TokenBuilder) -> Re
                 tb.add("        live_id!(align)=>self.").ident(&field.name).add(".align.apply(cx, apply_from, index, nodes),");
                 tb.add("        live_id!(flow)=>self.").ident(&field.name).add(".flow.apply(cx, apply_from, index, nodes),");
                 tb.add("        live_id!(spacing)=>self.").ident(&field.name).add(".spacing.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(row_spacing)=>self.").ident(&field.name).add(".row_spacing.apply(cx, apply_from, index, nodes),");
+                tb.add("        live_id!(grid)=>self.").ident(&field.name).add(".grid.apply(cx, apply_from, index, nodes),");
             }
         }
         // Unknown value handling
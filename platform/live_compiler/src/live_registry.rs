@@ -29,6 +29,7 @@ pub struct LiveFile {
     pub cargo_manifest_path: String,
     pub (crate) source: String,
     pub (crate) deps: BTreeSet<LiveModuleId>,
+    pub (crate) overlay: Option<LiveModuleId>,
     
     pub generation: LiveFileGeneration,
     pub original: LiveOriginal,
@@ -570,6 +571,63 @@ impl LiveRegistry {
         }
     }
 
+    // overlays the top level definitions of another module over a module, see
+    // overlay_original. Used for switching themes at runtime
+    pub fn set_module_overlay(&mut self, target: LiveModuleId, overlay: Option<LiveModuleId>) -> bool {
+        let file_id = if let Some(file_id) = self.module_id_to_file_id.get(&target) {
+            *file_id
+        }
+        else {
+            return false
+        };
+        if let Some(overlay) = overlay {
+            if !self.module_id_to_file_id.contains_key(&overlay) || overlay == target {
+                return false
+            }
+        }
+        let live_file = self.file_id_to_file_mut(file_id);
+        if live_file.overlay == overlay {
+            return false
+        }
+        if let Some(old) = live_file.overlay.take() {
+            live_file.deps.remove(&old);
+        }
+        if let Some(overlay) = overlay {
+            live_file.deps.insert(overlay);
+        }
+        live_file.overlay = overlay;
+        live_file.reexpand = true;
+        live_file.generation.next_gen();
+        true
+    }
+    
+    pub fn module_overlay(&self, target: LiveModuleId) -> Option<LiveModuleId> {
+        let file_id = self.module_id_to_file_id.get(&target)?;
+        self.live_files[file_id.to_index()].overlay
+    }
+    
+    // appends the top level definitions of the overlay, without its imports, to the end
+    // of the original. The expander overwrites a definition with a later one of the same
+    // name, so the overlay wins while everything still refers to the name
+    fn overlay_original(&self, file_id: LiveFileId, overlay_id: LiveFileId) -> LiveOriginal {
+        let original = &self.live_files[file_id.to_index()].original;
+        let overlay = &self.live_files[overlay_id.to_index()].original;
+        let mut nodes = original.nodes.clone();
+        let close = nodes.pop().unwrap();
+        for node in &overlay.nodes[1..overlay.nodes.len() - 1] {
+            if let LiveValue::Import(_) = node.value {
+                continue;
+            }
+            nodes.push(node.clone());
+        }
+        nodes.push(close);
+        LiveOriginal {
+            nodes,
+            edit_info: original.edit_info.clone(),
+            tokens: original.tokens.clone(),
+        }
+    }
+    
     pub fn register_live_file(
         &mut self,
         file_name: &str,
@@ -648,6 +706,7 @@ impl LiveRegistry {
             file_name: file_name.to_string(),
             start_pos,
             deps,
+            overlay: None,
             source,
             generation: LiveFileGeneration::default(),
             live_type_infos,
@@ -744,7 +803,10 @@ impl LiveRegistry {
             
             out_doc.nodes.clear();
             
-            let in_doc = &self.live_files[file_id.to_index()].original;
+            let overlaid = self.live_files[file_id.to_index()].overlay
+                .and_then( | overlay | self.module_id_to_file_id.get(&overlay))
+                .map( | overlay_id | self.overlay_original(*file_id, *overlay_id));
+            let in_doc = overlaid.as_ref().unwrap_or(&self.live_files[file_id.to_index()].original);
             
             let mut live_document_expander = LiveExpander {
                 live_registry: self,
@@ -38,6 +38,9 @@ pub use {
             DrawShaderDef,
             DrawShaderFieldKind,
             DrawShaderFlags,
+            DrawShaderSampler,
+            SamplerFilter,
+            SamplerAddress,
             DrawShaderConstTable,
             ValuePtr,
         },
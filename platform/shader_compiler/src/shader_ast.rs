@@ -72,6 +72,28 @@ pub struct DrawShaderFlags {
     pub debug: bool,
     pub draw_call_nocompare: bool,
     pub draw_call_always: bool,
+    pub sampler: DrawShaderSampler,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SamplerFilter {
+    Nearest,
+    Linear,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SamplerAddress {
+    Clamp,
+    Repeat,
+    Mirror,
+}
+
+/// How the textures of a shader are sampled, declared in the shader as `sampler_filter: Nearest`
+/// and `sampler_address: Repeat`. What is left out follows the defaults of each texture.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct DrawShaderSampler {
+    pub filter: Option<SamplerFilter>,
+    pub address: Option<SamplerAddress>,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -532,6 +532,27 @@ impl ShaderRegistry {
                         node_iter = doc.nodes.next_child(node_index);
                         continue;
                     }
+                    if prop.id == live_id!(sampler_filter) || prop.id == live_id!(sampler_address) {
+                        let value = match prop.value {
+                            LiveValue::BareEnum(value) | LiveValue::Id(value) => value,
+                            _ => LiveId(0)
+                        };
+                        let sampler = &mut draw_shader_def.flags.sampler;
+                        match (prop.id, value) {
+                            (live_id!(sampler_filter), live_id!(Nearest)) => sampler.filter = Some(SamplerFilter::Nearest),
+                            (live_id!(sampler_filter), live_id!(Linear)) => sampler.filter = Some(SamplerFilter::Linear),
+                            (live_id!(sampler_address), live_id!(Clamp)) => sampler.address = Some(SamplerAddress::Clamp),
+                            (live_id!(sampler_address), live_id!(Repeat)) => sampler.address = Some(SamplerAddress::Repeat),
+                            (live_id!(sampler_address), live_id!(Mirror)) => sampler.address = Some(SamplerAddress::Mirror),
+                            _ => return Err(LiveError {
+                                origin: live_error_origin!(),
+                                span: prop.origin.token_id().unwrap().into(),
+                                message: format!("Unknown {} value, expected Nearest or Linear for sampler_filter and Clamp, Repeat or Mirror for sampler_address", prop.id)
+                            })
+                        }
+                        node_iter = doc.nodes.next_child(node_index);
+                        continue;
+                    }
                     match prop.value {
                         LiveValue::Bool(_) |
                         LiveValue::Id(_)  |
@@ -28,6 +28,7 @@ use {
             Trigger,
             CxKeyboard,
             NextFrame,
+            ImageCapturedEvent,
         },
         cx_api::CxOsOp,
         area::Area,
@@ -84,6 +85,8 @@ pub struct Cx {
     
     pub (crate) new_next_frames: HashSet<NextFrame>,
     
+    pub (crate) captured_images: Vec<ImageCapturedEvent>,
+    
     pub (crate) dependencies: HashMap<String, CxDependency>,
     
     pub (crate) triggers: HashMap<Area, Vec<Trigger >>,
@@ -92,6 +95,7 @@ pub struct Cx {
 
     pub (crate) live_file_change_receiver: std::sync::mpsc::Receiver<Vec<LiveFileChange>>,
     pub (crate) live_file_change_sender: std::sync::mpsc::Sender<Vec<LiveFileChange >>,
+    pub (crate) live_edit_pending: bool,
 
     pub shader_registry: ShaderRegistry,
     
@@ -236,6 +240,8 @@ impl Cx {
             
             new_next_frames: Default::default(),
             
+            captured_images: Default::default(),
+            
             dependencies: Default::default(),
             
             triggers: Default::default(),
@@ -244,6 +250,7 @@ impl Cx {
             
             live_file_change_receiver: recv,
             live_file_change_sender: send,
+            live_edit_pending: false,
             
             shader_registry: ShaderRegistry::new(),
             
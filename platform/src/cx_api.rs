@@ -113,6 +113,27 @@ impl Cx {
     pub fn cpu_cores(&self) -> usize {self.cpu_cores}
     pub fn gpu_info(&self) -> &GpuInfo {&self.gpu_info}
     
+    /// Returns whether the OS appearance is set to dark, or `None` when the platform
+    /// doesn't expose it. On Linux this is the answer of the last
+    /// `refresh_os_prefers_dark_mode`, `None` until the first one came in
+    pub fn os_prefers_dark_mode(&self) -> Option<bool> {
+        #[cfg(target_os = "macos")]
+        return Some(crate::os::apple::apple_util::macos_prefers_dark_mode());
+        
+        #[cfg(target_os = "linux")]
+        return crate::os::linux::linux_theme::linux_prefers_dark_mode();
+        
+        #[allow(unreachable_code)]
+        None
+    }
+    
+    /// Asks the OS for its appearance again. On Linux this runs off the UI thread and
+    /// `Event::Signal` follows when `os_prefers_dark_mode` changed
+    pub fn refresh_os_prefers_dark_mode(&mut self) {
+        #[cfg(target_os = "linux")]
+        crate::os::linux::linux_theme::linux_refresh_prefers_dark_mode();
+    }
+    
     pub fn update_macos_menu(&mut self, menu: MacosMenu) {
         self.platform_ops.push(CxOsOp::UpdateMacosMenu(menu));
     }
@@ -148,6 +169,13 @@ impl Cx {
         self.platform_ops.push(CxOsOp::ShowClipboardActions(selected));
     }
 
+    /// Whether a drag can be released outside of every window of the app, which is then
+    /// reported as an `Event::DragEnd` without an `Event::Drop` before it. Only the macOS
+    /// and Windows backends drag across windows, the others don't drag at all.
+    pub fn os_drags_leave_windows(&self) -> bool {
+        cfg!(any(target_os = "macos", target_os = "windows")) && !self.in_makepad_studio
+    }
+    
     pub fn start_dragging(&mut self, items: Vec<DragItem>) {
         self.platform_ops.iter().for_each( | p | {
             if let CxOsOp::StartDragging{..} = p {
@@ -242,6 +270,28 @@ impl Cx {
         1.0
     }
     
+    pub fn get_window_id_of(&self, area: &Area) -> Option<WindowId> {
+        let draw_list_id = area.draw_list_id()?;
+        let pass_id = self.draw_lists[draw_list_id].pass_id?;
+        self.get_pass_window_id(pass_id)
+    }
+    
+    pub fn get_pass_window_id(&self, pass_id: PassId) -> Option<WindowId> {
+        let mut pass_id_walk = pass_id;
+        for _ in 0..25 {
+            match self.passes[pass_id_walk].parent {
+                CxPassParent::Window(window_id) => {
+                    return Some(window_id)
+                },
+                CxPassParent::Pass(next_pass_id) => {
+                    pass_id_walk = next_pass_id;
+                },
+                _ => {break;}
+            }
+        }
+        None
+    }
+    
     pub fn redraw_pass_and_parent_passes(&mut self, pass_id: PassId) {
         let mut walk_pass_id = pass_id;
         loop {
@@ -20,7 +20,7 @@ use {
         cx::Cx,
         texture::{Texture, TextureId},
         makepad_error_log::*,
-        geometry::GeometryId,
+        geometry::{Geometry, GeometryId},
         area::Area,
         geometry::{GeometryFields},
         live_traits::*,
@@ -134,6 +134,11 @@ impl DrawVars {
     pub fn empty_texture(&mut self, slot: usize) {
         self.texture_slots[slot] = None;
     }
+    
+    /// Draws with `geometry` instead of the geometry of the shader, it needs the same vertex layout.
+    pub fn set_geometry(&mut self, geometry: &Geometry) {
+        self.geometry_id = Some(geometry.geometry_id());
+    }
 
     pub fn redraw(&self, cx: &mut Cx) {
         self.area.redraw(cx);
@@ -637,6 +642,8 @@ impl DrawVars {
             live_id!(debug) => false,
             live_id!(debug_id) => false,
             live_id!(draw_call_group) => false,
+            live_id!(sampler_filter) => false,
+            live_id!(sampler_address) => false,
             _ => true
         };
         
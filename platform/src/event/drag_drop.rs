@@ -11,12 +11,14 @@ use {
         },
         cx::Cx,
         area::Area,
+        window::WindowId,
     },
 };
 
 
 #[derive(Clone, Debug)]
 pub struct DragEvent {
+    pub window_id: WindowId,
     pub modifiers: KeyModifiers,
     pub handled: Cell<bool>,
     pub abs: DVec2,
@@ -26,14 +28,23 @@ pub struct DragEvent {
 
 #[derive(Clone, Debug)]
 pub struct DropEvent {
+    pub window_id: WindowId,
     pub modifiers: KeyModifiers,
     pub handled: Cell<bool>,
     pub abs: DVec2,
     pub items: Rc<Vec<DragItem >>,
 }
 
+/// The end of a drag started with `Cx::start_dragging`, whether it was dropped or not.
+/// `screen_pos` is where it was released, in the screen coordinates of window positions.
+#[derive(Clone, Debug)]
+pub struct DragEndEvent {
+    pub screen_pos: DVec2,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct DragHitEvent {
+    pub window_id: WindowId,
     pub modifiers: KeyModifiers,
     pub abs: DVec2,
     pub rect: Rect,
@@ -44,6 +55,7 @@ pub struct DragHitEvent {
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct DropHitEvent {
+    pub window_id: WindowId,
     pub modifiers: KeyModifiers,
     pub abs: DVec2,
     pub rect: Rect,
@@ -111,12 +123,15 @@ impl Event {
         match self {
             Event::Drag(event) => {
                 let rect = area.get_clipped_rect(cx);
+                // with multiple windows the same position can be inside areas of different windows
+                let in_window = cx.get_window_id_of(&area).map_or(true, | window_id | window_id == event.window_id);
                 if area == cx.drag_drop.drag_area {
-                    if !event.handled.get() && Margin::rect_contains_with_margin(&rect, event.abs, &options.margin) {
+                    if !event.handled.get() && in_window && Margin::rect_contains_with_margin(&rect, event.abs, &options.margin) {
                         //log!("drag_hist_with_options: Drag, in drag area, event handled and rect ({:?}) contains ({},{}) with margin {:?}",rect,event.abs.x,event.abs.y,options.margin);
                         cx.drag_drop.next_drag_area = area;
                         event.handled.set(true);
                         DragHit::Drag(DragHitEvent {
+                            window_id: event.window_id,
                             rect,
                             modifiers: event.modifiers,
                             abs: event.abs,
@@ -127,6 +142,7 @@ impl Event {
                     } else {
                         //log!("drag_hist_with_options: Drag, in drag area, event not handled or rect ({:?}) doesn't contain ({},{}) with margin {:?}",rect,event.abs.x,event.abs.y,options.margin);
                         DragHit::Drag(DragHitEvent {
+                            window_id: event.window_id,
                             rect,
                             modifiers: event.modifiers,
                             state: DragState::Out,
@@ -136,11 +152,12 @@ impl Event {
                         })
                     }
                 } else {
-                    if !event.handled.get() && Margin::rect_contains_with_margin(&rect, event.abs, &options.margin) {
+                    if !event.handled.get() && in_window && Margin::rect_contains_with_margin(&rect, event.abs, &options.margin) {
                         //log!("drag_hits_with_options: Drag, not in drag_area, event not handled and rect ({:?}) contains ({},{}) with margin {:?}",rect,event.abs.x,event.abs.y,options.margin);
                         cx.drag_drop.next_drag_area = area;
                         event.handled.set(true);
                         DragHit::Drag(DragHitEvent {
+                            window_id: event.window_id,
                             modifiers: event.modifiers,
                             rect,
                             state: DragState::In,
@@ -156,11 +173,13 @@ impl Event {
             }
             Event::Drop(event) => {
                 let rect = area.get_clipped_rect(cx);
-                if !event.handled.get() && Margin::rect_contains_with_margin(&rect, event.abs, &options.margin) {
+                let in_window = cx.get_window_id_of(&area).map_or(true, | window_id | window_id == event.window_id);
+                if !event.handled.get() && in_window && Margin::rect_contains_with_margin(&rect, event.abs, &options.margin) {
                     //log!("drag_hits_with_options: Drop, event not handled and rect {:?} contains ({},{}) in margin {:?}",rect,event.abs.x,event.abs.y,options.margin);
                     cx.drag_drop.next_drag_area = Area::default();
                     event.handled.set(true);
                     DragHit::Drop(DropHitEvent {
+                        window_id: event.window_id,
                         modifiers: event.modifiers,
                         rect,
                         abs: event.abs,
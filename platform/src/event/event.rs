@@ -16,6 +16,7 @@ use {
             drag_drop::*,
             network::*,
             video_decoding::*,
+            image_capture::*,
         },
         animator::Ease,
         audio::AudioDevicesEvent,
@@ -69,7 +70,7 @@ pub enum Event {
     
     Drag(DragEvent),
     Drop(DropEvent),
-    DragEnd,
+    DragEnd(DragEndEvent),
     
     AudioDevices(AudioDevicesEvent),
     MidiPorts(MidiPortsEvent),
@@ -80,6 +81,8 @@ pub enum Event {
     VideoDecodingInitialized(VideoDecodingInitializedEvent),
     VideoChunkDecoded(LiveId),
     VideoDecodingError(VideoDecodingErrorEvent),
+
+    ImageCaptured(ImageCapturedEvent),
  
     #[cfg(target_arch = "wasm32")]
     ToWasmMsg(ToWasmMsgEvent),
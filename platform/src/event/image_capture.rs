@@ -0,0 +1,14 @@
+use crate::{
+    makepad_live_id::LiveId,
+    image_buffer::ImageBuffer,
+    pass::PassId,
+};
+
+/// The color output of a pass that was asked for with `Pass::capture_image` or
+/// `WindowHandle::capture_image`, read back after the pass was drawn.
+#[derive(Clone, Debug)]
+pub struct ImageCapturedEvent {
+    pub request_id: LiveId,
+    pub pass_id: PassId,
+    pub result: Result<ImageBuffer, String>,
+}
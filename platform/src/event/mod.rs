@@ -6,6 +6,7 @@ pub mod xr;
 pub mod drag_drop;
 pub mod network;
 pub mod video_decoding;
+pub mod image_capture;
 
 pub use event::*;
 pub use finger::*;
@@ -15,3 +16,4 @@ pub use xr::*;
 pub use drag_drop::*;
 pub use network::*;
 pub use video_decoding::*;
+pub use image_capture::*;
@@ -0,0 +1,275 @@
+use crate::{
+    cx::Cx,
+    live_traits::LiveNew,
+    texture::{Texture, TextureDesc, TextureFormat},
+};
+
+/// Pixels as `0xAARRGGBB` with straight alpha, row by row from the top.
+#[derive(Default, Clone)]
+pub struct ImageBuffer {
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u32>,
+}
+
+impl std::fmt::Debug for ImageBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ImageBuffer {{ width: {}, height: {} }}", self.width, self.height)
+    }
+}
+
+impl ImageBuffer {
+    pub fn new(in_data: &[u8], width: usize,height: usize) -> Result<ImageBuffer, String> {
+        let mut out = Vec::new();
+        let pixels = width * height;
+        out.resize(pixels, 0u32);
+        // input pixel packing
+        if in_data.len() /  pixels== 3{
+            for i in 0..pixels{
+                let r = in_data[i*3];
+                let g = in_data[i*3+1];
+                let b = in_data[i*3+2];
+                out[i] = 0xff000000 | ((r as u32)<<16) | ((g as u32)<<8) | ((b as u32)<<0);
+            }
+        }
+        else if in_data.len() / pixels == 4{
+            for i in 0..pixels{
+                let r = in_data[i*4];
+                let g = in_data[i*4+1];
+                let b = in_data[i*4+2];
+                let a = in_data[i*4+3];
+                out[i] = ((a as u32)<<24) | ((r as u32)<<16) | ((g as u32)<<8) | ((b as u32)<<0);
+            }
+        }
+        else{
+            return Err("ImageBuffer::new Image buffer pixel alignment not 3 or 4".to_string())
+        }
+        Ok(ImageBuffer {
+            width,
+            height,
+            data: out
+        })
+    }
+
+    /// Takes rows of premultiplied RGBA bytes from the bottom up, the way OpenGL reads them back.
+    pub fn from_premultiplied_rgba_bottom_up(rgba: &[u8], width: usize, height: usize) -> ImageBuffer {
+        let mut data = Vec::with_capacity(width * height);
+        for y in (0..height).rev() {
+            for p in rgba[y * width * 4..(y + 1) * width * 4].chunks_exact(4) {
+                let a = p[3] as u32;
+                let unpremultiply = | c: u8 | if a == 0 {0} else {((c as u32 * 255 + a / 2) / a).min(255)};
+                data.push((a << 24) | (unpremultiply(p[0]) << 16) | (unpremultiply(p[1]) << 8) | unpremultiply(p[2]));
+            }
+        }
+        ImageBuffer {width, height, data}
+    }
+
+    pub fn into_new_texture(self, cx:&mut Cx)->Texture{
+        let texture = Texture::new(cx);
+        self.into_texture(cx, &texture);
+        texture
+    }
+
+    pub fn into_texture(mut self, cx:&mut Cx, texture:&Texture){
+        texture.set_desc(
+            cx,
+            TextureDesc {
+                format: TextureFormat::ImageBGRA,
+                width: Some(self.width),
+                height: Some(self.height),
+            },
+        );
+        texture.swap_image_u32(cx, &mut self.data);
+    }
+
+    /// Encodes the image as an 8 bit RGBA PNG.
+    pub fn to_png(&self) -> Vec<u8> {
+        // every row starts with the filter that gives it the smallest sum of absolute bytes
+        let stride = self.width * 4;
+        let mut raw = Vec::with_capacity((stride + 1) * self.height);
+        let mut prev = vec![0u8; stride];
+        let mut row = vec![0u8; stride];
+        let mut filtered = vec![vec![0u8; stride]; 5];
+        for y in 0..self.height {
+            for (x, pixel) in self.data[y * self.width..(y + 1) * self.width].iter().enumerate() {
+                row[x * 4..x * 4 + 4].copy_from_slice(&[(pixel >> 16) as u8, (pixel >> 8) as u8, *pixel as u8, (pixel >> 24) as u8]);
+            }
+            for i in 0..stride {
+                let a = if i >= 4 {row[i - 4]} else {0};
+                let b = prev[i];
+                let c = if i >= 4 {prev[i - 4]} else {0};
+                filtered[0][i] = row[i];
+                filtered[1][i] = row[i].wrapping_sub(a);
+                filtered[2][i] = row[i].wrapping_sub(b);
+                filtered[3][i] = row[i].wrapping_sub(((a as u16 + b as u16) / 2) as u8);
+                filtered[4][i] = row[i].wrapping_sub(paeth(a, b, c));
+            }
+            let cost = | bytes: &[u8] | bytes.iter().map( | b | (*b as i8).unsigned_abs() as usize).sum::<usize>();
+            let filter = (0..5).min_by_key( | f | cost(&filtered[*f])).unwrap();
+            raw.push(filter as u8);
+            raw.extend_from_slice(&filtered[filter]);
+            std::mem::swap(&mut prev, &mut row);
+        }
+
+        let mut zlib = vec![0x78, 0x01];
+        deflate(&raw, &mut zlib);
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut ihdr = Vec::with_capacity(13);
+        ihdr.extend_from_slice(&(self.width as u32).to_be_bytes());
+        ihdr.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bits per channel, RGBA, deflate, adaptive filtering, not interlaced
+        ihdr.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let mut png = vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a];
+        for (kind, data) in [(b"IHDR", &ihdr), (b"IDAT", &zlib), (b"IEND", &Vec::new())] {
+            png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = png.len();
+            png.extend_from_slice(kind);
+            png.extend_from_slice(data);
+            let crc = crc32(&png[start..]);
+            png.extend_from_slice(&crc.to_be_bytes());
+        }
+        png
+    }
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = a as i16 + b as i16 - c as i16;
+    let (pa, pb, pc) = ((p - a as i16).abs(), (p - b as i16).abs(), (p - c as i16).abs());
+    if pa <= pb && pa <= pc {a} else if pb <= pc {b} else {c}
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (n, entry) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 != 0 {0xedb88320 ^ (c >> 1)} else {c >> 1};
+        }
+        *entry = c;
+    }
+    !data.iter().fold(!0u32, | c, b | table[((c ^ *b as u32) & 0xff) as usize] ^ (c >> 8))
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in data.chunks(5552) {
+        for byte in chunk {
+            a += *byte as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+struct BitWriter<'a> {
+    out: &'a mut Vec<u8>,
+    bits: u32,
+    count: u32,
+}
+
+impl<'a> BitWriter<'a> {
+    fn write(&mut self, value: u32, count: u32) {
+        self.bits |= value << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.out.push(self.bits as u8);
+            self.bits >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    // huffman codes go out from their most significant bit
+    fn write_code(&mut self, code: u32, count: u32) {
+        self.write(code.reverse_bits() >> (32 - count), count);
+    }
+
+    fn flush(&mut self) {
+        if self.count > 0 {
+            self.out.push(self.bits as u8);
+        }
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+// a single block with the fixed huffman codes, matches are found through hash chains
+fn deflate(data: &[u8], out: &mut Vec<u8>) {
+    const WINDOW: usize = 32768;
+    const MAX_CHAIN: usize = 64;
+    const HASH_SIZE: usize = 1 << 15;
+
+    let mut w = BitWriter {out, bits: 0, count: 0};
+    w.write(1, 1);
+    w.write(1, 2);
+
+    let write_literal = | w: &mut BitWriter, symbol: u32 | match symbol {
+        0..=143 => w.write_code(0x30 + symbol, 8),
+        144..=255 => w.write_code(0x190 + symbol - 144, 9),
+        256..=279 => w.write_code(symbol - 256, 7),
+        _ => w.write_code(0xc0 + symbol - 280, 8),
+    };
+    let hash = | i: usize | ((data[i] as usize) << 10 ^ (data[i + 1] as usize) << 5 ^ data[i + 2] as usize) & (HASH_SIZE - 1);
+
+    let mut head = vec![usize::MAX; HASH_SIZE];
+    let mut prev = vec![usize::MAX; WINDOW];
+    let insert = | i: usize, head: &mut Vec<usize>, prev: &mut Vec<usize> | {
+        if i + 2 < data.len() {
+            let h = hash(i);
+            prev[i % WINDOW] = head[h];
+            head[h] = i;
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let mut best_len = 0;
+        let mut best_dist = 0;
+        if i + 2 < data.len() {
+            let max_len = (data.len() - i).min(258);
+            let mut candidate = head[hash(i)];
+            let mut chain = 0;
+            while candidate != usize::MAX && i - candidate <= WINDOW && chain < MAX_CHAIN {
+                let len = (0..max_len).take_while( | k | data[candidate + k] == data[i + k]).count();
+                if len > best_len {
+                    best_len = len;
+                    best_dist = i - candidate;
+                    if len == max_len {
+                        break;
+                    }
+                }
+                let next = prev[candidate % WINDOW];
+                if next == usize::MAX || next >= candidate {
+                    break;
+                }
+                candidate = next;
+                chain += 1;
+            }
+        }
+        if best_len >= 3 {
+            let code = LENGTH_BASE.iter().rposition( | base | *base as usize <= best_len).unwrap();
+            write_literal(&mut w, 257 + code as u32);
+            w.write((best_len - LENGTH_BASE[code] as usize) as u32, LENGTH_EXTRA[code] as u32);
+            let code = DIST_BASE.iter().rposition( | base | *base as usize <= best_dist).unwrap();
+            w.write_code(code as u32, 5);
+            w.write((best_dist - DIST_BASE[code] as usize) as u32, DIST_EXTRA[code] as u32);
+            for k in 0..best_len {
+                insert(i + k, &mut head, &mut prev);
+            }
+            i += best_len;
+        }
+        else {
+            write_literal(&mut w, data[i] as u32);
+            insert(i, &mut head, &mut prev);
+            i += 1;
+        }
+    }
+    write_literal(&mut w, 256);
+    w.flush();
+}
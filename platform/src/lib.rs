@@ -29,6 +29,7 @@ mod area;
 mod window;
 mod pass;
 mod texture;
+mod image_buffer;
 mod cursor;
 mod macos_menu;
 mod animator;
@@ -112,6 +113,9 @@ pub use {
         ShaderEnum,
         DrawShaderPtr,
         ShaderTy,
+        DrawShaderSampler,
+        SamplerFilter,
+        SamplerAddress,
     },
     crate::{
         os::*,
@@ -185,6 +189,7 @@ pub use {
             XRUpdateEvent,
             DragEvent,
             DropEvent,
+            DragEndEvent,
             DragState,
             DragItem,
             DragResponse,
@@ -192,25 +197,30 @@ pub use {
             DragHitEvent,
             DropHitEvent,
             VideoColorFormat,
+            ImageCapturedEvent,
         },
         cursor::MouseCursor,
         macos_menu::MacosMenu,
         draw_matrix::DrawMatrix,
-        window::WindowHandle,
+        window::{WindowHandle, WindowId},
         pass::{
             PassId,
             CxPassParent,
             CxPassRect,
             Pass,
             PassClearColor,
-            PassClearDepth
+            PassClearDepth,
+            PassMatrixMode,
         },
         texture::{
             Texture,
             TextureId,
             TextureFormat,
-            TextureDesc
+            TextureDesc,
+            TextureRect,
+            TextureImageValue,
         },
+        image_buffer::ImageBuffer,
         live_prims::{
             LiveDependency,
             RcStringMut,
@@ -253,6 +253,24 @@ impl Cx {
         }
     }
     
+    /// Overlays the top level definitions of the `overlay` module over `target`, or removes
+    /// the overlay when `None`. All documents are re-expanded and the app receives
+    /// `Event::LiveEdit` so it can re-apply its live state without restarting
+    pub fn set_live_overlay(&mut self, target: LiveModuleId, overlay: Option<LiveModuleId>) {
+        let mut live_registry = self.live_registry.borrow_mut();
+        if !live_registry.set_module_overlay(target, overlay) {
+            return
+        }
+        let mut errs = Vec::new();
+        live_registry.expand_all_documents(&mut errs);
+        for err in errs {
+            error!("set_live_overlay: Error expanding live file {}", err);
+        }
+        drop(live_registry);
+        self.draw_shaders.reset_for_live_reload();
+        self.live_edit_pending = true;
+    }
+    
     // ok so now what. now we should run the expansion
     pub fn live_expand(&mut self) {
         let mut errs = Vec::new();
@@ -47,6 +47,15 @@ pub fn str_to_nsstring(str: &str) -> ObjcId {
     }
 }
 
+#[cfg(target_os = "macos")]
+pub fn macos_prefers_dark_mode() -> bool {
+    unsafe {
+        let defaults: ObjcId = msg_send![class!(NSUserDefaults), standardUserDefaults];
+        let style: ObjcId = msg_send![defaults, stringForKey: str_to_nsstring("AppleInterfaceStyle")];
+        style != nil && nsstring_to_string(style) == "Dark"
+    }
+}
+
 pub fn load_native_cursor(cursor_name: &str) -> ObjcId {
     let sel = Sel::register(cursor_name);
     let id: ObjcId = unsafe {msg_send![class!(NSCursor), performSelector: sel]};
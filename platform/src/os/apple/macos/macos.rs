@@ -342,6 +342,7 @@ impl Cx {
                 // ok here we send out to all our childprocesses
                 
                 self.handle_repaint(metal_windows, metal_cx);
+                self.call_image_captured_events();
                 
             }
             MacosEvent::MouseDown(e) => {
@@ -383,7 +384,7 @@ impl Cx {
                 self.call_event_handler(&Event::Drop(e));
                 self.drag_drop.cycle_drag();
             }
-            MacosEvent::DragEnd => {
+            MacosEvent::DragEnd(e) => {
                 // lets send mousebutton ups to fix missing it.
                 // TODO! make this more resilient
                 self.call_event_handler(&Event::MouseUp(MouseUpEvent {
@@ -396,7 +397,7 @@ impl Cx {
                 self.fingers.mouse_up(0);
                 self.fingers.cycle_hover_area(live_id!(mouse).into());
                 
-                self.call_event_handler(&Event::DragEnd);
+                self.call_event_handler(&Event::DragEnd(e));
                 self.drag_drop.cycle_drag();
             }
             MacosEvent::KeyDown(e) => {
@@ -37,6 +37,7 @@ use {
         event::{
             DragEvent,
             DropEvent,
+            DragEndEvent,
             DragItem,
             DragResponse
         },
@@ -627,9 +628,11 @@ pub fn define_cocoa_view_class() -> *const Class {
         cw.send_change_event();
     }
     
-    extern fn dragging_session_ended_at_point_operation(this: &Object, _: Sel, _session: ObjcId, _point: NSPoint, _operation: NSDragOperation) {
+    extern fn dragging_session_ended_at_point_operation(this: &Object, _: Sel, _session: ObjcId, point: NSPoint, _operation: NSDragOperation) {
         let window = get_cocoa_window(this);
-        window.do_callback(MacosEvent::DragEnd);
+        window.do_callback(MacosEvent::DragEnd(DragEndEvent {
+            screen_pos: DVec2 {x: point.x, y: point.y}
+        }));
     }
     
     extern fn dragging_entered(this: &Object, _: Sel, sender: ObjcId) -> NSDragOperation {
@@ -664,6 +667,7 @@ pub fn define_cocoa_view_class() -> *const Class {
         };
         
         window.do_callback(MacosEvent::Drag(DragEvent {
+            window_id: window.window_id,
             modifiers,
             handled: Cell::new(false),
             abs: pos,
@@ -759,6 +763,7 @@ pub fn define_cocoa_view_class() -> *const Class {
         let window = get_cocoa_window(this);
         let (items, pos) = get_drag_items_from_pasteboard(this, sender);
         window.do_callback(MacosEvent::Drop(DropEvent {
+            window_id: window.window_id,
             modifiers,
             handled: Cell::new(false),
             abs: pos,
@@ -15,6 +15,7 @@ use crate::{
         KeyEvent,
         DragEvent,
         DropEvent,
+        DragEndEvent,
         TextClipboardEvent,
         TimerEvent,
     },
@@ -40,7 +41,7 @@ pub enum MacosEvent {
     TextInput(TextInputEvent),
     Drag(DragEvent),
     Drop(DropEvent),
-    DragEnd,
+    DragEnd(DragEndEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
     TextCopy(TextClipboardEvent),
@@ -234,6 +234,7 @@ impl Cx {
                     let [presentable_image] = &swapchain.presentable_images;
                     if presentable_image.image.is_some() {
                         self.stdin_handle_repaint(metal_cx, swapchain, time as f32);
+                        self.call_image_captured_events();
                     }
                 }
             }
@@ -209,8 +209,9 @@ impl Cx {
                             &cxtexture.desc,
                         );
                     }
-                    else if cxtexture.update_image {
+                    else if cxtexture.update_image || cxtexture.update_rect.is_some() {
                         cxtexture.update_image = false;
+                        cxtexture.update_rect = None;
                         cxtexture.os.update_normal_texture(
                             metal_cx,
                             &cxtexture.desc,
@@ -832,7 +833,10 @@ impl CxOsTexture {
                     return
                 }
             }
-            _ => panic!(),
+            _ => {
+                error!("Texture format {:?} is not supported on metal", desc.format);
+                return
+            }
         }
         
         let need_alloc = if let Some(inner) = &self.inner {
@@ -146,6 +146,11 @@ impl Cx {
         self.inner_call_event_handler(event);
         self.inner_key_focus_change();
         self.handle_triggers();
+        if self.live_edit_pending {
+            self.live_edit_pending = false;
+            self.call_event_handler(&Event::LiveEdit);
+            self.redraw_all();
+        }
     }
 
     // helpers
@@ -169,4 +174,16 @@ impl Cx {
         std::mem::swap(&mut set, &mut self.new_next_frames);
         self.call_event_handler(&Event::NextFrame(NextFrameEvent {set, time: time, frame: self.repaint_id}));
     }
+    
+    // called after a repaint, requests on passes that were painted without being read back
+    // are on a backend that can't capture
+    pub (crate) fn call_image_captured_events(&mut self) {
+        for pass_id in self.passes.id_iter() {
+            let failed = self.passes[pass_id].fail_capture_requests(pass_id, "Image capture is not supported on this platform");
+            self.captured_images.extend(failed);
+        }
+        for event in std::mem::take(&mut self.captured_images) {
+            self.call_event_handler(&Event::ImageCaptured(event));
+        }
+    }
 }
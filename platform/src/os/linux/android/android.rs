@@ -350,6 +350,7 @@ impl Cx {
                 }
                 
                 self.handle_repaint();
+                self.call_image_captured_events();
             }
             else {
                 std::thread::sleep(Duration::from_millis(8));
@@ -601,6 +602,8 @@ impl Cx {
             zbias_step,
         );
         
+        self.opengl_read_pass_capture(pass_id, 0, 0, self.os.display_size.x as i32, self.os.display_size.y as i32);
+        
         //to_java.swap_buffers();
         //unsafe {
         //direct_app.drm.swap_buffers_and_wait(&direct_app.egl);
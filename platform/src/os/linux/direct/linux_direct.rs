@@ -139,6 +139,7 @@ impl Cx {
                 //profile_end("paint event handling", p);
                 //let p = profile_start();
                 self.handle_repaint(direct_app);
+                self.call_image_captured_events();
                 //profile_end("paint openGL", p);
             }
             DirectEvent::MouseDown(e) => {
@@ -244,6 +245,8 @@ impl Cx {
             zbias_step,
         );
         
+        self.opengl_read_pass_capture(pass_id, 0, 0, direct_app.drm.width as i32, direct_app.drm.height as i32);
+        
         unsafe {
             direct_app.drm.swap_buffers_and_wait(&direct_app.egl);
         }
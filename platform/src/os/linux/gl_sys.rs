@@ -54,12 +54,36 @@ pub const TEXTURE_MAX_LEVEL: types::GLenum = 0x813D;
 pub const TEXTURE_MAG_FILTER: types::GLenum = 0x2800;
 pub const RGBA: types::GLenum = 0x1908;
 pub const UNSIGNED_BYTE: types::GLenum = 0x1401;
+pub const PACK_ALIGNMENT: types::GLenum = 0x0D05;
 pub const DEPTH_COMPONENT32F: types::GLenum = 0x8CAC;
 pub const STATIC_DRAW: types::GLenum = 0x88E4;
 pub const NEAREST: types::GLenum = 0x2600;
 pub const TEXTURE_WRAP_S: types::GLenum = 0x2802;
 pub const TEXTURE_WRAP_T: types::GLenum = 0x2803;
 pub const CLAMP_TO_EDGE: types::GLenum = 0x812F;
+pub const REPEAT: types::GLenum = 0x2901;
+pub const MIRRORED_REPEAT: types::GLenum = 0x8370;
+pub const R8: types::GLenum = 0x8229;
+pub const RG8: types::GLenum = 0x822B;
+pub const R32F: types::GLenum = 0x822E;
+pub const RG32F: types::GLenum = 0x8230;
+pub const RGBA16F: types::GLenum = 0x881A;
+pub const RGBA32F: types::GLenum = 0x8814;
+pub const RED: types::GLenum = 0x1903;
+pub const RG: types::GLenum = 0x8227;
+pub const GREEN: types::GLenum = 0x1904;
+pub const BLUE: types::GLenum = 0x1905;
+pub const ALPHA: types::GLenum = 0x1906;
+pub const ZERO: types::GLenum = 0;
+pub const HALF_FLOAT: types::GLenum = 0x140B;
+pub const TEXTURE_SWIZZLE_R: types::GLenum = 0x8E42;
+pub const TEXTURE_SWIZZLE_G: types::GLenum = 0x8E43;
+pub const TEXTURE_SWIZZLE_B: types::GLenum = 0x8E44;
+pub const TEXTURE_SWIZZLE_A: types::GLenum = 0x8E45;
+pub const UNPACK_ALIGNMENT: types::GLenum = 0x0CF5;
+pub const UNPACK_ROW_LENGTH: types::GLenum = 0x0CF2;
+pub const UNPACK_SKIP_ROWS: types::GLenum = 0x0CF3;
+pub const UNPACK_SKIP_PIXELS: types::GLenum = 0x0CF4;
 pub const PROGRAM_BINARY_LENGTH: types::GLenum = 0x8741;
 pub const NO_ERROR: types::GLenum = 0x0;
 
@@ -111,6 +135,9 @@ pub const NO_ERROR: types::GLenum = 0x0;
 #[inline] pub unsafe fn BufferData(target: types::GLenum, size: types::GLsizeiptr, data: *const raw::c_void, usage: types::GLenum) -> () { mem::transmute::<_, extern "system" fn(types::GLenum, types::GLsizeiptr, *const raw::c_void, types::GLenum) -> ()>(storage::BufferData.f)(target, size, data, usage) }
 #[inline] pub unsafe fn Uniform1i(location: types::GLint, v0: types::GLint) -> () { mem::transmute::<_, extern "system" fn(types::GLint, types::GLint) -> ()>(storage::Uniform1i.f)(location, v0) }
 #[inline] pub unsafe fn GetError() -> types::GLenum { mem::transmute::<_, extern "system" fn() -> types::GLenum>(storage::GetError.f)() }
+#[inline] pub unsafe fn ReadPixels(x: types::GLint, y: types::GLint, width: types::GLsizei, height: types::GLsizei, format: types::GLenum, type_: types::GLenum, pixels: *mut raw::c_void) -> () { mem::transmute::<_, extern "system" fn(types::GLint, types::GLint, types::GLsizei, types::GLsizei, types::GLenum, types::GLenum, *mut raw::c_void) -> ()>(storage::ReadPixels.f)(x, y, width, height, format, type_, pixels) }
+#[inline] pub unsafe fn TexSubImage2D(target: types::GLenum, level: types::GLint, xoffset: types::GLint, yoffset: types::GLint, width: types::GLsizei, height: types::GLsizei, format: types::GLenum, type_: types::GLenum, pixels: *const raw::c_void) -> () { mem::transmute::<_, extern "system" fn(types::GLenum, types::GLint, types::GLint, types::GLint, types::GLsizei, types::GLsizei, types::GLenum, types::GLenum, *const raw::c_void) -> ()>(storage::TexSubImage2D.f)(target, level, xoffset, yoffset, width, height, format, type_, pixels) }
+#[inline] pub unsafe fn PixelStorei(pname: types::GLenum, param: types::GLint) -> () { mem::transmute::<_, extern "system" fn(types::GLenum, types::GLint) -> ()>(storage::PixelStorei.f)(pname, param) }
 #[inline] pub unsafe fn Finish() -> () { mem::transmute::<_, extern "system" fn() -> ()>(storage::Finish.f)() }
 #[inline] pub unsafe fn GetProgramBinary(program: types::GLuint, bufSize: types::GLsizei, length: *mut types::GLsizei, binaryFormat: *mut types::GLenum, binary: *mut raw::c_void) -> () { mem::transmute::<_, extern "system" fn(types::GLuint, types::GLsizei, *mut types::GLsizei, *mut types::GLenum, *mut raw::c_void) -> ()>(storage::GetProgramBinary.f)(program, bufSize, length, binaryFormat, binary) }
 #[inline] pub unsafe fn ProgramBinary(program: types::GLuint, binaryFormat: types::GLenum, binary: *const raw::c_void, length: types::GLsizei) -> () { mem::transmute::<_, extern "system" fn(types::GLuint, types::GLenum, *const raw::c_void, types::GLsizei) -> ()>(storage::ProgramBinary.f)(program, binaryFormat, binary, length) }            
@@ -171,6 +198,9 @@ mod storage {
     pub static mut BufferData: FnPtr = FnPtr::default();
     pub static mut Uniform1i: FnPtr = FnPtr::default();
     pub static mut GetError: FnPtr = FnPtr::default();
+    pub static mut ReadPixels: FnPtr = FnPtr::default();
+    pub static mut TexSubImage2D: FnPtr = FnPtr::default();
+    pub static mut PixelStorei: FnPtr = FnPtr::default();
     pub static mut Finish: FnPtr = FnPtr::default();
     pub static mut GetProgramBinary: FnPtr = FnPtr::default();
     pub static mut ProgramBinary: FnPtr = FnPtr::default();
@@ -230,6 +260,9 @@ pub unsafe fn load_with<F>(mut loadfn: F) where F: FnMut(&'static str) -> *const
     storage::BufferData = FnPtr::new(metaloadfn(&mut loadfn, "glBufferData", &["glBufferDataARB"]));
     storage::Uniform1i = FnPtr::new(metaloadfn(&mut loadfn, "glUniform1i", &["glUniform1iARB"]));
     storage::GetError = FnPtr::new(metaloadfn(&mut loadfn, "glGetError", &[]));
+    storage::ReadPixels = FnPtr::new(metaloadfn(&mut loadfn, "glReadPixels", &[]));
+    storage::TexSubImage2D = FnPtr::new(metaloadfn(&mut loadfn, "glTexSubImage2D", &[]));
+    storage::PixelStorei = FnPtr::new(metaloadfn(&mut loadfn, "glPixelStorei", &[]));
     storage::Finish = FnPtr::new(metaloadfn(&mut loadfn, "glFinish", &[]));
     storage::ClearDepthf = FnPtr::new(metaloadfn(&mut loadfn, "glClearDepthf", &["glClearDepthfOES"]));
     storage::GetProgramBinary = FnPtr::new(metaloadfn(&mut loadfn, "glGetProgramBinary", &["glGetProgramBinaryOES"]));
@@ -0,0 +1,52 @@
+use {
+    std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex
+    },
+    crate::thread::Signal,
+};
+
+// asking gsettings spawns a process, too slow for the UI thread. The query runs on a
+// thread and the UI is signalled when the answer changed
+static PREFERS_DARK_MODE: Mutex<Option<bool>> = Mutex::new(None);
+static QUERY_RUNNING: AtomicBool = AtomicBool::new(false);
+
+pub fn linux_prefers_dark_mode() -> Option<bool> {
+    *PREFERS_DARK_MODE.lock().unwrap()
+}
+
+pub fn linux_refresh_prefers_dark_mode() {
+    if QUERY_RUNNING.swap(true, Ordering::SeqCst) {
+        return
+    }
+    std::thread::spawn( || {
+        let dark = query_prefers_dark_mode();
+        let changed = std::mem::replace(&mut *PREFERS_DARK_MODE.lock().unwrap(), dark) != dark;
+        QUERY_RUNNING.store(false, Ordering::SeqCst);
+        if changed {
+            Signal::set_ui_signal();
+        }
+    });
+}
+
+// an explicit GTK_THEME wins, then the gnome color scheme and theme name
+fn query_prefers_dark_mode() -> Option<bool> {
+    if let Ok(theme) = std::env::var("GTK_THEME") {
+        return Some(theme.to_lowercase().contains("dark"))
+    }
+    let gsettings = | key: &str | std::process::Command::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", key])
+        .output()
+        .ok()
+        .filter( | output | output.status.success())
+        .map( | output | String::from_utf8_lossy(&output.stdout).to_lowercase());
+    if let Some(scheme) = gsettings("color-scheme") {
+        if scheme.contains("dark") {
+            return Some(true)
+        }
+        if scheme.contains("light") {
+            return Some(false)
+        }
+    }
+    gsettings("gtk-theme").map( | theme | theme.contains("dark"))
+}
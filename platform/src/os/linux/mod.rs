@@ -31,6 +31,8 @@ pub mod select_timer;
 pub mod pulse_audio; 
 #[cfg(not(target_os="android"))]
 pub mod pulse_sys;
+#[cfg(not(target_os="android"))]
+pub mod linux_theme;
 
 #[cfg(target_os="android")]
 pub mod android;
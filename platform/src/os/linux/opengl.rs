@@ -10,13 +10,15 @@ use {
     crate::{
         makepad_live_id::*,
         makepad_error_log::*,
-        makepad_shader_compiler::generate_glsl,
+        makepad_shader_compiler::{generate_glsl, DrawShaderSampler, SamplerFilter, SamplerAddress},
         cx::Cx,
-        texture::{TextureId, TextureDesc, TextureFormat},
+        texture::{TextureId, TextureDesc, TextureFormat, CxTexture},
         makepad_math::{Mat4, DVec2, Vec4},
         pass::{PassClearColor, PassClearDepth, PassId},
         draw_list::DrawListId,
         draw_shader::{CxDrawShaderMapping, DrawShaderTextureInput},
+        event::ImageCapturedEvent,
+        image_buffer::ImageBuffer,
     },
 };
 
@@ -189,14 +191,8 @@ impl Cx {
                             continue;
                         };
                         let cxtexture = &mut self.textures[texture_id];
-
-                        if cxtexture.update_image || cxtexture.image_u32.len() != 0 && cxtexture.os.gl_texture.is_none(){
-                            cxtexture.update_image = false;
-                            cxtexture.os.update_platform_texture_image2d(
-                                cxtexture.desc.width.unwrap() as u32,
-                                cxtexture.desc.height.unwrap() as u32,
-                                &cxtexture.image_u32
-                            );
+                        if cxtexture.desc.format.is_image() {
+                            cxtexture.update_platform_texture_image2d();
                         }
                     }
                     for i in 0..sh.mapping.textures.len() {
@@ -210,6 +206,7 @@ impl Cx {
                         gl_sys::ActiveTexture(gl_sys::TEXTURE0 + i as u32);
                         if let Some(texture) = cxtexture.os.gl_texture {
                             gl_sys::BindTexture(gl_sys::TEXTURE_2D, texture);
+                            cxtexture.os.apply_sampler(sh.mapping.flags.sampler);
                         }
                         else {
                             gl_sys::BindTexture(gl_sys::TEXTURE_2D, 0);
@@ -309,6 +306,7 @@ impl Cx {
         });
         let color_textures = color_textures_from_fb_texture
             .as_ref().map_or(&self.passes[pass_id].color_textures[..], |xs| &xs[..]);
+        let has_color_textures = !color_textures.is_empty();
 
         for (index, color_texture) in color_textures.iter().enumerate() {
             match color_texture.clear_color {
@@ -377,7 +375,7 @@ impl Cx {
         // HACK(eddyb) drain error queue, so that we can check erors below.
         while unsafe { gl_sys::GetError() } != 0 {}
 
-        unsafe {
+        let (x, y, width, height) = unsafe {
             let (x, mut y) = (0, 0);
             let width = (pass_size.x * dpi_factor) as u32;
             let height = (pass_size.y * dpi_factor) as u32;
@@ -398,7 +396,8 @@ impl Cx {
 
             gl_sys::Viewport(x as i32, y as i32, width as i32, height as i32);
             assert_eq!(gl_sys::GetError(), 0, "glViewport({x}, {y}, {width}, {height}) failed");
-        }
+            (x, y, width, height)
+        };
 
         if clear_flags != 0 {
             unsafe {
@@ -421,12 +420,43 @@ impl Cx {
             zbias_step,
         );
         
+        if has_color_textures {
+            self.opengl_read_pass_capture(pass_id, x as i32, y as i32, width as i32, height as i32);
+        }
+        
         unsafe {
             gl_sys::BindFramebuffer(gl_sys::FRAMEBUFFER, 0);
             //gl_sys::Finish();
         }
     }
     
+    /// Reads the bound framebuffer back for the capture requests of the pass.
+    pub (crate) fn opengl_read_pass_capture(&mut self, pass_id: PassId, x: i32, y: i32, width: i32, height: i32) {
+        if self.passes[pass_id].capture_requests.is_empty() {
+            return
+        }
+        if width <= 0 || height <= 0 {
+            for request_id in std::mem::take(&mut self.passes[pass_id].capture_requests) {
+                self.captured_images.push(ImageCapturedEvent {request_id, pass_id, result: Err("The pass has no size".to_string())});
+            }
+            return
+        }
+        let mut rgba = vec![0u8; width as usize * height as usize * 4];
+        unsafe {
+            gl_sys::PixelStorei(gl_sys::PACK_ALIGNMENT, 1);
+            gl_sys::ReadPixels(x, y, width, height, gl_sys::RGBA, gl_sys::UNSIGNED_BYTE, rgba.as_mut_ptr() as *mut _);
+        }
+        let result = if unsafe {gl_sys::GetError()} == 0 {
+            Ok(ImageBuffer::from_premultiplied_rgba_bottom_up(&rgba, width as usize, height as usize))
+        }
+        else {
+            Err("glReadPixels failed".to_string())
+        };
+        for request_id in std::mem::take(&mut self.passes[pass_id].capture_requests) {
+            self.captured_images.push(ImageCapturedEvent {request_id, pass_id, result: result.clone()});
+        }
+    }
+    
     pub fn opengl_compile_shaders(&mut self) {
         //let p = profile_start();
         for draw_shader_ptr in &self.draw_shaders.compile_set {
@@ -832,47 +862,142 @@ pub struct CxOsTexture {
     pub height: u64,
     pub gl_texture: Option<u32>,
     pub gl_renderbuffer: Option<u32>,
+    pub gl_image: bool,
+    pub gl_mipmaps: bool,
+    // min filter, mag filter and wrap mode last set on the texture
+    pub gl_sampler: Option<(u32, u32, u32)>,
 }
 
-impl CxOsTexture {
+impl CxTexture {
     
-    pub fn update_platform_texture_image2d(&mut self, width: u32, height: u32, image_u32: &Vec<u32>) {
+    pub fn update_platform_texture_image2d(&mut self) {
+        let (width, height) = (self.desc.width.unwrap_or(0), self.desc.height.unwrap_or(0));
+        let (image, pixels) = self.image_bytes();
+        if !self.update_image && self.update_rect.is_none() && (pixels == 0 || self.os.gl_texture.is_some()) {
+            return
+        }
+        let image = image.as_ptr();
+        let update_rect = if self.update_image {None} else {self.update_rect};
+        self.update_image = false;
+        self.update_rect = None;
         
-        if image_u32.len() != (width * height) as usize {
-            log!("update_platform_texture_image2d with wrong buffer_u32 size! {} {} {}", image_u32.len(), width, height);
+        if pixels != width * height {
+            log!("update_platform_texture_image2d with wrong image size! {} {} {}", pixels, width, height);
             return;
         }
         
-        if self.gl_texture.is_none() {
+        // sample2d reads .zyxw for the bytes of BGRA pixels, the swizzles make the other formats come out as rgba
+        let (internal_format, format, ty, swizzle) = match self.desc.format {
+            TextureFormat::ImageR8 => (gl_sys::R8, gl_sys::RED, gl_sys::UNSIGNED_BYTE, [gl_sys::ZERO, gl_sys::ZERO, gl_sys::RED, gl_sys::ONE]),
+            TextureFormat::ImageRG8 => (gl_sys::RG8, gl_sys::RG, gl_sys::UNSIGNED_BYTE, [gl_sys::ZERO, gl_sys::GREEN, gl_sys::RED, gl_sys::ONE]),
+            TextureFormat::ImageRf32 => (gl_sys::R32F, gl_sys::RED, gl_sys::FLOAT, [gl_sys::ZERO, gl_sys::ZERO, gl_sys::RED, gl_sys::ONE]),
+            TextureFormat::ImageRGf32 => (gl_sys::RG32F, gl_sys::RG, gl_sys::FLOAT, [gl_sys::ZERO, gl_sys::GREEN, gl_sys::RED, gl_sys::ONE]),
+            TextureFormat::ImageRGBAf16 => (gl_sys::RGBA16F, gl_sys::RGBA, gl_sys::HALF_FLOAT, [gl_sys::BLUE, gl_sys::GREEN, gl_sys::RED, gl_sys::ALPHA]),
+            TextureFormat::ImageRGBAf32 => (gl_sys::RGBA32F, gl_sys::RGBA, gl_sys::FLOAT, [gl_sys::BLUE, gl_sys::GREEN, gl_sys::RED, gl_sys::ALPHA]),
+            _ => (gl_sys::RGBA, gl_sys::RGBA, gl_sys::UNSIGNED_BYTE, [gl_sys::RED, gl_sys::GREEN, gl_sys::BLUE, gl_sys::ALPHA])
+        };
+        
+        let os = &mut self.os;
+        if os.gl_texture.is_none() {
             unsafe {
                 let mut gl_texture = std::mem::MaybeUninit::uninit();
                 gl_sys::GenTextures(1, gl_texture.as_mut_ptr());
-                self.gl_texture = Some(gl_texture.assume_init());
+                os.gl_texture = Some(gl_texture.assume_init());
             }
         }
         unsafe {
-            gl_sys::BindTexture(gl_sys::TEXTURE_2D, self.gl_texture.unwrap());
-            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MIN_FILTER, gl_sys::LINEAR_MIPMAP_LINEAR as i32);            
-            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MAG_FILTER, gl_sys::NEAREST as i32);
-            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_WRAP_S, gl_sys::CLAMP_TO_EDGE as i32);
-            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_WRAP_T, gl_sys::CLAMP_TO_EDGE as i32);
-            gl_sys::TexImage2D(
-                gl_sys::TEXTURE_2D,
-                0,
-                gl_sys::RGBA as i32,
-                width as i32,
-                height as i32,
-                0,
-                gl_sys::RGBA,
-                gl_sys::UNSIGNED_BYTE,
-                image_u32.as_ptr() as *const _
-            );
-            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_BASE_LEVEL, 0);
-            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MAX_LEVEL, 3);
-            gl_sys::GenerateMipmap(gl_sys::TEXTURE_2D);  
+            gl_sys::BindTexture(gl_sys::TEXTURE_2D, os.gl_texture.unwrap());
+            gl_sys::PixelStorei(gl_sys::UNPACK_ALIGNMENT, 1);
+            if let Some(rect) = update_rect {
+                gl_sys::PixelStorei(gl_sys::UNPACK_ROW_LENGTH, width as i32);
+                gl_sys::PixelStorei(gl_sys::UNPACK_SKIP_PIXELS, rect.x as i32);
+                gl_sys::PixelStorei(gl_sys::UNPACK_SKIP_ROWS, rect.y as i32);
+                gl_sys::TexSubImage2D(
+                    gl_sys::TEXTURE_2D,
+                    0,
+                    rect.x as i32,
+                    rect.y as i32,
+                    rect.width as i32,
+                    rect.height as i32,
+                    format,
+                    ty,
+                    image as *const _
+                );
+                gl_sys::PixelStorei(gl_sys::UNPACK_ROW_LENGTH, 0);
+                gl_sys::PixelStorei(gl_sys::UNPACK_SKIP_PIXELS, 0);
+                gl_sys::PixelStorei(gl_sys::UNPACK_SKIP_ROWS, 0);
+            }
+            else {
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_SWIZZLE_R, swizzle[0] as i32);
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_SWIZZLE_G, swizzle[1] as i32);
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_SWIZZLE_B, swizzle[2] as i32);
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_SWIZZLE_A, swizzle[3] as i32);
+                gl_sys::TexImage2D(
+                    gl_sys::TEXTURE_2D,
+                    0,
+                    internal_format as i32,
+                    width as i32,
+                    height as i32,
+                    0,
+                    format,
+                    ty,
+                    image as *const _
+                );
+            }
+            gl_sys::PixelStorei(gl_sys::UNPACK_ALIGNMENT, 4);
+            os.gl_image = true;
+            os.gl_mipmaps = self.mipmaps;
+            os.gl_sampler = None;
+            if self.mipmaps {
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_BASE_LEVEL, 0);
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MAX_LEVEL, 3);
+                gl_sys::GenerateMipmap(gl_sys::TEXTURE_2D);
+            }
+            else {
+                gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MAX_LEVEL, 0);
+            }
             gl_sys::BindTexture(gl_sys::TEXTURE_2D, 0);
         }
     }
+}
+
+impl CxOsTexture {
+    
+    // sets the filtering and wrapping of the bound texture, what the shader doesn't declare stays at the texture default
+    pub fn apply_sampler(&mut self, sampler: DrawShaderSampler) {
+        // render targets are left at the wrapping gl starts out with
+        let (default_min, default_mag, default_wrap) = if !self.gl_image {
+            (gl_sys::NEAREST, gl_sys::NEAREST, gl_sys::REPEAT)
+        }
+        else if self.gl_mipmaps {
+            (gl_sys::LINEAR_MIPMAP_LINEAR, gl_sys::NEAREST, gl_sys::CLAMP_TO_EDGE)
+        }
+        else {
+            (gl_sys::LINEAR, gl_sys::NEAREST, gl_sys::CLAMP_TO_EDGE)
+        };
+        let (min, mag) = match sampler.filter {
+            Some(SamplerFilter::Nearest) => (gl_sys::NEAREST, gl_sys::NEAREST),
+            Some(SamplerFilter::Linear) if self.gl_mipmaps => (gl_sys::LINEAR_MIPMAP_LINEAR, gl_sys::LINEAR),
+            Some(SamplerFilter::Linear) => (gl_sys::LINEAR, gl_sys::LINEAR),
+            None => (default_min, default_mag)
+        };
+        let wrap = match sampler.address {
+            Some(SamplerAddress::Repeat) => gl_sys::REPEAT,
+            Some(SamplerAddress::Mirror) => gl_sys::MIRRORED_REPEAT,
+            Some(SamplerAddress::Clamp) => gl_sys::CLAMP_TO_EDGE,
+            None => default_wrap
+        };
+        if self.gl_sampler == Some((min, mag, wrap)) {
+            return
+        }
+        self.gl_sampler = Some((min, mag, wrap));
+        unsafe {
+            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MIN_FILTER, min as i32);
+            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_MAG_FILTER, mag as i32);
+            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_WRAP_S, wrap as i32);
+            gl_sys::TexParameteri(gl_sys::TEXTURE_2D, gl_sys::TEXTURE_WRAP_T, wrap as i32);
+        }
+    }
     
     pub fn update_platform_render_target(&mut self, desc: &TextureDesc, default_size: DVec2, is_depth: bool) -> bool {
         let width = desc.width.unwrap_or(default_size.x as usize) as u64;
@@ -887,6 +1012,7 @@ impl CxOsTexture {
             self.alloc_desc = desc.clone();
             self.width = width;
             self.height = height;
+            self.gl_sampler = None;
             
             if !is_depth {
                 match desc.format {
@@ -130,6 +130,7 @@ impl Cx {
                 // ok here we send out to all our childprocesses
                 
                 self.handle_repaint(opengl_windows);
+                self.call_image_captured_events();
             }
             XlibEvent::MouseDown(e) => {
                 self.fingers.process_tap_count(
@@ -168,8 +169,8 @@ impl Cx {
             XlibEvent::Drop(e) => {
                 self.call_event_handler(&Event::Drop(e))
             }
-            XlibEvent::DragEnd => {
-                self.call_event_handler(&Event::DragEnd)
+            XlibEvent::DragEnd(e) => {
+                self.call_event_handler(&Event::DragEnd(e))
             }
             XlibEvent::KeyDown(e) => {
                 self.keyboard.process_key_down(e.clone());
@@ -222,6 +222,7 @@ impl Cx {
                     }
 
                     self.stdin_handle_repaint(swapchain.as_ref(), &mut present_index);
+                    self.call_image_captured_events();
                 }
             }
         }
@@ -82,6 +82,8 @@ impl Cx {
             &mut zbias,
             zbias_step,
         );
+        
+        self.opengl_read_pass_capture(pass_id, 0, 0, pix_width.floor() as i32, pix_height.floor() as i32);
 
         unsafe {
             let opengl_cx = self.os.opengl_cx.as_ref().unwrap();
@@ -13,6 +13,7 @@ use {
             KeyEvent,
             DragEvent,
             DropEvent,
+            DragEndEvent,
             TextClipboardEvent,
             TimerEvent,
         },
@@ -37,7 +38,7 @@ pub enum XlibEvent {
     TextInput(TextInputEvent),
     Drag(DragEvent),
     Drop(DropEvent),
-    DragEnd,
+    DragEnd(DragEndEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
     TextCopy(TextClipboardEvent),
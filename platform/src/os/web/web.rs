@@ -345,6 +345,7 @@ impl Cx {
                 self.webgl_compile_shaders();
             }
             self.handle_repaint(time);
+            self.call_image_captured_events();
         }
 
         if network_responses.len() != 0 {
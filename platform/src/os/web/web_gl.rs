@@ -13,6 +13,7 @@ use {
         },
         draw_vars::DRAW_CALL_TEXTURE_SLOTS,
         cx::Cx,
+        texture::TextureFormat,
         draw_list::DrawListId,
         pass::{PassId, PassClearColor, PassClearDepth},
     },
@@ -80,8 +81,13 @@ impl Cx {
                     };
                     
                     let cxtexture = &mut self.textures[texture_id];
-                    if cxtexture.update_image {
+                    if cxtexture.update_image || cxtexture.update_rect.is_some() {
                         cxtexture.update_image = false;
+                        cxtexture.update_rect = None;
+                        if !matches!(cxtexture.desc.format, TextureFormat::Default | TextureFormat::ImageBGRA) {
+                            error!("Texture format {:?} is not supported on webgl", cxtexture.desc.format);
+                            continue
+                        }
                         self.os.from_wasm(FromWasmAllocTextureImage2D {
                             texture_id: texture_id.0,
                             width: cxtexture.desc.width.unwrap(),
@@ -262,8 +262,9 @@ impl Cx {
                     
                     match cxtexture.desc.format { // we only allocate Image, Mapped and Shared textures.
                         TextureFormat::Default | TextureFormat::ImageBGRA => {
-                            if cxtexture.update_image {
+                            if cxtexture.update_image || cxtexture.update_rect.is_some() {
                                 cxtexture.update_image = false;
+                                cxtexture.update_rect = None;
                                 cxtexture.os.update_platform_texture_image_bgra(
                                     d3d11_cx,
                                     cxtexture.desc.width.unwrap() as u32,
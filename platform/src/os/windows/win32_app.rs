@@ -71,6 +71,7 @@ use {
                     LOGPIXELSX
                 },
                 Foundation::{
+                    POINT,
                     COLORREF,
                     S_OK,
                     HWND,
@@ -103,6 +104,7 @@ use {
             },
         },
         event::*,
+        makepad_math::DVec2,
         cursor::MouseCursor,
         os::{
             cx_native::EventFlow,
@@ -144,6 +146,12 @@ P1: IntoParam<IDropSource>,
     DoDragDrop(pdataobj.into_param().abi(), pdropsource.into_param().abi(), dwokeffects, pdweffect)
 }
 
+#[allow(non_snake_case)]
+pub unsafe fn GetCursorPos(lppoint: *mut POINT) -> BOOL {
+    ::windows_targets::link!("user32.dll" "system" fn GetCursorPos(lppoint: *mut POINT) -> BOOL);
+    GetCursorPos(lppoint)
+}
+
 pub struct Win32App {
     pub time_start: i64,
     pub time_freq: i64,
@@ -440,6 +448,12 @@ impl Win32App {
                     }
                 }
             }
+            // window positions are in screen pixels as well
+            let mut point = POINT::default();
+            unsafe {GetCursorPos(&mut point)};
+            Win32App::do_callback(Win32Event::DragSessionEnd(DragEndEvent {
+                screen_pos: DVec2 {x: point.x as f64, y: point.y as f64}
+            }));
         }
     }
     
@@ -14,6 +14,7 @@ use {
             KeyEvent,
             DragEvent,
             DropEvent,
+            DragEndEvent,
             TextClipboardEvent,
             TimerEvent,
         },
@@ -41,6 +42,8 @@ pub enum Win32Event {
     Drag(DragEvent),
     Drop(DropEvent),
     DragEnd,
+    // DoDragDrop returned, dropped in one of our windows or not
+    DragSessionEnd(DragEndEvent),
     KeyDown(KeyEvent),
     KeyUp(KeyEvent),
     TextCopy(TextClipboardEvent),
@@ -677,6 +677,7 @@ impl Win32Window {
                         window.do_callback(
                             Win32Event::Drag(
                                 DragEvent {
+                                    window_id: window.window_id,
                                     modifiers: KeyModifiers {
                                         shift: (flags & MK_SHIFT) != MODIFIERKEYS_FLAGS(0),
                                         control: (flags & MK_CONTROL) != MODIFIERKEYS_FLAGS(0),
@@ -704,6 +705,7 @@ impl Win32Window {
                         window.do_callback(
                             Win32Event::Drop(
                                 DropEvent {
+                                    window_id: window.window_id,
                                     modifiers: KeyModifiers {
                                         shift: (flags & MK_SHIFT) != MODIFIERKEYS_FLAGS(0),
                                         control: (flags & MK_CONTROL) != MODIFIERKEYS_FLAGS(0),
@@ -142,6 +142,7 @@ impl Cx {
                 // ok here we send out to all our childprocesses
                 
                 self.handle_repaint(d3d11_windows, d3d11_cx);
+                self.call_image_captured_events();
             }
             Win32Event::MouseDown(e) => {
                 self.fingers.process_tap_count(
@@ -195,6 +196,10 @@ impl Cx {
                 self.fingers.mouse_up(0);
                 self.fingers.cycle_hover_area(live_id!(mouse).into());
             }
+            Win32Event::DragSessionEnd(e) => {
+                self.call_event_handler(&Event::DragEnd(e));
+                self.drag_drop.cycle_drag();
+            }
             Win32Event::KeyDown(e) => {
                 self.keyboard.process_key_down(e.clone());
                 self.call_event_handler(&Event::KeyDown(e))
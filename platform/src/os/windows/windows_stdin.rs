@@ -213,6 +213,7 @@ impl Cx {
                     
                     // repaint
                     self.stdin_handle_repaint(d3d11_cx, swapchain.as_ref(), &mut present_index);
+                    self.call_image_captured_events();
                     
                     
                     // check if GPU is ready to flip frames
@@ -13,6 +13,7 @@ use crate::{
     makepad_math::*,
     id_pool::*,
     area::Area,
+    event::ImageCapturedEvent,
     window::WindowId,
     os::CxOsPass,
     cx::Cx,
@@ -197,6 +198,30 @@ impl Pass {
         cxpass.debug = debug;
     }
     
+    /// Reads the color output back after the pass is next drawn, it arrives as an
+    /// `Event::ImageCaptured` with `request_id`. Only the OpenGL backend of Linux and Android
+    /// reads it back, on Metal, D3D11 and WebGL the event carries an error.
+    pub fn capture_image(&self, cx: &mut Cx, request_id: LiveId) {
+        let cxpass = &mut cx.passes[self.pass_id()];
+        cxpass.capture_requests.push(request_id);
+        cxpass.paint_dirty = true;
+    }
+    
+}
+
+impl CxPass {
+    /// Answers the capture requests that are still waiting after the pass was painted
+    /// with an error, the backend didn't read the pass back.
+    pub fn fail_capture_requests(&mut self, pass_id: PassId, error: &str) -> Vec<ImageCapturedEvent> {
+        if self.paint_dirty {
+            return Vec::new()
+        }
+        std::mem::take(&mut self.capture_requests).into_iter().map( | request_id | ImageCapturedEvent {
+            request_id,
+            pass_id,
+            result: Err(error.to_string())
+        }).collect()
+    }
 }
 
 #[derive(Clone)]
@@ -272,6 +297,8 @@ pub struct CxPass {
     pub pass_rect: Option<CxPassRect>,
     pub pass_uniforms: PassUniforms,
     pub zbias_step: f32,
+    /// Request ids of `capture_image` calls waiting for the pass to be drawn.
+    pub capture_requests: Vec<LiveId>,
     pub os: CxOsPass,
 }
 
@@ -294,6 +321,7 @@ impl Default for CxPass {
             parent: CxPassParent::None,
             paint_dirty: false,
             pass_rect: None,
+            capture_requests: Vec::new(),
             os: CxOsPass::default()
         }
     }
@@ -64,14 +64,18 @@ impl std::ops::IndexMut<TextureId> for CxTexturePool {
 pub enum TextureFormat {
     Default,
     ImageBGRA,
+    ImageR8,
+    ImageRG8,
+    ImageRf32,
+    ImageRGf32,
+    /// Half floats, stored as their bits in a `u16`.
+    ImageRGBAf16,
+    ImageRGBAf32,
     Depth32Stencil8,
     RenderBGRA,
     RenderBGRAf16,
     RenderBGRAf32,
     SharedBGRA(crate::cx_stdin::PresentableImageId),
-    //    ImageBGRAf32,
-    //    ImageRf32,
-    //    ImageRGf32,
     //    MappedBGRA,
     //    MappedBGRAf32,
     //    MappedRf32,
@@ -84,6 +88,81 @@ impl TextureFormat{
              _=>false
          }
     }
+    
+    /// Whether the texture is uploaded from an image on the CPU rather than rendered to.
+    pub fn is_image(&self)->bool{
+        match self{
+            Self::Default | Self::ImageBGRA | Self::ImageR8 | Self::ImageRG8 | Self::ImageRf32 |
+            Self::ImageRGf32 | Self::ImageRGBAf16 | Self::ImageRGBAf32 => true,
+            _=>false
+        }
+    }
+    
+    /// The number of values per pixel in the image of the texture, a `u32` holds all of BGRA.
+    pub fn values_per_pixel(&self)->usize{
+        match self{
+            Self::ImageRG8 | Self::ImageRGf32 => 2,
+            Self::ImageRGBAf16 | Self::ImageRGBAf32 => 4,
+            _=>1
+        }
+    }
+}
+
+/// A rectangle of pixels in a texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextureRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl TextureRect {
+    pub fn union(&self, other: &TextureRect) -> TextureRect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        TextureRect {
+            x,
+            y,
+            width: (self.x + self.width).max(other.x + other.width) - x,
+            height: (self.y + self.height).max(other.y + other.height) - y,
+        }
+    }
+}
+
+/// The value types of texture images, `u32` for BGRA, `u8` for R8 and RG8, `u16` for half floats
+/// and `f32` for the float formats.
+pub trait TextureImageValue: Copy {
+    fn accepts_format(format: TextureFormat) -> bool;
+    fn image_mut(texture: &mut CxTexture) -> &mut Vec<Self>;
+}
+
+impl TextureImageValue for u32 {
+    fn accepts_format(format: TextureFormat) -> bool {
+        matches!(format, TextureFormat::Default | TextureFormat::ImageBGRA)
+    }
+    fn image_mut(texture: &mut CxTexture) -> &mut Vec<Self> {&mut texture.image_u32}
+}
+
+impl TextureImageValue for u8 {
+    fn accepts_format(format: TextureFormat) -> bool {
+        matches!(format, TextureFormat::ImageR8 | TextureFormat::ImageRG8)
+    }
+    fn image_mut(texture: &mut CxTexture) -> &mut Vec<Self> {&mut texture.image_u8}
+}
+
+impl TextureImageValue for u16 {
+    fn accepts_format(format: TextureFormat) -> bool {
+        matches!(format, TextureFormat::ImageRGBAf16)
+    }
+    fn image_mut(texture: &mut CxTexture) -> &mut Vec<Self> {&mut texture.image_f16}
+}
+
+impl TextureImageValue for f32 {
+    fn accepts_format(format: TextureFormat) -> bool {
+        matches!(format, TextureFormat::ImageRf32 | TextureFormat::ImageRGf32 | TextureFormat::ImageRGBAf32)
+    }
+    fn image_mut(texture: &mut CxTexture) -> &mut Vec<Self> {&mut texture.image_f32}
 }
 #[derive(Clone, Copy, PartialEq)]
 pub struct TextureDesc {
@@ -158,18 +237,113 @@ impl Texture {
     }
     
     pub fn swap_image_u32(&self, cx: &mut Cx, image_u32: &mut Vec<u32>) {
+        self.swap_image(cx, image_u32)
+    }
+    
+    pub fn swap_image_u8(&self, cx: &mut Cx, image_u8: &mut Vec<u8>) {
+        self.swap_image(cx, image_u8)
+    }
+    
+    pub fn swap_image_f16(&self, cx: &mut Cx, image_f16: &mut Vec<u16>) {
+        self.swap_image(cx, image_f16)
+    }
+    
+    pub fn swap_image_f32(&self, cx: &mut Cx, image_f32: &mut Vec<f32>) {
+        self.swap_image(cx, image_f32)
+    }
+    
+    fn swap_image<T: TextureImageValue>(&self, cx: &mut Cx, image: &mut Vec<T>) {
         let cxtexture = &mut cx.textures[self.texture_id()];
-        std::mem::swap(&mut cxtexture.image_u32, image_u32);
+        std::mem::swap(T::image_mut(cxtexture), image);
         cxtexture.update_image = true;
+        cxtexture.update_rect = None;
+    }
+    
+    /// Writes `data` into a rectangle of the image, only that part is uploaded again.
+    /// The image has to be set in full first.
+    pub fn update_image_rect<T: TextureImageValue>(&self, cx: &mut Cx, rect: TextureRect, data: &[T]) {
+        let cxtexture = &mut cx.textures[self.texture_id()];
+        let desc = cxtexture.desc;
+        if !T::accepts_format(desc.format) {
+            error!("update_image_rect: values don't match texture format {:?}", desc.format);
+            return
+        }
+        let (width, height) = if let (Some(width), Some(height)) = (desc.width, desc.height) {(width, height)} else {
+            error!("update_image_rect: texture has no size");
+            return
+        };
+        let values = desc.format.values_per_pixel();
+        if rect.x + rect.width > width || rect.y + rect.height > height || data.len() != rect.width * rect.height * values {
+            error!("update_image_rect: {:?} with {} values does not fit a {}x{} texture", rect, data.len(), width, height);
+            return
+        }
+        let image = T::image_mut(cxtexture);
+        if image.len() != width * height * values {
+            error!("update_image_rect: texture image not set");
+            return
+        }
+        for row in 0..rect.height {
+            let start = ((rect.y + row) * width + rect.x) * values;
+            image[start..start + rect.width * values].copy_from_slice(&data[row * rect.width * values..(row + 1) * rect.width * values]);
+        }
+        cxtexture.update_rect = Some(match cxtexture.update_rect {
+            Some(update_rect) => update_rect.union(&rect),
+            None => rect
+        });
+    }
+    
+    /// Whether the mipmaps of image textures are generated, they are by default.
+    pub fn set_mipmaps(&self, cx: &mut Cx, mipmaps: bool) {
+        let cxtexture = &mut cx.textures[self.texture_id()];
+        if cxtexture.mipmaps != mipmaps {
+            cxtexture.mipmaps = mipmaps;
+            cxtexture.update_image = true;
+        }
     }
 }
 
 
-#[derive(Default)]
 pub struct CxTexture {
     pub (crate) desc: TextureDesc,
     pub (crate) image_u32: Vec<u32>,
-    //pub(crate) _image_f32: Vec<f32>,
+    pub (crate) image_u8: Vec<u8>,
+    pub (crate) image_f16: Vec<u16>,
+    pub (crate) image_f32: Vec<f32>,
     pub (crate) update_image: bool,
+    // the part changed with update_image_rect since the last upload
+    pub (crate) update_rect: Option<TextureRect>,
+    pub (crate) mipmaps: bool,
     pub os: CxOsTexture
 }
+
+impl Default for CxTexture {
+    fn default() -> Self {
+        Self {
+            desc: TextureDesc::default(),
+            image_u32: Vec::new(),
+            image_u8: Vec::new(),
+            image_f16: Vec::new(),
+            image_f32: Vec::new(),
+            update_image: false,
+            update_rect: None,
+            mipmaps: true,
+            os: CxOsTexture::default()
+        }
+    }
+}
+
+impl CxTexture {
+    /// The image in the format of the texture as bytes, with the number of pixels in it.
+    pub (crate) fn image_bytes(&self) -> (&[u8], usize) {
+        fn as_bytes<T>(image: &[T]) -> &[u8] {
+            unsafe {std::slice::from_raw_parts(image.as_ptr() as *const u8, std::mem::size_of_val(image))}
+        }
+        let values = self.desc.format.values_per_pixel();
+        match self.desc.format {
+            TextureFormat::ImageR8 | TextureFormat::ImageRG8 => (&self.image_u8, self.image_u8.len() / values),
+            TextureFormat::ImageRGBAf16 => (as_bytes(&self.image_f16), self.image_f16.len() / values),
+            TextureFormat::ImageRf32 | TextureFormat::ImageRGf32 | TextureFormat::ImageRGBAf32 => (as_bytes(&self.image_f32), self.image_f32.len() / values),
+            _ => (as_bytes(&self.image_u32), self.image_u32.len()),
+        }
+    }
+}
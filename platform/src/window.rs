@@ -126,8 +126,14 @@ impl LiveApply for WindowHandle {
 
 impl WindowHandle {
     pub fn set_pass(&self, cx: &mut Cx, pass: &Pass) {
+        let capture_requests = std::mem::take(&mut cx.windows[self.window_id()].capture_requests);
         cx.windows[self.window_id()].main_pass_id = Some(pass.pass_id());
-        cx.passes[pass.pass_id()].parent = CxPassParent::Window(self.window_id());
+        let cxpass = &mut cx.passes[pass.pass_id()];
+        cxpass.parent = CxPassParent::Window(self.window_id());
+        if !capture_requests.is_empty() {
+            cxpass.capture_requests.extend(capture_requests);
+            cxpass.paint_dirty = true;
+        }
     }
     
     pub fn get_inner_size(&mut self, cx: &mut Cx) -> DVec2 {
@@ -138,6 +144,19 @@ impl WindowHandle {
         cx.windows[self.window_id()].get_position()
     }
     
+    /// Captures what the window shows the next time it is painted, see `Pass::capture_image`.
+    /// Linux and Android only, on Metal, D3D11 and WebGL the event carries an error.
+    pub fn capture_image(&self, cx: &mut Cx, request_id: LiveId) {
+        if let Some(pass_id) = cx.windows[self.window_id()].main_pass_id {
+            let cxpass = &mut cx.passes[pass_id];
+            cxpass.capture_requests.push(request_id);
+            cxpass.paint_dirty = true;
+        }
+        else {
+            cx.windows[self.window_id()].capture_requests.push(request_id);
+        }
+    }
+    
     pub fn minimize(&mut self, cx: &mut Cx) {
         cx.push_unique_platform_op(CxOsOp::MinimizeWindow(self.window_id()));
     }
@@ -192,6 +211,9 @@ pub struct CxWindow {
     pub is_created: bool,
     pub window_geom: WindowGeom,
     pub main_pass_id: Option<PassId>,
+    /// Request ids of `capture_image` calls made before the window had a pass, they move
+    /// to the pass when it is set.
+    pub capture_requests: Vec<LiveId>,
 }
 
 impl CxWindow {
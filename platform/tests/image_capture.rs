@@ -0,0 +1,42 @@
+use makepad_platform::*;
+
+fn cx() -> Cx {
+    Cx::new(Box::new( | _, _ | {}))
+}
+
+#[test]
+fn unread_captures_fail_after_the_paint() {
+    let mut cx = cx();
+    let pass = Pass::new(&mut cx);
+    pass.capture_image(&mut cx, live_id!(first));
+    pass.capture_image(&mut cx, live_id!(second));
+
+    // still waiting for the pass to be painted
+    assert!(cx.passes[pass.pass_id()].paint_dirty);
+    assert!(cx.passes[pass.pass_id()].fail_capture_requests(pass.pass_id(), "no capture").is_empty());
+
+    // painted by a backend that didn't read it back
+    cx.passes[pass.pass_id()].paint_dirty = false;
+    let failed = cx.passes[pass.pass_id()].fail_capture_requests(pass.pass_id(), "no capture");
+    let ids: Vec<LiveId> = failed.iter().map( | event | event.request_id).collect();
+    assert_eq!(ids, vec![live_id!(first), live_id!(second)]);
+    for event in &failed {
+        assert_eq!(event.pass_id, pass.pass_id());
+        assert_eq!(event.result.as_ref().unwrap_err(), "no capture");
+    }
+    // every request is answered once
+    assert!(cx.passes[pass.pass_id()].fail_capture_requests(pass.pass_id(), "no capture").is_empty());
+}
+
+#[test]
+fn window_captures_wait_for_the_pass() {
+    let mut cx = cx();
+    let window = WindowHandle::new(&mut cx);
+    window.capture_image(&mut cx, live_id!(shot));
+
+    let pass = Pass::new(&mut cx);
+    window.set_pass(&mut cx, &pass);
+    assert_eq!(cx.passes[pass.pass_id()].capture_requests, vec![live_id!(shot)]);
+    assert!(cx.passes[pass.pass_id()].paint_dirty);
+    assert!(cx.windows[window.window_id()].capture_requests.is_empty());
+}
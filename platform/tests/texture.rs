@@ -0,0 +1,84 @@
+use makepad_platform::*;
+
+fn rect(x: usize, y: usize, width: usize, height: usize) -> TextureRect {
+    TextureRect {x, y, width, height}
+}
+
+// a 4x3 texture of the given format, without an image yet
+fn new_texture(cx: &mut Cx, format: TextureFormat) -> Texture {
+    let texture = Texture::new(cx);
+    texture.set_desc(cx, TextureDesc {format, width: Some(4), height: Some(3)});
+    texture
+}
+
+#[test]
+fn union() {
+    assert_eq!(rect(1, 2, 3, 4).union(&rect(1, 2, 3, 4)), rect(1, 2, 3, 4));
+    assert_eq!(rect(0, 0, 2, 2).union(&rect(3, 1, 1, 4)), rect(0, 0, 4, 5));
+    // one inside the other
+    assert_eq!(rect(2, 2, 1, 1).union(&rect(0, 0, 8, 8)), rect(0, 0, 8, 8));
+}
+
+#[test]
+fn partial_write() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    let texture = new_texture(&mut cx, TextureFormat::ImageBGRA);
+    texture.swap_image_u32(&mut cx, &mut vec![0; 12]);
+    texture.update_image_rect(&mut cx, rect(1, 1, 2, 2), &[1u32, 2, 3, 4]);
+    // rects written one after the other both land
+    texture.update_image_rect(&mut cx, rect(3, 0, 1, 1), &[5u32]);
+    
+    let mut image = Vec::new();
+    texture.swap_image_u32(&mut cx, &mut image);
+    assert_eq!(image, vec![
+        0, 0, 0, 5,
+        0, 1, 2, 0,
+        0, 3, 4, 0,
+    ]);
+    
+    // formats with several values per pixel write whole pixels
+    let texture = new_texture(&mut cx, TextureFormat::ImageRG8);
+    texture.swap_image_u8(&mut cx, &mut vec![0; 24]);
+    texture.update_image_rect(&mut cx, rect(3, 2, 1, 1), &[7u8, 8]);
+    let mut image = Vec::new();
+    texture.swap_image_u8(&mut cx, &mut image);
+    assert_eq!(&image[20..], &[0, 0, 7, 8]);
+    assert!(image[..22].iter().all( | value | *value == 0));
+}
+
+#[test]
+fn rejects_out_of_range_rects() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    let texture = new_texture(&mut cx, TextureFormat::ImageBGRA);
+    texture.swap_image_u32(&mut cx, &mut vec![0; 12]);
+    // past the right and bottom edge
+    texture.update_image_rect(&mut cx, rect(3, 0, 2, 1), &[1u32, 1]);
+    texture.update_image_rect(&mut cx, rect(0, 2, 1, 2), &[1u32, 1]);
+    // not as many values as the rect has pixels
+    texture.update_image_rect(&mut cx, rect(0, 0, 2, 2), &[1u32, 1, 1]);
+    
+    let mut image = Vec::new();
+    texture.swap_image_u32(&mut cx, &mut image);
+    assert_eq!(image, vec![0; 12]);
+}
+
+#[test]
+fn rejects_format_mismatch() {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    let texture = new_texture(&mut cx, TextureFormat::ImageBGRA);
+    texture.swap_image_u32(&mut cx, &mut vec![0; 12]);
+    texture.update_image_rect(&mut cx, rect(0, 0, 1, 1), &[1u8]);
+    texture.update_image_rect(&mut cx, rect(0, 0, 1, 1), &[1.0f32]);
+    
+    let mut image = Vec::new();
+    texture.swap_image_u32(&mut cx, &mut image);
+    assert_eq!(image, vec![0; 12]);
+    
+    // an u8 image is not written through with the values of another format either
+    let texture = new_texture(&mut cx, TextureFormat::ImageR8);
+    texture.swap_image_u8(&mut cx, &mut vec![0; 12]);
+    texture.update_image_rect(&mut cx, rect(0, 0, 1, 1), &[1u32]);
+    let mut image = Vec::new();
+    texture.swap_image_u8(&mut cx, &mut image);
+    assert_eq!(image, vec![0; 12]);
+}
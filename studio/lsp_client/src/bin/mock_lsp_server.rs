@@ -0,0 +1,228 @@
+//! A tiny language server for testing clients against. It keeps the documents it is sent in sync,
+//! and treats them as plain words:
+//!
+//! - every `todo` is reported as a warning,
+//! - hovering a word shows the word,
+//! - completion offers the other words of the document that start with the word before the
+//!   cursor,
+//! - the definition of a word is the word after the `fn` that precedes it.
+
+use {
+    makepad_lsp_client::{
+        protocol::{
+            read_message, write_message, ContentChange, Diagnostic, DiagnosticSeverity,
+            Location, Position, Range,
+        },
+        JsonValue,
+    },
+    std::{
+        collections::{BTreeSet, HashMap},
+        io,
+        io::{BufReader, Write},
+    },
+};
+
+fn main() {
+    let stdin = io::stdin();
+    let mut stdin = BufReader::new(stdin.lock());
+    let mut stdout = io::stdout();
+    let mut documents: HashMap<String, String> = HashMap::new();
+    while let Ok(Some(message)) = read_message(&mut stdin) {
+        let params = &message["params"];
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("").to_string();
+        let position = Position::from_json(&params["position"]).unwrap_or_default();
+        let result = match message["method"].as_str().unwrap_or("") {
+            "initialize" => JsonValue::object([(
+                "capabilities",
+                JsonValue::object([
+                    ("textDocumentSync", 2u64.into()),
+                    ("hoverProvider", true.into()),
+                    ("completionProvider", JsonValue::object([])),
+                    ("definitionProvider", true.into()),
+                ]),
+            )]),
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                documents.insert(uri.clone(), text.to_string());
+                publish_diagnostics(&mut stdout, &uri, 0, &documents[&uri]);
+                continue;
+            }
+            "textDocument/didChange" => {
+                let text = documents.entry(uri.clone()).or_default();
+                for change in params["contentChanges"].as_array().unwrap_or(&[]) {
+                    let change = ContentChange::from_json(change).unwrap();
+                    match change.range {
+                        Some(range) => {
+                            let start = byte_offset(text, range.start);
+                            let end = byte_offset(text, range.end);
+                            text.replace_range(start..end, &change.text);
+                        }
+                        None => *text = change.text,
+                    }
+                }
+                let version = params["textDocument"]["version"].as_i64().unwrap_or(0);
+                publish_diagnostics(&mut stdout, &uri, version, text);
+                continue;
+            }
+            "textDocument/didClose" => {
+                documents.remove(&uri);
+                continue;
+            }
+            "textDocument/hover" => match word_at(&documents[&uri], position) {
+                Some((word, range)) => JsonValue::object([
+                    (
+                        "contents",
+                        JsonValue::object([("kind", "markdown".into()), ("value", word.into())]),
+                    ),
+                    ("range", range.to_json()),
+                ]),
+                None => JsonValue::Null,
+            },
+            "textDocument/completion" => {
+                let text = &documents[&uri];
+                // the part of the word before the cursor
+                let prefix = word_at(text, position).map_or("", |(word, range)| {
+                    let character = position.character - range.start.character;
+                    &word[..Position::new(0, character).byte_index(word)]
+                });
+                let words: BTreeSet<_> = words(text)
+                    .map(|(word, _)| word)
+                    .filter(|word| word.starts_with(prefix) && *word != prefix)
+                    .collect();
+                JsonValue::object([
+                    ("isIncomplete", false.into()),
+                    (
+                        "items",
+                        words
+                            .into_iter()
+                            .map(|word| {
+                                JsonValue::object([("label", word.into()), ("detail", "word".into())])
+                            })
+                            .collect::<Vec<_>>()
+                            .into(),
+                    ),
+                ])
+            }
+            "textDocument/definition" => {
+                let text = &documents[&uri];
+                let definition = word_at(text, position).and_then(|(word, _)| {
+                    let words: Vec<_> = words(text).collect();
+                    words
+                        .windows(2)
+                        .find(|pair| pair[0].0 == "fn" && pair[1].0 == word)
+                        .map(|pair| pair[1].1)
+                });
+                match definition {
+                    Some(range) => Location {
+                        uri: uri.clone(),
+                        range,
+                    }
+                    .to_json(),
+                    None => JsonValue::Null,
+                }
+            }
+            "shutdown" => JsonValue::Null,
+            "exit" => return,
+            _ if message["id"].is_null() => continue,
+            method => {
+                write_message(
+                    &mut stdout,
+                    &JsonValue::object([
+                        ("jsonrpc", "2.0".into()),
+                        ("id", message["id"].clone()),
+                        (
+                            "error",
+                            JsonValue::object([
+                                ("code", (-32601i64).into()),
+                                ("message", format!("unknown method {}", method).into()),
+                            ]),
+                        ),
+                    ]),
+                )
+                .unwrap();
+                continue;
+            }
+        };
+        write_message(
+            &mut stdout,
+            &JsonValue::object([
+                ("jsonrpc", "2.0".into()),
+                ("id", message["id"].clone()),
+                ("result", result),
+            ]),
+        )
+        .unwrap();
+    }
+}
+
+fn publish_diagnostics(stdout: &mut impl Write, uri: &str, version: i64, text: &str) {
+    let diagnostics: Vec<_> = words(text)
+        .filter(|(word, _)| *word == "todo")
+        .map(|(_, range)| {
+            Diagnostic {
+                range,
+                severity: Some(DiagnosticSeverity::Warning),
+                message: "found todo".to_string(),
+            }
+            .to_json()
+        })
+        .collect();
+    write_message(
+        stdout,
+        &JsonValue::object([
+            ("jsonrpc", "2.0".into()),
+            ("method", "textDocument/publishDiagnostics".into()),
+            (
+                "params",
+                JsonValue::object([
+                    ("uri", uri.into()),
+                    ("version", version.into()),
+                    ("diagnostics", diagnostics.into()),
+                ]),
+            ),
+        ]),
+    )
+    .unwrap();
+}
+
+// the words of `text`, which are runs of alphanumeric characters and underscores
+fn words(text: &str) -> impl Iterator<Item = (&str, Range)> {
+    text.lines().enumerate().flat_map(|(line_index, line)| {
+        let mut words = Vec::new();
+        let mut start = None;
+        for (byte_index, char) in line.char_indices().chain([(line.len(), ' ')]) {
+            let is_word_char = char.is_alphanumeric() || char == '_';
+            match start {
+                None if is_word_char => start = Some(byte_index),
+                Some(start_byte_index) if !is_word_char => {
+                    words.push((
+                        &line[start_byte_index..byte_index],
+                        Range::new(
+                            Position::from_byte_index(line_index, line, start_byte_index),
+                            Position::from_byte_index(line_index, line, byte_index),
+                        ),
+                    ));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        words
+    })
+}
+
+// the word that contains or ends at `position`
+fn word_at(text: &str, position: Position) -> Option<(&str, Range)> {
+    words(text).find(|(_, range)| range.start <= position && position <= range.end)
+}
+
+fn byte_offset(text: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (line_index, line) in text.split('\n').enumerate() {
+        if line_index == position.line as usize {
+            return offset + position.byte_index(line);
+        }
+        offset += line.len() + 1;
+    }
+    text.len()
+}
@@ -0,0 +1,457 @@
+use {
+    crate::protocol::{
+        path_to_uri, read_message, write_message, CompletionItem, ContentChange, Diagnostic, Hover,
+        Location, Position,
+    },
+    makepad_micro_serde::JsonValue,
+    std::{
+        collections::HashMap,
+        io,
+        io::BufReader,
+        path::Path,
+        process::{Child, ChildStdin, Command, Stdio},
+        sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError},
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// How to start a language server, and which files it is for.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServerConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub language_id: String,
+    pub extensions: Vec<String>,
+}
+
+impl ServerConfig {
+    pub fn rust_analyzer() -> Self {
+        Self {
+            command: "rust-analyzer".to_string(),
+            args: Vec::new(),
+            language_id: "rust".to_string(),
+            extensions: vec!["rs".to_string()],
+        }
+    }
+
+    pub fn handles(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| self.extensions.iter().any(|other| other == extension))
+    }
+}
+
+pub type RequestId = u64;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LspEvent {
+    /// The server answered the `initialize` request, and the messages that were queued until
+    /// then have been sent.
+    Initialized,
+    Diagnostics {
+        uri: String,
+        version: Option<i64>,
+        diagnostics: Vec<Diagnostic>,
+    },
+    Hover {
+        id: RequestId,
+        hover: Option<Hover>,
+    },
+    Completion {
+        id: RequestId,
+        items: Vec<CompletionItem>,
+    },
+    Definition {
+        id: RequestId,
+        locations: Vec<Location>,
+    },
+    /// The server answered a request with an error.
+    Error {
+        id: RequestId,
+        message: String,
+    },
+    /// The server closed its output, or sent something that isn't a message.
+    Exited,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum RequestKind {
+    Initialize,
+    Hover,
+    Completion,
+    Definition,
+    Shutdown,
+}
+
+/// A connection to a language server that runs as a child process and talks over its stdin and
+/// stdout.
+///
+/// Messages are read on a thread, which calls the `notify` callback passed to `spawn` whenever
+/// one arrives, so that the host can wake up and collect the events with `try_event`.
+pub struct LspClient {
+    config: ServerConfig,
+    child: Child,
+    stdin: ChildStdin,
+    message_receiver: Receiver<Option<JsonValue>>,
+    next_request_id: RequestId,
+    pending_requests: HashMap<RequestId, RequestKind>,
+    is_initialized: bool,
+    // messages that can't be sent before the server is initialized
+    queued_messages: Vec<JsonValue>,
+    versions: HashMap<String, i64>,
+}
+
+impl LspClient {
+    /// Starts the server for the workspace at `root_path`, and sends it the `initialize` request.
+    /// Until the server answers, the other messages are queued.
+    pub fn spawn(
+        config: &ServerConfig,
+        root_path: &Path,
+        notify: Box<dyn Fn() + Send>,
+    ) -> io::Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(root_path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap());
+        let (message_sender, message_receiver) = mpsc::channel();
+        thread::spawn(move || loop {
+            let message = read_message(&mut stdout).ok().flatten();
+            let is_end = message.is_none();
+            if message_sender.send(message).is_err() {
+                break;
+            }
+            notify();
+            if is_end {
+                break;
+            }
+        });
+        let mut client = Self {
+            config: config.clone(),
+            child,
+            stdin,
+            message_receiver,
+            next_request_id: 0,
+            pending_requests: HashMap::new(),
+            is_initialized: false,
+            queued_messages: Vec::new(),
+            versions: HashMap::new(),
+        };
+        let root_uri = path_to_uri(root_path);
+        client.request(
+            RequestKind::Initialize,
+            "initialize",
+            JsonValue::object([
+                ("processId", (std::process::id() as u64).into()),
+                ("rootUri", root_uri.as_str().into()),
+                (
+                    "workspaceFolders",
+                    vec![JsonValue::object([
+                        ("uri", root_uri.as_str().into()),
+                        ("name", "root".into()),
+                    ])]
+                    .into(),
+                ),
+                ("capabilities", capabilities()),
+            ]),
+        )?;
+        Ok(client)
+    }
+
+    pub fn config(&self) -> &ServerConfig {
+        &self.config
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+
+    /// The version of a document that was last sent to the server.
+    pub fn version(&self, uri: &str) -> Option<i64> {
+        self.versions.get(uri).copied()
+    }
+
+    pub fn did_open(&mut self, uri: &str, text: &str) -> io::Result<()> {
+        self.versions.insert(uri.to_string(), 0);
+        self.notify(
+            "textDocument/didOpen",
+            JsonValue::object([(
+                "textDocument",
+                JsonValue::object([
+                    ("uri", uri.into()),
+                    ("languageId", self.config.language_id.as_str().into()),
+                    ("version", 0i64.into()),
+                    ("text", text.into()),
+                ]),
+            )]),
+        )
+    }
+
+    /// Sends changes to an open document, which the server applies in order, each to the text
+    /// that the ones before it produced.
+    pub fn did_change(&mut self, uri: &str, changes: &[ContentChange]) -> io::Result<()> {
+        let version = self.versions.entry(uri.to_string()).or_insert(0);
+        *version += 1;
+        let version = *version;
+        self.notify(
+            "textDocument/didChange",
+            JsonValue::object([
+                (
+                    "textDocument",
+                    JsonValue::object([("uri", uri.into()), ("version", version.into())]),
+                ),
+                (
+                    "contentChanges",
+                    changes.iter().map(ContentChange::to_json).collect::<Vec<_>>().into(),
+                ),
+            ]),
+        )
+    }
+
+    pub fn did_close(&mut self, uri: &str) -> io::Result<()> {
+        self.versions.remove(uri);
+        self.notify(
+            "textDocument/didClose",
+            JsonValue::object([("textDocument", JsonValue::object([("uri", uri.into())]))]),
+        )
+    }
+
+    pub fn hover(&mut self, uri: &str, position: Position) -> io::Result<RequestId> {
+        self.request(RequestKind::Hover, "textDocument/hover", position_params(uri, position))
+    }
+
+    pub fn completion(&mut self, uri: &str, position: Position) -> io::Result<RequestId> {
+        self.request(
+            RequestKind::Completion,
+            "textDocument/completion",
+            position_params(uri, position),
+        )
+    }
+
+    pub fn definition(&mut self, uri: &str, position: Position) -> io::Result<RequestId> {
+        self.request(
+            RequestKind::Definition,
+            "textDocument/definition",
+            position_params(uri, position),
+        )
+    }
+
+    /// Returns the next event without waiting, handling the messages that don't produce one on
+    /// the way.
+    pub fn try_event(&mut self) -> Option<LspEvent> {
+        loop {
+            let message = match self.message_receiver.try_recv() {
+                Ok(message) => message,
+                Err(TryRecvError::Empty) => return None,
+                Err(TryRecvError::Disconnected) => return None,
+            };
+            if let Some(event) = self.handle_message(message) {
+                return Some(event);
+            }
+        }
+    }
+
+    /// Returns the next event, waiting at most `timeout` for it.
+    pub fn event_timeout(&mut self, timeout: Duration) -> Option<LspEvent> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let message = match self.message_receiver.recv_timeout(timeout) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => return None,
+                Err(RecvTimeoutError::Disconnected) => return None,
+            };
+            if let Some(event) = self.handle_message(message) {
+                return Some(event);
+            }
+        }
+    }
+
+    fn handle_message(&mut self, message: Option<JsonValue>) -> Option<LspEvent> {
+        let Some(message) = message else {
+            return Some(LspEvent::Exited);
+        };
+        let method = message["method"].as_str();
+        let id = &message["id"];
+        match (method, id) {
+            // a response to one of our requests
+            (None, id) => {
+                let id = id.as_u64()?;
+                let kind = self.pending_requests.remove(&id)?;
+                if let Some(message) = message["error"]["message"].as_str() {
+                    return Some(LspEvent::Error {
+                        id,
+                        message: message.to_string(),
+                    });
+                }
+                let result = &message["result"];
+                match kind {
+                    RequestKind::Initialize => {
+                        self.is_initialized = true;
+                        let result = self
+                            .send(&notification("initialized", JsonValue::object([])))
+                            .and_then(|_| {
+                                for message in std::mem::take(&mut self.queued_messages) {
+                                    self.send(&message)?;
+                                }
+                                Ok(())
+                            });
+                        Some(match result {
+                            Ok(()) => LspEvent::Initialized,
+                            Err(_) => LspEvent::Exited,
+                        })
+                    }
+                    RequestKind::Hover => Some(LspEvent::Hover {
+                        id,
+                        hover: Hover::from_json(result),
+                    }),
+                    RequestKind::Completion => Some(LspEvent::Completion {
+                        id,
+                        items: CompletionItem::list_from_json(result),
+                    }),
+                    RequestKind::Definition => Some(LspEvent::Definition {
+                        id,
+                        locations: Location::list_from_json(result),
+                    }),
+                    RequestKind::Shutdown => None,
+                }
+            }
+            (Some("textDocument/publishDiagnostics"), JsonValue::Null) => {
+                let params = &message["params"];
+                Some(LspEvent::Diagnostics {
+                    uri: params["uri"].as_str()?.to_string(),
+                    version: params["version"].as_i64(),
+                    diagnostics: params["diagnostics"]
+                        .as_array()
+                        .unwrap_or(&[])
+                        .iter()
+                        .filter_map(Diagnostic::from_json)
+                        .collect(),
+                })
+            }
+            (Some(_), JsonValue::Null) => None,
+            // a request from the server, which gets the most neutral answer we can give
+            (Some(method), id) => {
+                let result = match method {
+                    "workspace/configuration" => JsonValue::Array(
+                        message["params"]["items"]
+                            .as_array()
+                            .unwrap_or(&[])
+                            .iter()
+                            .map(|_| JsonValue::Null)
+                            .collect(),
+                    ),
+                    _ => JsonValue::Null,
+                };
+                let _ = self.send(&JsonValue::object([
+                    ("jsonrpc", "2.0".into()),
+                    ("id", id.clone()),
+                    ("result", result),
+                ]));
+                None
+            }
+        }
+    }
+
+    fn request(&mut self, kind: RequestKind, method: &str, params: JsonValue) -> io::Result<RequestId> {
+        let id = self.next_request_id;
+        self.next_request_id += 1;
+        self.pending_requests.insert(id, kind);
+        let message = JsonValue::object([
+            ("jsonrpc", "2.0".into()),
+            ("id", id.into()),
+            ("method", method.into()),
+            ("params", params),
+        ]);
+        if kind == RequestKind::Initialize || self.is_initialized {
+            self.send(&message)?;
+        } else {
+            self.queued_messages.push(message);
+        }
+        Ok(id)
+    }
+
+    fn notify(&mut self, method: &str, params: JsonValue) -> io::Result<()> {
+        let message = notification(method, params);
+        if self.is_initialized {
+            self.send(&message)
+        } else {
+            self.queued_messages.push(message);
+            Ok(())
+        }
+    }
+
+    fn send(&mut self, message: &JsonValue) -> io::Result<()> {
+        write_message(&mut self.stdin, message)
+    }
+}
+
+impl Drop for LspClient {
+    /// Asks the server to shut down, and kills it if it is still running a moment later.
+    fn drop(&mut self) {
+        if self.is_initialized {
+            let _ = self.request(RequestKind::Shutdown, "shutdown", JsonValue::Null);
+            let _ = self.send(&notification("exit", JsonValue::Null));
+        }
+        let deadline = Instant::now() + Duration::from_millis(500);
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn notification(method: &str, params: JsonValue) -> JsonValue {
+    JsonValue::object([
+        ("jsonrpc", "2.0".into()),
+        ("method", method.into()),
+        ("params", params),
+    ])
+}
+
+fn position_params(uri: &str, position: Position) -> JsonValue {
+    JsonValue::object([
+        ("textDocument", JsonValue::object([("uri", uri.into())])),
+        ("position", position.to_json()),
+    ])
+}
+
+fn capabilities() -> JsonValue {
+    JsonValue::object([(
+        "textDocument",
+        JsonValue::object([
+            (
+                "synchronization",
+                JsonValue::object([("dynamicRegistration", false.into())]),
+            ),
+            (
+                "hover",
+                JsonValue::object([(
+                    "contentFormat",
+                    vec!["plaintext".into(), "markdown".into()].into(),
+                )]),
+            ),
+            (
+                "completion",
+                JsonValue::object([(
+                    "completionItem",
+                    JsonValue::object([("snippetSupport", false.into())]),
+                )]),
+            ),
+            ("definition", JsonValue::object([("linkSupport", true.into())])),
+            (
+                "publishDiagnostics",
+                JsonValue::object([("versionSupport", true.into())]),
+            ),
+        ]),
+    )])
+}
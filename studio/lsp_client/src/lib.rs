@@ -0,0 +1,7 @@
+pub mod client;
+pub mod protocol;
+
+pub use {
+    self::client::{LspClient, LspEvent, RequestId, ServerConfig},
+    makepad_micro_serde::{self, DeJsonErr, JsonValue},
+};
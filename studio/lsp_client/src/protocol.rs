@@ -0,0 +1,346 @@
+use {
+    makepad_micro_serde::JsonValue,
+    std::{
+        io,
+        io::{BufRead, Write},
+        path::{Path, PathBuf},
+    },
+};
+
+/// A position in a text document. As in the protocol, `character` counts UTF-16 code units from
+/// the start of the line.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+impl Position {
+    pub fn new(line: u32, character: u32) -> Self {
+        Self { line, character }
+    }
+
+    /// The position of the byte at `byte_index` in `line`.
+    pub fn from_byte_index(line_index: usize, line: &str, byte_index: usize) -> Self {
+        Self {
+            line: line_index as u32,
+            character: utf16_len(&line[..byte_index.min(line.len())]) as u32,
+        }
+    }
+
+    /// The byte index of this position in `line`, which is clamped to the end of the line and
+    /// rounded down to a char boundary.
+    pub fn byte_index(self, line: &str) -> usize {
+        let mut character = 0;
+        for (byte_index, char) in line.char_indices() {
+            if character >= self.character as usize {
+                return byte_index;
+            }
+            character += char.len_utf16();
+            if character > self.character as usize {
+                return byte_index;
+            }
+        }
+        line.len()
+    }
+
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        Some(Self {
+            line: json["line"].as_u64()? as u32,
+            character: json["character"].as_u64()? as u32,
+        })
+    }
+
+    pub fn to_json(self) -> JsonValue {
+        JsonValue::object([
+            ("line", self.line.into()),
+            ("character", self.character.into()),
+        ])
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Range {
+    pub fn new(start: Position, end: Position) -> Self {
+        Self { start, end }
+    }
+
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        Some(Self {
+            start: Position::from_json(&json["start"])?,
+            end: Position::from_json(&json["end"])?,
+        })
+    }
+
+    pub fn to_json(self) -> JsonValue {
+        JsonValue::object([("start", self.start.to_json()), ("end", self.end.to_json())])
+    }
+}
+
+/// A change to a document, which replaces `range` with `text`, or the whole document if there is
+/// no range.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContentChange {
+    pub range: Option<Range>,
+    pub text: String,
+}
+
+impl ContentChange {
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        Some(Self {
+            range: match &json["range"] {
+                JsonValue::Null => None,
+                range => Some(Range::from_json(range)?),
+            },
+            text: json["text"].as_str()?.to_string(),
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        let mut fields = Vec::new();
+        if let Some(range) = self.range {
+            fields.push(("range", range.to_json()));
+        }
+        fields.push(("text", self.text.as_str().into()));
+        JsonValue::object(fields)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+    Hint = 4,
+}
+
+impl DiagnosticSeverity {
+    pub fn from_u64(severity: u64) -> Option<Self> {
+        match severity {
+            1 => Some(Self::Error),
+            2 => Some(Self::Warning),
+            3 => Some(Self::Information),
+            4 => Some(Self::Hint),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: Option<DiagnosticSeverity>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        Some(Self {
+            range: Range::from_json(&json["range"])?,
+            severity: json["severity"].as_u64().and_then(DiagnosticSeverity::from_u64),
+            message: json["message"].as_str()?.to_string(),
+        })
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("range", self.range.to_json()),
+            ("severity", self.severity.map(|severity| severity as u64).into()),
+            ("message", self.message.as_str().into()),
+        ])
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Hover {
+    /// The contents as plain text or markdown. Servers can send several blocks, which are joined
+    /// by blank lines.
+    pub contents: String,
+    pub range: Option<Range>,
+}
+
+impl Hover {
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        fn marked_string(json: &JsonValue) -> Option<&str> {
+            json.as_str().or_else(|| json["value"].as_str())
+        }
+
+        let contents = match &json["contents"] {
+            JsonValue::Array(values) => values
+                .iter()
+                .filter_map(marked_string)
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            contents => marked_string(contents)?.to_string(),
+        };
+        Some(Self {
+            contents,
+            range: Range::from_json(&json["range"]),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompletionItem {
+    pub label: String,
+    pub detail: Option<String>,
+    /// The text to insert in place of the word before the cursor, which is the label unless the
+    /// server says otherwise.
+    pub insert_text: String,
+}
+
+impl CompletionItem {
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        let label = json["label"].as_str()?.to_string();
+        let insert_text = json["textEdit"]["newText"]
+            .as_str()
+            .or_else(|| json["insertText"].as_str())
+            .unwrap_or(&label)
+            .to_string();
+        Some(Self {
+            label,
+            detail: json["detail"].as_str().map(|detail| detail.to_string()),
+            insert_text,
+        })
+    }
+
+    /// Parses the result of a completion request, which is either a list of items or an object
+    /// that holds them.
+    pub fn list_from_json(json: &JsonValue) -> Vec<Self> {
+        json.as_array()
+            .or_else(|| json["items"].as_array())
+            .unwrap_or(&[])
+            .iter()
+            .filter_map(Self::from_json)
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+impl Location {
+    /// Parses a `Location`, or a `LocationLink` to the name of its target.
+    pub fn from_json(json: &JsonValue) -> Option<Self> {
+        match json["targetUri"].as_str() {
+            Some(uri) => Some(Self {
+                uri: uri.to_string(),
+                range: Range::from_json(&json["targetSelectionRange"])?,
+            }),
+            None => Some(Self {
+                uri: json["uri"].as_str()?.to_string(),
+                range: Range::from_json(&json["range"])?,
+            }),
+        }
+    }
+
+    pub fn to_json(&self) -> JsonValue {
+        JsonValue::object([
+            ("uri", self.uri.as_str().into()),
+            ("range", self.range.to_json()),
+        ])
+    }
+
+    /// Parses the result of a definition request, which is nothing, one location or a list.
+    pub fn list_from_json(json: &JsonValue) -> Vec<Self> {
+        match json {
+            JsonValue::Array(values) => values.iter().filter_map(Self::from_json).collect(),
+            json => Self::from_json(json).into_iter().collect(),
+        }
+    }
+}
+
+pub fn utf16_len(string: &str) -> usize {
+    string.chars().map(char::len_utf16).sum()
+}
+
+/// Turns an absolute path into a `file://` URI, escaping the bytes that can't appear in one.
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            byte => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+/// Turns a `file://` URI back into a path, or returns `None` for other schemes.
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut iter = path.bytes();
+    while let Some(byte) = iter.next() {
+        if byte == b'%' {
+            let digits = [iter.next()?, iter.next()?];
+            bytes.push(u8::from_str_radix(std::str::from_utf8(&digits).ok()?, 16).ok()?);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    let path = String::from_utf8(bytes).ok()?;
+    // `file:///C:/dir` is `C:/dir` on Windows
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() => &path[1..],
+        _ => &path,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// Writes a message with the `Content-Length` header that the protocol frames messages with.
+pub fn write_message(writer: &mut impl Write, message: &JsonValue) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+/// Reads the next message, or returns `None` if the stream ended between messages.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<JsonValue>> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return match content_length {
+                None => Ok(None),
+                Some(_) => Err(io::ErrorKind::UnexpectedEof.into()),
+            };
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse::<usize>().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "invalid Content-Length")
+                })?);
+            }
+        }
+    }
+    let mut body = vec![0; content_length.unwrap()];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8(body)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    JsonValue::parse(&body)
+        .map(Some)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -0,0 +1,107 @@
+use {
+    makepad_lsp_client::{
+        protocol::{ContentChange, DiagnosticSeverity, Position, Range},
+        LspClient, LspEvent, ServerConfig,
+    },
+    std::{env, time::Duration},
+};
+
+const URI: &str = "file:///project/src/main.rs";
+
+fn spawn() -> LspClient {
+    let config = ServerConfig {
+        command: env!("CARGO_BIN_EXE_mock_lsp_server").to_string(),
+        ..ServerConfig::rust_analyzer()
+    };
+    LspClient::spawn(&config, &env::temp_dir(), Box::new(|| {})).unwrap()
+}
+
+fn next_event(client: &mut LspClient) -> LspEvent {
+    client.event_timeout(Duration::from_secs(10)).expect("no event from the server")
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+    Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1))
+}
+
+#[test]
+fn syncs_documents_incrementally() {
+    let mut client = spawn();
+    // this is queued until the server is initialized
+    client.did_open(URI, "fn main() {\n    todo\n}\n").unwrap();
+    assert!(!client.is_initialized());
+    assert_eq!(next_event(&mut client), LspEvent::Initialized);
+
+    let LspEvent::Diagnostics { uri, version, diagnostics } = next_event(&mut client) else {
+        panic!("expected diagnostics");
+    };
+    assert_eq!((uri.as_str(), version), (URI, Some(0)));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range, range((1, 4), (1, 8)));
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::Warning));
+
+    // each change applies to the text that the ones before it produced, and the columns count
+    // UTF-16 code units
+    client
+        .did_change(
+            URI,
+            &[
+                ContentChange {
+                    range: Some(range((1, 4), (1, 4))),
+                    text: "😀 ".to_string(),
+                },
+                ContentChange {
+                    range: Some(range((0, 0), (0, 0))),
+                    text: "// todo\n".to_string(),
+                },
+            ],
+        )
+        .unwrap();
+    let LspEvent::Diagnostics { version, diagnostics, .. } = next_event(&mut client) else {
+        panic!("expected diagnostics");
+    };
+    assert_eq!(version, Some(1));
+    assert_eq!(
+        diagnostics.iter().map(|diagnostic| diagnostic.range).collect::<Vec<_>>(),
+        vec![range((0, 3), (0, 7)), range((2, 7), (2, 11))]
+    );
+}
+
+#[test]
+fn answers_requests() {
+    let mut client = spawn();
+    client
+        .did_open(URI, "fn helper() {}\nfn main() {\n    help\n    helper();\n}\n")
+        .unwrap();
+    assert_eq!(next_event(&mut client), LspEvent::Initialized);
+    assert!(matches!(next_event(&mut client), LspEvent::Diagnostics { .. }));
+
+    let id = client.hover(URI, Position::new(3, 6)).unwrap();
+    let LspEvent::Hover { id: other_id, hover: Some(hover) } = next_event(&mut client) else {
+        panic!("expected hover");
+    };
+    assert_eq!(other_id, id);
+    assert_eq!(hover.contents, "helper");
+    assert_eq!(hover.range, Some(range((3, 4), (3, 10))));
+
+    let id = client.completion(URI, Position::new(2, 8)).unwrap();
+    let LspEvent::Completion { id: other_id, items } = next_event(&mut client) else {
+        panic!("expected completion");
+    };
+    assert_eq!(other_id, id);
+    assert_eq!(items.iter().map(|item| item.label.as_str()).collect::<Vec<_>>(), vec!["helper"]);
+    assert_eq!(items[0].insert_text, "helper");
+
+    let id = client.definition(URI, Position::new(3, 5)).unwrap();
+    let LspEvent::Definition { id: other_id, locations } = next_event(&mut client) else {
+        panic!("expected definition");
+    };
+    assert_eq!(other_id, id);
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].uri, URI);
+    assert_eq!(locations[0].range, range((0, 3), (0, 9)));
+
+    // there's no word at the end of the first line
+    client.hover(URI, Position::new(1, 11)).unwrap();
+    assert!(matches!(next_event(&mut client), LspEvent::Hover { hover: None, .. }));
+}
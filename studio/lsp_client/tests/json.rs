@@ -0,0 +1,64 @@
+use {
+    makepad_lsp_client::{
+        protocol::{path_to_uri, read_message, uri_to_path, write_message, Position},
+        JsonValue,
+    },
+    std::{io::BufReader, path::Path},
+};
+
+#[test]
+fn parses_and_prints_json() {
+    let value = JsonValue::parse(
+        r#" {"a": [1, -2.5e1, true, null], "b": {"c": "line\n\"quoted\" é 😀"}} "#,
+    )
+    .unwrap();
+    assert_eq!(value["a"][0].as_u64(), Some(1));
+    assert_eq!(value["a"][1].as_f64(), Some(-25.0));
+    assert_eq!(value["a"][2].as_bool(), Some(true));
+    assert!(value["a"][3].is_null());
+    assert!(value["missing"]["deeper"].is_null());
+    assert_eq!(value["b"]["c"].as_str(), Some("line\n\"quoted\" é 😀"));
+    assert_eq!(JsonValue::parse(&value.to_string()).unwrap(), value);
+    // control characters are escaped, and characters outside the basic plane can be
+    let value = JsonValue::parse(r#"["\u0001\u00e9\ud83d\ude00\b", 1e3, -2E-2]"#).unwrap();
+    assert_eq!(value[0].as_str(), Some("\u{1}é😀\u{8}"));
+    assert_eq!(value[1].as_u64(), Some(1000));
+    assert_eq!(value[2].as_f64(), Some(-0.02));
+    assert_eq!(value.to_string(), "[\"\\u0001é😀\\u0008\",1000,-0.02]");
+
+    for string in ["", "[1,", "{\"a\" 1}", "\"unterminated", "tru", "[1] 2", "\"\\u12\""] {
+        assert!(JsonValue::parse(string).is_err(), "{}", string);
+    }
+    assert!(JsonValue::parse(&"[".repeat(1000)).is_err());
+}
+
+#[test]
+fn frames_messages() {
+    let mut bytes = Vec::new();
+    let message = JsonValue::object([("id", 1u64.into()), ("method", "ünïcode".into())]);
+    write_message(&mut bytes, &message).unwrap();
+    write_message(&mut bytes, &JsonValue::Null).unwrap();
+    let string = String::from_utf8(bytes.clone()).unwrap();
+    assert!(string.starts_with("Content-Length: 29\r\n\r\n{"));
+
+    let mut reader = BufReader::new(bytes.as_slice());
+    assert_eq!(read_message(&mut reader).unwrap(), Some(message));
+    assert_eq!(read_message(&mut reader).unwrap(), Some(JsonValue::Null));
+    assert_eq!(read_message(&mut reader).unwrap(), None);
+}
+
+#[test]
+fn converts_positions_and_uris() {
+    // `é` is two bytes but one code unit, `😀` is four bytes but two code units
+    let line = "é😀x";
+    assert_eq!(Position::from_byte_index(3, line, 6), Position::new(3, 3));
+    assert_eq!(Position::new(3, 3).byte_index(line), 6);
+    assert_eq!(Position::new(3, 4).byte_index(line), 7);
+    // the middle of a surrogate pair, and past the end of the line
+    assert_eq!(Position::new(3, 2).byte_index(line), 2);
+    assert_eq!(Position::new(3, 9).byte_index(line), 7);
+
+    assert_eq!(path_to_uri(Path::new("/home/me/my file.rs")), "file:///home/me/my%20file.rs");
+    assert_eq!(uri_to_path("file:///home/me/my%20file.rs").unwrap(), Path::new("/home/me/my file.rs"));
+    assert_eq!(uri_to_path("untitled:1"), None);
+}
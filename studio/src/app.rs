@@ -1,10 +1,18 @@
 use crate::{
     makepad_code_editor::code_editor::*,
+    makepad_code_editor::diff_view::*,
     makepad_platform::*,
     makepad_draw::*,
     makepad_widgets::*,
     makepad_widgets::file_tree::*,
     file_system::file_system::*,
+    collab::collab_manager::{CollabConfig, CollabManager},
+    git::GitManager,
+    keymap::{StudioCommand, StudioKeymap},
+    lsp::{from_lsp_position, LspAction, LspManager, LspRequest},
+    recovery::RecoveryManager,
+    makepad_code_editor::text::Length,
+    makepad_lsp_client::protocol,
     build_manager::{
         run_view::*,
         log_list::{
@@ -19,12 +27,14 @@ use crate::{
         },
     }
 };
+use std::{collections::HashMap, rc::Rc};
 
 live_design!{
     import makepad_draw::shader::std::*;
     import makepad_widgets::base::*;
     import makepad_widgets::theme_desktop_dark::*;
     import makepad_code_editor::code_editor::CodeEditor;
+    import makepad_code_editor::diff_view::DiffView;
     
     import makepad_studio::build_manager::run_view::RunView;
     import makepad_studio::build_manager::log_list::LogList;
@@ -189,6 +199,7 @@ live_design!{
                 }
                 
                 CodeEditor = <CodeEditor> {}
+                DiffView = <DiffView> {}
                 EditFirst = <RectView> {
                     draw_bg: {color: #052329}
                     <View> {
@@ -255,6 +266,13 @@ pub struct App {
     #[live] ui: WidgetRef,
     #[live] build_manager: BuildManager,
     #[rust] file_system: FileSystem,
+    #[rust] keymap: StudioKeymap,
+    #[rust] lsp: LspManager,
+    #[rust] collab: CollabManager,
+    #[rust] recovery: RecoveryManager,
+    #[rust] git: GitManager,
+    // definitions to jump to once the tabs they were opened in have loaded
+    #[rust] pending_jumps: HashMap<LiveId, protocol::Position>,
 }
 
 impl LiveHook for App {
@@ -271,7 +289,13 @@ impl LiveHook for App {
     
     fn after_new_from_doc(&mut self, cx: &mut Cx) {
         self.file_system.init(cx);
-        self.build_manager.init(cx);
+        let collab_config = CollabConfig::load();
+        let collab_secret = collab_config.as_ref().filter(|config| config.host).map(|config| config.secret.clone());
+        self.build_manager.init(cx, collab_secret);
+        self.keymap = StudioKeymap::load();
+        self.lsp = LspManager::load();
+        self.collab = CollabManager::new(collab_config.as_ref());
+        self.recovery = RecoveryManager::load();
         
         //self.file_system.request_open_file(live_id!(file1), "examples/news_feed/src/app.rs".into());
     }
@@ -280,14 +304,33 @@ impl LiveHook for App {
 app_main!(App);
 
 impl App {
-    fn open_code_file_by_path(&mut self, cx: &mut Cx, path: &str) {
+    fn open_code_file_by_path(&mut self, cx: &mut Cx, path: &str) -> Option<LiveId> {
         let tab_id = LiveId::unique();
-        if let Some(file_id) = self.file_system.path_to_file_node_id(&path) {
-            self.file_system.request_open_file(tab_id, file_id);
-            let dock = self.ui.dock(id!(dock));
-            dock.create_and_select_tab(cx, live_id!(edit_tabs), tab_id, live_id!(CodeEditor), "".to_string(), TabClosable::Yes);
-            self.file_system.ensure_unique_tab_names(cx, &dock)
+        let file_id = self.file_system.path_to_file_node_id(&path)?;
+        self.file_system.request_open_file(tab_id, file_id);
+        let dock = self.ui.dock(id!(dock));
+        dock.create_and_select_tab(cx, live_id!(edit_tabs), tab_id, live_id!(CodeEditor), "".to_string(), TabClosable::Yes);
+        self.file_system.ensure_unique_tab_names(cx, &dock);
+        Some(tab_id)
+    }
+    
+    // opens a diff of the file of the focused editor against its version in the git index
+    fn show_diff(&mut self, cx: &mut Cx, dock: &DockRef) {
+        let focused_tab_id = dock.borrow_mut().unwrap().visible_items().find_map(|(item_id, item)| {
+            let editor = item.as_code_editor();
+            let editor = editor.borrow()?;
+            editor.has_key_focus(cx).then_some(item_id)
+        });
+        let Some(file_id) = focused_tab_id.and_then(|tab_id| self.file_system.tab_id_to_file_node_id.get(&tab_id).copied()) else {
+            return;
+        };
+        let tab_id = LiveId::unique();
+        if !self.git.open_diff(tab_id, file_id, &self.file_system) {
+            log!("{} has no version in the git index to compare with", self.file_system.file_node_path(file_id));
+            return;
         }
+        let name = format!("{} (diff)", self.file_system.file_node_name(file_id));
+        dock.create_and_select_tab(cx, live_id!(edit_tabs), tab_id, live_id!(DiffView), name, TabClosable::Yes);
     }
 }
 
@@ -329,6 +372,12 @@ impl AppMain for App {
                         code_editor.draw(cx, session);
                     }
                 }
+                else if let Some(mut diff_view) = next.as_diff_view().borrow_mut() {
+                    let current_id = dock.drawing_item_id().unwrap();
+                    if let Some((_, old_session, new_session)) = self.git.diff_mut(current_id) {
+                        diff_view.draw(cx, old_session, new_session);
+                    }
+                }
             }
             //profile_end!(dt);
             return
@@ -338,19 +387,19 @@ impl AppMain for App {
             self.build_manager.clear_active_builds();
         }
         
-        if let Event::KeyDown(KeyEvent {
-            key_code,
-            modifiers: KeyModifiers {logo, control, ..},
-            ..
-        }) = event {
-            if *control || *logo {
-                if let KeyCode::Backtick = key_code {
+        if let Event::KeyDown(KeyEvent {key_code, modifiers, ..}) = event {
+            match self.keymap.command(*key_code, *modifiers) {
+                Some(StudioCommand::Recompile) => {
                     self.build_manager.start_recompile(cx);
                 }
-                else if let KeyCode::KeyK = key_code {
+                Some(StudioCommand::ClearLog) => {
                     self.build_manager.clear_log(cx, &dock, &mut self.file_system);
                     log_list.redraw(cx);
                 }
+                Some(StudioCommand::ShowDiff) => {
+                    self.show_diff(cx, &dock);
+                }
+                None => {}
             }
         }
         
@@ -359,6 +408,10 @@ impl AppMain for App {
                 FileSystemAction::TreeLoaded => {
                     self.open_code_file_by_path(cx, "examples/news_feed/src/app.rs");
                 }
+                FileSystemAction::FileSaved {file_id, content} => {
+                    self.recovery.file_saved(file_id, &content);
+                    self.git.file_saved(cx, file_id);
+                }
                 FileSystemAction::RecompileNeeded => {
                     self.build_manager.start_recompile_timer(cx, &self.ui);
                 }
@@ -369,26 +422,123 @@ impl AppMain for App {
                 }
             }
         }
+        self.recovery.sync_documents(cx, &mut self.file_system, &dock);
+        self.recovery.handle_event(cx, event, &self.file_system);
+        self.collab.sync_documents(&mut self.file_system);
+        self.collab.handle_event(cx, event, &mut self.file_system, &dock);
+        self.lsp.sync_documents(&mut self.file_system);
+        
+        for action in self.lsp.handle_event(cx, event, &mut self.file_system, &dock) {
+            match action {
+                LspAction::ShowHover {tab_id, position, text} => {
+                    if let Some(mut editor) = dock.item(tab_id).as_code_editor().borrow_mut() {
+                        editor.show_tooltip(cx, position, &text);
+                    }
+                }
+                LspAction::ShowCompletions {tab_id, completions} => {
+                    if let Some(mut editor) = dock.item(tab_id).as_code_editor().borrow_mut() {
+                        if let Some(session) = self.file_system.get_session_mut(tab_id) {
+                            editor.show_completions(cx, session, completions);
+                        }
+                    }
+                }
+                LspAction::GoToDefinition {path, position} => {
+                    let Some(file_id) = self.file_system.path_to_file_node_id(&path) else {
+                        continue;
+                    };
+                    let tab_id = match self.file_system.file_node_id_to_tab_id(file_id) {
+                        Some(tab_id) => {
+                            dock.select_tab(cx, tab_id);
+                            Some(tab_id)
+                        }
+                        None => self.open_code_file_by_path(cx, &path),
+                    };
+                    let Some(tab_id) = tab_id else {
+                        continue;
+                    };
+                    match (dock.item(tab_id).as_code_editor().borrow_mut(), self.file_system.get_session_mut(tab_id)) {
+                        (Some(mut editor), Some(session)) => {
+                            let position = from_lsp_position(&session.document().as_text(), position);
+                            editor.set_cursor_and_scroll(cx, position, Length::zero(), session);
+                            editor.set_key_focus(cx);
+                        }
+                        _ => {
+                            self.pending_jumps.insert(tab_id, position);
+                        }
+                    }
+                }
+            }
+        }
         
         // lets iterate over the editors and handle events
+        let mut prompt_answers = Vec::new();
         for (item_id, item) in dock.borrow_mut().unwrap().visible_items() {
             if let Some(mut run_view) = item.as_run_view().borrow_mut() {
                 run_view.handle_event(cx, event, item_id, &mut self.build_manager);
             }
+            else if let Some(mut diff_view) = item.as_diff_view().borrow_mut() {
+                if let Some((file_id, old_session, new_session)) = self.git.diff_mut(item_id) {
+                    for action in diff_view.handle_event(cx, event, old_session, new_session) {
+                        match action {
+                            DiffViewAction::TextDidChange => {
+                                self.file_system.request_save_file_by_id(file_id);
+                            }
+                        }
+                    }
+                }
+            }
             else if let Some(mut code_editor) = item.as_code_editor().borrow_mut() {
+                if !Rc::ptr_eq(code_editor.keymap(), &self.keymap.editor) {
+                    code_editor.set_keymap(self.keymap.editor.clone());
+                }
+                let file_id = self.file_system.tab_id_to_file_node_id.get(&item_id).copied();
                 if let Some(session) = self.file_system.get_session_mut(item_id) {
-                    for action in code_editor.handle_event(cx, event, session) {
-                        match action {
+                    if let Some(position) = self.pending_jumps.remove(&item_id) {
+                        let position = from_lsp_position(&session.document().as_text(), position);
+                        code_editor.set_cursor_and_scroll(cx, position, Length::zero(), session);
+                        code_editor.set_key_focus(cx);
+                    }
+                    let actions = code_editor.handle_event(cx, event, session);
+                    if let Some(file_id) = file_id {
+                        self.collab.update_selections(item_id, file_id, &session.selections());
+                        if code_editor.has_key_focus(cx) && self.git.update_annotation(file_id, session) {
+                            code_editor.redraw(cx);
+                        }
+                    }
+                    for action in actions {
+                        let request = match action {
                             CodeEditorAction::TextDidChange => {
                                 // lets write the file
-                                self.file_system.request_save_file(item_id)
+                                self.file_system.request_save_file(item_id);
+                                continue;
                             }
+                            CodeEditorAction::RequestHover(position) => (LspRequest::Hover, position),
+                            CodeEditorAction::RequestCompletion(position) => (LspRequest::Completion, position),
+                            CodeEditorAction::GoToDefinition(position) => (LspRequest::Definition, position),
+                            CodeEditorAction::PromptAnswered(restore) => {
+                                // the dock is borrowed until all editors have handled the event
+                                if let Some(file_id) = file_id {
+                                    prompt_answers.push((file_id, restore));
+                                }
+                                continue;
+                            }
+                        };
+                        if let Some(file_id) = file_id {
+                            self.lsp.request(request.0, item_id, file_id, request.1);
                         }
                     }
                 }
             }
         }
         
+        for (file_id, restore) in prompt_answers {
+            self.recovery.answer_prompt(cx, file_id, restore, &mut self.file_system, &dock);
+        }
+        
+        // after the editors, so that the diff views see the edits they made
+        self.git.sync_documents(&mut self.file_system);
+        self.git.handle_event(cx, event, &mut self.file_system, &dock);
+        
         for action in self.build_manager.handle_event(cx, event, &mut self.file_system, &dock) {
             match action {
                 BuildManagerAction::RedrawLog => {
@@ -464,6 +614,9 @@ impl AppMain for App {
                 run_list.redraw(cx);
             }
             self.file_system.remove_tab(tab_id);
+            self.pending_jumps.remove(&tab_id);
+            self.collab.remove_tab(tab_id);
+            self.git.remove_tab(tab_id);
             self.file_system.ensure_unique_tab_names(cx, &dock);
         }
         
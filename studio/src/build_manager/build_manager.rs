@@ -15,9 +15,10 @@ use {
             build_protocol::*,
             build_client::BuildClient
         },
+        collab::collab_relay::CollabRelay,
         makepad_shell::*,
     },
-    makepad_code_editor::{text::Position, decoration::{Decoration}},
+    makepad_code_editor::{text::Position, decoration::{Decoration, Severity}},
     makepad_http::server::*,
     std::{
         collections::HashMap,
@@ -159,14 +160,15 @@ pub enum BuildManagerAction {
 
 impl BuildManager {
     
-    pub fn init(&mut self, cx: &mut Cx) {
+    /// Starts the http server, which runs a collab relay with the given secret if there is one.
+    pub fn init(&mut self, cx: &mut Cx, collab_secret: Option<String>) {
         // not great but it will do.
         self.clients = vec![BuildClient::new_with_local_server(&self.path)];
         self.update_run_list(cx);
         self.recompile_timer = cx.start_timeout(self.recompile_timeout);
         self.discover_external_ip(cx);
         // alright lets start our http server
-        self.start_http_server();
+        self.start_http_server(collab_secret);
     }
     
     
@@ -336,11 +338,12 @@ impl BuildManager {
                     if let Some(file_id) = file_system.path_to_file_node_id(&loc.file_name) {
                         if loc.level == LogItemLevel::Warning ||
                         loc.level == LogItemLevel::Error {
+                            let severity = if loc.level == LogItemLevel::Warning {Severity::Warning} else {Severity::Error};
                             file_system.add_decoration(file_id, Decoration::new(
                                 0,
                                 pos,
                                 pos + loc.length
-                            ));
+                            ).with_severity(severity));
                             file_system.redraw_view_by_file_id(cx, file_id, dock);
                         }
                     }
@@ -406,7 +409,7 @@ impl BuildManager {
         });
     }
     
-    pub fn start_http_server(&mut self) {
+    pub fn start_http_server(&mut self, collab_secret: Option<String>) {
         let addr = SocketAddr::new("0.0.0.0".parse().unwrap(), self.http_port as u16);
         let (tx_request, rx_request) = mpsc::channel::<HttpServerRequest> ();
         
@@ -470,15 +473,34 @@ impl BuildManager {
                 ("/makepad/".to_string(), makepad_path.clone()),
                 ("/".to_string(), "".to_string())
             ];
+            let mut collab_relay = collab_secret.map(CollabRelay::new);
             
             while let Ok(message) = rx_request.recv() {
                 // only store last change, fix later
                 match message {
-                    HttpServerRequest::ConnectWebSocket {web_socket_id: _, response_sender: _, headers: _} => {
+                    HttpServerRequest::ConnectWebSocket {web_socket_id, response_sender, headers} => {
+                        if headers.path == CollabRelay::PATH {
+                            match &mut collab_relay {
+                                Some(relay) if relay.is_authorized(&headers.lines) => {
+                                    relay.connect(web_socket_id, response_sender);
+                                }
+                                _ => {
+                                    log!("Refused a collab peer at {}", headers.addr);
+                                    // an empty message closes the web socket
+                                    let _ = response_sender.send(Vec::new());
+                                }
+                            }
+                        }
                     },
-                    HttpServerRequest::DisconnectWebSocket {web_socket_id: _} => {
+                    HttpServerRequest::DisconnectWebSocket {web_socket_id} => {
+                        if let Some(relay) = &mut collab_relay {
+                            relay.disconnect(web_socket_id);
+                        }
                     },
-                    HttpServerRequest::BinaryMessage {web_socket_id: _, response_sender: _, data: _} => {
+                    HttpServerRequest::BinaryMessage {web_socket_id, response_sender: _, data} => {
+                        if let Some(relay) = &mut collab_relay {
+                            relay.handle_message(web_socket_id, &data);
+                        }
                     }
                     HttpServerRequest::Get {headers, response_sender} => {
                         let path = &headers.path;
@@ -0,0 +1,133 @@
+use {
+    crate::{
+        collab::{
+            collab_protocol::{CollabNotification, CollabRequest},
+            collab_relay::CollabRelay,
+        },
+        makepad_micro_serde::*,
+        makepad_platform::*,
+    },
+    makepad_http::{
+        digest::base64_encode,
+        websocket::{mask_binary_message, WebSocket, WebSocketMessage},
+    },
+    std::{
+        io::{self, Read, Write},
+        net::{Shutdown, TcpStream},
+        sync::mpsc::{self, Receiver, Sender},
+        thread,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+pub enum CollabClientAction {
+    Notification(CollabNotification),
+    /// The connection to the relay could not be made, or was lost.
+    Disconnected(String),
+}
+
+/// A web socket connection to a collab relay, which runs on threads of its own. Requests that are
+/// sent before the connection is made are sent once it is.
+pub struct CollabClient {
+    request_sender: Sender<CollabRequest>,
+    action_receiver: ToUIReceiver<CollabClientAction>,
+}
+
+impl CollabClient {
+    pub fn connect(address: &str, secret: &str) -> Self {
+        let (request_sender, request_receiver) = mpsc::channel();
+        let action_receiver = ToUIReceiver::default();
+        let action_sender = action_receiver.sender();
+        let address = address.to_string();
+        let secret = secret.to_string();
+        thread::spawn(move || {
+            if let Err(err) = run_connection(&address, &secret, request_receiver, &action_sender) {
+                let _ = action_sender.send(CollabClientAction::Disconnected(format!("{}: {}", address, err)));
+            }
+        });
+        Self {
+            request_sender,
+            action_receiver,
+        }
+    }
+
+    pub fn send_request(&self, request: CollabRequest) {
+        let _ = self.request_sender.send(request);
+    }
+
+    pub fn try_recv_action(&self) -> Option<CollabClientAction> {
+        self.action_receiver.try_recv().ok()
+    }
+}
+
+fn run_connection(
+    address: &str,
+    secret: &str,
+    request_receiver: Receiver<CollabRequest>,
+    action_sender: &ToUISender<CollabClientAction>,
+) -> io::Result<()> {
+    let mut tcp_stream = TcpStream::connect(address)?;
+    let seed = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |duration| duration.as_nanos());
+    write!(
+        tcp_stream,
+        "GET {} HTTP/1.1\r\n\
+            Host: {}\r\n\
+            Upgrade: websocket\r\n\
+            Connection: Upgrade\r\n\
+            Sec-WebSocket-Key: {}\r\n\
+            Sec-WebSocket-Version: 13\r\n\
+            Authorization: Bearer {}\r\n\r\n",
+        CollabRelay::PATH,
+        address,
+        base64_encode(&seed.to_le_bytes()),
+        secret,
+    )?;
+    // the response has no body, so it ends at the first empty line
+    let mut response = Vec::new();
+    let mut byte = [0u8];
+    while !response.ends_with(b"\r\n\r\n") {
+        if tcp_stream.read(&mut byte)? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        response.push(byte[0]);
+    }
+    if !response.starts_with(b"HTTP/1.1 101") {
+        return Err(io::Error::new(io::ErrorKind::Other, "the relay refused the connection"));
+    }
+
+    let mut write_tcp_stream = tcp_stream.try_clone()?;
+    let mask = (seed as u32).to_le_bytes();
+    thread::spawn(move || {
+        while let Ok(request) = request_receiver.recv() {
+            let message = mask_binary_message(&request.serialize_bin(), mask);
+            if write_tcp_stream.write_all(&message).is_err() {
+                break;
+            }
+        }
+        let _ = write_tcp_stream.shutdown(Shutdown::Both);
+    });
+
+    let mut web_socket = WebSocket::new();
+    let mut data = [0u8; 65535];
+    loop {
+        let n = tcp_stream.read(&mut data)?;
+        if n == 0 {
+            return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "the relay closed the connection"));
+        }
+        let mut result = Ok(());
+        web_socket.parse(&data[0..n], |message| match message {
+            Ok(WebSocketMessage::Binary(data)) => match CollabNotification::deserialize_bin(data) {
+                Ok(notification) => {
+                    let _ = action_sender.send(CollabClientAction::Notification(notification));
+                }
+                Err(err) => result = Err(io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", err))),
+            },
+            Ok(WebSocketMessage::Close) => {
+                result = Err(io::Error::new(io::ErrorKind::ConnectionAborted, "the relay closed the connection"));
+            }
+            Ok(_) => {}
+            Err(err) => result = Err(io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", err))),
+        });
+        result?;
+    }
+}
@@ -0,0 +1,310 @@
+use {
+    crate::{
+        collab::{
+            collab_client::{CollabClient, CollabClientAction},
+            collab_protocol::{CollabNotification, CollabRequest},
+        },
+        file_system::file_system::{FileSystem, OpenDoc},
+        makepad_code_editor::{
+            collab::SyncState,
+            selection::Selection,
+            text::{Change, Edit, Position},
+        },
+        makepad_widgets::*,
+        makepad_widgets::file_tree::FileNodeId,
+    },
+    makepad_toml_parser::{parse_toml, Toml},
+    std::{
+        collections::HashMap,
+        fs,
+        sync::mpsc::{self, Receiver},
+    },
+};
+
+/// The settings in the `collab.toml` in the working directory:
+///
+/// ```toml
+/// [collab]
+/// # the relay to share the open files through
+/// address = "192.168.1.10:8001"
+/// # every peer needs the same secret, the relay refuses the ones that don't have it
+/// secret = "a long random string"
+/// # runs the relay in the http server of this Studio, only on the peer that hosts the session
+/// host = true
+/// ```
+///
+/// The secret is sent in the clear, so it only keeps out the peers on the network that don't
+/// know it. Without the file, nothing is shared or hosted.
+pub struct CollabConfig {
+    pub address: Option<String>,
+    pub secret: String,
+    pub host: bool,
+}
+
+impl CollabConfig {
+    pub const PATH: &'static str = "collab.toml";
+
+    pub fn load() -> Option<Self> {
+        let string = fs::read_to_string(Self::PATH).ok()?;
+        let toml = match parse_toml(&string) {
+            Ok(toml) => toml,
+            Err(err) => {
+                error!("Cannot load {}: {}", Self::PATH, err.msg);
+                return None;
+            }
+        };
+        let address = match toml.get("collab.address") {
+            Some(Toml::Str(address, _)) => Some(address.clone()),
+            None => None,
+            _ => {
+                error!("Cannot load {}: collab.address should be a string", Self::PATH);
+                return None;
+            }
+        };
+        let secret = match toml.get("collab.secret") {
+            // it goes in a header line of the web socket request
+            Some(Toml::Str(secret, _)) if !secret.is_empty() && !secret.contains(char::is_control) => secret.clone(),
+            _ => {
+                error!("Cannot load {}: collab.secret should be a non-empty string on a single line", Self::PATH);
+                return None;
+            }
+        };
+        let host = match toml.get("collab.host") {
+            Some(Toml::Bool(host, _)) => *host,
+            None => false,
+            _ => {
+                error!("Cannot load {}: collab.host should be a boolean", Self::PATH);
+                return None;
+            }
+        };
+        Some(Self {address, secret, host})
+    }
+}
+
+/// Edits the open documents together with other peers, through the collab relay that is
+/// configured in `CollabConfig`.
+#[derive(Default)]
+pub struct CollabManager {
+    client: Option<CollabClient>,
+    documents: HashMap<FileNodeId, SharedDocument>,
+    // the selections of each tab as they were last sent, so that tabs of the same document don't
+    // take turns sending theirs
+    tab_selections: HashMap<LiveId, Vec<(Position, Position)>>,
+}
+
+struct SharedDocument {
+    path: String,
+    state: SharedDocumentState,
+}
+
+enum SharedDocumentState {
+    Joining,
+    Joined {
+        peer_id: u64,
+        sync_state: SyncState,
+        edit_receiver: Receiver<Vec<Edit>>,
+    },
+    Left,
+}
+
+impl CollabManager {
+    pub fn new(config: Option<&CollabConfig>) -> Self {
+        let Some((address, secret)) = config.and_then(|config| Some((config.address.as_ref()?, &config.secret))) else {
+            return Self::default();
+        };
+        log!("Sharing open files through the collab relay at {}", address);
+        Self {
+            client: Some(CollabClient::connect(address, secret)),
+            ..Self::default()
+        }
+    }
+
+    /// Joins the sessions for the documents that have been loaded since the last call, and sends
+    /// the edits that have been made to the shared ones.
+    pub fn sync_documents(&mut self, file_system: &mut FileSystem) {
+        let Some(client) = &self.client else {
+            return;
+        };
+        for (file_id, open_doc) in &file_system.open_documents {
+            let OpenDoc::Document(document) = open_doc else {
+                continue;
+            };
+            if self.documents.contains_key(file_id) {
+                continue;
+            }
+            let path = file_system.file_node_path(*file_id);
+            client.send_request(CollabRequest::Join {
+                path: path.clone(),
+                text: document.as_text().clone(),
+            });
+            self.documents.insert(*file_id, SharedDocument {
+                path,
+                state: SharedDocumentState::Joining,
+            });
+        }
+        self.send_edits();
+    }
+
+    /// Shows the selections of the session in a tab to the other peers, if they changed.
+    pub fn update_selections(&mut self, tab_id: LiveId, file_id: FileNodeId, selections: &[Selection]) {
+        let (Some(client), Some(document)) = (&self.client, self.documents.get(&file_id)) else {
+            return;
+        };
+        let SharedDocumentState::Joined {sync_state, ..} = &document.state else {
+            return;
+        };
+        // positions in the local text are only positions in the shared one when it is synced
+        if !sync_state.is_synced() {
+            return;
+        }
+        let selections: Vec<_> = selections
+            .iter()
+            .map(|selection| (selection.anchor, selection.cursor.position))
+            .collect();
+        if self.tab_selections.get(&tab_id) == Some(&selections) {
+            return;
+        }
+        client.send_request(CollabRequest::SetSelections {
+            path: document.path.clone(),
+            revision: sync_state.revision(),
+            selections: selections.clone(),
+        });
+        self.tab_selections.insert(tab_id, selections);
+    }
+
+    pub fn remove_tab(&mut self, tab_id: LiveId) {
+        self.tab_selections.remove(&tab_id);
+    }
+
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, file_system: &mut FileSystem, dock: &DockRef) {
+        let Event::Signal = event else {
+            return;
+        };
+        // the edits that are made from here on are the ones of other peers
+        self.send_edits();
+        while let Some(action) = self.client.as_ref().and_then(|client| client.try_recv_action()) {
+            match action {
+                CollabClientAction::Notification(notification) => {
+                    self.handle_notification(cx, notification, file_system, dock);
+                }
+                CollabClientAction::Disconnected(message) => {
+                    error!("Lost the connection to the collab relay at {}", message);
+                    self.client = None;
+                    for (file_id, document) in &mut self.documents {
+                        document.state = SharedDocumentState::Left;
+                        if let Some(OpenDoc::Document(document)) = file_system.open_documents.get_mut(file_id) {
+                            document.clear_peer_selections();
+                        }
+                        file_system.redraw_view_by_file_id(cx, *file_id, dock);
+                    }
+                }
+            }
+        }
+    }
+
+    fn handle_notification(
+        &mut self,
+        cx: &mut Cx,
+        notification: CollabNotification,
+        file_system: &mut FileSystem,
+        dock: &DockRef,
+    ) {
+        let path = match &notification {
+            CollabNotification::Joined {path, ..}
+            | CollabNotification::ChangesApplied {path, ..}
+            | CollabNotification::SelectionsChanged {path, ..}
+            | CollabNotification::PeerLeft {path, ..} => path,
+        };
+        let Some((&file_id, shared_document)) = self.documents.iter_mut().find(|(_, document)| document.path == *path) else {
+            return;
+        };
+        let Some(OpenDoc::Document(document)) = file_system.open_documents.get_mut(&file_id) else {
+            return;
+        };
+        let mut new_state = None;
+        match (notification, &mut shared_document.state) {
+            (CollabNotification::Joined {peer_id, revision, text, ..}, SharedDocumentState::Joining) => {
+                // the text of the session wins over the local one, which may be a different version
+                let local_text = document.as_text().clone();
+                if local_text != text {
+                    document.apply_remote_changes(vec![
+                        Change::Delete(Position::zero(), local_text.length()),
+                        Change::Insert(Position::zero(), text),
+                    ]);
+                }
+                let (edit_sender, edit_receiver) = mpsc::channel();
+                document.add_edit_listener(edit_sender);
+                new_state = Some(SharedDocumentState::Joined {
+                    peer_id,
+                    sync_state: SyncState::new(revision),
+                    edit_receiver,
+                });
+            }
+            (CollabNotification::ChangesApplied {peer_id: change_peer_id, changes, ..}, SharedDocumentState::Joined {peer_id, sync_state, edit_receiver, ..}) => {
+                if change_peer_id == *peer_id {
+                    if let Some((revision, changes)) = sync_state.confirm_changes() {
+                        if let Some(client) = &self.client {
+                            client.send_request(CollabRequest::ApplyChanges {
+                                path: shared_document.path.clone(),
+                                revision,
+                                changes,
+                            });
+                        }
+                    }
+                    return;
+                }
+                document.apply_remote_changes(sync_state.apply_remote_changes(changes));
+                // these are the changes that were just applied
+                while edit_receiver.try_recv().is_ok() {}
+                if let Some(tab_id) = file_system.file_node_id_to_tab_id(file_id) {
+                    file_system.request_save_file(tab_id);
+                }
+            }
+            (CollabNotification::SelectionsChanged {peer_id, selections, ..}, SharedDocumentState::Joined {sync_state, ..}) => {
+                document.set_peer_selections(
+                    peer_id as usize,
+                    selections.into_iter().map(|(anchor, cursor)| (
+                        sync_state.transform_remote_position(anchor),
+                        sync_state.transform_remote_position(cursor),
+                    )),
+                );
+            }
+            (CollabNotification::PeerLeft {peer_id: left_peer_id, ..}, SharedDocumentState::Joined {peer_id, ..}) => {
+                if left_peer_id == *peer_id {
+                    log!("Stopped sharing {}", shared_document.path);
+                    document.clear_peer_selections();
+                    new_state = Some(SharedDocumentState::Left);
+                } else {
+                    document.set_peer_selections(left_peer_id as usize, []);
+                }
+            }
+            _ => return,
+        }
+        if let Some(state) = new_state {
+            shared_document.state = state;
+        }
+        file_system.redraw_view_by_file_id(cx, file_id, dock);
+    }
+
+    fn send_edits(&mut self) {
+        let Some(client) = &self.client else {
+            return;
+        };
+        for document in self.documents.values_mut() {
+            let SharedDocumentState::Joined {sync_state, edit_receiver, ..} = &mut document.state else {
+                continue;
+            };
+            let mut changes = Vec::new();
+            while let Ok(edits) = edit_receiver.try_recv() {
+                changes.extend(edits.into_iter().map(|edit| edit.change));
+            }
+            if let Some((revision, changes)) = sync_state.apply_local_changes(changes) {
+                client.send_request(CollabRequest::ApplyChanges {
+                    path: document.path.clone(),
+                    revision,
+                    changes,
+                });
+            }
+        }
+    }
+}
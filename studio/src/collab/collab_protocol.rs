@@ -0,0 +1,44 @@
+use crate::{
+    makepad_code_editor::text::{Change, Position, Text},
+    makepad_micro_serde::{SerBin, DeBin, DeBinErr},
+};
+
+/// Types for the protocol between Studio and the collab relay, which runs in the http server of
+/// the Studio that hosts the session.
+///
+/// Each file that is edited together has a session on the relay, named after its path relative
+/// to the root of the file tree. The first peer to join a session provides its text. The relay
+/// puts the changes of all peers in a single order and passes them on to every peer of the
+/// session, including the one that made them, which takes that as a confirmation. See
+/// `makepad_code_editor::collab` for how concurrent changes are transformed.
+
+/// A type for representing a request to the collab relay.
+#[derive(Clone, Debug, SerBin, DeBin)]
+pub enum CollabRequest {
+    /// Requests to join the session for the file at the given path, which is started with the
+    /// given text if there is none yet.
+    Join {path: String, text: Text},
+    /// Requests to leave the session for the file at the given path.
+    Leave {path: String},
+    /// Requests to apply the given changes, which were made to the given revision of the file.
+    ApplyChanges {path: String, revision: usize, changes: Vec<Change>},
+    /// Requests to show the given selections, as (anchor, cursor) pairs in the given revision of
+    /// the file, to the other peers.
+    SetSelections {path: String, revision: usize, selections: Vec<(Position, Position)>},
+}
+
+/// A type for representing a notification from the collab relay.
+#[derive(Clone, Debug, SerBin, DeBin)]
+pub enum CollabNotification {
+    /// Notifies a peer that it joined the session for a file, which has the given text at the
+    /// given revision. The peer is known by the given id from now on.
+    Joined {path: String, peer_id: u64, revision: usize, text: Text},
+    /// Notifies that the relay applied the given changes of the given peer, which advances the
+    /// revision of the file by one.
+    ChangesApplied {path: String, peer_id: u64, changes: Vec<Change>},
+    /// Notifies that the given peer changed its selections, which are in the latest revision of
+    /// the file.
+    SelectionsChanged {path: String, peer_id: u64, selections: Vec<(Position, Position)>},
+    /// Notifies that the given peer left the session for a file.
+    PeerLeft {path: String, peer_id: u64},
+}
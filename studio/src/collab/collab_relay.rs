@@ -0,0 +1,221 @@
+use {
+    crate::{
+        collab::collab_protocol::{CollabNotification, CollabRequest},
+        makepad_code_editor::{
+            collab::{transform_position, SharedText},
+            text::{Drift, Position},
+        },
+        makepad_error_log::*,
+        makepad_micro_serde::*,
+    },
+    std::{collections::HashMap, sync::mpsc::Sender},
+};
+
+/// Relays the edits of the peers that connect to the http server of Studio over a web socket.
+/// Requests come in on the thread of the http server, which calls into the relay directly.
+///
+/// Only peers that know the secret of the relay can connect, they send it in the `Authorization`
+/// header of the web socket request.
+pub struct CollabRelay {
+    secret: String,
+    // the senders of the web sockets of the connected peers, by web socket id
+    peers: HashMap<u64, Sender<Vec<u8>>>,
+    sessions: HashMap<String, Session>,
+}
+
+struct Session {
+    text: SharedText,
+    peers: HashMap<u64, SessionPeer>,
+}
+
+struct SessionPeer {
+    // the latest revision that the peer sent changes or selections in, it never sends any in an
+    // older one, so the history before the oldest of these can be dropped
+    revision: usize,
+    // the selections of the peer, in the latest revision of the text
+    selections: Vec<(Position, Position)>,
+}
+
+impl CollabRelay {
+    pub const PATH: &'static str = "/$collab";
+
+    pub fn new(secret: String) -> Self {
+        Self {
+            secret,
+            peers: HashMap::new(),
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Returns whether the header lines of a web socket request carry the secret of the relay.
+    pub fn is_authorized(&self, header_lines: &[String]) -> bool {
+        header_lines.iter().any(|line| {
+            let Some((name, value)) = line.split_once(':') else {
+                return false;
+            };
+            let Some(secret) = value.trim().strip_prefix("Bearer ") else {
+                return false;
+            };
+            name.eq_ignore_ascii_case("authorization") && constant_time_eq(secret.as_bytes(), self.secret.as_bytes())
+        })
+    }
+
+    pub fn connect(&mut self, peer_id: u64, sender: Sender<Vec<u8>>) {
+        self.peers.insert(peer_id, sender);
+    }
+
+    pub fn disconnect(&mut self, peer_id: u64) {
+        if self.peers.remove(&peer_id).is_none() {
+            return;
+        }
+        let paths: Vec<_> = self
+            .sessions
+            .iter()
+            .filter(|(_, session)| session.peers.contains_key(&peer_id))
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in paths {
+            self.leave(peer_id, &path);
+        }
+    }
+
+    pub fn handle_message(&mut self, peer_id: u64, data: &[u8]) {
+        if !self.peers.contains_key(&peer_id) {
+            return;
+        }
+        let request = match CollabRequest::deserialize_bin(data) {
+            Ok(request) => request,
+            Err(err) => {
+                error!("Cannot deserialize collab request: {:?}", err);
+                return;
+            }
+        };
+        match request {
+            CollabRequest::Join {path, text} => {
+                if text.as_lines().is_empty() {
+                    return;
+                }
+                let session = self.sessions.entry(path.clone()).or_insert_with(|| Session {
+                    text: SharedText::new(text),
+                    peers: HashMap::new(),
+                });
+                session.peers.insert(peer_id, SessionPeer {
+                    revision: session.text.revision(),
+                    selections: Vec::new(),
+                });
+                let joined = CollabNotification::Joined {
+                    path: path.clone(),
+                    peer_id,
+                    revision: session.text.revision(),
+                    text: session.text.as_text().clone(),
+                };
+                let selections: Vec<_> = session
+                    .peers
+                    .iter()
+                    .filter(|(other_peer_id, peer)| **other_peer_id != peer_id && !peer.selections.is_empty())
+                    .map(|(other_peer_id, peer)| CollabNotification::SelectionsChanged {
+                        path: path.clone(),
+                        peer_id: *other_peer_id,
+                        selections: peer.selections.clone(),
+                    })
+                    .collect();
+                self.send(peer_id, &joined);
+                for notification in &selections {
+                    self.send(peer_id, notification);
+                }
+            }
+            CollabRequest::Leave {path} => self.leave(peer_id, &path),
+            CollabRequest::ApplyChanges {path, revision, changes} => {
+                let Some(session) = self.sessions.get_mut(&path).filter(|session| session.peers.contains_key(&peer_id)) else {
+                    return;
+                };
+                let Some(changes) = session.text.apply_changes(revision, changes) else {
+                    // the text of the peer is out of sync, so it can't take part anymore
+                    error!("Collab peer {} sent changes that don't fit {}", peer_id, path);
+                    self.leave(peer_id, &path);
+                    return;
+                };
+                for peer in session.peers.values_mut() {
+                    for (anchor, cursor) in &mut peer.selections {
+                        *anchor = transform_position(*anchor, &changes, Drift::Before);
+                        *cursor = transform_position(*cursor, &changes, Drift::Before);
+                    }
+                }
+                session.set_peer_revision(peer_id, revision);
+                let peer_ids: Vec<_> = session.peers.keys().copied().collect();
+                let notification = CollabNotification::ChangesApplied {path, peer_id, changes};
+                for other_peer_id in peer_ids {
+                    self.send(other_peer_id, &notification);
+                }
+            }
+            CollabRequest::SetSelections {path, revision, selections} => {
+                let Some(session) = self.sessions.get_mut(&path).filter(|session| session.peers.contains_key(&peer_id)) else {
+                    return;
+                };
+                let selections: Vec<_> = selections
+                    .into_iter()
+                    .filter_map(|(anchor, cursor)| Some((
+                        session.text.transform_position(revision, anchor)?,
+                        session.text.transform_position(revision, cursor)?,
+                    )))
+                    .collect();
+                if let Some(peer) = session.peers.get_mut(&peer_id) {
+                    peer.selections = selections.clone();
+                }
+                session.set_peer_revision(peer_id, revision);
+                let peer_ids: Vec<_> = session.peers.keys().copied().filter(|other_peer_id| *other_peer_id != peer_id).collect();
+                let notification = CollabNotification::SelectionsChanged {path, peer_id, selections};
+                for other_peer_id in peer_ids {
+                    self.send(other_peer_id, &notification);
+                }
+            }
+        }
+    }
+
+    fn leave(&mut self, peer_id: u64, path: &str) {
+        let Some(session) = self.sessions.get_mut(path) else {
+            return;
+        };
+        if session.peers.remove(&peer_id).is_none() {
+            return;
+        }
+        let peer_ids: Vec<_> = session.peers.keys().copied().collect();
+        if peer_ids.is_empty() {
+            self.sessions.remove(path);
+        } else {
+            session.trim_history();
+        }
+        // the peer that left is told as well, in case it didn't ask to
+        let notification = CollabNotification::PeerLeft {path: path.to_string(), peer_id};
+        for other_peer_id in peer_ids.into_iter().chain([peer_id]) {
+            self.send(other_peer_id, &notification);
+        }
+    }
+
+    fn send(&self, peer_id: u64, notification: &CollabNotification) {
+        if let Some(sender) = self.peers.get(&peer_id) {
+            let _ = sender.send(notification.serialize_bin());
+        }
+    }
+}
+
+impl Session {
+    fn set_peer_revision(&mut self, peer_id: u64, revision: usize) {
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.revision = peer.revision.max(revision);
+        }
+        self.trim_history();
+    }
+
+    fn trim_history(&mut self) {
+        if let Some(revision) = self.peers.values().map(|peer| peer.revision).min() {
+            self.text.trim_history(revision);
+        }
+    }
+}
+
+// compares without returning early, so that the time it takes doesn't tell how much of a guessed
+// secret is right
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
@@ -0,0 +1,4 @@
+pub mod collab_client;
+pub mod collab_manager;
+pub mod collab_protocol;
+pub mod collab_relay;
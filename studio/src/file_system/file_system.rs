@@ -57,6 +57,11 @@ pub struct FileEdge {
 
 pub enum FileSystemAction {
     TreeLoaded,
+    /// A file was written to disk, with `content`.
+    FileSaved {
+        file_id: FileNodeId,
+        content: String,
+    },
     RecompileNeeded,
     LiveReloadNeeded(LiveFileChange)
 }
@@ -115,7 +120,7 @@ impl FileSystem {
                         // dock.select_tab(cx, dock, state, live_id!(file_tree).into(), live_id!(file_tree).into(), Animate::No);
                     }
                     FileResponse::OpenFile(result) => match result {
-                        Ok((_unix_path, data, id)) => {
+                        Ok((unix_path, data, id)) => {
                             let file_id = FileNodeId(LiveId(id));
                             let dock = ui.dock(id!(dock));
                             for (tab_id, file_id) in &self.tab_id_to_file_node_id {
@@ -125,7 +130,8 @@ impl FileSystem {
                             }
                             if let Some(OpenDoc::Decorations(dec)) = self.open_documents.get(&file_id) {
                                 let dec = dec.clone();
-                                self.open_documents.insert(file_id, OpenDoc::Document(Document::new(data.into(), dec)));
+                                let extension = std::path::Path::new(&unix_path).extension().and_then( | extension | extension.to_str()).unwrap_or("");
+                                self.open_documents.insert(file_id, OpenDoc::Document(Document::for_extension(data.into(), dec, extension)));
                             }else {panic!()}
                             
                             ui.redraw(cx);
@@ -138,8 +144,12 @@ impl FileSystem {
                         }
                     }
                     FileResponse::SaveFile(result) => match result {
-                        Ok((path, old, new, _id)) => {
+                        Ok((path, old, new, id)) => {
                             // alright file has been saved
+                            dispatch_action(cx, FileSystemAction::FileSaved {
+                                file_id: FileNodeId(LiveId(id)),
+                                content: new.clone(),
+                            });
                             // now we need to check if a live_design!{} changed or something outside it
                             if old != new {
                                 let mut old_neg = Vec::new();
@@ -196,17 +206,20 @@ impl FileSystem {
     
     
     pub fn request_save_file(&mut self, tab_id: LiveId) {
-        // ok lets see if we have a document
-        // ifnot, we create a new one
         if let Some(file_id) = self.tab_id_to_file_node_id.get(&tab_id) {
-            if let Some(OpenDoc::Document(doc)) = self.open_documents.get(&file_id) {
-                let text = doc.as_text().to_string();
-                let path = self.file_node_path(*file_id);
-                self.file_client.send_request(FileRequest::SaveFile(path.clone(), text, file_id.0.0));
-            }
+            self.request_save_file_by_id(*file_id);
         };
     }
     
+    /// Saves a document that may not have a tab of its own, such as one shown in a diff view.
+    pub fn request_save_file_by_id(&mut self, file_id: FileNodeId) {
+        if let Some(OpenDoc::Document(doc)) = self.open_documents.get(&file_id) {
+            let text = doc.as_text().to_string();
+            let path = self.file_node_path(file_id);
+            self.file_client.send_request(FileRequest::SaveFile(path.clone(), text, file_id.0.0));
+        }
+    }
+    
     pub fn clear_decorations(&mut self, file_node_id: &FileNodeId) {
         // ok lets see if we have a document
         // ifnot, we create a new one
@@ -217,6 +230,14 @@ impl FileSystem {
         };
     }
     
+    pub fn clear_decorations_with_id(&mut self, file_node_id: &FileNodeId, id: usize) {
+        match self.open_documents.get_mut(file_node_id) {
+            Some(OpenDoc::Decorations(dec)) => dec.clear_with_id(id),
+            Some(OpenDoc::Document(doc)) => doc.clear_decorations_with_id(id),
+            None => ()
+        };
+    }
+    
     pub fn clear_all_decorations(&mut self) {
         // ok lets see if we have a document
         // ifnot, we create a new one
@@ -0,0 +1,395 @@
+use {
+    crate::{
+        file_system::file_system::{FileSystem, OpenDoc},
+        makepad_code_editor::{
+            decoration::DecorationSet,
+            diff::{self, LineChange},
+            inlays::InlineInlay,
+            text::{Edit, Text},
+            Document, Session,
+        },
+        makepad_shell::shell_env_cap,
+        makepad_widgets::*,
+        makepad_widgets::file_tree::FileNodeId,
+    },
+    std::{
+        collections::HashMap,
+        env, fs,
+        path::{Path, PathBuf},
+        sync::mpsc::{self, Receiver, Sender},
+        thread,
+        time::{SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Compares the documents that Studio has open with the versions of their files in the git index,
+/// so that their editors mark the lines that changed, and annotates the line of the cursor with the
+/// commit that last changed it. Git runs on a thread of its own when a document is opened, and
+/// shortly after it was last saved.
+///
+/// A document can also be shown side by side with its version in the index, in a `DiffView`.
+pub struct GitManager {
+    root_path: PathBuf,
+    documents: HashMap<FileNodeId, GitDocument>,
+    diffs: HashMap<LiveId, Diff>,
+    result_sender: Sender<GitResult>,
+    result_receiver: Receiver<GitResult>,
+    signal: Signal,
+    refresh_timer: Timer,
+}
+
+struct GitDocument {
+    path: String,
+    // saved since git last ran for it
+    is_stale: bool,
+    is_running: bool,
+    index_text: Option<Text>,
+    blame: Option<Blame>,
+    edit_receiver: Receiver<Vec<Edit>>,
+    // how the lines of the document map to the lines that were blamed, until it is edited
+    blame_changes: Option<Vec<LineChange>>,
+    // the line that the annotation is on, with its text
+    annotation: Option<(usize, String)>,
+}
+
+struct Blame {
+    lines: Vec<String>,
+    // `None` for the lines that aren't committed yet
+    line_commits: Vec<Option<usize>>,
+    commits: Vec<Commit>,
+}
+
+struct Commit {
+    author: String,
+    time: u64,
+    summary: String,
+}
+
+struct Diff {
+    file_id: FileNodeId,
+    old_session: Session,
+    new_session: Session,
+    edit_receiver: Receiver<Vec<Edit>>,
+}
+
+struct GitResult {
+    file_id: FileNodeId,
+    index_text: Option<Text>,
+    blame: Option<Blame>,
+}
+
+impl Default for GitManager {
+    fn default() -> Self {
+        let (result_sender, result_receiver) = mpsc::channel();
+        Self {
+            root_path: env::current_dir().unwrap_or_default(),
+            documents: HashMap::new(),
+            diffs: HashMap::new(),
+            result_sender,
+            result_receiver,
+            signal: Signal::new(),
+            refresh_timer: Timer::default(),
+        }
+    }
+}
+
+impl GitManager {
+    // how long after the last save git runs, in seconds
+    const REFRESH_DELAY: f64 = 1.0;
+
+    /// Runs git for the documents that have been loaded since the last call.
+    pub fn sync_documents(&mut self, file_system: &mut FileSystem) {
+        let file_ids: Vec<_> = file_system
+            .open_documents
+            .iter()
+            .filter(|(file_id, open_doc)| {
+                matches!(open_doc, OpenDoc::Document(_)) && !self.documents.contains_key(file_id)
+            })
+            .map(|(file_id, _)| *file_id)
+            .collect();
+        for file_id in file_ids {
+            let Some(OpenDoc::Document(document)) = file_system.open_documents.get(&file_id) else {
+                continue;
+            };
+            let (edit_sender, edit_receiver) = mpsc::channel();
+            document.clone().add_edit_listener(edit_sender);
+            self.documents.insert(file_id, GitDocument {
+                path: file_system.file_node_path(file_id),
+                is_stale: true,
+                is_running: false,
+                index_text: None,
+                blame: None,
+                edit_receiver,
+                blame_changes: None,
+                annotation: None,
+            });
+            self.run_git(file_id);
+        }
+    }
+
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, file_system: &mut FileSystem, dock: &DockRef) {
+        if self.refresh_timer.is_event(event).is_some() {
+            let file_ids: Vec<_> = self
+                .documents
+                .iter()
+                .filter(|(_, document)| document.is_stale && !document.is_running)
+                .map(|(file_id, _)| *file_id)
+                .collect();
+            for file_id in file_ids {
+                self.run_git(file_id);
+            }
+        }
+        if let Event::Signal = event {
+            while let Ok(result) = self.result_receiver.try_recv() {
+                self.apply_result(cx, result, file_system, dock);
+            }
+        }
+        for (tab_id, diff) in &mut self.diffs {
+            let mut is_edited = false;
+            while diff.edit_receiver.try_recv().is_ok() {
+                is_edited = true;
+            }
+            if is_edited {
+                let text = diff.new_session.document().as_text().clone();
+                diff.old_session.document().clone().set_diff_base(Some(text));
+                dock.item(*tab_id).redraw(cx);
+            }
+        }
+    }
+
+    pub fn file_saved(&mut self, cx: &mut Cx, file_id: FileNodeId) {
+        if let Some(document) = self.documents.get_mut(&file_id) {
+            document.is_stale = true;
+            cx.stop_timer(self.refresh_timer);
+            self.refresh_timer = cx.start_timeout(Self::REFRESH_DELAY);
+        }
+    }
+
+    /// Moves the blame annotation of a document to the line of the cursor of the given session, and
+    /// returns `true` if it changed.
+    pub fn update_annotation(&mut self, file_id: FileNodeId, session: &Session) -> bool {
+        let Some(document) = self.documents.get_mut(&file_id) else {
+            return false;
+        };
+        let mut is_edited = false;
+        while document.edit_receiver.try_recv().is_ok() {
+            is_edited = true;
+        }
+        if is_edited {
+            document.blame_changes = None;
+        }
+        let Some(blame) = &document.blame else {
+            return false;
+        };
+        let selection = session.selections()[session.last_added_selection_index().unwrap_or(0)];
+        let line_index = selection.cursor.position.line_index;
+        let text = session.document().as_text();
+        let blame_changes = document
+            .blame_changes
+            .get_or_insert_with(|| diff::diff_lines(&blame.lines, text.as_lines()));
+        let commit = diff::map_new_line_index(blame_changes, line_index)
+            .ok()
+            .and_then(|blamed_line_index| blame.line_commits.get(blamed_line_index).copied().flatten())
+            .map(|commit_index| &blame.commits[commit_index]);
+        let annotation = (line_index, annotation_text(commit));
+        // an edit can move the annotation within its line, so it is placed again after one
+        if !is_edited && document.annotation.as_ref() == Some(&annotation) {
+            return false;
+        }
+        let line_len = text.as_lines()[line_index].len();
+        drop(text);
+        let mut open_document = session.document().clone();
+        open_document.clear_inline_inlays();
+        open_document.set_inline_inlays(line_index, vec![(line_len, InlineInlay::Text(annotation.1.clone()))]);
+        document.annotation = Some(annotation);
+        true
+    }
+
+    /// Compares a document with its version in the git index in the diff view of the given tab.
+    /// Returns `false` if the document isn't loaded, or its file isn't in the index.
+    pub fn open_diff(&mut self, tab_id: LiveId, file_id: FileNodeId, file_system: &FileSystem) -> bool {
+        let Some(OpenDoc::Document(document)) = file_system.open_documents.get(&file_id) else {
+            return false;
+        };
+        let Some(document_state) = self.documents.get(&file_id) else {
+            return false;
+        };
+        let Some(index_text) = document_state.index_text.clone() else {
+            return false;
+        };
+        let mut document = document.clone();
+        let (edit_sender, edit_receiver) = mpsc::channel();
+        document.add_edit_listener(edit_sender);
+        self.diffs.insert(tab_id, Diff {
+            file_id,
+            old_session: old_session(index_text, &document_state.path, &document),
+            new_session: Session::new(document),
+            edit_receiver,
+        });
+        true
+    }
+
+    /// The document that the diff view of a tab is for, with the sessions of its old and new side.
+    pub fn diff_mut(&mut self, tab_id: LiveId) -> Option<(FileNodeId, &mut Session, &mut Session)> {
+        self.diffs
+            .get_mut(&tab_id)
+            .map(|diff| (diff.file_id, &mut diff.old_session, &mut diff.new_session))
+    }
+
+    pub fn remove_tab(&mut self, tab_id: LiveId) {
+        self.diffs.remove(&tab_id);
+    }
+
+    fn run_git(&mut self, file_id: FileNodeId) {
+        let Some(document) = self.documents.get_mut(&file_id) else {
+            return;
+        };
+        document.is_stale = false;
+        document.is_running = true;
+        let root_path = self.root_path.clone();
+        let path = document.path.clone();
+        let result_sender = self.result_sender.clone();
+        let signal = self.signal.clone();
+        thread::spawn(move || {
+            // git fails for files that aren't tracked, which simply have nothing to show. A path
+            // after a bare colon is relative to the top of the repository, `./` makes it relative
+            // to the root, which can be a directory inside it
+            let index_text = shell_env_cap(&[], &root_path, "git", &["show", &format!(":./{}", path)])
+                .ok()
+                .map(Text::from);
+            let blame = shell_env_cap(&[], &root_path, "git", &["blame", "--porcelain", "--", &path])
+                .ok()
+                .and_then(|output| {
+                    let text = fs::read_to_string(root_path.join(&path)).ok()?;
+                    Some(parse_blame(&output, Text::from(text)))
+                });
+            let _ = result_sender.send(GitResult {
+                file_id,
+                index_text,
+                blame,
+            });
+            signal.set();
+        });
+    }
+
+    fn apply_result(&mut self, cx: &mut Cx, result: GitResult, file_system: &mut FileSystem, dock: &DockRef) {
+        let Some(document) = self.documents.get_mut(&result.file_id) else {
+            return;
+        };
+        document.is_running = false;
+        document.blame = result.blame;
+        document.blame_changes = None;
+        document.annotation = None;
+        if let Some(OpenDoc::Document(open_document)) = file_system.open_documents.get_mut(&result.file_id) {
+            open_document.set_diff_base(result.index_text.clone());
+            if document.blame.is_none() {
+                open_document.clear_inline_inlays();
+            }
+        }
+        let is_index_changed = document.index_text != result.index_text;
+        document.index_text = result.index_text;
+        for (tab_id, diff) in &mut self.diffs {
+            if diff.file_id != result.file_id || !is_index_changed {
+                continue;
+            }
+            if let Some(index_text) = document.index_text.clone() {
+                diff.old_session = old_session(index_text, &document.path, diff.new_session.document());
+            }
+            dock.item(*tab_id).redraw(cx);
+        }
+        file_system.redraw_view_by_file_id(cx, result.file_id, dock);
+        if document.is_stale {
+            // saved again while git was running
+            self.run_git(result.file_id);
+        }
+    }
+}
+
+fn old_session(index_text: Text, path: &str, new_document: &Document) -> Session {
+    let extension = Path::new(path).extension().and_then(|extension| extension.to_str()).unwrap_or("");
+    let mut document = Document::for_extension(index_text, DecorationSet::new(), extension);
+    document.set_diff_base(Some(new_document.as_text().clone()));
+    Session::new(document)
+}
+
+// parses the output of `git blame --porcelain`, in which every line of the file is preceded by a
+// header with its commit, and the first line of a commit by the details of the commit as well
+fn parse_blame(output: &str, text: Text) -> Blame {
+    let mut commit_indices = HashMap::new();
+    let mut commits = Vec::new();
+    let mut line_commits = vec![None; text.as_lines().len()];
+    let mut commit_index = None;
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        let mut words = line.split(' ');
+        let key = words.next().unwrap_or("");
+        if key.len() == 40 && key.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            let index = *commit_indices.entry(key).or_insert_with(|| {
+                commits.push(Commit {
+                    author: String::new(),
+                    time: 0,
+                    summary: String::new(),
+                });
+                commits.len() - 1
+            });
+            // lines that aren't committed yet are blamed on a commit of zeros
+            let is_committed = key.bytes().any(|byte| byte != b'0');
+            let final_line_number: usize = words.nth(1).and_then(|word| word.parse().ok()).unwrap_or(0);
+            if let Some(line_commit) = final_line_number.checked_sub(1).and_then(|line_index| line_commits.get_mut(line_index)) {
+                *line_commit = is_committed.then_some(index);
+            }
+            commit_index = Some(index);
+            continue;
+        }
+        let Some(index) = commit_index else {
+            continue;
+        };
+        let value = line.get(key.len() + 1..).unwrap_or("");
+        match key {
+            "author" => commits[index].author = value.to_string(),
+            "author-time" => commits[index].time = value.parse().unwrap_or(0),
+            "summary" => commits[index].summary = value.to_string(),
+            _ => {}
+        }
+    }
+    Blame {
+        lines: text.as_lines().to_vec(),
+        line_commits,
+        commits,
+    }
+}
+
+fn annotation_text(commit: Option<&Commit>) -> String {
+    let Some(commit) = commit else {
+        return "    Not committed yet".to_string();
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    format!(
+        "    {}, {} • {}",
+        commit.author,
+        relative_time(now.saturating_sub(commit.time)),
+        commit.summary
+    )
+}
+
+fn relative_time(seconds: u64) -> String {
+    const UNITS: [(u64, &str); 6] = [
+        (365 * 24 * 60 * 60, "year"),
+        (30 * 24 * 60 * 60, "month"),
+        (7 * 24 * 60 * 60, "week"),
+        (24 * 60 * 60, "day"),
+        (60 * 60, "hour"),
+        (60, "minute"),
+    ];
+    for (unit_seconds, name) in UNITS {
+        let count = seconds / unit_seconds;
+        if count > 0 {
+            return format!("{} {}{} ago", count, name, if count == 1 { "" } else { "s" });
+        }
+    }
+    "just now".to_string()
+}
@@ -0,0 +1,95 @@
+use {
+    crate::{
+        makepad_code_editor::keymap::{parse_table, KeyStroke, Keymap, KeymapError, Os},
+        makepad_widgets::*,
+    },
+    std::{fs, rc::Rc},
+};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum StudioCommand {
+    Recompile,
+    ClearLog,
+    ShowDiff,
+}
+
+impl StudioCommand {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "recompile" => Some(Self::Recompile),
+            "clear_log" => Some(Self::ClearLog),
+            "show_diff" => Some(Self::ShowDiff),
+            _ => None,
+        }
+    }
+}
+
+/// The keymap that the code editors share, and the shortcuts of Studio itself, which a
+/// `keymap.toml` in the working directory can override. Studio's shortcuts live in its `studio`
+/// table, and can't be chords.
+pub struct StudioKeymap {
+    pub editor: Rc<Keymap>,
+    shortcuts: Vec<(KeyStroke, StudioCommand)>,
+}
+
+impl StudioKeymap {
+    pub const PATH: &'static str = "keymap.toml";
+
+    pub fn load() -> Self {
+        let Ok(string) = fs::read_to_string(Self::PATH) else {
+            return Self::default();
+        };
+        match Self::from_toml(&string) {
+            Ok(keymap) => keymap,
+            Err(err) => {
+                error!("Cannot load {}: {}", Self::PATH, err);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn from_toml(string: &str) -> Result<Self, KeymapError> {
+        let os = Os::current();
+        let mut keymap = Self {
+            editor: Rc::new(Keymap::from_toml(string, os)?),
+            ..Self::default()
+        };
+        for (keys, name) in parse_table(string, "studio", os)? {
+            let [stroke] = keys[..] else {
+                return Err(KeymapError::InvalidKeys(
+                    keys.iter().map(|stroke| stroke.to_string()).collect::<Vec<_>>().join(" ")
+                ));
+            };
+            keymap.shortcuts.retain(|(other, _)| *other != stroke);
+            if name != "none" {
+                let command = StudioCommand::from_name(&name).ok_or(KeymapError::UnknownCommand(name))?;
+                keymap.shortcuts.push((stroke, command));
+            }
+        }
+        Ok(keymap)
+    }
+
+    pub fn command(&self, key_code: KeyCode, modifiers: KeyModifiers) -> Option<StudioCommand> {
+        let stroke = KeyStroke::new(key_code, modifiers);
+        self.shortcuts.iter().find(|(other, _)| *other == stroke).map(|&(_, command)| command)
+    }
+}
+
+impl Default for StudioKeymap {
+    fn default() -> Self {
+        let primary = Os::current().primary_modifier();
+        let control = KeyModifiers {control: true, ..Default::default()};
+        let logo = KeyModifiers {logo: true, ..Default::default()};
+        // recompiling and clearing the log have always taken either modifier, on every OS
+        Self {
+            editor: Rc::new(Keymap::default()),
+            shortcuts: vec![
+                (KeyStroke::new(KeyCode::Backtick, control), StudioCommand::Recompile),
+                (KeyStroke::new(KeyCode::Backtick, logo), StudioCommand::Recompile),
+                (KeyStroke::new(KeyCode::KeyK, control), StudioCommand::ClearLog),
+                (KeyStroke::new(KeyCode::KeyK, logo), StudioCommand::ClearLog),
+                (KeyStroke::new(KeyCode::KeyD, KeyModifiers {alt: true, ..primary}), StudioCommand::ShowDiff),
+            ],
+        }
+    }
+}
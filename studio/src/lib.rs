@@ -1,12 +1,18 @@
 //pub mod app_inner;
 //pub mod app_state;
 pub mod app;
+pub mod collab;
 pub mod file_system;
+pub mod git;
+pub mod keymap;
+pub mod lsp;
+pub mod recovery;
 pub mod build_manager;
 
 //pub use makepad_code_editor;
 pub use makepad_file_protocol;
 pub use makepad_file_server;
+pub use makepad_lsp_client;
 pub use makepad_widgets;
 pub use makepad_widgets::makepad_draw;
 pub use makepad_draw::makepad_platform;
@@ -0,0 +1,450 @@
+use {
+    crate::{
+        file_system::file_system::{FileSystem, OpenDoc},
+        makepad_code_editor::{
+            completion::Completion,
+            decoration::{Decoration, Severity},
+            text::{Change, Edit, Length, Position, Text},
+        },
+        makepad_lsp_client::{
+            protocol::{self, path_to_uri, uri_to_path, ContentChange, DiagnosticSeverity, Range},
+            LspClient, LspEvent, RequestId, ServerConfig,
+        },
+        makepad_widgets::*,
+        makepad_widgets::file_tree::FileNodeId,
+    },
+    makepad_toml_parser::{parse_toml, Toml},
+    std::{
+        collections::HashMap,
+        env, fs,
+        path::PathBuf,
+        sync::mpsc::{self, Receiver},
+    },
+};
+
+/// The id of the decorations that show the diagnostics of a language server, which are replaced
+/// whenever it sends new ones.
+pub const DIAGNOSTIC_DECORATION_ID: usize = 1;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LspRequest {
+    Hover,
+    Completion,
+    Definition,
+}
+
+pub enum LspAction {
+    ShowHover {
+        tab_id: LiveId,
+        position: Position,
+        text: String,
+    },
+    ShowCompletions {
+        tab_id: LiveId,
+        completions: Vec<Completion>,
+    },
+    /// Open the file at `path`, relative to the root of the file tree, at `position`.
+    GoToDefinition {
+        path: String,
+        position: protocol::Position,
+    },
+}
+
+/// Runs the language servers for the documents that Studio has open, which are started when the
+/// first document they are for is opened. Servers are configured in an `lsp.toml` in the working
+/// directory, with a table per server:
+///
+/// ```toml
+/// [rust]
+/// command = "rust-analyzer"
+/// args = []
+/// extensions = ["rs"]
+/// ```
+///
+/// The name of the table is the language id that documents are opened with. Without the file,
+/// rust-analyzer is used for Rust.
+pub struct LspManager {
+    root_path: PathBuf,
+    servers: Vec<Server>,
+    // the documents that no server is for are `None`
+    documents: HashMap<FileNodeId, Option<SyncedDocument>>,
+    requests: HashMap<(usize, RequestId), PendingRequest>,
+    signal: Signal,
+}
+
+struct Server {
+    config: ServerConfig,
+    state: ServerState,
+}
+
+enum ServerState {
+    NotStarted,
+    Running(LspClient),
+    Failed,
+}
+
+impl Server {
+    fn client(&mut self) -> Option<&mut LspClient> {
+        match &mut self.state {
+            ServerState::Running(client) => Some(client),
+            _ => None,
+        }
+    }
+}
+
+struct SyncedDocument {
+    server_index: usize,
+    uri: String,
+    // the text as the server has it, which the edits to the document are applied to as they are
+    // sent
+    text: Text,
+    edit_receiver: Receiver<Vec<Edit>>,
+}
+
+struct PendingRequest {
+    tab_id: LiveId,
+    position: Position,
+}
+
+impl LspManager {
+    pub const PATH: &'static str = "lsp.toml";
+
+    pub fn load() -> Self {
+        let Ok(string) = fs::read_to_string(Self::PATH) else {
+            return Self::default();
+        };
+        match parse_configs(&string) {
+            Ok(configs) => Self::new(configs),
+            Err(err) => {
+                error!("Cannot load {}: {}", Self::PATH, err);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn new(configs: Vec<ServerConfig>) -> Self {
+        Self {
+            root_path: env::current_dir().unwrap_or_default(),
+            servers: configs
+                .into_iter()
+                .map(|config| Server {
+                    config,
+                    state: ServerState::NotStarted,
+                })
+                .collect(),
+            documents: HashMap::new(),
+            requests: HashMap::new(),
+            signal: Signal::new(),
+        }
+    }
+
+    /// Opens the documents that have been loaded since the last call with their servers, and
+    /// sends the servers the edits that have been made to the open ones.
+    pub fn sync_documents(&mut self, file_system: &mut FileSystem) {
+        let file_ids: Vec<_> = file_system
+            .open_documents
+            .iter()
+            .filter(|(file_id, open_doc)| {
+                matches!(open_doc, OpenDoc::Document(_)) && !self.documents.contains_key(file_id)
+            })
+            .map(|(file_id, _)| *file_id)
+            .collect();
+        for file_id in file_ids {
+            let document = self.open_document(file_system, file_id);
+            self.documents.insert(file_id, document);
+        }
+        self.send_edits();
+    }
+
+    pub fn request(
+        &mut self,
+        request: LspRequest,
+        tab_id: LiveId,
+        file_id: FileNodeId,
+        position: Position,
+    ) {
+        // the edit that led to the request may not have been sent yet
+        self.send_edits();
+        let Some(Some(document)) = self.documents.get(&file_id) else {
+            return;
+        };
+        let Some(client) = self.servers[document.server_index].client() else {
+            return;
+        };
+        let lsp_position = to_lsp_position(&document.text, position);
+        let result = match request {
+            LspRequest::Hover => client.hover(&document.uri, lsp_position),
+            LspRequest::Completion => client.completion(&document.uri, lsp_position),
+            LspRequest::Definition => client.definition(&document.uri, lsp_position),
+        };
+        match result {
+            Ok(id) => {
+                self.requests.insert(
+                    (document.server_index, id),
+                    PendingRequest { tab_id, position },
+                );
+            }
+            Err(err) => error!("Cannot send request to {}: {}", client.config().command, err),
+        }
+    }
+
+    pub fn handle_event(
+        &mut self,
+        cx: &mut Cx,
+        event: &Event,
+        file_system: &mut FileSystem,
+        dock: &DockRef,
+    ) -> Vec<LspAction> {
+        let mut actions = Vec::new();
+        if let Event::Signal = event {
+            // diagnostics for an older version of a document are dropped, so the server has to
+            // know about the latest one
+            self.send_edits();
+            for server_index in 0..self.servers.len() {
+                while let Some(event) = self.servers[server_index].client().and_then(|client| client.try_event()) {
+                    match event {
+                        LspEvent::Initialized => {}
+                        LspEvent::Diagnostics {uri, version, diagnostics} => {
+                            let client = self.servers[server_index].client().unwrap();
+                            if version.is_some() && version != client.version(&uri) {
+                                continue;
+                            }
+                            let Some((file_id, document)) = self.documents.iter().find_map(|(file_id, document)| {
+                                document.as_ref().filter(|document| document.uri == uri).map(|document| (*file_id, document))
+                            }) else {
+                                continue;
+                            };
+                            file_system.clear_decorations_with_id(&file_id, DIAGNOSTIC_DECORATION_ID);
+                            for diagnostic in diagnostics {
+                                let (start, end) = to_positions(&document.text, diagnostic.range);
+                                let severity = match diagnostic.severity {
+                                    // the protocol leaves diagnostics without a severity to the client
+                                    Some(DiagnosticSeverity::Error) | None => Severity::Error,
+                                    Some(DiagnosticSeverity::Warning) => Severity::Warning,
+                                    Some(DiagnosticSeverity::Information) => Severity::Information,
+                                    Some(DiagnosticSeverity::Hint) => Severity::Hint,
+                                };
+                                file_system.add_decoration(file_id, Decoration::new(DIAGNOSTIC_DECORATION_ID, start, end).with_severity(severity));
+                            }
+                            file_system.redraw_view_by_file_id(cx, file_id, dock);
+                        }
+                        LspEvent::Hover {id, hover} => {
+                            let Some(request) = self.requests.remove(&(server_index, id)) else {
+                                continue;
+                            };
+                            if let Some(hover) = hover.filter(|hover| !hover.contents.trim().is_empty()) {
+                                actions.push(LspAction::ShowHover {
+                                    tab_id: request.tab_id,
+                                    position: request.position,
+                                    text: hover.contents,
+                                });
+                            }
+                        }
+                        LspEvent::Completion {id, items} => {
+                            let Some(request) = self.requests.remove(&(server_index, id)) else {
+                                continue;
+                            };
+                            if !items.is_empty() {
+                                actions.push(LspAction::ShowCompletions {
+                                    tab_id: request.tab_id,
+                                    completions: items
+                                        .into_iter()
+                                        .map(|item| Completion {
+                                            label: item.label,
+                                            detail: item.detail,
+                                            insert_text: item.insert_text,
+                                        })
+                                        .collect(),
+                                });
+                            }
+                        }
+                        LspEvent::Definition {id, locations} => {
+                            if self.requests.remove(&(server_index, id)).is_none() {
+                                continue;
+                            }
+                            let Some(location) = locations.into_iter().next() else {
+                                continue;
+                            };
+                            let path = uri_to_path(&location.uri).and_then(|path| {
+                                let path = path.strip_prefix(&self.root_path).ok()?;
+                                Some(path.components().map(|component| component.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+                            });
+                            match path {
+                                Some(path) => actions.push(LspAction::GoToDefinition {
+                                    path,
+                                    position: location.range.start,
+                                }),
+                                None => log!("Cannot open {}, which is outside the workspace", location.uri),
+                            }
+                        }
+                        LspEvent::Error {id, message} => {
+                            self.requests.remove(&(server_index, id));
+                            error!("{}: {}", self.servers[server_index].config.command, message);
+                        }
+                        LspEvent::Exited => {
+                            error!("Language server {} exited", self.servers[server_index].config.command);
+                            self.servers[server_index].state = ServerState::Failed;
+                        }
+                    }
+                }
+            }
+        }
+        actions
+    }
+
+    fn open_document(&mut self, file_system: &mut FileSystem, file_id: FileNodeId) -> Option<SyncedDocument> {
+        let path = self.root_path.join(file_system.file_node_path(file_id));
+        let server_index = self.servers.iter().position(|server| server.config.handles(&path))?;
+        let Some(OpenDoc::Document(document)) = file_system.open_documents.get_mut(&file_id) else {
+            return None;
+        };
+        let server = &mut self.servers[server_index];
+        if let ServerState::NotStarted = server.state {
+            let signal = self.signal.clone();
+            server.state = match LspClient::spawn(&server.config, &self.root_path, Box::new(move || signal.set())) {
+                Ok(client) => ServerState::Running(client),
+                Err(err) => {
+                    error!("Cannot start language server {}: {}", server.config.command, err);
+                    ServerState::Failed
+                }
+            };
+        }
+        let client = server.client()?;
+        let uri = path_to_uri(&path);
+        let text = document.as_text().clone();
+        if let Err(err) = client.did_open(&uri, &text.to_string()) {
+            error!("Cannot open {} with {}: {}", uri, client.config().command, err);
+        }
+        let (edit_sender, edit_receiver) = mpsc::channel();
+        document.add_edit_listener(edit_sender);
+        Some(SyncedDocument {
+            server_index,
+            uri,
+            text,
+            edit_receiver,
+        })
+    }
+
+    fn send_edits(&mut self) {
+        for document in self.documents.values_mut().flatten() {
+            let mut changes = Vec::new();
+            while let Ok(edits) = document.edit_receiver.try_recv() {
+                for edit in edits {
+                    changes.push(to_content_change(&document.text, &edit.change));
+                    document.text.apply_change(edit.change);
+                }
+            }
+            if changes.is_empty() {
+                continue;
+            }
+            if let Some(client) = self.servers[document.server_index].client() {
+                if let Err(err) = client.did_change(&document.uri, &changes) {
+                    error!("Cannot send changes to {}: {}", client.config().command, err);
+                }
+            }
+        }
+    }
+}
+
+impl Default for LspManager {
+    fn default() -> Self {
+        Self::new(vec![ServerConfig::rust_analyzer()])
+    }
+}
+
+pub fn to_lsp_position(text: &Text, position: Position) -> protocol::Position {
+    protocol::Position::from_byte_index(
+        position.line_index,
+        &text.as_lines()[position.line_index],
+        position.byte_index,
+    )
+}
+
+/// Converts a position from a server, which may be past the end of `text` if the server has a
+/// different idea of it.
+pub fn from_lsp_position(text: &Text, position: protocol::Position) -> Position {
+    let lines = text.as_lines();
+    let line_index = (position.line as usize).min(lines.len() - 1);
+    Position {
+        line_index,
+        byte_index: position.byte_index(&lines[line_index]),
+    }
+}
+
+// an empty range covers the char after it, so that it can be seen
+fn to_positions(text: &Text, range: Range) -> (Position, Position) {
+    let start = from_lsp_position(text, range.start);
+    let mut end = from_lsp_position(text, range.end);
+    if end <= start {
+        let byte_count = text.as_lines()[start.line_index][start.byte_index..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        end = start + Length {line_count: 0, byte_count};
+    }
+    (start, end)
+}
+
+fn to_content_change(text: &Text, change: &Change) -> ContentChange {
+    match change {
+        Change::Insert(position, inserted_text) => {
+            let position = to_lsp_position(text, *position);
+            ContentChange {
+                range: Some(Range::new(position, position)),
+                text: inserted_text.to_string(),
+            }
+        }
+        Change::Delete(start, length) => ContentChange {
+            range: Some(Range::new(
+                to_lsp_position(text, *start),
+                to_lsp_position(text, *start + *length),
+            )),
+            text: String::new(),
+        },
+    }
+}
+
+fn parse_configs(string: &str) -> Result<Vec<ServerConfig>, String> {
+    let toml = parse_toml(string).map_err(|err| err.msg)?;
+    let mut configs: Vec<ServerConfig> = Vec::new();
+    let mut keys: Vec<_> = toml.keys().collect();
+    keys.sort();
+    for key in keys {
+        let Some((language_id, field)) = key.rsplit_once('.') else {
+            return Err(format!("{} is not in a table", key));
+        };
+        let index = match configs.iter().position(|config| config.language_id == language_id) {
+            Some(index) => index,
+            None => {
+                configs.push(ServerConfig {
+                    command: String::new(),
+                    args: Vec::new(),
+                    language_id: language_id.to_string(),
+                    extensions: Vec::new(),
+                });
+                configs.len() - 1
+            }
+        };
+        let config = &mut configs[index];
+        match (field, &toml[key]) {
+            ("command", Toml::Str(command, _)) => config.command = command.clone(),
+            ("args", Toml::Array(values)) => config.args = strings(key, values)?,
+            ("extensions", Toml::Array(values)) => config.extensions = strings(key, values)?,
+            _ => return Err(format!("unexpected {}", key)),
+        }
+    }
+    if let Some(config) = configs.iter().find(|config| config.command.is_empty()) {
+        return Err(format!("{} has no command", config.language_id));
+    }
+    Ok(configs)
+}
+
+fn strings(key: &str, values: &[Toml]) -> Result<Vec<String>, String> {
+    values
+        .iter()
+        .map(|value| match value {
+            Toml::Str(string, _) => Ok(string.clone()),
+            _ => Err(format!("{} should only hold strings", key)),
+        })
+        .collect()
+}
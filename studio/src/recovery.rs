@@ -0,0 +1,416 @@
+use {
+    crate::{
+        file_system::file_system::{FileSystem, OpenDoc},
+        makepad_code_editor::{
+            code_editor::CodeEditorWidgetRefExt,
+            history::HistoryStacks,
+            text::{Edit, Text},
+            Selection,
+        },
+        makepad_micro_serde::*,
+        makepad_widgets::*,
+        makepad_widgets::file_tree::FileNodeId,
+    },
+    makepad_toml_parser::{parse_toml, Toml},
+    std::{
+        collections::{HashMap, HashSet},
+        fs,
+        io::{self, Write},
+        path::PathBuf,
+        sync::mpsc::{self, Receiver},
+    },
+};
+
+/// Keeps a journal of every open document in a recovery directory, with its text, undo history
+/// and selections, so that edits that never made it to disk can be restored after a crash. The
+/// editors of a file whose journal has such edits ask whether to restore them when it is opened.
+///
+/// A journal starts with a snapshot of the document, after which the edits made since are
+/// appended. It is compacted into a new snapshot when the edits outgrow the snapshot, when the
+/// file is saved, and when Studio exits.
+///
+/// The undo history of a file can be kept across sessions as well, for as long as the file doesn't
+/// change outside of Studio. This is turned on in a `recovery.toml` in the working directory:
+///
+/// ```toml
+/// [recovery]
+/// persistent_undo = true
+/// ```
+#[derive(Default)]
+pub struct RecoveryManager {
+    persistent_undo: bool,
+    timer: Timer,
+    documents: HashMap<FileNodeId, JournaledDocument>,
+}
+
+struct JournaledDocument {
+    path: String,
+    // the hash of the text as it was last read from or written to disk
+    saved_hash: u64,
+    edit_receiver: Receiver<Vec<Edit>>,
+    // the edits that aren't in the journal on disk yet
+    edits: Vec<Edit>,
+    // the size of the journal on disk, and how much of it was appended after its snapshot
+    journal_len: Option<(usize, usize)>,
+    needs_snapshot: bool,
+    recovery: Option<Recovery>,
+}
+
+// a journal with edits that were never saved, which isn't overwritten until the editors have
+// asked whether to restore it
+struct Recovery {
+    journal: Journal,
+    edits: Vec<Edit>,
+    message: String,
+    prompted_tab_ids: HashSet<LiveId>,
+}
+
+#[derive(SerBin, DeBin)]
+struct Journal {
+    path: String,
+    saved_hash: u64,
+    text: Text,
+    stacks: HistoryStacks,
+    selections: Vec<Selection>,
+}
+
+impl RecoveryManager {
+    pub const PATH: &'static str = "recovery.toml";
+    pub const DIRECTORY: &'static str = "target/studio_recovery";
+    // how often the journals of the documents that changed are written, in seconds
+    const INTERVAL: f64 = 2.0;
+    // appended edits are compacted into a snapshot once they are larger than it, or than this
+    const MIN_COMPACT_LEN: usize = 64 * 1024;
+
+    pub fn load() -> Self {
+        let Ok(string) = fs::read_to_string(Self::PATH) else {
+            return Self::default();
+        };
+        let persistent_undo = match parse_toml(&string) {
+            Ok(toml) => match toml.get("recovery.persistent_undo") {
+                Some(Toml::Bool(persistent_undo, _)) => *persistent_undo,
+                None => false,
+                _ => {
+                    error!("Cannot load {}: recovery.persistent_undo should be a bool", Self::PATH);
+                    false
+                }
+            },
+            Err(err) => {
+                error!("Cannot load {}: {}", Self::PATH, err.msg);
+                false
+            }
+        };
+        Self {
+            persistent_undo,
+            ..Self::default()
+        }
+    }
+
+    /// Reads the journals of the documents that have been loaded since the last call, and shows
+    /// the prompt to restore unsaved edits in the editors that don't show it yet.
+    pub fn sync_documents(&mut self, cx: &mut Cx, file_system: &mut FileSystem, dock: &DockRef) {
+        let file_ids: Vec<_> = file_system
+            .open_documents
+            .iter()
+            .filter(|(file_id, open_doc)| {
+                matches!(open_doc, OpenDoc::Document(_)) && !self.documents.contains_key(file_id)
+            })
+            .map(|(file_id, _)| *file_id)
+            .collect();
+        for file_id in file_ids {
+            self.open_document(file_id, file_system);
+        }
+        for (file_id, document) in &mut self.documents {
+            let Some(recovery) = &mut document.recovery else {
+                continue;
+            };
+            for tab_id in tab_ids(file_system, *file_id) {
+                if recovery.prompted_tab_ids.contains(&tab_id) {
+                    continue;
+                }
+                if let Some(mut editor) = dock.item(tab_id).as_code_editor().borrow_mut() {
+                    editor.show_prompt(cx, &recovery.message, "Restore", "Discard");
+                    recovery.prompted_tab_ids.insert(tab_id);
+                }
+            }
+        }
+    }
+
+    pub fn handle_event(&mut self, cx: &mut Cx, event: &Event, file_system: &FileSystem) {
+        if self.timer.is_empty() {
+            self.timer = cx.start_interval(Self::INTERVAL);
+        }
+        if self.timer.is_event(event).is_some() {
+            self.write_journals(file_system, false);
+        }
+        if let Event::Destruct = event {
+            self.write_journals(file_system, true);
+        }
+    }
+
+    pub fn file_saved(&mut self, file_id: FileNodeId, content: &str) {
+        if let Some(document) = self.documents.get_mut(&file_id) {
+            document.saved_hash = content_hash(content);
+            document.needs_snapshot = true;
+        }
+    }
+
+    /// Restores the unsaved edits of a document if `restore` is `true`, or discards them, after
+    /// one of its editors asked about them.
+    pub fn answer_prompt(
+        &mut self,
+        cx: &mut Cx,
+        file_id: FileNodeId,
+        restore: bool,
+        file_system: &mut FileSystem,
+        dock: &DockRef,
+    ) {
+        let Some(document) = self.documents.get_mut(&file_id) else {
+            return;
+        };
+        let Some(recovery) = document.recovery.take() else {
+            return;
+        };
+        for tab_id in recovery.prompted_tab_ids {
+            if let Some(mut editor) = dock.item(tab_id).as_code_editor().borrow_mut() {
+                editor.close_prompt(cx);
+            }
+        }
+        // either way, the journal on disk is replaced with the state of the document at the next
+        // write
+        document.needs_snapshot = true;
+        if restore {
+            log!("Restoring the unsaved edits to {}", document.path);
+            restore_journal(file_system, file_id, recovery.journal, recovery.edits);
+            if let Some(tab_id) = file_system.file_node_id_to_tab_id(file_id) {
+                file_system.request_save_file(tab_id);
+            }
+        }
+        file_system.redraw_view_by_file_id(cx, file_id, dock);
+    }
+
+    fn open_document(&mut self, file_id: FileNodeId, file_system: &mut FileSystem) {
+        let Some(OpenDoc::Document(document)) = file_system.open_documents.get(&file_id) else {
+            return;
+        };
+        let mut document = document.clone();
+        let path = file_system.file_node_path(file_id);
+        let saved_hash = content_hash(&document.as_text().to_string());
+        let mut recovery = None;
+        match read_journal(&path) {
+            Some((journal, edits)) if content_hash(&journal_text(&journal, &edits).to_string()) == saved_hash => {
+                if self.persistent_undo {
+                    restore_journal(file_system, file_id, journal, edits);
+                }
+            }
+            Some((journal, edits)) => {
+                let message = if journal.saved_hash == saved_hash {
+                    format!("{} has edits that were never saved.", path)
+                } else {
+                    format!("{} has edits that were never saved, to a version that has since changed on disk.", path)
+                };
+                recovery = Some(Recovery {
+                    journal,
+                    edits,
+                    message,
+                    prompted_tab_ids: HashSet::new(),
+                });
+            }
+            None => {}
+        }
+        let (edit_sender, edit_receiver) = mpsc::channel();
+        document.add_edit_listener(edit_sender);
+        self.documents.insert(file_id, JournaledDocument {
+            path,
+            saved_hash,
+            edit_receiver,
+            edits: Vec::new(),
+            journal_len: None,
+            needs_snapshot: false,
+            recovery,
+        });
+    }
+
+    // appends the edits of the documents that changed to their journals, or replaces them with a
+    // snapshot, which every journal with appended edits gets when `compact` is set
+    fn write_journals(&mut self, file_system: &FileSystem, compact: bool) {
+        for (file_id, document) in &mut self.documents {
+            if document.recovery.is_some() {
+                continue;
+            }
+            while let Ok(edits) = document.edit_receiver.try_recv() {
+                document.edits.extend(edits);
+            }
+            let Some(OpenDoc::Document(open_document)) = file_system.open_documents.get(file_id) else {
+                continue;
+            };
+            let has_appended_edits = document.journal_len.map_or(false, |(_, appended_len)| appended_len > 0);
+            if document.edits.is_empty() && !document.needs_snapshot && !(compact && has_appended_edits) {
+                continue;
+            }
+            let text = open_document.as_text().clone();
+            // without persistent undo, a journal is only needed while there are unsaved edits
+            let result = if !self.persistent_undo && content_hash(&text.to_string()) == document.saved_hash {
+                remove_journal(&document.path).map(|()| None)
+            } else {
+                match document.journal_len {
+                    Some((len, appended_len))
+                        if !document.needs_snapshot
+                            && !compact
+                            && appended_len <= (len - appended_len).max(Self::MIN_COMPACT_LEN) =>
+                    {
+                        append_journal(&document.path, &document.edits)
+                            .map(|record_len| Some((len + record_len, appended_len + record_len)))
+                    }
+                    _ => {
+                        let selections = tab_ids(file_system, *file_id)
+                            .into_iter()
+                            .find_map(|tab_id| file_system.tab_id_to_session.get(&tab_id))
+                            .map(|session| session.selections().to_vec())
+                            .unwrap_or_default();
+                        write_journal(&Journal {
+                            path: document.path.clone(),
+                            saved_hash: document.saved_hash,
+                            text,
+                            stacks: open_document.history_stacks(),
+                            selections,
+                        })
+                        .map(|len| Some((len, 0)))
+                    }
+                }
+            };
+            match result {
+                Ok(journal_len) => {
+                    document.journal_len = journal_len;
+                    document.edits.clear();
+                    document.needs_snapshot = false;
+                }
+                Err(err) => {
+                    error!("Cannot write the journal of {}: {}", document.path, err);
+                    // whatever made it to disk can't be appended to anymore
+                    document.journal_len = None;
+                }
+            }
+        }
+    }
+}
+
+// the edits after the snapshot are applied as if made elsewhere, so that the undo history of the
+// snapshot still applies after them
+fn restore_journal(file_system: &mut FileSystem, file_id: FileNodeId, journal: Journal, edits: Vec<Edit>) {
+    let Some(OpenDoc::Document(document)) = file_system.open_documents.get_mut(&file_id) else {
+        return;
+    };
+    let selections: Vec<Selection> = journal
+        .selections
+        .iter()
+        .map(|selection| edits.iter().fold(*selection, |selection, edit| selection.apply_edit(edit)))
+        .collect();
+    document.restore_history(journal.text, journal.stacks);
+    if !edits.is_empty() {
+        document.apply_remote_changes(edits.into_iter().map(|edit| edit.change).collect());
+    }
+    let selections = &selections;
+    for tab_id in tab_ids(file_system, file_id) {
+        if let Some(session) = file_system.get_session_mut(tab_id) {
+            // the selections have to be moved by the restored text before they are replaced
+            session.handle_changes();
+            session.set_selections(selections);
+        }
+    }
+}
+
+fn tab_ids(file_system: &FileSystem, file_id: FileNodeId) -> Vec<LiveId> {
+    file_system
+        .tab_id_to_file_node_id
+        .iter()
+        .filter(|(_, other_file_id)| **other_file_id == file_id)
+        .map(|(tab_id, _)| *tab_id)
+        .collect()
+}
+
+fn content_hash(content: &str) -> u64 {
+    LiveId::from_str(content).0
+}
+
+fn journal_path(path: &str) -> PathBuf {
+    PathBuf::from(RecoveryManager::DIRECTORY).join(format!("{:016x}.bin", LiveId::from_str(path).0))
+}
+
+// the text of the snapshot with the edits after it
+fn journal_text(journal: &Journal, edits: &[Edit]) -> Text {
+    let mut text = journal.text.clone();
+    for edit in edits {
+        text.apply_change(edit.change.clone());
+    }
+    text
+}
+
+// a journal is a sequence of records, each prefixed with its length: the snapshot, followed by
+// the edits that were appended to it
+fn record(data: Vec<u8>) -> Vec<u8> {
+    let mut record = (data.len() as u64).to_le_bytes().to_vec();
+    record.extend(data);
+    record
+}
+
+fn read_journal(path: &str) -> Option<(Journal, Vec<Edit>)> {
+    let data = fs::read(journal_path(path)).ok()?;
+    let mut records = Vec::new();
+    let mut rest = data.as_slice();
+    while rest.len() >= 8 {
+        let len = u64::from_le_bytes(rest[..8].try_into().unwrap()) as usize;
+        // a record that was cut off by a crash while it was appended is left out
+        let Some(record) = 8usize.checked_add(len).and_then(|end| rest.get(8..end)) else {
+            break;
+        };
+        records.push(record);
+        rest = &rest[8 + len..];
+    }
+    let (snapshot, appended) = records.split_first()?;
+    match Journal::deserialize_bin(snapshot) {
+        // journals are named by the hash of the path, which the journal of another file can share
+        Ok(journal) if journal.path == path => {
+            let mut edits = Vec::new();
+            for record in appended {
+                match Vec::<Edit>::deserialize_bin(record) {
+                    Ok(record_edits) => edits.extend(record_edits),
+                    Err(_) => break,
+                }
+            }
+            Some((journal, edits))
+        }
+        Ok(_) => None,
+        Err(err) => {
+            error!("Cannot read the journal of {}: {:?}", path, err);
+            None
+        }
+    }
+}
+
+// writes a journal with just a snapshot, and returns its length
+fn write_journal(journal: &Journal) -> io::Result<usize> {
+    let path = journal_path(&journal.path);
+    fs::create_dir_all(RecoveryManager::DIRECTORY)?;
+    // the journal is written next to the old one first, so that a crash can't leave half of it
+    let temp_path = path.with_extension("tmp");
+    let data = record(journal.serialize_bin());
+    fs::write(&temp_path, &data)?;
+    fs::rename(temp_path, path)?;
+    Ok(data.len())
+}
+
+// appends edits to a journal, and returns the length of their record
+fn append_journal(path: &str, edits: &[Edit]) -> io::Result<usize> {
+    let data = record(edits.to_vec().serialize_bin());
+    let mut file = fs::OpenOptions::new().append(true).open(journal_path(path))?;
+    file.write_all(&data)?;
+    Ok(data.len())
+}
+
+fn remove_journal(path: &str) -> io::Result<()> {
+    match fs::remove_file(journal_path(path)) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,375 @@
+use {
+    std::collections::BTreeMap,
+    crate::{
+        makepad_draw::*,
+        makepad_micro_serde::*,
+        widget::*,
+    }
+};
+
+// A platform neutral accessibility tree, modelled after AccessKit.
+// Widgets describe themselves through Widget::accessibility into an AccessTreeBuilder,
+// every Window rebuilds its AccessTree when drawn and diffs it against the one handed out
+// before (Window::take_access_update), for a platform bridge (AT-SPI, UIA, NSAccessibility)
+// or to inspect in tests.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, SerJson, DeJson, SerBin, DeBin)]
+pub struct AccessNodeId(pub u64);
+
+impl AccessNodeId {
+    pub fn from_widget(uid: WidgetUid) -> Self {
+        Self(uid.0)
+    }
+
+    // nodes that are not widgets themselves (tabs in a dock, items in a list)
+    // are keyed off their owning widget and a stable LiveId
+    pub fn from_widget_sub(uid: WidgetUid, sub: LiveId) -> Self {
+        Self(LiveId(uid.0).id_append(sub).0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerJson, DeJson, SerBin, DeBin)]
+pub enum AccessRole {
+    Unknown,
+    Application,
+    Window,
+    Group,
+    Button,
+    Link,
+    Label,
+    Image,
+    CheckBox,
+    RadioButton,
+    TextInput,
+    Slider,
+    ComboBox,
+    TabList,
+    Tab,
+    TabPanel,
+    Splitter,
+    List,
+    ListItem,
+    Tree,
+    TreeItem,
+    Menu,
+    MenuItem,
+    DisclosureTriangle,
+}
+
+impl Default for AccessRole {
+    fn default() -> Self {Self::Unknown}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerJson, DeJson, SerBin, DeBin)]
+pub enum AccessAction {
+    Click,
+    Focus,
+    SetValue,
+    Increment,
+    Decrement,
+    Expand,
+    Collapse,
+    Select,
+    Close,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerJson, DeJson, SerBin, DeBin)]
+pub enum AccessToggled {
+    False,
+    True,
+    Mixed
+}
+
+#[derive(Clone, Debug, Default, PartialEq, SerJson, DeJson, SerBin, DeBin)]
+pub struct AccessNode {
+    pub id: AccessNodeId,
+    pub role: AccessRole,
+    pub name: String,
+    pub value: Option<String>,
+    pub toggled: Option<AccessToggled>,
+    pub numeric: Option<(f64, f64, f64)>,
+    pub selected: Option<bool>,
+    pub disabled: bool,
+    pub bounds: (f64, f64, f64, f64),
+    pub actions: Vec<AccessAction>,
+    pub children: Vec<AccessNodeId>,
+}
+
+impl AccessNode {
+    pub fn new(id: AccessNodeId, role: AccessRole) -> Self {
+        Self {
+            id,
+            role,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_value(mut self, value: &str) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn with_toggled(mut self, toggled: bool) -> Self {
+        self.toggled = Some(if toggled {AccessToggled::True} else {AccessToggled::False});
+        self
+    }
+
+    pub fn with_numeric(mut self, value: f64, min: f64, max: f64) -> Self {
+        self.numeric = Some((value, min, max));
+        self
+    }
+
+    pub fn with_selected(mut self, selected: bool) -> Self {
+        self.selected = Some(selected);
+        self
+    }
+
+    pub fn with_bounds(mut self, rect: Rect) -> Self {
+        self.bounds = (rect.pos.x, rect.pos.y, rect.size.x, rect.size.y);
+        self
+    }
+
+    pub fn with_actions(mut self, actions: &[AccessAction]) -> Self {
+        self.actions = actions.to_vec();
+        self
+    }
+
+    pub fn rect(&self) -> Rect {
+        Rect {
+            pos: dvec2(self.bounds.0, self.bounds.1),
+            size: dvec2(self.bounds.2, self.bounds.3)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct AccessTreeBuilder {
+    nodes: BTreeMap<AccessNodeId, AccessNode>,
+    stack: Vec<AccessNodeId>,
+    root: Option<AccessNodeId>,
+}
+
+impl AccessTreeBuilder {
+    fn attach(&mut self, node: AccessNode) -> AccessNodeId {
+        let id = node.id;
+        if let Some(parent) = self.stack.last() {
+            if let Some(parent) = self.nodes.get_mut(parent) {
+                parent.children.push(id);
+            }
+        }
+        else if self.root.is_none() {
+            self.root = Some(id);
+        }
+        self.nodes.insert(id, node);
+        id
+    }
+
+    // adds a node without children
+    pub fn leaf(&mut self, node: AccessNode) {
+        self.attach(node);
+    }
+
+    // adds a node and makes it the parent of all following nodes until end
+    pub fn begin(&mut self, node: AccessNode) {
+        let id = self.attach(node);
+        self.stack.push(id);
+    }
+
+    pub fn end(&mut self) {
+        self.stack.pop();
+    }
+
+    pub fn finish(self) -> AccessTree {
+        AccessTree {
+            root: self.root,
+            nodes: self.nodes.into_values().collect()
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, SerJson, DeJson, SerBin, DeBin)]
+pub struct AccessTree {
+    pub root: Option<AccessNodeId>,
+    pub nodes: Vec<AccessNode>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, SerJson, DeJson, SerBin, DeBin)]
+pub struct AccessTreeUpdate {
+    pub root: Option<AccessNodeId>,
+    pub updated: Vec<AccessNode>,
+    pub removed: Vec<AccessNodeId>,
+}
+
+impl AccessTreeUpdate {
+    pub fn is_empty(&self) -> bool {
+        self.updated.is_empty() && self.removed.is_empty()
+    }
+}
+
+impl AccessTree {
+    pub fn from_widget(cx: &Cx, widget: &WidgetRef) -> Self {
+        let mut builder = AccessTreeBuilder::default();
+        widget.accessibility(cx, &mut builder);
+        builder.finish()
+    }
+
+    pub fn node(&self, id: AccessNodeId) -> Option<&AccessNode> {
+        self.nodes.binary_search_by_key(&id, | node | node.id).ok().map( | index | &self.nodes[index])
+    }
+
+    pub fn find_by_name(&self, role: AccessRole, name: &str) -> Option<&AccessNode> {
+        self.nodes.iter().find( | node | node.role == role && node.name == name)
+    }
+
+    // produces the minimal update that turns old into self
+    pub fn diff(&self, old: &AccessTree) -> AccessTreeUpdate {
+        let mut update = AccessTreeUpdate {
+            root: self.root,
+            ..Default::default()
+        };
+        for node in &self.nodes {
+            match old.node(node.id) {
+                Some(old_node) if old_node == node => (),
+                _ => update.updated.push(node.clone())
+            }
+        }
+        for node in &old.nodes {
+            if self.node(node.id).is_none() {
+                update.removed.push(node.id);
+            }
+        }
+        update
+    }
+
+    pub fn apply_update(&mut self, update: &AccessTreeUpdate) {
+        let mut nodes: BTreeMap<AccessNodeId, AccessNode> = std::mem::take(&mut self.nodes)
+            .into_iter().map( | node | (node.id, node)).collect();
+        for id in &update.removed {
+            nodes.remove(id);
+        }
+        for node in &update.updated {
+            nodes.insert(node.id, node.clone());
+        }
+        self.root = update.root;
+        self.nodes = nodes.into_values().collect();
+    }
+
+    // a readable indented dump, handy for asserting on in tests
+    pub fn to_text(&self) -> String {
+        fn dump(tree: &AccessTree, id: AccessNodeId, depth: usize, out: &mut String) {
+            if let Some(node) = tree.node(id) {
+                for _ in 0..depth {
+                    out.push_str("  ");
+                }
+                out.push_str(&format!("{:?}", node.role));
+                if !node.name.is_empty() {
+                    out.push_str(&format!(" {:?}", node.name));
+                }
+                if let Some(value) = &node.value {
+                    out.push_str(&format!(" value={:?}", value));
+                }
+                if let Some(toggled) = &node.toggled {
+                    out.push_str(&format!(" toggled={:?}", toggled));
+                }
+                if let Some(selected) = &node.selected {
+                    out.push_str(&format!(" selected={}", selected));
+                }
+                out.push('\n');
+                for child in &node.children {
+                    dump(tree, *child, depth + 1, out);
+                }
+            }
+        }
+        let mut out = String::new();
+        if let Some(root) = self.root {
+            dump(self, root, 0, &mut out);
+        }
+        out
+    }
+}
+
+// holds the tree of the last frame and the one last handed out, so a platform bridge
+// gets incremental updates however often it asks
+#[derive(Default)]
+pub struct AccessTreeTracker {
+    tree: AccessTree,
+    reported: AccessTree,
+}
+
+impl AccessTreeTracker {
+    pub fn tree(&self) -> &AccessTree {
+        &self.tree
+    }
+
+    pub fn set_tree(&mut self, tree: AccessTree) {
+        self.tree = tree;
+    }
+
+    // the changes since the previous call
+    pub fn take_update(&mut self) -> Option<AccessTreeUpdate> {
+        let update = self.tree.diff(&self.reported);
+        self.reported = self.tree.clone();
+        if update.is_empty() {
+            None
+        }
+        else {
+            Some(update)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(label: &str) -> AccessTree {
+        let mut builder = AccessTreeBuilder::default();
+        builder.begin(AccessNode::new(AccessNodeId(1), AccessRole::Window).with_name("app"));
+        builder.leaf(AccessNode::new(AccessNodeId(2), AccessRole::Button).with_name(label).with_actions(&[AccessAction::Click]));
+        if label == "ok" {
+            builder.leaf(AccessNode::new(AccessNodeId(3), AccessRole::CheckBox).with_toggled(true));
+        }
+        builder.end();
+        builder.finish()
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = tree("ok");
+        let new = tree("cancel");
+        assert_eq!(new.to_text(), "Window \"app\"\n  Button \"cancel\"\n");
+
+        let update = new.diff(&old);
+        assert_eq!(update.removed, vec![AccessNodeId(3)]);
+        assert_eq!(update.updated.iter().map( | node | node.id).collect::<Vec<_>>(), vec![AccessNodeId(1), AccessNodeId(2)]);
+        assert!(new.diff(&new).is_empty());
+
+        let mut applied = old.clone();
+        applied.apply_update(&update);
+        assert_eq!(applied, new);
+
+        let json = new.serialize_json();
+        assert_eq!(AccessTree::deserialize_json(&json).unwrap(), new);
+    }
+
+    #[test]
+    fn test_tracker() {
+        let mut tracker = AccessTreeTracker::default();
+        tracker.set_tree(tree("ok"));
+        assert_eq!(tracker.take_update().unwrap().updated.len(), 3);
+        assert!(tracker.take_update().is_none());
+
+        // frames in between updates are folded into one
+        tracker.set_tree(tree("cancel"));
+        tracker.set_tree(tree("close"));
+        let update = tracker.take_update().unwrap();
+        assert_eq!(update.removed, vec![AccessNodeId(3)]);
+        assert_eq!(update.updated.iter().map( | node | node.id).collect::<Vec<_>>(), vec![AccessNodeId(1), AccessNodeId(2)]);
+        assert_eq!(tracker.tree(), &tree("close"));
+    }
+}
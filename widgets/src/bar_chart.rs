@@ -0,0 +1,266 @@
+use {
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+        chart::*,
+    }
+};
+
+live_design!{
+    BarChartBase = {{BarChart}} {}
+}
+
+/// Bars are drawn from zero to each point's y at its x position. Multiple series
+/// are grouped side by side within the spacing of the x values.
+#[derive(Live)]
+pub struct BarChart {
+    #[walk] walk: Walk,
+    #[live] axes: ChartAxes,
+    #[live] draw_bar: DrawChartBar,
+    // fraction of the x spacing covered by a group of bars
+    #[live(0.8)] bar_fill: f64,
+    #[live(true)] follow: bool,
+    #[live] max_points: usize,
+    #[rust] data: ChartData,
+    #[rust] view: ChartView,
+    #[rust] hover: Option<(usize, usize)>,
+}
+
+impl LiveHook for BarChart {
+    fn before_live_design(cx: &mut Cx) {
+        register_widget!(cx, BarChart)
+    }
+
+    fn after_new_from_doc(&mut self, _cx: &mut Cx) {
+        self.view.following = self.follow;
+    }
+
+    fn after_apply(&mut self, _cx: &mut Cx, _from: ApplyFrom, _index: usize, _nodes: &[LiveNode]) {
+        self.data.max_points = if self.max_points > 0 {Some(self.max_points)} else {None};
+    }
+}
+
+impl BarChart {
+    pub fn handle_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, ChartAction)) {
+        let hit = event.hits(cx, self.axes.area());
+        if self.view.handle_hit(cx, &hit) {
+            self.axes.redraw(cx);
+            dispatch_action(cx, ChartAction::ViewChanged);
+        }
+        match hit {
+            Hit::FingerHoverIn(_) | Hit::FingerHoverOver(_) | Hit::FingerHoverOut(_) | Hit::FingerDown(_) => {
+                let hover = self.find_hover();
+                if hover != self.hover {
+                    self.hover = hover;
+                    self.axes.redraw(cx);
+                    if let Some((series, index)) = hover {
+                        let point = self.data.series[series].points[index];
+                        dispatch_action(cx, ChartAction::Hover {series, index, point});
+                    }
+                    else {
+                        dispatch_action(cx, ChartAction::HoverOut);
+                    }
+                }
+            }
+            _ => ()
+        }
+    }
+
+    // the smallest distance between neighbouring x values, which sets the bar width
+    fn spacing(&self) -> f64 {
+        let mut spacing = f64::INFINITY;
+        for series in &self.data.series {
+            for pair in series.points.windows(2) {
+                let d = pair[1].x - pair[0].x;
+                if d > 0.0 && d < spacing {
+                    spacing = d;
+                }
+            }
+        }
+        if spacing.is_finite() {spacing} else {1.0}
+    }
+
+    // the x range in data space covered by a bar of a series
+    fn bar_span(&self, spacing: f64, series: usize, x: f64) -> (f64, f64) {
+        let count = self.data.series.len().max(1) as f64;
+        let width = spacing * self.bar_fill / count;
+        let x0 = x - spacing * self.bar_fill * 0.5 + width * series as f64;
+        (x0, x0 + width)
+    }
+
+    fn find_hover(&self) -> Option<(usize, usize)> {
+        let pos = self.view.hover?;
+        let map = &self.view.map;
+        if !map.rect.contains(pos) {
+            return None
+        }
+        let x = map.to_data(pos).x;
+        let spacing = self.spacing();
+        for (series_index, series) in self.data.series.iter().enumerate() {
+            let i = series.points.partition_point( | p | p.x < x - spacing);
+            for (index, point) in series.points.iter().enumerate().skip(i) {
+                if point.x > x + spacing {
+                    break;
+                }
+                let (x0, x1) = self.bar_span(spacing, series_index, point.x);
+                if x >= x0 && x <= x1 {
+                    return Some((series_index, index))
+                }
+            }
+        }
+        None
+    }
+
+    pub fn draw_walk(&mut self, cx: &mut Cx2d, walk: Walk) {
+        let spacing = self.spacing();
+        let bounds = self.data.bounds().map( | (min, max) | {
+            (dvec2(min.x - spacing * 0.5, min.y), dvec2(max.x + spacing * 0.5, max.y))
+        });
+        let (x, y) = self.view.resolve(bounds, true);
+        let map = self.axes.begin(cx, walk, x, y);
+        self.view.map = map;
+        let zero = map.to_screen(dvec2(0.0, 0.0)).y;
+
+        self.draw_bar.begin_many_instances(cx);
+        for (series_index, series) in self.data.series.iter().enumerate() {
+            self.draw_bar.color = series.color;
+            let points = &series.points;
+            let start = points.partition_point( | p | p.x < map.x.0 - spacing);
+            let end = points.partition_point( | p | p.x <= map.x.1 + spacing);
+            // bars thinner than a pixel are merged per pixel column, keeping the largest
+            let mut column: Option<(f64, f64)> = None;
+            for (index, point) in points[start..end].iter().enumerate() {
+                let (x0, x1) = self.bar_span(spacing, series_index, point.x);
+                let sx0 = map.to_screen(dvec2(x0, 0.0)).x;
+                let sx1 = map.to_screen(dvec2(x1, 0.0)).x;
+                let sy = map.to_screen(*point).y;
+                self.draw_bar.hover = if self.hover == Some((series_index, start + index)) {1.0} else {0.0};
+                if sx1 - sx0 >= 1.0 {
+                    draw_bar(&mut self.draw_bar, cx, sx0, sx1, sy, zero);
+                    continue;
+                }
+                let col = sx0.floor();
+                match &mut column {
+                    Some((c, y)) if *c == col => {
+                        if (sy - zero).abs() > (*y - zero).abs() {
+                            *y = sy;
+                        }
+                    }
+                    _ => {
+                        if let Some((c, y)) = column {
+                            draw_bar(&mut self.draw_bar, cx, c, c + 1.0, y, zero);
+                        }
+                        column = Some((col, sy));
+                    }
+                }
+            }
+            if let Some((c, y)) = column {
+                draw_bar(&mut self.draw_bar, cx, c, c + 1.0, y, zero);
+            }
+        }
+        self.draw_bar.end_many_instances(cx);
+
+        let hover = self.hover.and_then( | (s, i) | self.data.series.get(s).and_then( | series | series.points.get(i).map( | p | (series, *p))));
+        self.axes.end(cx, &map, hover.map( | (series, point) | (point, series.name.as_str())));
+    }
+
+    pub fn set_series(&mut self, cx: &mut Cx, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        self.data.set_series(index, name, color, points);
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    /// Sets a series from plain values, placed at x = 0, 1, 2, ..
+    pub fn set_values(&mut self, cx: &mut Cx, index: usize, name: &str, color: Vec4, values: &[f64]) {
+        let points = values.iter().enumerate().map( | (i, v) | dvec2(i as f64, *v)).collect();
+        self.set_series(cx, index, name, color, points);
+    }
+
+    pub fn append(&mut self, cx: &mut Cx, index: usize, points: &[DVec2]) {
+        self.data.append(index, points);
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    pub fn clear(&mut self, cx: &mut Cx) {
+        self.data.clear();
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    pub fn reset_view(&mut self, cx: &mut Cx) {
+        self.view.reset(self.follow);
+        self.axes.redraw(cx);
+    }
+}
+
+fn draw_bar(draw_bar: &mut DrawChartBar, cx: &mut Cx2d, x0: f64, x1: f64, y: f64, zero: f64) {
+    let top = y.min(zero);
+    let height = (y - zero).abs().max(1.0);
+    draw_bar.draw_abs(cx, Rect {pos: dvec2(x0, top), size: dvec2((x1 - x0).max(1.0), height)});
+}
+
+impl Widget for BarChart {
+    fn redraw(&mut self, cx: &mut Cx) {
+        self.axes.redraw(cx);
+    }
+
+    fn handle_widget_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem)) {
+        let uid = self.widget_uid();
+        self.handle_event_with(cx, event, &mut | cx, action | {
+            dispatch_action(cx, WidgetActionItem::new(action.into(), uid))
+        });
+    }
+
+    fn walk(&mut self, _cx: &mut Cx) -> Walk {self.walk}
+
+    fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
+        self.draw_walk(cx, walk);
+        WidgetDraw::done()
+    }
+}
+
+#[derive(Clone, PartialEq, WidgetRef)]
+pub struct BarChartRef(WidgetRef);
+
+impl BarChartRef {
+    pub fn set_series(&self, cx: &mut Cx, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_series(cx, index, name, color, points);
+        }
+    }
+
+    pub fn set_values(&self, cx: &mut Cx, index: usize, name: &str, color: Vec4, values: &[f64]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_values(cx, index, name, color, values);
+        }
+    }
+
+    pub fn append(&self, cx: &mut Cx, index: usize, points: &[DVec2]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.append(cx, index, points);
+        }
+    }
+
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear(cx);
+        }
+    }
+
+    pub fn reset_view(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.reset_view(cx);
+        }
+    }
+
+    pub fn hovered(&self, actions: &WidgetActions) -> Option<(usize, usize, DVec2)> {
+        if let Some(item) = actions.find_single_action(self.widget_uid()) {
+            if let ChartAction::Hover {series, index, point} = item.action() {
+                return Some((series, index, point))
+            }
+        }
+        None
+    }
+}
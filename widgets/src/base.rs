@@ -15,6 +15,7 @@ live_design!{
     import crate::fold_header::FoldHeaderBase;
     import crate::hook_widget::HookWidgetBase;
     import crate::image::ImageBase;
+    import crate::svg::SvgBase;
     import crate::rotated_image::RotatedImageBase;
     import crate::video::VideoBase;
     import crate::popup_menu::PopupMenuBase;
@@ -42,6 +43,9 @@ live_design!{
     import crate::page_flip::PageFlipBase;
     import crate::keyboard_view::KeyboardViewBase;
     import crate::window_menu::WindowMenuBase;
+    import crate::line_chart::LineChartBase;
+    import crate::bar_chart::BarChartBase;
+    import crate::scatter_plot::ScatterPlotBase;
     
     import makepad_draw::shader::std::*;
     import makepad_draw::shader::draw_color::DrawColor;
@@ -100,6 +104,12 @@ live_design!{
         }
     }
     
+    Svg = <SvgBase> {
+        width: Fit
+        height: Fit
+        draw_svg: {color: #f}
+    }
+    
     RotatedImage = <RotatedImageBase> {
         
         width: Fit
@@ -589,6 +599,7 @@ live_design!{
     FoldHeaderBase = <FoldHeaderBase> {}
     HookWidgetBase = <HookWidgetBase> {}
     ImageBase = <ImageBase> {}
+    SvgBase = <SvgBase> {}
     RotatedImageBase = <RotatedImageBase> {}
     VideoBase = <VideoBase> {}
     LabelBase = <LabelBase> {}
@@ -611,4 +622,7 @@ live_design!{
     TextInputBase = <TextInputBase>{}
     DrawScrollShadowBase = <DrawScrollShadowBase>{}
     WindowMenuBase = <WindowMenuBase>{}
+    LineChartBase = <LineChartBase>{}
+    BarChartBase = <BarChartBase>{}
+    ScatterPlotBase = <ScatterPlotBase>{}
 }
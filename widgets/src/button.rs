@@ -2,7 +2,8 @@ use {
     crate::{
         makepad_derive_widget::*,
         makepad_draw::*,
-        widget::*
+        widget::*,
+        accessibility::*,
     }
 };
 live_design!{
@@ -74,6 +75,15 @@ impl Widget for Button{
     fn set_text(&mut self, v:&str){
         self.text.as_mut_empty().push_str(v);
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Button)
+                .with_name(self.text.as_ref())
+                .with_bounds(self.draw_bg.area().get_rect(cx))
+                .with_actions(&[AccessAction::Click, AccessAction::Focus])
+        );
+    }
 }
 
 impl Button {
@@ -0,0 +1,493 @@
+use {
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        makepad_platform::event::DigitId,
+        widget::*,
+    }
+};
+
+live_design!{
+    import makepad_draw::shader::std::*;
+
+    DrawChartLine = {{DrawChartLine}} {
+        fn pixel(self) -> vec4 {
+            // distance to the segment p0-p1 in local pixel space
+            let p = self.pos * self.rect_size;
+            let pa = p - self.p0;
+            let ba = self.p1 - self.p0;
+            let h = clamp(dot(pa, ba) / max(dot(ba, ba), 0.0001), 0.0, 1.0);
+            let d = length(pa - ba * h);
+            let alpha = clamp(self.line_width * 0.5 - d + 0.5, 0.0, 1.0);
+            return Pal::premul(vec4(self.color.xyz, self.color.w * alpha));
+        }
+    }
+
+    DrawChartPoint = {{DrawChartPoint}} {
+        fn pixel(self) -> vec4 {
+            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+            let r = 0.5 * min(self.rect_size.x, self.rect_size.y);
+            sdf.circle(self.rect_size.x * 0.5, self.rect_size.y * 0.5, r - 0.5);
+            sdf.fill(mix(self.color, #f, self.hover * 0.5));
+            return sdf.result;
+        }
+    }
+
+    DrawChartBar = {{DrawChartBar}} {
+        fn pixel(self) -> vec4 {
+            let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+            let r = min(self.radius, 0.5 * self.rect_size.x);
+            // only round the end of the bar that points away from the axis
+            sdf.box(0., 0., self.rect_size.x, self.rect_size.y + r, r);
+            sdf.fill(mix(self.color, #f, self.hover * 0.3));
+            return sdf.result;
+        }
+    }
+
+    ChartAxes = {{ChartAxes}} {}
+}
+
+#[derive(Live, LiveHook)]
+#[repr(C)]
+pub struct DrawChartLine {
+    #[deref] draw_super: DrawQuad,
+    #[live] pub color: Vec4,
+    #[live(1.5)] pub line_width: f32,
+    #[live] pub p0: Vec2,
+    #[live] pub p1: Vec2,
+}
+
+impl DrawChartLine {
+    /// Draws a segment between two absolute positions
+    pub fn draw_segment(&mut self, cx: &mut Cx2d, a: DVec2, b: DVec2) {
+        let pad = self.line_width as f64 * 0.5 + 1.0;
+        let pos = dvec2(a.x.min(b.x) - pad, a.y.min(b.y) - pad);
+        let size = dvec2((a.x - b.x).abs() + 2.0 * pad, (a.y - b.y).abs() + 2.0 * pad);
+        self.p0 = (a - pos).into();
+        self.p1 = (b - pos).into();
+        self.draw_abs(cx, Rect {pos, size});
+    }
+}
+
+#[derive(Live, LiveHook)]
+#[repr(C)]
+pub struct DrawChartPoint {
+    #[deref] draw_super: DrawQuad,
+    #[live] pub color: Vec4,
+    #[live] pub hover: f32,
+}
+
+#[derive(Live, LiveHook)]
+#[repr(C)]
+pub struct DrawChartBar {
+    #[deref] draw_super: DrawQuad,
+    #[live] pub color: Vec4,
+    #[live] pub hover: f32,
+    #[live(2.0)] pub radius: f32,
+}
+
+/// One data series of a chart. Line charts expect the points sorted on x,
+/// which is what streaming appends naturally produce.
+#[derive(Clone, Debug, Default)]
+pub struct ChartSeries {
+    pub name: String,
+    pub color: Vec4,
+    pub points: Vec<DVec2>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ChartData {
+    pub series: Vec<ChartSeries>,
+    /// Streaming appends drop the oldest points beyond this count
+    pub max_points: Option<usize>,
+    bounds: Option<(DVec2, DVec2)>,
+}
+
+impl ChartData {
+    pub fn set_series(&mut self, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        while self.series.len() <= index {
+            self.series.push(ChartSeries::default());
+        }
+        self.series[index] = ChartSeries {name: name.to_string(), color, points};
+        self.trim(index);
+        self.bounds = None;
+    }
+
+    pub fn append(&mut self, index: usize, points: &[DVec2]) {
+        while self.series.len() <= index {
+            self.series.push(ChartSeries::default());
+        }
+        self.series[index].points.extend_from_slice(points);
+        if self.trim(index) {
+            self.bounds = None;
+        }
+        else if let Some((min, max)) = &mut self.bounds {
+            for p in points {
+                *min = dvec2(min.x.min(p.x), min.y.min(p.y));
+                *max = dvec2(max.x.max(p.x), max.y.max(p.y));
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.series.clear();
+        self.bounds = None;
+    }
+
+    fn trim(&mut self, index: usize) -> bool {
+        if let Some(max_points) = self.max_points {
+            let points = &mut self.series[index].points;
+            if points.len() > max_points {
+                points.drain(0..points.len() - max_points);
+                return true
+            }
+        }
+        false
+    }
+
+    /// The min and max of all points, or `None` when there is no data
+    pub fn bounds(&mut self) -> Option<(DVec2, DVec2)> {
+        if self.bounds.is_none() {
+            let mut iter = self.series.iter().flat_map( | s | s.points.iter());
+            if let Some(first) = iter.next() {
+                let mut min = *first;
+                let mut max = *first;
+                for p in iter {
+                    min = dvec2(min.x.min(p.x), min.y.min(p.y));
+                    max = dvec2(max.x.max(p.x), max.y.max(p.y));
+                }
+                self.bounds = Some((min, max));
+            }
+        }
+        self.bounds
+    }
+}
+
+/// Maps between data space and screen space for the plot area of a chart
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChartMap {
+    pub rect: Rect,
+    pub x: (f64, f64),
+    pub y: (f64, f64),
+}
+
+impl ChartMap {
+    pub fn to_screen(&self, p: DVec2) -> DVec2 {
+        dvec2(
+            self.rect.pos.x + (p.x - self.x.0) / (self.x.1 - self.x.0) * self.rect.size.x,
+            self.rect.pos.y + self.rect.size.y - (p.y - self.y.0) / (self.y.1 - self.y.0) * self.rect.size.y,
+        )
+    }
+
+    pub fn to_data(&self, p: DVec2) -> DVec2 {
+        dvec2(
+            self.x.0 + (p.x - self.rect.pos.x) / self.rect.size.x * (self.x.1 - self.x.0),
+            self.y.0 + (self.rect.pos.y + self.rect.size.y - p.y) / self.rect.size.y * (self.y.1 - self.y.0),
+        )
+    }
+
+    /// Data units per pixel on the x axis
+    pub fn x_scale(&self) -> f64 {
+        (self.x.1 - self.x.0) / self.rect.size.x.max(1.0)
+    }
+}
+
+/// Generates 'nice' tick positions (1, 2 and 5 times a power of ten) covering
+/// min..max with roughly `count` ticks. Returns the ticks and the step.
+pub fn chart_ticks(min: f64, max: f64, count: usize) -> (Vec<f64>, f64) {
+    let span = max - min;
+    if !span.is_finite() || span <= 0.0 || count == 0 {
+        return (vec![], 0.0)
+    }
+    let rough = span / count as f64;
+    let mag = 10f64.powf(rough.log10().floor());
+    let norm = rough / mag;
+    let nice = if norm < 1.5 {1.0} else if norm < 3.0 {2.0} else if norm < 7.0 {5.0} else {10.0};
+    let step = nice * mag;
+    let mut ticks = Vec::new();
+    let first = (min / step).ceil();
+    // a nice step is at least two thirds of the rough one, so twice the count is always enough
+    for i in 0..count * 2 + 2 {
+        let tick = (first + i as f64) * step;
+        // far from zero the index stops changing once it exceeds 2^53
+        if tick > max + step * 1e-9 || ticks.last() == Some(&tick) {
+            break
+        }
+        ticks.push(tick);
+    }
+    (ticks, step)
+}
+
+/// Formats a tick value with just enough decimals for the given step
+pub fn chart_tick_label(value: f64, step: f64) -> String {
+    let decimals = if step > 0.0 {(-step.log10().floor()).max(0.0) as usize} else {0};
+    let value = if value.abs() < step * 1e-9 {0.0} else {value};
+    format!("{:.*}", decimals, value)
+}
+
+#[derive(Clone, WidgetAction)]
+pub enum ChartAction {
+    /// The pointer is over a data point
+    Hover {series: usize, index: usize, point: DVec2},
+    HoverOut,
+    /// The user panned or zoomed
+    ViewChanged,
+    None
+}
+
+/// The visible range of a chart and the pan/zoom gesture state
+#[derive(Clone, Debug, Default)]
+pub struct ChartView {
+    /// Fixed ranges, `None` fits the data
+    pub x_range: Option<(f64, f64)>,
+    pub y_range: Option<(f64, f64)>,
+    /// Keep the newest data in view when appending
+    pub following: bool,
+    pub hover: Option<DVec2>,
+    pub map: ChartMap,
+    fingers: Vec<(DigitId, DVec2)>,
+}
+
+impl ChartView {
+    pub fn reset(&mut self, follow: bool) {
+        self.x_range = None;
+        self.y_range = None;
+        self.following = follow;
+    }
+
+    /// Resolves the ranges to draw with, fitting the data where no range is fixed
+    pub fn resolve(&mut self, bounds: Option<(DVec2, DVec2)>, include_zero: bool) -> ((f64, f64), (f64, f64)) {
+        let (min, max) = bounds.unwrap_or((dvec2(0.0, 0.0), dvec2(1.0, 1.0)));
+        let x = match self.x_range {
+            Some((x0, x1)) if self.following && x1 < max.x => {
+                // slide the window to the newest data, keeping its width
+                let range = (max.x - (x1 - x0), max.x);
+                self.x_range = Some(range);
+                range
+            }
+            Some(range) => range,
+            None => (min.x, max.x)
+        };
+        let y = match self.y_range {
+            Some(range) => range,
+            None => {
+                let (mut y0, mut y1) = (min.y, max.y);
+                if include_zero {
+                    y0 = y0.min(0.0);
+                    y1 = y1.max(0.0);
+                }
+                let pad = (y1 - y0) * 0.05;
+                (y0 - pad, y1 + pad)
+            }
+        };
+        (widen(x), widen(y))
+    }
+
+    /// Handles pan (drag, horizontal scroll), zoom (scroll wheel, pinch) and hover.
+    /// Returns true when the view changed
+    pub fn handle_hit(&mut self, cx: &mut Cx, hit: &Hit) -> bool {
+        match hit {
+            Hit::FingerDown(fe) => {
+                cx.set_key_focus(Area::Empty);
+                if fe.tap_count == 2 {
+                    // double tap fits the data again
+                    self.reset(true);
+                    self.fingers.clear();
+                    return true
+                }
+                self.fingers.retain( | (id, _) | *id != fe.digit_id);
+                self.fingers.push((fe.digit_id, fe.abs));
+                self.hover = None;
+            }
+            Hit::FingerMove(fe) => {
+                let old = self.fingers.clone();
+                if let Some(finger) = self.fingers.iter_mut().find( | (id, _) | *id == fe.digit_id) {
+                    finger.1 = fe.abs;
+                }
+                else {
+                    return false
+                }
+                let map = self.map;
+                let (from, to, scale) = if old.len() >= 2 {
+                    let (a0, b0) = (old[0].1, old[1].1);
+                    let (a1, b1) = (self.fingers[0].1, self.fingers[1].1);
+                    let d0 = dvec2((b0.x - a0.x).abs(), (b0.y - a0.y).abs());
+                    let d1 = dvec2((b1.x - a1.x).abs(), (b1.y - a1.y).abs());
+                    // zoom each axis by how much the fingers spread on it
+                    let axis_scale = | d0: f64, d1: f64 | if d0 > 20.0 && d1 > 20.0 {d0 / d1} else {1.0};
+                    ((a0 + b0) * 0.5, (a1 + b1) * 0.5, dvec2(axis_scale(d0.x, d1.x), axis_scale(d0.y, d1.y)))
+                }
+                else {
+                    (old[0].1, self.fingers[0].1, dvec2(1.0, 1.0))
+                };
+                self.transform(map, from, to, scale);
+                return true
+            }
+            Hit::FingerUp(fe) => {
+                self.fingers.retain( | (id, _) | *id != fe.digit_id);
+            }
+            Hit::FingerScroll(fe) => {
+                let map = self.map;
+                let zoom = (fe.scroll.y * 0.003).exp();
+                let scale = if fe.modifiers.shift {dvec2(zoom, 1.0)} else {dvec2(zoom, zoom)};
+                let pan = dvec2(-fe.scroll.x, 0.0);
+                self.transform(map, fe.abs, fe.abs + pan, scale);
+                return true
+            }
+            Hit::FingerHoverIn(fe) | Hit::FingerHoverOver(fe) => {
+                self.hover = Some(fe.abs);
+            }
+            Hit::FingerHoverOut(_) => {
+                self.hover = None;
+            }
+            _ => ()
+        }
+        false
+    }
+
+    // keeps the data point under `from` under `to`, scaling the range around it
+    fn transform(&mut self, map: ChartMap, from: DVec2, to: DVec2, scale: DVec2) {
+        if map.rect.size.x <= 0.0 || map.rect.size.y <= 0.0 {
+            return
+        }
+        let anchor = map.to_data(from);
+        let x_span = clamp_span((map.x.1 - map.x.0) * scale.x, anchor.x);
+        let y_span = clamp_span((map.y.1 - map.y.0) * scale.y, anchor.y);
+        let fx = (to.x - map.rect.pos.x) / map.rect.size.x;
+        let fy = (map.rect.pos.y + map.rect.size.y - to.y) / map.rect.size.y;
+        let x0 = anchor.x - fx * x_span;
+        let y0 = anchor.y - fy * y_span;
+        self.x_range = Some((x0, x0 + x_span));
+        self.y_range = Some((y0, y0 + y_span));
+        self.following = false;
+    }
+}
+
+// the smallest span that still has distinct values around `center`, relative to its magnitude
+fn min_span(center: f64) -> f64 {
+    (center.abs() * 1e-12).max(1e-12)
+}
+
+// keeps zooming from narrowing a range past the precision of f64, or widening it past its range
+fn clamp_span(span: f64, center: f64) -> f64 {
+    span.max(min_span(center)).min(1e300)
+}
+
+fn widen(range: (f64, f64)) -> (f64, f64) {
+    let center = (range.0 + range.1) * 0.5;
+    if range.1 - range.0 > min_span(center) {
+        range
+    }
+    else {
+        let half = min_span(center).max(0.5);
+        (center - half, center + half)
+    }
+}
+
+/// Background, grid, tick labels, crosshair and value readout shared by the charts
+#[derive(Live, LiveHook)]
+pub struct ChartAxes {
+    #[live] draw_bg: DrawColor,
+    #[live] draw_grid: DrawColor,
+    #[live] draw_axis: DrawColor,
+    #[live] draw_label: DrawText,
+    #[live] draw_crosshair: DrawColor,
+    #[live] draw_readout: DrawColor,
+    #[live] draw_readout_text: DrawText,
+    #[live] padding: Padding,
+    #[live(6usize)] x_ticks: usize,
+    #[live(5usize)] y_ticks: usize,
+    #[live(true)] show_crosshair: bool,
+}
+
+impl ChartAxes {
+    pub fn area(&self) -> Area {
+        self.draw_bg.area()
+    }
+
+    pub fn redraw(&mut self, cx: &mut Cx) {
+        self.draw_bg.redraw(cx);
+    }
+
+    /// Draws the background, grid and tick labels and begins a turtle clipped to the
+    /// plot area. Series are drawn with absolute positions from the returned map
+    pub fn begin(&mut self, cx: &mut Cx2d, walk: Walk, x: (f64, f64), y: (f64, f64)) -> ChartMap {
+        let rect = cx.walk_turtle(walk);
+        self.draw_bg.draw_abs(cx, rect);
+        let plot = Rect {
+            pos: rect.pos + dvec2(self.padding.left, self.padding.top),
+            size: dvec2(
+                (rect.size.x - self.padding.left - self.padding.right).max(1.0),
+                (rect.size.y - self.padding.top - self.padding.bottom).max(1.0)
+            )
+        };
+        let map = ChartMap {rect: plot, x, y};
+
+        let (x_ticks, x_step) = chart_ticks(x.0, x.1, self.x_ticks);
+        for tick in x_ticks {
+            let sx = map.to_screen(dvec2(tick, y.0)).x;
+            self.draw_grid.draw_abs(cx, Rect {pos: dvec2(sx.floor(), plot.pos.y), size: dvec2(1.0, plot.size.y)});
+            let label = chart_tick_label(tick, x_step);
+            let width = self.label_width(cx, &label);
+            self.draw_label.draw_abs(cx, dvec2(sx - width * 0.5, plot.pos.y + plot.size.y + 4.0), &label);
+        }
+        let (y_ticks, y_step) = chart_ticks(y.0, y.1, self.y_ticks);
+        for tick in y_ticks {
+            let sy = map.to_screen(dvec2(x.0, tick)).y;
+            self.draw_grid.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, sy.floor()), size: dvec2(plot.size.x, 1.0)});
+            let label = chart_tick_label(tick, y_step);
+            let width = self.label_width(cx, &label);
+            let height = self.draw_label.get_line_spacing();
+            self.draw_label.draw_abs(cx, dvec2(plot.pos.x - width - 6.0, sy - height * 0.5), &label);
+        }
+        self.draw_axis.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, plot.pos.y), size: dvec2(1.0, plot.size.y)});
+        self.draw_axis.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, plot.pos.y + plot.size.y), size: dvec2(plot.size.x, 1.0)});
+
+        cx.begin_turtle(Walk::fixed_size(plot.size).with_abs_pos(plot.pos), Layout::default());
+        map
+    }
+
+    /// Ends the plot area and draws the crosshair with a readout of `hover`
+    pub fn end(&mut self, cx: &mut Cx2d, map: &ChartMap, hover: Option<(DVec2, &str)>) {
+        cx.end_turtle();
+        if !self.show_crosshair {
+            return
+        }
+        if let Some((point, name)) = hover {
+            let plot = map.rect;
+            let p = map.to_screen(point);
+            if !plot.contains(p) {
+                return
+            }
+            self.draw_crosshair.draw_abs(cx, Rect {pos: dvec2(p.x.floor(), plot.pos.y), size: dvec2(1.0, plot.size.y)});
+            self.draw_crosshair.draw_abs(cx, Rect {pos: dvec2(plot.pos.x, p.y.floor()), size: dvec2(plot.size.x, 1.0)});
+
+            let (_, x_step) = chart_ticks(map.x.0, map.x.1, self.x_ticks * 10);
+            let (_, y_step) = chart_ticks(map.y.0, map.y.1, self.y_ticks * 10);
+            let text = format!(
+                "{}{}{}, {}",
+                name,
+                if name.is_empty() {""} else {": "},
+                chart_tick_label(point.x, x_step),
+                chart_tick_label(point.y, y_step)
+            );
+            let width = self.draw_readout_text.compute_geom(cx, Walk::fit(), &text).map(| g | g.measured_width).unwrap_or(0.0);
+            let height = self.draw_readout_text.get_line_spacing();
+            let size = dvec2(width + 12.0, height + 8.0);
+            // keep the readout inside the plot, flipping it to the other side of the point
+            let mut pos = p + dvec2(8.0, -size.y - 8.0);
+            if pos.x + size.x > plot.pos.x + plot.size.x {
+                pos.x = p.x - size.x - 8.0;
+            }
+            if pos.y < plot.pos.y {
+                pos.y = p.y + 8.0;
+            }
+            self.draw_readout.draw_abs(cx, Rect {pos, size});
+            self.draw_readout_text.draw_abs(cx, pos + dvec2(6.0, 4.0), &text);
+        }
+    }
+
+    fn label_width(&self, cx: &Cx2d, label: &str) -> f64 {
+        self.draw_label.compute_geom(cx, Walk::fit(), label).map( | g | g.measured_width).unwrap_or(0.0)
+    }
+}
@@ -3,6 +3,7 @@ use {
         makepad_derive_widget::*,
         makepad_draw::*,
         widget::*,
+        accessibility::*,
     }
 };
 
@@ -156,6 +157,16 @@ impl Widget for CheckBox {
     fn set_text(&mut self, v: &str) {
         self.text.as_mut_empty().push_str(v);
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::CheckBox)
+                .with_name(self.text.as_ref())
+                .with_toggled(self.animator_in_state(cx, id!(selected.on)))
+                .with_bounds(self.draw_check.area().get_rect(cx))
+                .with_actions(&[AccessAction::Click])
+        );
+    }
 }
 
 #[derive(Clone, PartialEq, WidgetRef)]
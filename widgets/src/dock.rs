@@ -1,11 +1,14 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use crate::{
     makepad_derive_widget::*,
+    makepad_micro_serde::*,
     widget::*,
+    accessibility::*,
     makepad_draw::*,
     splitter::{SplitterAction, Splitter, SplitterAlign},
     tab::{TabClosable},
     tab_bar::{TabBarAction, TabBar},
+    window::{Window, WindowAction},
 };
 
 live_design!{
@@ -50,6 +53,8 @@ pub struct Dock {
     
     #[live] tab_bar: Option<LivePtr>,
     #[live] splitter: Option<LivePtr>,
+    #[live] floating_window: Option<LivePtr>,
+    #[live(true)] tear_off_tabs: bool,
     
     #[rust] area: Area,
     
@@ -61,6 +66,11 @@ pub struct Dock {
     #[rust] items: ComponentMap<LiveId, (LiveId, WidgetRef)>,
     #[rust] drop_state: Option<DropPosition>,
     #[rust] dock_item_iter_stack: Vec<(LiveId, usize)>,
+    
+    #[rust] floating: Vec<DockFloating>,
+    #[rust] floating_windows: HashMap<LiveId, FloatingWindowWrap>,
+    #[rust] tab_drag: Option<LiveId>,
+    #[rust] drop_window: Option<LiveId>,
 }
 
 pub struct DockVisibleItemIterator<'a> {
@@ -116,6 +126,22 @@ struct TabBarWrap {
     contents_rect: Rect
 }
 
+// A dock subtree that was torn off into its own OS window.
+// The items stay in dock_items, root points at the Tabs or Splitter at the top of the window
+#[derive(Clone, Debug)]
+struct DockFloating {
+    id: LiveId,
+    root: LiveId,
+    position: Option<DVec2>,
+    size: Option<DVec2>,
+}
+
+struct FloatingWindowWrap {
+    window: Window,
+    area: Area,
+    drop_target_draw_list: DrawList2d,
+}
+
 #[derive(Copy, Debug, Clone)]
 enum DrawStackItem {
     Invalid,
@@ -125,7 +151,9 @@ enum DrawStackItem {
     Tabs {id: LiveId},
     TabLabel {id: LiveId, index: usize},
     Tab {id: LiveId},
-    TabContent {id: LiveId}
+    TabContent {id: LiveId},
+    FloatingWindow {index: usize},
+    FloatingWindowEnd {id: LiveId},
 }
 
 impl DrawStackItem {
@@ -161,7 +189,8 @@ pub enum DockAction {
 pub struct DropPosition {
     part: DropPart,
     rect: Rect,
-    id: LiveId
+    id: LiveId,
+    window: Option<LiveId>
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -199,6 +228,142 @@ pub enum DockItem {
     }
 }
 
+// The serializable form of the dock tree, used to persist user rearrangements.
+// LiveIds are stored as their raw u64 value, kinds also store their name when known
+// so a saved layout stays readable.
+#[derive(Clone, Debug, Default, SerJson, DeJson)]
+pub struct DockState {
+    pub items: Vec<DockItemState>,
+    // absent in layouts saved before windows could be torn off
+    pub floating: Option<Vec<DockFloatingState>>,
+}
+
+// a torn off window, root is the id of the dock item shown in it
+#[derive(Clone, Debug, SerJson, DeJson)]
+pub struct DockFloatingState {
+    pub id: u64,
+    pub root: u64,
+    pub position: Option<(f64, f64)>,
+    pub size: Option<(f64, f64)>,
+}
+
+#[derive(Clone, Debug, SerJson, DeJson)]
+pub enum DockItemState {
+    Splitter {
+        id: u64,
+        axis: DockAxisState,
+        align: DockAlignState,
+        a: u64,
+        b: u64
+    },
+    Tabs {
+        id: u64,
+        tabs: Vec<u64>,
+        selected: usize,
+        closable: bool
+    },
+    Tab {
+        id: u64,
+        name: String,
+        closable: bool,
+        kind: u64,
+        kind_name: String
+    }
+}
+
+#[derive(Clone, Copy, Debug, SerJson, DeJson)]
+pub enum DockAxisState {
+    Horizontal,
+    Vertical
+}
+
+#[derive(Clone, Copy, Debug, SerJson, DeJson)]
+pub enum DockAlignState {
+    FromA(f64),
+    FromB(f64),
+    Weighted(f64)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum DockStateError {
+    Json(String),
+    NoRoot,
+    // the root of the dock or of a floating window is a tab instead of a splitter or tab bar
+    TabAsRoot(LiveId),
+    // a splitter refers to a tab, a tab bar to something other than a tab, or an item is
+    // reachable twice, e.g. because it refers to itself or to one of its ancestors
+    InvalidChild {parent: LiveId, child: LiveId},
+}
+
+impl DockItemState {
+    fn id(&self) -> LiveId {
+        match self {
+            Self::Splitter {id, ..} | Self::Tabs {id, ..} | Self::Tab {id, ..} => LiveId(*id)
+        }
+    }
+    
+    fn from_dock_item(id: LiveId, item: &DockItem) -> Self {
+        match item {
+            DockItem::Splitter {axis, align, a, b} => Self::Splitter {
+                id: id.0,
+                axis: match axis {
+                    Axis::Horizontal => DockAxisState::Horizontal,
+                    Axis::Vertical => DockAxisState::Vertical
+                },
+                align: match align {
+                    SplitterAlign::FromA(v) => DockAlignState::FromA(*v),
+                    SplitterAlign::FromB(v) => DockAlignState::FromB(*v),
+                    SplitterAlign::Weighted(v) => DockAlignState::Weighted(*v),
+                },
+                a: a.0,
+                b: b.0
+            },
+            DockItem::Tabs {tabs, selected, closable} => Self::Tabs {
+                id: id.0,
+                tabs: tabs.iter().map( | v | v.0).collect(),
+                selected: *selected,
+                closable: *closable
+            },
+            DockItem::Tab {name, closable, kind} => Self::Tab {
+                id: id.0,
+                name: name.clone(),
+                closable: *closable,
+                kind: kind.0,
+                kind_name: kind.as_string( | v | v.unwrap_or("").to_string())
+            }
+        }
+    }
+    
+    fn to_dock_item(&self) -> DockItem {
+        match self {
+            Self::Splitter {axis, align, a, b, ..} => DockItem::Splitter {
+                axis: match axis {
+                    DockAxisState::Horizontal => Axis::Horizontal,
+                    DockAxisState::Vertical => Axis::Vertical
+                },
+                align: match align {
+                    DockAlignState::FromA(v) => SplitterAlign::FromA(*v),
+                    DockAlignState::FromB(v) => SplitterAlign::FromB(*v),
+                    DockAlignState::Weighted(v) => SplitterAlign::Weighted(*v),
+                },
+                a: LiveId(*a),
+                b: LiveId(*b)
+            },
+            Self::Tabs {tabs, selected, closable, ..} => DockItem::Tabs {
+                tabs: tabs.iter().map( | v | LiveId(*v)).collect(),
+                selected: *selected,
+                closable: *closable
+            },
+            Self::Tab {name, closable, kind, kind_name, ..} => DockItem::Tab {
+                name: name.clone(),
+                closable: *closable,
+                // prefer the name so a layout survives a change in id hashing
+                kind: if kind_name.len() > 0 {LiveId::from_str(kind_name)} else {LiveId(*kind)}
+            }
+        }
+    }
+}
+
 impl LiveHook for Dock {
     fn apply_value_instance(&mut self, cx: &mut Cx, from: ApplyFrom, index: usize, nodes: &[LiveNode]) -> usize {
         let id = nodes[index].id;
@@ -275,39 +440,52 @@ impl Dock {
         
         if self.drop_target_draw_list.begin(cx, Walk::default()).is_redrawing() {
             if let Some(pos) = &self.drop_state {
-                self.drag_quad.draw_abs(cx, pos.rect);
+                if pos.window.is_none() {
+                    self.drag_quad.draw_abs(cx, pos.rect);
+                }
             }
             self.drop_target_draw_list.end(cx);
         }
         
-        self.tab_bars.retain_visible();
-        self.splitters.retain_visible();
+        // floating windows that didn't redraw this frame still need their tab bars and splitters
+        let owners = Self::floating_owners(&self.floating, &self.dock_items);
+        self.tab_bars.retain_visible_and( | id, _ | owners.contains_key(id));
+        self.splitters.retain_visible_and( | id, _ | owners.contains_key(id));
         
         // lets draw the corners here
-        for splitter in self.splitters.values() {
-            self.round_corner.draw_corners(cx, splitter.area_a().get_rect(cx));
-            self.round_corner.draw_corners(cx, splitter.area_b().get_rect(cx));
+        for (splitter_id, splitter) in self.splitters.iter() {
+            if !owners.contains_key(splitter_id) {
+                self.round_corner.draw_corners(cx, splitter.area_a().get_rect(cx));
+                self.round_corner.draw_corners(cx, splitter.area_b().get_rect(cx));
+            }
         }
         self.round_corner.draw_corners(cx, cx.turtle().rect());
         
         cx.end_turtle_with_area(&mut self.area);
     }
     
-    fn find_drop_position(&self, cx: &Cx, abs: DVec2) -> Option<DropPosition> {
+    fn find_drop_position(&self, cx: &Cx, abs: DVec2, window: Option<LiveId>) -> Option<DropPosition> {
+        let owners = Self::floating_owners(&self.floating, &self.dock_items);
         for (tab_bar_id, tab_bar) in self.tab_bars.iter() {
+            // positions are relative to the window, only look at tab bars in the window of the drag
+            if owners.get(tab_bar_id).cloned() != window {
+                continue
+            }
             let rect = tab_bar.contents_rect;
             if let Some((tab_id, rect)) = tab_bar.tab_bar.is_over_tab(cx, abs) {
                 return Some(DropPosition {
                     part: DropPart::Tab,
                     id: tab_id,
-                    rect
+                    rect,
+                    window
                 })
             }
             else if let Some(rect) = tab_bar.tab_bar.is_over_tab_bar(cx, abs) {
                 return Some(DropPosition {
                     part: DropPart::TabBar,
                     id: *tab_bar_id,
-                    rect
+                    rect,
+                    window
                 })
             }
             else if rect.contains(abs) {
@@ -323,7 +501,8 @@ impl Dock {
                                 x: rect.size.x / 2.0,
                                 y: rect.size.y,
                             },
-                        }
+                        },
+                        window
                     })
                 } else if (bottom_right.x - abs.x) / rect.size.x < 0.1 {
                     return Some(DropPosition {
@@ -338,7 +517,8 @@ impl Dock {
                                 x: rect.size.x / 2.0,
                                 y: rect.size.y,
                             },
-                        }
+                        },
+                        window
                     })
                 } else if (abs.y - top_left.y) / rect.size.y < 0.1 {
                     return Some(DropPosition {
@@ -350,7 +530,8 @@ impl Dock {
                                 x: rect.size.x,
                                 y: rect.size.y / 2.0,
                             },
-                        }
+                        },
+                        window
                     })
                 } else if (bottom_right.y - abs.y) / rect.size.y < 0.1 {
                     return Some(DropPosition {
@@ -365,13 +546,15 @@ impl Dock {
                                 x: rect.size.x,
                                 y: rect.size.y / 2.0,
                             },
-                        }
+                        },
+                        window
                     })
                 } else {
                     return Some(DropPosition {
                         part: DropPart::Center,
                         id: *tab_bar_id,
-                        rect
+                        rect,
+                        window
                     })
                 }
             }
@@ -414,6 +597,12 @@ impl Dock {
     }
     
     fn set_parent_split(&mut self, what_item: LiveId, replace_item: LiveId) {
+        for floating in &mut self.floating {
+            if floating.root == what_item {
+                floating.root = replace_item;
+                return
+            }
+        }
         for item in self.dock_items.values_mut() {
             match item {
                 DockItem::Splitter {a, b, ..} => {
@@ -525,7 +714,12 @@ impl Dock {
                     let tabs_id = *tabs_id;
                     tabs.remove(pos);
                     if tabs.len() == 0 { // unsplit
-                        if *closable {
+                        if let Some(floating_id) = self.floating.iter().find( | f | f.root == tabs_id).map( | f | f.id) {
+                            // the last tab left a floating window, the window goes with it
+                            self.dock_items.remove(&tabs_id);
+                            self.remove_floating(cx, floating_id, true);
+                        }
+                        else if *closable {
                             self.unsplit_tabs(cx, tabs_id);
                         }
                         self.area.redraw(cx);
@@ -564,7 +758,7 @@ impl Dock {
     }
     
     fn handle_drop(&mut self, cx: &mut Cx, abs: DVec2, item: LiveId, is_move: bool) -> bool {
-        if let Some(pos) = self.find_drop_position(cx, abs) {
+        if let Some(pos) = self.find_drop_position(cx, abs, self.drop_window) {
             // ok now what
             // we have a pos
             match pos.part {
@@ -720,6 +914,415 @@ impl Dock {
         }
     }
     
+    fn dock_item_accessibility(&self, cx: &Cx, tree: &mut AccessTreeBuilder, id: LiveId) {
+        let uid = self.widget_uid();
+        match self.dock_items.get(&id) {
+            Some(DockItem::Splitter {a, b, ..}) => {
+                tree.begin(AccessNode::new(AccessNodeId::from_widget_sub(uid, id), AccessRole::Group));
+                self.dock_item_accessibility(cx, tree, *a);
+                if let Some(splitter) = self.splitters.get(&id) {
+                    tree.leaf(splitter.access_node(cx, AccessNodeId::from_widget_sub(uid, id.str_append("split_bar"))));
+                }
+                self.dock_item_accessibility(cx, tree, *b);
+                tree.end();
+            }
+            Some(DockItem::Tabs {tabs, selected, ..}) => {
+                tree.begin(AccessNode::new(AccessNodeId::from_widget_sub(uid, id), AccessRole::TabList));
+                for (index, tab_id) in tabs.iter().enumerate() {
+                    if let Some(DockItem::Tab {name, closable, ..}) = self.dock_items.get(tab_id) {
+                        let actions: &[AccessAction] = if *closable {&[AccessAction::Select, AccessAction::Close]} else {&[AccessAction::Select]};
+                        tree.leaf(
+                            AccessNode::new(AccessNodeId::from_widget_sub(uid, *tab_id), AccessRole::Tab)
+                                .with_name(name)
+                                .with_selected(index == *selected)
+                                .with_actions(actions)
+                        );
+                    }
+                }
+                tree.end();
+                if let Some(tab_id) = tabs.get(*selected) {
+                    let mut panel = AccessNode::new(AccessNodeId::from_widget_sub(uid, id.str_append("panel")), AccessRole::TabPanel);
+                    if let Some(tab_bar) = self.tab_bars.get(&id) {
+                        panel = panel.with_bounds(tab_bar.contents_rect);
+                    }
+                    if let Some(DockItem::Tab {name, ..}) = self.dock_items.get(tab_id) {
+                        panel = panel.with_name(name);
+                    }
+                    tree.begin(panel);
+                    if let Some((_, widget)) = self.items.get(tab_id) {
+                        widget.accessibility(cx, tree);
+                    }
+                    tree.end();
+                }
+            }
+            Some(DockItem::Tab {..}) | None => ()
+        }
+    }
+    
+    // checks that the items reachable from the roots form trees of splitters and tab bars with
+    // tabs as leaves, missing items are allowed and are cleaned up by the caller
+    fn validate_items(dock_items: &HashMap<LiveId, DockItem>, floating: &[DockFloating]) -> Result<(), DockStateError> {
+        let mut visited = HashSet::new();
+        let roots = std::iter::once(live_id!(root)).chain(floating.iter().map( | f | f.root));
+        for root in roots {
+            if let Some(DockItem::Tab {..}) = dock_items.get(&root) {
+                return Err(DockStateError::TabAsRoot(root))
+            }
+            if !visited.insert(root) {
+                return Err(DockStateError::InvalidChild {parent: root, child: root})
+            }
+            let mut stack = vec![root];
+            while let Some(parent) = stack.pop() {
+                let (children, wants_tab): (Vec<LiveId>, bool) = match dock_items.get(&parent) {
+                    Some(DockItem::Splitter {a, b, ..}) => (vec![*a, *b], false),
+                    Some(DockItem::Tabs {tabs, ..}) => (tabs.clone(), true),
+                    Some(DockItem::Tab {..}) | None => continue
+                };
+                for child in children {
+                    let Some(item) = dock_items.get(&child) else {continue};
+                    let is_tab = matches!(item, DockItem::Tab {..});
+                    if is_tab != wants_tab || !visited.insert(child) {
+                        return Err(DockStateError::InvalidChild {parent, child})
+                    }
+                    stack.push(child);
+                }
+            }
+        }
+        Ok(())
+    }
+    
+    // the ids of every dock item in the main dock or in one of the floating windows
+    fn reachable_items(dock_items: &HashMap<LiveId, DockItem>, floating: &[DockFloating]) -> HashSet<LiveId> {
+        let mut reachable = HashSet::new();
+        let mut stack: Vec<LiveId> = std::iter::once(live_id!(root)).chain(floating.iter().map( | f | f.root)).collect();
+        while let Some(id) = stack.pop() {
+            if !reachable.insert(id) {
+                continue
+            }
+            match dock_items.get(&id) {
+                Some(DockItem::Splitter {a, b, ..}) => {
+                    stack.push(*a);
+                    stack.push(*b);
+                }
+                Some(DockItem::Tabs {tabs, ..}) => {
+                    stack.extend(tabs.iter());
+                }
+                Some(DockItem::Tab {..}) | None => ()
+            }
+        }
+        reachable
+    }
+    
+    // maps every dock item inside a floating window to the id of that window
+    fn floating_owners(floating: &[DockFloating], dock_items: &HashMap<LiveId, DockItem>) -> HashMap<LiveId, LiveId> {
+        let mut owners = HashMap::new();
+        for floating in floating {
+            let mut stack = vec![floating.root];
+            while let Some(id) = stack.pop() {
+                match dock_items.get(&id) {
+                    Some(DockItem::Splitter {a, b, ..}) => {
+                        stack.push(*a);
+                        stack.push(*b);
+                    }
+                    Some(DockItem::Tabs {tabs, ..}) => {
+                        stack.extend(tabs.iter());
+                    }
+                    Some(DockItem::Tab {..}) | None => ()
+                }
+                owners.insert(id, floating.id);
+            }
+        }
+        owners
+    }
+    
+    fn floating_of_window(&self, window_id: WindowId) -> Option<LiveId> {
+        self.floating_windows.iter().find( | (_, wrap) | wrap.window.window_id() == window_id).map( | (id, _) | *id)
+    }
+    
+    fn first_tabs(&self, id: LiveId) -> Option<LiveId> {
+        match self.dock_items.get(&id) {
+            Some(DockItem::Splitter {a, b, ..}) => self.first_tabs(*a).or_else( || self.first_tabs(*b)),
+            Some(DockItem::Tabs {..}) => Some(id),
+            _ => None
+        }
+    }
+    
+    fn floating_window_or_create(&mut self, cx: &mut Cx, floating_id: LiveId) {
+        if self.floating_windows.contains_key(&floating_id) {
+            return
+        }
+        let Some(floating) = self.floating.iter().find( | f | f.id == floating_id) else {return};
+        let title = self.first_tabs(floating.root).and_then( | tabs_id | {
+            if let Some(DockItem::Tabs {tabs, selected, ..}) = self.dock_items.get(&tabs_id) {
+                if let Some(DockItem::Tab {name, ..}) = tabs.get(*selected).and_then( | tab_id | self.dock_items.get(tab_id)) {
+                    return Some(name.clone())
+                }
+            }
+            None
+        });
+        let window = Window::new_from_ptr(cx, self.floating_window);
+        // the os window gets created after this event, so the create values still apply
+        let cx_window = &mut cx.windows[window.window_id()];
+        if let Some(title) = title {
+            cx_window.create_title = title;
+        }
+        if floating.position.is_some() {
+            cx_window.create_position = floating.position;
+        }
+        if floating.size.is_some() {
+            cx_window.create_inner_size = floating.size;
+        }
+        self.floating_windows.insert(floating_id, FloatingWindowWrap {
+            window,
+            area: Area::Empty,
+            drop_target_draw_list: DrawList2d::new(cx),
+        });
+    }
+    
+    fn remove_floating(&mut self, cx: &mut Cx, floating_id: LiveId, close_window: bool) {
+        self.floating.retain( | f | f.id != floating_id);
+        if let Some(mut wrap) = self.floating_windows.remove(&floating_id) {
+            if close_window {
+                wrap.window.close(cx);
+            }
+        }
+        if self.drop_window == Some(floating_id) {
+            self.drop_window = None;
+        }
+        self.area.redraw(cx);
+    }
+    
+    fn float_tab(&mut self, cx: &mut Cx, tab_id: LiveId, position: Option<DVec2>) -> Option<LiveId> {
+        if !matches!(self.dock_items.get(&tab_id), Some(DockItem::Tab {..})) {
+            return None
+        }
+        // a tab that already has a window to itself stays where it is
+        if let Some(tabs_id) = self.find_tab_bar_of_tab(tab_id) {
+            if let Some(DockItem::Tabs {tabs, ..}) = self.dock_items.get(&tabs_id) {
+                if tabs.len() == 1 && self.floating.iter().any( | f | f.root == tabs_id) {
+                    return None
+                }
+            }
+        }
+        self.close_tab(cx, tab_id, true);
+        
+        let floating_id = LiveId::unique();
+        let tabs_id = LiveId::unique();
+        self.dock_items.insert(tabs_id, DockItem::Tabs {
+            tabs: vec![tab_id],
+            selected: 0,
+            closable: true
+        });
+        self.floating.push(DockFloating {
+            id: floating_id,
+            root: tabs_id,
+            position,
+            size: None
+        });
+        self.floating_window_or_create(cx, floating_id);
+        self.area.redraw(cx);
+        Some(floating_id)
+    }
+    
+    // moves all tabs of a floating window back into the first tab bar of the main dock
+    fn dock_floating(&mut self, cx: &mut Cx, floating_id: LiveId, close_window: bool) {
+        let Some(floating) = self.floating.iter().find( | f | f.id == floating_id) else {return};
+        let mut moved_tabs = Vec::new();
+        let mut stack = vec![floating.root];
+        while let Some(id) = stack.pop() {
+            match self.dock_items.remove(&id) {
+                Some(DockItem::Splitter {a, b, ..}) => {
+                    stack.push(b);
+                    stack.push(a);
+                }
+                Some(DockItem::Tabs {tabs, ..}) => {
+                    moved_tabs.extend(tabs);
+                }
+                Some(tab @ DockItem::Tab {..}) => {
+                    self.dock_items.insert(id, tab);
+                }
+                None => ()
+            }
+        }
+        self.remove_floating(cx, floating_id, close_window);
+        if let Some(target) = self.first_tabs(live_id!(root)) {
+            if let Some(DockItem::Tabs {tabs, selected, ..}) = self.dock_items.get_mut(&target) {
+                if moved_tabs.len() > 0 {
+                    tabs.extend(moved_tabs);
+                    *selected = tabs.len() - 1;
+                }
+            }
+            self.redraw_item(cx, target);
+        }
+        else {
+            for tab_id in moved_tabs {
+                self.dock_items.remove(&tab_id);
+                self.items.remove(&tab_id);
+            }
+        }
+    }
+    
+    fn redraw_drop_targets(&mut self, cx: &mut Cx) {
+        self.drop_target_draw_list.redraw(cx);
+        for wrap in self.floating_windows.values() {
+            wrap.drop_target_draw_list.redraw(cx);
+        }
+    }
+    
+    pub fn save_state(&self) -> DockState {
+        // walk from the roots so the saved order is stable and unreachable items are dropped
+        let mut state = DockState::default();
+        let mut stack: Vec<LiveId> = self.floating.iter().rev().map( | f | f.root).collect();
+        stack.push(live_id!(root));
+        while let Some(id) = stack.pop() {
+            if let Some(item) = self.dock_items.get(&id) {
+                match item {
+                    DockItem::Splitter {a, b, ..} => {
+                        stack.push(*b);
+                        stack.push(*a);
+                    }
+                    DockItem::Tabs {tabs, ..} => {
+                        stack.extend(tabs.iter().rev());
+                    }
+                    DockItem::Tab {..} => ()
+                }
+                state.items.push(DockItemState::from_dock_item(id, item));
+            }
+        }
+        state.floating = Some(self.floating.iter().map( | f | DockFloatingState {
+            id: f.id.0,
+            root: f.root.0,
+            position: f.position.map( | v | (v.x, v.y)),
+            size: f.size.map( | v | (v.x, v.y)),
+        }).collect());
+        state
+    }
+    
+    pub fn load_state(&mut self, cx: &mut Cx, state: &DockState) -> Result<(), DockStateError> {
+        let mut dock_items: HashMap<LiveId, DockItem> = state.items.iter().map( | item | {
+            (item.id(), item.to_dock_item())
+        }).collect();
+        
+        if !dock_items.contains_key(&live_id!(root)) {
+            return Err(DockStateError::NoRoot)
+        }
+        let mut floating: Vec<DockFloating> = state.floating.iter().flatten().map( | f | DockFloating {
+            id: LiveId(f.id),
+            root: LiveId(f.root),
+            position: f.position.map( | (x, y) | dvec2(x, y)),
+            size: f.size.map( | (x, y) | dvec2(x, y)),
+        }).collect();
+        Self::validate_items(&dock_items, &floating)?;
+        
+        // tabs whose kind has no template anymore cannot be recreated, drop them
+        let templates = &self.templates;
+        dock_items.retain( | id, item | {
+            if let DockItem::Tab {kind, ..} = item {
+                if !templates.contains_key(kind) {
+                    log!("Dock::load_state dropping tab {} of unknown kind {}", id, kind);
+                    return false
+                }
+            }
+            true
+        });
+        let existing_tabs: HashSet<LiveId> = dock_items.iter().filter_map( | (id, item) | {
+            if let DockItem::Tab {..} = item {Some(*id)} else {None}
+        }).collect();
+        for item in dock_items.values_mut() {
+            if let DockItem::Tabs {tabs, selected, ..} = item {
+                tabs.retain( | id | existing_tabs.contains(id));
+                if *selected >= tabs.len() {
+                    *selected = tabs.len().max(1) - 1;
+                }
+            }
+        }
+        // splitters pointing at missing children collapse into their remaining child
+        loop {
+            let broken = dock_items.iter().find_map( | (id, item) | {
+                if let DockItem::Splitter {a, b, ..} = item {
+                    match (dock_items.contains_key(a), dock_items.contains_key(b)) {
+                        (true, true) => None,
+                        (true, false) => Some((*id, Some(*a))),
+                        (false, true) => Some((*id, Some(*b))),
+                        (false, false) => Some((*id, None)),
+                    }
+                }
+                else {
+                    None
+                }
+            });
+            let Some((splitter_id, survivor)) = broken else {break};
+            dock_items.remove(&splitter_id);
+            if let Some(survivor) = survivor {
+                if splitter_id == live_id!(root) {
+                    if let Some(item) = dock_items.remove(&survivor) {
+                        dock_items.insert(live_id!(root), item);
+                    }
+                }
+                else if let Some(f) = floating.iter_mut().find( | f | f.root == splitter_id) {
+                    f.root = survivor;
+                }
+                else {
+                    for item in dock_items.values_mut() {
+                        if let DockItem::Splitter {a, b, ..} = item {
+                            if *a == splitter_id {*a = survivor}
+                            if *b == splitter_id {*b = survivor}
+                        }
+                    }
+                }
+            }
+        }
+        if !dock_items.contains_key(&live_id!(root)) {
+            return Err(DockStateError::NoRoot)
+        }
+        // windows that lost all their tabs are not restored
+        floating.retain( | f | match dock_items.get(&f.root) {
+            Some(DockItem::Splitter {..}) => true,
+            Some(DockItem::Tabs {tabs, ..}) => tabs.len() > 0,
+            _ => false
+        });
+        // items that neither the root nor a floating window leads to, like tabs that are in
+        // no tab bar, would never be shown, drop them
+        let reachable = Self::reachable_items(&dock_items, &floating);
+        dock_items.retain( | id, _ | {
+            if !reachable.contains(id) {
+                log!("Dock::load_state dropping item {} that is not in the layout", id);
+                return false
+            }
+            true
+        });
+        let tab_ids: Vec<LiveId> = dock_items.iter().filter_map( | (id, item) | {
+            if let DockItem::Tab {..} = item {Some(*id)} else {None}
+        }).collect();
+        
+        self.dock_items = dock_items;
+        let old_floating: Vec<LiveId> = self.floating_windows.keys().cloned().collect();
+        for floating_id in old_floating {
+            if !floating.iter().any( | f | f.id == floating_id) {
+                if let Some(mut wrap) = self.floating_windows.remove(&floating_id) {
+                    wrap.window.close(cx);
+                }
+            }
+        }
+        self.floating = floating;
+        for floating_id in self.floating.iter().map( | f | f.id).collect::<Vec<_>>() {
+            self.floating_window_or_create(cx, floating_id);
+        }
+        // existing tab contents are kept only if the tab is still of the same kind
+        let dock_items = &self.dock_items;
+        self.items.retain( | id, (kind, _) | {
+            matches!(dock_items.get(id), Some(DockItem::Tab {kind: tab_kind, ..}) if tab_kind == kind)
+        });
+        for id in tab_ids {
+            if let Some(DockItem::Tab {kind, ..}) = self.dock_items.get(&id) {
+                let kind = *kind;
+                self.item_or_create(cx, id, kind);
+            }
+        }
+        self.area.redraw(cx);
+        Ok(())
+    }
+    
     pub fn drawing_item_id(&self) -> Option<LiveId> {
         if let Some(stack) = self.draw_state.as_ref() {
             match stack.last() {
@@ -741,10 +1344,49 @@ impl Widget for Dock {
     }
     
     fn handle_widget_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem)) {
-        // call handle on all tab bars, splitters,
         let uid = self.widget_uid();
+        
+        // floating windows get their own window events, a closed window docks its tabs back
+        let mut closed = Vec::new();
+        for (floating_id, wrap) in self.floating_windows.iter_mut() {
+            let floating = &mut self.floating;
+            wrap.window.handle_event_with(cx, event, &mut | _, action | match action {
+                WindowAction::WindowClosed => closed.push(*floating_id),
+                WindowAction::WindowGeomChange(ev) => if let Some(f) = floating.iter_mut().find( | f | f.id == *floating_id) {
+                    f.position = Some(ev.new_geom.position);
+                    f.size = Some(ev.new_geom.inner_size);
+                }
+                _ => ()
+            });
+        }
+        for floating_id in closed {
+            self.dock_floating(cx, floating_id, false);
+        }
+        
+        // pointer events only go to the parts of the dock that live in the window they happened in
+        let event_window = match event {
+            Event::MouseDown(e) => Some(e.window_id),
+            Event::MouseMove(e) => Some(e.window_id),
+            Event::MouseUp(e) => Some(e.window_id),
+            Event::Scroll(e) => Some(e.window_id),
+            Event::TouchUpdate(e) => Some(e.window_id),
+            _ => None
+        };
+        let owners = if self.floating.len() > 0 && event_window.is_some() {
+            Self::floating_owners(&self.floating, &self.dock_items)
+        }
+        else {
+            HashMap::new()
+        };
+        let event_floating = event_window.and_then( | window_id | self.floating_of_window(window_id));
+        let in_window = | id: &LiveId | event_window.is_none() || owners.get(id).cloned() == event_floating;
+        
+        // call handle on all tab bars, splitters,
         let dock_items = &mut self.dock_items;
         for (panel_id, splitter) in self.splitters.iter_mut() {
+            if !in_window(panel_id) {
+                continue
+            }
             splitter
                 .handle_event_with(cx, event, &mut | cx, action | match action {
                 SplitterAction::Changed {axis, align} => {
@@ -759,6 +1401,9 @@ impl Widget for Dock {
             });
         }
         for (panel_id, tab_bar) in self.tab_bars.iter_mut() {
+            if !in_window(panel_id) {
+                continue
+            }
             let contents_view = &mut tab_bar.contents_draw_list;
             for action in tab_bar.tab_bar.handle_event(cx, event) {
                 match action {
@@ -784,36 +1429,55 @@ impl Widget for Dock {
                 }
             };
         }
-        for (_, item) in self.items.values_mut() {
+        for (item_id, (_, item)) in self.items.iter_mut() {
+            if !in_window(item_id) {
+                continue
+            }
             item.handle_widget_event_with(cx, event, dispatch_action);
         }
         
-        if let Event::DragEnd = event {
-            // end our possible dragstate
-            self.drop_state = None;
-            self.drop_target_draw_list.redraw(cx);
+        match event {
+            Event::Drop(_) => {
+                // the tab landed somewhere in one of our windows
+                self.tab_drag = None;
+            }
+            Event::DragEnd(e) => {
+                // end our possible dragstate
+                self.drop_state = None;
+                self.redraw_drop_targets(cx);
+                // a tab dragged out of every window without being dropped becomes a window of
+                // its own, where it was released
+                if let Some(tab_id) = self.tab_drag.take() {
+                    self.float_tab(cx, tab_id, Some(e.screen_pos));
+                }
+            }
+            _ => ()
         }
         
         // alright lets manage the drag areas
-        match event.drag_hits(cx, self.area) {
-            DragHit::Drag(f) => {
-                self.drop_state = None;
-                self.drop_target_draw_list.redraw(cx);
-                match f.state {
-                    DragState::In | DragState::Over => {
-                        dispatch_action(cx, DockAction::Drag(f.clone()).into_action(uid))
+        let mut drop_areas = vec![(None, self.area)];
+        drop_areas.extend(self.floating_windows.iter().map( | (id, wrap) | (Some(*id), wrap.area)));
+        for (window, area) in drop_areas {
+            match event.drag_hits(cx, area) {
+                DragHit::Drag(f) => {
+                    self.drop_state = None;
+                    self.redraw_drop_targets(cx);
+                    match f.state {
+                        DragState::In | DragState::Over => {
+                            dispatch_action(cx, DockAction::Drag(f.clone()).into_action(uid))
+                        }
+                        DragState::Out => {}
                     }
-                    DragState::Out => {}
                 }
+                DragHit::Drop(f) => {
+                    self.drop_state = None;
+                    self.drop_window = window;
+                    self.redraw_drop_targets(cx);
+                    dispatch_action(cx, DockAction::Drop(f.clone()).into_action(uid))
+                }
+                _ => {}
             }
-            DragHit::Drop(f) => {
-                self.drop_state = None;
-                self.drop_target_draw_list.redraw(cx);
-                dispatch_action(cx, DockAction::Drop(f.clone()).into_action(uid))
-            }
-            _ => {}
         }
-        
     }
     
     fn find_widgets(&mut self, path: &[LiveId], cached: WidgetCache, results: &mut WidgetSet) {
@@ -834,10 +1498,29 @@ impl Widget for Dock {
     
     fn walk(&mut self, _cx: &mut Cx) -> Walk {self.walk}
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.begin(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Group)
+                .with_bounds(self.area.get_rect(cx))
+        );
+        self.dock_item_accessibility(cx, tree, live_id!(root));
+        tree.end();
+        for floating in &self.floating {
+            let mut node = AccessNode::new(AccessNodeId::from_widget_sub(self.widget_uid(), floating.id), AccessRole::Window);
+            if let Some(wrap) = self.floating_windows.get(&floating.id) {
+                node = node.with_name(&cx.windows[wrap.window.window_id()].create_title);
+            }
+            tree.begin(node);
+            self.dock_item_accessibility(cx, tree, floating.root);
+            tree.end();
+        }
+    }
+    
     fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
         if self.draw_state.begin_with(cx, &self.dock_items, | _, dock_items | {
             let id = live_id!(root);
-            vec![DrawStackItem::from_dock_item(id, dock_items.get(&id))]
+            // the main tree first, then the floating windows
+            vec![DrawStackItem::FloatingWindow {index: 0}, DrawStackItem::from_dock_item(id, dock_items.get(&id))]
         }) {
             self.begin(cx, walk);
         }
@@ -936,6 +1619,42 @@ impl Widget for Dock {
                     }
                     else {panic!()}
                 }
+                Some(DrawStackItem::FloatingWindow {index}) => {
+                    if let Some(floating) = self.floating.get(index) {
+                        let (id, root) = (floating.id, floating.root);
+                        stack.push(DrawStackItem::FloatingWindow {index: index + 1});
+                        if let Some(wrap) = self.floating_windows.get_mut(&id) {
+                            if wrap.window.begin(cx).is_redrawing() {
+                                wrap.window.widget(id!(caption_bar)).draw_widget_all(cx);
+                                cx.begin_turtle(Walk::default(), self.layout);
+                                stack.push(DrawStackItem::FloatingWindowEnd {id});
+                                stack.push(DrawStackItem::from_dock_item(root, self.dock_items.get(&root)));
+                            }
+                        }
+                    }
+                }
+                Some(DrawStackItem::FloatingWindowEnd {id}) => {
+                    let owners = Self::floating_owners(&self.floating, &self.dock_items);
+                    if let Some(wrap) = self.floating_windows.get_mut(&id) {
+                        if wrap.drop_target_draw_list.begin(cx, Walk::default()).is_redrawing() {
+                            if let Some(pos) = &self.drop_state {
+                                if pos.window == Some(id) {
+                                    self.drag_quad.draw_abs(cx, pos.rect);
+                                }
+                            }
+                            wrap.drop_target_draw_list.end(cx);
+                        }
+                        for (splitter_id, splitter) in self.splitters.iter() {
+                            if owners.get(splitter_id) == Some(&id) {
+                                self.round_corner.draw_corners(cx, splitter.area_a().get_rect(cx));
+                                self.round_corner.draw_corners(cx, splitter.area_b().get_rect(cx));
+                            }
+                        }
+                        self.round_corner.draw_corners(cx, cx.turtle().rect());
+                        cx.end_turtle_with_area(&mut wrap.area);
+                        wrap.window.end(cx);
+                    }
+                }
                 Some(DrawStackItem::Invalid) => {}
                 None => {
                     break
@@ -1018,7 +1737,8 @@ impl DockRef {
     // user wants to drag, set dh accordingly
     pub fn accept_drag(&self, cx: &mut Cx, dh: DragHitEvent, dr: DragResponse) {
         if let Some(mut dock) = self.borrow_mut() {
-            if let Some(pos) = dock.find_drop_position(cx, dh.abs) {
+            let window = dock.floating_of_window(dh.window_id);
+            if let Some(pos) = dock.find_drop_position(cx, dh.abs, window) {
                 dh.response.set(dr);
                 dock.drop_state = Some(pos);
             }
@@ -1092,9 +1812,54 @@ impl DockRef {
         }
     }
     
-    pub fn tab_start_drag(&self, cx: &mut Cx, _tab_id: LiveId, item: DragItem) {
+    pub fn tab_start_drag(&self, cx: &mut Cx, tab_id: LiveId, item: DragItem) {
+        if let Some(mut dock) = self.borrow_mut() {
+            // without drags that leave the window a tab could never be torn off
+            if dock.tear_off_tabs && cx.os_drags_leave_windows() {
+                dock.tab_drag = Some(tab_id);
+            }
+        }
         cx.start_dragging(vec![item]);
     }
+    
+    // tears a tab off into a new window, returns the id of the floating window
+    pub fn float_tab(&self, cx: &mut Cx, tab_id: LiveId, position: Option<DVec2>) -> Option<LiveId> {
+        if let Some(mut dock) = self.borrow_mut() {
+            return dock.float_tab(cx, tab_id, position)
+        }
+        None
+    }
+    
+    pub fn dock_floating(&self, cx: &mut Cx, floating_id: LiveId) {
+        if let Some(mut dock) = self.borrow_mut() {
+            dock.dock_floating(cx, floating_id, true);
+        }
+    }
+    
+    pub fn save_state(&self) -> Option<DockState> {
+        if let Some(dock) = self.borrow() {
+            return Some(dock.save_state())
+        }
+        None
+    }
+    
+    pub fn load_state(&self, cx: &mut Cx, state: &DockState) -> Result<(), DockStateError> {
+        if let Some(mut dock) = self.borrow_mut() {
+            return dock.load_state(cx, state)
+        }
+        Ok(())
+    }
+    
+    pub fn save_state_json(&self) -> Option<String> {
+        self.save_state().map( | state | state.serialize_json())
+    }
+    
+    pub fn load_state_json(&self, cx: &mut Cx, json: &str) -> Result<(), DockStateError> {
+        match DockState::deserialize_json(json) {
+            Ok(state) => self.load_state(cx, &state),
+            Err(err) => Err(DockStateError::Json(format!("{:?}", err)))
+        }
+    }
 }
 
 #[derive(Clone, WidgetSet)]
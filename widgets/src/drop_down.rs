@@ -6,6 +6,7 @@ use {
         popup_menu::{PopupMenu, PopupMenuAction},
         makepad_draw::*,
         widget::*,
+        accessibility::*,
     }
 };
 
@@ -285,6 +286,16 @@ impl Widget for DropDown {
         self.draw_walk(cx, walk);
         WidgetDraw::done()
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let value = self.labels.get(self.selected_item).map( | v | v.as_str()).unwrap_or("");
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::ComboBox)
+                .with_value(value)
+                .with_bounds(self.draw_bg.area().get_rect(cx))
+                .with_actions(&[AccessAction::Click, AccessAction::Expand])
+        );
+    }
 }
 
 #[derive(Clone, PartialEq, WidgetRef)]
@@ -182,6 +182,7 @@ impl FileTreeNode {
                 right: depth as f64 * 4.0,
                 bottom: 0.0,
             },
+            ..Walk::default()
         }
     }
     
@@ -405,7 +406,7 @@ impl FileTree {
         self.scroll_bars.handle_event_with(cx, event, &mut | _, _ | {});
         
         match event {
-            Event::DragEnd => self.dragging_node_id = None,
+            Event::DragEnd(_) => self.dragging_node_id = None,
             _ => ()
         }
         
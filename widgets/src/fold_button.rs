@@ -2,6 +2,7 @@ use crate::{
     makepad_derive_widget::*,
     makepad_draw::*,
     widget::*,
+    accessibility::*,
 };
 
 live_design!{
@@ -121,6 +122,16 @@ impl Widget for FoldButton {
         self.draw_walk(cx, walk);
         WidgetDraw::done()
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let open = self.animator_in_state(cx, id!(open.yes));
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::DisclosureTriangle)
+                .with_toggled(open)
+                .with_bounds(self.draw_bg.area().get_rect(cx))
+                .with_actions(&[if open {AccessAction::Collapse} else {AccessAction::Expand}])
+        );
+    }
 }
 
 
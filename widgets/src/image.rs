@@ -2,7 +2,8 @@ use crate::{
     makepad_derive_widget::*,
     image_cache::*,
     makepad_draw::*,
-    widget::*
+    widget::*,
+    accessibility::*,
 };
 
 live_design!{
@@ -13,8 +14,11 @@ live_design!{
 pub struct Image {
     #[walk] walk: Walk,
     #[live] draw_bg: DrawQuad,
-    #[live] min_width: i64,
-    #[live] min_height: i64,
+    // the size in physical pixels that the aspect ratio is taken from until a texture is loaded,
+    // which unlike the min size of the walk doesn't constrain the layout. This used to be set
+    // with min_width and min_height, those are the walk's limits now as on every other widget
+    #[live] fallback_width: i64,
+    #[live] fallback_height: i64,
     #[live(1.0)] width_scale: f64,
     #[live] fit: ImageFit,
     #[live] source: LiveDependency,
@@ -57,6 +61,13 @@ impl Widget for Image {
     fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
         self.draw_walk(cx, walk)
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Image)
+                .with_bounds(self.draw_bg.area().get_rect(cx))
+        );
+    }
 }
 
 impl Image {
@@ -69,13 +80,13 @@ impl Image {
         let (width, height) = if let Some(image_texture) = &self.texture {
             self.draw_bg.draw_vars.set_texture(0, image_texture);
             let desc = image_texture.get_desc(cx);
-            let width = desc.width.unwrap_or(self.min_width as usize) as f64 / dpi;
-            let height = desc.height.unwrap_or(self.min_height as usize) as f64 / dpi;
+            let width = desc.width.unwrap_or(self.fallback_width as usize) as f64 / dpi;
+            let height = desc.height.unwrap_or(self.fallback_height as usize) as f64 / dpi;
             (width*self.width_scale,height)
         }
         else {
             self.draw_bg.draw_vars.empty_texture(0);
-            (self.min_width as f64 / dpi, self.min_height as f64 / dpi)
+            (self.fallback_width as f64 / dpi, self.fallback_height as f64 / dpi)
         };
         
         let aspect = width / height;
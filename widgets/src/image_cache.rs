@@ -1,7 +1,8 @@
 use crate::{makepad_draw::*};
 use std::collections::HashMap;
-use makepad_zune_jpeg::JpegDecoder;
-use makepad_zune_png::PngDecoder;
+// ImageBuffer lived here before it moved to the platform, the decoding methods come with
+// the ImageBufferDecode trait
+pub use crate::makepad_draw::{ImageBuffer, image_decode::ImageBufferDecode};
 
 
 #[derive(Live, LiveHook)]
@@ -15,102 +16,6 @@ pub enum ImageFit{
 }
 
 
-#[derive(Default, Clone)] 
-pub struct ImageBuffer {
-    pub width: usize,
-    pub height: usize,
-    pub data: Vec<u32>,
-}
-
-impl ImageBuffer {
-    pub fn new(in_data: &[u8], width: usize,height: usize) -> Result<ImageBuffer, String> {
-        let mut out = Vec::new();
-        let pixels = width * height;
-        out.resize(pixels, 0u32);
-        // input pixel packing
-        if in_data.len() /  pixels== 3{
-            for i in 0..pixels{
-                let r = in_data[i*3];
-                let g = in_data[i*3+1];
-                let b = in_data[i*3+2];
-                out[i] = 0xff000000 | ((r as u32)<<16) | ((g as u32)<<8) | ((b as u32)<<0);
-            }
-        }
-        else if in_data.len() / pixels == 4{
-            for i in 0..pixels{
-                let r = in_data[i*4];
-                let g = in_data[i*4+1];
-                let b = in_data[i*4+2];
-                let a = in_data[i*4+3];
-                out[i] = ((a as u32)<<24) | ((r as u32)<<16) | ((g as u32)<<8) | ((b as u32)<<0);
-            }
-        }
-        else{
-            return Err("ImageBuffer::new Image buffer pixel alignment not 3 or 4".to_string())
-        }
-        Ok(ImageBuffer {
-            width,
-            height,
-            data: out
-        })
-    }
-    
-    pub fn into_new_texture(self, cx:&mut Cx)->Texture{
-        let texture = Texture::new(cx);
-        self.into_texture(cx, &texture);
-        texture
-    }
-    
-    pub fn into_texture(mut self, cx:&mut Cx, texture:&Texture){
-        texture.set_desc(
-            cx,
-            TextureDesc {
-                format: TextureFormat::ImageBGRA,
-                width: Some(self.width),
-                height: Some(self.height),
-            },
-        );
-        texture.swap_image_u32(cx, &mut self.data);
-    }
-    
-    
-    pub fn from_png(
-        data: &[u8]
-    ) -> Result<Self, String> {
-        let mut decoder = PngDecoder::new(data);
-        match decoder.decode() {
-            Ok(image) => {
-                if let Some(data) = image.u8(){
-                    let (width,height) = decoder.get_dimensions().unwrap();
-                    ImageBuffer::new(&data, width as usize, height as usize)
-                }
-                else{
-                    Err("Error decoding PNG: image data empty".to_string())
-                }
-            }
-            Err(err) => {
-                Err(format!("Error decoding PNG: {:?}", err))
-            }
-        }
-    }
-
-    pub fn from_jpg(
-        data: &[u8]
-    ) -> Result<Self, String> {
-        let mut decoder = JpegDecoder::new(&*data);
-        // decode the file
-        match decoder.decode() {
-            Ok(data) => {
-                let info = decoder.info().unwrap();
-                ImageBuffer::new(&data, info.width as usize, info.height as usize)
-            },
-            Err(err) => {
-                Err(format!("Error decoding JPG: {:?}", err))
-            }
-        }
-    }
-}
-
 pub struct ImageCache {
     map: HashMap<String, Texture>,
 }
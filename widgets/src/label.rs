@@ -2,7 +2,8 @@ use {
     crate::{
         makepad_derive_widget::*,
         makepad_draw::*,
-        widget::*
+        widget::*,
+        accessibility::*,
     }
 };
 
@@ -47,6 +48,14 @@ impl Widget for Label {
     fn set_text(&mut self, v:&str){
         self.text.as_mut_empty().push_str(v);
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Label)
+                .with_name(self.text.as_ref())
+                .with_bounds(self.draw_text.area().get_rect(cx))
+        );
+    }
 }
 
 
@@ -8,6 +8,7 @@ pub use makepad_derive_widget::*;
 pub mod button;
 pub mod label;
 pub mod image;
+pub mod svg;
 pub mod link_label;
 pub mod drop_down;
 pub mod popup_menu;
@@ -44,25 +45,35 @@ pub mod flat_list;
 pub mod file_tree;
 pub mod slides_view;
 pub mod color_picker;
+pub mod chart;
+pub mod line_chart;
+pub mod bar_chart;
+pub mod scatter_plot;
 
 pub mod debug_view;
 pub mod nav_control;
 
 pub mod view;
 pub mod widget;
+pub mod accessibility;
 
 #[macro_use]
 pub mod data_binding;
 
 mod base;
 mod theme_desktop_dark;
+mod theme_desktop_light;
+mod theme_desktop_high_contrast;
+pub mod theme;
 pub mod image_cache;
 
 pub use crate::{
     data_binding::{DataBindingStore, DataBindingMap},
+    theme::{Theme, CxThemeApi},
     button::*,
     view::*,
     image::*,
+    svg::*,
     label::*,
     slider::*,
     check_box::*,
@@ -83,6 +94,21 @@ pub use crate::{
     scroll_shadow::{DrawScrollShadow},
     scroll_bar::{ScrollBar},
     slides_view::{SlidesView},
+    chart::{ChartAction, ChartSeries},
+    line_chart::*,
+    bar_chart::*,
+    scatter_plot::*,
+    accessibility::{
+        AccessTree,
+        AccessTreeBuilder,
+        AccessTreeUpdate,
+        AccessTreeTracker,
+        AccessNode,
+        AccessNodeId,
+        AccessRole,
+        AccessAction,
+        AccessToggled,
+    },
     widget::{
         WidgetSet,
         WidgetSetIterator,
@@ -111,10 +137,13 @@ pub fn live_design(cx: &mut Cx) {
     crate::splitter::live_design(cx);
     crate::base::live_design(cx);
     crate::theme_desktop_dark::live_design(cx);
+    crate::theme_desktop_light::live_design(cx);
+    crate::theme_desktop_high_contrast::live_design(cx);
     crate::slider::live_design(cx);
     crate::label::live_design(cx);
     crate::nav_control::live_design(cx);
     crate::image::live_design(cx);
+    crate::svg::live_design(cx);
     crate::rotated_image::live_design(cx);
     crate::video::live_design(cx);
     crate::view::live_design(cx);
@@ -142,6 +171,10 @@ pub fn live_design(cx: &mut Cx) {
     crate::tab_bar::live_design(cx);
     crate::dock::live_design(cx);
     crate::color_picker::live_design(cx);
+    crate::chart::live_design(cx);
+    crate::line_chart::live_design(cx);
+    crate::bar_chart::live_design(cx);
+    crate::scatter_plot::live_design(cx);
     crate::file_tree::live_design(cx);
     crate::slides_view::live_design(cx);
     crate::tab_close_button::live_design(cx);
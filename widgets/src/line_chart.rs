@@ -0,0 +1,266 @@
+use {
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+        chart::*,
+    }
+};
+
+live_design!{
+    LineChartBase = {{LineChart}} {}
+}
+
+#[derive(Live)]
+pub struct LineChart {
+    #[walk] walk: Walk,
+    #[live] axes: ChartAxes,
+    #[live] draw_line: DrawChartLine,
+    #[live] draw_marker: DrawChartPoint,
+    #[live(7.0)] marker_size: f64,
+    // keep the newest points in view while streaming
+    #[live(true)] follow: bool,
+    // oldest points are dropped beyond this count, 0 keeps everything
+    #[live] max_points: usize,
+    #[rust] data: ChartData,
+    #[rust] view: ChartView,
+    #[rust] hover: Option<(usize, usize)>,
+}
+
+impl LiveHook for LineChart {
+    fn before_live_design(cx: &mut Cx) {
+        register_widget!(cx, LineChart)
+    }
+
+    fn after_apply(&mut self, _cx: &mut Cx, _from: ApplyFrom, _index: usize, _nodes: &[LiveNode]) {
+        self.view.following = self.follow;
+        self.data.max_points = if self.max_points > 0 {Some(self.max_points)} else {None};
+    }
+}
+
+impl LineChart {
+    pub fn handle_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, ChartAction)) {
+        let hit = event.hits(cx, self.axes.area());
+        if self.view.handle_hit(cx, &hit) {
+            self.axes.redraw(cx);
+            dispatch_action(cx, ChartAction::ViewChanged);
+        }
+        match hit {
+            Hit::FingerHoverIn(_) | Hit::FingerHoverOver(_) | Hit::FingerHoverOut(_) | Hit::FingerDown(_) => {
+                let hover = self.find_hover();
+                if hover != self.hover {
+                    self.hover = hover;
+                    self.axes.redraw(cx);
+                    if let Some((series, index)) = hover {
+                        let point = self.data.series[series].points[index];
+                        dispatch_action(cx, ChartAction::Hover {series, index, point});
+                    }
+                    else {
+                        dispatch_action(cx, ChartAction::HoverOut);
+                    }
+                }
+            }
+            _ => ()
+        }
+    }
+
+    // the nearest point on screen to the pointer, looking only around its x position
+    fn find_hover(&self) -> Option<(usize, usize)> {
+        let pos = self.view.hover?;
+        let map = &self.view.map;
+        if !map.rect.contains(pos) {
+            return None
+        }
+        let x = map.to_data(pos).x;
+        let mut best = None;
+        let mut best_dist = f64::INFINITY;
+        for (series_index, series) in self.data.series.iter().enumerate() {
+            let i = series.points.partition_point( | p | p.x < x);
+            for index in [i.saturating_sub(1), i] {
+                if let Some(point) = series.points.get(index) {
+                    let dist = map.to_screen(*point).distance(&pos);
+                    if dist < best_dist {
+                        best_dist = dist;
+                        best = Some((series_index, index));
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    pub fn draw_walk(&mut self, cx: &mut Cx2d, walk: Walk) {
+        let bounds = self.data.bounds();
+        let (x, y) = self.view.resolve(bounds, false);
+        let map = self.axes.begin(cx, walk, x, y);
+        self.view.map = map;
+
+        self.draw_line.begin_many_instances(cx);
+        for series in &self.data.series {
+            draw_line_series(&mut self.draw_line, cx, &map, series);
+        }
+        self.draw_line.end_many_instances(cx);
+
+        let hover = self.hover.and_then( | (s, i) | self.data.series.get(s).and_then( | series | series.points.get(i).map( | p | (series, *p))));
+        if let Some((series, point)) = hover {
+            let p = map.to_screen(point);
+            self.draw_marker.color = series.color;
+            self.draw_marker.draw_abs(cx, Rect {
+                pos: p - dvec2(self.marker_size, self.marker_size) * 0.5,
+                size: dvec2(self.marker_size, self.marker_size)
+            });
+        }
+        self.axes.end(cx, &map, hover.map( | (series, point) | (point, series.name.as_str())));
+    }
+
+    pub fn set_series(&mut self, cx: &mut Cx, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        self.data.set_series(index, name, color, points);
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    /// Appends points to a series, for real time data
+    pub fn append(&mut self, cx: &mut Cx, index: usize, points: &[DVec2]) {
+        self.data.append(index, points);
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    pub fn clear(&mut self, cx: &mut Cx) {
+        self.data.clear();
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    /// Fits the data again after panning or zooming
+    pub fn reset_view(&mut self, cx: &mut Cx) {
+        self.view.reset(self.follow);
+        self.axes.redraw(cx);
+    }
+
+    /// Shows a fixed window of the x axis, which slides along with appended data when following
+    pub fn set_x_range(&mut self, cx: &mut Cx, min: f64, max: f64) {
+        self.view.x_range = Some((min, max));
+        self.axes.redraw(cx);
+    }
+}
+
+/// Draws a polyline of the visible points. When there are more points than pixel
+/// columns each column is reduced to its min/max envelope, so the instance count
+/// stays bounded by the plot width
+fn draw_line_series(draw_line: &mut DrawChartLine, cx: &mut Cx2d, map: &ChartMap, series: &ChartSeries) {
+    let points = &series.points;
+    if points.len() < 2 {
+        return
+    }
+    draw_line.color = series.color;
+    // one extra point on each side so the line runs off the plot edges
+    let start = points.partition_point( | p | p.x < map.x.0).saturating_sub(1);
+    let end = (points.partition_point( | p | p.x <= map.x.1) + 1).min(points.len());
+    if end <= start + 1 {
+        return
+    }
+    let visible = &points[start..end];
+
+    if visible.len() <= map.rect.size.x as usize * 2 {
+        let mut last = map.to_screen(visible[0]);
+        for point in &visible[1..] {
+            let next = map.to_screen(*point);
+            draw_line.draw_segment(cx, last, next);
+            last = next;
+        }
+        return
+    }
+
+    let mut column = f64::NAN;
+    let mut low = 0.0;
+    let mut high = 0.0;
+    let mut last: Option<DVec2> = None;
+    for point in visible {
+        let p = map.to_screen(*point);
+        let col = p.x.floor();
+        if col != column {
+            if !column.is_nan() && high > low {
+                draw_line.draw_segment(cx, dvec2(column + 0.5, low), dvec2(column + 0.5, high));
+            }
+            if let Some(last) = last {
+                draw_line.draw_segment(cx, last, p);
+            }
+            column = col;
+            low = p.y;
+            high = p.y;
+        }
+        else {
+            low = f64::min(low, p.y);
+            high = f64::max(high, p.y);
+        }
+        last = Some(p);
+    }
+    if !column.is_nan() && high > low {
+        draw_line.draw_segment(cx, dvec2(column + 0.5, low), dvec2(column + 0.5, high));
+    }
+}
+
+impl Widget for LineChart {
+    fn redraw(&mut self, cx: &mut Cx) {
+        self.axes.redraw(cx);
+    }
+
+    fn handle_widget_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem)) {
+        let uid = self.widget_uid();
+        self.handle_event_with(cx, event, &mut | cx, action | {
+            dispatch_action(cx, WidgetActionItem::new(action.into(), uid))
+        });
+    }
+
+    fn walk(&mut self, _cx: &mut Cx) -> Walk {self.walk}
+
+    fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
+        self.draw_walk(cx, walk);
+        WidgetDraw::done()
+    }
+}
+
+#[derive(Clone, PartialEq, WidgetRef)]
+pub struct LineChartRef(WidgetRef);
+
+impl LineChartRef {
+    pub fn set_series(&self, cx: &mut Cx, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_series(cx, index, name, color, points);
+        }
+    }
+
+    pub fn append(&self, cx: &mut Cx, index: usize, points: &[DVec2]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.append(cx, index, points);
+        }
+    }
+
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear(cx);
+        }
+    }
+
+    pub fn reset_view(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.reset_view(cx);
+        }
+    }
+
+    pub fn set_x_range(&self, cx: &mut Cx, min: f64, max: f64) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_x_range(cx, min, max);
+        }
+    }
+
+    pub fn hovered(&self, actions: &WidgetActions) -> Option<(usize, usize, DVec2)> {
+        if let Some(item) = actions.find_single_action(self.widget_uid()) {
+            if let ChartAction::Hover {series, index, point} = item.action() {
+                return Some((series, index, point))
+            }
+        }
+        None
+    }
+}
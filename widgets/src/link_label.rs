@@ -2,6 +2,7 @@ use {
     crate::{
         makepad_derive_widget::*,
         widget::*,
+        accessibility::*,
         makepad_draw::*,
         button::{Button, ButtonAction}
     }
@@ -51,6 +52,16 @@ impl Widget for LinkLabel {
     fn set_text(&mut self, v:&str){
         self.button.set_text(v);
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let mut button = AccessTreeBuilder::default();
+        self.button.accessibility(cx, &mut button);
+        if let Some(mut node) = button.finish().nodes.pop() {
+            node.id = AccessNodeId::from_widget(self.widget_uid());
+            node.role = AccessRole::Link;
+            tree.leaf(node);
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, WidgetRef)]
@@ -2,6 +2,7 @@
 use {
     crate::{
         widget::*,
+        accessibility::*,
         makepad_derive_widget::*,
         window::*,
         makepad_draw::*,
@@ -62,6 +63,18 @@ impl Widget for MultiWindow {
         }
     }
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.begin(AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Application));
+        let mut ids: Vec<LiveId> = self.windows.keys().cloned().collect();
+        ids.sort_by_key( | id | id.0);
+        for id in ids {
+            if let Some(window) = self.windows.get_mut(&id) {
+                window.accessibility(cx, tree);
+            }
+        }
+        tree.end();
+    }
+    
     fn handle_widget_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem)) {
         for window in self.windows.values_mut() {
             window.handle_widget_event_with(cx, event, dispatch_action);
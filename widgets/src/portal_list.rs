@@ -1,6 +1,7 @@
 
 use crate::{
     widget::*,
+    accessibility::*,
     makepad_derive_widget::*,
     makepad_draw::*,
     scroll_bar::{ScrollBar, ScrollBarAction}
@@ -318,7 +319,8 @@ impl PortalList {
                         abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y + self.first_scroll)),
                         margin: Default::default(),
                         width: Size::Fill,
-                        height: Size::Fit
+                        height: Size::Fit,
+                        ..Walk::default()
                     }, Layout::flow_down());
                     return Some(self.first_id)
                 }
@@ -347,7 +349,8 @@ impl PortalList {
                                 abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y)),
                                 margin: Default::default(),
                                 width: Size::Fill,
-                                height: Size::Fit
+                                height: Size::Fit,
+                                ..Walk::default()
                             }, Layout::flow_down());
                             return Some(self.first_id - 1);
                         }
@@ -374,7 +377,8 @@ impl PortalList {
                         abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y + pos + rect.size.index(vi))),
                         margin: Default::default(),
                         width: Size::Fill,
-                        height: Size::Fit
+                        height: Size::Fit,
+                        ..Walk::default()
                     }, Layout::flow_down());
                     return Some(index + 1)
                 }
@@ -406,7 +410,8 @@ impl PortalList {
                                     abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y)),
                                     margin: Default::default(),
                                     width: Size::Fill,
-                                    height: Size::Fit
+                                    height: Size::Fit,
+                                    ..Walk::default()
                                 }, Layout::flow_down());
                                 return Some(last_index + 1);
                             }
@@ -431,7 +436,8 @@ impl PortalList {
                         abs_pos: Some(dvec2(viewport.pos.x, viewport.pos.y)),
                         margin: Default::default(),
                         width: Size::Fill,
-                        height: Size::Fit
+                        height: Size::Fit,
+                        ..Walk::default()
                     }, Layout::flow_down());
                     
                     return Some(index - 1);
@@ -488,6 +494,24 @@ impl Widget for PortalList {
         self.area.redraw(cx);
     }
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let uid = self.widget_uid();
+        tree.begin(
+            AccessNode::new(AccessNodeId::from_widget(uid), AccessRole::List)
+                .with_bounds(self.area.get_rect(cx))
+        );
+        let mut keys: Vec<(u64, LiveId)> = self.items.keys().cloned().collect();
+        keys.sort_by_key( | (index, _) | *index);
+        for key in keys {
+            if let Some(item) = self.items.get(&key) {
+                tree.begin(AccessNode::new(AccessNodeId::from_widget_sub(uid, LiveId(key.0)), AccessRole::ListItem));
+                item.accessibility(cx, tree);
+                tree.end();
+            }
+        }
+        tree.end();
+    }
+    
     fn handle_widget_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem)) {
         let uid = self.widget_uid();
         
@@ -3,6 +3,7 @@ use {
         makepad_derive_widget::*,
         makepad_draw::*,
         widget::*,
+        accessibility::*,
     }
 };
 
@@ -121,6 +122,16 @@ impl Widget for RadioButton {
         self.draw_walk(cx, walk);
         WidgetDraw::done()
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::RadioButton)
+                .with_name(&self.label)
+                .with_toggled(self.animator_in_state(cx, id!(selected.on)))
+                .with_bounds(self.draw_radio.area().get_rect(cx))
+                .with_actions(&[AccessAction::Click])
+        );
+    }
 }
 
 #[derive(Clone, PartialEq, WidgetRef)]
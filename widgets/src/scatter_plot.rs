@@ -0,0 +1,201 @@
+use {
+    crate::{
+        makepad_derive_widget::*,
+        makepad_draw::*,
+        widget::*,
+        chart::*,
+    }
+};
+
+live_design!{
+    ScatterPlotBase = {{ScatterPlot}} {}
+}
+
+/// Points don't need to be sorted on x, every visible point becomes one instance
+#[derive(Live)]
+pub struct ScatterPlot {
+    #[walk] walk: Walk,
+    #[live] axes: ChartAxes,
+    #[live] draw_point: DrawChartPoint,
+    #[live(4.0)] point_size: f64,
+    #[live(false)] follow: bool,
+    #[live] max_points: usize,
+    #[rust] data: ChartData,
+    #[rust] view: ChartView,
+    #[rust] hover: Option<(usize, usize)>,
+}
+
+impl LiveHook for ScatterPlot {
+    fn before_live_design(cx: &mut Cx) {
+        register_widget!(cx, ScatterPlot)
+    }
+
+    fn after_new_from_doc(&mut self, _cx: &mut Cx) {
+        self.view.following = self.follow;
+    }
+
+    fn after_apply(&mut self, _cx: &mut Cx, _from: ApplyFrom, _index: usize, _nodes: &[LiveNode]) {
+        self.data.max_points = if self.max_points > 0 {Some(self.max_points)} else {None};
+    }
+}
+
+impl ScatterPlot {
+    pub fn handle_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, ChartAction)) {
+        let hit = event.hits(cx, self.axes.area());
+        if self.view.handle_hit(cx, &hit) {
+            self.axes.redraw(cx);
+            dispatch_action(cx, ChartAction::ViewChanged);
+        }
+        match hit {
+            Hit::FingerHoverIn(_) | Hit::FingerHoverOver(_) | Hit::FingerHoverOut(_) | Hit::FingerDown(_) => {
+                let hover = self.find_hover();
+                if hover != self.hover {
+                    self.hover = hover;
+                    self.axes.redraw(cx);
+                    if let Some((series, index)) = hover {
+                        let point = self.data.series[series].points[index];
+                        dispatch_action(cx, ChartAction::Hover {series, index, point});
+                    }
+                    else {
+                        dispatch_action(cx, ChartAction::HoverOut);
+                    }
+                }
+            }
+            _ => ()
+        }
+    }
+
+    // the nearest point within a few point sizes of the pointer
+    fn find_hover(&self) -> Option<(usize, usize)> {
+        let pos = self.view.hover?;
+        let map = &self.view.map;
+        if !map.rect.contains(pos) {
+            return None
+        }
+        let mut best = None;
+        let mut best_dist = (self.point_size * 2.0).max(6.0);
+        for (series_index, series) in self.data.series.iter().enumerate() {
+            for (index, point) in series.points.iter().enumerate() {
+                let dist = map.to_screen(*point).distance(&pos);
+                if dist < best_dist {
+                    best_dist = dist;
+                    best = Some((series_index, index));
+                }
+            }
+        }
+        best
+    }
+
+    pub fn draw_walk(&mut self, cx: &mut Cx2d, walk: Walk) {
+        let bounds = self.data.bounds();
+        let (x, y) = self.view.resolve(bounds, false);
+        let map = self.axes.begin(cx, walk, x, y);
+        self.view.map = map;
+
+        let size = dvec2(self.point_size, self.point_size);
+        let visible = Rect {pos: map.rect.pos - size, size: map.rect.size + size * 2.0};
+        self.draw_point.begin_many_instances(cx);
+        self.draw_point.hover = 0.0;
+        for series in &self.data.series {
+            self.draw_point.color = series.color;
+            for point in &series.points {
+                let p = map.to_screen(*point);
+                if visible.contains(p) {
+                    self.draw_point.draw_abs(cx, Rect {pos: p - size * 0.5, size});
+                }
+            }
+        }
+        self.draw_point.end_many_instances(cx);
+
+        let hover = self.hover.and_then( | (s, i) | self.data.series.get(s).and_then( | series | series.points.get(i).map( | p | (series, *p))));
+        if let Some((series, point)) = hover {
+            let p = map.to_screen(point);
+            let size = size * 2.0;
+            self.draw_point.color = series.color;
+            self.draw_point.hover = 1.0;
+            self.draw_point.draw_abs(cx, Rect {pos: p - size * 0.5, size});
+        }
+        self.axes.end(cx, &map, hover.map( | (series, point) | (point, series.name.as_str())));
+    }
+
+    pub fn set_series(&mut self, cx: &mut Cx, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        self.data.set_series(index, name, color, points);
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    pub fn append(&mut self, cx: &mut Cx, index: usize, points: &[DVec2]) {
+        self.data.append(index, points);
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    pub fn clear(&mut self, cx: &mut Cx) {
+        self.data.clear();
+        self.hover = None;
+        self.axes.redraw(cx);
+    }
+
+    pub fn reset_view(&mut self, cx: &mut Cx) {
+        self.view.reset(self.follow);
+        self.axes.redraw(cx);
+    }
+}
+
+impl Widget for ScatterPlot {
+    fn redraw(&mut self, cx: &mut Cx) {
+        self.axes.redraw(cx);
+    }
+
+    fn handle_widget_event_with(&mut self, cx: &mut Cx, event: &Event, dispatch_action: &mut dyn FnMut(&mut Cx, WidgetActionItem)) {
+        let uid = self.widget_uid();
+        self.handle_event_with(cx, event, &mut | cx, action | {
+            dispatch_action(cx, WidgetActionItem::new(action.into(), uid))
+        });
+    }
+
+    fn walk(&mut self, _cx: &mut Cx) -> Walk {self.walk}
+
+    fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
+        self.draw_walk(cx, walk);
+        WidgetDraw::done()
+    }
+}
+
+#[derive(Clone, PartialEq, WidgetRef)]
+pub struct ScatterPlotRef(WidgetRef);
+
+impl ScatterPlotRef {
+    pub fn set_series(&self, cx: &mut Cx, index: usize, name: &str, color: Vec4, points: Vec<DVec2>) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.set_series(cx, index, name, color, points);
+        }
+    }
+
+    pub fn append(&self, cx: &mut Cx, index: usize, points: &[DVec2]) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.append(cx, index, points);
+        }
+    }
+
+    pub fn clear(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.clear(cx);
+        }
+    }
+
+    pub fn reset_view(&self, cx: &mut Cx) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.reset_view(cx);
+        }
+    }
+
+    pub fn hovered(&self, actions: &WidgetActions) -> Option<(usize, usize, DVec2)> {
+        if let Some(item) = actions.find_single_action(self.widget_uid()) {
+            if let ChartAction::Hover {series, index, point} = item.action() {
+                return Some((series, index, point))
+            }
+        }
+        None
+    }
+}
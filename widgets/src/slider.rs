@@ -3,6 +3,7 @@ use {
         makepad_derive_widget::*,
         makepad_draw::*,
         widget::*,
+        accessibility::*,
         text_input::{TextInput, TextInputAction}
     }
 };
@@ -214,6 +215,17 @@ impl Widget for Slider {
         WidgetDraw::done()
     }
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Slider)
+                .with_name(&self.text)
+                .with_value(&self.to_external().to_string())
+                .with_numeric(self.to_external(), self.min, self.max)
+                .with_bounds(self.draw_slider.area().get_rect(cx))
+                .with_actions(&[AccessAction::Increment, AccessAction::Decrement, AccessAction::SetValue])
+        );
+    }
+    
     fn widget_to_data(&self, _cx: &mut Cx, actions:&WidgetActions, nodes: &mut LiveNodeVec, path: &[LiveId])->bool{
         match actions.single_action(self.widget_uid()) {
             SliderAction::TextSlide(v) | SliderAction::Slide(v) => {
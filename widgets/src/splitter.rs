@@ -2,6 +2,7 @@ use crate::{
     makepad_derive_widget::*,
     makepad_draw::*,
     widget::*,
+    accessibility::*,
 };
 
 live_design!{
@@ -90,6 +91,15 @@ impl Widget for Splitter {
         self.b.find_widgets(path, cached, results);
     }
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let uid = self.widget_uid();
+        tree.begin(AccessNode::new(AccessNodeId::from_widget(uid), AccessRole::Group));
+        self.a.accessibility(cx, tree);
+        tree.leaf(self.access_node(cx, AccessNodeId::from_widget_sub(uid, live_id!(split_bar))));
+        self.b.accessibility(cx, tree);
+        tree.end();
+    }
+    
     fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
         if self.draw_state.begin(cx, DrawState::DrawA) {
             self.begin(cx, walk);
@@ -157,6 +167,17 @@ impl Splitter {
         self.axis
     }
 
+    pub fn access_node(&self, cx: &Cx, id: AccessNodeId) -> AccessNode {
+        let size = match self.axis {
+            Axis::Horizontal => self.rect.size.x,
+            Axis::Vertical => self.rect.size.y
+        };
+        AccessNode::new(id, AccessRole::Splitter)
+            .with_numeric(self.position, 0.0, size)
+            .with_bounds(self.draw_splitter.area().get_rect(cx))
+            .with_actions(&[AccessAction::Increment, AccessAction::Decrement, AccessAction::SetValue])
+    }
+    
     pub fn area_a(&self) -> Area {
         self.area_a
     }
@@ -0,0 +1,64 @@
+use crate::{
+    makepad_derive_widget::*,
+    makepad_draw::*,
+    widget::*,
+    accessibility::*,
+};
+
+live_design!{
+    SvgBase = {{Svg}} {}
+}
+
+#[derive(Live)]
+pub struct Svg {
+    #[walk] walk: Walk,
+    #[live] draw_svg: DrawSvg,
+}
+
+impl LiveHook for Svg {
+    fn before_live_design(cx: &mut Cx) {
+        register_widget!(cx, Svg)
+    }
+}
+
+impl Widget for Svg {
+    fn redraw(&mut self, cx: &mut Cx) {
+        self.draw_svg.redraw(cx)
+    }
+    
+    fn walk(&mut self, _cx: &mut Cx) -> Walk {
+        self.walk
+    }
+    
+    fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
+        self.draw_svg.draw_walk(cx, walk);
+        WidgetDraw::done()
+    }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        tree.leaf(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Image)
+                .with_bounds(self.draw_svg.area().get_rect(cx))
+        );
+    }
+}
+
+#[derive(Clone, Default, PartialEq, WidgetRef)]
+pub struct SvgRef(WidgetRef);
+
+impl SvgRef {
+    pub fn load_svg_from_data(&self, cx: &mut Cx, data: &[u8]) -> Result<(), String> {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.draw_svg.load_svg_from_data(data)?;
+            inner.redraw(cx);
+        }
+        Ok(())
+    }
+    
+    pub fn set_color(&self, cx: &mut Cx, color: Vec4) {
+        if let Some(mut inner) = self.borrow_mut() {
+            inner.draw_svg.color = color;
+            inner.redraw(cx);
+        }
+    }
+}
@@ -3,6 +3,7 @@ use {
         makepad_derive_widget::*,
         makepad_draw::*,
         widget::*,
+        accessibility::*,
     }
 };
 
@@ -116,6 +117,18 @@ impl Widget for TextInput {
     fn set_text(&mut self, v: &str) {
         self.filter_input(&v, None);
     }
+    
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let mut node = AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::TextInput)
+            .with_name(&self.empty_message)
+            .with_bounds(self.draw_bg.area().get_rect(cx))
+            .with_actions(if self.read_only {&[AccessAction::Focus]} else {&[AccessAction::Focus, AccessAction::SetValue]});
+        // never leak the contents of a password field
+        if !self.secret {
+            node = node.with_value(&self.text);
+        }
+        tree.leaf(node);
+    }
 }
 
 #[derive(Clone, PartialEq, WidgetAction)]
@@ -0,0 +1,102 @@
+use crate::makepad_platform::*;
+
+/// The themes shipped with the widget library. All widget styles in
+/// `theme_desktop_dark` are written against theme tokens (`THEME_COLOR_*`,
+/// `THEME_FONT_SIZE_*`, `THEME_SPACE_*`, `THEME_CORNER_RADIUS*`), the other themes
+/// only redefine those tokens. Every `THEME_COLOR_*` and `THEME_FONT_SIZE_*` token is
+/// defined by each theme, the layout tokens are shared by all of them and only defined
+/// in `theme_desktop_dark`: `THEME_SPACE_*`, `THEME_CORNER_RADIUS*`, `THEME_DATA_*`,
+/// `THEME_TAB_HEIGHT` and `THEME_SPLITTER_*`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl Theme {
+    fn overlay_module(&self) -> Option<LiveModuleId> {
+        match self {
+            Self::Dark => None,
+            Self::Light => Some(LiveModuleId(live_id!(makepad_widgets), live_id!(theme_desktop_light))),
+            Self::HighContrast => Some(LiveModuleId(live_id!(makepad_widgets), live_id!(theme_desktop_high_contrast))),
+        }
+    }
+
+    /// The theme matching the OS appearance, if the platform exposes it
+    pub fn from_os(cx: &Cx) -> Option<Theme> {
+        cx.os_prefers_dark_mode().map( | dark | if dark {Self::Dark} else {Self::Light})
+    }
+}
+
+#[derive(Default)]
+struct ThemeState {
+    theme: Theme,
+    follow_os: bool,
+    // the OS preference the theme was last switched for, signals from other threads
+    // leave the theme alone unless the preference changed since
+    os_prefers_dark: Option<bool>,
+}
+
+pub trait CxThemeApi {
+    /// Switches the theme at runtime. The live documents are re-expanded with the
+    /// theme tokens and the app receives `Event::LiveEdit` to re-apply them
+    fn set_theme(&mut self, theme: Theme);
+    fn theme(&mut self) -> Theme;
+    /// Follows the OS dark/light preference, falling back to the current theme when
+    /// the platform doesn't report one. The preference is checked again when the app
+    /// regains focus
+    fn follow_os_theme(&mut self, follow: bool);
+    /// Asks the OS for its preference again, the answer may come in later
+    fn update_os_theme(&mut self);
+    /// Switches to the last known OS preference if it changed since the last switch, for
+    /// when it comes in on `Event::Signal`
+    fn apply_os_theme(&mut self);
+}
+
+impl CxThemeApi for Cx {
+    fn set_theme(&mut self, theme: Theme) {
+        let state = self.global::<ThemeState>();
+        if state.theme == theme {
+            return
+        }
+        state.theme = theme;
+        let dark = LiveModuleId(live_id!(makepad_widgets), live_id!(theme_desktop_dark));
+        self.set_live_overlay(dark, theme.overlay_module());
+    }
+
+    fn theme(&mut self) -> Theme {
+        self.global::<ThemeState>().theme
+    }
+
+    fn follow_os_theme(&mut self, follow: bool) {
+        let state = self.global::<ThemeState>();
+        state.follow_os = follow;
+        state.os_prefers_dark = None;
+        self.update_os_theme();
+    }
+
+    fn update_os_theme(&mut self) {
+        if !self.global::<ThemeState>().follow_os {
+            return
+        }
+        self.refresh_os_prefers_dark_mode();
+        self.apply_os_theme();
+    }
+
+    fn apply_os_theme(&mut self) {
+        if !self.global::<ThemeState>().follow_os {
+            return
+        }
+        let os_prefers_dark = self.os_prefers_dark_mode();
+        let state = self.global::<ThemeState>();
+        if os_prefers_dark.is_none() || state.os_prefers_dark == os_prefers_dark {
+            return
+        }
+        state.os_prefers_dark = os_prefers_dark;
+        if let Some(theme) = Theme::from_os(self) {
+            self.set_theme(theme);
+        }
+    }
+}
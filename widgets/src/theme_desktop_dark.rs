@@ -4,22 +4,30 @@ live_design!{
     import makepad_draw::shader::std::*;
     import crate::base::*;
     
+    // Theme tokens. Widgets below only reference these, the light and high contrast
+    // themes override them at runtime (see theme.rs)
+    
+    const THEME_FONT_SIZE_BASE = 9.4
+    const THEME_FONT_SIZE_CODE = 9.0
+    const THEME_FONT_SIZE_CONTROL = 11.0
+    const THEME_FONT_SIZE_SMALL = 6.0
+    
     THEME_FONT_LABEL = {
-        font_size: 9.4,
+        font_size: (THEME_FONT_SIZE_BASE),
         font: {
             path: dep("crate://self/resources/GoNotoKurrent-Regular.ttf")
         }
     }
     
     THEME_FONT_DATA = {
-        font_size: 9.4,
+        font_size: (THEME_FONT_SIZE_BASE),
         font: {
             path: dep("crate://self/resources/GoNotoKurrent-Regular.ttf")
         }
     }
     
     THEME_FONT_META = { 
-        font_size: 9.4,
+        font_size: (THEME_FONT_SIZE_BASE),
         top_drop: 1.2,
         font: {
             path: dep("crate://self/resources/GoNotoKurrent-Regular.ttf")
@@ -31,7 +39,7 @@ live_design!{
             path: dep("crate://self/resources/LiberationMono-Regular.ttf")
         }
         brightness: 1.1
-        font_size: 9.0
+        font_size: (THEME_FONT_SIZE_CODE)
         line_spacing: 2.0
         top_drop: 1.3
     }
@@ -91,7 +99,7 @@ live_design!{
     ))
     
     const THEME_COLOR_EDITOR_SELECTED = (THEME_COLOR_BG_SELECTED)
-    const THEME_COLOR_EDITOR_SELECTED_UNFOCUSSED = (THEME_COLOR_BG_SELECTED_UNFOCUSSED)
+    const THEME_COLOR_EDITOR_SELECTED_UNFOCUSSED = (THEME_COLOR_BG_UNFOCUSSED)
     
     const THEME_COLOR_BG_CURSOR = (blend(
         THEME_COLOR_BG_EDITOR,
@@ -132,6 +140,7 @@ live_design!{
     const THEME_COLOR_ICON_PANIC = (THEME_COLOR_HIGH)
     const THEME_COLOR_DRAG_QUAD = (THEME_COLOR_UP_50)
     const THEME_COLOR_PANIC = #f0f
+    const THEME_COLOR_TRANSPARENT = #0000
     
     const THEME_TAB_HEIGHT = 26.0,
     const THEME_SPLITTER_HORIZONTAL = 16.0,
@@ -141,6 +150,100 @@ live_design!{
     const THEME_SPLITTER_MAX_VERTICAL = (THEME_SPLITTER_HORIZONTAL + THEME_SPLITTER_SIZE),
     const THEME_SPLITTER_SIZE = 5.0
     
+    // SPACING AND CORNER RADII
+    
+    const THEME_SPACE_CONTROL_MARGIN = 1.0
+    const THEME_SPACE_CONTROL_PADDING_H = 14.0
+    const THEME_SPACE_CONTROL_PADDING_V = 10.0
+    
+    const THEME_CORNER_RADIUS_TINY = 0.5
+    const THEME_CORNER_RADIUS_SMALL = 1.5
+    const THEME_CORNER_RADIUS_INPUT = 2.0
+    const THEME_CORNER_RADIUS = 3.0
+    const THEME_CORNER_RADIUS_LARGE = 10.0
+    
+    // CONTROL COLORS
+    
+    const THEME_COLOR_LABEL = #8
+    const THEME_COLOR_CONTROL_TEXT = #9
+    const THEME_COLOR_CONTROL_TEXT_FOCUS = #b
+    const THEME_COLOR_CONTROL_TEXT_HOVER = #c
+    const THEME_COLOR_CONTROL_MARK = #f
+    const THEME_COLOR_CONTROL_MARK_OFF = #fff0
+    
+    const THEME_COLOR_BUTTON_BODY = #53
+    const THEME_COLOR_BUTTON_BODY_HOVER = #5c
+    const THEME_COLOR_BUTTON_BODY_PRESSED = #33
+    const THEME_COLOR_BUTTON_BEVEL = #6d
+    const THEME_COLOR_BUTTON_BEVEL_PRESSED = #1f
+    const THEME_COLOR_BUTTON_SHADOW = #2f
+    const THEME_COLOR_BUTTON_SHADOW_HOVER = #1f
+    
+    const THEME_COLOR_CHECK_BG = #x00000077
+    const THEME_COLOR_CHECK_BG_BOTTOM = #x00000044
+    const THEME_COLOR_CHECK_BG_HOVER = #x000000AA
+    const THEME_COLOR_CHECK_BG_HOVER_BOTTOM = #x00000066
+    const THEME_COLOR_CHECK_OUTLINE = #x888
+    const THEME_COLOR_CHECK_WELL = #2
+    const THEME_COLOR_CHECK_TEXT = #fff6
+    
+    const THEME_COLOR_WINDOW_BUTTON = #3
+    const THEME_COLOR_WINDOW_BUTTON_HOVER = #6
+    const THEME_COLOR_WINDOW_BUTTON_PRESSED = #9
+    const THEME_COLOR_WINDOW_BUTTON_CLOSE_HOVER = #e00
+    const THEME_COLOR_WINDOW_BUTTON_CLOSE_PRESSED = #c00
+    const THEME_COLOR_WINDOW_BUTTON_ICON = #f
+    const THEME_COLOR_WINDOW_BUTTON_XR_HOVER = #0aa
+    const THEME_COLOR_WINDOW_BUTTON_XR_PRESSED = #077
+    const THEME_COLOR_WINDOW_BUTTON_XR_ICON = #8
+    
+    const THEME_COLOR_INPUT_BG = #2
+    const THEME_COLOR_INPUT_BG_HOVER = #3
+    const THEME_COLOR_INPUT_TEXT = #xFFFFFF55
+    const THEME_COLOR_INPUT_TEXT_HOVER = #xFFFFFF88
+    const THEME_COLOR_INPUT_TEXT_FOCUS = #xFFFFFFCC
+    const THEME_COLOR_INPUT_TEXT_EMPTY = #3
+    const THEME_COLOR_INPUT_CURSOR = #f
+    const THEME_COLOR_INPUT_CURSOR_UNFOCUSSED = #ccc0
+    const THEME_COLOR_INPUT_SELECT = #xFFFFFF40
+    const THEME_COLOR_INPUT_SELECT_UNFOCUSSED = #5550
+    
+    const THEME_COLOR_SLIDER_TRACK = #38
+    const THEME_COLOR_SLIDER_TRACK_FOCUS = #30
+    const THEME_COLOR_SLIDER_FILL = #5
+    const THEME_COLOR_SLIDER_FILL_HOVER = #68
+    const THEME_COLOR_SLIDER_NUB = #8
+    const THEME_COLOR_SLIDER_NUB_DRAG = #c
+    const THEME_COLOR_SLIDER_NUB_BG = #eee0
+    
+    const THEME_COLOR_RADIO_TAB = #x99EEFF
+    const THEME_COLOR_RADIO_TAB_ACTIVE = #00000000
+    const THEME_COLOR_RADIO_TAB_TEXT = #x00000088
+    const THEME_COLOR_RADIO_TAB_TEXT_HOVER = #x000000CC
+    const THEME_COLOR_RADIO_TAB_TEXT_SELECTED = #xFFFFFF66
+    
+    const THEME_COLOR_FOLD_ARROW = #a
+    const THEME_COLOR_FOLD_ARROW_HOVER = #f
+    
+    const THEME_COLOR_POPUP_BG = #0
+    const THEME_COLOR_POPUP_BORDER = #0000
+    const THEME_COLOR_POPUP_ITEM_BG = #0
+    const THEME_COLOR_POPUP_ITEM_BG_HOVER = #4
+    
+    const THEME_COLOR_NAV_FOCUS = #000f
+    const THEME_COLOR_NAV_TEXT = #a
+    const THEME_COLOR_TAB_DRAG = #c
+    
+    const THEME_COLOR_MOUSE_CURSOR = #000
+    const THEME_COLOR_MOUSE_CURSOR_BORDER = #fff
+    const THEME_COLOR_SCROLL_SHADOW = #000
+    
+    const THEME_COLOR_SLIDE_BG = #x1A
+    const THEME_COLOR_SLIDE_TEXT = #D
+    const THEME_COLOR_SLIDE_TITLE = #f
+    const THEME_COLOR_SLIDE_CHAPTER_BG = #xFF5C39
+    const THEME_COLOR_SLIDE_CHAPTER_TITLE = #x181818
+    
     
     
     ScrollBar = <ScrollBarBase> {
@@ -149,7 +252,7 @@ live_design!{
         min_handle_size: 30.0
         draw_bar: {
             //draw_depth: 5.0
-            uniform border_radius: 1.5
+            uniform border_radius: (THEME_CORNER_RADIUS_SMALL)
             instance bar_width: 6.0
             instance pressed: 0.0
             instance hover: 0.0
@@ -235,7 +338,7 @@ live_design!{
         width: Fit
         height: Fit
         draw_text: {
-            color: #8,
+            color: (THEME_COLOR_LABEL),
             text_style: <THEME_FONT_LABEL>{}
             wrap: Word
         }
@@ -248,9 +351,9 @@ live_design!{
     Button = <ButtonBase> {
         width: Fit,
         height: Fit,
-        margin: {left: 1.0, right: 1.0, top: 1.0, bottom: 1.0}
+        margin: {left: (THEME_SPACE_CONTROL_MARGIN), right: (THEME_SPACE_CONTROL_MARGIN), top: (THEME_SPACE_CONTROL_MARGIN), bottom: (THEME_SPACE_CONTROL_MARGIN)}
         align: {x: 0.5, y: 0.5}
-        padding: {left: 14.0, top: 10.0, right: 14.0, bottom: 10.0}
+        padding: {left: (THEME_SPACE_CONTROL_PADDING_H), top: (THEME_SPACE_CONTROL_PADDING_V), right: (THEME_SPACE_CONTROL_PADDING_H), bottom: (THEME_SPACE_CONTROL_PADDING_V)}
         
         label_walk: {
             width: Fit,
@@ -261,16 +364,16 @@ live_design!{
             instance hover: 0.0
             instance pressed: 0.0
             text_style: <THEME_FONT_LABEL>{
-                font_size: 11.0
+                font_size: (THEME_FONT_SIZE_CONTROL)
             }
             fn get_color(self) -> vec4 {
                 return mix(
                     mix(
-                        #9,
-                        #c,
+                        THEME_COLOR_CONTROL_TEXT,
+                        THEME_COLOR_CONTROL_TEXT_HOVER,
                         self.hover
                     ),
-                    #9,
+                    THEME_COLOR_CONTROL_TEXT,
                     self.pressed
                 )
             }
@@ -282,11 +385,11 @@ live_design!{
             fn get_color(self) -> vec4 {
                 return mix(
                     mix(
-                        #9,
-                        #c,
+                        THEME_COLOR_CONTROL_TEXT,
+                        THEME_COLOR_CONTROL_TEXT_HOVER,
                         self.hover
                     ),
-                    #9,
+                    THEME_COLOR_CONTROL_TEXT,
                     self.pressed
                 )
             }
@@ -295,17 +398,17 @@ live_design!{
         draw_bg: {
             instance hover: 0.0
             instance pressed: 0.0
-            uniform border_radius: 3.0
+            uniform border_radius: (THEME_CORNER_RADIUS)
             
             fn pixel(self) -> vec4 {
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                 let grad_top = 5.0;
                 let grad_bot = 1.0;
-                let body = mix(mix(#53, #5c, self.hover), #33, self.pressed);
+                let body = mix(mix(THEME_COLOR_BUTTON_BODY, THEME_COLOR_BUTTON_BODY_HOVER, self.hover), THEME_COLOR_BUTTON_BODY_PRESSED, self.pressed);
                 let body_transp = vec4(body.xyz, 0.0);
-                let top_gradient = mix(body_transp, mix(#6d, #1f, self.pressed), max(0.0, grad_top - sdf.pos.y) / grad_top);
+                let top_gradient = mix(body_transp, mix(THEME_COLOR_BUTTON_BEVEL, THEME_COLOR_BUTTON_BEVEL_PRESSED, self.pressed), max(0.0, grad_top - sdf.pos.y) / grad_top);
                 let bot_gradient = mix(
-                    mix(body_transp, #5c, self.pressed),
+                    mix(body_transp, THEME_COLOR_BUTTON_BODY_HOVER, self.pressed),
                     top_gradient,
                     clamp((self.rect_size.y - grad_bot - sdf.pos.y - 1.0) / grad_bot, 0.0, 1.0)
                 );
@@ -315,7 +418,7 @@ live_design!{
                 sdf.move_to(shift_inward, self.rect_size.y - self.border_radius);
                 sdf.line_to(self.rect_size.x - shift_inward, self.rect_size.y - self.border_radius);
                 sdf.stroke(
-                    mix(mix(#2f, #1f, self.hover), #0000, self.pressed),
+                    mix(mix(THEME_COLOR_BUTTON_SHADOW, THEME_COLOR_BUTTON_SHADOW_HOVER, self.hover), THEME_COLOR_TRANSPARENT, self.pressed),
                     self.border_radius
                 )
                 
@@ -402,49 +505,49 @@ live_design!{
                         let left = 3;
                         let sz = self.size;
                         let c = vec2(left + sz, self.rect_size.y * 0.5);
-                        sdf.box(left, c.y - sz, sz * 2.0, sz * 2.0, 3.0); // rounding = 3rd value
-                        sdf.fill_keep(mix(mix(#x00000077, #x00000044, pow(self.pos.y, 1.)), mix(#x000000AA, #x00000066, pow(self.pos.y, 1.0)), self.hover))
-                        sdf.stroke(#x888, 1.0) // outline
+                        sdf.box(left, c.y - sz, sz * 2.0, sz * 2.0, THEME_CORNER_RADIUS); // rounding = 3rd value
+                        sdf.fill_keep(mix(mix(THEME_COLOR_CHECK_BG, THEME_COLOR_CHECK_BG_BOTTOM, pow(self.pos.y, 1.)), mix(THEME_COLOR_CHECK_BG_HOVER, THEME_COLOR_CHECK_BG_HOVER_BOTTOM, pow(self.pos.y, 1.0)), self.hover))
+                        sdf.stroke(THEME_COLOR_CHECK_OUTLINE, 1.0) // outline
                         let szs = sz * 0.5;
                         let dx = 1.0;
                         sdf.move_to(left + 4.0, c.y);
                         sdf.line_to(c.x, c.y + szs);
                         sdf.line_to(c.x + szs, c.y - szs);
-                        sdf.stroke(mix(#fff0, #f, self.selected), 1.25);
+                        sdf.stroke(mix(THEME_COLOR_CONTROL_MARK_OFF, THEME_COLOR_CONTROL_MARK, self.selected), 1.25);
                     }
                     CheckType::Radio => {
                         let sz = self.size;
                         let left = sz + 1.;
                         let c = vec2(left + sz, self.rect_size.y * 0.5);
                         sdf.circle(left, c.y, sz);
-                        sdf.fill(#2);
+                        sdf.fill(THEME_COLOR_CHECK_WELL);
                         let isz = sz * 0.5;
                         sdf.circle(left, c.y, isz);
-                        sdf.fill(mix(#fff0, #f, self.selected));
+                        sdf.fill(mix(THEME_COLOR_CONTROL_MARK_OFF, THEME_COLOR_CONTROL_MARK, self.selected));
                     }
                     CheckType::Toggle => {
                         let sz = self.size;
                         let left = sz + 1.;
                         let c = vec2(left + sz, self.rect_size.y * 0.5);
                         sdf.box(left, c.y - sz, sz * 3.0, sz * 2.0, 0.5 * sz);
-                        sdf.fill(#2);
+                        sdf.fill(THEME_COLOR_CHECK_WELL);
                         let isz = sz * 0.5;
                         sdf.circle(left + sz + self.selected * sz, c.y, isz);
                         sdf.circle(left + sz + self.selected * sz, c.y, 0.5 * isz);
                         sdf.subtract();
                         sdf.circle(left + sz + self.selected * sz, c.y, isz);
                         sdf.blend(self.selected)
-                        sdf.fill(#f);
+                        sdf.fill(THEME_COLOR_CONTROL_MARK);
                     }
                     CheckType::None => {
-                        return #0000
+                        return THEME_COLOR_TRANSPARENT
                     }
                 }
                 return sdf.result
             }
         }
         draw_text: {
-            color: #9,
+            color: (THEME_COLOR_CONTROL_TEXT),
             instance focus: 0.0
             instance selected: 0.0
             instance hover: 0.0
@@ -452,16 +555,16 @@ live_design!{
                 font: {
                     //path: d"resources/IBMPlexSans-SemiBold.ttf"
                 }
-                font_size: 11.0
+                font_size: (THEME_FONT_SIZE_CONTROL)
             }
             fn get_color(self) -> vec4 {
                 return mix(
                     mix(
-                        #fff6,
-                        #fff6,
+                        THEME_COLOR_CHECK_TEXT,
+                        THEME_COLOR_CHECK_TEXT,
                         self.hover
                     ),
-                    #fff6,
+                    THEME_COLOR_CHECK_TEXT,
                     self.selected
                 )
             }
@@ -474,11 +577,11 @@ live_design!{
             fn get_color(self) -> vec4 {
                 return mix(
                     mix(
-                        #9,
-                        #c,
+                        THEME_COLOR_CONTROL_TEXT,
+                        THEME_COLOR_CONTROL_TEXT_HOVER,
                         self.hover
                     ),
-                    #f,
+                    THEME_COLOR_CONTROL_MARK,
                     self.selected
                 )
             }
@@ -557,40 +660,40 @@ live_design!{
                 // WindowsMin
                 match self.button_type {
                     DesktopButtonType::WindowsMin => {
-                        sdf.clear(mix(#3, mix(#6, #9, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_WINDOW_BUTTON, mix(THEME_COLOR_WINDOW_BUTTON_HOVER, THEME_COLOR_WINDOW_BUTTON_PRESSED, self.pressed), self.hover));
                         sdf.move_to(c.x - sz, c.y);
                         sdf.line_to(c.x + sz, c.y);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_WINDOW_BUTTON_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::WindowsMax => {
-                        sdf.clear(mix(#3, mix(#6, #9, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_WINDOW_BUTTON, mix(THEME_COLOR_WINDOW_BUTTON_HOVER, THEME_COLOR_WINDOW_BUTTON_PRESSED, self.pressed), self.hover));
                         sdf.rect(c.x - sz, c.y - sz, 2. * sz, 2. * sz);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_WINDOW_BUTTON_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::WindowsMaxToggled => {
-                        let clear = mix(#3, mix(#6, #9, self.pressed), self.hover);
+                        let clear = mix(THEME_COLOR_WINDOW_BUTTON, mix(THEME_COLOR_WINDOW_BUTTON_HOVER, THEME_COLOR_WINDOW_BUTTON_PRESSED, self.pressed), self.hover);
                         sdf.clear(clear);
                         let sz = 3.5;
                         sdf.rect(c.x - sz + 1., c.y - sz - 1., 2. * sz, 2. * sz);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_WINDOW_BUTTON_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         sdf.rect(c.x - sz - 1., c.y - sz + 1., 2. * sz, 2. * sz);
                         sdf.fill_keep(clear);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_WINDOW_BUTTON_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::WindowsClose => {
-                        sdf.clear(mix(#3, mix(#e00, #c00, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_WINDOW_BUTTON, mix(THEME_COLOR_WINDOW_BUTTON_CLOSE_HOVER, THEME_COLOR_WINDOW_BUTTON_CLOSE_PRESSED, self.pressed), self.hover));
                         sdf.move_to(c.x - sz, c.y - sz);
                         sdf.line_to(c.x + sz, c.y + sz);
                         sdf.move_to(c.x - sz, c.y + sz);
                         sdf.line_to(c.x + sz, c.y - sz);
-                        sdf.stroke(#f, 0.5 + 0.5 * self.dpi_dilate);
+                        sdf.stroke(THEME_COLOR_WINDOW_BUTTON_ICON, 0.5 + 0.5 * self.dpi_dilate);
                         return sdf.result;
                     }
                     DesktopButtonType::XRMode => {
-                        sdf.clear(mix(#3, mix(#0aa, #077, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_WINDOW_BUTTON, mix(THEME_COLOR_WINDOW_BUTTON_XR_HOVER, THEME_COLOR_WINDOW_BUTTON_XR_PRESSED, self.pressed), self.hover));
                         let w = 12.;
                         let h = 8.;
                         sdf.box(c.x - w, c.y - h, 2. * w, 2. * h, 2.);
@@ -601,13 +704,13 @@ live_design!{
                         sdf.subtract();
                         sdf.circle(c.x, c.y + h - 0.75, 2.5);
                         sdf.subtract();
-                        sdf.fill(#8);
+                        sdf.fill(THEME_COLOR_WINDOW_BUTTON_XR_ICON);
                         
                         return sdf.result;
                     }
                     DesktopButtonType::Fullscreen => {
                         sz = 8.;
-                        sdf.clear(mix(#3, mix(#6, #9, self.pressed), self.hover));
+                        sdf.clear(mix(THEME_COLOR_WINDOW_BUTTON, mix(THEME_COLOR_WINDOW_BUTTON_HOVER, THEME_COLOR_WINDOW_BUTTON_PRESSED, self.pressed), self.hover));
                         sdf.rect(c.x - sz, c.y - sz, 2. * sz, 2. * sz);
                         sdf.rect(c.x - sz + 1.5, c.y - sz + 1.5, 2. * (sz - 1.5), 2. * (sz - 1.5));
                         sdf.subtract();
@@ -615,12 +718,12 @@ live_design!{
                         sdf.subtract();
                         sdf.rect(c.x - sz - 2., c.y - sz + 4., 2. * (sz + 2.), 2. * (sz - 4.));
                         sdf.subtract();
-                        sdf.fill(#f); //, 0.5 + 0.5 * dpi_dilate);
+                        sdf.fill(THEME_COLOR_WINDOW_BUTTON_ICON); //, 0.5 + 0.5 * dpi_dilate);
                         
                         return sdf.result;
                     }
                 }
-                return #f00;
+                return THEME_COLOR_PANIC;
             }
         }
         animator: {
@@ -662,14 +765,14 @@ live_design!{
     NavControl = <NavControlBase> {
         draw_focus: {
             fn pixel(self) -> vec4 {
-                return #000f
+                return THEME_COLOR_NAV_FOCUS
             }
         }
         draw_text: {
             text_style: {
-                font_size: 6
+                font_size: (THEME_FONT_SIZE_SMALL)
             },
-            color: #a
+            color: (THEME_COLOR_NAV_TEXT)
         }
     }
     
@@ -734,8 +837,8 @@ live_design!{
         mouse_cursor_size: vec2(20, 20),
         draw_cursor: {
             instance border_width: 1.5
-            instance color: #000
-            instance border_color: #fff
+            instance color: (THEME_COLOR_MOUSE_CURSOR)
+            instance border_color: (THEME_COLOR_MOUSE_CURSOR_BORDER)
             
             fn get_color(self) -> vec4 {
                 return self.color
@@ -942,7 +1045,7 @@ live_design!{
                         THEME_COLOR_BG_EDITOR,
                         self.selected
                     ),
-                    #f,
+                    THEME_COLOR_WHITE,
                     0.0 //mix(self.hover * 0.05, self.hover * -0.025, self.selected)
                 );
                 /*sdf.clear(color)
@@ -1002,7 +1105,7 @@ live_design!{
         tab: <Tab> {}
         draw_drag: {
             draw_depth: 10
-            color: #c
+            color: (THEME_COLOR_TAB_DRAG)
         }
         draw_fill: {
             color: (THEME_COLOR_BG_HEADER)
@@ -1027,7 +1130,7 @@ live_design!{
     Dock = <DockBase> {
         round_corner: {
             draw_depth: 6.0
-            border_radius: 10.0
+            border_radius: (THEME_CORNER_RADIUS_LARGE)
             fn pixel(self) -> vec4 {
                 
                 let pos = vec2(
@@ -1060,6 +1163,9 @@ live_design!{
         }
         tab_bar: <TabBar> {}
         splitter: <Splitter> {}
+        floating_window: <Window> {
+            window: {inner_size: vec2(640, 480)}
+        }
     }
     
     
@@ -1092,8 +1198,8 @@ live_design!{
         draw_bg: {
             instance selected: 0.0
             instance hover: 0.0
-            instance color: #0
-            instance color_selected: #4
+            instance color: (THEME_COLOR_POPUP_ITEM_BG)
+            instance color_selected: (THEME_COLOR_POPUP_ITEM_BG_HOVER)
             
             fn pixel(self) -> vec4 {
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size);
@@ -1114,7 +1220,7 @@ live_design!{
                 sdf.move_to(c.x - sz + dx * 0.5, c.y - sz + dx);
                 sdf.line_to(c.x, c.y + sz);
                 sdf.line_to(c.x + sz, c.y - sz);
-                sdf.stroke(mix(#fff0, #f, self.selected), 1.0);
+                sdf.stroke(mix(THEME_COLOR_CONTROL_MARK_OFF, THEME_COLOR_CONTROL_MARK, self.selected), 1.0);
                 
                 return sdf.result;
             }
@@ -1172,9 +1278,9 @@ live_design!{
         height: Fit
         
         draw_bg: {
-            instance color: #0
+            instance color: (THEME_COLOR_POPUP_BG)
             instance border_width: 0.0,
-            instance border_color: #0000,
+            instance border_color: (THEME_COLOR_POPUP_BORDER),
             instance inset: vec4(0.0, 0.0, 0.0, 0.0),
             instance radius: 4.0
             
@@ -1211,14 +1317,14 @@ live_design!{
                 return mix(
                     mix(
                         mix(
-                            #9,
-                            #b,
+                            THEME_COLOR_CONTROL_TEXT,
+                            THEME_COLOR_CONTROL_TEXT_FOCUS,
                             self.focus
                         ),
-                        #c,
+                        THEME_COLOR_CONTROL_TEXT_HOVER,
                         self.hover
                     ),
-                    #9,
+                    THEME_COLOR_CONTROL_TEXT,
                     self.pressed
                 )
             }
@@ -1228,7 +1334,7 @@ live_design!{
             instance hover: 0.0
             instance pressed: 0.0
             instance focus: 0.0,
-            uniform border_radius: 0.5
+            uniform border_radius: (THEME_CORNER_RADIUS_TINY)
             
             fn get_bg(self, inout sdf: Sdf2d) {
                 sdf.box(
@@ -1238,7 +1344,7 @@ live_design!{
                     self.rect_size.y,
                     self.border_radius
                 )
-                sdf.fill(mix(#2, #3, self.hover));
+                sdf.fill(mix(THEME_COLOR_INPUT_BG, THEME_COLOR_INPUT_BG_HOVER, self.hover));
             }
             
             fn pixel(self) -> vec4 {
@@ -1253,7 +1359,7 @@ live_design!{
                 sdf.line_to(c.x, c.y + sz * 0.75);
                 sdf.close_path();
                 
-                sdf.fill(mix(#8, #c, self.hover));
+                sdf.fill(mix(THEME_COLOR_LABEL, THEME_COLOR_CONTROL_TEXT_HOVER, self.hover));
                 
                 return sdf.result
             }
@@ -1548,7 +1654,7 @@ live_design!{
                 sdf.line_to(c.x, c.y - sz);
                 sdf.line_to(c.x + sz, c.y + sz);
                 sdf.close_path();
-                sdf.fill(mix(#a, #f, self.hover));
+                sdf.fill(mix(THEME_COLOR_FOLD_ARROW, THEME_COLOR_FOLD_ARROW_HOVER, self.hover));
                 return sdf.result * self.fade;
             }
         }
@@ -1647,11 +1753,11 @@ live_design!{
             fn get_color(self) -> vec4 {
                 return mix(
                     mix(
-                        #9,
-                        #c,
+                        THEME_COLOR_CONTROL_TEXT,
+                        THEME_COLOR_CONTROL_TEXT_HOVER,
                         self.hover
                     ),
-                    #9,
+                    THEME_COLOR_CONTROL_TEXT,
                     self.pressed
                 )
             }
@@ -1735,8 +1841,8 @@ live_design!{
         draw_radio: {
             
             uniform size: 7.0;
-            uniform color_active: #00000000
-            uniform color_inactive: #x99EEFF
+            uniform color_active: (THEME_COLOR_RADIO_TAB_ACTIVE)
+            uniform color_inactive: (THEME_COLOR_RADIO_TAB)
             
             fn pixel(self) -> vec4 {
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size)
@@ -1746,10 +1852,10 @@ live_design!{
                         let left = sz + 1.;
                         let c = vec2(left + sz, self.rect_size.y * 0.5);
                         sdf.circle(left, c.y, sz);
-                        sdf.fill(#2);
+                        sdf.fill(THEME_COLOR_CHECK_WELL);
                         let isz = sz * 0.5;
                         sdf.circle(left, c.y, isz);
-                        sdf.fill(mix(#fff0, #f, self.selected));
+                        sdf.fill(mix(THEME_COLOR_CONTROL_MARK_OFF, THEME_COLOR_CONTROL_MARK, self.selected));
                     }
                     RadioType::Tab => {
                         let sz = self.size;
@@ -1772,11 +1878,11 @@ live_design!{
             instance focus: 0.0
             instance selected: 0.0
             
-            uniform color_unselected: #x00000088
-            uniform color_unselected_hover: #x000000CC
-            uniform color_selected: #xFFFFFF66
+            uniform color_unselected: (THEME_COLOR_RADIO_TAB_TEXT)
+            uniform color_unselected_hover: (THEME_COLOR_RADIO_TAB_TEXT_HOVER)
+            uniform color_selected: (THEME_COLOR_RADIO_TAB_TEXT_SELECTED)
             
-            color: #9
+            color: (THEME_COLOR_CONTROL_TEXT)
             text_style: {
                 font: {
                     //path: d"resources/ibmplexsans-semibold.ttf"
@@ -1803,11 +1909,11 @@ live_design!{
             fn get_color(self) -> vec4 {
                 return mix(
                     mix(
-                        #9,
-                        #c,
+                        THEME_COLOR_CONTROL_TEXT,
+                        THEME_COLOR_CONTROL_TEXT_HOVER,
                         self.hover
                     ),
-                    #9,
+                    THEME_COLOR_CONTROL_TEXT,
                     self.selected
                 )
             }
@@ -1933,21 +2039,21 @@ live_design!{
                 mix(
                     mix(
                         mix(
-                            #xFFFFFF55,
-                            #xFFFFFF88,
+                            THEME_COLOR_INPUT_TEXT,
+                            THEME_COLOR_INPUT_TEXT_HOVER,
                             self.hover
                         ),
-                        #xFFFFFFCC,
+                        THEME_COLOR_INPUT_TEXT_FOCUS,
                         self.focus
                     ),
-                    #3,
+                    THEME_COLOR_INPUT_TEXT_EMPTY,
                     self.is_empty
                 )
             }
         }
         draw_cursor: {
             instance focus: 0.0
-            uniform border_radius: 0.5
+            uniform border_radius: (THEME_CORNER_RADIUS_TINY)
             fn pixel(self) -> vec4 {
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size);
                 sdf.box(
@@ -1957,7 +2063,7 @@ live_design!{
                     self.rect_size.y,
                     self.border_radius
                 )
-                sdf.fill(mix(#ccc0, #f, self.focus));
+                sdf.fill(mix(THEME_COLOR_INPUT_CURSOR_UNFOCUSSED, THEME_COLOR_INPUT_CURSOR, self.focus));
                 return sdf.result
             }
         }
@@ -1966,7 +2072,7 @@ live_design!{
         draw_select: {
             instance hover: 0.0
             instance focus: 0.0
-            uniform border_radius: 2.0
+            uniform border_radius: (THEME_CORNER_RADIUS_INPUT)
             fn pixel(self) -> vec4 {
                 //return mix(#f00,#0f0,self.pos.y)
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size);
@@ -1977,7 +2083,7 @@ live_design!{
                     self.rect_size.y,
                     self.border_radius
                 )
-                sdf.fill(mix(#5550, #xFFFFFF40, self.focus)); // Pad color
+                sdf.fill(mix(THEME_COLOR_INPUT_SELECT_UNFOCUSSED, THEME_COLOR_INPUT_SELECT, self.focus)); // Pad color
                 return sdf.result
             }
         }
@@ -1990,9 +2096,9 @@ live_design!{
         on_focus_select_all: false,
         empty_message: "0",
         draw_bg: {
-            instance radius: 2.0
+            instance radius: (THEME_CORNER_RADIUS_INPUT)
             instance border_width: 0.0
-            instance border_color: #3
+            instance border_color: (THEME_COLOR_INPUT_BG_HOVER)
             instance inset: vec4(0.0, 0.0, 0.0, 0.0)
             
             fn get_color(self) -> vec4 {
@@ -2091,10 +2197,10 @@ live_design!{
                 
                 let sdf = Sdf2d::viewport(self.pos * self.rect_size)
                 
-                let slider_bg_color = mix(#38, #30, self.focus);
-                let slider_color = mix(mix(#5, #68, self.hover), #68, self.focus);
-                let nub_color = mix(mix(#8, #f, self.hover), mix(#c, #f, self.drag), self.focus);
-                let nubbg_color = mix(#eee0, #8, self.drag);
+                let slider_bg_color = mix(THEME_COLOR_SLIDER_TRACK, THEME_COLOR_SLIDER_TRACK_FOCUS, self.focus);
+                let slider_color = mix(mix(THEME_COLOR_SLIDER_FILL, THEME_COLOR_SLIDER_FILL_HOVER, self.hover), THEME_COLOR_SLIDER_FILL_HOVER, self.focus);
+                let nub_color = mix(mix(THEME_COLOR_SLIDER_NUB, THEME_COLOR_CONTROL_MARK, self.hover), mix(THEME_COLOR_SLIDER_NUB_DRAG, THEME_COLOR_CONTROL_MARK, self.drag), self.focus);
+                let nubbg_color = mix(THEME_COLOR_SLIDER_NUB_BG, THEME_COLOR_SLIDER_NUB, self.drag);
                 
                 match self.slider_type {
                     SliderType::Horizontal => {
@@ -2125,7 +2231,7 @@ live_design!{
         }
         
         draw_text: {
-            color: #9
+            color: (THEME_COLOR_CONTROL_TEXT)
         }
         
         label_walk: {
@@ -2149,8 +2255,8 @@ live_design!{
             numeric_only: true,
             draw_bg: {
                 shape: None
-                color: #5
-                radius: 2.0
+                color: (THEME_COLOR_SLIDER_FILL)
+                radius: (THEME_CORNER_RADIUS_INPUT)
             },
             
             padding: 0,
@@ -2210,7 +2316,7 @@ live_design!{
     
     SlideBody = <Label> {
         draw_text: {
-            color: #D
+            color: (THEME_COLOR_SLIDE_TEXT)
             text_style: {
                 font_size: 35
             }
@@ -2219,13 +2325,13 @@ live_design!{
     }
     
     Slide = <RoundedView> {
-        draw_bg: {color: #x1A, radius: 5.0}
+        draw_bg: {color: (THEME_COLOR_SLIDE_BG), radius: 5.0}
         width: Fill,
         height: Fill
         align: {x: 0.0, y: 0.5} flow: Down, spacing: 10, padding: 50
         title = <Label> {
             draw_text: {
-                color: #f
+                color: (THEME_COLOR_SLIDE_TITLE)
                 text_style: {
                     font_size: 84
                 }
@@ -2235,13 +2341,13 @@ live_design!{
     }
     
     SlideChapter = <Slide> {
-        draw_bg: {color: #xFF5C39, radius: 5.0}
+        draw_bg: {color: (THEME_COLOR_SLIDE_CHAPTER_BG), radius: 5.0}
         width: Fill,
         height: Fill
         align: {x: 0.0, y: 0.5} flow: Down, spacing: 10, padding: 50
         title = <Label> {
             draw_text: {
-                color: #x181818
+                color: (THEME_COLOR_SLIDE_CHAPTER_TITLE)
                 text_style: {
                     font_size: 120
                 }
@@ -2270,7 +2376,53 @@ live_design!{
                 alpha = pow(pos.x, 0.5);
             }
             //turn vec4(base,is_viz);
-            return Pal::premul(mix(vec4(#000.xyz, is_viz), vec4(base, 0.), alpha));
+            return Pal::premul(mix(vec4(THEME_COLOR_SCROLL_SHADOW.xyz, is_viz), vec4(base, 0.), alpha));
+        }
+    }
+    
+    // Charts
+    
+    ChartAxes = {
+        padding: {left: 48.0, top: 10.0, right: 14.0, bottom: 24.0}
+        draw_bg: {color: (THEME_COLOR_BG_EDITOR)}
+        draw_grid: {color: (THEME_COLOR_UP_4)}
+        draw_axis: {color: (THEME_COLOR_UP_25)}
+        draw_label: {
+            color: (THEME_COLOR_TEXT_META)
+            text_style: <THEME_FONT_DATA> {}
+        }
+        draw_crosshair: {color: (THEME_COLOR_UP_25)}
+        draw_readout: {
+            fn pixel(self) -> vec4 {
+                let sdf = Sdf2d::viewport(self.pos * self.rect_size);
+                sdf.box(0., 0., self.rect_size.x, self.rect_size.y, THEME_CORNER_RADIUS);
+                sdf.fill(THEME_COLOR_BG_HEADER);
+                return sdf.result;
+            }
         }
+        draw_readout_text: {
+            color: (THEME_COLOR_TEXT_SELECTED)
+            text_style: <THEME_FONT_DATA> {}
+        }
+    }
+    
+    LineChart = <LineChartBase> {
+        width: Fill,
+        height: Fill
+        axes: <ChartAxes> {}
+        draw_line: {line_width: 1.5}
+    }
+    
+    BarChart = <BarChartBase> {
+        width: Fill,
+        height: Fill
+        axes: <ChartAxes> {}
+        draw_bar: {radius: (THEME_CORNER_RADIUS_INPUT)}
+    }
+    
+    ScatterPlot = <ScatterPlotBase> {
+        width: Fill,
+        height: Fill
+        axes: <ChartAxes> {}
     }
 }
@@ -0,0 +1,141 @@
+use crate::makepad_platform::*;
+
+// Token overrides for the high contrast theme: pure black backgrounds, white text and
+// outlines and a strong yellow accent, with slightly larger fonts
+
+live_design!{
+    const THEME_FONT_SIZE_BASE = 10.5
+    const THEME_FONT_SIZE_CODE = 10.0
+    const THEME_FONT_SIZE_CONTROL = 12.0
+    const THEME_FONT_SIZE_SMALL = 7.0
+    
+    const THEME_BRIGHTNESS = #000
+    const THEME_COLOR_HIGHLIGHT = #x1A3FA0
+    const THEME_COLOR_HIGH = #F33
+    const THEME_COLOR_MID = #FD0
+    const THEME_COLOR_LOW = #4F4
+    
+    const THEME_COLOR_WHITE = #FFF
+    const THEME_COLOR_UP_80 = #FFF
+    const THEME_COLOR_UP_50 = #FFF
+    const THEME_COLOR_UP_25 = #FFFFFFC0
+    const THEME_COLOR_UP_15 = #FFFFFF60
+    const THEME_COLOR_UP_10 = #FFFFFF50
+    const THEME_COLOR_UP_4 = #FFFFFF20
+    const THEME_COLOR_DOWN_7 = #00000000
+    const THEME_COLOR_DOWN_10 = #00000000
+    const THEME_COLOR_DOWN_20 = #00000000
+    const THEME_COLOR_DOWN_50 = #00000000
+    const THEME_COLOR_BLACK = #000
+    
+    // the derived colors are repeated from theme_desktop_dark, so that every color
+    // token is defined by this theme
+    const THEME_COLOR_BG_APP = (THEME_BRIGHTNESS)
+    const THEME_COLOR_BG_HEADER = (blend(THEME_COLOR_BG_APP, THEME_COLOR_DOWN_10))
+    const THEME_COLOR_CLEAR = (THEME_COLOR_BG_APP)
+    const THEME_COLOR_BG_EDITOR = (blend(THEME_COLOR_BG_HEADER, THEME_COLOR_DOWN_10))
+    const THEME_COLOR_BG_ODD = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_DOWN_7))
+    const THEME_COLOR_BG_SELECTED = (THEME_COLOR_HIGHLIGHT)
+    const THEME_COLOR_BG_UNFOCUSSED = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_UP_10))
+    const THEME_COLOR_EDITOR_SELECTED = (THEME_COLOR_BG_SELECTED)
+    const THEME_COLOR_EDITOR_SELECTED_UNFOCUSSED = (THEME_COLOR_BG_UNFOCUSSED)
+    const THEME_COLOR_BG_CURSOR = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_UP_4))
+    const THEME_COLOR_FG_CURSOR = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_UP_50))
+    
+    const THEME_COLOR_TEXT_DEFAULT = (THEME_COLOR_UP_50)
+    const THEME_COLOR_TEXT_HOVER = (THEME_COLOR_UP_80)
+    const THEME_COLOR_TEXT_META = (THEME_COLOR_UP_25)
+    const THEME_COLOR_TEXT_SELECTED = (THEME_COLOR_UP_80)
+    
+    const THEME_COLOR_SCROLL_BAR_DEFAULT = (THEME_COLOR_UP_10)
+    const THEME_COLOR_CONTROL_HOVER = (blend(THEME_COLOR_BG_HEADER, THEME_COLOR_UP_50))
+    const THEME_COLOR_CONTROL_PRESSED = (blend(THEME_COLOR_BG_HEADER, THEME_COLOR_UP_25))
+    
+    const THEME_COLOR_ICON_WAIT = (THEME_COLOR_LOW)
+    const THEME_COLOR_ERROR = (THEME_COLOR_HIGH)
+    const THEME_COLOR_WARNING = (THEME_COLOR_MID)
+    const THEME_COLOR_ICON_PANIC = (THEME_COLOR_HIGH)
+    const THEME_COLOR_DRAG_QUAD = (THEME_COLOR_UP_50)
+    const THEME_COLOR_PANIC = #f0f
+    const THEME_COLOR_TRANSPARENT = #0000
+    
+    const THEME_COLOR_LABEL = #f
+    const THEME_COLOR_CONTROL_TEXT = #f
+    const THEME_COLOR_CONTROL_TEXT_FOCUS = #FD0
+    const THEME_COLOR_CONTROL_TEXT_HOVER = #FD0
+    const THEME_COLOR_CONTROL_MARK = #FD0
+    const THEME_COLOR_CONTROL_MARK_OFF = #FFDD0000
+    
+    const THEME_COLOR_BUTTON_BODY = #000
+    const THEME_COLOR_BUTTON_BODY_HOVER = #x1A3FA0
+    const THEME_COLOR_BUTTON_BODY_PRESSED = #x0A2070
+    const THEME_COLOR_BUTTON_BEVEL = #f
+    const THEME_COLOR_BUTTON_BEVEL_PRESSED = #FD0
+    const THEME_COLOR_BUTTON_SHADOW = #f
+    const THEME_COLOR_BUTTON_SHADOW_HOVER = #FD0
+    
+    const THEME_COLOR_CHECK_BG = #000
+    const THEME_COLOR_CHECK_BG_BOTTOM = #000
+    const THEME_COLOR_CHECK_BG_HOVER = #x1A3FA0
+    const THEME_COLOR_CHECK_BG_HOVER_BOTTOM = #x1A3FA0
+    const THEME_COLOR_CHECK_OUTLINE = #f
+    const THEME_COLOR_CHECK_WELL = #4
+    const THEME_COLOR_CHECK_TEXT = #f
+    
+    const THEME_COLOR_WINDOW_BUTTON = #000
+    const THEME_COLOR_WINDOW_BUTTON_HOVER = #x1A3FA0
+    const THEME_COLOR_WINDOW_BUTTON_PRESSED = #x0A2070
+    const THEME_COLOR_WINDOW_BUTTON_CLOSE_HOVER = #e00
+    const THEME_COLOR_WINDOW_BUTTON_CLOSE_PRESSED = #a00
+    const THEME_COLOR_WINDOW_BUTTON_ICON = #f
+    const THEME_COLOR_WINDOW_BUTTON_XR_HOVER = #x1A3FA0
+    const THEME_COLOR_WINDOW_BUTTON_XR_PRESSED = #x0A2070
+    const THEME_COLOR_WINDOW_BUTTON_XR_ICON = #f
+    
+    const THEME_COLOR_INPUT_BG = #000
+    const THEME_COLOR_INPUT_BG_HOVER = #2
+    const THEME_COLOR_INPUT_TEXT = #f
+    const THEME_COLOR_INPUT_TEXT_HOVER = #f
+    const THEME_COLOR_INPUT_TEXT_FOCUS = #FD0
+    const THEME_COLOR_INPUT_TEXT_EMPTY = #8
+    const THEME_COLOR_INPUT_CURSOR = #FD0
+    const THEME_COLOR_INPUT_CURSOR_UNFOCUSSED = #FFDD0000
+    const THEME_COLOR_INPUT_SELECT = #x1A3FA0
+    const THEME_COLOR_INPUT_SELECT_UNFOCUSSED = #x1A3FA000
+    
+    const THEME_COLOR_SLIDER_TRACK = #6
+    const THEME_COLOR_SLIDER_TRACK_FOCUS = #8
+    const THEME_COLOR_SLIDER_FILL = #f
+    const THEME_COLOR_SLIDER_FILL_HOVER = #FD0
+    const THEME_COLOR_SLIDER_NUB = #f
+    const THEME_COLOR_SLIDER_NUB_DRAG = #FD0
+    const THEME_COLOR_SLIDER_NUB_BG = #fff0
+    
+    const THEME_COLOR_RADIO_TAB = #x1A3FA0
+    const THEME_COLOR_RADIO_TAB_ACTIVE = #000
+    const THEME_COLOR_RADIO_TAB_TEXT = #f
+    const THEME_COLOR_RADIO_TAB_TEXT_HOVER = #FD0
+    const THEME_COLOR_RADIO_TAB_TEXT_SELECTED = #FD0
+    
+    const THEME_COLOR_FOLD_ARROW = #f
+    const THEME_COLOR_FOLD_ARROW_HOVER = #FD0
+    
+    const THEME_COLOR_POPUP_BG = #000
+    const THEME_COLOR_POPUP_BORDER = #f
+    const THEME_COLOR_POPUP_ITEM_BG = #000
+    const THEME_COLOR_POPUP_ITEM_BG_HOVER = #x1A3FA0
+    
+    const THEME_COLOR_NAV_FOCUS = #FD0F
+    const THEME_COLOR_NAV_TEXT = #f
+    const THEME_COLOR_TAB_DRAG = #FD0
+    
+    const THEME_COLOR_MOUSE_CURSOR = #000
+    const THEME_COLOR_MOUSE_CURSOR_BORDER = #fff
+    const THEME_COLOR_SCROLL_SHADOW = #0000
+    
+    const THEME_COLOR_SLIDE_BG = #000
+    const THEME_COLOR_SLIDE_TEXT = #f
+    const THEME_COLOR_SLIDE_TITLE = #FD0
+    const THEME_COLOR_SLIDE_CHAPTER_BG = #000
+    const THEME_COLOR_SLIDE_CHAPTER_TITLE = #FD0
+}
@@ -0,0 +1,142 @@
+use crate::makepad_platform::*;
+
+// Token overrides for the light theme. Only tokens are defined here, they replace the
+// same-named definitions of theme_desktop_dark when the theme is switched (see theme.rs)
+
+live_design!{
+    const THEME_FONT_SIZE_BASE = 9.4
+    const THEME_FONT_SIZE_CODE = 9.0
+    const THEME_FONT_SIZE_CONTROL = 11.0
+    const THEME_FONT_SIZE_SMALL = 6.0
+    
+    const THEME_BRIGHTNESS = #xEC
+    const THEME_COLOR_HIGHLIGHT = #xB8D4F4
+    const THEME_COLOR_HIGH = #C00
+    const THEME_COLOR_MID = #D80
+    const THEME_COLOR_LOW = #690
+    
+    // in the light theme 'up' darkens and 'down' lightens
+    const THEME_COLOR_WHITE = #000
+    const THEME_COLOR_UP_80 = #000000DD
+    const THEME_COLOR_UP_50 = #000000A0
+    const THEME_COLOR_UP_25 = #00000060
+    const THEME_COLOR_UP_15 = #00000026
+    const THEME_COLOR_UP_10 = #0000001A
+    const THEME_COLOR_UP_4 = #0000000A
+    const THEME_COLOR_DOWN_7 = #FFFFFF13
+    const THEME_COLOR_DOWN_10 = #FFFFFF30
+    const THEME_COLOR_DOWN_20 = #FFFFFF40
+    const THEME_COLOR_DOWN_50 = #FFFFFF80
+    const THEME_COLOR_BLACK = #FFF
+    
+    // the derived colors are repeated from theme_desktop_dark, so that every color
+    // token is defined by this theme
+    const THEME_COLOR_BG_APP = (THEME_BRIGHTNESS)
+    const THEME_COLOR_BG_HEADER = (blend(THEME_COLOR_BG_APP, THEME_COLOR_DOWN_10))
+    const THEME_COLOR_CLEAR = (THEME_COLOR_BG_APP)
+    const THEME_COLOR_BG_EDITOR = (blend(THEME_COLOR_BG_HEADER, THEME_COLOR_DOWN_10))
+    const THEME_COLOR_BG_ODD = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_DOWN_7))
+    const THEME_COLOR_BG_SELECTED = (THEME_COLOR_HIGHLIGHT)
+    const THEME_COLOR_BG_UNFOCUSSED = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_UP_10))
+    const THEME_COLOR_EDITOR_SELECTED = (THEME_COLOR_BG_SELECTED)
+    const THEME_COLOR_EDITOR_SELECTED_UNFOCUSSED = (THEME_COLOR_BG_UNFOCUSSED)
+    const THEME_COLOR_BG_CURSOR = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_UP_4))
+    const THEME_COLOR_FG_CURSOR = (blend(THEME_COLOR_BG_EDITOR, THEME_COLOR_UP_50))
+    
+    const THEME_COLOR_TEXT_DEFAULT = (THEME_COLOR_UP_50)
+    const THEME_COLOR_TEXT_HOVER = (THEME_COLOR_UP_80)
+    const THEME_COLOR_TEXT_META = (THEME_COLOR_UP_25)
+    const THEME_COLOR_TEXT_SELECTED = (THEME_COLOR_UP_80)
+    
+    const THEME_COLOR_SCROLL_BAR_DEFAULT = (THEME_COLOR_UP_10)
+    const THEME_COLOR_CONTROL_HOVER = (blend(THEME_COLOR_BG_HEADER, THEME_COLOR_UP_50))
+    const THEME_COLOR_CONTROL_PRESSED = (blend(THEME_COLOR_BG_HEADER, THEME_COLOR_UP_25))
+    
+    const THEME_COLOR_ICON_WAIT = (THEME_COLOR_LOW)
+    const THEME_COLOR_ERROR = (THEME_COLOR_HIGH)
+    const THEME_COLOR_WARNING = (THEME_COLOR_MID)
+    const THEME_COLOR_ICON_PANIC = (THEME_COLOR_HIGH)
+    const THEME_COLOR_DRAG_QUAD = (THEME_COLOR_UP_50)
+    const THEME_COLOR_PANIC = #C0C
+    const THEME_COLOR_TRANSPARENT = #0000
+    
+    const THEME_COLOR_LABEL = #4
+    const THEME_COLOR_CONTROL_TEXT = #3
+    const THEME_COLOR_CONTROL_TEXT_FOCUS = #2
+    const THEME_COLOR_CONTROL_TEXT_HOVER = #0
+    const THEME_COLOR_CONTROL_MARK = #x2A64C8
+    const THEME_COLOR_CONTROL_MARK_OFF = #x2A64C800
+    
+    const THEME_COLOR_BUTTON_BODY = #xF8
+    const THEME_COLOR_BUTTON_BODY_HOVER = #xFF
+    const THEME_COLOR_BUTTON_BODY_PRESSED = #xD8
+    const THEME_COLOR_BUTTON_BEVEL = #xFF
+    const THEME_COLOR_BUTTON_BEVEL_PRESSED = #xB0
+    const THEME_COLOR_BUTTON_SHADOW = #xB8
+    const THEME_COLOR_BUTTON_SHADOW_HOVER = #xA8
+    
+    const THEME_COLOR_CHECK_BG = #xFFFFFFDD
+    const THEME_COLOR_CHECK_BG_BOTTOM = #xF0F0F0DD
+    const THEME_COLOR_CHECK_BG_HOVER = #xFFFFFFFF
+    const THEME_COLOR_CHECK_BG_HOVER_BOTTOM = #xF4F4F4FF
+    const THEME_COLOR_CHECK_OUTLINE = #x888
+    const THEME_COLOR_CHECK_WELL = #xD0
+    const THEME_COLOR_CHECK_TEXT = #000A
+    
+    const THEME_COLOR_WINDOW_BUTTON = #xE0
+    const THEME_COLOR_WINDOW_BUTTON_HOVER = #xC8
+    const THEME_COLOR_WINDOW_BUTTON_PRESSED = #xB0
+    const THEME_COLOR_WINDOW_BUTTON_CLOSE_HOVER = #e22
+    const THEME_COLOR_WINDOW_BUTTON_CLOSE_PRESSED = #c00
+    const THEME_COLOR_WINDOW_BUTTON_ICON = #2
+    const THEME_COLOR_WINDOW_BUTTON_XR_HOVER = #8DD
+    const THEME_COLOR_WINDOW_BUTTON_XR_PRESSED = #6BB
+    const THEME_COLOR_WINDOW_BUTTON_XR_ICON = #4
+    
+    const THEME_COLOR_INPUT_BG = #xFA
+    const THEME_COLOR_INPUT_BG_HOVER = #xFF
+    const THEME_COLOR_INPUT_TEXT = #00000099
+    const THEME_COLOR_INPUT_TEXT_HOVER = #000000BB
+    const THEME_COLOR_INPUT_TEXT_FOCUS = #x000000EE
+    const THEME_COLOR_INPUT_TEXT_EMPTY = #xAA
+    const THEME_COLOR_INPUT_CURSOR = #0
+    const THEME_COLOR_INPUT_CURSOR_UNFOCUSSED = #3330
+    const THEME_COLOR_INPUT_SELECT = #x2A64C850
+    const THEME_COLOR_INPUT_SELECT_UNFOCUSSED = #x2A64C800
+    
+    const THEME_COLOR_SLIDER_TRACK = #xC8
+    const THEME_COLOR_SLIDER_TRACK_FOCUS = #xB8
+    const THEME_COLOR_SLIDER_FILL = #x8AA8D8
+    const THEME_COLOR_SLIDER_FILL_HOVER = #x5A88D0
+    const THEME_COLOR_SLIDER_NUB = #5
+    const THEME_COLOR_SLIDER_NUB_DRAG = #x2A64C8
+    const THEME_COLOR_SLIDER_NUB_BG = #5550
+    
+    const THEME_COLOR_RADIO_TAB = #xD0E4F8
+    const THEME_COLOR_RADIO_TAB_ACTIVE = #00000000
+    const THEME_COLOR_RADIO_TAB_TEXT = #x00000088
+    const THEME_COLOR_RADIO_TAB_TEXT_HOVER = #x000000CC
+    const THEME_COLOR_RADIO_TAB_TEXT_SELECTED = #x000000DD
+    
+    const THEME_COLOR_FOLD_ARROW = #6
+    const THEME_COLOR_FOLD_ARROW_HOVER = #0
+    
+    const THEME_COLOR_POPUP_BG = #xF4
+    const THEME_COLOR_POPUP_BORDER = #xC0
+    const THEME_COLOR_POPUP_ITEM_BG = #xF4
+    const THEME_COLOR_POPUP_ITEM_BG_HOVER = #xD8
+    
+    const THEME_COLOR_NAV_FOCUS = #ffff
+    const THEME_COLOR_NAV_TEXT = #4
+    const THEME_COLOR_TAB_DRAG = #4
+    
+    const THEME_COLOR_MOUSE_CURSOR = #000
+    const THEME_COLOR_MOUSE_CURSOR_BORDER = #fff
+    const THEME_COLOR_SCROLL_SHADOW = #000
+    
+    const THEME_COLOR_SLIDE_BG = #xF0
+    const THEME_COLOR_SLIDE_TEXT = #2
+    const THEME_COLOR_SLIDE_TITLE = #0
+    const THEME_COLOR_SLIDE_CHAPTER_BG = #xFF5C39
+    const THEME_COLOR_SLIDE_CHAPTER_TITLE = #x181818
+}
@@ -4,6 +4,7 @@ use {
         makepad_derive_widget::*,
         makepad_draw::*,
         widget::*,
+        accessibility::AccessTreeBuilder,
         scroll_bars::ScrollBars,
     },
 };
@@ -521,6 +522,17 @@ impl Widget for View {
         }
     }
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        if !self.visible {
+            return
+        }
+        for id in &self.draw_order {
+            if let Some(child) = self.children.get_mut(id) {
+                child.accessibility(cx, tree);
+            }
+        }
+    }
+    
     fn find_widgets(&mut self, path: &[LiveId], cached: WidgetCache, results: &mut WidgetSet) {
         match cached {
             WidgetCache::Yes | WidgetCache::Clear => {
@@ -596,7 +608,7 @@ impl View {
             abs_pos: walk.abs_pos,
             width: if walk.width.is_fill() {walk.width}else {Size::Fixed(view_size.x)},
             height: if walk.height.is_fill() {walk.height}else {Size::Fixed(view_size.y)},
-            margin: walk.margin
+            ..walk
         }
     }
     
@@ -1,5 +1,6 @@
 use {
     crate::makepad_draw::*,
+    crate::accessibility::AccessTreeBuilder,
     std::fmt::{Formatter, Debug, Error},
     std::collections::BTreeMap,
     std::any::TypeId,
@@ -88,6 +89,10 @@ pub trait Widget: LiveApply {
         self.set_text(v);
         self.redraw(cx);
     }
+    
+    // describe this widget (and its children) for assistive technology
+    fn accessibility(&mut self, _cx: &Cx, _tree: &mut AccessTreeBuilder) {
+    }
     /*
     fn create_child(
         &mut self,
@@ -537,6 +542,12 @@ impl WidgetRef {
         }
     }
     
+    pub fn accessibility(&self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        if let Some(inner) = self.0.borrow_mut().as_mut() {
+            inner.accessibility(cx, tree)
+        }
+    }
+    
     pub fn borrow_mut<T: 'static + Widget>(&self) -> Option<std::cell::RefMut<'_, T >> {
         if let Ok(ret) = std::cell::RefMut::filter_map(self.0.borrow_mut(), | inner | {
             if let Some(inner) = inner.as_mut() {
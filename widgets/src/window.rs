@@ -6,6 +6,8 @@ use crate::{
     button::*,
     view::*,
     widget::*,
+    accessibility::*,
+    theme::CxThemeApi,
 };
 
 live_design!{
@@ -43,6 +45,8 @@ pub struct Window {
     
     // testing
     #[rust] draw_state: DrawStateWrap<DrawState>,
+    // rebuilt on every draw once a bridge asked for it
+    #[rust] access_tracker: Option<AccessTreeTracker>,
     
 }
 
@@ -116,6 +120,11 @@ impl Window {
             }
             cx.repaint_pass_and_child_passes(self.pass.pass_id());
         }
+        match event {
+            Event::AppGotFocus => cx.update_os_theme(),
+            Event::Signal => cx.apply_os_theme(),
+            _ => ()
+        }
         let is_for_other_window = match event {
             Event::WindowCloseRequested(ev) => ev.window_id != self.window.window_id(),
             Event::WindowClosed(ev) => {
@@ -200,6 +209,9 @@ impl Window {
             }
         }
         
+        if let Event::Signal = event {
+            Cx2d::handle_system_fonts_scan(cx);
+        }
         if let Event::ClearAtlasses = event {
             Cx2d::reset_fonts_atlas(cx);
             Cx2d::reset_icon_atlas(cx);
@@ -216,6 +228,14 @@ impl Window {
         }
     }
     
+    pub fn window_id(&self) -> WindowId {
+        self.window.window_id()
+    }
+    
+    pub fn close(&mut self, cx: &mut Cx) {
+        self.window.close(cx);
+    }
+    
     pub fn begin(&mut self, cx: &mut Cx2d) -> Redrawing {
 
         if !cx.will_redraw(&mut self.main_draw_list, Walk::default()) {
@@ -252,6 +272,27 @@ impl Window {
         
         self.main_draw_list.end(cx);
         cx.end_pass(&self.pass);
+        self.update_access_tree(cx);
+    }
+    
+    /// The changes to the accessibility tree of this window since the last call, for a
+    /// platform bridge. The first call returns the whole tree, from then on the tree is
+    /// rebuilt whenever the window is drawn
+    pub fn take_access_update(&mut self, cx: &Cx) -> Option<AccessTreeUpdate> {
+        if self.access_tracker.is_none() {
+            self.access_tracker = Some(AccessTreeTracker::default());
+            self.update_access_tree(cx);
+        }
+        self.access_tracker.as_mut().unwrap().take_update()
+    }
+    
+    fn update_access_tree(&mut self, cx: &Cx) {
+        if self.access_tracker.is_none() {
+            return
+        }
+        let mut builder = AccessTreeBuilder::default();
+        self.accessibility(cx, &mut builder);
+        self.access_tracker.as_mut().unwrap().set_tree(builder.finish());
     }
 }
 
@@ -285,6 +326,17 @@ impl Widget for Window {
         self.view.find_widgets(path, cached, results);
     }
     
+    fn accessibility(&mut self, cx: &Cx, tree: &mut AccessTreeBuilder) {
+        let cx_window = &cx.windows[self.window.window_id()];
+        tree.begin(
+            AccessNode::new(AccessNodeId::from_widget(self.widget_uid()), AccessRole::Window)
+                .with_name(&cx_window.create_title)
+                .with_bounds(Rect {pos: DVec2::default(), size: cx_window.window_geom.inner_size})
+        );
+        self.view.accessibility(cx, tree);
+        tree.end();
+    }
+    
     fn draw_walk_widget(&mut self, cx: &mut Cx2d, walk: Walk) -> WidgetDraw {
         if self.draw_state.begin(cx, DrawState::Drawing) {
             if self.begin(cx).is_not_redrawing() {
@@ -0,0 +1,103 @@
+use makepad_widgets::*;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    TestWindow = <Window> {
+        body = <View> {
+            save = <Button> {text: "Save"}
+            wrap = <CheckBox> {text: "Wrap lines"}
+            dock = <Dock> {
+                root = Tabs {
+                    tabs: [edit_a, edit_b],
+                    selected: 1
+                }
+                edit_a = Tab {
+                    name: "a.rs"
+                    kind: Editor
+                }
+                edit_b = Tab {
+                    name: "b.rs"
+                    kind: Editor
+                }
+                Editor = <View> {}
+            }
+        }
+    }
+}
+
+fn cx() -> Cx {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    live_design(&mut cx);
+    cx.live_expand();
+    cx
+}
+
+fn new_window(cx: &mut Cx) -> WidgetRef {
+    let module_id = LiveModuleId::from_str(module_path!()).unwrap();
+    let window = Window::new_from_module(cx, module_id, live_id!(TestWindow)).unwrap();
+    WidgetRef::new_with_inner(Box::new(window))
+}
+
+fn draw(cx: &mut Cx, window: &WidgetRef) {
+    let draw_event = DrawEvent {redraw_all: true, ..Default::default()};
+    let cx = &mut Cx2d::new(cx, &draw_event);
+    window.draw_widget_all(cx);
+}
+
+fn take_update(cx: &Cx, window: &WidgetRef) -> Option<AccessTreeUpdate> {
+    window.borrow_mut::<Window>().unwrap().take_access_update(cx)
+}
+
+#[test]
+fn widgets_describe_themselves() {
+    let mut cx = cx();
+    let window = new_window(&mut cx);
+    draw(&mut cx, &window);
+    let mut tree = AccessTree::default();
+    tree.apply_update(&take_update(&cx, &window).unwrap());
+
+    let root = tree.node(tree.root.unwrap()).unwrap();
+    assert_eq!(root.role, AccessRole::Window);
+    let save = tree.find_by_name(AccessRole::Button, "Save").unwrap();
+    assert!(save.actions.contains(&AccessAction::Click));
+    let wrap = tree.find_by_name(AccessRole::CheckBox, "Wrap lines").unwrap();
+    assert_eq!(wrap.toggled, Some(AccessToggled::False));
+    assert_eq!(tree.find_by_name(AccessRole::Tab, "a.rs").unwrap().selected, Some(false));
+    assert_eq!(tree.find_by_name(AccessRole::Tab, "b.rs").unwrap().selected, Some(true));
+    assert!(tree.find_by_name(AccessRole::TabPanel, "b.rs").is_some());
+    assert!(tree.nodes.iter().any( | node | node.role == AccessRole::TabList));
+}
+
+#[test]
+fn redraws_hand_out_only_the_changes() {
+    let mut cx = cx();
+    let window = new_window(&mut cx);
+    draw(&mut cx, &window);
+    let mut tree = AccessTree::default();
+    tree.apply_update(&take_update(&cx, &window).unwrap());
+
+    // nothing changed since the last update
+    draw(&mut cx, &window);
+    assert_eq!(take_update(&cx, &window), None);
+
+    window.button(id!(save)).set_text("Save all");
+    window.dock(id!(dock)).select_tab(&mut cx, live_id!(edit_a));
+    draw(&mut cx, &window);
+    let update = take_update(&cx, &window).unwrap();
+    assert!(update.removed.is_empty());
+    let mut names: Vec<(AccessRole, &str)> = update.updated.iter().map( | node | (node.role, node.name.as_str())).collect();
+    names.sort_by_key( | name | format!("{:?}", name));
+    assert_eq!(names, vec![
+        (AccessRole::Button, "Save all"),
+        (AccessRole::Tab, "a.rs"),
+        (AccessRole::Tab, "b.rs"),
+        (AccessRole::TabPanel, "a.rs"),
+    ]);
+
+    tree.apply_update(&update);
+    assert!(tree.find_by_name(AccessRole::Button, "Save").is_none());
+    assert_eq!(tree.find_by_name(AccessRole::Tab, "a.rs").unwrap().selected, Some(true));
+}
@@ -0,0 +1,53 @@
+use makepad_widgets::{
+    chart::{chart_ticks, ChartMap, ChartView},
+    *,
+};
+
+#[test]
+fn ticks_are_nice_and_cover_the_range() {
+    let (ticks, step) = chart_ticks(0.0, 10.0, 5);
+    assert_eq!(step, 2.0);
+    assert_eq!(ticks, vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+
+    let (ticks, step) = chart_ticks(-0.35, 0.35, 4);
+    assert_eq!(step, 0.2);
+    assert_eq!(ticks.len(), 3);
+
+    assert!(chart_ticks(1.0, 1.0, 5).0.is_empty());
+    assert!(chart_ticks(0.0, 1.0, 0).0.is_empty());
+}
+
+#[test]
+fn ticks_far_from_zero_terminate() {
+    // the tick index used to stop changing past 2^53, so the loop never ended
+    let (ticks, _) = chart_ticks(1e20, 1e20 + 1e5, 5);
+    assert!(ticks.len() <= 12);
+    let (ticks, _) = chart_ticks(1e300, 1e300 * (1.0 + 1e-15), 10);
+    assert!(ticks.len() <= 22);
+}
+
+#[test]
+fn degenerate_ranges_are_widened_by_their_magnitude() {
+    let mut view = ChartView::default();
+    view.x_range = Some((1e20, 1e20));
+    view.y_range = Some((3.0, 3.0));
+    let (x, y) = view.resolve(None, false);
+    assert!(x.1 > x.0);
+    assert_eq!(y, (2.5, 3.5));
+}
+
+#[test]
+fn map_round_trips() {
+    let map = ChartMap {
+        rect: Rect {pos: dvec2(10.0, 20.0), size: dvec2(200.0, 100.0)},
+        x: (0.0, 4.0),
+        y: (-1.0, 1.0),
+    };
+    // y grows upwards
+    assert_eq!(map.to_screen(dvec2(0.0, -1.0)), dvec2(10.0, 120.0));
+    assert_eq!(map.to_screen(dvec2(4.0, 1.0)), dvec2(210.0, 20.0));
+    assert_eq!(map.x_scale(), 0.02);
+    let p = dvec2(1.5, 0.25);
+    let q = map.to_data(map.to_screen(p));
+    assert!((q - p).length() < 1e-12);
+}
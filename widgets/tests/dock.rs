@@ -0,0 +1,275 @@
+use makepad_widgets::{makepad_micro_serde::*, *};
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    TestDock = <Dock> {
+        root = Splitter {
+            axis: Horizontal,
+            align: FromA(200.0),
+            a: left,
+            b: right
+        }
+        left = Tabs {
+            tabs: [files],
+            selected: 0
+        }
+        right = Tabs {
+            tabs: [edit_a, edit_b],
+            selected: 1
+        }
+        files = Tab {
+            name: "Files"
+            kind: Files
+        }
+        edit_a = Tab {
+            name: "a.rs"
+            kind: Editor
+        }
+        edit_b = Tab {
+            name: "b.rs"
+            kind: Editor
+        }
+        Files = <View> {}
+        Editor = <View> {}
+    }
+}
+
+fn cx() -> Cx {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    live_design(&mut cx);
+    cx.live_expand();
+    cx
+}
+
+fn new_dock(cx: &mut Cx) -> DockRef {
+    let module_id = LiveModuleId::from_str(module_path!()).unwrap();
+    let dock = Dock::new_from_module(cx, module_id, live_id!(TestDock)).unwrap();
+    WidgetRef::new_with_inner(Box::new(dock)).as_dock()
+}
+
+#[test]
+fn torn_off_tabs_are_restored() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let floating_id = dock.float_tab(&mut cx, live_id!(edit_b), Some(dvec2(100.0, 50.0))).unwrap();
+    let json = dock.save_state_json().unwrap();
+    
+    let restored = new_dock(&mut cx);
+    restored.load_state_json(&mut cx, &json).unwrap();
+    let floating = restored.save_state().unwrap().floating.unwrap();
+    assert_eq!(floating.len(), 1);
+    assert_eq!(floating[0].id, floating_id.0);
+    assert_eq!(floating[0].position, Some((100.0, 50.0)));
+    assert_eq!(Some(LiveId(floating[0].root)), restored.find_tab_bar_of_tab(live_id!(edit_b)));
+    assert_eq!(restored.find_tab_bar_of_tab(live_id!(edit_a)), Some(live_id!(right)));
+    assert_eq!(restored.save_state_json().unwrap(), json);
+}
+
+fn drag_tab_out(cx: &mut Cx, dock: &DockRef, tab_id: LiveId, screen_pos: DVec2) {
+    dock.tab_start_drag(cx, tab_id, DragItem::String {value: String::new(), internal_id: Some(tab_id)});
+    dock.handle_widget_event(cx, &Event::DragEnd(DragEndEvent {screen_pos}));
+}
+
+#[test]
+fn tabs_released_outside_are_torn_off() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    drag_tab_out(&mut cx, &dock, live_id!(edit_b), dvec2(300.0, 200.0));
+    let floating = dock.save_state().unwrap().floating.unwrap();
+    if cx.os_drags_leave_windows() {
+        assert_eq!(floating.len(), 1);
+        assert_eq!(floating[0].position, Some((300.0, 200.0)));
+        assert_eq!(Some(LiveId(floating[0].root)), dock.find_tab_bar_of_tab(live_id!(edit_b)));
+    }
+    else {
+        assert_eq!(floating.len(), 0);
+        assert_eq!(dock.find_tab_bar_of_tab(live_id!(edit_b)), Some(live_id!(right)));
+    }
+}
+
+#[test]
+fn tabs_dropped_in_a_window_stay_docked() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let window_id = WindowHandle::new(&mut cx).window_id();
+    dock.tab_start_drag(&mut cx, live_id!(edit_a), DragItem::String {value: String::new(), internal_id: None});
+    dock.handle_widget_event(&mut cx, &Event::Drop(DropEvent {
+        window_id,
+        modifiers: Default::default(),
+        handled: Default::default(),
+        abs: dvec2(-1000.0, -1000.0),
+        items: Default::default(),
+    }));
+    dock.handle_widget_event(&mut cx, &Event::DragEnd(DragEndEvent {screen_pos: dvec2(0.0, 0.0)}));
+    assert_eq!(dock.save_state().unwrap().floating.unwrap().len(), 0);
+    assert_eq!(dock.find_tab_bar_of_tab(live_id!(edit_a)), Some(live_id!(right)));
+}
+
+#[test]
+fn loads_layouts_saved_without_floating_windows() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let mut state = dock.save_state().unwrap();
+    state.floating = None;
+    let json = state.serialize_json();
+    assert!(!json.contains("floating"));
+    
+    // windows that are open when such a layout is loaded are closed
+    dock.float_tab(&mut cx, live_id!(edit_a), None).unwrap();
+    dock.load_state_json(&mut cx, &json).unwrap();
+    assert_eq!(dock.save_state().unwrap().floating.unwrap().len(), 0);
+    assert_eq!(dock.find_tab_bar_of_tab(live_id!(edit_a)), Some(live_id!(right)));
+}
+
+fn item(state: &mut DockState, id: LiveId) -> &mut DockItemState {
+    state.items.iter_mut().find( | item | match item {
+        DockItemState::Splitter {id: item_id, ..}
+            | DockItemState::Tabs {id: item_id, ..}
+            | DockItemState::Tab {id: item_id, ..} => *item_id == id.0
+    }).unwrap()
+}
+
+fn set_kind(state: &mut DockState, id: LiveId, name: &str) {
+    if let DockItemState::Tab {kind, kind_name, ..} = item(state, id) {
+        *kind = LiveId::from_str(name).0;
+        *kind_name = name.to_string();
+    }
+}
+
+fn kind_of_item(dock: &DockRef, id: LiveId) -> Option<LiveId> {
+    dock.borrow_mut().unwrap().items().get(&id).map( | (kind, _) | *kind)
+}
+
+#[test]
+fn round_trips_through_json() {
+    let mut cx = cx();
+    let json = new_dock(&mut cx).save_state_json().unwrap();
+    let restored = new_dock(&mut cx);
+    restored.load_state_json(&mut cx, &json).unwrap();
+    assert_eq!(restored.save_state_json().unwrap(), json);
+    for id in [live_id!(files), live_id!(edit_a), live_id!(edit_b)] {
+        assert!(!restored.item(id).is_empty());
+    }
+}
+
+#[test]
+fn drops_tabs_of_unknown_kinds() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let mut state = dock.save_state().unwrap();
+    set_kind(&mut state, live_id!(edit_b), "Removed");
+    dock.load_state(&mut cx, &state).unwrap();
+    
+    let mut state = dock.save_state().unwrap();
+    assert_eq!(dock.find_tab_bar_of_tab(live_id!(edit_b)), None);
+    assert!(matches!(
+        item(&mut state, live_id!(right)),
+        DockItemState::Tabs {tabs, selected: 0, ..} if *tabs == vec![live_id!(edit_a).0]
+    ));
+}
+
+#[test]
+fn drops_tabs_that_are_in_no_tab_bar() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let mut state = dock.save_state().unwrap();
+    // an orphaned tab, and a tab bar no splitter leads to
+    state.items.push(DockItemState::Tab {
+        id: live_id!(orphan).0,
+        name: "orphan.rs".to_string(),
+        closable: true,
+        kind: live_id!(Editor).0,
+        kind_name: "Editor".to_string()
+    });
+    state.items.push(DockItemState::Tabs {
+        id: live_id!(lost).0,
+        tabs: vec![live_id!(orphan_in_bar).0],
+        selected: 0,
+        closable: true
+    });
+    state.items.push(DockItemState::Tab {
+        id: live_id!(orphan_in_bar).0,
+        name: "lost.rs".to_string(),
+        closable: true,
+        kind: live_id!(Editor).0,
+        kind_name: "Editor".to_string()
+    });
+    dock.load_state(&mut cx, &state).unwrap();
+    
+    assert!(dock.item(live_id!(orphan)).is_empty());
+    assert!(dock.item(live_id!(orphan_in_bar)).is_empty());
+    assert_eq!(kind_of_item(&dock, live_id!(orphan)), None);
+    let saved = dock.save_state().unwrap();
+    assert_eq!(saved.items.len(), state.items.len() - 3);
+}
+
+#[test]
+fn recreates_tabs_whose_kind_changed() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let mut state = dock.save_state().unwrap();
+    dock.load_state(&mut cx, &state).unwrap();
+    assert_eq!(kind_of_item(&dock, live_id!(edit_a)), Some(live_id!(Editor)));
+    
+    set_kind(&mut state, live_id!(edit_a), "Files");
+    dock.load_state(&mut cx, &state).unwrap();
+    assert_eq!(kind_of_item(&dock, live_id!(edit_a)), Some(live_id!(Files)));
+}
+
+#[test]
+fn rejects_malformed_layouts() {
+    let mut cx = cx();
+    let dock = new_dock(&mut cx);
+    let saved = dock.save_state().unwrap();
+    let load = | cx: &mut Cx, f: &dyn Fn(&mut DockState)| {
+        let mut state = saved.clone();
+        f(&mut state);
+        dock.load_state(cx, &state)
+    };
+    
+    // a splitter pointing at a tab
+    assert_eq!(
+        load(&mut cx, &| state | if let DockItemState::Splitter {a, ..} = item(state, live_id!(root)) {
+            *a = live_id!(files).0
+        }),
+        Err(DockStateError::InvalidChild {parent: live_id!(root), child: live_id!(files)})
+    );
+    // a tab bar holding a tab bar
+    assert_eq!(
+        load(&mut cx, &| state | if let DockItemState::Tabs {tabs, ..} = item(state, live_id!(left)) {
+            tabs.push(live_id!(right).0)
+        }),
+        Err(DockStateError::InvalidChild {parent: live_id!(left), child: live_id!(right)})
+    );
+    // a splitter containing itself, or the root
+    assert_eq!(
+        load(&mut cx, &| state | if let DockItemState::Splitter {b, ..} = item(state, live_id!(root)) {
+            *b = live_id!(root).0
+        }),
+        Err(DockStateError::InvalidChild {parent: live_id!(root), child: live_id!(root)})
+    );
+    // a tab shown in two tab bars
+    assert_eq!(
+        load(&mut cx, &| state | if let DockItemState::Tabs {tabs, ..} = item(state, live_id!(left)) {
+            tabs.push(live_id!(edit_a).0)
+        }),
+        Err(DockStateError::InvalidChild {parent: live_id!(left), child: live_id!(edit_a)})
+    );
+    // a tab as the root
+    assert_eq!(
+        load(&mut cx, &| state | {
+            state.items.retain( | item | !matches!(item, DockItemState::Splitter {..}));
+            if let DockItemState::Tab {id, ..} = item(state, live_id!(files)) {
+                *id = live_id!(root).0
+            }
+        }),
+        Err(DockStateError::TabAsRoot(live_id!(root)))
+    );
+    assert!(matches!(dock.load_state_json(&mut cx, "{\"items\":["), Err(DockStateError::Json(_))));
+    
+    // nothing was loaded
+    assert_eq!(dock.save_state().unwrap().serialize_json(), saved.serialize_json());
+}
@@ -0,0 +1,72 @@
+use makepad_widgets::*;
+
+live_design!{
+    import makepad_widgets::base::*;
+    import makepad_widgets::theme_desktop_dark::*;
+
+    FallbackImage = <Image> {
+        width: Fill,
+        height: Fit,
+        fallback_width: 200,
+        fallback_height: 100,
+        fit: Horizontal
+    }
+    MaxWidthImage = <FallbackImage> {max_width: 300.0}
+    MaxHeightImage = <FallbackImage> {max_height: 120.0}
+    MinHeightImage = <FallbackImage> {min_height: 250.0}
+}
+
+fn cx() -> Cx {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    live_design(&mut cx);
+    cx.live_expand();
+    cx
+}
+
+fn new_image(cx: &mut Cx, name: LiveId) -> WidgetRef {
+    let module_id = LiveModuleId::from_str(module_path!()).unwrap();
+    let image = Image::new_from_module(cx, module_id, name).unwrap();
+    WidgetRef::new_with_inner(Box::new(image))
+}
+
+// draws the image into a 400 wide column and reads its rect back
+fn image_rect(cx: &mut Cx, image: &WidgetRef) -> Rect {
+    let draw_event = DrawEvent::default();
+    let cx = &mut Cx2d::new(cx, &draw_event);
+    let pass = Pass::new(cx);
+    cx.begin_pass(&pass, Some(1.0));
+    let draw_list = DrawList2d::new(cx);
+    let redraw_id = cx.redraw_id;
+    cx.draw_lists[draw_list.draw_list_id()].redraw_id = redraw_id;
+    cx.draw_list_stack.push(draw_list.draw_list_id());
+    cx.begin_turtle(Walk::fixed(400.0, 1000.0), Layout::flow_down());
+    image.draw_widget_all(cx);
+    cx.end_turtle();
+    let tree = AccessTree::from_widget(cx, image);
+    tree.nodes[0].rect()
+}
+
+#[test]
+fn images_without_a_size_use_the_fallback_size() {
+    let mut cx = cx();
+    let image = new_image(&mut cx, live_id!(FallbackImage));
+    assert_eq!(image_rect(&mut cx, &image).size, dvec2(400.0, 200.0));
+
+    // a texture that doesn't know its size yet doesn't change that
+    let texture = Texture::new(&mut cx);
+    texture.set_desc(&mut cx, TextureDesc::default());
+    image.as_image().set_texture(Some(texture));
+    assert_eq!(image_rect(&mut cx, &image).size, dvec2(400.0, 200.0));
+}
+
+#[test]
+fn fallback_sized_images_respect_the_walk_limits() {
+    let mut cx = cx();
+    let image = new_image(&mut cx, live_id!(MaxWidthImage));
+    assert_eq!(image_rect(&mut cx, &image).size, dvec2(300.0, 150.0));
+    let image = new_image(&mut cx, live_id!(MaxHeightImage));
+    assert_eq!(image_rect(&mut cx, &image).size, dvec2(400.0, 120.0));
+    let image = new_image(&mut cx, live_id!(MinHeightImage));
+    assert_eq!(image_rect(&mut cx, &image).size, dvec2(400.0, 250.0));
+}
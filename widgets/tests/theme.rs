@@ -0,0 +1,65 @@
+use makepad_widgets::{
+    makepad_platform::makepad_live_compiler::{live_error::LiveError, live_registry::LiveScopeTarget},
+    *
+};
+
+fn cx() -> Cx {
+    let mut cx = Cx::new(Box::new( | _, _ | {}));
+    makepad_widgets::live_design(&mut cx);
+    cx.live_expand();
+    cx
+}
+
+fn dark() -> LiveModuleId {
+    LiveModuleId(live_id!(makepad_widgets), live_id!(theme_desktop_dark))
+}
+
+// switches the overlay the way set_theme does, but returns the expansion errors
+fn overlay(cx: &mut Cx, module: LiveId) -> Vec<LiveError> {
+    let mut live_registry = cx.live_registry.borrow_mut();
+    live_registry.set_module_overlay(dark(), Some(LiveModuleId(live_id!(makepad_widgets), module)));
+    let mut errs = Vec::new();
+    live_registry.expand_all_documents(&mut errs);
+    errs
+}
+
+fn token(cx: &Cx, name: LiveId) -> LiveValue {
+    let live_registry = cx.live_registry.borrow();
+    match live_registry.find_module_id_name(name, dark()) {
+        Some(LiveScopeTarget::LivePtr(ptr)) => live_registry.ptr_to_node(ptr).value.clone(),
+        _ => panic!("no token {}", name)
+    }
+}
+
+#[test]
+fn themes_expand_without_errors() {
+    for module in [live_id!(theme_desktop_light), live_id!(theme_desktop_high_contrast)] {
+        let mut cx = cx();
+        let dark_mark_off = token(&cx, live_id!(THEME_COLOR_CONTROL_MARK_OFF));
+        let errs = overlay(&mut cx, module);
+        assert!(errs.is_empty(), "{:?}", errs);
+        assert_ne!(token(&cx, live_id!(THEME_COLOR_CONTROL_MARK_OFF)), dark_mark_off);
+    }
+}
+
+fn tokens(source: &str, prefix: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = source.lines()
+        .filter_map( | line | line.trim().strip_prefix("const "))
+        .filter_map( | line | line.split_whitespace().next())
+        .filter( | name | name.starts_with(prefix))
+        .map( | name | name.to_string())
+        .collect();
+    tokens.sort();
+    tokens
+}
+
+#[test]
+fn themes_define_every_color_and_font_size() {
+    let dark = include_str!("../src/theme_desktop_dark.rs");
+    assert!(tokens(dark, "THEME_COLOR_").len() > 100);
+    for theme in [include_str!("../src/theme_desktop_light.rs"), include_str!("../src/theme_desktop_high_contrast.rs")] {
+        for prefix in ["THEME_COLOR_", "THEME_FONT_SIZE_"] {
+            assert_eq!(tokens(theme, prefix), tokens(dark, prefix));
+        }
+    }
+}